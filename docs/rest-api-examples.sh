@@ -101,4 +101,220 @@ echo -e "\n15. Check response headers (pagination info):"
 curl -I -X GET "$BASE_URL/tasks?_start=0&_end=10" \
   -H "Accept: application/json"
 
+# Workflow examples
+echo -e "\n--- WORKFLOWS ---"
+
+echo "16. Create a workflow whose second step reads the first step's output:"
+# Steps reference tasks by ID, UUID or name
+curl -X POST "$BASE_URL/workflows" \
+  -H "Content-Type: application/json" \
+  -d '{
+    "name": "weather-report",
+    "steps": [
+      {"id": "fetch", "taskId": "weather-api", "input": {"city": {"$from": "input.city"}}},
+      {
+        "id": "notify",
+        "taskId": "send-notification",
+        "dependsOn": ["fetch"],
+        "input": {"message": {"$from": "steps.fetch.conditions"}},
+        "maxRetries": 2,
+        "retryDelaySeconds": 10,
+        "onFailure": "CONTINUE"
+      }
+    ]
+  }' | jq
+
+echo -e "\n17. Run a workflow and inspect the run:"
+# Replace with actual workflow and run IDs
+curl -X POST "$BASE_URL/workflows/1/run" \
+  -H "Content-Type: application/json" \
+  -d '{"input": {"city": "Oslo"}}' | jq
+curl -X GET "$BASE_URL/workflow-runs/1" \
+  -H "Accept: application/json" | jq
+
+# Job dependency examples
+echo -e "\n--- JOB DEPENDENCIES ---"
+
+echo "18. Queue a job that runs with its parent's output once the parent completes:"
+# Replace with actual task and parent job IDs
+curl -X POST "$BASE_URL/jobs" \
+  -H "Content-Type: application/json" \
+  -d '{
+    "taskId": "2",
+    "input": {"format": "summary"},
+    "dependsOn": [{"jobId": "1", "condition": "ON_SUCCESS", "passOutput": true}]
+  }' | jq
+
+echo -e "\n19. Queue a cleanup job that only runs if the parent fails:"
+curl -X POST "$BASE_URL/jobs" \
+  -H "Content-Type: application/json" \
+  -d '{
+    "taskId": "3",
+    "input": {},
+    "dependsOn": [{"jobId": "1", "condition": "ON_FAILURE"}]
+  }' | jq
+
+# Webhook trigger examples
+echo -e "\n--- WEBHOOK TRIGGERS ---"
+
+echo "20. Create a trigger that queues a deploy job for signed GitHub pushes to main:"
+# The signing key is a task secret; create it first with the secrets endpoint
+curl -X POST "$BASE_URL/webhook-triggers" \
+  -H "Content-Type: application/json" \
+  -d '{
+    "name": "github-push",
+    "taskId": "deploy",
+    "signature": {
+      "secretName": "GITHUB_WEBHOOK_SECRET",
+      "algorithm": "SHA256",
+      "header": "X-Hub-Signature-256",
+      "prefix": "sha256="
+    },
+    "inputMapping": {
+      "branch": {"$from": "payload.ref"},
+      "commit": {"$from": "payload.after"},
+      "event": {"$from": "headers.x-github-event"}
+    },
+    "filterSchema": {
+      "type": "object",
+      "properties": {"ref": {"const": "refs/heads/main"}},
+      "required": ["ref"]
+    },
+    "deliveryIdHeader": "X-GitHub-Delivery"
+  }' | jq
+
+echo -e "\n21. Deliver a signed payload to the trigger:"
+PAYLOAD='{"ref": "refs/heads/main", "after": "9fceb02"}'
+SIGNATURE=$(printf '%s' "$PAYLOAD" | openssl dgst -sha256 -hmac "$GITHUB_WEBHOOK_SECRET" | sed 's/^.* //')
+curl -X POST "$BASE_URL/triggers/github-push" \
+  -H "Content-Type: application/json" \
+  -H "X-GitHub-Event: push" \
+  -H "X-GitHub-Delivery: 72d3162e-cc78-11e3-81ab-4c9367dc0958" \
+  -H "X-Hub-Signature-256: sha256=$SIGNATURE" \
+  -d "$PAYLOAD" | jq
+
+# Schedule policy examples
+echo -e "\n--- SCHEDULE POLICIES ---"
+
+echo "22. Run a report at 09:00 Oslo time on weekdays, never overlapping and catching up after downtime:"
+curl -X POST "$BASE_URL/schedules" \
+  -H "Content-Type: application/json" \
+  -d '{
+    "taskId": "1",
+    "name": "morning-report",
+    "cronExpression": "0 0 9 * * MON-FRI",
+    "policy": {
+      "timezone": "Europe/Oslo",
+      "overlap": "SKIP",
+      "misfire": "CATCH_UP",
+      "catchUpLimit": 3,
+      "jitterSeconds": 120,
+      "blackoutWindows": [{"start": "23:00", "end": "06:00"}]
+    }
+  }' | jq
+
+echo -e "\n23. Queue runs behind a still-running one instead of skipping them:"
+# Replace with an actual schedule ID; the policy replaces the current one
+curl -X PATCH "$BASE_URL/schedules/1" \
+  -H "Content-Type: application/json" \
+  -d '{"policy": {"timezone": "Europe/Oslo", "overlap": "QUEUE", "misfire": "FIRE_ONCE"}}' | jq
+
+# Idempotent job creation examples
+echo -e "\n--- IDEMPOTENT JOBS ---"
+
+echo "24. Queue a job under an idempotency key; repeating the request returns the same job with 200:"
+for attempt in 1 2; do
+  curl -i -X POST "$BASE_URL/jobs" \
+    -H "Content-Type: application/json" \
+    -H "Idempotency-Key: invoice-2024-0042" \
+    -d '{"taskId": "1", "input": {"invoice": 42}}'
+  echo
+done
+
+echo -e "\n25. Deduplicate identical input of a task within 10 minutes by setting its metadata:"
+# Replace with an actual task ID
+curl -X PATCH "$BASE_URL/tasks/1" \
+  -H "Content-Type: application/json" \
+  -d '{"metadata": {"dedupe_window_minutes": 10}}' | jq
+
+# Result caching examples
+echo -e "\n--- RESULT CACHING ---"
+
+echo "26. Serve results of a task for 10 minutes to jobs with the same city, across task versions:"
+# Replace with an actual task ID; jobs served from the cache get an execution with cacheHit set
+curl -X PATCH "$BASE_URL/tasks/1" \
+  -H "Content-Type: application/json" \
+  -d '{"metadata": {"cache": {"ttl_seconds": 600, "key_fields": ["city"], "per_version": false}}}' | jq
+
+echo -e "\n27. List the results cached for a task:"
+curl -s "$BASE_URL/tasks/1/cache" | jq
+
+echo -e "\n28. Purge the cached results of a task:"
+curl -X DELETE "$BASE_URL/tasks/1/cache" | jq
+
+# S3 output examples
+echo -e "\n--- S3 OUTPUT ---"
+
+echo "29. Queue a job that uploads its output to a MinIO bucket:"
+# Credentials may be left out to use AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY of the server;
+# the secret access key is read from the global secret named by secretAccessKeySecret on delivery
+curl -X POST "$BASE_URL/jobs" \
+  -H "Content-Type: application/json" \
+  -d '{
+    "taskId": "1",
+    "input": {"city": "Oslo"},
+    "outputDestinations": [{
+      "destinationType": "s3",
+      "s3": {
+        "bucket": "ratchet-results",
+        "key": "results/{{date}}/{{job_id}}.json",
+        "region": "us-east-1",
+        "endpoint": "http://localhost:9000",
+        "pathStyle": true,
+        "format": "JSON",
+        "storageClass": "STANDARD",
+        "metadata": {"execution": "{{execution_id}}"},
+        "accessKeyId": "minioadmin",
+        "secretAccessKeySecret": "MINIO_SECRET_KEY"
+      }
+    }]
+  }' | jq
+
+# Database output examples
+echo -e "\n--- DATABASE OUTPUT ---"
+
+echo "30. Queue a job that upserts one row per element of its array output into a reporting table:"
+# "reports" names a database under output.security.database_connections in the server config;
+# columns starting with / are JSON pointers into each element; other values are templates
+curl -X POST "$BASE_URL/jobs" \
+  -H "Content-Type: application/json" \
+  -d '{
+    "taskId": "1",
+    "input": {"cities": ["Oslo", "Bergen"]},
+    "outputDestinations": [{
+      "destinationType": "database",
+      "database": {
+        "connection": "reports",
+        "table": "weather_reports",
+        "columns": {
+          "city": "/city",
+          "temperature": "/temperature",
+          "job_id": "{{job_id}}"
+        },
+        "upsert": true,
+        "keyColumns": ["city"],
+        "batchSize": 500
+      }
+    }]
+  }' | jq
+
+# Output delivery ledger examples
+echo -e "\n--- OUTPUT DELIVERIES ---"
+
+echo "31. List the output deliveries of a job that are retrying or dead-lettered:"
+curl -s "$BASE_URL/deliveries/failed?jobId=1" | jq
+
+echo "32. Redeliver a dead-lettered output now:"
+curl -X POST "$BASE_URL/deliveries/1/redeliver" | jq
+
 echo -e "\n=== End of Examples ==="
\ No newline at end of file
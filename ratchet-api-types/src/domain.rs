@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 use crate::enums::*;
@@ -42,6 +43,12 @@ pub struct UnifiedTask {
     pub needs_push: bool,
     pub last_synced_at: Option<DateTime<Utc>>,
 
+    // Supply-chain verification for registry tasks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature_status: Option<TaskSignatureStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signed_by: Option<String>,
+
     // Additional fields for detailed view
     #[serde(skip_serializing_if = "Option::is_none")]
     pub input_schema: Option<serde_json::Value>,
@@ -309,11 +316,29 @@ pub struct UnifiedExecution {
     pub duration_ms: Option<i32>,
     pub http_requests: Option<serde_json::Value>,
     pub recording_path: Option<String>,
+    /// Execution that started this one with `ratchet.call`
+    pub parent_execution_uuid: Option<Uuid>,
+    /// Output was served from the task's result cache instead of running the task
+    #[serde(default)]
+    pub cache_hit: bool,
 
     // Computed fields
     pub can_retry: bool,
     pub can_cancel: bool,
     pub progress: Option<f32>,
+    pub progress_message: Option<String>,
+    pub partial_output: Option<serde_json::Value>,
+}
+
+/// A line of console output written by a task during an execution
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(SimpleObject))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UnifiedExecutionLog {
+    pub timestamp: DateTime<Utc>,
+    pub level: ExecutionLogLevel,
+    pub message: String,
 }
 
 /// Unified Job representation
@@ -332,6 +357,54 @@ pub struct UnifiedJob {
     pub scheduled_for: Option<DateTime<Utc>>,
     pub error_message: Option<String>,
     pub output_destinations: Option<Vec<UnifiedOutputDestination>>,
+    /// Input passed to the task, on top of the outputs passed on by dependencies
+    #[serde(default)]
+    pub input: Option<serde_json::Value>,
+    /// Jobs that must finish before this one is picked up
+    #[serde(default)]
+    pub depends_on: Vec<UnifiedJobDependency>,
+    /// Client-chosen key under which the job was queued; queueing again with it returns this job
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+    /// Schedule that queued the job, if any
+    #[serde(default)]
+    pub schedule_id: Option<ApiId>,
+}
+
+/// A job's dependency on another job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(SimpleObject))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UnifiedJobDependency {
+    /// The parent job
+    pub job_id: ApiId,
+    /// Outcome of the parent that lets the dependent run; any other outcome cancels it
+    #[serde(default)]
+    pub condition: JobDependencyCondition,
+    /// Merge the parent's output into the dependent's input
+    #[serde(default)]
+    pub pass_output: bool,
+}
+
+impl UnifiedJobDependency {
+    /// Drop repeats of the same dependency, keeping the order they were declared in
+    ///
+    /// A job can depend on a parent only once; a parent listed again with another
+    /// condition or output passing is an error carrying that parent.
+    pub fn dedupe(dependencies: Vec<Self>) -> Result<Vec<Self>, ApiId> {
+        let mut unique: Vec<Self> = Vec::with_capacity(dependencies.len());
+        for dependency in dependencies {
+            let Some(existing) = unique.iter().find(|existing| existing.job_id == dependency.job_id) else {
+                unique.push(dependency);
+                continue;
+            };
+            if (existing.condition, existing.pass_output) != (dependency.condition, dependency.pass_output) {
+                return Err(dependency.job_id);
+            }
+        }
+        Ok(unique)
+    }
 }
 
 /// Unified Schedule representation
@@ -341,7 +414,10 @@ pub struct UnifiedJob {
 #[serde(rename_all = "camelCase")]
 pub struct UnifiedSchedule {
     pub id: ApiId,
+    /// Task to run; for workflow schedules, the task of the workflow's first step
     pub task_id: ApiId,
+    /// Workflow to run instead of the task
+    pub workflow_id: Option<ApiId>,
     pub name: String,
     pub description: Option<String>,
     pub cron_expression: String,
@@ -351,6 +427,57 @@ pub struct UnifiedSchedule {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub output_destinations: Option<Vec<UnifiedOutputDestination>>,
+    /// Timezone, overlap, misfire, jitter and blackout settings
+    #[serde(default)]
+    pub policy: UnifiedSchedulePolicy,
+}
+
+/// When and how a schedule fires beyond its cron expression
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(SimpleObject))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "camelCase", default)]
+pub struct UnifiedSchedulePolicy {
+    /// IANA timezone the cron expression and blackout windows are read in, e.g. `Europe/Oslo`
+    pub timezone: String,
+    /// What to do when the schedule fires while its previous run is still going
+    pub overlap: ScheduleOverlapPolicy,
+    /// What to do on startup about fire times missed while the server was down
+    pub misfire: ScheduleMisfirePolicy,
+    /// Most missed runs fired by the `CATCH_UP` misfire policy (default 10)
+    pub catch_up_limit: Option<i32>,
+    /// Delay every run by a random number of seconds up to this value
+    pub jitter_seconds: i32,
+    /// Times of day during which the schedule does not fire
+    pub blackout_windows: Vec<UnifiedBlackoutWindow>,
+}
+
+impl Default for UnifiedSchedulePolicy {
+    fn default() -> Self {
+        Self {
+            timezone: "UTC".to_string(),
+            overlap: ScheduleOverlapPolicy::default(),
+            misfire: ScheduleMisfirePolicy::default(),
+            catch_up_limit: None,
+            jitter_seconds: 0,
+            blackout_windows: Vec::new(),
+        }
+    }
+}
+
+/// A daily period, in the schedule's timezone, during which a schedule does not fire
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(SimpleObject))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UnifiedBlackoutWindow {
+    /// Start time as `HH:MM`
+    pub start: String,
+    /// End time as `HH:MM`; a window ending before it starts runs past midnight
+    pub end: String,
+    /// Days the window starts on, e.g. `["SAT", "SUN"]`; every day when empty
+    #[serde(default)]
+    pub days: Vec<String>,
 }
 
 /// Unified Output Destination representation
@@ -383,7 +510,7 @@ pub struct UnifiedSchedule {
 ))]
 #[serde(rename_all = "camelCase")]
 pub struct UnifiedOutputDestination {
-    /// Type of destination: "webhook", "filesystem", "database", or "stdio"
+    /// Type of destination: "webhook", "filesystem", "database", "stdio" or "s3"
     #[cfg_attr(feature = "openapi", schema(example = "webhook"))]
     pub destination_type: String,
 
@@ -399,6 +526,14 @@ pub struct UnifiedOutputDestination {
 
     /// Stdio configuration (when destination_type is "stdio")
     pub stdio: Option<UnifiedStdioConfig>,
+
+    /// S3 configuration (when destination_type is "s3")
+    #[serde(default)]
+    pub s3: Option<UnifiedS3Config>,
+
+    /// Database configuration (when destination_type is "database")
+    #[serde(default)]
+    pub database: Option<UnifiedDatabaseConfig>,
 }
 
 /// Unified Filesystem Configuration
@@ -642,6 +777,106 @@ pub struct UnifiedStdioConfig {
     pub prefix: Option<String>,
 }
 
+/// Unified S3 Configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(SimpleObject))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[cfg_attr(feature = "openapi", schema(
+    description = "Configuration for S3 and S3-compatible output destinations",
+    example = json!({
+        "bucket": "ratchet-results",
+        "key": "jobs/{{job_id}}.json",
+        "region": "us-east-1",
+        "endpoint": "http://minio:9000",
+        "pathStyle": true,
+        "format": "JSON",
+        "storageClass": "STANDARD_IA",
+        "metadata": {"job": "{{job_id}}"}
+    })
+))]
+#[serde(rename_all = "camelCase")]
+pub struct UnifiedS3Config {
+    /// Bucket to upload to
+    #[cfg_attr(feature = "openapi", schema(example = "ratchet-results"))]
+    pub bucket: String,
+
+    /// Object key template
+    #[cfg_attr(feature = "openapi", schema(example = "jobs/{{job_id}}.json"))]
+    pub key: String,
+
+    /// Region used to sign requests
+    #[cfg_attr(feature = "openapi", schema(example = "us-east-1"))]
+    pub region: String,
+
+    /// Endpoint of an S3-compatible store; AWS when unset
+    #[cfg_attr(feature = "openapi", schema(example = "http://minio:9000"))]
+    pub endpoint: Option<String>,
+
+    /// Whether to address the bucket in the path rather than the host name
+    #[serde(default)]
+    pub path_style: bool,
+
+    /// Output format of the object
+    pub format: OutputFormat,
+
+    /// Optional storage class
+    #[cfg_attr(feature = "openapi", schema(example = "STANDARD_IA"))]
+    pub storage_class: Option<String>,
+
+    /// Object metadata; values are templates
+    pub metadata: Option<HashMap<String, String>>,
+
+    /// Access key ID; the AWS_* environment variables are used when unset
+    pub access_key_id: Option<String>,
+
+    /// Name of the global secret holding the secret access key, read from the secret store on delivery
+    pub secret_access_key_secret: Option<String>,
+}
+
+/// Unified Database Configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(SimpleObject))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[cfg_attr(feature = "openapi", schema(
+    description = "Configuration for database output destinations",
+    example = json!({
+        "connection": "reports",
+        "table": "weather_reports",
+        "columns": {
+            "city": "/city",
+            "temperature": "/temperature",
+            "job_id": "{{job_id}}"
+        },
+        "upsert": true,
+        "keyColumns": ["city"]
+    })
+))]
+#[serde(rename_all = "camelCase")]
+pub struct UnifiedDatabaseConfig {
+    /// Name of a database connection in the server configuration
+    #[cfg_attr(feature = "openapi", schema(example = "reports"))]
+    pub connection: String,
+
+    /// Table to write to, optionally schema-qualified
+    #[cfg_attr(feature = "openapi", schema(example = "weather_reports"))]
+    pub table: String,
+
+    /// Column values: JSON pointers into the output (`/city`) or templates (`{{job_id}}`)
+    pub columns: HashMap<String, String>,
+
+    /// Whether to update rows that already exist
+    #[serde(default)]
+    pub upsert: bool,
+
+    /// Columns identifying a row when upserting
+    #[serde(default)]
+    pub key_columns: Vec<String>,
+
+    /// Rows per INSERT when the output is an array
+    #[cfg_attr(feature = "openapi", schema(example = 100))]
+    pub batch_size: Option<i32>,
+}
+
 /// Worker status representation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "graphql", derive(SimpleObject))]
@@ -713,3 +948,220 @@ pub struct UnifiedApiKey {
     pub usage_count: i64,
     // Key hash is never included in API responses
 }
+
+/// Task secret metadata; the secret value is never part of API responses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(SimpleObject))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UnifiedSecret {
+    pub id: ApiId,
+    pub name: String,
+    pub scope: SecretScope,
+    /// Repository ID for repository secrets, task UUID for task secrets
+    pub scope_id: Option<String>,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A named graph of task steps run as one unit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(SimpleObject))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UnifiedWorkflow {
+    pub id: ApiId,
+    pub name: String,
+    pub description: Option<String>,
+    pub enabled: bool,
+    pub steps: Vec<UnifiedWorkflowStep>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl UnifiedWorkflow {
+    /// Find a step by its ID
+    pub fn step(&self, step_id: &str) -> Option<&UnifiedWorkflowStep> {
+        self.steps.iter().find(|step| step.id == step_id)
+    }
+}
+
+/// One step of a workflow: a task run once the steps it depends on have completed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(SimpleObject))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UnifiedWorkflowStep {
+    /// Identifier of the step, unique within the workflow
+    pub id: String,
+    pub task_id: ApiId,
+    /// Steps that must complete before this one starts
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Input template; `{"$from": "input.a"}` or `{"$from": "steps.<id>.b"}` values are
+    /// replaced by the workflow input or a dependency's output. Defaults to the workflow input.
+    #[serde(default)]
+    pub input: Option<serde_json::Value>,
+    /// How many times a failed step is retried
+    #[serde(default)]
+    pub max_retries: i32,
+    #[serde(default)]
+    pub retry_delay_seconds: i32,
+    #[serde(default)]
+    pub on_failure: WorkflowFailurePolicy,
+}
+
+/// A single run of a workflow and the state of each of its steps
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(SimpleObject))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UnifiedWorkflowRun {
+    pub id: ApiId,
+    pub uuid: Uuid,
+    pub workflow_id: ApiId,
+    /// Schedule that started the run, if any
+    pub schedule_id: Option<ApiId>,
+    pub status: WorkflowRunStatus,
+    pub input: serde_json::Value,
+    /// Outputs of the final steps, keyed by step ID
+    pub output: Option<serde_json::Value>,
+    pub error_message: Option<String>,
+    pub steps: Vec<UnifiedWorkflowStepRun>,
+    pub queued_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl UnifiedWorkflowRun {
+    /// Find the state of a step by its ID
+    pub fn step(&self, step_id: &str) -> Option<&UnifiedWorkflowStepRun> {
+        self.steps.iter().find(|step| step.step_id == step_id)
+    }
+}
+
+/// State of one step within a workflow run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(SimpleObject))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UnifiedWorkflowStepRun {
+    pub step_id: String,
+    pub status: WorkflowStepStatus,
+    /// Execution of the latest attempt
+    pub execution_id: Option<ApiId>,
+    pub attempts: i32,
+    pub output: Option<serde_json::Value>,
+    pub error_message: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// A named endpoint that queues a job of a task when an external system posts to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(SimpleObject))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UnifiedWebhookTrigger {
+    pub id: ApiId,
+    /// Name in the trigger URL, `/triggers/{name}`
+    pub name: String,
+    pub description: Option<String>,
+    pub task_id: ApiId,
+    pub enabled: bool,
+    pub signature: UnifiedTriggerSignature,
+    /// Input template; `{"$from": "payload.a"}` or `{"$from": "headers.x-event"}` values are
+    /// replaced by a field of the payload or a request header. Defaults to the whole payload.
+    #[serde(default)]
+    pub input_mapping: Option<serde_json::Value>,
+    /// JSON schema the payload must match; other deliveries are accepted but queue no job
+    #[serde(default)]
+    pub filter_schema: Option<serde_json::Value>,
+    /// Header carrying a unique delivery ID; repeated IDs queue no new job, nor do
+    /// repeated signatures whether or not this is set
+    #[serde(default)]
+    pub delivery_id_header: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// How a webhook trigger verifies the HMAC signature of a delivery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(SimpleObject))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UnifiedTriggerSignature {
+    /// Name of the secret holding the signing key, resolved like the task's own secrets
+    pub secret_name: String,
+    #[serde(default)]
+    pub algorithm: TriggerSignatureAlgorithm,
+    /// Header carrying the hex encoded signature, e.g. `X-Hub-Signature-256`
+    pub header: String,
+    /// Prefix in front of the signature, e.g. `sha256=`
+    #[serde(default)]
+    pub prefix: Option<String>,
+    /// Header carrying a Unix timestamp; when set, `{timestamp}.{body}` is signed
+    /// instead of the body and old deliveries are rejected
+    #[serde(default)]
+    pub timestamp_header: Option<String>,
+    /// How old a timestamped delivery may be, in seconds (default 300)
+    #[serde(default)]
+    pub max_age_seconds: Option<i64>,
+}
+
+/// Outcome of a delivery to a webhook trigger
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(SimpleObject))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UnifiedTriggerDelivery {
+    pub trigger: String,
+    pub status: TriggerDeliveryStatus,
+    /// The queued job; for duplicates, the job queued by the first delivery
+    pub job_id: Option<ApiId>,
+}
+
+/// One attempt to deliver the output of a job to one of its destinations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(SimpleObject))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UnifiedDeliveryAttempt {
+    pub id: ApiId,
+    pub job_id: ApiId,
+    pub execution_id: ApiId,
+    pub destination_type: String,
+    /// Where the output went, e.g. a URL or file path
+    pub destination_id: String,
+    /// Position of the destination in the output destinations of the job
+    pub destination_index: i32,
+    /// Number of the attempt, starting at 1
+    pub attempt: i32,
+    pub status: OutputDeliveryStatus,
+    pub delivery_time_ms: i32,
+    pub size_bytes: i32,
+    pub response_info: Option<String>,
+    pub error_message: Option<String>,
+    /// When a retrying delivery is due, or a redelivery claim lapses
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A task result served from the result cache until it expires
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(SimpleObject))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UnifiedCachedResult {
+    /// Task version the result belongs to, `*` when the task shares results across versions
+    pub task_version: String,
+    /// Hash of the input fields that form the key
+    pub input_hash: String,
+    /// Execution that produced the result
+    pub execution_uuid: Uuid,
+    pub output: serde_json::Value,
+    pub duration_ms: i64,
+    pub cached_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
@@ -108,3 +108,220 @@ pub enum ApiKeyPermissions {
     ExecuteOnly,
     Admin,
 }
+
+/// Severity of a line of task console output, ordered from least to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(Enum))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ExecutionLogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl ExecutionLogLevel {
+    /// Parse a level name as written by tasks (`log` is an alias of `info`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "trace" | "debug" => Some(Self::Debug),
+            "log" | "info" => Some(Self::Info),
+            "warn" | "warning" => Some(Self::Warn),
+            "error" => Some(Self::Error),
+            _ => None,
+        }
+    }
+}
+
+/// Which executions can read a task secret
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(Enum))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SecretScope {
+    /// Every task
+    Global,
+    /// Tasks loaded from one repository
+    Repository,
+    /// A single task
+    Task,
+}
+
+impl SecretScope {
+    /// Parse a scope name, ignoring case
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "global" => Some(Self::Global),
+            "repository" | "repo" => Some(Self::Repository),
+            "task" => Some(Self::Task),
+            _ => None,
+        }
+    }
+}
+
+/// When a job that depends on another job may run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(Enum))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum JobDependencyCondition {
+    /// Once the parent job has completed
+    #[default]
+    OnSuccess,
+    /// Once the parent job has failed for good
+    OnFailure,
+    /// Once the parent job has finished, whatever the outcome
+    Always,
+}
+
+/// What a workflow run does when a step fails after its retries
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(Enum))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum WorkflowFailurePolicy {
+    /// Stop the run and mark it failed
+    #[default]
+    Abort,
+    /// Skip the steps depending on the failed step and run the others
+    Continue,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(Enum))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum WorkflowRunStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(Enum))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum WorkflowStepStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+    /// Not run because a step it depends on failed or the run was aborted
+    Skipped,
+}
+
+/// Hash function of the HMAC a webhook trigger verifies
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(Enum))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TriggerSignatureAlgorithm {
+    #[default]
+    Sha256,
+    Sha512,
+}
+
+/// What a webhook trigger did with a delivery
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(Enum))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TriggerDeliveryStatus {
+    /// A job was queued
+    Queued,
+    /// The delivery ID was seen before; no new job was queued
+    Duplicate,
+    /// The payload did not match the trigger's filter schema
+    Filtered,
+}
+
+/// State of an attempt to deliver the output of a job to one destination
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(Enum))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OutputDeliveryStatus {
+    /// The output was delivered
+    Delivered,
+    /// The attempt failed and a later attempt replaced it
+    Failed,
+    /// The attempt failed and another is due at `nextAttemptAt`
+    Retrying,
+    /// A server is delivering the output again
+    Redelivering,
+    /// The retry policy gave up; only a manual redelivery tries again
+    DeadLetter,
+}
+
+/// Outcome of verifying a registry task's detached signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(Enum))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TaskSignatureStatus {
+    /// Signed by a key in the repository's trust store
+    Verified,
+    /// The task carries no signature
+    Unsigned,
+    /// Signed by a key the repository does not trust
+    Untrusted,
+    /// The signature does not match the task contents
+    Invalid,
+}
+
+impl TaskSignatureStatus {
+    /// Parse the status name stored with a task, ignoring case
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "verified" => Some(Self::Verified),
+            "unsigned" => Some(Self::Unsigned),
+            "untrusted" => Some(Self::Untrusted),
+            "invalid" => Some(Self::Invalid),
+            _ => None,
+        }
+    }
+
+    /// Status name as stored with a task
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Verified => "verified",
+            Self::Unsigned => "unsigned",
+            Self::Untrusted => "untrusted",
+            Self::Invalid => "invalid",
+        }
+    }
+}
+
+/// What a schedule does when it fires while its previous run is still going
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(Enum))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ScheduleOverlapPolicy {
+    /// Start another run alongside the previous one
+    #[default]
+    Allow,
+    /// Do not start a run
+    Skip,
+    /// Start the run once the previous one has finished
+    Queue,
+    /// Cancel the previous run and start a new one
+    CancelPrevious,
+}
+
+/// What a schedule does about fire times missed while the server was down
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(Enum))]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ScheduleMisfirePolicy {
+    /// Ignore missed fire times
+    #[default]
+    Skip,
+    /// Run once if any fire time was missed
+    FireOnce,
+    /// Run once for every missed fire time, up to the schedule's catch-up limit
+    CatchUp,
+}
@@ -13,15 +13,19 @@ pub mod pagination;
 
 // Re-export main types for convenience
 pub use domain::{
-    ConnectionTestResult, CreateRepositoryRequest, CreateTaskRequest, PushResult, SyncResult, TaskConflict,
+    ConnectionTestResult, CreateRepositoryRequest, UnifiedCachedResult, CreateTaskRequest, UnifiedDatabaseConfig, UnifiedDeliveryAttempt, PushResult, SyncResult, TaskConflict,
     TaskRepositoryInfo, UnifiedApiKey, UnifiedApiKeyAuth, UnifiedBasicAuth, UnifiedBearerAuth, UnifiedExecution, 
-    UnifiedFilesystemConfig, UnifiedJob, UnifiedOutputDestination, UnifiedRetryPolicy, UnifiedSchedule, 
-    UnifiedSession, UnifiedStdioConfig, UnifiedTask, UnifiedTaskRepository, UnifiedUser, UnifiedWebhookAuth, 
-    UnifiedWebhookConfig, UnifiedWorkerStatus, UpdateRepositoryRequest, UpdateTaskSourceRequest,
+    UnifiedExecutionLog, UnifiedFilesystemConfig, UnifiedJob, UnifiedJobDependency, UnifiedOutputDestination, UnifiedRetryPolicy, UnifiedS3Config, UnifiedSchedule,
+    UnifiedSchedulePolicy, UnifiedBlackoutWindow,
+    UnifiedSecret, UnifiedSession, UnifiedStdioConfig, UnifiedTask, UnifiedTaskRepository, UnifiedTriggerDelivery, UnifiedTriggerSignature,
+    UnifiedUser, UnifiedWebhookAuth, UnifiedWebhookTrigger,
+    UnifiedWebhookConfig, UnifiedWorkerStatus, UnifiedWorkflow, UnifiedWorkflowRun, UnifiedWorkflowStep, UnifiedWorkflowStepRun,
+    UpdateRepositoryRequest, UpdateTaskSourceRequest,
 };
 pub use enums::{
-    ApiKeyPermissions, CompressionType, ExecutionStatus, HttpMethod, JobPriority, JobStatus, OutputFormat, UserRole,
-    WorkerStatusType,
+    ApiKeyPermissions, CompressionType, ExecutionLogLevel, ExecutionStatus, HttpMethod, JobDependencyCondition, JobPriority, JobStatus, OutputDeliveryStatus, OutputFormat, ScheduleMisfirePolicy, ScheduleOverlapPolicy,
+    SecretScope, TaskSignatureStatus, UserRole,
+    TriggerDeliveryStatus, TriggerSignatureAlgorithm, WorkerStatusType, WorkflowFailurePolicy, WorkflowRunStatus, WorkflowStepStatus,
 };
 pub use errors::ApiError;
 pub use ids::ApiId;
@@ -123,13 +123,10 @@ impl HttpCache {
     pub fn from_config(config: HttpCacheConfig) -> Self {
         let inner = match config.cache_type {
             crate::config::CacheType::Moka => {
-                let mut builder = MokaCache::builder();
-                builder = builder
+                // Capacity counts entries; `max_response_size` bounds the size of each
+                let builder = MokaCache::builder()
                     .max_capacity(config.max_entries as u64)
-                    .time_to_live(Duration::from_secs(config.default_ttl_seconds))
-                    .weigher(|_k, v: &Arc<CachedHttpResponse>| {
-                        (v.size_bytes / 1024) as u32 // KB
-                    });
+                    .time_to_live(Duration::from_secs(config.default_ttl_seconds));
 
                 HttpCacheImpl::Moka(builder.build())
             }
@@ -162,6 +159,14 @@ impl HttpCache {
         Ok(response)
     }
 
+    /// Get a cached response even if it is stale, so it can be revalidated
+    pub async fn get_stale(&self, key: &HttpCacheKey) -> CacheResult<Option<Arc<CachedHttpResponse>>> {
+        match &self.inner {
+            HttpCacheImpl::Ttl(cache) => cache.get(key).await,
+            HttpCacheImpl::Moka(cache) => cache.get(key).await,
+        }
+    }
+
     /// Cache a response
     pub async fn put(
         &self,
@@ -312,6 +317,38 @@ mod tests {
         assert_eq!(cached.unwrap().status_code, 200);
     }
 
+    #[tokio::test]
+    async fn test_get_stale_keeps_expired_responses() {
+        let cache = HttpCache::from_config(HttpCacheConfig {
+            enabled: true,
+            max_entries: 100,
+            max_response_size: 1024,
+            default_ttl_seconds: 300,
+            honor_cache_control: true,
+            cache_type: crate::config::CacheType::Moka,
+        });
+        let key = HttpCacheKey::new("GET", "https://api.example.com/stale");
+        let response = CachedHttpResponse {
+            status_code: 200,
+            headers: HashMap::new(),
+            body: b"stale".to_vec(),
+            cached_at: Utc::now() - chrono::Duration::minutes(2),
+            expires_at: Some(Utc::now() - chrono::Duration::minutes(1)),
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+            size_bytes: 5,
+        };
+
+        cache.put(key.clone(), response, None).await.unwrap();
+        let stale = cache.get_stale(&key).await.unwrap().unwrap();
+        assert!(stale.is_stale());
+        assert_eq!(stale.etag.as_deref(), Some("\"v1\""));
+
+        // A plain get drops the stale entry
+        assert!(cache.get(&key).await.unwrap().is_none());
+        assert!(cache.get_stale(&key).await.unwrap().is_none());
+    }
+
     #[tokio::test]
     async fn test_cache_control_parsing() {
         let header = "max-age=3600, public, s-maxage=7200";
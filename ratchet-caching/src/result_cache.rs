@@ -1,6 +1,8 @@
 //! Task execution result cache implementation
 
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -11,6 +13,77 @@ use crate::{
     CacheError, CacheResult, CacheStats,
 };
 
+/// Task metadata key with the result caching settings of a task
+pub const CACHE_METADATA_KEY: &str = "cache";
+
+/// Task version used in the keys of tasks whose results are shared across versions
+const ANY_VERSION: &str = "*";
+
+/// Result caching settings a task opts into through its metadata
+///
+/// ```json
+/// { "cache": { "ttl_seconds": 600, "key_fields": ["city"], "per_version": true } }
+/// ```
+///
+/// Without `key_fields` the whole input forms the key; with `per_version` off,
+/// results outlive task updates.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResultCachePolicy {
+    /// Seconds a result is served from the cache
+    pub ttl_seconds: u64,
+
+    /// Top-level input fields that form the key
+    pub key_fields: Option<Vec<String>>,
+
+    /// Whether each task version has its own results
+    pub per_version: bool,
+}
+
+impl Default for ResultCachePolicy {
+    fn default() -> Self {
+        Self {
+            ttl_seconds: 600,
+            key_fields: None,
+            per_version: true,
+        }
+    }
+}
+
+impl ResultCachePolicy {
+    /// Read the policy of a task from its metadata; `None` when the task does not cache results
+    ///
+    /// `"cache": true` opts in with the default settings.
+    pub fn from_task_metadata(metadata: &serde_json::Value) -> Option<Self> {
+        let policy = match metadata.get(CACHE_METADATA_KEY)? {
+            serde_json::Value::Bool(true) => Self::default(),
+            value @ serde_json::Value::Object(_) => serde_json::from_value(value.clone()).ok()?,
+            _ => return None,
+        };
+        (policy.ttl_seconds > 0).then_some(policy)
+    }
+
+    /// How long results are served from the cache
+    pub fn ttl(&self) -> Duration {
+        Duration::from_secs(self.ttl_seconds)
+    }
+
+    /// Key of the result of running version `task_version` of a task on `input`
+    pub fn key(&self, task_id: impl Into<String>, task_version: &str, input: &serde_json::Value) -> ResultCacheKey {
+        let version = if self.per_version { task_version } else { ANY_VERSION };
+        match &self.key_fields {
+            Some(fields) => {
+                let selected: serde_json::Map<String, serde_json::Value> = fields
+                    .iter()
+                    .map(|field| (field.clone(), input.get(field).cloned().unwrap_or_default()))
+                    .collect();
+                ResultCacheKey::new(task_id, version, &serde_json::Value::Object(selected))
+            }
+            None => ResultCacheKey::new(task_id, version, input),
+        }
+    }
+}
+
 /// Result cache key
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ResultCacheKey {
@@ -67,6 +140,10 @@ pub struct CachedResult {
 
     /// Result size in bytes
     pub size_bytes: usize,
+
+    /// When the result stops being served, if sooner than the cache's own TTL
+    #[serde(default)]
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 impl CachedResult {
@@ -82,6 +159,7 @@ impl CachedResult {
             duration_ms,
             cached_at: chrono::Utc::now(),
             size_bytes,
+            expires_at: None,
         }
     }
 
@@ -96,8 +174,13 @@ impl CachedResult {
             duration_ms,
             cached_at: chrono::Utc::now(),
             size_bytes,
+            expires_at: None,
         }
     }
+
+    fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= chrono::Utc::now())
+    }
 }
 
 /// Result cache implementation
@@ -107,6 +190,9 @@ pub struct ResultCache {
 
     /// Configuration
     config: ResultCacheConfig,
+
+    /// Keys of the results cached for each task
+    keys: Mutex<HashMap<String, HashSet<ResultCacheKey>>>,
 }
 
 /// Inner cache implementation
@@ -137,7 +223,11 @@ impl ResultCache {
             }
         };
 
-        Self { inner, config }
+        Self {
+            inner,
+            config,
+            keys: Mutex::new(HashMap::new()),
+        }
     }
 
     /// Get a cached result
@@ -146,14 +236,31 @@ impl ResultCache {
             return Ok(None);
         }
 
-        match &self.inner {
-            ResultCacheImpl::Ttl(cache) => cache.get(key).await,
-            ResultCacheImpl::Moka(cache) => cache.get(key).await,
+        let result = match &self.inner {
+            ResultCacheImpl::Ttl(cache) => cache.get(key).await?,
+            ResultCacheImpl::Moka(cache) => cache.get(key).await?,
+        };
+        match result {
+            Some(result) if result.is_expired() => {
+                self.remove(key).await?;
+                Ok(None)
+            }
+            Some(result) => Ok(Some(result)),
+            None => {
+                self.forget(key);
+                Ok(None)
+            }
         }
     }
 
-    /// Cache a result
+    /// Cache a result for the configured TTL
     pub async fn put(&self, key: ResultCacheKey, result: CachedResult) -> CacheResult<()> {
+        let ttl = Duration::from_secs(self.config.ttl_seconds);
+        self.put_with_ttl(key, result, ttl).await
+    }
+
+    /// Cache a result for `ttl`, or the configured TTL if that is shorter
+    pub async fn put_with_ttl(&self, key: ResultCacheKey, mut result: CachedResult, ttl: Duration) -> CacheResult<()> {
         if !self.config.enabled {
             return Ok(());
         }
@@ -171,16 +278,26 @@ impl ResultCache {
             )));
         }
 
+        let ttl = ttl.min(Duration::from_secs(self.config.ttl_seconds));
+        result.expires_at = chrono::Duration::from_std(ttl)
+            .ok()
+            .map(|ttl| result.cached_at + ttl);
         let result = Arc::new(result);
 
+        self.keys
+            .lock()
+            .entry(key.task_id.clone())
+            .or_default()
+            .insert(key.clone());
         match &self.inner {
-            ResultCacheImpl::Ttl(cache) => cache.put(key, result).await,
-            ResultCacheImpl::Moka(cache) => cache.put(key, result).await,
+            ResultCacheImpl::Ttl(cache) => cache.put_with_ttl(key, result, ttl).await,
+            ResultCacheImpl::Moka(cache) => cache.put_with_ttl(key, result, ttl).await,
         }
     }
 
     /// Remove a cached result
     pub async fn remove(&self, key: &ResultCacheKey) -> CacheResult<Option<Arc<CachedResult>>> {
+        self.forget(key);
         match &self.inner {
             ResultCacheImpl::Ttl(cache) => cache.remove(key).await,
             ResultCacheImpl::Moka(cache) => cache.remove(key).await,
@@ -189,18 +306,53 @@ impl ResultCache {
 
     /// Clear all cached results
     pub async fn clear(&self) -> CacheResult<()> {
+        self.keys.lock().clear();
         match &self.inner {
             ResultCacheImpl::Ttl(cache) => cache.clear().await,
             ResultCacheImpl::Moka(cache) => cache.clear().await,
         }
     }
 
-    /// Clear results for a specific task
-    pub async fn clear_task(&self, _task_id: &str) -> CacheResult<usize> {
-        // This is a simplified implementation
-        // In production, you'd maintain an index of keys by task_id
-        self.clear().await?;
-        Ok(0)
+    /// Results currently cached for a task, oldest first
+    pub async fn entries(&self, task_id: &str) -> CacheResult<Vec<(ResultCacheKey, Arc<CachedResult>)>> {
+        let mut entries = Vec::new();
+        for key in self.task_keys(task_id) {
+            if let Some(result) = self.get(&key).await? {
+                entries.push((key, result));
+            }
+        }
+        entries.sort_by_key(|(_, result)| result.cached_at);
+        Ok(entries)
+    }
+
+    /// Clear results for a specific task, returning how many were removed
+    pub async fn clear_task(&self, task_id: &str) -> CacheResult<usize> {
+        let mut removed = 0;
+        for key in self.task_keys(task_id) {
+            if self.remove(&key).await?.is_some_and(|result| !result.is_expired()) {
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    fn task_keys(&self, task_id: &str) -> Vec<ResultCacheKey> {
+        self.keys
+            .lock()
+            .get(task_id)
+            .map(|keys| keys.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Drop a key from the index, e.g. after the store evicted its result
+    fn forget(&self, key: &ResultCacheKey) {
+        let mut keys = self.keys.lock();
+        if let Some(task_keys) = keys.get_mut(&key.task_id) {
+            task_keys.remove(key);
+            if task_keys.is_empty() {
+                keys.remove(&key.task_id);
+            }
+        }
     }
 
     /// Get cache statistics
@@ -290,6 +442,60 @@ mod tests {
         assert!(cached.is_none());
     }
 
+    #[test]
+    fn test_policy_from_task_metadata() {
+        let input = serde_json::json!({"city": "Oslo", "request_id": 1});
+
+        let policy = ResultCachePolicy::from_task_metadata(&serde_json::json!({
+            "cache": {"ttl_seconds": 60, "key_fields": ["city"], "per_version": false}
+        }))
+        .unwrap();
+        assert_eq!(policy.ttl(), Duration::from_secs(60));
+        assert_eq!(
+            policy.key("task1", "1.0.0", &input),
+            policy.key("task1", "2.0.0", &serde_json::json!({"city": "Oslo", "request_id": 2}))
+        );
+        assert_ne!(
+            policy.key("task1", "1.0.0", &input),
+            policy.key("task1", "1.0.0", &serde_json::json!({"city": "Bergen"}))
+        );
+
+        let per_version = ResultCachePolicy::from_task_metadata(&serde_json::json!({"cache": true})).unwrap();
+        assert_ne!(per_version.key("task1", "1.0.0", &input), per_version.key("task1", "2.0.0", &input));
+
+        assert!(ResultCachePolicy::from_task_metadata(&serde_json::json!({})).is_none());
+        assert!(ResultCachePolicy::from_task_metadata(&serde_json::json!({"cache": false})).is_none());
+        assert!(ResultCachePolicy::from_task_metadata(&serde_json::json!({"cache": {"ttl_seconds": 0}})).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_task_entries_expire_and_purge() {
+        let config = ResultCacheConfig {
+            enabled: true,
+            cache_type: crate::config::CacheType::Moka,
+            ..ResultCacheConfig::default()
+        };
+        let cache = ResultCache::from_config(config);
+
+        for (task, i) in [("task1", 1), ("task1", 2), ("task2", 3)] {
+            let key = ResultCacheKey::new(task, "1.0.0", &serde_json::json!({ "i": i }));
+            let result = CachedResult::success(uuid::Uuid::new_v4(), serde_json::json!(i), 10);
+            cache.put_with_ttl(key, result, Duration::from_secs(60)).await.unwrap();
+        }
+        let short = ResultCacheKey::new("task1", "1.0.0", &serde_json::json!({ "i": 4 }));
+        let result = CachedResult::success(uuid::Uuid::new_v4(), serde_json::json!(4), 10);
+        cache.put_with_ttl(short.clone(), result, Duration::from_millis(50)).await.unwrap();
+
+        // The per-result TTL applies even though Moka only knows the configured one
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(cache.get(&short).await.unwrap().is_none());
+        assert_eq!(cache.entries("task1").await.unwrap().len(), 2);
+
+        assert_eq!(cache.clear_task("task1").await.unwrap(), 2);
+        assert!(cache.entries("task1").await.unwrap().is_empty());
+        assert_eq!(cache.entries("task2").await.unwrap().len(), 1);
+    }
+
     #[tokio::test]
     async fn test_deterministic_check() {
         let deterministic_task = serde_json::json!({
@@ -6,10 +6,22 @@
 pub mod generate;
 pub mod js_execution;
 pub mod recording;
+#[cfg(all(feature = "javascript", feature = "http", feature = "recording"))]
+pub mod replay;
+#[cfg(all(feature = "javascript", feature = "http"))]
+pub mod test_runner;
 
 // Re-export commonly used types for convenience
 pub use generate::{generate_task, GeneratedTaskInfo, TaskGenerationConfig};
 
 pub use js_execution::{execute_task, execute_task_with_lib_compatibility, ExecutionMode, TaskInput};
 
-pub use recording::{finalize_recording, get_recording_dir, is_recording, set_recording_dir};
+pub use recording::{
+    finalize_recording, get_recording_dir, is_recording, record_input, record_output, set_recording_dir,
+};
+
+#[cfg(all(feature = "javascript", feature = "http", feature = "recording"))]
+pub use replay::{replay_recording, ReplayReport};
+
+#[cfg(all(feature = "javascript", feature = "http"))]
+pub use test_runner::{discover_test_cases, json_diff, run_task_tests, TaskTestCase, TestReport};
@@ -4,6 +4,7 @@
 //! functionality from both ratchet_lib and ratchet-http.
 
 use anyhow::Result;
+use serde_json::Value as JsonValue;
 use std::path::PathBuf;
 use tracing::{debug, info};
 
@@ -66,3 +67,25 @@ pub fn finalize_recording() -> Result<()> {
     debug!("Recording finalization not available - recording not enabled");
     Ok(())
 }
+
+/// Write the task input to the current recording session
+#[cfg(feature = "recording")]
+pub fn record_input(input: &JsonValue) -> Result<()> {
+    http_recording::record_input(input)
+}
+
+#[cfg(not(feature = "recording"))]
+pub fn record_input(_input: &JsonValue) -> Result<()> {
+    Ok(())
+}
+
+/// Write the task output to the current recording session
+#[cfg(feature = "recording")]
+pub fn record_output(output: &JsonValue) -> Result<()> {
+    http_recording::record_output(output)
+}
+
+#[cfg(not(feature = "recording"))]
+pub fn record_output(_output: &JsonValue) -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,167 @@
+//! Deterministic replay of recorded task executions
+//!
+//! A recording directory written by `ratchet run-once --record` contains the task
+//! input (`input.json`), its output (`output.json`) and the HTTP traffic
+//! (`requests.har`). Replay runs the task against that input while serving every
+//! `fetch` from the HAR file, then compares the new output with the recorded one.
+
+use crate::test_runner::{json_diff, JsonDifference};
+use anyhow::{Context, Result};
+use ratchet_http::ReplayClient;
+use ratchet_js::{execution::execute_js_with_content, FileSystemTask};
+use serde_json::Value as JsonValue;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Outcome of replaying a recording
+#[derive(Debug, Clone)]
+pub struct ReplayReport {
+    pub recording_dir: PathBuf,
+    pub expected_output: Option<JsonValue>,
+    pub actual_output: Option<JsonValue>,
+    pub error: Option<String>,
+    pub differences: Vec<JsonDifference>,
+    pub unmatched_requests: Vec<String>,
+    pub unused_requests: Vec<String>,
+}
+
+impl ReplayReport {
+    /// Whether the replay reproduced the recorded output using only recorded traffic
+    pub fn is_success(&self) -> bool {
+        self.error.is_none() && self.differences.is_empty() && self.unmatched_requests.is_empty()
+    }
+
+    /// Render a human readable summary
+    pub fn to_text(&self) -> String {
+        let mut out = format!("Replaying recording {:?}\n", self.recording_dir);
+        if let Some(error) = &self.error {
+            out.push_str(&format!("  execution failed: {}\n", error));
+        }
+        for request in &self.unmatched_requests {
+            out.push_str(&format!("  unmatched request: {}\n", request));
+        }
+        for request in &self.unused_requests {
+            out.push_str(&format!("  recorded request not replayed: {}\n", request));
+        }
+        for difference in &self.differences {
+            out.push_str(&format!("  {}\n", difference));
+        }
+        if self.expected_output.is_none() {
+            out.push_str("  no recorded output to compare against\n");
+        }
+        out.push_str(&format!(
+            "Replay result: {}\n",
+            if self.is_success() { "ok" } else { "FAILED" }
+        ));
+        out
+    }
+}
+
+fn read_json(path: &Path) -> Result<Option<JsonValue>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+    let value = serde_json::from_str(&content).with_context(|| format!("Invalid JSON in {:?}", path))?;
+    Ok(Some(value))
+}
+
+/// Replay the recording in `recording_dir` against the task at `task_dir`
+pub async fn replay_recording(task_dir: &Path, recording_dir: &Path) -> Result<ReplayReport> {
+    let task = FileSystemTask::from_fs(task_dir).map_err(|e| anyhow::anyhow!("Failed to load task: {}", e))?;
+
+    let input = read_json(&recording_dir.join("input.json"))?.unwrap_or_else(|| JsonValue::Object(Default::default()));
+    let expected_output = read_json(&recording_dir.join("output.json"))?;
+
+    let har_path = recording_dir.join("requests.har");
+    let client = if har_path.exists() {
+        ReplayClient::from_har_file(&har_path)?
+    } else {
+        ReplayClient::new(Vec::new())
+    };
+
+    info!("Replaying recording {:?} for task '{}'", recording_dir, task.label());
+
+    let execution = execute_js_with_content(
+        &task.content,
+        input,
+        task.input_schema.as_ref(),
+        task.output_schema.as_ref(),
+        &client,
+        None,
+    )
+    .await;
+
+    let (actual_output, error) = match execution {
+        Ok(output) => (Some(output), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+
+    let differences = match (&expected_output, &actual_output) {
+        (Some(expected), Some(actual)) => json_diff(expected, actual),
+        _ => Vec::new(),
+    };
+
+    Ok(ReplayReport {
+        recording_dir: recording_dir.to_path_buf(),
+        expected_output,
+        actual_output,
+        error,
+        differences,
+        unmatched_requests: client.unmatched_requests(),
+        unused_requests: client
+            .unused_exchanges()
+            .iter()
+            .map(|exchange| format!("{} {}", exchange.method, exchange.url))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_replay_recording() {
+        let temp_dir = tempdir().unwrap();
+        let task_dir = temp_dir.path().join("task");
+        let recording_dir = temp_dir.path().join("recording");
+        fs::create_dir_all(&task_dir).unwrap();
+        fs::create_dir_all(&recording_dir).unwrap();
+
+        fs::write(task_dir.join("metadata.json"), r#"{ "label": "sample", "version": "1.0.0" }"#).unwrap();
+        fs::write(
+            task_dir.join("main.js"),
+            r#"async function main(input) {
+                const response = await fetch("http://api/users/" + input.id);
+                const user = await response.json();
+                return { name: user.name };
+            }"#,
+        )
+        .unwrap();
+
+        fs::write(recording_dir.join("input.json"), r#"{ "id": 7 }"#).unwrap();
+        fs::write(recording_dir.join("output.json"), r#"{ "name": "Ada" }"#).unwrap();
+        let har = json!({
+            "log": {
+                "entries": [{
+                    "request": { "method": "GET", "url": "http://api/users/7" },
+                    "response": { "status": 200, "statusText": "OK", "headers": [], "content": { "text": "{\"name\":\"Ada\"}" } }
+                }]
+            }
+        });
+        fs::write(recording_dir.join("requests.har"), har.to_string()).unwrap();
+
+        let report = replay_recording(&task_dir, &recording_dir).await.unwrap();
+        assert!(report.is_success(), "{}", report.to_text());
+
+        fs::write(recording_dir.join("input.json"), r#"{ "id": 8 }"#).unwrap();
+        let report = replay_recording(&task_dir, &recording_dir).await.unwrap();
+        assert!(!report.is_success());
+        assert_eq!(report.unmatched_requests, vec!["GET http://api/users/8".to_string()]);
+        assert_eq!(report.unused_requests, vec!["GET http://api/users/7".to_string()]);
+    }
+}
@@ -0,0 +1,560 @@
+//! Task test runner
+//!
+//! Discovers the `tests/*.json` cases that `generate_task` scaffolds, runs each one
+//! through the ratchet-js engine and compares the produced output with the expected
+//! output. Test cases may register HTTP mocks, in which case the task runs against an
+//! offline `HttpManager` that only answers the mocked requests.
+
+use anyhow::{Context, Result};
+use ratchet_http::{HttpManager, HttpMethod};
+use ratchet_js::{execution::execute_js_with_content, FileSystemTask};
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+/// A single test case loaded from a task's `tests` directory
+#[derive(Debug, Clone, Deserialize)]
+pub struct TaskTestCase {
+    /// Test name, defaults to the file name without extension
+    #[serde(default)]
+    pub name: String,
+
+    /// Optional human readable description
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Input passed to the task
+    #[serde(default)]
+    pub input: JsonValue,
+
+    /// Expected task output; when absent only successful execution is checked
+    #[serde(default, alias = "expected")]
+    pub expected_output: Option<JsonValue>,
+
+    /// Whether the task is expected to fail for this input
+    #[serde(default)]
+    pub should_fail: bool,
+
+    /// Substring the error message must contain when `should_fail` is set
+    #[serde(default)]
+    pub expected_error: Option<String>,
+
+    /// Mocks applied while this test runs
+    #[serde(default)]
+    pub mocks: TestMocks,
+
+    /// Single-mock form used by older task repositories (`"mock": { "http": { ... } }`)
+    #[serde(default)]
+    mock: Option<LegacyMock>,
+
+    /// File the test case was loaded from
+    #[serde(skip)]
+    pub source: PathBuf,
+}
+
+impl TaskTestCase {
+    /// All HTTP mocks for this test case, including the legacy single-mock form
+    pub fn http_mocks(&self) -> impl Iterator<Item = &HttpMock> {
+        self.mocks
+            .http
+            .iter()
+            .chain(self.mock.as_ref().and_then(|m| m.http.as_ref()))
+    }
+}
+
+/// Mocks a test case can register
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TestMocks {
+    /// HTTP responses keyed by method and URL
+    #[serde(default)]
+    pub http: Vec<HttpMock>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct LegacyMock {
+    #[serde(default)]
+    http: Option<HttpMock>,
+}
+
+/// A mocked HTTP response
+#[derive(Debug, Clone, Deserialize)]
+pub struct HttpMock {
+    /// Request method, defaults to GET
+    #[serde(default)]
+    pub method: HttpMethod,
+
+    /// Request URL
+    pub url: String,
+
+    /// Response body returned to the task
+    pub response: JsonValue,
+}
+
+/// Outcome of a single test case
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestStatus {
+    Passed,
+    Failed,
+}
+
+/// Result of running a single test case
+#[derive(Debug, Clone)]
+pub struct TestCaseResult {
+    pub name: String,
+    pub status: TestStatus,
+    pub duration: Duration,
+    pub message: Option<String>,
+    pub differences: Vec<JsonDifference>,
+    pub actual_output: Option<JsonValue>,
+}
+
+impl TestCaseResult {
+    pub fn passed(&self) -> bool {
+        self.status == TestStatus::Passed
+    }
+}
+
+/// Results of running every test case of a task
+#[derive(Debug, Clone)]
+pub struct TestReport {
+    pub task_name: String,
+    pub task_path: PathBuf,
+    pub results: Vec<TestCaseResult>,
+    pub duration: Duration,
+}
+
+impl TestReport {
+    pub fn passed(&self) -> usize {
+        self.results.iter().filter(|r| r.passed()).count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.results.len() - self.passed()
+    }
+
+    /// Whether every test case passed
+    pub fn is_success(&self) -> bool {
+        self.failed() == 0
+    }
+
+    /// Render a human readable summary
+    pub fn to_text(&self) -> String {
+        let mut out = format!("Running {} test(s) for task '{}'\n", self.results.len(), self.task_name);
+        for result in &self.results {
+            let label = if result.passed() { "ok" } else { "FAILED" };
+            out.push_str(&format!(
+                "  test {} ... {} ({} ms)\n",
+                result.name,
+                label,
+                result.duration.as_millis()
+            ));
+            if let Some(message) = &result.message {
+                out.push_str(&format!("      {}\n", message));
+            }
+            for difference in &result.differences {
+                out.push_str(&format!("      {}\n", difference));
+            }
+        }
+        out.push_str(&format!(
+            "\nTest result: {}. {} passed; {} failed; finished in {} ms\n",
+            if self.is_success() { "ok" } else { "FAILED" },
+            self.passed(),
+            self.failed(),
+            self.duration.as_millis()
+        ));
+        out
+    }
+
+    /// Render the report in TAP version 13 format
+    pub fn to_tap(&self) -> String {
+        let mut out = format!("TAP version 13\n1..{}\n", self.results.len());
+        for (index, result) in self.results.iter().enumerate() {
+            let status = if result.passed() { "ok" } else { "not ok" };
+            out.push_str(&format!("{} {} - {}\n", status, index + 1, result.name));
+            if !result.passed() {
+                out.push_str("  ---\n");
+                if let Some(message) = &result.message {
+                    out.push_str(&format!("  message: {}\n", serde_json::to_string(message).unwrap_or_default()));
+                }
+                if !result.differences.is_empty() {
+                    out.push_str("  differences:\n");
+                    for difference in &result.differences {
+                        out.push_str(&format!(
+                            "    - {}\n",
+                            serde_json::to_string(&difference.to_string()).unwrap_or_default()
+                        ));
+                    }
+                }
+                out.push_str("  ...\n");
+            }
+        }
+        out
+    }
+
+    /// Render the report as a JUnit XML document
+    pub fn to_junit_xml(&self) -> String {
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str(&format!(
+            "<testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" time=\"{:.3}\">\n",
+            xml_escape(&self.task_name),
+            self.results.len(),
+            self.failed(),
+            self.duration.as_secs_f64()
+        ));
+        for result in &self.results {
+            out.push_str(&format!(
+                "  <testcase classname=\"{}\" name=\"{}\" time=\"{:.3}\"",
+                xml_escape(&self.task_name),
+                xml_escape(&result.name),
+                result.duration.as_secs_f64()
+            ));
+            if result.passed() {
+                out.push_str("/>\n");
+                continue;
+            }
+            out.push_str(">\n");
+            let message = result.message.as_deref().unwrap_or("Test failed");
+            let details: Vec<String> = result.differences.iter().map(|d| d.to_string()).collect();
+            out.push_str(&format!(
+                "    <failure message=\"{}\">{}</failure>\n",
+                xml_escape(message),
+                xml_escape(&details.join("\n"))
+            ));
+            out.push_str("  </testcase>\n");
+        }
+        out.push_str("</testsuite>\n");
+        out
+    }
+}
+
+/// A single structural difference between expected and actual JSON
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonDifference {
+    /// JSON pointer to the differing value
+    pub path: String,
+    pub kind: DifferenceKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DifferenceKind {
+    /// Present in the expected output but not in the actual output
+    Missing { expected: JsonValue },
+    /// Present in the actual output but not in the expected output
+    Unexpected { actual: JsonValue },
+    /// Present in both with different values
+    Changed { expected: JsonValue, actual: JsonValue },
+}
+
+impl fmt::Display for JsonDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() { "/" } else { &self.path };
+        match &self.kind {
+            DifferenceKind::Missing { expected } => write!(f, "{}: missing, expected {}", path, expected),
+            DifferenceKind::Unexpected { actual } => write!(f, "{}: unexpected value {}", path, actual),
+            DifferenceKind::Changed { expected, actual } => {
+                write!(f, "{}: expected {}, got {}", path, expected, actual)
+            }
+        }
+    }
+}
+
+/// Compute the structural differences between two JSON values
+pub fn json_diff(expected: &JsonValue, actual: &JsonValue) -> Vec<JsonDifference> {
+    let mut differences = Vec::new();
+    diff_at(String::new(), expected, actual, &mut differences);
+    differences
+}
+
+fn diff_at(path: String, expected: &JsonValue, actual: &JsonValue, out: &mut Vec<JsonDifference>) {
+    match (expected, actual) {
+        (JsonValue::Object(expected_map), JsonValue::Object(actual_map)) => {
+            for (key, expected_value) in expected_map {
+                let child = format!("{}/{}", path, escape_pointer(key));
+                match actual_map.get(key) {
+                    Some(actual_value) => diff_at(child, expected_value, actual_value, out),
+                    None => out.push(JsonDifference {
+                        path: child,
+                        kind: DifferenceKind::Missing {
+                            expected: expected_value.clone(),
+                        },
+                    }),
+                }
+            }
+            for (key, actual_value) in actual_map {
+                if !expected_map.contains_key(key) {
+                    out.push(JsonDifference {
+                        path: format!("{}/{}", path, escape_pointer(key)),
+                        kind: DifferenceKind::Unexpected {
+                            actual: actual_value.clone(),
+                        },
+                    });
+                }
+            }
+        }
+        (JsonValue::Array(expected_items), JsonValue::Array(actual_items)) => {
+            for (index, expected_value) in expected_items.iter().enumerate() {
+                let child = format!("{}/{}", path, index);
+                match actual_items.get(index) {
+                    Some(actual_value) => diff_at(child, expected_value, actual_value, out),
+                    None => out.push(JsonDifference {
+                        path: child,
+                        kind: DifferenceKind::Missing {
+                            expected: expected_value.clone(),
+                        },
+                    }),
+                }
+            }
+            for (index, actual_value) in actual_items.iter().enumerate().skip(expected_items.len()) {
+                out.push(JsonDifference {
+                    path: format!("{}/{}", path, index),
+                    kind: DifferenceKind::Unexpected {
+                        actual: actual_value.clone(),
+                    },
+                });
+            }
+        }
+        _ if expected != actual => out.push(JsonDifference {
+            path,
+            kind: DifferenceKind::Changed {
+                expected: expected.clone(),
+                actual: actual.clone(),
+            },
+        }),
+        _ => {}
+    }
+}
+
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Load every `*.json` test case from the task's `tests` directory, sorted by file name
+pub fn discover_test_cases(task_dir: &Path) -> Result<Vec<TaskTestCase>> {
+    let tests_dir = task_dir.join("tests");
+    if !tests_dir.is_dir() {
+        debug!("No tests directory found at {:?}", tests_dir);
+        return Ok(Vec::new());
+    }
+
+    let mut paths: Vec<PathBuf> = fs::read_dir(&tests_dir)
+        .context(format!("Failed to read tests directory: {:?}", tests_dir))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("json"))
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let content = fs::read_to_string(&path).context(format!("Failed to read test file: {:?}", path))?;
+            let mut test_case: TaskTestCase =
+                serde_json::from_str(&content).context(format!("Invalid test file: {:?}", path))?;
+            if test_case.name.is_empty() {
+                test_case.name = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .unwrap_or("unnamed")
+                    .to_string();
+            }
+            test_case.source = path;
+            Ok(test_case)
+        })
+        .collect()
+}
+
+/// Run every test case of the task at `task_dir`
+///
+/// When `filter` is given only test cases whose name contains it are run.
+pub async fn run_task_tests(task_dir: &Path, filter: Option<&str>) -> Result<TestReport> {
+    let task = FileSystemTask::from_fs(task_dir).map_err(|e| anyhow::anyhow!("Failed to load task: {}", e))?;
+    task.validate().map_err(|e| anyhow::anyhow!("Invalid task: {}", e))?;
+
+    let test_cases: Vec<TaskTestCase> = discover_test_cases(task_dir)?
+        .into_iter()
+        .filter(|test_case| filter.map(|f| test_case.name.contains(f)).unwrap_or(true))
+        .collect();
+
+    info!("Running {} test case(s) for task '{}'", test_cases.len(), task.label());
+
+    let started = Instant::now();
+    let mut results = Vec::with_capacity(test_cases.len());
+    for test_case in &test_cases {
+        results.push(run_test_case(&task, test_case).await);
+    }
+
+    Ok(TestReport {
+        task_name: task.label().to_string(),
+        task_path: task_dir.to_path_buf(),
+        results,
+        duration: started.elapsed(),
+    })
+}
+
+/// Run a single test case against a loaded task
+pub async fn run_test_case(task: &FileSystemTask, test_case: &TaskTestCase) -> TestCaseResult {
+    debug!("Executing test case: {}", test_case.name);
+
+    let mut http_manager = HttpManager::new();
+    let mut mock_count = 0;
+    for mock in test_case.http_mocks() {
+        http_manager.add_mock(mock.method, &mock.url, mock.response.clone());
+        mock_count += 1;
+    }
+    if mock_count > 0 {
+        // Only mocked requests are answered so tests never reach the network
+        http_manager.set_offline();
+    }
+
+    let started = Instant::now();
+    let execution = execute_js_with_content(
+        &task.content,
+        test_case.input.clone(),
+        task.input_schema.as_ref(),
+        task.output_schema.as_ref(),
+        &http_manager,
+        None,
+    )
+    .await;
+    let duration = started.elapsed();
+
+    let mut result = TestCaseResult {
+        name: test_case.name.clone(),
+        status: TestStatus::Failed,
+        duration,
+        message: None,
+        differences: Vec::new(),
+        actual_output: None,
+    };
+
+    match (execution, test_case.should_fail) {
+        (Ok(actual), false) => {
+            if let Some(expected) = &test_case.expected_output {
+                result.differences = json_diff(expected, &actual);
+            }
+            if result.differences.is_empty() {
+                result.status = TestStatus::Passed;
+            } else {
+                result.message = Some("Output does not match expected result".to_string());
+            }
+            result.actual_output = Some(actual);
+        }
+        (Ok(actual), true) => {
+            result.message = Some("Expected the task to fail but it succeeded".to_string());
+            result.actual_output = Some(actual);
+        }
+        (Err(e), false) => {
+            result.message = Some(format!("Execution error: {}", e));
+        }
+        (Err(e), true) => {
+            let error = e.to_string();
+            match &test_case.expected_error {
+                Some(expected) if !error.contains(expected.as_str()) => {
+                    result.message = Some(format!(
+                        "Expected error containing '{}', got: {}",
+                        expected, error
+                    ));
+                }
+                _ => result.status = TestStatus::Passed,
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn write_task(dir: &Path, main_js: &str) {
+        fs::create_dir_all(dir.join("tests")).unwrap();
+        fs::write(
+            dir.join("metadata.json"),
+            r#"{ "label": "sample", "version": "1.0.0" }"#,
+        )
+        .unwrap();
+        fs::write(dir.join("main.js"), main_js).unwrap();
+    }
+
+    #[test]
+    fn test_json_diff_reports_paths() {
+        let expected = json!({ "a": 1, "b": { "c": [1, 2] }, "d": "x" });
+        let actual = json!({ "a": 1, "b": { "c": [1, 3, 4] }, "e": true });
+
+        let differences: Vec<String> = json_diff(&expected, &actual).iter().map(|d| d.to_string()).collect();
+
+        assert_eq!(
+            differences,
+            vec![
+                "/b/c/1: expected 2, got 3",
+                "/b/c/2: unexpected value 4",
+                "/d: missing, expected \"x\"",
+                "/e: unexpected value true",
+            ]
+        );
+        assert!(json_diff(&expected, &expected).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_task_tests_with_mocks_and_failures() {
+        let temp_dir = tempdir().unwrap();
+        let task_dir = temp_dir.path().join("task");
+        write_task(
+            &task_dir,
+            r#"async function main(input) {
+                if (input.url) {
+                    const response = await fetch(input.url);
+                    return await response.json();
+                }
+                return { doubled: input.n * 2 };
+            }"#,
+        );
+        fs::write(
+            task_dir.join("tests/test-001.json"),
+            r#"{ "input": { "n": 2 }, "expected_output": { "doubled": 4 } }"#,
+        )
+        .unwrap();
+        fs::write(
+            task_dir.join("tests/test-002.json"),
+            r#"{ "name": "wrong", "input": { "n": 2 }, "expected": { "doubled": 5 } }"#,
+        )
+        .unwrap();
+        fs::write(
+            task_dir.join("tests/test-003.json"),
+            r#"{
+                "input": { "url": "http://example.com/data" },
+                "expected_output": { "value": 42 },
+                "mocks": { "http": [{ "url": "http://example.com/data", "response": { "value": 42 } }] }
+            }"#,
+        )
+        .unwrap();
+
+        let report = run_task_tests(&task_dir, None).await.unwrap();
+
+        assert_eq!(report.results.len(), 3);
+        assert_eq!(report.passed(), 2);
+        assert!(!report.is_success());
+        assert_eq!(report.results[0].name, "test-001");
+        assert_eq!(report.results[1].differences.len(), 1);
+        assert!(report.to_tap().contains("not ok 2 - wrong"));
+        assert!(report.to_junit_xml().contains("failures=\"1\""));
+
+        let filtered = run_task_tests(&task_dir, Some("test-00")).await.unwrap();
+        assert!(filtered.is_success());
+    }
+}
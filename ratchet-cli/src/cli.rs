@@ -41,6 +41,10 @@ pub enum Commands {
         /// Record execution to directory with timestamp
         #[arg(long, value_name = "PATH")]
         record: Option<PathBuf>,
+
+        /// Path to configuration file, whose execution limits apply to the run
+        #[arg(long, value_name = "PATH")]
+        config: Option<PathBuf>,
     },
 
     /// Start the Ratchet server
@@ -98,11 +102,23 @@ pub enum Commands {
         fix: bool,
     },
 
-    /// Test a task
+    /// Run the test cases in a task's tests directory
     Test {
         /// Path to the file system resource
         #[arg(long, value_name = "STRING")]
         from_fs: String,
+
+        /// Report format: text, junit, tap
+        #[arg(long, value_name = "FORMAT", default_value = "text")]
+        format: String,
+
+        /// Write the report to a file instead of stdout
+        #[arg(long, value_name = "PATH")]
+        output: Option<PathBuf>,
+
+        /// Only run test cases whose name contains this string
+        #[arg(long, value_name = "STRING")]
+        filter: Option<String>,
     },
 
     /// Replay a recorded task execution
@@ -111,7 +127,7 @@ pub enum Commands {
         #[arg(long, value_name = "STRING")]
         from_fs: String,
 
-        /// Path to the recording directory
+        /// Recording session directory containing input.json, output.json and requests.har
         #[arg(long, value_name = "PATH")]
         recording: Option<PathBuf>,
     },
@@ -134,6 +150,18 @@ pub enum Commands {
         repo_cmd: RepoCommands,
     },
 
+    /// Manage encrypted task secrets
+    Secret {
+        #[command(subcommand)]
+        secret_cmd: SecretCommands,
+    },
+
+    /// Sign tasks for registries that verify signatures
+    Task {
+        #[command(subcommand)]
+        task_cmd: TaskCommands,
+    },
+
     /// Start an interactive console for Ratchet administration
     Console {
         /// Path to configuration file
@@ -314,6 +342,91 @@ pub enum ConfigCommands {
     },
 }
 
+#[derive(Subcommand)]
+pub enum SecretCommands {
+    /// Create or replace a secret
+    Set {
+        /// Secret name, as used with ratchet.secrets.get
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        /// Secret value (read from stdin when omitted)
+        #[arg(long, value_name = "STRING")]
+        value: Option<String>,
+
+        /// Scope of the secret: global, repository or task
+        #[arg(long, value_name = "SCOPE", default_value = "global")]
+        scope: String,
+
+        /// Repository ID for repository secrets, task UUID for task secrets
+        #[arg(long, value_name = "ID")]
+        scope_id: Option<String>,
+
+        /// Secret description
+        #[arg(long, value_name = "STRING")]
+        description: Option<String>,
+
+        /// Path to configuration file
+        #[arg(long, value_name = "PATH")]
+        config: Option<PathBuf>,
+    },
+
+    /// List secret names and scopes
+    List {
+        /// Only list secrets in this scope
+        #[arg(long, value_name = "SCOPE")]
+        scope: Option<String>,
+
+        /// Path to configuration file
+        #[arg(long, value_name = "PATH")]
+        config: Option<PathBuf>,
+    },
+
+    /// Delete a secret
+    Delete {
+        /// Secret name
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        /// Scope of the secret: global, repository or task
+        #[arg(long, value_name = "SCOPE", default_value = "global")]
+        scope: String,
+
+        /// Repository ID for repository secrets, task UUID for task secrets
+        #[arg(long, value_name = "ID")]
+        scope_id: Option<String>,
+
+        /// Path to configuration file
+        #[arg(long, value_name = "PATH")]
+        config: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TaskCommands {
+    /// Write a detached signature.json for a task directory
+    Sign {
+        /// Path to the task directory
+        #[arg(long, value_name = "STRING")]
+        from_fs: String,
+
+        /// Ed25519 private key in PKCS#8 DER format
+        #[arg(long, value_name = "PATH")]
+        key: PathBuf,
+    },
+
+    /// Generate an ed25519 signing key and print its public key
+    Keygen {
+        /// Where to write the PKCS#8 DER private key
+        #[arg(long, value_name = "PATH")]
+        output: PathBuf,
+
+        /// Overwrite an existing key file
+        #[arg(long)]
+        force: bool,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum RepoCommands {
     /// Initialize a new task repository
@@ -28,11 +28,11 @@ use ratchet_core::task::Task as CoreTask;
 use ratchet_runtime::{InMemoryTaskExecutor, TaskExecutor};
 
 #[cfg(feature = "javascript")]
-use ratchet_js::load_and_execute_task;
+use ratchet_js::load_and_execute_task_with_limits;
 
 mod cli;
 mod commands;
-use cli::{Cli, Commands, ConfigCommands, GenerateCommands, RepoCommands};
+use cli::{Cli, Commands, ConfigCommands, GenerateCommands, RepoCommands, SecretCommands, TaskCommands};
 
 /// Convert ratchet-storage RepositoryFactory to ratchet_lib RepositoryFactory
 // Legacy repository factory function removed in 0.5.0 - use ratchet-storage directly
@@ -304,6 +304,10 @@ async fn mcp_serve_command_with_config(config: RatchetConfig, transport: &str, h
         task_timeout_seconds: 300,
         restart_on_crash: true,
         max_restart_attempts: 3,
+        limits: config.execution.limits.clone(),
+        calls: None,
+        egress: Default::default(),
+        http_client: Default::default(),
     };
     let execution_bridge = Arc::new(ExecutionBridge::new(execution_config));
 
@@ -1008,6 +1012,181 @@ async fn list_tasks(config_path: Option<&PathBuf>, format: &str) -> Result<()> {
     Ok(())
 }
 
+/// Open the secret store of the configured database
+#[cfg(feature = "server")]
+async fn open_secret_store(config_path: Option<&PathBuf>) -> Result<ratchet_server::security::SecretManager> {
+    use ratchet_server::security::{secrets_encryption_service, EncryptionService, SecretManager};
+    use ratchet_storage::seaorm::connection::DatabaseConnection;
+    use ratchet_storage::seaorm::repositories::TaskSecretRepository;
+
+    let config = load_config(config_path)?;
+    let server_config = config.server.as_ref().ok_or_else(|| {
+        anyhow::anyhow!("No server configuration found. Database connection required for secrets.")
+    })?;
+
+    let storage_db_config = ratchet_storage::seaorm::config::DatabaseConfig {
+        url: server_config.database.url.clone(),
+        max_connections: server_config.database.max_connections,
+        connection_timeout: server_config.database.connection_timeout,
+    };
+    let connection = DatabaseConnection::new(storage_db_config)
+        .await
+        .context("Failed to connect to database")?;
+    connection.migrate().await.context("Failed to run database migrations")?;
+
+    if std::env::var(ratchet_server::security::SECRETS_KEY_ENV).is_err() {
+        return Err(anyhow::anyhow!(
+            "{} must be set to the server's secrets key",
+            ratchet_server::security::SECRETS_KEY_ENV
+        ));
+    }
+    let encryption = secrets_encryption_service().await?;
+
+    Ok(SecretManager::new(
+        TaskSecretRepository::new(connection),
+        encryption as Arc<dyn EncryptionService>,
+    ))
+}
+
+/// Manage encrypted task secrets
+#[cfg(feature = "server")]
+async fn secret_command(secret_cmd: SecretCommands) -> Result<()> {
+    use ratchet_api_types::SecretScope;
+    use ratchet_interfaces::{SecretKey, SecretStore};
+    use std::io::Read;
+
+    let parse_scope = |name: &str| {
+        SecretScope::from_name(name)
+            .ok_or_else(|| anyhow::anyhow!("Unknown secret scope '{}'; expected global, repository or task", name))
+    };
+
+    match secret_cmd {
+        SecretCommands::Set {
+            name,
+            value,
+            scope,
+            scope_id,
+            description,
+            config,
+        } => {
+            let key = SecretKey::new(name, parse_scope(&scope)?, scope_id);
+            // Reading from stdin keeps the value out of shell history
+            let value = match value {
+                Some(value) => value,
+                None => {
+                    let mut value = String::new();
+                    std::io::stdin()
+                        .read_to_string(&mut value)
+                        .context("Failed to read secret value from stdin")?;
+                    value.trim_end_matches(['\r', '\n']).to_string()
+                }
+            };
+
+            let store = open_secret_store(config.as_ref()).await?;
+            let secret = store.set_secret(key, &value, description).await?;
+            println!("Secret '{}' stored ({:?} scope)", secret.name, secret.scope);
+        }
+        SecretCommands::List { scope, config } => {
+            let scope = scope.as_deref().map(parse_scope).transpose()?;
+            let store = open_secret_store(config.as_ref()).await?;
+            let secrets = store.list_secrets(scope).await?;
+
+            if secrets.is_empty() {
+                println!("No secrets found.");
+            } else {
+                println!("{:<30} {:<12} {:<38} {}", "Name", "Scope", "Scope ID", "Description");
+                println!("{}", "=".repeat(100));
+                for secret in &secrets {
+                    println!(
+                        "{:<30} {:<12} {:<38} {}",
+                        secret.name,
+                        format!("{:?}", secret.scope),
+                        secret.scope_id.as_deref().unwrap_or("-"),
+                        secret.description.as_deref().unwrap_or("")
+                    );
+                }
+            }
+        }
+        SecretCommands::Delete {
+            name,
+            scope,
+            scope_id,
+            config,
+        } => {
+            let key = SecretKey::new(name.clone(), parse_scope(&scope)?, scope_id);
+            let store = open_secret_store(config.as_ref()).await?;
+            if store.delete_secret(key).await? {
+                println!("Secret '{}' deleted", name);
+            } else {
+                return Err(anyhow::anyhow!("Secret '{}' not found", name));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "server"))]
+async fn secret_command(_secret_cmd: SecretCommands) -> Result<()> {
+    Err(anyhow::anyhow!(
+        "Server feature not enabled. Please compile with --features server"
+    ))
+}
+
+/// Sign tasks and manage signing keys
+#[cfg(any(feature = "server", feature = "git"))]
+async fn task_command(task_cmd: TaskCommands) -> Result<()> {
+    use ratchet_registry::TaskSigner;
+
+    match task_cmd {
+        TaskCommands::Sign { from_fs, key } => {
+            let task_dir = Path::new(&from_fs);
+            if !task_dir.join("metadata.json").exists() {
+                return Err(anyhow::anyhow!("{} is not a task directory", from_fs));
+            }
+
+            let pkcs8 = std::fs::read(&key).with_context(|| format!("Failed to read key {}", key.display()))?;
+            let signer = TaskSigner::from_pkcs8(&pkcs8)?;
+            let signature = signer.sign_task_directory(task_dir).await?;
+            println!(
+                "Signed {} with key {}",
+                task_dir.join(ratchet_registry::signing::SIGNATURE_FILE).display(),
+                signature.key_id
+            );
+        }
+        TaskCommands::Keygen { output, force } => {
+            if output.exists() && !force {
+                return Err(anyhow::anyhow!(
+                    "{} already exists. Use --force to overwrite",
+                    output.display()
+                ));
+            }
+
+            let pkcs8 = TaskSigner::generate()?;
+            let signer = TaskSigner::from_pkcs8(&pkcs8)?;
+            std::fs::write(&output, &pkcs8).with_context(|| format!("Failed to write {}", output.display()))?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(&output, std::fs::Permissions::from_mode(0o600))?;
+            }
+
+            println!("Private key written to {}", output.display());
+            println!("Key ID:     {}", signer.key_id());
+            println!("Public key: {}", signer.public_key_base64());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(any(feature = "server", feature = "git")))]
+async fn task_command(_task_cmd: TaskCommands) -> Result<()> {
+    Err(anyhow::anyhow!(
+        "Registry support not enabled. Please compile with --features git"
+    ))
+}
+
 /// Display status information
 async fn status_command(config_path: Option<&PathBuf>) -> Result<()> {
     let config = load_config(config_path)?;
@@ -1138,7 +1317,7 @@ async fn test_http_request(_url: &str, _method: &str, _headers: Option<&str>, _b
 /// JavaScript execution functions  
 #[cfg(feature = "javascript")]
 async fn execute_js_task(
-    _config_path: Option<&PathBuf>,
+    config_path: Option<&PathBuf>,
     script_path: &str,
     input_data: Option<&str>,
     output_format: &str,
@@ -1151,8 +1330,9 @@ async fn execute_js_task(
         None => json!({}),
     };
 
-    // Execute the task directly from filesystem path
-    let result = load_and_execute_task(script_path, input)
+    // Execute the task directly from filesystem path, under the configured execution limits
+    let limits = load_config(config_path)?.execution.limits;
+    let result = load_and_execute_task_with_limits(script_path, input, &limits)
         .await
         .map_err(|e| anyhow::anyhow!("JavaScript task execution failed: {}", e))?;
 
@@ -1191,16 +1371,175 @@ async fn execute_js_task(
     ))
 }
 
+/// Execute a JavaScript task and record its input, output and HTTP traffic
+#[cfg(feature = "javascript")]
+async fn execute_js_task_recorded(
+    config_path: Option<&PathBuf>,
+    script_path: &str,
+    input_data: Option<&str>,
+    record_dir: &Path,
+) -> Result<()> {
+    let limits = load_config(config_path)?.execution.limits;
+    let input: JsonValue = match input_data {
+        Some(data) => from_str(data).context("Failed to parse input JSON")?,
+        None => json!({}),
+    };
+
+    let session_dir = record_dir.join(format!(
+        "ratchet_session_{}",
+        chrono::Utc::now().format("%Y%m%d_%H%M%S")
+    ));
+    std::fs::create_dir_all(&session_dir)
+        .context(format!("Failed to create recording directory: {:?}", session_dir))?;
+
+    ratchet_cli_tools::set_recording_dir(session_dir.clone())?;
+    ratchet_cli_tools::record_input(&input)?;
+
+    let result = load_and_execute_task_with_limits(script_path, input, &limits).await;
+    if let Ok(output) = &result {
+        ratchet_cli_tools::record_output(output)?;
+    }
+    ratchet_cli_tools::finalize_recording()?;
+
+    let result = result.map_err(|e| anyhow::anyhow!("JavaScript task execution failed: {}", e))?;
+    println!("{}", to_string_pretty(&result)?);
+    info!("Recording saved to {:?}", session_dir);
+
+    Ok(())
+}
+
+#[cfg(not(feature = "javascript"))]
+async fn execute_js_task_recorded(
+    _config_path: Option<&PathBuf>,
+    _script_path: &str,
+    _input_data: Option<&str>,
+    _record_dir: &Path,
+) -> Result<()> {
+    Err(anyhow::anyhow!(
+        "JavaScript feature not enabled. Please compile with --features javascript"
+    ))
+}
+
+/// Replay a recorded execution against its captured HTTP traffic
+#[cfg(feature = "javascript")]
+async fn replay_task(task_path: &str, recording_dir: &Path) -> Result<()> {
+    info!("Replaying {:?} for task: {}", recording_dir, task_path);
+
+    let report = ratchet_cli_tools::replay_recording(Path::new(task_path), recording_dir).await?;
+    print!("{}", report.to_text());
+
+    if let Some(output) = &report.actual_output {
+        debug!("Replay output: {}", to_string_pretty(output)?);
+    }
+
+    if !report.is_success() {
+        return Err(anyhow::anyhow!("Replay did not reproduce the recorded execution"));
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "javascript"))]
+async fn replay_task(_task_path: &str, _recording_dir: &Path) -> Result<()> {
+    Err(anyhow::anyhow!(
+        "JavaScript feature not enabled. Please compile with --features javascript"
+    ))
+}
+
+/// Run a task's test cases and report the results
+#[cfg(feature = "javascript")]
+async fn run_task_tests(
+    task_path: &str,
+    format: &str,
+    output_path: Option<&PathBuf>,
+    filter: Option<&str>,
+) -> Result<()> {
+    info!("Running tests for task: {}", task_path);
+
+    let report = ratchet_cli_tools::run_task_tests(Path::new(task_path), filter).await?;
+
+    let rendered = match format.to_lowercase().as_str() {
+        "text" => report.to_text(),
+        "junit" => report.to_junit_xml(),
+        "tap" => report.to_tap(),
+        _ => {
+            return Err(anyhow::anyhow!(
+                "Unsupported report format: {}. Use text, junit, or tap",
+                format
+            ));
+        }
+    };
+
+    match output_path {
+        Some(path) => {
+            std::fs::write(path, &rendered).context(format!("Failed to write test report to {:?}", path))?;
+            println!(
+                "{} passed, {} failed. Report written to {:?}",
+                report.passed(),
+                report.failed(),
+                path
+            );
+        }
+        None => print!("{}", rendered),
+    }
+
+    if report.results.is_empty() {
+        warn!("No test cases found in {}/tests", task_path);
+    }
+
+    if !report.is_success() {
+        return Err(anyhow::anyhow!(
+            "{} of {} test(s) failed",
+            report.failed(),
+            report.results.len()
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "javascript"))]
+async fn run_task_tests(
+    _task_path: &str,
+    _format: &str,
+    _output_path: Option<&PathBuf>,
+    _filter: Option<&str>,
+) -> Result<()> {
+    Err(anyhow::anyhow!(
+        "JavaScript feature not enabled. Please compile with --features javascript"
+    ))
+}
+
+/// Run this process as a task worker spawned by the worker process manager
+#[cfg(feature = "runtime")]
+async fn run_worker(worker_id: String) -> Result<()> {
+    ratchet_runtime::worker_main(worker_id)
+        .await
+        .map_err(|e| anyhow::anyhow!("Worker failed: {:?}", e))
+}
+
+#[cfg(not(feature = "runtime"))]
+async fn run_worker(_worker_id: String) -> Result<()> {
+    Err(anyhow::anyhow!("Worker mode requires the 'runtime' feature"))
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
-        .init();
-
     // Parse command line arguments
     let cli = Cli::parse();
 
+    // Initialize logging; worker processes use stdout for IPC, so they log to stderr
+    let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    if cli.worker {
+        tracing_subscriber::fmt()
+            .with_env_filter(env_filter)
+            .with_writer(std::io::stderr)
+            .init();
+        let worker_id = cli.worker_id.clone().unwrap_or_else(|| format!("worker-{}", std::process::id()));
+        return run_worker(worker_id).await;
+    }
+    tracing_subscriber::fmt().with_env_filter(env_filter).init();
+
     // Handle subcommands
     match cli.command {
         Some(Commands::Config { config_cmd }) => match config_cmd {
@@ -1300,18 +1639,34 @@ async fn main() -> Result<()> {
         Some(Commands::RunOnce {
             from_fs,
             input_json,
-            record: _,
-        }) => {
-            execute_js_task(None, &from_fs, input_json.as_deref(), "json").await?;
-        }
+            record,
+            config,
+        }) => match record {
+            Some(record_dir) => {
+                execute_js_task_recorded(config.as_ref(), &from_fs, input_json.as_deref(), &record_dir).await?
+            }
+            None => execute_js_task(config.as_ref(), &from_fs, input_json.as_deref(), "json").await?,
+        },
         Some(Commands::Validate { from_fs, fix }) => {
             validate_task(&from_fs, fix).await?;
         }
-        Some(Commands::Test { from_fs }) => {
-            execute_js_task(None, &from_fs, None, "json").await?;
+        Some(Commands::Test {
+            from_fs,
+            format,
+            output,
+            filter,
+        }) => {
+            run_task_tests(&from_fs, &format, output.as_ref(), filter.as_deref()).await?;
+        }
+        Some(Commands::Replay { from_fs, recording }) => {
+            let recording = recording.ok_or_else(|| anyhow::anyhow!("--recording is required for replay"))?;
+            replay_task(&from_fs, &recording).await?;
+        }
+        Some(Commands::Secret { secret_cmd }) => {
+            secret_command(secret_cmd).await?;
         }
-        Some(Commands::Replay { from_fs, recording: _ }) => {
-            execute_js_task(None, &from_fs, None, "json").await?;
+        Some(Commands::Task { task_cmd }) => {
+            task_command(task_cmd).await?;
         }
         Some(Commands::Console {
             config,
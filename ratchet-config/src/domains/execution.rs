@@ -1,6 +1,6 @@
 //! Task execution configuration
 
-use crate::error::ConfigResult;
+use crate::error::{ConfigError, ConfigResult};
 use crate::validation::{validate_positive, validate_required_string, Validatable};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
@@ -34,6 +34,80 @@ pub struct ExecutionConfig {
         default = "default_timeout_grace_period"
     )]
     pub timeout_grace_period: Duration,
+
+    /// Resource limits applied to every task unless overridden in task metadata
+    #[serde(default)]
+    pub limits: ResourceLimits,
+
+    /// How long an idempotency key keeps returning the job it was first used for
+    #[serde(
+        with = "crate::domains::utils::serde_duration",
+        default = "default_idempotency_retention"
+    )]
+    pub idempotency_retention: Duration,
+}
+
+/// Per-execution resource limits
+///
+/// Unset limits are not enforced. Loop, recursion, output and wall-clock limits are
+/// enforced by the JavaScript engine; heap and wall-clock limits are also enforced
+/// by the worker coordinator, which kills and restarts the worker process when they
+/// trip. Tasks can tighten or relax them under `limits` in their metadata.
+///
+/// Heap limits need a worker process of their own. Executions that run inside the
+/// server or CLI process (jobs, workflow steps, `ratchet.call` and `run-once`)
+/// cannot enforce them and refuse to run with `max_heap_mb` set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResourceLimits {
+    /// Maximum resident memory of the worker process in megabytes; only worker
+    /// processes enforce it
+    pub max_heap_mb: Option<u64>,
+
+    /// Maximum number of iterations of a single loop
+    pub max_loop_iterations: Option<u64>,
+
+    /// Maximum call depth
+    pub max_recursion_depth: Option<usize>,
+
+    /// Maximum size of the serialized task output in bytes
+    pub max_output_bytes: Option<usize>,
+
+    /// Wall-clock limit in seconds, overriding `max_execution_duration`
+    pub timeout_seconds: Option<u64>,
+
+    /// Maximum nesting of `ratchet.call`; the runtime applies its own default when unset
+    pub max_call_depth: Option<usize>,
+}
+
+impl ResourceLimits {
+    /// Combine with per-task overrides; limits set in `overrides` take precedence
+    pub fn with_overrides(&self, overrides: &ResourceLimits) -> Self {
+        Self {
+            max_heap_mb: overrides.max_heap_mb.or(self.max_heap_mb),
+            max_loop_iterations: overrides.max_loop_iterations.or(self.max_loop_iterations),
+            max_recursion_depth: overrides.max_recursion_depth.or(self.max_recursion_depth),
+            max_output_bytes: overrides.max_output_bytes.or(self.max_output_bytes),
+            timeout_seconds: overrides.timeout_seconds.or(self.timeout_seconds),
+            max_call_depth: overrides.max_call_depth.or(self.max_call_depth),
+        }
+    }
+
+    /// Whether no limit is set
+    pub fn is_unlimited(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Refuse limits that only a worker process can enforce, for executions that run in-process
+    pub fn check_in_process(&self) -> ConfigResult<()> {
+        match self.max_heap_mb {
+            Some(mb) => Err(ConfigError::ValidationError(format!(
+                "a heap limit of {} MB can only be enforced on a worker process",
+                mb
+            ))),
+            None => Ok(()),
+        }
+    }
 }
 
 /// JavaScript fetch variables configuration
@@ -69,6 +143,8 @@ impl Default for ExecutionConfig {
             validate_schemas: true,
             max_concurrent_tasks: default_max_concurrent_tasks(),
             timeout_grace_period: default_timeout_grace_period(),
+            limits: ResourceLimits::default(),
+            idempotency_retention: default_idempotency_retention(),
         }
     }
 }
@@ -102,9 +178,17 @@ impl Validatable for ExecutionConfig {
 
         validate_positive(self.max_concurrent_tasks, "max_concurrent_tasks", self.domain_name())?;
 
+        validate_positive(
+            self.idempotency_retention.as_secs(),
+            "idempotency_retention",
+            self.domain_name(),
+        )?;
+
         // Validate fetch variables
         self.fetch_variables.validate()?;
 
+        self.limits.validate()?;
+
         Ok(())
     }
 
@@ -129,6 +213,35 @@ impl Validatable for FetchVariables {
     }
 }
 
+impl Validatable for ResourceLimits {
+    fn validate(&self) -> ConfigResult<()> {
+        if let Some(value) = self.max_heap_mb {
+            validate_positive(value, "max_heap_mb", self.domain_name())?;
+        }
+        if let Some(value) = self.max_loop_iterations {
+            validate_positive(value, "max_loop_iterations", self.domain_name())?;
+        }
+        if let Some(value) = self.max_recursion_depth {
+            validate_positive(value, "max_recursion_depth", self.domain_name())?;
+        }
+        if let Some(value) = self.max_output_bytes {
+            validate_positive(value, "max_output_bytes", self.domain_name())?;
+        }
+        if let Some(value) = self.timeout_seconds {
+            validate_positive(value, "timeout_seconds", self.domain_name())?;
+        }
+        if let Some(value) = self.max_call_depth {
+            validate_positive(value, "max_call_depth", self.domain_name())?;
+        }
+
+        Ok(())
+    }
+
+    fn domain_name(&self) -> &'static str {
+        "execution.limits"
+    }
+}
+
 // Default value functions
 fn default_max_execution_duration() -> Duration {
     Duration::from_secs(300) // 5 minutes
@@ -142,6 +255,10 @@ fn default_timeout_grace_period() -> Duration {
     Duration::from_secs(5)
 }
 
+fn default_idempotency_retention() -> Duration {
+    Duration::from_secs(24 * 60 * 60) // 24 hours
+}
+
 fn default_url_var() -> String {
     "__fetch_url".to_string()
 }
@@ -184,6 +301,32 @@ mod tests {
         assert!(config.validate().is_err());
     }
 
+    #[test]
+    fn test_resource_limits_overrides() {
+        let global = ResourceLimits {
+            max_heap_mb: Some(512),
+            max_loop_iterations: Some(1_000_000),
+            ..Default::default()
+        };
+        let task = ResourceLimits {
+            max_loop_iterations: Some(10),
+            max_output_bytes: Some(1024),
+            ..Default::default()
+        };
+
+        let merged = global.with_overrides(&task);
+        assert_eq!(merged.max_heap_mb, Some(512));
+        assert_eq!(merged.max_loop_iterations, Some(10));
+        assert_eq!(merged.max_output_bytes, Some(1024));
+        assert!(ResourceLimits::default().is_unlimited());
+        assert!(merged.check_in_process().is_err());
+        assert!(task.check_in_process().is_ok());
+
+        let mut config = ExecutionConfig::default();
+        config.limits.max_recursion_depth = Some(0);
+        assert!(config.validate().is_err());
+    }
+
     #[test]
     fn test_fetch_variables_validation() {
         let mut vars = FetchVariables::default();
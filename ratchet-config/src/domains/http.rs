@@ -1,8 +1,9 @@
 //! HTTP client configuration
 
-use crate::error::ConfigResult;
+use crate::error::{ConfigError, ConfigResult};
 use crate::validation::{validate_positive, validate_required_string, Validatable};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use std::time::Duration;
 
 /// HTTP client configuration
@@ -32,6 +33,26 @@ pub struct HttpConfig {
     /// Proxy configuration
     #[serde(skip_serializing_if = "Option::is_none")]
     pub proxy: Option<ProxyConfig>,
+
+    /// Additional trusted CA certificates and client certificates
+    #[serde(default)]
+    pub tls: HttpTlsConfig,
+
+    /// Where task `fetch` calls may connect
+    #[serde(default)]
+    pub egress: EgressPolicy,
+
+    /// Shared response cache for task `fetch` calls
+    #[serde(default)]
+    pub cache: HttpResponseCacheConfig,
+
+    /// Retries of task `fetch` calls that failed transiently
+    #[serde(default)]
+    pub retry: HttpRetryConfig,
+
+    /// Per-host circuit breakers for task `fetch` calls
+    #[serde(default)]
+    pub circuit_breaker: HttpCircuitBreakerConfig,
 }
 
 /// Connection pool configuration
@@ -55,7 +76,10 @@ pub struct ConnectionPoolConfig {
 }
 
 /// Proxy configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Proxy URLs may use the `http`, `https`, `socks5` or `socks5h` scheme; with
+/// `socks5h` host names are resolved by the proxy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ProxyConfig {
     /// HTTP proxy URL
     pub http_proxy: Option<String>,
@@ -63,10 +87,352 @@ pub struct ProxyConfig {
     /// HTTPS proxy URL
     pub https_proxy: Option<String>,
 
+    /// Proxy for requests of either scheme without a proxy of their own
+    #[serde(default)]
+    pub all_proxy: Option<String>,
+
     /// No proxy hosts (comma-separated)
+    ///
+    /// Entries are host names, which also match their subdomains, domains with
+    /// a leading `.` or `*.`, IP addresses, CIDR ranges, or `*` for every host.
     pub no_proxy: Option<String>,
 }
 
+/// Certificates used by outbound HTTP clients
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpTlsConfig {
+    /// PEM files of CA certificates trusted in addition to the built-in roots
+    pub ca_bundles: Vec<PathBuf>,
+
+    /// Client certificates presented to the hosts they are configured for
+    pub client_identities: Vec<ClientIdentityConfig>,
+}
+
+/// Client certificate presented to some hosts for mutual TLS
+///
+/// The certificate chain and private key come either from PEM files or from
+/// a global secret in the secret store holding both in PEM form.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClientIdentityConfig {
+    /// Hosts the certificate is presented to; `*.example.com` matches any subdomain of `example.com`
+    pub hosts: Vec<String>,
+
+    /// PEM file with the certificate chain, and the private key unless `key_path` is set
+    pub cert_path: Option<PathBuf>,
+
+    /// PEM file with the private key
+    pub key_path: Option<PathBuf>,
+
+    /// Name of the secret holding the certificate chain and private key
+    pub secret: Option<String>,
+}
+
+/// How outbound HTTP clients connect: proxies and certificates
+///
+/// Unlike [`HttpClientPolicy`], tasks cannot override any of it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpTransportConfig {
+    /// Proxies requests are sent through
+    pub proxy: Option<ProxyConfig>,
+
+    /// Additional trusted CA certificates and client certificates
+    pub tls: HttpTlsConfig,
+}
+
+impl HttpTransportConfig {
+    /// Names of the secrets client identities are read from
+    pub fn secret_names(&self) -> Vec<String> {
+        self.tls
+            .client_identities
+            .iter()
+            .filter_map(|identity| identity.secret.clone())
+            .collect()
+    }
+}
+
+/// Outbound connection policy for task HTTP calls
+///
+/// Deny lists always win. A non-empty allow list admits only what it names;
+/// `allow_hosts` and `allow_cidrs` together form one list, so a destination
+/// matching either is allowed. Private, loopback and link-local addresses are
+/// refused while `block_private_ranges` is set unless an `allow_cidrs` entry
+/// contains them.
+///
+/// Requests sent through a proxy are checked against the addresses their host
+/// resolves to locally. While `block_private_ranges` is set, hosts that do not
+/// resolve locally are refused unless `allow_hosts` names them.
+///
+/// Tasks can declare a policy of their own under `egress` in their metadata;
+/// their requests must then pass both policies.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EgressPolicy {
+    /// Host names requests may go to; `*.example.com` matches any subdomain of `example.com`
+    pub allow_hosts: Vec<String>,
+
+    /// Host names requests may not go to, in the same form as `allow_hosts`
+    pub deny_hosts: Vec<String>,
+
+    /// Address ranges in CIDR notation requests may connect to
+    pub allow_cidrs: Vec<String>,
+
+    /// Address ranges in CIDR notation requests may not connect to
+    pub deny_cidrs: Vec<String>,
+
+    /// Ports requests may connect to
+    pub allow_ports: Vec<u16>,
+
+    /// Ports requests may not connect to
+    pub deny_ports: Vec<u16>,
+
+    /// URL schemes requests may use
+    #[serde(default = "default_allow_schemes")]
+    pub allow_schemes: Vec<String>,
+
+    /// URL schemes requests may not use
+    pub deny_schemes: Vec<String>,
+
+    /// Refuse private, loopback, link-local and other non-public addresses
+    #[serde(default = "crate::domains::utils::default_true")]
+    pub block_private_ranges: bool,
+}
+
+impl Default for EgressPolicy {
+    fn default() -> Self {
+        Self {
+            allow_hosts: Vec::new(),
+            deny_hosts: Vec::new(),
+            allow_cidrs: Vec::new(),
+            deny_cidrs: Vec::new(),
+            allow_ports: Vec::new(),
+            deny_ports: Vec::new(),
+            allow_schemes: default_allow_schemes(),
+            deny_schemes: Vec::new(),
+            block_private_ranges: true,
+        }
+    }
+}
+
+impl EgressPolicy {
+    /// A policy that allows every destination
+    pub fn unrestricted() -> Self {
+        Self {
+            allow_schemes: Vec::new(),
+            block_private_ranges: false,
+            ..Self::default()
+        }
+    }
+}
+
+/// Shared cache of responses to task HTTP calls
+///
+/// Only `GET` and `HEAD` responses are stored. `Cache-Control: no-store` and
+/// `private` responses are never stored, nor are responses to requests with an
+/// `Authorization` header unless they are marked `public` or carry `s-maxage`.
+/// A stored response is only reused for requests with the same headers, so
+/// responses to one caller's credentials or cookies never reach another.
+/// Entries carrying an `ETag` or `Last-Modified` validator are kept after they
+/// go stale and are revalidated with a conditional request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpResponseCacheConfig {
+    /// Whether responses are cached
+    #[serde(default = "crate::domains::utils::default_false")]
+    pub enabled: bool,
+
+    /// Maximum number of cached responses
+    #[serde(default = "default_cache_max_entries")]
+    pub max_entries: usize,
+
+    /// Largest response body that is cached, in bytes
+    #[serde(default = "default_cache_max_response_size")]
+    pub max_response_size: usize,
+
+    /// How long responses without `max-age`, `s-maxage` or `Expires` stay fresh;
+    /// zero stores them for revalidation only
+    #[serde(with = "crate::domains::utils::serde_duration", default = "default_cache_ttl")]
+    pub default_ttl: Duration,
+
+    /// How long a response is kept at most, fresh or not; until then a stale
+    /// response with a validator is revalidated rather than fetched again
+    #[serde(with = "crate::domains::utils::serde_duration", default = "default_cache_retention")]
+    pub retention: Duration,
+}
+
+/// Retries of task HTTP calls
+///
+/// Only idempotent methods are retried, after a network error or a response
+/// with one of `retry_on_status`. A `Retry-After` header given in seconds
+/// replaces the computed delay, up to `max_delay_ms`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpRetryConfig {
+    /// Attempts per request, including the first; 1 disables retries
+    #[serde(default = "default_retry_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Delay before the first retry in milliseconds
+    #[serde(default = "default_retry_initial_delay_ms")]
+    pub initial_delay_ms: u64,
+
+    /// Longest delay between attempts in milliseconds
+    #[serde(default = "default_retry_max_delay_ms")]
+    pub max_delay_ms: u64,
+
+    /// Factor the delay grows by after each retry
+    #[serde(default = "default_retry_backoff_multiplier")]
+    pub backoff_multiplier: f64,
+
+    /// Randomize delays so concurrent tasks do not retry in lockstep
+    #[serde(default = "crate::domains::utils::default_true")]
+    pub jitter: bool,
+
+    /// Response statuses that are retried
+    #[serde(default = "default_retry_on_status")]
+    pub retry_on_status: Vec<u16>,
+}
+
+/// Circuit breakers for the hosts task HTTP calls go to
+///
+/// A host's breaker opens once `failure_threshold` of at least `min_requests`
+/// requests within `window` failed with a network error or a 5xx response.
+/// While open, requests to the host fail immediately; after `open_duration`
+/// requests are let through again and `success_threshold` successes close it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpCircuitBreakerConfig {
+    /// Whether breakers are applied
+    #[serde(default = "crate::domains::utils::default_false")]
+    pub enabled: bool,
+
+    /// Failures within the window that open a breaker
+    #[serde(default = "default_breaker_failure_threshold")]
+    pub failure_threshold: u32,
+
+    /// Successes that close a half-open breaker
+    #[serde(default = "default_breaker_success_threshold")]
+    pub success_threshold: u32,
+
+    /// Requests within the window before a breaker may open
+    #[serde(default = "default_breaker_min_requests")]
+    pub min_requests: u32,
+
+    /// Window failures are counted over
+    #[serde(with = "crate::domains::utils::serde_duration", default = "default_breaker_window")]
+    pub window: Duration,
+
+    /// How long a breaker stays open before requests are tried again
+    #[serde(
+        with = "crate::domains::utils::serde_duration",
+        default = "default_breaker_open_duration"
+    )]
+    pub open_duration: Duration,
+}
+
+/// Caching, retry and circuit breaking applied to task HTTP calls
+///
+/// Built from [`HttpConfig`]; tasks can override any part of it under `http`
+/// in their metadata, e.g. `{"http": {"retry": {"max_attempts": 5}}}`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpClientPolicy {
+    pub cache: HttpResponseCacheConfig,
+    pub retry: HttpRetryConfig,
+    pub circuit_breaker: HttpCircuitBreakerConfig,
+}
+
+impl HttpClientPolicy {
+    /// Apply overrides declared by a task, keeping every setting they do not name
+    pub fn with_overrides(&self, overrides: &serde_json::Value) -> ConfigResult<Self> {
+        let invalid = |message: String| ConfigError::DomainError {
+            domain: "http".to_string(),
+            message,
+        };
+        if !overrides.is_object() {
+            return Err(invalid("HTTP policy overrides must be an object".to_string()));
+        }
+
+        let mut merged = serde_json::to_value(self).map_err(|e| invalid(e.to_string()))?;
+        merge_json(&mut merged, overrides);
+        let policy: Self =
+            serde_json::from_value(merged).map_err(|e| invalid(format!("Invalid HTTP policy override: {}", e)))?;
+        policy.validate()?;
+        Ok(policy)
+    }
+}
+
+/// Recursively merge `overrides` into `target`, replacing everything but objects
+fn merge_json(target: &mut serde_json::Value, overrides: &serde_json::Value) {
+    match (target, overrides) {
+        (serde_json::Value::Object(target), serde_json::Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                merge_json(target.entry(key.clone()).or_insert(serde_json::Value::Null), value);
+            }
+        }
+        (target, value) => *target = value.clone(),
+    }
+}
+
+impl HttpConfig {
+    /// Caching, retry and circuit breaking settings of this configuration
+    pub fn client_policy(&self) -> HttpClientPolicy {
+        HttpClientPolicy {
+            cache: self.cache.clone(),
+            retry: self.retry.clone(),
+            circuit_breaker: self.circuit_breaker.clone(),
+        }
+    }
+
+    /// Proxy and certificate settings of this configuration
+    pub fn transport_config(&self) -> HttpTransportConfig {
+        HttpTransportConfig {
+            proxy: self.proxy.clone(),
+            tls: self.tls.clone(),
+        }
+    }
+}
+
+impl Default for HttpResponseCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_entries: default_cache_max_entries(),
+            max_response_size: default_cache_max_response_size(),
+            default_ttl: default_cache_ttl(),
+            retention: default_cache_retention(),
+        }
+    }
+}
+
+impl Default for HttpRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_retry_max_attempts(),
+            initial_delay_ms: default_retry_initial_delay_ms(),
+            max_delay_ms: default_retry_max_delay_ms(),
+            backoff_multiplier: default_retry_backoff_multiplier(),
+            jitter: true,
+            retry_on_status: default_retry_on_status(),
+        }
+    }
+}
+
+impl Default for HttpCircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            failure_threshold: default_breaker_failure_threshold(),
+            success_threshold: default_breaker_success_threshold(),
+            min_requests: default_breaker_min_requests(),
+            window: default_breaker_window(),
+            open_duration: default_breaker_open_duration(),
+        }
+    }
+}
+
 impl Default for HttpConfig {
     fn default() -> Self {
         Self {
@@ -76,6 +442,11 @@ impl Default for HttpConfig {
             verify_ssl: true,
             connection_pool: ConnectionPoolConfig::default(),
             proxy: None,
+            tls: HttpTlsConfig::default(),
+            egress: EgressPolicy::default(),
+            cache: HttpResponseCacheConfig::default(),
+            retry: HttpRetryConfig::default(),
+            circuit_breaker: HttpCircuitBreakerConfig::default(),
         }
     }
 }
@@ -105,6 +476,24 @@ impl Validatable for HttpConfig {
         if let Some(ref proxy) = self.proxy {
             proxy.validate()?;
         }
+        self.tls.validate()?;
+
+        self.egress.validate()?;
+        self.client_policy().validate()?;
+
+        Ok(())
+    }
+
+    fn domain_name(&self) -> &'static str {
+        "http"
+    }
+}
+
+impl Validatable for HttpClientPolicy {
+    fn validate(&self) -> ConfigResult<()> {
+        self.cache.validate()?;
+        self.retry.validate()?;
+        self.circuit_breaker.validate()?;
 
         Ok(())
     }
@@ -114,6 +503,60 @@ impl Validatable for HttpConfig {
     }
 }
 
+impl Validatable for HttpResponseCacheConfig {
+    fn validate(&self) -> ConfigResult<()> {
+        validate_positive(self.max_entries, "max_entries", self.domain_name())?;
+        validate_positive(self.max_response_size, "max_response_size", self.domain_name())?;
+
+        Ok(())
+    }
+
+    fn domain_name(&self) -> &'static str {
+        "http.cache"
+    }
+}
+
+impl Validatable for HttpRetryConfig {
+    fn validate(&self) -> ConfigResult<()> {
+        validate_positive(self.max_attempts, "max_attempts", self.domain_name())?;
+
+        if self.max_delay_ms < self.initial_delay_ms {
+            return Err(ConfigError::DomainError {
+                domain: self.domain_name().to_string(),
+                message: "max_delay_ms must not be less than initial_delay_ms".to_string(),
+            });
+        }
+
+        if self.backoff_multiplier.is_nan() || self.backoff_multiplier < 1.0 {
+            return Err(ConfigError::DomainError {
+                domain: self.domain_name().to_string(),
+                message: "backoff_multiplier must be at least 1".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn domain_name(&self) -> &'static str {
+        "http.retry"
+    }
+}
+
+impl Validatable for HttpCircuitBreakerConfig {
+    fn validate(&self) -> ConfigResult<()> {
+        validate_positive(self.failure_threshold, "failure_threshold", self.domain_name())?;
+        validate_positive(self.success_threshold, "success_threshold", self.domain_name())?;
+        validate_positive(self.window.as_secs(), "window", self.domain_name())?;
+        validate_positive(self.open_duration.as_secs(), "open_duration", self.domain_name())?;
+
+        Ok(())
+    }
+
+    fn domain_name(&self) -> &'static str {
+        "http.circuit_breaker"
+    }
+}
+
 impl Validatable for ConnectionPoolConfig {
     fn validate(&self) -> ConfigResult<()> {
         validate_positive(self.max_idle_per_host, "max_idle_per_host", self.domain_name())?;
@@ -137,22 +580,114 @@ impl Validatable for ConnectionPoolConfig {
 impl Validatable for ProxyConfig {
     fn validate(&self) -> ConfigResult<()> {
         // Validate proxy URLs if present
-        if let Some(ref url) = self.http_proxy {
-            crate::validation::validate_url(url, "http_proxy", self.domain_name())?;
+        let proxies = [
+            ("http_proxy", &self.http_proxy),
+            ("https_proxy", &self.https_proxy),
+            ("all_proxy", &self.all_proxy),
+        ];
+        for (field, url) in proxies {
+            let Some(url) = url else {
+                continue;
+            };
+            crate::validation::validate_url(url, field, self.domain_name())?;
+            let scheme = url.split_once("://").map(|(scheme, _)| scheme.to_ascii_lowercase());
+            if !matches!(scheme.as_deref(), Some("http" | "https" | "socks5" | "socks5h")) {
+                return Err(ConfigError::DomainError {
+                    domain: self.domain_name().to_string(),
+                    message: format!("{} must use the http, https, socks5 or socks5h scheme", field),
+                });
+            }
         }
 
-        if let Some(ref url) = self.https_proxy {
-            crate::validation::validate_url(url, "https_proxy", self.domain_name())?;
+        Ok(())
+    }
+
+    fn domain_name(&self) -> &'static str {
+        "http.proxy"
+    }
+}
+
+impl Validatable for HttpTlsConfig {
+    fn validate(&self) -> ConfigResult<()> {
+        for identity in &self.client_identities {
+            identity.validate()?;
         }
 
         Ok(())
     }
 
     fn domain_name(&self) -> &'static str {
-        "http.proxy"
+        "http.tls"
     }
 }
 
+impl Validatable for ClientIdentityConfig {
+    fn validate(&self) -> ConfigResult<()> {
+        let invalid = |message: &str| ConfigError::DomainError {
+            domain: self.domain_name().to_string(),
+            message: message.to_string(),
+        };
+
+        if self.hosts.is_empty() || self.hosts.iter().any(|host| host.trim().is_empty()) {
+            return Err(invalid("client identities must name the hosts they are presented to"));
+        }
+        match (&self.cert_path, &self.secret) {
+            (Some(_), Some(_)) => return Err(invalid("cert_path and secret cannot both be set")),
+            (None, None) => return Err(invalid("either cert_path or secret must be set")),
+            (None, Some(secret)) => {
+                validate_required_string(secret, "secret", self.domain_name())?;
+                if self.key_path.is_some() {
+                    return Err(invalid("key_path requires cert_path"));
+                }
+            }
+            (Some(_), None) => {}
+        }
+
+        Ok(())
+    }
+
+    fn domain_name(&self) -> &'static str {
+        "http.tls.client_identities"
+    }
+}
+
+impl Validatable for EgressPolicy {
+    fn validate(&self) -> ConfigResult<()> {
+        for cidr in self.allow_cidrs.iter().chain(&self.deny_cidrs) {
+            if !is_valid_cidr(cidr) {
+                return Err(ConfigError::DomainError {
+                    domain: self.domain_name().to_string(),
+                    message: format!("'{}' is not a valid CIDR range", cidr),
+                });
+            }
+        }
+
+        for host in self.allow_hosts.iter().chain(&self.deny_hosts) {
+            validate_required_string(host, "allow_hosts/deny_hosts", self.domain_name())?;
+        }
+
+        Ok(())
+    }
+
+    fn domain_name(&self) -> &'static str {
+        "http.egress"
+    }
+}
+
+/// Whether `cidr` is an IP address, optionally followed by `/<prefix length>`
+fn is_valid_cidr(cidr: &str) -> bool {
+    let (address, prefix) = match cidr.split_once('/') {
+        Some((address, prefix)) => (address, Some(prefix)),
+        None => (cidr, None),
+    };
+    let max_prefix = match address.trim().parse::<std::net::IpAddr>() {
+        Ok(std::net::IpAddr::V4(_)) => 32,
+        Ok(std::net::IpAddr::V6(_)) => 128,
+        Err(_) => return false,
+    };
+    prefix.is_none_or(|prefix| prefix.trim().parse::<u8>().is_ok_and(|prefix| prefix <= max_prefix))
+}
+
 // Default value functions
 fn default_timeout() -> Duration {
     Duration::from_secs(30)
@@ -166,6 +701,66 @@ fn default_user_agent() -> String {
     "Ratchet/1.0".to_string()
 }
 
+fn default_allow_schemes() -> Vec<String> {
+    vec!["http".to_string(), "https".to_string()]
+}
+
+fn default_cache_max_entries() -> usize {
+    1000
+}
+
+fn default_cache_max_response_size() -> usize {
+    1024 * 1024 // 1MB
+}
+
+fn default_cache_ttl() -> Duration {
+    Duration::from_secs(0)
+}
+
+fn default_cache_retention() -> Duration {
+    Duration::from_secs(3600)
+}
+
+fn default_retry_max_attempts() -> u32 {
+    1
+}
+
+fn default_retry_initial_delay_ms() -> u64 {
+    200
+}
+
+fn default_retry_max_delay_ms() -> u64 {
+    10_000
+}
+
+fn default_retry_backoff_multiplier() -> f64 {
+    2.0
+}
+
+fn default_retry_on_status() -> Vec<u16> {
+    vec![429, 502, 503, 504]
+}
+
+fn default_breaker_failure_threshold() -> u32 {
+    5
+}
+
+fn default_breaker_success_threshold() -> u32 {
+    2
+}
+
+fn default_breaker_min_requests() -> u32 {
+    5
+}
+
+fn default_breaker_window() -> Duration {
+    Duration::from_secs(60)
+}
+
+fn default_breaker_open_duration() -> Duration {
+    Duration::from_secs(30)
+}
+
 fn default_max_idle_per_host() -> usize {
     10
 }
@@ -211,6 +806,7 @@ mod tests {
         let mut proxy = ProxyConfig {
             http_proxy: Some("http://proxy.example.com:8080".to_string()),
             https_proxy: None,
+            all_proxy: Some("socks5h://proxy.example.com:1080".to_string()),
             no_proxy: None,
         };
         assert!(proxy.validate().is_ok());
@@ -218,5 +814,101 @@ mod tests {
         // Test invalid URL
         proxy.http_proxy = Some("not-a-url".to_string());
         assert!(proxy.validate().is_err());
+
+        // Only HTTP and SOCKS5 proxies are supported
+        proxy.http_proxy = None;
+        proxy.all_proxy = Some("socks4://proxy.example.com:1080".to_string());
+        assert!(proxy.validate().is_err());
+    }
+
+    #[test]
+    fn test_client_identity_validation() {
+        let mut tls = HttpTlsConfig {
+            ca_bundles: vec![PathBuf::from("/etc/ratchet/internal-ca.pem")],
+            client_identities: vec![ClientIdentityConfig {
+                hosts: vec!["*.internal.example.com".to_string()],
+                cert_path: Some(PathBuf::from("/etc/ratchet/client.pem")),
+                key_path: Some(PathBuf::from("/etc/ratchet/client.key")),
+                secret: None,
+            }],
+        };
+        assert!(tls.validate().is_ok());
+
+        // Files and secrets are alternatives
+        tls.client_identities[0].secret = Some("internal-client-cert".to_string());
+        assert!(tls.validate().is_err());
+        tls.client_identities[0].cert_path = None;
+        tls.client_identities[0].key_path = None;
+        assert!(tls.validate().is_ok());
+
+        let config = HttpConfig {
+            tls: tls.clone(),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.transport_config().secret_names(),
+            vec!["internal-client-cert".to_string()]
+        );
+
+        tls.client_identities[0].hosts.clear();
+        assert!(tls.validate().is_err());
+    }
+
+    #[test]
+    fn test_egress_policy_defaults_block_private_ranges() {
+        let policy: EgressPolicy = serde_json::from_str(r#"{"deny_hosts": ["metadata.internal"]}"#).unwrap();
+        assert!(policy.block_private_ranges);
+        assert_eq!(policy.allow_schemes, vec!["http".to_string(), "https".to_string()]);
+        assert_eq!(policy.deny_hosts, vec!["metadata.internal".to_string()]);
+        assert_eq!(HttpConfig::default().egress, EgressPolicy::default());
+    }
+
+    #[test]
+    fn test_egress_policy_validation() {
+        let mut policy = EgressPolicy {
+            allow_cidrs: vec![
+                "10.0.0.0/8".to_string(),
+                "fd00::/8".to_string(),
+                "192.168.1.10".to_string(),
+            ],
+            ..EgressPolicy::default()
+        };
+        assert!(policy.validate().is_ok());
+
+        policy.deny_cidrs = vec!["10.0.0.0/33".to_string()];
+        assert!(policy.validate().is_err());
+
+        policy.deny_cidrs = vec!["not-a-range".to_string()];
+        assert!(policy.validate().is_err());
+    }
+
+    #[test]
+    fn test_client_policy_overrides_merge_with_defaults() {
+        let mut config = HttpConfig::default();
+        config.retry.max_attempts = 3;
+        config.circuit_breaker.enabled = true;
+        let policy = config.client_policy();
+        assert!(!policy.cache.enabled);
+
+        let overridden = policy
+            .with_overrides(&serde_json::json!({
+                "cache": { "enabled": true, "default_ttl": 60 },
+                "retry": { "retry_on_status": [503] }
+            }))
+            .unwrap();
+        assert!(overridden.cache.enabled);
+        assert_eq!(overridden.cache.default_ttl, Duration::from_secs(60));
+        assert_eq!(overridden.cache.max_entries, policy.cache.max_entries);
+        assert_eq!(overridden.retry.max_attempts, 3);
+        assert_eq!(overridden.retry.retry_on_status, vec![503]);
+        assert!(overridden.circuit_breaker.enabled);
+
+        assert!(policy
+            .with_overrides(&serde_json::json!({ "retry": { "max_attempts": 0 } }))
+            .is_err());
+        assert!(policy
+            .with_overrides(&serde_json::json!({ "retry": { "max_attempts": "many" } }))
+            .is_err());
+        assert!(policy.with_overrides(&serde_json::json!(["cache"])).is_err());
     }
 }
@@ -34,7 +34,7 @@ pub struct OutputConfig {
     #[serde(default)]
     pub formatting: OutputFormattingConfig,
 
-    /// Security configuration for webhook URLs
+    /// Security configuration for webhook URLs and output databases
     #[serde(default)]
     pub security: OutputSecurityConfig,
 }
@@ -110,6 +110,12 @@ pub enum OutputDestinationConfigTemplate {
         key_template: String,
         /// AWS region
         region: String,
+        /// Endpoint of an S3-compatible store such as MinIO (default: AWS)
+        #[serde(default)]
+        endpoint: Option<String>,
+        /// Address the bucket in the path rather than the host name
+        #[serde(default)]
+        path_style: bool,
         /// AWS access key ID (optional, can use environment)
         access_key_id: Option<String>,
         /// AWS secret access key (optional, can use environment)
@@ -187,7 +193,7 @@ pub struct OutputFormattingConfig {
     pub pretty_json: bool,
 }
 
-/// Security configuration for webhook URLs
+/// Security configuration for webhook URLs and output databases
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct OutputSecurityConfig {
@@ -204,6 +210,22 @@ pub struct OutputSecurityConfig {
     /// Additional allowed domains for webhook URLs (bypasses security checks)
     #[serde(default)]
     pub allowed_webhook_domains: Vec<String>,
+
+    /// Databases that database output destinations may write to, by the name destinations use;
+    /// destinations cannot give a connection string of their own
+    #[serde(default)]
+    pub database_connections: HashMap<String, OutputDatabaseConnection>,
+}
+
+/// A database that database output destinations may write to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputDatabaseConnection {
+    /// Connection string of the database
+    pub url: String,
+
+    /// Connection pool configuration
+    #[serde(default)]
+    pub pool_config: DatabasePoolConfig,
 }
 
 impl Default for OutputConfig {
@@ -247,6 +269,7 @@ impl Default for OutputSecurityConfig {
             allow_localhost_webhooks: false,
             allow_private_network_webhooks: false,
             allowed_webhook_domains: Vec::new(),
+            database_connections: HashMap::new(),
         }
     }
 }
@@ -573,6 +596,12 @@ impl Validatable for OutputSecurityConfig {
             validate_required_string(domain, "allowed_webhook_domains", self.domain_name())?;
         }
 
+        for (name, connection) in &self.database_connections {
+            validate_required_string(name, "database_connections", self.domain_name())?;
+            validate_required_string(&connection.url, "database_connections.url", self.domain_name())?;
+            connection.pool_config.validate()?;
+        }
+
         Ok(())
     }
 
@@ -786,4 +815,26 @@ mod tests {
         // Should always fail for cloud metadata endpoints regardless of config
         assert!(metadata_webhook.validate_with_security("test", &security_config).is_err());
     }
+
+    #[test]
+    fn test_database_connections_validation() {
+        let mut security_config = OutputSecurityConfig::default();
+        security_config.database_connections.insert(
+            "reports".to_string(),
+            OutputDatabaseConnection {
+                url: "postgres://reports.internal/reports".to_string(),
+                pool_config: DatabasePoolConfig::default(),
+            },
+        );
+        assert!(security_config.validate().is_ok());
+
+        security_config.database_connections.insert(
+            "empty".to_string(),
+            OutputDatabaseConnection {
+                url: String::new(),
+                pool_config: DatabasePoolConfig::default(),
+            },
+        );
+        assert!(security_config.validate().is_err());
+    }
 }
@@ -1,7 +1,7 @@
 //! Registry configuration for task sources
 
 use crate::error::ConfigResult;
-use crate::validation::{validate_positive, validate_required_string, validate_url, Validatable};
+use crate::validation::{validate_enum_choice, validate_positive, validate_required_string, validate_url, Validatable};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::Duration;
@@ -25,6 +25,25 @@ pub struct RegistryConfig {
     /// Authentication configuration for registry sources
     #[serde(default)]
     pub auth: HashMap<String, RegistryAuthConfig>,
+
+    /// Keys trusted to sign the tasks of each repository
+    #[serde(default)]
+    pub trust: Vec<RegistryTrustConfig>,
+}
+
+/// Signing keys trusted for one repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryTrustConfig {
+    /// Repository URL or filesystem path, as the registry source names it
+    pub repository: String,
+
+    /// What to do with tasks that are unsigned or fail verification: off, warn, quarantine or require
+    #[serde(default = "default_signature_policy")]
+    pub policy: String,
+
+    /// Base64-encoded ed25519 public keys
+    #[serde(default)]
+    pub public_keys: Vec<String>,
 }
 
 /// Registry source configuration
@@ -280,6 +299,7 @@ impl Default for RegistryConfig {
             default_polling_interval: default_polling_interval(),
             cache: RegistryCacheConfig::default(),
             auth: HashMap::new(),
+            trust: Vec::new(),
         }
     }
 }
@@ -366,6 +386,16 @@ impl Validatable for RegistryConfig {
             auth.validate_with_name(name)?;
         }
 
+        for trust in &self.trust {
+            validate_required_string(&trust.repository, "trust.repository", self.domain_name())?;
+            validate_enum_choice(
+                &trust.policy,
+                &["off", "warn", "quarantine", "require"],
+                "trust.policy",
+                self.domain_name(),
+            )?;
+        }
+
         Ok(())
     }
 
@@ -585,6 +615,10 @@ fn default_s3_timeout() -> Duration {
     Duration::from_secs(60)
 }
 
+fn default_signature_policy() -> String {
+    "off".to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -631,6 +665,22 @@ mod tests {
         assert!(invalid_auth.validate_with_name("test").is_err());
     }
 
+    #[test]
+    fn test_registry_trust_validation() {
+        let mut config = RegistryConfig {
+            trust: vec![RegistryTrustConfig {
+                repository: "https://registry.example.com".to_string(),
+                policy: "require".to_string(),
+                public_keys: vec!["a2V5".to_string()],
+            }],
+            ..RegistryConfig::default()
+        };
+        assert!(config.validate().is_ok());
+
+        config.trust[0].policy = "strict".to_string();
+        assert!(config.validate().is_err());
+    }
+
     #[test]
     fn test_source_type_uri_validation() {
         // Test S3 URI
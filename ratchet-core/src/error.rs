@@ -107,6 +107,9 @@ pub enum ExecutionError {
 
     #[error("Worker error: {0}")]
     WorkerError(String),
+
+    #[error("Resource limit exceeded: {0}")]
+    ResourceExhausted(String),
 }
 
 /// Storage-related errors
@@ -303,6 +306,9 @@ impl StandardizedError for RatchetError {
             RatchetError::Execution(ExecutionError::WorkerError(_)) => (
                 "EXECUTION_WORKER_ERROR", ErrorCategory::Server, true, Some(Duration::from_secs(1)), 500
             ),
+            RatchetError::Execution(ExecutionError::ResourceExhausted(_)) => (
+                "RESOURCE_EXHAUSTED", ErrorCategory::Client, false, None, 422
+            ),
             RatchetError::ExecutionError(_) => (
                 "EXECUTION_ERROR", ErrorCategory::Server, false, None, 500
             ),
@@ -404,6 +410,9 @@ impl RatchetError {
             RatchetError::Validation(_) => "VALIDATION_ERROR",
             RatchetError::Config(_) => "CONFIG_ERROR",
             RatchetError::Timeout(_) => "TIMEOUT",
+            RatchetError::Execution(ExecutionError::Cancelled) => "EXECUTION_CANCELLED",
+            RatchetError::Execution(ExecutionError::Timeout(_)) => "EXECUTION_TIMEOUT",
+            RatchetError::Execution(ExecutionError::ResourceExhausted(_)) => "RESOURCE_EXHAUSTED",
             RatchetError::Network(_) => "NETWORK_ERROR",
             RatchetError::Service(ServiceError::Unavailable(_)) => "SERVICE_UNAVAILABLE",
             _ => "INTERNAL_ERROR",
@@ -176,6 +176,21 @@ impl ErrorSanitizer {
         self.apply_final_sanitization(sanitized)
     }
 
+    /// Redact sensitive values and paths from free-form text such as task output
+    ///
+    /// Unlike [`Self::sanitize_message`] the text is neither categorized nor
+    /// shortened; only the matched values are replaced.
+    pub fn redact(&self, text: &str) -> String {
+        let mut redacted = text.to_string();
+        for pattern in &self.sensitive_patterns {
+            redacted = pattern.replace_all(&redacted, "[REDACTED]").to_string();
+        }
+        for pattern in &self.path_patterns {
+            redacted = pattern.replace_all(&redacted, "[PATH]").to_string();
+        }
+        redacted
+    }
+
     /// Check for custom error mappings
     fn check_custom_mappings(&self, message: &str) -> Option<String> {
         for (pattern, replacement) in &self.config.custom_mappings {
@@ -593,6 +608,17 @@ mod tests {
         assert!(sanitized.message.ends_with("..."));
     }
 
+    #[test]
+    fn test_redact_keeps_surrounding_text() {
+        let sanitizer = ErrorSanitizer::default();
+
+        let redacted = sanitizer.redact("Fetched 3 items using password=hunter2secret");
+        assert!(redacted.starts_with("Fetched 3 items using"));
+        assert!(!redacted.contains("hunter2secret"));
+
+        assert_eq!(sanitizer.redact("Processing batch 2 of 5"), "Processing batch 2 of 5");
+    }
+
     #[test]
     fn test_convenience_functions() {
         let validation_error = ErrorSanitizer::validation_error("email");
@@ -8,7 +8,7 @@ use serde_json::Value as JsonValue;
 
 use crate::{ExecutionError, ProcessExecutorConfig, ProcessTaskExecutor, TaskExecutionResult};
 use ratchet_interfaces::execution::{
-    ExecutionContext, ExecutionResult, ExecutionStatus, ExecutorMetrics, TaskExecutor,
+    ExecutionCanceller, ExecutionContext, ExecutionResult, ExecutionStatus, ExecutorMetrics, TaskExecutor,
 };
 
 /// Bridge that adapts ProcessTaskExecutor to the TaskExecutor interface
@@ -39,6 +39,10 @@ impl ExecutionBridge {
             task_timeout_seconds: timeout_seconds,
             restart_on_crash: true,
             max_restart_attempts: 3,
+            limits: Default::default(),
+            calls: None,
+            egress: Default::default(),
+            http_client: Default::default(),
         };
         Self::new(config)
     }
@@ -59,6 +63,12 @@ impl ExecutionBridge {
     }
 }
 
+impl ExecutionCanceller for ExecutionBridge {
+    fn cancel_execution(&self, execution_id: &str) -> bool {
+        self.inner.cancel_execution(execution_id)
+    }
+}
+
 #[async_trait]
 impl TaskExecutor for ExecutionBridge {
     type Error = ExecutionError;
@@ -115,8 +125,21 @@ impl TaskExecutor for ExecutionBridge {
 
 /// Convert internal TaskExecutionResult to interface ExecutionResult (for IPC results)
 fn convert_execution_result(result: TaskExecutionResult) -> ExecutionResult {
+    let error_code = result
+        .error_details
+        .as_ref()
+        .and_then(|details| details.get("error_code"))
+        .and_then(JsonValue::as_str);
+
+    // A task stopped by its wall-clock limit failed; only explicit requests cancel it
     let status = if result.success {
         ExecutionStatus::Success
+    } else if error_code == Some("EXECUTION_CANCELLED") {
+        ExecutionStatus::Cancelled {
+            reason: result
+                .error_message
+                .unwrap_or_else(|| "Task execution was cancelled".to_string()),
+        }
     } else {
         ExecutionStatus::Failed {
             error_message: result
@@ -142,9 +165,16 @@ impl ExecutionConfigAdapter {
     pub fn from_execution_config(config: &ratchet_config::domains::execution::ExecutionConfig) -> ExecutionBridge {
         let executor_config = ProcessExecutorConfig {
             worker_count: config.max_concurrent_tasks,
-            task_timeout_seconds: config.max_execution_duration.as_secs(),
+            task_timeout_seconds: config
+                .limits
+                .timeout_seconds
+                .unwrap_or_else(|| config.max_execution_duration.as_secs()),
             restart_on_crash: true,
             max_restart_attempts: 3,
+            limits: config.limits.clone(),
+            calls: None,
+            egress: Default::default(),
+            http_client: Default::default(),
         };
         ExecutionBridge::new(executor_config)
     }
@@ -161,6 +191,10 @@ impl ExecutionConfigAdapter {
             task_timeout_seconds: 30,
             restart_on_crash: false,
             max_restart_attempts: 0,
+            limits: Default::default(),
+            calls: None,
+            egress: Default::default(),
+            http_client: Default::default(),
         };
         ExecutionBridge::new(config)
     }
@@ -179,6 +213,10 @@ mod tests {
             task_timeout_seconds: 60,
             restart_on_crash: true,
             max_restart_attempts: 3,
+            limits: Default::default(),
+            calls: None,
+            egress: Default::default(),
+            http_client: Default::default(),
         };
 
         let bridge = ExecutionBridge::new(config);
@@ -224,6 +262,7 @@ mod tests {
             started_at: start,
             completed_at: end,
             duration_ms: 1500,
+            logs: Vec::new(),
         };
 
         let converted = convert_execution_result(success_result);
@@ -241,6 +280,7 @@ mod tests {
             started_at: start,
             completed_at: end,
             duration_ms: 500,
+            logs: Vec::new(),
         };
 
         let converted = convert_execution_result(failed_result);
@@ -251,6 +291,39 @@ mod tests {
             }
             _ => panic!("Expected failed status"),
         }
+
+        // Test timed out result conversion
+        let timed_out_result = TaskExecutionResult {
+            success: false,
+            output: None,
+            error_message: Some("Task exceeded the wall-clock limit of 30s".to_string()),
+            error_details: Some(json!({"error_type": "timeout", "error_code": "EXECUTION_TIMEOUT"})),
+            started_at: start,
+            completed_at: end,
+            duration_ms: 30000,
+            logs: Vec::new(),
+        };
+
+        let converted = convert_execution_result(timed_out_result);
+        match converted.status {
+            ExecutionStatus::Failed { error_message } => assert!(error_message.contains("wall-clock limit")),
+            other => panic!("expected a failed execution, got {:?}", other),
+        }
+
+        // Test cancelled result conversion
+        let cancelled_result = TaskExecutionResult {
+            success: false,
+            output: None,
+            error_message: Some("Execution cancelled".to_string()),
+            error_details: Some(json!({"error_type": "cancelled", "error_code": "EXECUTION_CANCELLED"})),
+            started_at: start,
+            completed_at: end,
+            duration_ms: 100,
+            logs: Vec::new(),
+        };
+
+        let converted = convert_execution_result(cancelled_result);
+        assert!(matches!(converted.status, ExecutionStatus::Cancelled { .. }));
     }
 
     #[tokio::test]
@@ -0,0 +1,411 @@
+//! In-process execution of tasks called with `ratchet.call`
+//!
+//! [`TaskServiceCaller`] resolves called tasks through a [`TaskService`] and runs
+//! them on the caller's worker thread. Running them in place rather than handing
+//! them back to the executor keeps a worker from waiting on itself, and lets the
+//! called task share the caller's cancellation and wall-clock limit.
+
+use async_trait::async_trait;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use ratchet_api_types::{ApiId, ExecutionLogLevel, ExecutionStatus, UnifiedExecution, UnifiedExecutionLog, UnifiedTask};
+use ratchet_core::validation::validate_json;
+use ratchet_interfaces::{declared_secrets, ExecutionLogSink, RepositoryFactory, SecretStore, TaskService};
+use ratchet_js::{
+    ConsoleCapture, EgressPolicy, ExecutionContext as JsExecutionContext, HttpClientState, JsTask, JsTaskRunner,
+    ProgressReporter, ResourceLimits, TaskCallError, TaskCallRequest, TaskCaller, TaskCalls, TaskSecrets,
+};
+
+use crate::worker::RunningExecutions;
+
+/// Runs called tasks from a [`TaskService`] on the calling worker
+pub struct TaskServiceCaller {
+    tasks: Arc<dyn TaskService>,
+    repositories: Option<Arc<dyn RepositoryFactory>>,
+    secret_store: Option<Arc<dyn SecretStore>>,
+    log_sink: Option<Arc<dyn ExecutionLogSink>>,
+    limits: ResourceLimits,
+    egress: EgressPolicy,
+    http_client: HttpClientState,
+}
+
+impl TaskServiceCaller {
+    /// Resolve called tasks by name or UUID through `tasks`
+    pub fn new(tasks: Arc<dyn TaskService>) -> Self {
+        Self {
+            tasks,
+            repositories: None,
+            secret_store: None,
+            log_sink: None,
+            limits: ResourceLimits::default(),
+            egress: EgressPolicy::default(),
+            http_client: HttpClientState::default(),
+        }
+    }
+
+    /// Record every call as an execution linked to the execution that made it
+    pub fn with_repositories(mut self, repositories: Arc<dyn RepositoryFactory>) -> Self {
+        self.repositories = Some(repositories);
+        self
+    }
+
+    /// Hand the secrets declared by called tasks to them
+    pub fn with_secret_store(mut self, secret_store: Arc<dyn SecretStore>) -> Self {
+        self.secret_store = Some(secret_store);
+        self
+    }
+
+    /// Store the console output of recorded calls through `sink`, like that of jobs
+    pub fn with_log_sink(mut self, sink: Arc<dyn ExecutionLogSink>) -> Self {
+        self.log_sink = Some(sink);
+        self
+    }
+
+    /// Enforce resource limits on called tasks
+    pub fn with_limits(mut self, limits: ResourceLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Restrict where called tasks may connect, on top of the policy each declares
+    pub fn with_egress_policy(mut self, egress: EgressPolicy) -> Self {
+        self.egress = egress;
+        self
+    }
+
+    /// Share a response cache and circuit breakers with the `fetch` calls of called tasks
+    pub fn with_http_client(mut self, http_client: HttpClientState) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Run the task named in `request` on behalf of `calls`
+    ///
+    /// Returns the ID of the execution recorded for the run alongside its result;
+    /// there is none when the request was rejected before the task started or
+    /// recording failed.
+    pub async fn execute(
+        &self,
+        calls: &TaskCalls,
+        request: TaskCallRequest,
+    ) -> (Option<ApiId>, Result<JsonValue, TaskCallError>) {
+        let task = match self.resolve_task(&request).await {
+            Ok(task) => task,
+            Err(e) => return (None, Err(e)),
+        };
+
+        if let Some(schema) = &task.input_schema {
+            if let Err(e) = validate_json(&request.input, schema) {
+                let error = TaskCallError::InvalidInput {
+                    task: task.name.clone(),
+                    message: e.to_string(),
+                };
+                return (None, Err(error));
+            }
+        }
+
+        let uuid = Uuid::new_v4();
+        debug!(
+            "Task {} calls {} as execution {}",
+            calls.stack().last().map(String::as_str).unwrap_or("<unknown>"),
+            task.name,
+            uuid
+        );
+
+        let started = std::time::Instant::now();
+        let recorded = self
+            .record_started(&task, uuid, &request.input, calls.execution_id())
+            .await;
+        // The called task gets its own console and progress, which nobody watches
+        let console = ConsoleCapture::new();
+        let (progress, _updates) = ProgressReporter::channel();
+        let result = self
+            .run(calls, &task, uuid, request.input, console.clone(), progress)
+            .await;
+        if let Some(id) = &recorded {
+            self.record_finished(id.clone(), uuid, &result, &console, started).await;
+        }
+        (recorded, result)
+    }
+
+    /// Run `task` as the execution `uuid`, which the caller has recorded
+    ///
+    /// The run is tracked in `running` under the execution UUID while it lasts, so
+    /// cancelling the execution stops it like one running on a worker. Progress the
+    /// task reports is delivered to `progress` and its console output captured in
+    /// `console`.
+    pub async fn run_execution(
+        self: &Arc<Self>,
+        task: &UnifiedTask,
+        uuid: Uuid,
+        input: JsonValue,
+        running: &RunningExecutions,
+        progress: ProgressReporter,
+        console: ConsoleCapture,
+    ) -> Result<JsonValue, TaskCallError> {
+        let execution_id = uuid.to_string();
+        let interrupt = running.register(&execution_id);
+        let calls = TaskCalls::new(self.clone()).for_execution(task.name.clone(), Some(execution_id.clone()));
+        let result = interrupt
+            .scope(self.run(&calls, task, uuid, input, console, progress))
+            .await;
+        running.remove(&execution_id);
+        result
+    }
+
+    async fn resolve_task(&self, request: &TaskCallRequest) -> Result<UnifiedTask, TaskCallError> {
+        let found = match Uuid::parse_str(&request.task) {
+            Ok(uuid) => self.tasks.find_by_id(uuid).await,
+            Err(_) => self.tasks.find_by_name(&request.task).await,
+        };
+        let task = found
+            .map_err(|e| TaskCallError::Failed {
+                task: request.task.clone(),
+                message: e.to_string(),
+            })?
+            .filter(|task| task.enabled)
+            .ok_or_else(|| TaskCallError::NotFound {
+                task: request.task.clone(),
+            })?;
+
+        if let Some(version) = &request.version {
+            if version != &task.version {
+                return Err(TaskCallError::VersionNotFound {
+                    task: request.task.clone(),
+                    version: version.clone(),
+                });
+            }
+        }
+        Ok(task)
+    }
+
+    async fn resolve_secrets(&self, task: &UnifiedTask) -> Result<TaskSecrets, TaskCallError> {
+        let names = task.metadata.as_ref().map(declared_secrets).unwrap_or_default();
+        if names.is_empty() {
+            return Ok(TaskSecrets::new(HashMap::new()));
+        }
+
+        let store = self.secret_store.as_ref().ok_or_else(|| TaskCallError::Failed {
+            task: task.name.clone(),
+            message: "the task declares secrets but no secret store is configured".to_string(),
+        })?;
+        let task_uuid = task.uuid.to_string();
+        let repository_id = task.repository_info.repository_id.to_string();
+        let values = store
+            .resolve_secrets(&names, Some(&task_uuid), Some(&repository_id))
+            .await
+            .map_err(|e| TaskCallError::Failed {
+                task: task.name.clone(),
+                message: e.to_string(),
+            })?;
+        Ok(TaskSecrets::new(values))
+    }
+
+    /// Store a pending execution for the call, returning its storage ID
+    ///
+    /// Recording is best effort: tasks that only exist in a registry have no
+    /// storage ID, and a failure to record must not fail the call.
+    async fn record_started(
+        &self,
+        task: &UnifiedTask,
+        uuid: Uuid,
+        input: &JsonValue,
+        parent: Option<&str>,
+    ) -> Option<ApiId> {
+        let executions = self.repositories.as_ref()?.execution_repository();
+        task.id.as_i32()?;
+
+        let execution = UnifiedExecution {
+            id: ApiId::from_uuid(uuid),
+            uuid,
+            task_id: task.id.clone(),
+            input: input.clone(),
+            output: None,
+            status: ExecutionStatus::Pending,
+            error_message: None,
+            error_details: None,
+            queued_at: chrono::Utc::now(),
+            started_at: None,
+            completed_at: None,
+            duration_ms: None,
+            http_requests: None,
+            recording_path: None,
+            parent_execution_uuid: parent.and_then(|parent| Uuid::parse_str(parent).ok()),
+            cache_hit: false,
+            can_retry: false,
+            can_cancel: false,
+            progress: None,
+            progress_message: None,
+            partial_output: None,
+        };
+
+        let created = match executions.create(execution).await {
+            Ok(created) => created,
+            Err(e) => {
+                warn!("Failed to record call to task {}: {}", task.name, e);
+                return None;
+            }
+        };
+        if let Err(e) = executions.mark_started(created.id.clone()).await {
+            warn!("Failed to mark execution {} as started: {}", created.id, e);
+        }
+        Some(created.id)
+    }
+
+    async fn record_finished(
+        &self,
+        id: ApiId,
+        uuid: Uuid,
+        result: &Result<JsonValue, TaskCallError>,
+        console: &ConsoleCapture,
+        started: std::time::Instant,
+    ) {
+        let Some(repositories) = &self.repositories else {
+            return;
+        };
+        let executions = repositories.execution_repository();
+
+        let logs = execution_logs(console);
+        match &self.log_sink {
+            Some(sink) => sink.record_logs(&uuid.to_string(), logs).await,
+            None if !logs.is_empty() => {
+                if let Err(e) = executions.append_logs(id.clone(), logs).await {
+                    warn!("Failed to store logs for execution {}: {}", id, e);
+                }
+            }
+            None => {}
+        }
+
+        let recorded = match result {
+            Ok(output) => {
+                let duration_ms = started.elapsed().as_millis() as i32;
+                executions.mark_completed(id.clone(), output.clone(), Some(duration_ms)).await
+            }
+            Err(error) => {
+                let details = serde_json::json!({ "error_code": error.code() });
+                executions.mark_failed(id.clone(), error.to_string(), Some(details)).await
+            }
+        };
+        if let Err(e) = recorded {
+            warn!("Failed to record the result of execution {}: {}", id, e);
+        }
+    }
+
+    async fn run(
+        &self,
+        calls: &TaskCalls,
+        task: &UnifiedTask,
+        uuid: Uuid,
+        input: JsonValue,
+        console: ConsoleCapture,
+        progress: ProgressReporter,
+    ) -> Result<JsonValue, TaskCallError> {
+        let secrets = self.resolve_secrets(task).await?;
+        let task_egress = declared_egress_policy(task)?;
+        let limits = match declared_limits(task)? {
+            Some(task_limits) => self.limits.with_overrides(&task_limits),
+            None => self.limits.clone(),
+        };
+        limits.check_in_process().map_err(|e| TaskCallError::Failed {
+            task: task.name.clone(),
+            message: e.to_string(),
+        })?;
+
+        // The task gets its own secrets; it shares the caller's interrupt, so
+        // cancelling the caller also stops a called task
+        let mut runner = JsTaskRunner::new()
+            .with_limits(limits)
+            .with_egress_policy(self.egress.clone())
+            .with_http_client(self.http_client.clone())
+            .with_console(console)
+            .with_progress(progress)
+            .with_secrets(secrets.clone())
+            .with_calls(calls.child(task.name.clone(), Some(uuid.to_string())));
+        if let Some(policy) = task_egress {
+            runner = runner.with_task_egress_policy(policy);
+        }
+        if let Some(policy) = task.metadata.as_ref().and_then(|metadata| metadata.get("http")) {
+            runner = runner.with_task_http_policy(policy.clone());
+        }
+
+        let js_task = JsTask {
+            name: task.name.clone(),
+            content: task.source_code.clone(),
+            input_schema: None,
+            output_schema: None,
+        };
+        let context = JsExecutionContext {
+            execution_id: uuid.to_string(),
+            task_id: task.uuid.to_string(),
+            task_version: task.version.clone(),
+            job_id: None,
+        };
+
+        // Errors reach the calling task and the execution record, so they must not carry secrets
+        let output = runner
+            .execute_task(&js_task, input, Some(context))
+            .await
+            .map_err(|e| TaskCallError::Failed {
+                task: task.name.clone(),
+                message: secrets.redact(&e.to_string()),
+            })?;
+
+        if let Some(schema) = &task.output_schema {
+            validate_json(&output, schema).map_err(|e| TaskCallError::InvalidOutput {
+                task: task.name.clone(),
+                message: e.to_string(),
+            })?;
+        }
+        Ok(output)
+    }
+}
+
+/// Take the console output captured so far as execution log entries
+pub fn execution_logs(console: &ConsoleCapture) -> Vec<UnifiedExecutionLog> {
+    console
+        .take()
+        .into_iter()
+        .map(|entry| UnifiedExecutionLog {
+            timestamp: entry.timestamp,
+            level: ExecutionLogLevel::from_name(entry.level.as_str()).unwrap_or(ExecutionLogLevel::Info),
+            message: entry.message,
+        })
+        .collect()
+}
+
+/// The egress policy a task declares under `egress` in its metadata
+fn declared_egress_policy(task: &UnifiedTask) -> Result<Option<EgressPolicy>, TaskCallError> {
+    let Some(policy) = task.metadata.as_ref().and_then(|metadata| metadata.get("egress")) else {
+        return Ok(None);
+    };
+    serde_json::from_value(policy.clone())
+        .map(Some)
+        .map_err(|e| TaskCallError::Failed {
+            task: task.name.clone(),
+            message: format!("invalid egress policy: {}", e),
+        })
+}
+
+/// The resource limits a task declares under `limits` in its metadata
+fn declared_limits(task: &UnifiedTask) -> Result<Option<ResourceLimits>, TaskCallError> {
+    let Some(limits) = task.metadata.as_ref().and_then(|metadata| metadata.get("limits")) else {
+        return Ok(None);
+    };
+    serde_json::from_value(limits.clone())
+        .map(Some)
+        .map_err(|e| TaskCallError::Failed {
+            task: task.name.clone(),
+            message: format!("invalid resource limits: {}", e),
+        })
+}
+
+#[async_trait(?Send)]
+impl TaskCaller for TaskServiceCaller {
+    async fn call_task(&self, calls: &TaskCalls, request: TaskCallRequest) -> Result<JsonValue, TaskCallError> {
+        self.execute(calls, request).await.1
+    }
+}
@@ -42,6 +42,12 @@ pub enum ExecutionError {
     #[error("Timeout error: {0}")]
     TimeoutError(String),
 
+    #[error("Resource limit exceeded: {0}")]
+    ResourceExhausted(String),
+
+    #[error("Execution cancelled")]
+    Cancelled,
+
     #[error("Configuration error: {0}")]
     ConfigurationError(String),
 }
@@ -5,6 +5,7 @@
 //! extracted from ratchet-lib to break circular dependencies.
 
 pub mod bridge;
+pub mod calls;
 pub mod error;
 pub mod executor;
 pub mod ipc;
@@ -12,10 +13,14 @@ pub mod process;
 pub mod worker;
 
 // Re-export main types
+pub use calls::{execution_logs, TaskServiceCaller};
 pub use error::{ExecutionError, ExecutionResult};
 pub use executor::{LocalExecutionContext, TaskExecutor};
 pub use process::{ProcessExecutorConfig, ProcessTaskExecutor};
-pub use worker::{WorkerConfig, WorkerProcess, WorkerProcessManager, WorkerProcessStatus};
+pub use worker::{RunningExecutions, WorkerConfig, WorkerProcess, WorkerProcessManager, WorkerProcessStatus};
+
+// Re-export the task call types executors are configured with
+pub use ratchet_js::{ConsoleCapture, ProgressReporter, TaskCallError, TaskCallRequest, TaskCaller, TaskCalls};
 
 // Re-export bridge types for interface compatibility
 pub use bridge::{ExecutionBridge, ExecutionConfigAdapter};
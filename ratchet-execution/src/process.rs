@@ -4,19 +4,23 @@ use async_trait::async_trait;
 use serde_json::Value as JsonValue;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc, RwLock};
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 use crate::error::{ExecutionError, ExecutionResult};
 use crate::executor::TaskExecutor;
-use crate::ipc::{CoordinatorMessage, ExecutionContext as IpcExecutionContext, TaskExecutionResult, WorkerMessage};
-use crate::worker::{WorkerConfig, WorkerProcessManager};
+use crate::ipc::{
+    CoordinatorMessage, ExecutionContext as IpcExecutionContext, TaskExecutionResult, TaskProgressUpdate, WorkerMessage,
+};
+use crate::worker::{RunningExecutions, WorkerConfig, WorkerProcessManager};
+use ratchet_js::{EgressPolicy, HttpClientState, ResourceLimits, TaskCalls};
 
 /// Process-based task executor that uses worker processes for task execution
 /// This solves the Send/Sync issues by running JavaScript tasks in separate processes
 pub struct ProcessTaskExecutor {
     worker_manager: Arc<RwLock<WorkerProcessManager>>,
+    running: RunningExecutions,
     config: ProcessExecutorConfig,
 }
 
@@ -27,6 +31,13 @@ pub struct ProcessExecutorConfig {
     pub task_timeout_seconds: u64,
     pub restart_on_crash: bool,
     pub max_restart_attempts: u32,
+    pub limits: ResourceLimits,
+    /// Handles `ratchet.call` from tasks; calls are rejected when unset
+    pub calls: Option<TaskCalls>,
+    /// Where task `fetch` calls may connect, on top of the policy a task declares
+    pub egress: EgressPolicy,
+    /// Response cache, circuit breakers and HTTP client policy shared by task `fetch` calls
+    pub http_client: HttpClientState,
 }
 
 impl Default for ProcessExecutorConfig {
@@ -36,6 +47,10 @@ impl Default for ProcessExecutorConfig {
             task_timeout_seconds: 300, // 5 minutes
             restart_on_crash: true,
             max_restart_attempts: 3,
+            limits: ResourceLimits::default(),
+            calls: None,
+            egress: EgressPolicy::default(),
+            http_client: HttpClientState::default(),
         }
     }
 }
@@ -51,11 +66,22 @@ impl ProcessTaskExecutor {
             health_check_interval_seconds: 30,
             task_timeout_seconds: config.task_timeout_seconds,
             worker_idle_timeout_seconds: Some(3600), // 1 hour
+            limits: config.limits.clone(),
+            calls: config.calls.clone(),
+            egress: config.egress.clone(),
+            http_client: config.http_client.clone(),
+            cancel_grace_period_seconds: 5,
         };
 
-        let worker_manager = Arc::new(RwLock::new(WorkerProcessManager::new(worker_config)));
+        let manager = WorkerProcessManager::new(worker_config);
+        let running = manager.running_executions();
+        let worker_manager = Arc::new(RwLock::new(manager));
 
-        Self { worker_manager, config }
+        Self {
+            worker_manager,
+            running,
+            config,
+        }
     }
 
     /// Create a new executor with default configuration
@@ -98,6 +124,19 @@ impl ProcessTaskExecutor {
         task_path: String,
         input_data: JsonValue,
         execution_context: Option<IpcExecutionContext>,
+    ) -> Result<TaskExecutionResult, ExecutionError> {
+        self.execute_task_direct_with_progress(task_id, task_path, input_data, execution_context, None)
+            .await
+    }
+
+    /// Execute a task directly, forwarding the progress it reports to `progress`
+    pub async fn execute_task_direct_with_progress(
+        &self,
+        task_id: i32,
+        task_path: String,
+        input_data: JsonValue,
+        execution_context: Option<IpcExecutionContext>,
+        progress: Option<mpsc::UnboundedSender<TaskProgressUpdate>>,
     ) -> Result<TaskExecutionResult, ExecutionError> {
         debug!("Executing task {} directly at path: {}", task_id, task_path);
 
@@ -117,7 +156,7 @@ impl ProcessTaskExecutor {
         // Get worker manager and send task to a worker
         let mut manager = self.worker_manager.write().await;
         let timeout = Duration::from_secs(self.config.task_timeout_seconds);
-        let result = manager.send_task(message, timeout).await;
+        let result = manager.send_task_with_progress(message, timeout, progress).await;
 
         match result {
             Ok(CoordinatorMessage::TaskResult { result, .. }) => {
@@ -180,6 +219,27 @@ impl ProcessTaskExecutor {
         }
     }
 
+    /// Ask a running execution to stop
+    ///
+    /// The task stops at its next safe point; if it does not within the grace
+    /// period it is abandoned and reported as cancelled. Returns `false` if the
+    /// execution is not running.
+    pub fn cancel_execution(&self, execution_id: &str) -> bool {
+        let cancelled = self.running.cancel(execution_id);
+        if cancelled {
+            info!("Cancellation requested for execution {}", execution_id);
+        }
+        cancelled
+    }
+
+    /// Registry of the running executions this executor can cancel
+    ///
+    /// Executions run elsewhere can be registered here, so cancelling through this
+    /// executor reaches them too.
+    pub fn running_executions(&self) -> RunningExecutions {
+        self.running.clone()
+    }
+
     /// Get statistics about worker processes
     pub async fn get_worker_stats(&self) -> Vec<crate::worker::WorkerStats> {
         let manager = self.worker_manager.read().await;
@@ -199,6 +259,12 @@ impl ProcessTaskExecutor {
     }
 }
 
+impl ratchet_interfaces::ExecutionCanceller for ProcessTaskExecutor {
+    fn cancel_execution(&self, execution_id: &str) -> bool {
+        ProcessTaskExecutor::cancel_execution(self, execution_id)
+    }
+}
+
 #[async_trait(?Send)]
 impl TaskExecutor for ProcessTaskExecutor {
     async fn execute_task(
@@ -421,6 +487,10 @@ mod tests {
             task_timeout_seconds: 60,
             restart_on_crash: false,
             max_restart_attempts: 1,
+            limits: Default::default(),
+            calls: None,
+            egress: Default::default(),
+            http_client: Default::default(),
         };
 
         let executor = ProcessTaskExecutor::new(config);
@@ -1,16 +1,54 @@
 //! Worker process management for task execution
 
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex as StdMutex};
 use std::time::Duration;
-use tokio::sync::{oneshot, Mutex};
+use tokio::sync::{mpsc, oneshot, Mutex};
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 use serde_json::Value as JsonValue;
 
 use crate::error::ExecutionError;
-use crate::ipc::{CoordinatorMessage, TaskExecutionResult, WorkerMessage, WorkerStatus, ExecutionContext};
-use ratchet_js::{JsTask, JsTaskRunner, ExecutionContext as JsExecutionContext};
+use crate::ipc::{
+    CoordinatorMessage, ExecutionContext, TaskExecutionResult, TaskLogEntry, TaskProgressUpdate, WorkerMessage,
+    WorkerStatus,
+};
+use ratchet_js::{
+    js_task::JsTaskError, ConsoleCapture, ExecutionInterrupt, JsExecutionError, JsTask, JsTaskRunner,
+    EgressPolicy, ExecutionContext as JsExecutionContext, HttpClientState, ProgressReporter, ResourceLimits, TaskCalls,
+    TaskSecrets, DEFAULT_MAX_CALL_DEPTH,
+};
+
+/// Convert captured console output into its IPC representation
+fn task_log_entries(console: &ConsoleCapture) -> Vec<TaskLogEntry> {
+    console
+        .take()
+        .into_iter()
+        .map(|entry| TaskLogEntry {
+            timestamp: entry.timestamp,
+            level: entry.level.as_str().to_string(),
+            message: entry.message,
+        })
+        .collect()
+}
+
+/// Create a reporter whose updates are forwarded to `sender` in their IPC form
+fn forward_progress(sender: mpsc::UnboundedSender<TaskProgressUpdate>) -> ProgressReporter {
+    let (reporter, mut updates) = ProgressReporter::channel();
+    tokio::spawn(async move {
+        while let Some(update) = updates.recv().await {
+            let forwarded = TaskProgressUpdate {
+                fraction: update.fraction,
+                message: update.message,
+                partial_output: update.partial_output,
+            };
+            if sender.send(forwarded).is_err() {
+                break;
+            }
+        }
+    });
+    reporter
+}
 
 /// Configuration for worker processes
 #[derive(Debug, Clone)]
@@ -22,6 +60,15 @@ pub struct WorkerConfig {
     pub health_check_interval_seconds: u64,
     pub task_timeout_seconds: u64,
     pub worker_idle_timeout_seconds: Option<u64>,
+    pub limits: ResourceLimits,
+    /// Handles `ratchet.call` from tasks; calls are rejected when unset
+    pub calls: Option<TaskCalls>,
+    /// Where task `fetch` calls may connect, on top of the policy a task declares
+    pub egress: EgressPolicy,
+    /// Response cache, circuit breakers and HTTP client policy shared by task `fetch` calls
+    pub http_client: HttpClientState,
+    /// How long a cancelled task may take to stop before it is abandoned
+    pub cancel_grace_period_seconds: u64,
 }
 
 impl Default for WorkerConfig {
@@ -34,6 +81,61 @@ impl Default for WorkerConfig {
             health_check_interval_seconds: 30,
             task_timeout_seconds: 300,               // 5 minutes
             worker_idle_timeout_seconds: Some(3600), // 1 hour
+            limits: ResourceLimits::default(),
+            calls: None,
+            egress: EgressPolicy::default(),
+            http_client: HttpClientState::default(),
+            cancel_grace_period_seconds: 5,
+        }
+    }
+}
+
+/// Interrupts of the executions currently running, keyed by execution ID
+#[derive(Debug, Clone, Default)]
+pub struct RunningExecutions {
+    interrupts: Arc<StdMutex<HashMap<String, ExecutionInterrupt>>>,
+}
+
+impl RunningExecutions {
+    /// Ask a running execution to stop
+    ///
+    /// Returns `false` if no execution with this ID is running.
+    pub fn cancel(&self, execution_id: &str) -> bool {
+        let interrupt = self
+            .interrupts
+            .lock()
+            .ok()
+            .and_then(|interrupts| interrupts.get(execution_id).cloned());
+        match interrupt {
+            Some(interrupt) => {
+                interrupt.interrupt();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether an execution with this ID is running
+    pub fn is_running(&self, execution_id: &str) -> bool {
+        self.interrupts
+            .lock()
+            .map(|interrupts| interrupts.contains_key(execution_id))
+            .unwrap_or(false)
+    }
+
+    /// Track a new execution, returning the interrupt that stops it
+    pub fn register(&self, execution_id: &str) -> ExecutionInterrupt {
+        let interrupt = ExecutionInterrupt::new();
+        if let Ok(mut interrupts) = self.interrupts.lock() {
+            interrupts.insert(execution_id.to_string(), interrupt.clone());
+        }
+        interrupt
+    }
+
+    /// Stop tracking a finished execution
+    pub fn remove(&self, execution_id: &str) {
+        if let Ok(mut interrupts) = self.interrupts.lock() {
+            interrupts.remove(execution_id);
         }
     }
 }
@@ -129,6 +231,7 @@ pub struct WorkerProcessManager {
     workers: HashMap<String, WorkerProcess>,
     _pending_tasks: Arc<Mutex<HashMap<Uuid, oneshot::Sender<Result<TaskExecutionResult, ExecutionError>>>>>,
     _task_queue: Arc<Mutex<Vec<WorkerMessage>>>,
+    running: RunningExecutions,
 }
 
 impl WorkerProcessManager {
@@ -139,9 +242,15 @@ impl WorkerProcessManager {
             workers: HashMap::new(),
             _pending_tasks: Arc::new(Mutex::new(HashMap::new())),
             _task_queue: Arc::new(Mutex::new(Vec::new())),
+            running: RunningExecutions::default(),
         }
     }
 
+    /// Registry used to cancel executions while they run
+    pub fn running_executions(&self) -> RunningExecutions {
+        self.running.clone()
+    }
+
     /// Start all worker processes
     pub async fn start(&mut self) -> Result<(), ExecutionError> {
         info!("Starting {} worker processes", self.config.worker_count);
@@ -177,7 +286,17 @@ impl WorkerProcessManager {
     pub async fn send_task(
         &mut self,
         message: WorkerMessage,
-        _timeout: Duration,
+        timeout: Duration,
+    ) -> Result<CoordinatorMessage, ExecutionError> {
+        self.send_task_with_progress(message, timeout, None).await
+    }
+
+    /// Send a task to a worker, forwarding the progress it reports to `progress`
+    pub async fn send_task_with_progress(
+        &mut self,
+        message: WorkerMessage,
+        timeout: Duration,
+        progress: Option<mpsc::UnboundedSender<TaskProgressUpdate>>,
     ) -> Result<CoordinatorMessage, ExecutionError> {
         // Find an available worker
         let worker_id = self
@@ -195,9 +314,74 @@ impl WorkerProcessManager {
                 ..
             } => {
                 let started_at = chrono::Utc::now();
-                
-                // Execute the JavaScript task
-                let result = match self.execute_javascript_task(&task_path, input_data, execution_context).await {
+                let execution_id = execution_context.execution_id.clone();
+                let interrupt = self.running.register(&execution_id);
+                let grace = Duration::from_secs(self.config.cancel_grace_period_seconds);
+
+                // Limits the task declares override the configured ones, including the
+                // wall-clock limit. A task whose limits cannot be read, or that sets a heap
+                // limit this in-process worker cannot enforce, does not run.
+                let limits = execution_context
+                    .limits
+                    .clone()
+                    .map(serde_json::from_value::<ResourceLimits>)
+                    .transpose()
+                    .map(|task_limits| match task_limits {
+                        Some(task_limits) => self.config.limits.with_overrides(&task_limits),
+                        None => self.config.limits.clone(),
+                    })
+                    .map_err(|e| ExecutionError::TaskExecutionError(format!("Invalid resource limits: {}", e)))
+                    .and_then(|limits| {
+                        limits
+                            .check_in_process()
+                            .map_err(|e| ExecutionError::TaskExecutionError(e.to_string()))?;
+                        Ok(limits)
+                    });
+                let timeout = limits
+                    .as_ref()
+                    .ok()
+                    .and_then(|limits| limits.timeout_seconds)
+                    .map_or(timeout, Duration::from_secs);
+
+                // Execute the JavaScript task, bounded by the wall-clock limit. A cancelled
+                // task that does not reach a safe point within the grace period is abandoned.
+                let reporter = progress.map(forward_progress);
+                let console = ConsoleCapture::new();
+                let secrets = TaskSecrets::new(execution_context.secrets.clone());
+                let run = async {
+                    self.execute_javascript_task(
+                        &task_path,
+                        input_data,
+                        execution_context,
+                        limits?,
+                        interrupt.clone(),
+                        reporter,
+                        console.clone(),
+                        secrets.clone(),
+                    )
+                    .await
+                };
+                let execution = tokio::time::timeout(timeout, async {
+                    tokio::select! {
+                        result = run => result,
+                        _ = async {
+                            interrupt.interrupted().await;
+                            tokio::time::sleep(grace).await;
+                        } => Err(ExecutionError::Cancelled),
+                    }
+                })
+                .await
+                .unwrap_or_else(|_| {
+                    // Stop the script thread as well; it yields to the interrupt regularly
+                    interrupt.interrupt();
+                    Err(ExecutionError::TimeoutError(format!(
+                        "Task exceeded the wall-clock limit of {}s",
+                        timeout.as_secs()
+                    )))
+                });
+                self.running.remove(&execution_id);
+
+                let result = match execution {
                     Ok(output) => {
                         let completed_at = chrono::Utc::now();
                         let duration_ms = (completed_at - started_at).num_milliseconds() as i32;
@@ -210,20 +394,38 @@ impl WorkerProcessManager {
                             started_at,
                             completed_at,
                             duration_ms,
+                            logs: task_log_entries(&console),
                         }
                     }
                     Err(error) => {
                         let completed_at = chrono::Utc::now();
                         let duration_ms = (completed_at - started_at).num_milliseconds() as i32;
                         
+                        let error_details = match &error {
+                            ExecutionError::TimeoutError(_) => Some(serde_json::json!({
+                                "error_type": "timeout",
+                                "error_code": "EXECUTION_TIMEOUT",
+                            })),
+                            ExecutionError::ResourceExhausted(_) => Some(serde_json::json!({
+                                "error_type": "resource_exhausted",
+                                "error_code": "RESOURCE_EXHAUSTED",
+                            })),
+                            ExecutionError::Cancelled => Some(serde_json::json!({
+                                "error_type": "cancelled",
+                                "error_code": "EXECUTION_CANCELLED",
+                            })),
+                            _ => None,
+                        };
+
                         TaskExecutionResult {
                             success: false,
                             output: None,
-                            error_message: Some(error.to_string()),
-                            error_details: None,
+                            error_message: Some(secrets.redact(&error.to_string())),
+                            error_details,
                             started_at,
                             completed_at,
                             duration_ms,
+                            logs: task_log_entries(&console),
                         }
                     }
                 };
@@ -308,11 +510,17 @@ impl WorkerProcessManager {
 
     /// Execute a JavaScript task using the ratchet-js engine
     /// This runs in a separate thread to avoid Send issues with Boa engine
+    #[allow(clippy::too_many_arguments)]
     async fn execute_javascript_task(
         &self,
         task_path: &str,
         input_data: JsonValue,
         execution_context: ExecutionContext,
+        limits: ResourceLimits,
+        interrupt: ExecutionInterrupt,
+        progress: Option<ProgressReporter>,
+        console: ConsoleCapture,
+        secrets: TaskSecrets,
     ) -> Result<JsonValue, ExecutionError> {
         debug!("Executing JavaScript task at path: {}", task_path);
 
@@ -337,9 +545,47 @@ impl WorkerProcessManager {
             job_id: execution_context.job_id.clone(),
         });
 
+        // A task whose declared policy cannot be read does not run unrestricted
+        let task_egress = execution_context
+            .egress_policy
+            .clone()
+            .map(serde_json::from_value::<EgressPolicy>)
+            .transpose()
+            .map_err(|e| ExecutionError::TaskExecutionError(format!("Invalid egress policy: {}", e)))?;
+        let egress = self.config.egress.clone();
+        let http_client = self.config.http_client.clone();
+        let http_policy = execution_context.http_policy.clone();
+
+        let calls = self.config.calls.as_ref().map(|calls| {
+            calls
+                .clone()
+                .with_max_depth(limits.max_call_depth.unwrap_or(DEFAULT_MAX_CALL_DEPTH))
+                .for_execution(task_name.clone(), Some(execution_context.execution_id.clone()))
+        });
+
         // Execute the task in a separate thread to avoid Send issues with Boa
         let result = tokio::task::spawn_blocking(move || {
-            let runner = JsTaskRunner::new();
+            let mut runner = JsTaskRunner::new()
+                .with_limits(limits)
+                .with_interrupt(interrupt)
+                .with_console(console)
+                .with_egress_policy(egress)
+                .with_http_client(http_client);
+            if let Some(policy) = task_egress {
+                runner = runner.with_task_egress_policy(policy);
+            }
+            if let Some(policy) = http_policy {
+                runner = runner.with_task_http_policy(policy);
+            }
+            if let Some(progress) = progress {
+                runner = runner.with_progress(progress);
+            }
+            if !secrets.is_empty() {
+                runner = runner.with_secrets(secrets);
+            }
+            if let Some(calls) = calls {
+                runner = runner.with_calls(calls);
+            }
             // Use the sync blocking execution since we're in a blocking task
             tokio::runtime::Handle::current().block_on(async move {
                 runner.execute_task(&js_task, input_data, js_context).await
@@ -347,7 +593,16 @@ impl WorkerProcessManager {
         })
         .await
         .map_err(|e| ExecutionError::TaskExecutionError(format!("Task execution failed: {}", e)))?
-        .map_err(|e| ExecutionError::TaskExecutionError(format!("JavaScript execution failed: {}", e)))?;
+        .map_err(|e| match e {
+            JsTaskError::JsExecutionError(JsExecutionError::ResourceExhausted(message)) => {
+                ExecutionError::ResourceExhausted(message)
+            }
+            JsTaskError::JsExecutionError(JsExecutionError::Cancelled) => ExecutionError::Cancelled,
+            JsTaskError::JsExecutionError(e @ JsExecutionError::Timeout(_)) => {
+                ExecutionError::TimeoutError(e.to_string())
+            }
+            e => ExecutionError::TaskExecutionError(format!("JavaScript execution failed: {}", e)),
+        })?;
 
         debug!("JavaScript task completed successfully");
         Ok(result)
@@ -432,6 +687,20 @@ mod tests {
         assert_eq!(worker.status, WorkerProcessStatus::Starting);
     }
 
+    #[test]
+    fn test_running_executions_cancel() {
+        let running = RunningExecutions::default();
+        assert!(!running.cancel("exec-1"));
+
+        let interrupt = running.register("exec-1");
+        assert!(running.is_running("exec-1"));
+        assert!(running.cancel("exec-1"));
+        assert!(interrupt.is_interrupted());
+
+        running.remove("exec-1");
+        assert!(!running.is_running("exec-1"));
+    }
+
     #[tokio::test]
     async fn test_worker_manager_creation() {
         let config = WorkerConfig::default();
@@ -565,6 +834,10 @@ function main(input) {
             job_id: None,
             task_id: "heartbeat".to_string(),
             task_version: "1.0.0".to_string(),
+            secrets: Default::default(),
+            egress_policy: None,
+            http_policy: None,
+            limits: None,
         };
 
         let message = WorkerMessage::ExecuteTask {
@@ -595,4 +868,62 @@ function main(input) {
             panic!("Expected TaskResult response, got: {:?}", result);
         }
     }
+
+    #[tokio::test]
+    async fn test_task_declared_limits_override_configured_limits() {
+        let config = WorkerConfig {
+            worker_count: 1,
+            ..Default::default()
+        };
+        let mut manager = WorkerProcessManager::new(config);
+        manager.start().await.unwrap();
+
+        let execution_context = ExecutionContext::new(Uuid::new_v4(), None, Uuid::new_v4(), "1.0.0".to_string())
+            .with_limits(Some(serde_json::json!({ "max_output_bytes": 10 })));
+        let message = WorkerMessage::ExecuteTask {
+            job_id: 1,
+            task_id: 2,
+            task_path: "heartbeat".to_string(),
+            input_data: serde_json::json!({}),
+            execution_context,
+            correlation_id: Uuid::new_v4(),
+        };
+
+        match manager.send_task(message, Duration::from_secs(10)).await {
+            Ok(CoordinatorMessage::TaskResult { result, .. }) => {
+                assert!(!result.success);
+                assert_eq!(result.error_details.unwrap()["error_code"], "RESOURCE_EXHAUSTED");
+            }
+            other => panic!("Expected TaskResult response, got: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_heap_limits_are_refused_in_process() {
+        let config = WorkerConfig {
+            worker_count: 1,
+            ..Default::default()
+        };
+        let mut manager = WorkerProcessManager::new(config);
+        manager.start().await.unwrap();
+
+        let execution_context = ExecutionContext::new(Uuid::new_v4(), None, Uuid::new_v4(), "1.0.0".to_string())
+            .with_limits(Some(serde_json::json!({ "max_heap_mb": 64 })));
+        let message = WorkerMessage::ExecuteTask {
+            job_id: 1,
+            task_id: 2,
+            task_path: "heartbeat".to_string(),
+            input_data: serde_json::json!({}),
+            execution_context,
+            correlation_id: Uuid::new_v4(),
+        };
+
+        match manager.send_task(message, Duration::from_secs(10)).await {
+            Ok(CoordinatorMessage::TaskResult { result, .. }) => {
+                assert!(!result.success);
+                assert!(result.error_message.unwrap().contains("heap limit"));
+            }
+            other => panic!("Expected TaskResult response, got: {:?}", other),
+        }
+    }
 }
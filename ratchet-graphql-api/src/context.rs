@@ -1,7 +1,10 @@
 //! GraphQL context types for dependency injection
 
 use crate::events::EventBroadcaster;
-use ratchet_interfaces::{RegistryManager, RepositoryFactory, TaskRegistry, TaskValidator};
+use ratchet_interfaces::{
+    ExecutionCanceller, OutputDeliveryStore, RegistryManager, RepositoryFactory, SecretStore, TaskRegistry,
+    TaskResultCache, TaskValidator, WorkflowStore,
+};
 use ratchet_mcp::server::adapter::RatchetMcpAdapter;
 use std::sync::Arc;
 
@@ -14,6 +17,11 @@ pub struct GraphQLContext {
     pub validator: Arc<dyn TaskValidator>,
     pub event_broadcaster: Arc<EventBroadcaster>,
     pub mcp_adapter: Option<Arc<RatchetMcpAdapter>>,
+    pub execution_canceller: Option<Arc<dyn ExecutionCanceller>>,
+    pub secret_store: Option<Arc<dyn SecretStore>>,
+    pub workflow_store: Option<Arc<dyn WorkflowStore>>,
+    pub result_cache: Option<Arc<dyn TaskResultCache>>,
+    pub delivery_store: Option<Arc<dyn OutputDeliveryStore>>,
 }
 
 impl GraphQLContext {
@@ -30,6 +38,11 @@ impl GraphQLContext {
             validator,
             event_broadcaster: Arc::new(EventBroadcaster::new()),
             mcp_adapter: None,
+            execution_canceller: None,
+            secret_store: None,
+            workflow_store: None,
+            result_cache: None,
+            delivery_store: None,
         }
     }
 
@@ -48,6 +61,11 @@ impl GraphQLContext {
             validator,
             event_broadcaster,
             mcp_adapter: None,
+            execution_canceller: None,
+            secret_store: None,
+            workflow_store: None,
+            result_cache: None,
+            delivery_store: None,
         }
     }
 
@@ -67,8 +85,64 @@ impl GraphQLContext {
             validator,
             event_broadcaster,
             mcp_adapter: Some(mcp_adapter),
+            execution_canceller: None,
+            secret_store: None,
+            workflow_store: None,
+            result_cache: None,
+            delivery_store: None,
         }
     }
+
+    /// Stop running executions through `canceller` when they are cancelled
+    pub fn with_execution_canceller(mut self, canceller: Arc<dyn ExecutionCanceller>) -> Self {
+        self.execution_canceller = Some(canceller);
+        self
+    }
+
+    /// Manage task secrets through `store`
+    pub fn with_secret_store(mut self, store: Arc<dyn SecretStore>) -> Self {
+        self.secret_store = Some(store);
+        self
+    }
+
+    /// Manage and run workflows through `store`
+    pub fn with_workflow_store(mut self, store: Arc<dyn WorkflowStore>) -> Self {
+        self.workflow_store = Some(store);
+        self
+    }
+
+    /// Inspect and purge memoized task results through `cache`
+    pub fn with_result_cache(mut self, cache: Arc<dyn TaskResultCache>) -> Self {
+        self.result_cache = Some(cache);
+        self
+    }
+
+    /// List and redeliver failed output deliveries through `store`
+    pub fn with_delivery_store(mut self, store: Arc<dyn OutputDeliveryStore>) -> Self {
+        self.delivery_store = Some(store);
+        self
+    }
+
+    /// The workflow store, or a service-unavailable error when none is configured
+    pub(crate) fn workflows(&self) -> Result<&Arc<dyn WorkflowStore>, ratchet_api_types::ApiError> {
+        self.workflow_store
+            .as_ref()
+            .ok_or_else(|| ratchet_api_types::ApiError::service_unavailable(Some("Workflows are not configured")))
+    }
+
+    /// The task result cache, or a service-unavailable error when none is configured
+    pub(crate) fn result_cache(&self) -> Result<&Arc<dyn TaskResultCache>, ratchet_api_types::ApiError> {
+        self.result_cache
+            .as_ref()
+            .ok_or_else(|| ratchet_api_types::ApiError::service_unavailable(Some("The result cache is not configured")))
+    }
+
+    /// The delivery ledger, or a service-unavailable error when none is configured
+    pub(crate) fn deliveries(&self) -> Result<&Arc<dyn OutputDeliveryStore>, ratchet_api_types::ApiError> {
+        self.delivery_store.as_ref().ok_or_else(|| {
+            ratchet_api_types::ApiError::service_unavailable(Some("The delivery ledger is not configured"))
+        })
+    }
 }
 
 /// Configuration for GraphQL setup
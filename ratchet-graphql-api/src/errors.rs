@@ -34,5 +34,49 @@ impl From<GraphQLError> for ApiError {
     }
 }
 
+/// Convert a secret store error, keeping not-found and validation errors distinct
+pub(crate) fn secret_error(error: ratchet_interfaces::SecretError) -> ApiError {
+    use ratchet_interfaces::SecretError;
+
+    match error {
+        SecretError::NotFound { name } => ApiError::not_found("Secret", &name),
+        SecretError::Invalid { message } => ApiError::bad_request(message),
+        other => ApiError::internal_error(other.to_string()),
+    }
+}
+
+/// Convert a result cache error, keeping unknown tasks distinct
+pub(crate) fn result_cache_error(error: ratchet_interfaces::ResultCacheError) -> ApiError {
+    use ratchet_interfaces::ResultCacheError;
+
+    match error {
+        ResultCacheError::TaskNotFound { id } => ApiError::not_found("Task", &id),
+        ResultCacheError::Storage { message } => ApiError::internal_error(message),
+    }
+}
+
+/// Convert a delivery ledger error, keeping unknown and busy deliveries distinct
+pub(crate) fn delivery_error(error: ratchet_interfaces::DeliveryLedgerError) -> ApiError {
+    use ratchet_interfaces::DeliveryLedgerError;
+
+    match error {
+        DeliveryLedgerError::NotFound { id } => ApiError::not_found("Delivery", &id),
+        error @ DeliveryLedgerError::NotRedeliverable { .. } => ApiError::conflict("Redelivery", &error.to_string()),
+        DeliveryLedgerError::Storage { message } => ApiError::internal_error(message),
+    }
+}
+
+/// Convert a workflow store error, keeping not-found and validation errors distinct
+pub(crate) fn workflow_error(error: ratchet_interfaces::WorkflowError) -> ApiError {
+    use ratchet_interfaces::WorkflowError;
+
+    match error {
+        WorkflowError::NotFound { id } => ApiError::not_found("Workflow", &id),
+        WorkflowError::RunNotFound { id } => ApiError::not_found("Workflow run", &id),
+        WorkflowError::Invalid { message } => ApiError::bad_request(message),
+        WorkflowError::Storage { message } => ApiError::internal_error(message),
+    }
+}
+
 /// Result type for GraphQL operations using unified error types
 pub type Result<T> = std::result::Result<T, ApiError>;
@@ -1,8 +1,13 @@
 //! GraphQL mutation resolvers
 
-use crate::{context::GraphQLContext, types::*};
+use crate::{
+    context::GraphQLContext,
+    errors::{delivery_error, result_cache_error, secret_error, workflow_error},
+    types::*,
+};
 use async_graphql::{Context, Object, Result};
-use ratchet_api_types::ApiError;
+use ratchet_api_types::{ApiError, ApiId, SecretScope, UnifiedWorkflow};
+use ratchet_interfaces::{DatabaseError, SecretKey};
 use ratchet_core::validation::{ErrorSanitizer, InputValidator};
 use serde_json::Value as JsonValue;
 use tracing::warn;
@@ -67,6 +72,8 @@ impl Mutation {
             sync_status: "local".to_string(),
             needs_push: false,
             last_synced_at: None,
+            signature_status: None,
+            signed_by: None,
             input_schema: input.input_schema,
             output_schema: input.output_schema,
             metadata: input.metadata,
@@ -209,9 +216,13 @@ impl Mutation {
             duration_ms: None,
             http_requests: None,
             recording_path: None,
+            parent_execution_uuid: None,
+            cache_hit: false,
             can_retry: false,
             can_cancel: true,
             progress: None,
+            progress_message: None,
+            partial_output: None,
         };
 
         // Create the execution using the repository
@@ -236,6 +247,30 @@ impl Mutation {
             .map_err(|e| ApiError::internal_error(format!("Failed to fetch task: {}", e)))?
             .ok_or_else(|| ApiError::bad_request("Task not found"))?;
 
+        // Validate that the jobs it depends on exist
+        let job_repo = context.repositories.job_repository();
+        let depends_on = ratchet_api_types::UnifiedJobDependency::dedupe(
+            input
+                .depends_on
+                .unwrap_or_default()
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        )
+        .map_err(|job_id| {
+            ApiError::bad_request(format!(
+                "Dependency job {} is listed more than once with different settings",
+                job_id
+            ))
+        })?;
+        for dependency in &depends_on {
+            job_repo
+                .find_by_id(dependency.job_id.as_i32().unwrap_or(0))
+                .await
+                .map_err(|e| ApiError::internal_error(format!("Failed to fetch job: {}", e)))?
+                .ok_or_else(|| ApiError::bad_request(format!("Dependency job {} not found", dependency.job_id)))?;
+        }
+
         // Create UnifiedJob from input
         let unified_job = ratchet_api_types::UnifiedJob {
             id: ratchet_api_types::ApiId::from_i32(0), // Will be set by database
@@ -248,10 +283,13 @@ impl Mutation {
             scheduled_for: input.scheduled_for,
             error_message: None,
             output_destinations: None, // TODO: Add support for output destinations in input
+            input: input.input,
+            depends_on,
+            idempotency_key: input.idempotency_key,
+            schedule_id: None,
         };
 
-        // Create the job using the repository
-        let job_repo = context.repositories.job_repository();
+        // Create the job using the repository; a repeated key or input returns the earlier job
         let created_job = job_repo
             .create(unified_job)
             .await
@@ -264,14 +302,6 @@ impl Mutation {
     async fn create_schedule(&self, ctx: &Context<'_>, input: CreateScheduleInput) -> Result<Schedule> {
         let context = ctx.data::<GraphQLContext>()?;
 
-        // Validate that task exists
-        let task_repo = context.repositories.task_repository();
-        let _task = task_repo
-            .find_by_id(input.task_id.0.as_i32().unwrap_or(0))
-            .await
-            .map_err(|e| ApiError::internal_error(format!("Failed to fetch task: {}", e)))?
-            .ok_or_else(|| ApiError::bad_request("Task not found"))?;
-
         // Validate input
         let validator = InputValidator::new();
         let sanitizer = ErrorSanitizer::default();
@@ -297,10 +327,43 @@ impl Mutation {
             }
         }
 
+        // Workflow schedules are stored with the task of the workflow's first step
+        let (task_id, workflow_id) = match input.workflow_id {
+            Some(workflow_id) => {
+                let workflow = context
+                    .workflows()?
+                    .find_workflow(workflow_id.0.clone())
+                    .await
+                    .map_err(workflow_error)?
+                    .ok_or_else(|| ApiError::not_found("Workflow", &workflow_id.0.to_string()))?;
+                let first_step = workflow
+                    .steps
+                    .first()
+                    .ok_or_else(|| ApiError::bad_request("Workflow has no steps"))?;
+                (first_step.task_id.clone(), Some(workflow.id))
+            }
+            None => (
+                input
+                    .task_id
+                    .ok_or_else(|| ApiError::bad_request("Either taskId or workflowId is required"))?
+                    .0,
+                None,
+            ),
+        };
+
+        // Validate that task exists
+        let task_repo = context.repositories.task_repository();
+        let _task = task_repo
+            .find_by_id(task_id.as_i32().unwrap_or(0))
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Failed to fetch task: {}", e)))?
+            .ok_or_else(|| ApiError::bad_request("Task not found"))?;
+
         // Create UnifiedSchedule from input
         let unified_schedule = ratchet_api_types::UnifiedSchedule {
             id: ratchet_api_types::ApiId::from_i32(0), // Will be set by database
-            task_id: input.task_id.0,
+            task_id,
+            workflow_id,
             name: input.name,
             description: input.description,
             cron_expression: input.cron_expression,
@@ -310,6 +373,7 @@ impl Mutation {
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
             output_destinations: None, // GraphQL doesn't support output destinations yet
+            policy: input.policy.map(Into::into).unwrap_or_default(),
         };
 
         // Create the schedule using the repository
@@ -317,7 +381,10 @@ impl Mutation {
         let created_schedule = schedule_repo
             .create(unified_schedule)
             .await
-            .map_err(|e| ApiError::internal_error(format!("Failed to create schedule: {}", e)))?;
+            .map_err(|e| match e {
+                DatabaseError::Validation { message } => ApiError::bad_request(message),
+                e => ApiError::internal_error(format!("Failed to create schedule: {}", e)),
+            })?;
 
         Ok(created_schedule)
     }
@@ -380,6 +447,9 @@ impl Mutation {
         if let Some(enabled) = input.enabled {
             existing_schedule.enabled = enabled;
         }
+        if let Some(policy) = input.policy {
+            policy.apply_to(&mut existing_schedule.policy);
+        }
 
         // Update timestamp
         existing_schedule.updated_at = chrono::Utc::now();
@@ -388,7 +458,10 @@ impl Mutation {
         let updated_schedule = schedule_repo
             .update(existing_schedule)
             .await
-            .map_err(|e| ApiError::internal_error(format!("Failed to update schedule: {}", e)))?;
+            .map_err(|e| match e {
+                DatabaseError::Validation { message } => ApiError::bad_request(message),
+                e => ApiError::internal_error(format!("Failed to update schedule: {}", e)),
+            })?;
 
         Ok(updated_schedule)
     }
@@ -625,6 +698,199 @@ impl Mutation {
         Ok(true)
     }
 
+    /// Cancel a pending or running execution
+    async fn cancel_execution(&self, ctx: &Context<'_>, id: GraphQLApiId) -> Result<Execution> {
+        let context = ctx.data::<GraphQLContext>()?;
+
+        let execution_repo = context.repositories.execution_repository();
+        let execution = execution_repo
+            .find_by_id(id.0.as_i32().unwrap_or(0))
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Failed to fetch execution: {}", e)))?
+            .ok_or_else(|| ApiError::not_found("Execution", &id.0.to_string()))?;
+
+        if !matches!(
+            execution.status,
+            ratchet_api_types::ExecutionStatus::Pending | ratchet_api_types::ExecutionStatus::Running
+        ) {
+            return Err(ApiError::conflict("Execution", "execution has already finished").into());
+        }
+
+        // Record the cancellation before stopping the task, so whatever runs it sees why it stopped
+        execution_repo
+            .mark_cancelled(execution.id.clone())
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Failed to cancel execution: {}", e)))?;
+
+        // Ask the executor to stop the task; a pending execution never reaches a worker
+        if let Some(canceller) = &context.execution_canceller {
+            canceller.cancel_execution(&execution.uuid.to_string());
+        }
+
+        let cancelled_execution = execution_repo
+            .find_by_id(id.0.as_i32().unwrap_or(0))
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Failed to fetch execution: {}", e)))?
+            .ok_or_else(|| ApiError::not_found("Execution", &id.0.to_string()))?;
+
+        context.event_broadcaster.broadcast_execution(cancelled_execution.clone());
+        Ok(cancelled_execution)
+    }
+
+    /// Create or replace a secret; the value is encrypted and never returned
+    async fn set_secret(&self, ctx: &Context<'_>, input: SetSecretInput) -> Result<Secret> {
+        let context = ctx.data::<GraphQLContext>()?;
+        let store = context
+            .secret_store
+            .as_ref()
+            .ok_or_else(|| ApiError::service_unavailable(Some("Secret storage is not configured")))?;
+
+        let key = SecretKey::new(input.name, input.scope.unwrap_or(SecretScope::Global), input.scope_id);
+        Ok(store
+            .set_secret(key, &input.value, input.description)
+            .await
+            .map_err(secret_error)?)
+    }
+
+    /// Delete a secret, returning whether it existed
+    async fn delete_secret(
+        &self,
+        ctx: &Context<'_>,
+        name: String,
+        scope: Option<SecretScopeGraphQL>,
+        scope_id: Option<String>,
+    ) -> Result<bool> {
+        let context = ctx.data::<GraphQLContext>()?;
+        let store = context
+            .secret_store
+            .as_ref()
+            .ok_or_else(|| ApiError::service_unavailable(Some("Secret storage is not configured")))?;
+
+        let key = SecretKey::new(name, scope.unwrap_or(SecretScope::Global), scope_id);
+        Ok(store.delete_secret(key).await.map_err(secret_error)?)
+    }
+
+    /// Create a workflow from steps that run registered tasks
+    async fn create_workflow(&self, ctx: &Context<'_>, input: CreateWorkflowInput) -> Result<Workflow> {
+        let context = ctx.data::<GraphQLContext>()?;
+
+        let now = chrono::Utc::now();
+        let workflow = UnifiedWorkflow {
+            id: ApiId::from_i32(0), // Will be set by database
+            name: input.name,
+            description: input.description,
+            enabled: input.enabled.unwrap_or(true),
+            steps: input.steps.into_iter().map(Into::into).collect(),
+            created_at: now,
+            updated_at: now,
+        };
+        Ok(context
+            .workflows()?
+            .create_workflow(workflow)
+            .await
+            .map_err(workflow_error)?)
+    }
+
+    /// Update a workflow; omitted fields are left unchanged
+    async fn update_workflow(&self, ctx: &Context<'_>, id: GraphQLApiId, input: UpdateWorkflowInput) -> Result<Workflow> {
+        let context = ctx.data::<GraphQLContext>()?;
+        let store = context.workflows()?;
+
+        let mut workflow = store
+            .find_workflow(id.0.clone())
+            .await
+            .map_err(workflow_error)?
+            .ok_or_else(|| ApiError::not_found("Workflow", &id.0.to_string()))?;
+        if let Some(name) = input.name {
+            workflow.name = name;
+        }
+        if let Some(description) = input.description {
+            workflow.description = Some(description);
+        }
+        if let Some(enabled) = input.enabled {
+            workflow.enabled = enabled;
+        }
+        if let Some(steps) = input.steps {
+            workflow.steps = steps.into_iter().map(Into::into).collect();
+        }
+
+        Ok(store.update_workflow(workflow).await.map_err(workflow_error)?)
+    }
+
+    /// Drop the results cached for a task, returning how many were dropped
+    async fn purge_task_cache(&self, ctx: &Context<'_>, task_id: GraphQLApiId) -> Result<i32> {
+        let context = ctx.data::<GraphQLContext>()?;
+        let purged = context
+            .result_cache()?
+            .purge(task_id.0)
+            .await
+            .map_err(result_cache_error)?;
+        Ok(purged as i32)
+    }
+
+    /// Deliver the output of a failed delivery again, returning the new attempt
+    async fn redeliver_output(&self, ctx: &Context<'_>, delivery_id: GraphQLApiId) -> Result<DeliveryAttempt> {
+        let context = ctx.data::<GraphQLContext>()?;
+        Ok(context
+            .deliveries()?
+            .redeliver(delivery_id.0)
+            .await
+            .map_err(delivery_error)?)
+    }
+
+    /// Delete a workflow and its runs, returning whether it existed
+    async fn delete_workflow(&self, ctx: &Context<'_>, id: GraphQLApiId) -> Result<bool> {
+        let context = ctx.data::<GraphQLContext>()?;
+        Ok(context.workflows()?.delete_workflow(id.0).await.map_err(workflow_error)?)
+    }
+
+    /// Queue a run of a workflow; the job processor runs its steps in dependency order
+    async fn run_workflow(&self, ctx: &Context<'_>, id: GraphQLApiId, input: Option<JsonValue>) -> Result<WorkflowRun> {
+        let context = ctx.data::<GraphQLContext>()?;
+        let input = input.unwrap_or_else(|| serde_json::json!({}));
+        Ok(context
+            .workflows()?
+            .queue_run(id.0, input, None)
+            .await
+            .map_err(workflow_error)?)
+    }
+
+    /// Cancel a queued or processing job
+    async fn cancel_job(&self, ctx: &Context<'_>, id: GraphQLApiId) -> Result<Job> {
+        let context = ctx.data::<GraphQLContext>()?;
+
+        let job_repo = context.repositories.job_repository();
+        let job = job_repo
+            .find_by_id(id.0.as_i32().unwrap_or(0))
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Failed to fetch job: {}", e)))?
+            .ok_or_else(|| ApiError::not_found("Job", &id.0.to_string()))?;
+
+        if matches!(
+            job.status,
+            ratchet_api_types::JobStatus::Completed
+                | ratchet_api_types::JobStatus::Failed
+                | ratchet_api_types::JobStatus::Cancelled
+        ) {
+            return Err(ApiError::conflict("Job", "job has already finished").into());
+        }
+
+        job_repo
+            .cancel(job.id.clone())
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Failed to cancel job: {}", e)))?;
+
+        let cancelled_job: Job = job_repo
+            .find_by_id(id.0.as_i32().unwrap_or(0))
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Failed to fetch job: {}", e)))?
+            .ok_or_else(|| ApiError::not_found("Job", &id.0.to_string()))?
+            .into();
+
+        context.event_broadcaster.broadcast_job(cancelled_job.clone());
+        Ok(cancelled_job)
+    }
+
     /// Update an existing job
     async fn update_job(&self, ctx: &Context<'_>, id: GraphQLApiId, input: UpdateJobInput) -> Result<Job> {
         let context = ctx.data::<GraphQLContext>()?;
@@ -725,6 +991,7 @@ impl Mutation {
                             OutputDestinationType::Webhook => "webhook".to_string(),
                             OutputDestinationType::File => "file".to_string(),
                             OutputDestinationType::Database => "database".to_string(),
+                            OutputDestinationType::S3 => "s3".to_string(),
                         },
                         template: None,
                         filesystem: None,
@@ -742,6 +1009,26 @@ impl Mutation {
                             authentication: None,
                         }),
                         stdio: None,
+                        s3: dest.s3.map(|s3| ratchet_api_types::UnifiedS3Config {
+                            bucket: s3.bucket,
+                            key: s3.key,
+                            region: s3.region,
+                            endpoint: s3.endpoint,
+                            path_style: s3.path_style,
+                            format: ratchet_api_types::OutputFormat::Json,
+                            storage_class: s3.storage_class,
+                            metadata: s3.metadata,
+                            access_key_id: s3.access_key_id,
+                            secret_access_key_secret: s3.secret_access_key_secret,
+                        }),
+                        database: dest.database.map(|db| ratchet_api_types::UnifiedDatabaseConfig {
+                            connection: db.connection,
+                            table: db.table,
+                            columns: db.columns,
+                            upsert: db.upsert,
+                            key_columns: db.key_columns,
+                            batch_size: db.batch_size,
+                        }),
                     }
                 })
                 .collect()
@@ -759,6 +1046,10 @@ impl Mutation {
             scheduled_for: None,
             error_message: None,
             output_destinations,
+            input: Some(input.input_data),
+            depends_on: Vec::new(),
+            idempotency_key: None,
+            schedule_id: None,
         };
 
         // Create the job using the repository
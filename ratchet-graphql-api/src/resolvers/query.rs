@@ -1,10 +1,14 @@
 //! GraphQL query resolvers
 
-use crate::{context::GraphQLContext, types::*};
+use crate::{
+    context::GraphQLContext,
+    errors::{delivery_error, result_cache_error, secret_error, workflow_error},
+    types::*,
+};
 use async_graphql::{Context, Object, Result};
 use ratchet_api_types::{
     pagination::{ListInput, SortInput},
-    ApiId,
+    ApiError, ApiId,
 };
 use ratchet_interfaces::{ExecutionFilters, JobFilters, ScheduleFilters, TaskFilters};
 
@@ -233,6 +237,24 @@ impl Query {
         }
     }
 
+    /// Get console output captured from an execution, oldest first
+    ///
+    /// `level` filters out less severe entries and `limit` keeps only the most recent ones.
+    async fn execution_logs(
+        &self,
+        ctx: &Context<'_>,
+        id: GraphQLApiId,
+        level: Option<ExecutionLogLevelGraphQL>,
+        limit: Option<i32>,
+    ) -> Result<Vec<ExecutionLog>> {
+        let context = ctx.data::<GraphQLContext>()?;
+        let execution_repo = context.repositories.execution_repository();
+
+        let api_id: ApiId = id.into();
+        let limit = limit.map(|limit| limit.max(0) as usize);
+        Ok(execution_repo.find_logs(api_id, level, limit).await?)
+    }
+
     /// Get all jobs with optional filtering
     async fn jobs(
         &self,
@@ -481,6 +503,76 @@ impl Query {
         })
     }
 
+    /// List stored secrets, optionally restricted to one scope; values are never returned
+    async fn secrets(&self, ctx: &Context<'_>, scope: Option<SecretScopeGraphQL>) -> Result<Vec<Secret>> {
+        let context = ctx.data::<GraphQLContext>()?;
+        let store = context
+            .secret_store
+            .as_ref()
+            .ok_or_else(|| ApiError::service_unavailable(Some("Secret storage is not configured")))?;
+
+        Ok(store.list_secrets(scope).await.map_err(secret_error)?)
+    }
+
+    /// List the results cached for a task, oldest first
+    async fn task_cache_entries(&self, ctx: &Context<'_>, task_id: GraphQLApiId) -> Result<Vec<CachedResult>> {
+        let context = ctx.data::<GraphQLContext>()?;
+        Ok(context
+            .result_cache()?
+            .entries(task_id.0)
+            .await
+            .map_err(result_cache_error)?)
+    }
+
+    /// List output deliveries that are waiting for a retry, being redelivered or dead-lettered
+    async fn failed_deliveries(
+        &self,
+        ctx: &Context<'_>,
+        job_id: Option<GraphQLApiId>,
+        execution_id: Option<GraphQLApiId>,
+    ) -> Result<Vec<DeliveryAttempt>> {
+        let context = ctx.data::<GraphQLContext>()?;
+        Ok(context
+            .deliveries()?
+            .failed_deliveries(job_id.map(|id| id.0), execution_id.map(|id| id.0))
+            .await
+            .map_err(delivery_error)?)
+    }
+
+    /// List all workflows
+    async fn workflows(&self, ctx: &Context<'_>) -> Result<Vec<Workflow>> {
+        let context = ctx.data::<GraphQLContext>()?;
+        Ok(context.workflows()?.list_workflows().await.map_err(workflow_error)?)
+    }
+
+    /// Get a workflow by ID
+    async fn workflow(&self, ctx: &Context<'_>, id: GraphQLApiId) -> Result<Option<Workflow>> {
+        let context = ctx.data::<GraphQLContext>()?;
+        Ok(context.workflows()?.find_workflow(id.0).await.map_err(workflow_error)?)
+    }
+
+    /// List the latest workflow runs, newest first, optionally of one workflow
+    async fn workflow_runs(
+        &self,
+        ctx: &Context<'_>,
+        workflow_id: Option<GraphQLApiId>,
+        limit: Option<i32>,
+    ) -> Result<Vec<WorkflowRun>> {
+        let context = ctx.data::<GraphQLContext>()?;
+        let limit = limit.unwrap_or(50).max(0) as u64;
+        Ok(context
+            .workflows()?
+            .list_runs(workflow_id.map(|id| id.0), limit)
+            .await
+            .map_err(workflow_error)?)
+    }
+
+    /// Get a workflow run with the state of each of its steps
+    async fn workflow_run(&self, ctx: &Context<'_>, id: GraphQLApiId) -> Result<Option<WorkflowRun>> {
+        let context = ctx.data::<GraphQLContext>()?;
+        Ok(context.workflows()?.find_run(id.0).await.map_err(workflow_error)?)
+    }
+
     /// Get system health status
     async fn health(&self, ctx: &Context<'_>) -> Result<HealthStatus> {
         let _context = ctx.data::<GraphQLContext>()?;
@@ -3,7 +3,7 @@
 use super::scalars::GraphQLApiId;
 use async_graphql::{InputObject, SimpleObject};
 use chrono::{DateTime, Utc};
-use ratchet_api_types::{ExecutionStatus, UnifiedExecution};
+use ratchet_api_types::{ExecutionLogLevel, ExecutionStatus, UnifiedExecution, UnifiedExecutionLog};
 use serde_json::Value as JsonValue;
 
 /// GraphQL Execution type - using UnifiedExecution directly for API consistency
@@ -12,6 +12,12 @@ pub type Execution = UnifiedExecution;
 /// GraphQL ExecutionStatus - using unified ExecutionStatus directly
 pub type ExecutionStatusGraphQL = ExecutionStatus;
 
+/// GraphQL execution log entry - using UnifiedExecutionLog directly
+pub type ExecutionLog = UnifiedExecutionLog;
+
+/// GraphQL log level - using unified ExecutionLogLevel directly
+pub type ExecutionLogLevelGraphQL = ExecutionLogLevel;
+
 /// Input type for creating executions
 #[derive(InputObject)]
 #[graphql(rename_fields = "camelCase")]
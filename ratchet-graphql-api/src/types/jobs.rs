@@ -3,7 +3,11 @@
 use super::scalars::GraphQLApiId;
 use async_graphql::{InputObject, SimpleObject};
 use chrono::{DateTime, Utc};
-use ratchet_api_types::{JobPriority, JobStatus, UnifiedJob};
+use ratchet_api_types::{
+    JobDependencyCondition, JobPriority, JobStatus, UnifiedDeliveryAttempt, UnifiedJob, UnifiedJobDependency,
+};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
 
 /// GraphQL Job type with additional fields for GraphQL API
 #[derive(SimpleObject, Clone, Debug)]
@@ -19,6 +23,9 @@ pub struct Job {
     pub scheduled_for: Option<DateTime<Utc>>,
     pub error_message: Option<String>,
     pub output_destinations: Option<Vec<OutputDestination>>,
+    pub input: Option<JsonValue>,
+    pub depends_on: Vec<JobDependency>,
+    pub idempotency_key: Option<String>,
 }
 
 impl From<UnifiedJob> for Job {
@@ -32,6 +39,7 @@ impl From<UnifiedJob> for Job {
                         "webhook" => OutputDestinationType::Webhook,
                         "file" => OutputDestinationType::File,
                         "database" => OutputDestinationType::Database,
+                        "s3" => OutputDestinationType::S3,
                         _ => OutputDestinationType::Webhook, // Default fallback
                     };
                     OutputDestination { destination_type }
@@ -50,6 +58,9 @@ impl From<UnifiedJob> for Job {
             scheduled_for: job.scheduled_for,
             error_message: job.error_message,
             output_destinations,
+            input: job.input,
+            depends_on: job.depends_on,
+            idempotency_key: job.idempotency_key,
         }
     }
 }
@@ -60,6 +71,33 @@ pub type JobStatusGraphQL = JobStatus;
 /// GraphQL JobPriority - using unified JobPriority directly
 pub type JobPriorityGraphQL = JobPriority;
 
+/// GraphQL JobDependency type - using UnifiedJobDependency directly
+pub type JobDependency = UnifiedJobDependency;
+
+/// GraphQL DeliveryAttempt type - using UnifiedDeliveryAttempt directly
+pub type DeliveryAttempt = UnifiedDeliveryAttempt;
+
+/// Input type for a dependency of a new job on an existing one
+#[derive(InputObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct JobDependencyInput {
+    pub job_id: GraphQLApiId,
+    /// Outcome of the parent that lets the job run; defaults to ON_SUCCESS
+    pub condition: Option<JobDependencyCondition>,
+    /// Merge the parent's output into the job's input
+    pub pass_output: Option<bool>,
+}
+
+impl From<JobDependencyInput> for UnifiedJobDependency {
+    fn from(input: JobDependencyInput) -> Self {
+        Self {
+            job_id: input.job_id.0,
+            condition: input.condition.unwrap_or_default(),
+            pass_output: input.pass_output.unwrap_or(false),
+        }
+    }
+}
+
 /// Input type for creating jobs
 #[derive(InputObject)]
 #[graphql(rename_fields = "camelCase")]
@@ -68,6 +106,11 @@ pub struct CreateJobInput {
     pub priority: Option<JobPriorityGraphQL>,
     pub scheduled_for: Option<DateTime<Utc>>,
     pub max_retries: Option<i32>,
+    pub input: Option<JsonValue>,
+    /// Jobs that must finish before this one runs
+    pub depends_on: Option<Vec<JobDependencyInput>>,
+    /// Key under which to queue the job; repeating a recent key returns the first job
+    pub idempotency_key: Option<String>,
 }
 
 /// Input type for updating jobs
@@ -152,6 +195,8 @@ pub struct ExecuteTaskInput {
 pub struct OutputDestinationInput {
     pub destination_type: OutputDestinationType,
     pub webhook: Option<WebhookDestinationInput>,
+    pub s3: Option<S3DestinationInput>,
+    pub database: Option<DatabaseDestinationInput>,
 }
 
 /// Webhook destination configuration
@@ -164,6 +209,38 @@ pub struct WebhookDestinationInput {
     pub retry_policy: Option<RetryPolicyInput>,
 }
 
+/// S3 destination configuration
+#[derive(InputObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct S3DestinationInput {
+    pub bucket: String,
+    pub key: String,
+    pub region: String,
+    pub endpoint: Option<String>,
+    #[graphql(default)]
+    pub path_style: bool,
+    pub storage_class: Option<String>,
+    pub metadata: Option<HashMap<String, String>>,
+    pub access_key_id: Option<String>,
+    /// Name of the global secret holding the secret access key
+    pub secret_access_key_secret: Option<String>,
+}
+
+/// Database destination configuration
+#[derive(InputObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct DatabaseDestinationInput {
+    /// Name of a database connection in the server configuration
+    pub connection: String,
+    pub table: String,
+    pub columns: HashMap<String, String>,
+    #[graphql(default)]
+    pub upsert: bool,
+    #[graphql(default)]
+    pub key_columns: Vec<String>,
+    pub batch_size: Option<i32>,
+}
+
 /// Retry policy configuration
 #[derive(InputObject)]
 #[graphql(rename_fields = "camelCase")]
@@ -183,6 +260,8 @@ pub enum OutputDestinationType {
     File,
     #[graphql(name = "DATABASE")]
     Database,
+    #[graphql(name = "S3")]
+    S3,
 }
 
 /// Output destination info for responses
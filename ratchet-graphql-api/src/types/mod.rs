@@ -7,16 +7,20 @@ pub mod executions;
 pub mod jobs;
 pub mod scalars;
 pub mod schedules;
+pub mod secrets;
 pub mod tasks;
 pub mod workers;
+pub mod workflows;
 
 // Re-export all types
 pub use executions::*;
 pub use jobs::*;
 pub use scalars::*;
 pub use schedules::*;
+pub use secrets::*;
 pub use tasks::*;
 pub use workers::*;
+pub use workflows::*;
 
 /// Pagination metadata for GraphQL responses - using unified PaginationMeta directly
 pub type PaginationMetaGraphQL = PaginationMeta;
@@ -3,7 +3,9 @@
 use super::scalars::GraphQLApiId;
 use async_graphql::{InputObject, SimpleObject};
 use chrono::{DateTime, Utc};
-use ratchet_api_types::UnifiedSchedule;
+use ratchet_api_types::{
+    ScheduleMisfirePolicy, ScheduleOverlapPolicy, UnifiedBlackoutWindow, UnifiedSchedule, UnifiedSchedulePolicy,
+};
 
 /// GraphQL Schedule type - using UnifiedSchedule directly for API consistency
 pub type Schedule = UnifiedSchedule;
@@ -12,11 +14,15 @@ pub type Schedule = UnifiedSchedule;
 #[derive(InputObject)]
 #[graphql(rename_fields = "camelCase")]
 pub struct CreateScheduleInput {
-    pub task_id: GraphQLApiId,
+    /// Task to run; required unless `workflow_id` is set
+    pub task_id: Option<GraphQLApiId>,
+    /// Workflow to run instead of a single task
+    pub workflow_id: Option<GraphQLApiId>,
     pub name: String,
     pub description: Option<String>,
     pub cron_expression: String,
     pub enabled: Option<bool>,
+    pub policy: Option<SchedulePolicyInput>,
 }
 
 /// Input type for updating schedules
@@ -27,6 +33,76 @@ pub struct UpdateScheduleInput {
     pub description: Option<String>,
     pub cron_expression: Option<String>,
     pub enabled: Option<bool>,
+    /// Policy fields to change; omitted fields are left unchanged
+    pub policy: Option<SchedulePolicyInput>,
+}
+
+/// Input type for the timezone, overlap, misfire, jitter and blackout policy of a schedule
+#[derive(InputObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct SchedulePolicyInput {
+    /// IANA timezone the cron expression is evaluated in, e.g. `Europe/Oslo`
+    pub timezone: Option<String>,
+    pub overlap: Option<ScheduleOverlapPolicy>,
+    pub misfire: Option<ScheduleMisfirePolicy>,
+    pub catch_up_limit: Option<i32>,
+    pub jitter_seconds: Option<i32>,
+    /// Replaces all blackout windows of the schedule
+    pub blackout_windows: Option<Vec<BlackoutWindowInput>>,
+}
+
+impl SchedulePolicyInput {
+    /// Overwrite the fields of `policy` this input sets
+    pub fn apply_to(self, policy: &mut UnifiedSchedulePolicy) {
+        if let Some(timezone) = self.timezone {
+            policy.timezone = timezone;
+        }
+        if let Some(overlap) = self.overlap {
+            policy.overlap = overlap;
+        }
+        if let Some(misfire) = self.misfire {
+            policy.misfire = misfire;
+        }
+        if let Some(catch_up_limit) = self.catch_up_limit {
+            policy.catch_up_limit = Some(catch_up_limit);
+        }
+        if let Some(jitter_seconds) = self.jitter_seconds {
+            policy.jitter_seconds = jitter_seconds;
+        }
+        if let Some(windows) = self.blackout_windows {
+            policy.blackout_windows = windows.into_iter().map(Into::into).collect();
+        }
+    }
+}
+
+impl From<SchedulePolicyInput> for UnifiedSchedulePolicy {
+    fn from(input: SchedulePolicyInput) -> Self {
+        let mut policy = UnifiedSchedulePolicy::default();
+        input.apply_to(&mut policy);
+        policy
+    }
+}
+
+/// Input type for a daily window in which a schedule does not fire
+#[derive(InputObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct BlackoutWindowInput {
+    /// Local start time, `HH:MM`
+    pub start: String,
+    /// Local end time, `HH:MM`; earlier than `start` for windows that run past midnight
+    pub end: String,
+    /// Days the window starts on, e.g. `["SAT", "SUN"]`; every day when omitted
+    pub days: Option<Vec<String>>,
+}
+
+impl From<BlackoutWindowInput> for UnifiedBlackoutWindow {
+    fn from(input: BlackoutWindowInput) -> Self {
+        Self {
+            start: input.start,
+            end: input.end,
+            days: input.days.unwrap_or_default(),
+        }
+    }
 }
 
 /// Input type for schedule filtering
@@ -0,0 +1,22 @@
+//! GraphQL types for task secrets
+
+use async_graphql::InputObject;
+use ratchet_api_types::{SecretScope, UnifiedSecret};
+
+/// GraphQL Secret type - using UnifiedSecret directly; values are never exposed
+pub type Secret = UnifiedSecret;
+
+/// GraphQL SecretScope - using unified SecretScope directly
+pub type SecretScopeGraphQL = SecretScope;
+
+/// Input type for creating or replacing a secret
+#[derive(InputObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct SetSecretInput {
+    pub name: String,
+    pub value: String,
+    pub scope: Option<SecretScopeGraphQL>,
+    /// Repository ID for repository secrets, task UUID for task secrets
+    pub scope_id: Option<String>,
+    pub description: Option<String>,
+}
@@ -2,12 +2,15 @@
 
 use async_graphql::{InputObject, SimpleObject};
 use chrono::{DateTime, Utc};
-use ratchet_api_types::UnifiedTask;
+use ratchet_api_types::{UnifiedCachedResult, UnifiedTask};
 use serde_json::Value as JsonValue;
 
 /// GraphQL Task type - using UnifiedTask directly for API consistency
 pub type Task = UnifiedTask;
 
+/// GraphQL CachedResult type - using UnifiedCachedResult directly
+pub type CachedResult = UnifiedCachedResult;
+
 /// Input type for creating tasks
 #[derive(InputObject)]
 #[graphql(rename_fields = "camelCase")]
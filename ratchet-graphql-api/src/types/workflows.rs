@@ -0,0 +1,69 @@
+//! GraphQL types for workflows
+
+use super::scalars::GraphQLApiId;
+use async_graphql::InputObject;
+use ratchet_api_types::{
+    UnifiedWorkflow, UnifiedWorkflowRun, UnifiedWorkflowStep, UnifiedWorkflowStepRun, WorkflowFailurePolicy,
+};
+use serde_json::Value as JsonValue;
+
+/// GraphQL Workflow type - using UnifiedWorkflow directly
+pub type Workflow = UnifiedWorkflow;
+
+/// GraphQL WorkflowStep type - using UnifiedWorkflowStep directly
+pub type WorkflowStep = UnifiedWorkflowStep;
+
+/// GraphQL WorkflowRun type - using UnifiedWorkflowRun directly
+pub type WorkflowRun = UnifiedWorkflowRun;
+
+/// GraphQL WorkflowStepRun type - using UnifiedWorkflowStepRun directly
+pub type WorkflowStepRun = UnifiedWorkflowStepRun;
+
+/// Input type for one workflow step
+#[derive(InputObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct WorkflowStepInput {
+    pub id: String,
+    /// Task ID, UUID or name
+    pub task_id: GraphQLApiId,
+    pub depends_on: Option<Vec<String>>,
+    /// Input template; `{"$from": "input.a"}` or `{"$from": "steps.<id>.b"}` values are filled in
+    pub input: Option<JsonValue>,
+    pub max_retries: Option<i32>,
+    pub retry_delay_seconds: Option<i32>,
+    pub on_failure: Option<WorkflowFailurePolicy>,
+}
+
+impl From<WorkflowStepInput> for UnifiedWorkflowStep {
+    fn from(input: WorkflowStepInput) -> Self {
+        Self {
+            id: input.id,
+            task_id: input.task_id.0,
+            depends_on: input.depends_on.unwrap_or_default(),
+            input: input.input,
+            max_retries: input.max_retries.unwrap_or(0),
+            retry_delay_seconds: input.retry_delay_seconds.unwrap_or(0),
+            on_failure: input.on_failure.unwrap_or_default(),
+        }
+    }
+}
+
+/// Input type for creating workflows
+#[derive(InputObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct CreateWorkflowInput {
+    pub name: String,
+    pub description: Option<String>,
+    pub enabled: Option<bool>,
+    pub steps: Vec<WorkflowStepInput>,
+}
+
+/// Input type for updating workflows; omitted fields are left unchanged
+#[derive(InputObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct UpdateWorkflowInput {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub enabled: Option<bool>,
+    pub steps: Option<Vec<WorkflowStepInput>>,
+}
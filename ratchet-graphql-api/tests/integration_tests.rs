@@ -899,6 +899,8 @@ fn create_test_task() -> UnifiedTask {
         sync_status: "synced".to_string(),
         needs_push: false,
         last_synced_at: Some(Utc::now()),
+        signature_status: None,
+        signed_by: None,
         input_schema: Some(json!({"type": "object", "properties": {}})),
         output_schema: Some(json!({"type": "object", "properties": {"result": {"type": "string"}}})),
         metadata: None,
@@ -921,9 +923,13 @@ fn create_test_execution() -> UnifiedExecution {
         duration_ms: Some(1000),
         http_requests: None,
         recording_path: None,
+        parent_execution_uuid: None,
+        cache_hit: false,
         can_retry: false,
         can_cancel: false,
         progress: Some(100.0),
+        progress_message: None,
+        partial_output: None,
     }
 }
 
@@ -939,6 +945,10 @@ fn create_test_job() -> UnifiedJob {
         scheduled_for: None,
         error_message: None,
         output_destinations: None,
+        input: None,
+        depends_on: Vec::new(),
+        idempotency_key: None,
+        schedule_id: None,
     }
 }
 
@@ -946,6 +956,7 @@ fn create_test_schedule() -> UnifiedSchedule {
     UnifiedSchedule {
         id: ApiId::from_i32(1),
         task_id: ApiId::from_i32(1),
+        workflow_id: None,
         name: "test-schedule".to_string(),
         description: Some("A test schedule".to_string()),
         cron_expression: "0 0 * * *".to_string(),
@@ -955,6 +966,7 @@ fn create_test_schedule() -> UnifiedSchedule {
         created_at: Utc::now(),
         updated_at: Utc::now(),
         output_destinations: None,
+        policy: Default::default(),
     }
 }
 
@@ -0,0 +1,480 @@
+//! Shared cache of responses to task HTTP calls
+//!
+//! Freshness follows `Cache-Control` (`s-maxage`, then `max-age`), then
+//! `Expires`, then the configured default TTL. Stale responses carrying an
+//! `ETag` or `Last-Modified` validator are revalidated with a conditional
+//! request, and a `304 Not Modified` refreshes the stored copy. Entries are
+//! keyed on the method, URL and all request headers.
+
+use crate::types::{HttpMethod, HttpRequest, HttpResponse};
+use chrono::{DateTime, Utc};
+use ratchet_caching::config::{CacheType, HttpCacheConfig};
+use ratchet_caching::http_cache::{CachedHttpResponse, HttpCache, HttpCacheKey};
+use ratchet_config::domains::http::HttpResponseCacheConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::debug;
+
+/// Statuses whose responses may be stored
+const CACHEABLE_STATUSES: [u16; 6] = [200, 203, 300, 301, 404, 410];
+
+/// Request headers that make a request bypass the cache, because the task
+/// expects to see the server's own answer to them
+const BYPASS_HEADERS: [&str; 3] = ["if-none-match", "if-modified-since", "range"];
+
+/// Counters of a shared response cache
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpCacheMetrics {
+    /// Requests answered from the cache without contacting the server
+    pub hits: u64,
+    /// Requests answered by the server
+    pub misses: u64,
+    /// Requests answered from the cache after the server confirmed it was current
+    pub revalidations: u64,
+    /// Responses stored
+    pub stores: u64,
+    /// Responses currently held
+    pub entries: u64,
+}
+
+/// What the cache holds for a request
+pub(crate) enum Lookup {
+    /// A fresh response to serve as is
+    Fresh(HttpResponse),
+    /// A stale response to revalidate
+    Stale(Arc<CachedHttpResponse>),
+    /// Nothing usable; the response may be stored once fetched
+    Miss,
+    /// The request may neither be answered from nor stored in the cache
+    Bypass,
+}
+
+/// Response store shared by the HTTP managers of many executions
+pub(crate) struct ResponseCache {
+    store: HttpCache,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    revalidations: AtomicU64,
+    stores: AtomicU64,
+}
+
+impl std::fmt::Debug for ResponseCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponseCache")
+            .field("hits", &self.hits)
+            .field("misses", &self.misses)
+            .field("revalidations", &self.revalidations)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ResponseCache {
+    /// Create a store sized by `config`; its other settings apply per request
+    pub(crate) fn new(config: &HttpResponseCacheConfig) -> Self {
+        let store = HttpCache::from_config(HttpCacheConfig {
+            enabled: true,
+            max_entries: config.max_entries,
+            max_response_size: config.max_response_size,
+            default_ttl_seconds: config.retention.as_secs().max(1),
+            honor_cache_control: true,
+            cache_type: CacheType::Moka,
+        });
+
+        Self {
+            store,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            revalidations: AtomicU64::new(0),
+            stores: AtomicU64::new(0),
+        }
+    }
+
+    /// Find what the cache holds for `request`
+    pub(crate) async fn lookup(&self, request: &HttpRequest) -> Lookup {
+        if !matches!(request.method, HttpMethod::Get | HttpMethod::Head)
+            || BYPASS_HEADERS.iter().any(|name| request.header(name).is_some())
+        {
+            return Lookup::Bypass;
+        }
+        let directives = request
+            .header("cache-control")
+            .map(|value| HttpCache::parse_cache_control(&value.to_ascii_lowercase()))
+            .unwrap_or_default();
+        if directives.no_store {
+            return Lookup::Bypass;
+        }
+
+        let cached = match self.store.get_stale(&cache_key(request)).await {
+            Ok(Some(cached)) => cached,
+            Ok(None) => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                return Lookup::Miss;
+            }
+            Err(e) => {
+                debug!("Response cache lookup failed: {}", e);
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                return Lookup::Miss;
+            }
+        };
+
+        let must_revalidate = directives.no_cache || directives.max_age == Some(Duration::ZERO);
+        if !cached.is_stale() && !must_revalidate {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Lookup::Fresh(cached_response(&request.url, &cached));
+        }
+        if cached.etag.is_some() || cached.last_modified.is_some() {
+            return Lookup::Stale(cached);
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        Lookup::Miss
+    }
+
+    /// Copy of `request` asking the server whether `cached` is still current
+    pub(crate) fn conditional_request(request: &HttpRequest, cached: &CachedHttpResponse) -> HttpRequest {
+        let mut conditional = request.clone();
+        if let Some(etag) = &cached.etag {
+            conditional = conditional.with_header("If-None-Match", etag.clone());
+        }
+        if let Some(last_modified) = &cached.last_modified {
+            conditional = conditional.with_header("If-Modified-Since", last_modified.clone());
+        }
+        conditional
+    }
+
+    /// Handle the server's answer to a conditional request for a stale response
+    ///
+    /// A `304 Not Modified` refreshes and returns the stored response; any
+    /// other answer replaces it.
+    pub(crate) async fn revalidated(
+        &self,
+        request: &HttpRequest,
+        cached: &CachedHttpResponse,
+        response: HttpResponse,
+        config: &HttpResponseCacheConfig,
+    ) -> HttpResponse {
+        if response.status != 304 {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            self.store(request, &response, config).await;
+            return response;
+        }
+
+        self.revalidations.fetch_add(1, Ordering::Relaxed);
+        let mut refreshed = cached_response(&request.url, cached);
+        for (name, value) in response.headers {
+            if name.eq_ignore_ascii_case("content-length") {
+                continue;
+            }
+            refreshed
+                .headers
+                .retain(|(existing, _)| !existing.eq_ignore_ascii_case(&name));
+            refreshed.headers.push((name, value));
+        }
+        self.store(request, &refreshed, config).await;
+        refreshed
+    }
+
+    /// Store `response` if it may be reused for later requests
+    pub(crate) async fn store(&self, request: &HttpRequest, response: &HttpResponse, config: &HttpResponseCacheConfig) {
+        // Responses reached through a redirect are not stored, so a request is
+        // never answered with content from a host it did not ask for
+        if !same_url(&request.url, &response.url) {
+            return;
+        }
+        let Some(freshness) = freshness(request, response, config) else {
+            return;
+        };
+
+        let etag = response.header("etag").map(str::to_string);
+        let last_modified = response.header("last-modified").map(str::to_string);
+        if freshness.is_zero() && etag.is_none() && last_modified.is_none() {
+            return;
+        }
+
+        let cached_at = Utc::now();
+        let expires_at = chrono::Duration::from_std(freshness)
+            .ok()
+            .and_then(|freshness| cached_at.checked_add_signed(freshness));
+        let cached = CachedHttpResponse {
+            status_code: response.status,
+            headers: response.headers.iter().cloned().collect::<HashMap<_, _>>(),
+            body: response.body.clone(),
+            cached_at,
+            expires_at,
+            etag,
+            last_modified,
+            size_bytes: response.body.len(),
+        };
+
+        match self.store.put(cache_key(request), cached, None).await {
+            Ok(()) => {
+                self.stores.fetch_add(1, Ordering::Relaxed);
+                debug!("Cached {} {} for {:?}", request.method, request.url, freshness);
+            }
+            Err(e) => debug!("Not caching {} {}: {}", request.method, request.url, e),
+        }
+    }
+
+    /// Current counters
+    pub(crate) async fn metrics(&self) -> HttpCacheMetrics {
+        let entries = self
+            .store
+            .stats()
+            .await
+            .map(|stats| stats.entry_count as u64)
+            .unwrap_or(0);
+        HttpCacheMetrics {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            revalidations: self.revalidations.load(Ordering::Relaxed),
+            stores: self.stores.load(Ordering::Relaxed),
+            entries,
+        }
+    }
+}
+
+/// Every request header is part of the key, so a stored response is only
+/// reused for requests sending the same credentials, cookies and other headers
+fn cache_key(request: &HttpRequest) -> HttpCacheKey {
+    let mut headers: Vec<(String, String)> = request
+        .headers
+        .iter()
+        .map(|(name, value)| (name.to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+    headers.sort();
+    HttpCacheKey::new(request.method.as_str(), request.url.clone()).with_headers(headers)
+}
+
+fn same_url(requested: &str, received: &str) -> bool {
+    match (url::Url::parse(requested), url::Url::parse(received)) {
+        (Ok(requested), Ok(received)) => requested == received,
+        _ => requested == received,
+    }
+}
+
+/// How long `response` stays fresh, or `None` if it may not be stored
+fn freshness(request: &HttpRequest, response: &HttpResponse, config: &HttpResponseCacheConfig) -> Option<Duration> {
+    if !CACHEABLE_STATUSES.contains(&response.status) || response.body.len() > config.max_response_size {
+        return None;
+    }
+
+    let directives = response
+        .header("cache-control")
+        .map(|value| HttpCache::parse_cache_control(&value.to_ascii_lowercase()))
+        .unwrap_or_default();
+    if directives.no_store || directives.private {
+        return None;
+    }
+    // Responses to authenticated requests are only shared when explicitly allowed
+    if request.header("authorization").is_some() && !directives.public && directives.s_maxage.is_none() {
+        return None;
+    }
+    // Responses varying on request headers would need one entry per variant
+    if let Some(vary) = response.header("vary") {
+        let varies = vary
+            .split(',')
+            .map(str::trim)
+            .any(|name| !name.is_empty() && !name.eq_ignore_ascii_case("accept-encoding"));
+        if varies {
+            return None;
+        }
+    }
+
+    if directives.no_cache {
+        return Some(Duration::ZERO);
+    }
+    if let Some(max_age) = directives.s_maxage.or(directives.max_age) {
+        let age = response
+            .header("age")
+            .and_then(|age| age.trim().parse::<u64>().ok())
+            .map(Duration::from_secs)
+            .unwrap_or_default();
+        return Some(max_age.saturating_sub(age));
+    }
+    if let Some(expires) = response.header("expires") {
+        // An invalid date means the response is already stale
+        let Ok(expires) = DateTime::parse_from_rfc2822(expires.trim()) else {
+            return Some(Duration::ZERO);
+        };
+        let date = response
+            .header("date")
+            .and_then(|date| DateTime::parse_from_rfc2822(date.trim()).ok())
+            .map(|date| date.with_timezone(&Utc))
+            .unwrap_or_else(Utc::now);
+        return Some((expires.with_timezone(&Utc) - date).to_std().unwrap_or_default());
+    }
+
+    Some(config.default_ttl)
+}
+
+fn cached_response(url: &str, cached: &CachedHttpResponse) -> HttpResponse {
+    let mut headers: Vec<(String, String)> = cached
+        .headers
+        .iter()
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+    headers.sort();
+
+    HttpResponse {
+        url: url.to_string(),
+        status: cached.status_code,
+        status_text: reqwest::StatusCode::from_u16(cached.status_code)
+            .ok()
+            .and_then(|status| status.canonical_reason())
+            .unwrap_or("Unknown Status")
+            .to_string(),
+        headers,
+        body: cached.body.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> HttpResponseCacheConfig {
+        HttpResponseCacheConfig {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    fn response(url: &str, headers: &[(&str, &str)]) -> HttpResponse {
+        HttpResponse {
+            url: url.to_string(),
+            status: 200,
+            status_text: "OK".to_string(),
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            body: b"{\"value\":1}".to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_freshness_follows_cache_headers() {
+        let request = HttpRequest::new(HttpMethod::Get, "https://api.example.com/data");
+        let url = "https://api.example.com/data";
+        let config = config();
+
+        let fresh = response(url, &[("Cache-Control", "public, max-age=60"), ("Age", "20")]);
+        assert_eq!(freshness(&request, &fresh, &config), Some(Duration::from_secs(40)));
+
+        let shared = response(url, &[("Cache-Control", "max-age=60, s-maxage=120")]);
+        assert_eq!(freshness(&request, &shared, &config), Some(Duration::from_secs(120)));
+
+        let expires = response(
+            url,
+            &[
+                ("Date", "Wed, 21 Oct 2015 07:28:00 GMT"),
+                ("Expires", "Wed, 21 Oct 2015 07:38:00 GMT"),
+            ],
+        );
+        assert_eq!(freshness(&request, &expires, &config), Some(Duration::from_secs(600)));
+
+        let revalidate = response(url, &[("Cache-Control", "no-cache"), ("ETag", "\"v1\"")]);
+        assert_eq!(freshness(&request, &revalidate, &config), Some(Duration::ZERO));
+
+        for headers in [
+            [("Cache-Control", "no-store")],
+            [("Cache-Control", "private, max-age=60")],
+            [("Vary", "Accept-Language")],
+        ] {
+            assert_eq!(freshness(&request, &response(url, &headers), &config), None);
+        }
+
+        let authorized = request.clone().with_header("Authorization", "Bearer token");
+        assert_eq!(freshness(&authorized, &fresh, &config), Some(Duration::from_secs(40)));
+        let unmarked = response(url, &[("Cache-Control", "max-age=60")]);
+        assert_eq!(freshness(&authorized, &unmarked, &config), None);
+    }
+
+    #[tokio::test]
+    async fn test_lookup_serves_fresh_and_revalidates_stale() {
+        let cache = ResponseCache::new(&config());
+        let url = "https://api.example.com/data";
+        let request = HttpRequest::new(HttpMethod::Get, url);
+
+        assert!(matches!(cache.lookup(&request).await, Lookup::Miss));
+        cache
+            .store(&request, &response(url, &[("Cache-Control", "max-age=60")]), &config())
+            .await;
+        match cache.lookup(&request).await {
+            Lookup::Fresh(cached) => assert_eq!(cached.body, b"{\"value\":1}".to_vec()),
+            _ => panic!("expected a fresh response"),
+        }
+
+        // Without freshness the entry is kept only for its validator
+        let other = HttpRequest::new(HttpMethod::Get, "https://api.example.com/other");
+        let validated = response("https://api.example.com/other", &[("ETag", "\"v1\"")]);
+        cache.store(&other, &validated, &config()).await;
+        let Lookup::Stale(stale) = cache.lookup(&other).await else {
+            panic!("expected a stale response");
+        };
+        let conditional = ResponseCache::conditional_request(&other, &stale);
+        assert_eq!(conditional.header("if-none-match"), Some("\"v1\""));
+
+        let not_modified = HttpResponse {
+            status: 304,
+            body: Vec::new(),
+            ..response(
+                "https://api.example.com/other",
+                &[("ETag", "\"v1\""), ("X-Refreshed", "yes")],
+            )
+        };
+        let refreshed = cache.revalidated(&other, &stale, not_modified, &config()).await;
+        assert_eq!(refreshed.status, 200);
+        assert_eq!(refreshed.header("x-refreshed"), Some("yes"));
+        assert_eq!(refreshed.body, b"{\"value\":1}".to_vec());
+
+        // Requests that ask for the server's own answer bypass the cache
+        let bypass = request.clone().with_header("Cache-Control", "no-store");
+        assert!(matches!(cache.lookup(&bypass).await, Lookup::Bypass));
+        let post = HttpRequest::new(HttpMethod::Post, url);
+        assert!(matches!(cache.lookup(&post).await, Lookup::Bypass));
+
+        let metrics = cache.metrics().await;
+        assert_eq!(metrics.hits, 1);
+        assert_eq!(metrics.misses, 1);
+        assert_eq!(metrics.revalidations, 1);
+        assert_eq!(metrics.stores, 3);
+    }
+
+    #[tokio::test]
+    async fn test_responses_are_reused_only_for_the_same_request_headers() {
+        let cache = ResponseCache::new(&config());
+        let url = "https://api.example.com/account";
+        let shared = response(url, &[("Cache-Control", "public, max-age=60")]);
+        let alice = HttpRequest::new(HttpMethod::Get, url).with_header("Cookie", "session=alice");
+        cache.store(&alice, &shared, &config()).await;
+
+        assert!(matches!(cache.lookup(&alice).await, Lookup::Fresh(_)));
+        for other in [
+            HttpRequest::new(HttpMethod::Get, url),
+            HttpRequest::new(HttpMethod::Get, url).with_header("Cookie", "session=bob"),
+            HttpRequest::new(HttpMethod::Get, url)
+                .with_header("Cookie", "session=alice")
+                .with_header("X-Api-Key", "key"),
+        ] {
+            assert!(matches!(cache.lookup(&other).await, Lookup::Miss));
+        }
+
+        // Header names are case-insensitive
+        let same = HttpRequest::new(HttpMethod::Get, url).with_header("cookie", "session=alice");
+        assert!(matches!(cache.lookup(&same).await, Lookup::Fresh(_)));
+    }
+
+    #[tokio::test]
+    async fn test_redirected_responses_are_not_stored() {
+        let cache = ResponseCache::new(&config());
+        let request = HttpRequest::new(HttpMethod::Get, "https://api.example.com/moved");
+        let redirected = response("https://elsewhere.example.com/data", &[("Cache-Control", "max-age=60")]);
+
+        cache.store(&request, &redirected, &config()).await;
+        assert!(matches!(cache.lookup(&request).await, Lookup::Miss));
+    }
+}
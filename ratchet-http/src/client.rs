@@ -1,8 +1,13 @@
 //! HTTP client implementation
 
+use crate::cache::{Lookup, ResponseCache};
 use crate::config::HttpConfig;
+use crate::egress::{self, EgressGuard, EgressPolicy};
 use crate::errors::HttpError;
-use crate::types::HttpMethod;
+use crate::resilience;
+use crate::state::HttpClientState;
+use crate::transport::HttpTransport;
+use crate::types::{HttpMethod, HttpRequest, HttpResponse};
 use anyhow::Result;
 use chrono::Utc;
 use reqwest::{
@@ -13,6 +18,7 @@ use reqwest::{
 use serde_json::{json, Value as JsonValue};
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::Arc;
 use tracing::{debug, info, warn};
 
 /// HTTP client trait for making HTTP requests
@@ -24,6 +30,26 @@ pub trait HttpClient: Send + Sync {
         params: Option<&JsonValue>,
         body: Option<&JsonValue>,
     ) -> Result<JsonValue, HttpError>;
+
+    /// Send a raw request and return the raw response.
+    ///
+    /// The default implementation goes through [`HttpClient::call_http`], so
+    /// request and response bodies are limited to what that JSON interface can carry.
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, HttpError> {
+        let headers: serde_json::Map<String, JsonValue> = request
+            .headers
+            .iter()
+            .map(|(name, value)| (name.clone(), JsonValue::String(value.clone())))
+            .collect();
+        let params = json!({ "method": request.method.as_str(), "headers": headers });
+        let body = request.body.as_ref().map(|bytes| {
+            serde_json::from_slice::<JsonValue>(bytes)
+                .unwrap_or_else(|_| JsonValue::String(String::from_utf8_lossy(bytes).into_owned()))
+        });
+
+        let legacy = self.call_http(&request.url, Some(&params), body.as_ref()).await?;
+        Ok(response_from_legacy(&request.url, &legacy))
+    }
 }
 
 /// HTTP Manager for handling HTTP requests with mock support
@@ -32,6 +58,11 @@ pub struct HttpManager {
     offline: bool,
     mocks: HashMap<String, JsonValue>,
     config: HttpConfig,
+    egress: Vec<EgressGuard>,
+    shared: Option<HttpClientState>,
+    transport: Option<HttpTransport>,
+    same_origin_redirects: bool,
+    max_response_bytes: Option<usize>,
 }
 
 impl Default for HttpManager {
@@ -53,9 +84,69 @@ impl HttpManager {
             offline: false,
             mocks: HashMap::new(),
             config,
+            egress: Vec::new(),
+            shared: None,
+            transport: None,
+            same_origin_redirects: false,
+            max_response_bytes: None,
         }
     }
 
+    /// Restrict where requests may connect to
+    ///
+    /// May be called more than once, for example with a global and a per-task
+    /// policy; a request must then be allowed by every policy. Mocked responses
+    /// in offline mode are not subject to the policies.
+    pub fn with_egress_policy(mut self, policy: &EgressPolicy) -> Result<Self, HttpError> {
+        self.egress.push(EgressGuard::new(policy)?);
+        Ok(self)
+    }
+
+    /// Share a response cache and per-host circuit breakers with other managers
+    ///
+    /// The manager adopts the state's caching, retry and circuit breaker policy,
+    /// and its transport if it has one; without shared state responses are not
+    /// cached and breakers are not applied, though requests are still retried.
+    pub fn with_client_state(mut self, state: HttpClientState) -> Self {
+        self.config.client_policy = state.policy().clone();
+        if let Some(transport) = state.transport() {
+            self.transport = Some(transport.clone());
+        }
+        self.shared = Some(state);
+        self
+    }
+
+    /// Send requests through the proxies, and with the CA and client
+    /// certificates, of `transport`
+    pub fn with_transport(mut self, transport: HttpTransport) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Override caching, retry and circuit breaker settings, for example with
+    /// those a task declares in its metadata
+    pub fn with_policy_overrides(mut self, overrides: &JsonValue) -> Result<Self, HttpError> {
+        self.config.client_policy = self
+            .config
+            .client_policy
+            .with_overrides(overrides)
+            .map_err(|e| HttpError::ConfigError(e.to_string()))?;
+        Ok(self)
+    }
+
+    /// Return redirects to another origin instead of following them, for
+    /// requests carrying credentials that must not leave their origin
+    pub fn with_same_origin_redirects(mut self) -> Self {
+        self.same_origin_redirects = true;
+        self
+    }
+
+    /// Refuse responses whose body is larger than `limit` bytes
+    pub fn with_max_response_bytes(mut self, limit: usize) -> Self {
+        self.max_response_bytes = Some(limit);
+        self
+    }
+
     /// Set offline mode
     pub fn set_offline(&mut self) {
         self.offline = true;
@@ -101,197 +192,405 @@ impl HttpManager {
     }
 }
 
-#[async_trait::async_trait]
-impl HttpClient for HttpManager {
-    /// Perform an HTTP request similar to the JavaScript fetch API
-    async fn call_http(
-        &self,
-        url: &str,
-        params: Option<&JsonValue>,
-        body: Option<&JsonValue>,
-    ) -> Result<JsonValue, HttpError> {
-        let start_time = Utc::now();
-
-        info!("Making HTTP request to: {}", url);
-        debug!("Request params: {:?}", params);
-        debug!("Request body: {:?}", body);
+impl HttpManager {
+    /// Find the mock registered for a request, falling back to partial URL matches
+    fn find_mock(&self, method: HttpMethod, url: &str) -> Option<&JsonValue> {
+        let mock_key = format!("{}:{}", method.as_str(), url);
+        if let Some(mock_response) = self.mocks.get(&mock_key) {
+            debug!("Found matching mock response for {} {}", method, url);
+            return Some(mock_response);
+        }
 
-        // Extract method from params or default to GET
-        let method = if let Some(params) = params {
-            if let Some(method_str) = params.get("method").and_then(|m| m.as_str()) {
-                method_str.parse().unwrap_or(HttpMethod::Get)
+        self.mocks.iter().find_map(|(key, response)| {
+            let (mock_method, mock_url) = key.split_once(':')?;
+            if mock_method.eq_ignore_ascii_case(method.as_str()) && (url.contains(mock_url) || mock_url.contains(url)) {
+                debug!("Found partial matching mock response for {} {}", method, url);
+                Some(response)
             } else {
-                HttpMethod::Get
+                None
             }
-        } else {
-            HttpMethod::Get
+        })
+    }
+
+    /// Client for a request to `url`, refusing it first if an egress policy does not allow it
+    async fn build_client(&self, url: &str) -> Result<Client, HttpError> {
+        debug!("Creating HTTP client with {}s timeout", self.config.timeout.as_secs());
+        // Create a client with configured settings
+        let client_builder = Client::builder()
+            .timeout(self.config.timeout)
+            .user_agent(&self.config.user_agent)
+            .danger_accept_invalid_certs(!self.config.verify_ssl);
+        let parsed = reqwest::Url::parse(url);
+        let client_builder = match &self.transport {
+            Some(transport) => transport.apply(client_builder, parsed.as_ref().ok()),
+            None => client_builder,
         };
 
-        // Check if we're in offline mode and return mock data if available
-        if self.offline {
-            debug!("Offline mode enabled, checking for mock response");
-            let mock_key = format!("{}:{}", method.as_str(), url);
-
-            if let Some(mock_response) = self.mocks.get(&mock_key) {
-                debug!("Found matching mock response for {} {}", method, url);
-                // Create a response object from the mock data
-                let response = json!({
-                    "ok": true,
-                    "status": 200,
-                    "statusText": "OK",
-                    "headers": {},
-                    "body": mock_response
-                });
-                return Ok(response);
-            } else {
-                // Check for partial URL matches
-                for (key, response) in &self.mocks {
-                    if let Some((mock_method, mock_url)) = key.split_once(':') {
-                        if mock_method.eq_ignore_ascii_case(method.as_str())
-                            && (url.contains(mock_url) || mock_url.contains(url))
-                        {
-                            debug!("Found partial matching mock response for {} {}", method, url);
-                            let response = json!({
-                                "ok": true,
-                                "status": 200,
-                                "statusText": "OK",
-                                "headers": {},
-                                "body": response
-                            });
-                            return Ok(response);
-                        }
+        // Under an egress policy every hop is checked before connecting, and host
+        // names resolve only to addresses the policy allows
+        let guards: Option<Arc<[EgressGuard]>> = (!self.egress.is_empty()).then(|| self.egress.clone().into());
+        // Clients presenting a client certificate stay on the host it was configured for
+        let identity_host = match (&self.transport, &parsed) {
+            (Some(transport), Ok(parsed)) => transport.identity_host(parsed),
+            _ => None,
+        };
+        let pinned_origin = match &parsed {
+            Ok(parsed) if self.same_origin_redirects => Some(parsed.origin()),
+            _ => None,
+        };
+        let client_builder = match &guards {
+            Some(guards) => {
+                let parsed = parsed.map_err(|e| HttpError::InvalidUrl(format!("{}: {}", url, e)))?;
+                if let Err(violation) = egress::check_url(guards, &parsed) {
+                    return Err(egress::audit_violation(url, &violation));
+                }
+
+                // A proxy resolves the host itself, and is trusted to be reachable
+                let mut resolver = egress::EgressResolver::new(guards.clone());
+                if let Some(transport) = self.transport.as_ref().filter(|t| t.proxy_for(&parsed).is_some()) {
+                    if let Err(violation) = egress::check_resolved(guards, &parsed).await {
+                        return Err(egress::audit_violation(url, &violation));
                     }
+                    resolver = resolver.trusting(transport.proxy_hosts());
                 }
+                client_builder
+                    .dns_resolver(Arc::new(resolver))
+                    .redirect(egress::redirect_policy(
+                        guards.clone(),
+                        self.config.max_redirects as usize,
+                        identity_host,
+                        pinned_origin,
+                    ))
+            }
+            None => client_builder.redirect(egress::redirect_policy(
+                Vec::new().into(),
+                self.config.max_redirects as usize,
+                identity_host,
+                pinned_origin,
+            )),
+        };
+        Ok(client_builder.build()?)
+    }
+
+    /// Answer `request` from the shared response cache when possible, otherwise from the network
+    async fn fetch(&self, client: &Client, request: &HttpRequest) -> Result<HttpResponse, HttpError> {
+        let config = &self.config.client_policy.cache;
+        let Some(cache) = self
+            .shared
+            .as_ref()
+            .filter(|_| config.enabled)
+            .map(|shared| shared.cache())
+        else {
+            return self.send_with_retries(client, request).await;
+        };
 
-                debug!("No matching mock response found for {} {}", method, url);
-                return Err(HttpError::InvalidUrl(
-                    "No mock response available in offline mode".to_string(),
-                ));
+        match cache.lookup(request).await {
+            Lookup::Fresh(response) => {
+                debug!("Answered {} {} from the response cache", request.method, request.url);
+                Ok(response)
+            }
+            Lookup::Stale(cached) => {
+                debug!("Revalidating cached response for {} {}", request.method, request.url);
+                let conditional = ResponseCache::conditional_request(request, &cached);
+                let response = self.send_with_retries(client, &conditional).await?;
+                Ok(cache.revalidated(request, &cached, response, config).await)
+            }
+            Lookup::Miss => {
+                let response = self.send_with_retries(client, request).await?;
+                cache.store(request, &response, config).await;
+                Ok(response)
             }
+            Lookup::Bypass => self.send_with_retries(client, request).await,
         }
+    }
 
-        // If no mock data or mock doesn't match, perform a real HTTP request
-        debug!("Creating HTTP client with {}s timeout", self.config.timeout.as_secs());
-        // Create a client with configured settings
-        let client = Client::builder()
-            .timeout(self.config.timeout)
-            .user_agent(&self.config.user_agent)
-            .danger_accept_invalid_certs(!self.config.verify_ssl)
-            .redirect(reqwest::redirect::Policy::limited(self.config.max_redirects as usize))
-            .build()?;
-
-        // Extract headers for recording
-        let request_headers: Option<HashMap<String, String>> = if let Some(params) = params {
-            params.get("headers").and_then(|h| h.as_object()).map(|headers| {
-                headers
-                    .iter()
-                    .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
-                    .collect()
-            })
+    /// Send `request`, retrying transient failures and honouring its host's circuit breaker
+    async fn send_with_retries(&self, client: &Client, request: &HttpRequest) -> Result<HttpResponse, HttpError> {
+        let policy = &self.config.client_policy;
+        let retry = resilience::retry_policy(&policy.retry);
+        let max_attempts = if resilience::is_idempotent(request.method) {
+            retry.max_attempts.max(1)
         } else {
-            None
+            1
         };
-
-        // Convert body to string for recording
-        let request_body_str = body.map(|b| {
-            if let Some(s) = b.as_str() {
-                s.to_string()
-            } else {
-                serde_json::to_string(b).unwrap_or_default()
+        let breaker = match (&self.shared, resilience::breaker_host(&request.url)) {
+            (Some(shared), Some(host)) if policy.circuit_breaker.enabled => {
+                Some((shared.breakers().breaker(&host), host))
             }
-        });
+            _ => None,
+        };
 
-        let reqwest_method = reqwest::Method::from(method);
+        let mut attempt = 1;
+        loop {
+            if let Some((breaker, host)) = &breaker {
+                if breaker.is_open() {
+                    breaker.record_rejection();
+                    warn!(
+                        "Circuit breaker open for {}, refusing {} {}",
+                        host, request.method, request.url
+                    );
+                    return Err(HttpError::CircuitOpen { host: host.clone() });
+                }
+            }
 
-        debug!("Building {} request to {}", method, url);
-        // Build the request
-        let mut request = client.request(reqwest_method, url);
-
-        // Add headers if provided
-        if let Some(params) = params {
-            if let Some(headers) = params.get("headers").and_then(|h| h.as_object()) {
-                debug!("Adding {} custom headers", headers.len());
-                let mut header_map = HeaderMap::new();
-                for (key, value) in headers {
-                    if let Some(value_str) = value.as_str() {
-                        let header_name =
-                            HeaderName::from_str(key).map_err(|_| HttpError::InvalidHeaderName(key.to_string()))?;
-
-                        if let Ok(header_value) = HeaderValue::from_str(value_str) {
-                            header_map.insert(header_name, header_value);
-                        }
-                    }
+            let outcome = self.send_once(client, request).await;
+            if let Some((breaker, _)) = &breaker {
+                if resilience::is_host_failure(&outcome) {
+                    breaker.record_failure();
+                } else if outcome.is_ok() {
+                    breaker.record_success();
                 }
-                request = request.headers(header_map);
             }
+            if attempt >= max_attempts || !resilience::should_retry(&outcome, &policy.retry) {
+                return outcome;
+            }
+
+            let delay = resilience::retry_delay(&outcome, &retry, attempt);
+            match &outcome {
+                Ok(response) => warn!(
+                    "{} {} returned {}, retrying in {:?}",
+                    request.method, request.url, response.status, delay
+                ),
+                Err(e) => warn!(
+                    "{} {} failed: {}, retrying in {:?}",
+                    request.method, request.url, e, delay
+                ),
+            }
+            if let Some(shared) = &self.shared {
+                shared.record_retry();
+            }
+            tokio::time::sleep(delay).await;
+            attempt += 1;
         }
+    }
 
-        // Add body if provided
-        if let Some(body) = body {
-            // Check if the Content-Type header indicates form data
-            let is_form_data = if let Some(params) = params {
-                if let Some(headers) = params.get("headers").and_then(|h| h.as_object()) {
-                    headers
-                        .get("Content-Type")
-                        .and_then(|ct| ct.as_str())
-                        .map(|ct| ct.contains("application/x-www-form-urlencoded"))
-                        .unwrap_or(false)
-                } else {
-                    false
-                }
-            } else {
-                false
-            };
+    /// Send `request` once
+    async fn send_once(&self, client: &Client, request: &HttpRequest) -> Result<HttpResponse, HttpError> {
+        let method = request.method;
+        let url = request.url.as_str();
 
-            if is_form_data {
-                // Send as form data if body is a string
-                if let Some(body_str) = body.as_str() {
-                    debug!("Adding form-encoded body to request");
-                    request = request.body(body_str.to_string());
-                } else {
-                    debug!("Adding JSON body to request (form data expected but body is not string)");
-                    request = request.json(body);
-                }
-            } else {
-                debug!("Adding JSON body to request");
-                request = request.json(body);
+        debug!("Building {} request to {}", method, url);
+        let mut header_map = HeaderMap::new();
+        for (key, value) in &request.headers {
+            let header_name = HeaderName::from_str(key).map_err(|_| HttpError::InvalidHeaderName(key.to_string()))?;
+            if let Ok(header_value) = HeaderValue::from_str(value) {
+                header_map.append(header_name, header_value);
             }
         }
 
+        let mut builder = client.request(reqwest::Method::from(method), url).headers(header_map);
+        if let Some(body) = &request.body {
+            builder = builder.body(body.clone());
+        }
+
         // Send the request and get the response
         debug!("Sending HTTP request");
-        let response = request.send().await?;
+        let response = match builder.send().await {
+            Ok(response) => response,
+            Err(e) => {
+                return Err(match egress::find_violation(&e) {
+                    Some(violation) => egress::audit_violation(e.url().map(|u| u.as_str()).unwrap_or(url), &violation),
+                    None => e.into(),
+                })
+            }
+        };
 
-        // Get status info
         let status = response.status();
-        let status_code = status.as_u16();
-        let status_text = status.canonical_reason().unwrap_or("Unknown Status");
+        let final_url = response.url().to_string();
+        let status_text = status.canonical_reason().unwrap_or("Unknown Status").to_string();
 
-        info!("HTTP response received: {} {}", status_code, status_text);
+        info!("HTTP response received: {} {}", status.as_u16(), status_text);
 
-        // Collect response headers for recording
-        let response_headers: HashMap<String, String> = response
+        let response_headers: Vec<(String, String)> = response
             .headers()
             .iter()
             .filter_map(|(name, value)| value.to_str().ok().map(|v| (name.to_string(), v.to_string())))
             .collect();
 
-        // Try to parse the response as JSON, fall back to text if it fails
-        debug!("Parsing response body");
-        let response_body = match response.json::<JsonValue>().await {
-            Ok(json_data) => {
-                debug!("Successfully parsed response as JSON");
-                json_data
+        let response_body = self.read_body(url, response).await?;
+        debug!("Response body received: {} bytes", response_body.len());
+
+        Ok(HttpResponse {
+            url: final_url,
+            status: status.as_u16(),
+            status_text,
+            headers: response_headers,
+            body: response_body,
+        })
+    }
+
+    /// Read the body of a response, refusing one larger than the configured limit
+    async fn read_body(&self, url: &str, mut response: reqwest::Response) -> Result<Vec<u8>, HttpError> {
+        let Some(limit) = self.max_response_bytes else {
+            return Ok(response.bytes().await?.to_vec());
+        };
+        let too_large = || HttpError::ResponseTooLarge {
+            url: url.to_string(),
+            limit,
+        };
+        if response.content_length().is_some_and(|length| length > limit as u64) {
+            return Err(too_large());
+        }
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if body.len() + chunk.len() > limit {
+                return Err(too_large());
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+}
+
+/// Build a raw request from the legacy `(url, params, body)` call shape
+pub(crate) fn legacy_request(url: &str, params: Option<&JsonValue>, body: Option<&JsonValue>) -> HttpRequest {
+    // Extract method from params or default to GET
+    let method = params
+        .and_then(|p| p.get("method"))
+        .and_then(|m| m.as_str())
+        .and_then(|m| m.parse().ok())
+        .unwrap_or(HttpMethod::Get);
+
+    let mut request = HttpRequest::new(method, url);
+
+    if let Some(headers) = params.and_then(|p| p.get("headers")).and_then(|h| h.as_object()) {
+        for (key, value) in headers {
+            if let Some(value_str) = value.as_str() {
+                request = request.with_header(key.clone(), value_str);
+            }
+        }
+    }
+
+    if let Some(body) = body {
+        // Check if the Content-Type header indicates form data
+        let is_form_data = request
+            .header("Content-Type")
+            .map(|ct| ct.contains("application/x-www-form-urlencoded"))
+            .unwrap_or(false);
+
+        match body.as_str() {
+            Some(body_str) if is_form_data => {
+                debug!("Adding form-encoded body to request");
+                request = request.with_body(body_str.as_bytes().to_vec());
             }
-            Err(_) => {
-                warn!("Failed to parse response as JSON, falling back to text");
-                // Fall back to text - we need to send a new request since json() consumes the response
-                let text_response = client.request(reqwest::Method::from(method), url).send().await?;
-                let text = text_response.text().await?;
-                debug!("Response parsed as text: {} bytes", text.len());
-                json!(text)
+            _ => {
+                debug!("Adding JSON body to request");
+                if request.header("Content-Type").is_none() {
+                    request = request.with_header("Content-Type", "application/json");
+                }
+                request = request.with_body(serde_json::to_vec(body).unwrap_or_default());
             }
-        };
+        }
+    }
+
+    request
+}
+
+/// Convert a raw response into the legacy JSON response object
+pub(crate) fn legacy_response(response: &HttpResponse) -> JsonValue {
+    let headers: HashMap<&str, &str> = response
+        .headers
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .collect();
+
+    // Try to parse the response as JSON, fall back to text if it fails
+    let body = match serde_json::from_slice::<JsonValue>(&response.body) {
+        Ok(json_data) => json_data,
+        Err(_) => {
+            debug!("Response body is not JSON, returning text");
+            json!(response.text())
+        }
+    };
+
+    json!({
+        "ok": response.is_success(),
+        "status": response.status,
+        "statusText": response.status_text,
+        "headers": headers,
+        "body": body
+    })
+}
+
+/// Convert a legacy JSON response object into a raw response
+fn response_from_legacy(url: &str, legacy: &JsonValue) -> HttpResponse {
+    let headers = legacy
+        .get("headers")
+        .and_then(|h| h.as_object())
+        .map(|headers| {
+            headers
+                .iter()
+                .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let body = match legacy.get("body") {
+        Some(JsonValue::String(text)) => text.clone().into_bytes(),
+        Some(JsonValue::Null) | None => Vec::new(),
+        Some(other) => serde_json::to_vec(other).unwrap_or_default(),
+    };
+
+    HttpResponse {
+        url: url.to_string(),
+        status: legacy.get("status").and_then(|s| s.as_u64()).unwrap_or(200) as u16,
+        status_text: legacy
+            .get("statusText")
+            .and_then(|s| s.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        headers,
+        body,
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpClient for HttpManager {
+    /// Perform an HTTP request similar to the JavaScript fetch API
+    async fn call_http(
+        &self,
+        url: &str,
+        params: Option<&JsonValue>,
+        body: Option<&JsonValue>,
+    ) -> Result<JsonValue, HttpError> {
+        debug!("Request params: {:?}", params);
+        debug!("Request body: {:?}", body);
+
+        let response = self.send(legacy_request(url, params, body)).await?;
+
+        debug!("HTTP call completed successfully");
+        Ok(legacy_response(&response))
+    }
+
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, HttpError> {
+        let start_time = Utc::now();
+        let method = request.method;
+        let url = request.url.as_str();
+
+        info!("Making HTTP request to: {}", url);
+
+        // Check if we're in offline mode and return mock data if available
+        if self.offline {
+            debug!("Offline mode enabled, checking for mock response");
+            return match self.find_mock(method, url) {
+                Some(mock_response) => Ok(HttpResponse {
+                    url: url.to_string(),
+                    status: 200,
+                    status_text: "OK".to_string(),
+                    headers: vec![("content-type".to_string(), "application/json".to_string())],
+                    body: serde_json::to_vec(mock_response)?,
+                }),
+                None => {
+                    debug!("No matching mock response found for {} {}", method, url);
+                    Err(HttpError::InvalidUrl(
+                        "No mock response available in offline mode".to_string(),
+                    ))
+                }
+            };
+        }
+
+        let client = self.build_client(url).await?;
+        let result = self.fetch(&client, &request).await?;
 
         // Record the HTTP request if recording is enabled
         #[cfg(feature = "recording")]
@@ -300,15 +599,17 @@ impl HttpClient for HttpManager {
             let duration_ms = (end_time - start_time).num_milliseconds() as u64;
 
             if crate::recording::is_recording() {
-                let response_body_str = serde_json::to_string(&response_body).unwrap_or_default();
+                let request_headers: HashMap<String, String> = request.headers.iter().cloned().collect();
+                let response_headers: HashMap<String, String> = result.headers.iter().cloned().collect();
+                let request_body = request.body.as_ref().map(|b| String::from_utf8_lossy(b).into_owned());
                 if let Err(e) = crate::recording::record_http_request(
                     url,
                     method.as_str(),
-                    request_headers.as_ref(),
-                    request_body_str.as_deref(),
-                    status_code,
+                    Some(&request_headers),
+                    request_body.as_deref(),
+                    result.status,
                     Some(&response_headers),
-                    &response_body_str,
+                    &result.text(),
                     start_time,
                     duration_ms,
                 ) {
@@ -316,18 +617,9 @@ impl HttpClient for HttpManager {
                 }
             }
         }
+        #[cfg(not(feature = "recording"))]
+        let _ = start_time;
 
-        // Construct a response object similar to JavaScript's Response
-        debug!("Constructing response object");
-        let result = json!({
-            "ok": status.is_success(),
-            "status": status_code,
-            "statusText": status_text,
-            "headers": response_headers,
-            "body": response_body
-        });
-
-        debug!("HTTP call completed successfully");
         Ok(result)
     }
 }
@@ -336,3 +628,373 @@ impl HttpClient for HttpManager {
 pub fn create_http_manager() -> HttpManager {
     HttpManager::new()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HttpClientPolicy, HttpClientState};
+
+    #[tokio::test]
+    async fn test_offline_mock_via_send_and_call_http() {
+        let mut manager = HttpManager::new();
+        manager.set_offline();
+        manager.add_mock(HttpMethod::Get, "http://example.com/data", json!({ "value": 42 }));
+
+        let response = manager
+            .send(HttpRequest::new(HttpMethod::Get, "http://example.com/data"))
+            .await
+            .unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.header("Content-Type"), Some("application/json"));
+        assert_eq!(serde_json::from_slice::<JsonValue>(&response.body).unwrap(), json!({ "value": 42 }));
+
+        let legacy = manager.call_http("http://example.com/data", None, None).await.unwrap();
+        assert_eq!(legacy["ok"], true);
+        assert_eq!(legacy["body"], json!({ "value": 42 }));
+    }
+
+    #[test]
+    fn test_legacy_request_encodes_body() {
+        let params = json!({ "method": "POST", "headers": { "X-Token": "abc" } });
+        let request = legacy_request("http://example.com", Some(&params), Some(&json!({ "a": 1 })));
+        assert_eq!(request.method, HttpMethod::Post);
+        assert_eq!(request.header("x-token"), Some("abc"));
+        assert_eq!(request.header("content-type"), Some("application/json"));
+        assert_eq!(request.body.as_deref(), Some(br#"{"a":1}"#.as_slice()));
+
+        let form_params = json!({ "headers": { "Content-Type": "application/x-www-form-urlencoded" } });
+        let form = legacy_request("http://example.com", Some(&form_params), Some(&json!("a=1&b=2")));
+        assert_eq!(form.body.as_deref(), Some(b"a=1&b=2".as_slice()));
+    }
+
+    #[test]
+    fn test_legacy_response_falls_back_to_text() {
+        let response = HttpResponse {
+            url: "http://example.com".to_string(),
+            status: 404,
+            status_text: "Not Found".to_string(),
+            headers: vec![("content-type".to_string(), "text/plain".to_string())],
+            body: b"missing".to_vec(),
+        };
+
+        let legacy = legacy_response(&response);
+        assert_eq!(legacy["ok"], false);
+        assert_eq!(legacy["status"], 404);
+        assert_eq!(legacy["body"], "missing");
+        assert_eq!(legacy["headers"]["content-type"], "text/plain");
+    }
+
+    /// Answer requests on a local port with `responses` in turn, repeating them
+    /// once exhausted, and record the head of every request received
+    async fn scripted_server(responses: Vec<String>) -> (String, Arc<std::sync::Mutex<Vec<String>>>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+        let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let received = requests.clone();
+        tokio::spawn(async move {
+            let mut responses = responses.into_iter().cycle();
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buffer = [0u8; 4096];
+                let read = stream.read(&mut buffer).await.unwrap_or(0);
+                received
+                    .lock()
+                    .unwrap()
+                    .push(String::from_utf8_lossy(&buffer[..read]).into_owned());
+                let _ = stream.write_all(responses.next().unwrap().as_bytes()).await;
+            }
+        });
+        (base, requests)
+    }
+
+    fn reply(status: &str, headers: &[&str], body: &str) -> String {
+        let mut reply = format!(
+            "HTTP/1.1 {}\r\nConnection: close\r\nContent-Length: {}\r\n",
+            status,
+            body.len()
+        );
+        for header in headers {
+            reply.push_str(header);
+            reply.push_str("\r\n");
+        }
+        reply.push_str("\r\n");
+        reply.push_str(body);
+        reply
+    }
+
+    #[tokio::test]
+    async fn test_idempotent_requests_are_retried() {
+        let (base, requests) = scripted_server(vec![
+            reply("503 Service Unavailable", &["Retry-After: 0"], ""),
+            reply("200 OK", &[], "done"),
+        ])
+        .await;
+
+        let mut policy = HttpClientPolicy::default();
+        policy.retry.max_attempts = 3;
+        policy.retry.initial_delay_ms = 1;
+        let state = HttpClientState::new(policy);
+        let manager = HttpManager::new().with_client_state(state.clone());
+
+        let response = manager
+            .send(HttpRequest::new(HttpMethod::Get, format!("{}/flaky", base)))
+            .await
+            .unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.text(), "done");
+        assert_eq!(requests.lock().unwrap().len(), 2);
+        assert_eq!(state.metrics().await.retries, 1);
+
+        // Requests that are not idempotent are sent once
+        let response = manager
+            .send(HttpRequest::new(HttpMethod::Post, format!("{}/flaky", base)))
+            .await
+            .unwrap();
+        assert_eq!(response.status, 503);
+        assert_eq!(requests.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_refuses_failing_host() {
+        let (base, requests) = scripted_server(vec![reply("500 Internal Server Error", &[], "")]).await;
+
+        let mut policy = HttpClientPolicy::default();
+        policy.circuit_breaker.enabled = true;
+        policy.circuit_breaker.failure_threshold = 2;
+        policy.circuit_breaker.min_requests = 2;
+        let state = HttpClientState::new(policy);
+        let manager = HttpManager::new().with_client_state(state.clone());
+
+        for _ in 0..2 {
+            let response = manager
+                .send(HttpRequest::new(HttpMethod::Get, format!("{}/down", base)))
+                .await
+                .unwrap();
+            assert_eq!(response.status, 500);
+        }
+        let error = manager
+            .send(HttpRequest::new(HttpMethod::Get, format!("{}/down", base)))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, HttpError::CircuitOpen { .. }), "{}", error);
+        assert_eq!(requests.lock().unwrap().len(), 2);
+
+        let metrics = state.metrics().await;
+        assert_eq!(metrics.circuit_breakers.len(), 1);
+        assert_eq!(metrics.circuit_breakers[0].state, crate::CircuitState::Open);
+        assert_eq!(metrics.circuit_breakers[0].rejected, 1);
+
+        // A manager may opt out of the shared breakers
+        let opted_out = HttpManager::new()
+            .with_client_state(state)
+            .with_policy_overrides(&json!({ "circuit_breaker": { "enabled": false } }))
+            .unwrap();
+        let response = opted_out
+            .send(HttpRequest::new(HttpMethod::Get, format!("{}/down", base)))
+            .await
+            .unwrap();
+        assert_eq!(response.status, 500);
+    }
+
+    #[tokio::test]
+    async fn test_response_cache_serves_and_revalidates() {
+        let (base, requests) = scripted_server(vec![
+            reply("200 OK", &["Cache-Control: max-age=60"], "fresh"),
+            reply("200 OK", &["Cache-Control: no-cache", "ETag: \"v1\""], "validated"),
+            reply("304 Not Modified", &["ETag: \"v1\""], ""),
+        ])
+        .await;
+
+        let mut policy = HttpClientPolicy::default();
+        policy.cache.enabled = true;
+        let state = HttpClientState::new(policy);
+        let manager = HttpManager::new().with_client_state(state.clone());
+        let send = |path: &str| manager.send(HttpRequest::new(HttpMethod::Get, format!("{}{}", base, path)));
+
+        assert_eq!(send("/fresh").await.unwrap().text(), "fresh");
+        assert_eq!(send("/fresh").await.unwrap().text(), "fresh");
+        assert_eq!(requests.lock().unwrap().len(), 1);
+
+        assert_eq!(send("/validated").await.unwrap().text(), "validated");
+        let revalidated = send("/validated").await.unwrap();
+        assert_eq!(revalidated.status, 200);
+        assert_eq!(revalidated.text(), "validated");
+        {
+            let requests = requests.lock().unwrap();
+            assert_eq!(requests.len(), 3);
+            assert!(requests[2].to_ascii_lowercase().contains("if-none-match: \"v1\""));
+        }
+
+        let metrics = state.metrics().await.cache;
+        assert_eq!(metrics.hits, 1);
+        assert_eq!(metrics.misses, 2);
+        assert_eq!(metrics.revalidations, 1);
+
+        // Caching stays off for managers that do not share state
+        let (base, requests) = scripted_server(vec![reply("200 OK", &["Cache-Control: max-age=60"], "")]).await;
+        for _ in 0..2 {
+            HttpManager::new()
+                .send(HttpRequest::new(HttpMethod::Get, format!("{}/", base)))
+                .await
+                .unwrap();
+        }
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_egress_policy_refuses_private_destinations() {
+        let manager = HttpManager::new().with_egress_policy(&EgressPolicy::default()).unwrap();
+
+        // An address literal is refused before connecting
+        let error = manager
+            .send(HttpRequest::new(
+                HttpMethod::Get,
+                "http://169.254.169.254/latest/meta-data",
+            ))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, HttpError::EgressDenied { .. }), "{}", error);
+
+        // A host name is refused once it resolves to a private address
+        let error = manager
+            .send(HttpRequest::new(HttpMethod::Get, "http://localhost:9/"))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&error, HttpError::EgressDenied { reason, .. } if reason.contains("private range")),
+            "{}",
+            error
+        );
+    }
+
+    #[tokio::test]
+    async fn test_requests_go_through_configured_proxy() {
+        use crate::transport::{HttpTransportConfig, ProxyConfig};
+
+        let (proxy, requests) = scripted_server(vec![reply("200 OK", &[], "proxied")]).await;
+        let config = HttpTransportConfig {
+            proxy: Some(ProxyConfig {
+                http_proxy: Some(proxy),
+                https_proxy: None,
+                all_proxy: None,
+                no_proxy: Some("bypassed.invalid".to_string()),
+            }),
+            ..Default::default()
+        };
+        let transport = HttpTransport::new(&config, &HashMap::new()).unwrap();
+        let state = HttpClientState::default().with_transport(transport);
+        // The proxy listens on a loopback address the default policy would refuse
+        let manager = HttpManager::new()
+            .with_client_state(state)
+            .with_egress_policy(&EgressPolicy::default())
+            .unwrap();
+
+        // A name that only the proxy can resolve is handed to it
+        let response = manager
+            .send(HttpRequest::new(HttpMethod::Get, "http://api.example.invalid/data"))
+            .await
+            .unwrap();
+        assert_eq!(response.text(), "proxied");
+        assert!(
+            requests.lock().unwrap()[0].starts_with("GET http://api.example.invalid/data HTTP/1.1"),
+            "{:?}",
+            requests.lock().unwrap()
+        );
+
+        // Targets are still checked where they resolve locally
+        let error = manager
+            .send(HttpRequest::new(HttpMethod::Get, "http://localhost/"))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, HttpError::EgressDenied { .. }), "{}", error);
+
+        // Hosts on the no-proxy list are connected to directly
+        assert!(manager
+            .send(HttpRequest::new(HttpMethod::Get, "http://bypassed.invalid/"))
+            .await
+            .is_err());
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_egress_policy_checks_redirects() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // A local server that redirects every request to a denied host
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buffer = [0u8; 1024];
+                let _ = stream.read(&mut buffer).await;
+                let response = format!(
+                    "HTTP/1.1 302 Found\r\nLocation: http://denied.localhost:{}/\r\nContent-Length: 0\r\n\r\n",
+                    port
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+
+        let policy = EgressPolicy {
+            deny_hosts: vec!["denied.localhost".to_string()],
+            ..EgressPolicy::unrestricted()
+        };
+        let manager = HttpManager::new().with_egress_policy(&policy).unwrap();
+        let error = manager
+            .send(HttpRequest::new(HttpMethod::Get, format!("http://127.0.0.1:{}/", port)))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&error, HttpError::EgressDenied { reason, .. } if reason.contains("denied.localhost")),
+            "{}",
+            error
+        );
+    }
+
+    #[tokio::test]
+    async fn test_responses_over_the_size_limit_are_refused() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // A local server that announces the size of its body only on /sized
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buffer = [0u8; 1024];
+                let read = stream.read(&mut buffer).await.unwrap_or(0);
+                let body = "x".repeat(64);
+                let response = if String::from_utf8_lossy(&buffer[..read]).starts_with("GET /sized") {
+                    format!("HTTP/1.1 200 OK\r\nContent-Length: 64\r\n\r\n{}", body)
+                } else {
+                    format!(
+                        "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n40\r\n{}\r\n0\r\n\r\n",
+                        body
+                    )
+                };
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+
+        let get = |path: &str| HttpRequest::new(HttpMethod::Get, format!("http://127.0.0.1:{}{}", port, path));
+        for path in ["/sized", "/chunked"] {
+            let error = HttpManager::new()
+                .with_max_response_bytes(32)
+                .send(get(path))
+                .await
+                .unwrap_err();
+            assert!(
+                matches!(error, HttpError::ResponseTooLarge { limit: 32, .. }),
+                "{}",
+                error
+            );
+
+            let response = HttpManager::new()
+                .with_max_response_bytes(64)
+                .send(get(path))
+                .await
+                .unwrap();
+            assert_eq!(response.body.len(), 64);
+        }
+    }
+}
@@ -1,6 +1,6 @@
 //! HTTP configuration
 
-use ratchet_config::domains::http::HttpConfig as ConfigHttpConfig;
+use ratchet_config::domains::http::{HttpClientPolicy, HttpConfig as ConfigHttpConfig};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
@@ -18,6 +18,10 @@ pub struct HttpConfig {
 
     /// Whether to verify SSL certificates
     pub verify_ssl: bool,
+
+    /// Response caching, retries and circuit breaking
+    #[serde(default)]
+    pub client_policy: HttpClientPolicy,
 }
 
 impl Default for HttpConfig {
@@ -27,6 +31,7 @@ impl Default for HttpConfig {
             max_redirects: 10,
             user_agent: "Ratchet/1.0".to_string(),
             verify_ssl: true,
+            client_policy: HttpClientPolicy::default(),
         }
     }
 }
@@ -34,6 +39,7 @@ impl Default for HttpConfig {
 impl From<ConfigHttpConfig> for HttpConfig {
     fn from(config: ConfigHttpConfig) -> Self {
         Self {
+            client_policy: config.client_policy(),
             timeout: config.timeout,
             max_redirects: config.max_redirects,
             user_agent: config.user_agent,
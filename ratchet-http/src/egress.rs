@@ -0,0 +1,606 @@
+//! Outbound connection policy enforcement
+//!
+//! An [`EgressGuard`] is a parsed [`EgressPolicy`]. [`HttpManager`](crate::HttpManager)
+//! checks the scheme, host and port of every URL it connects to against its guards,
+//! including each redirect hop, and resolves host names itself through
+//! [`EgressResolver`]. The resolver hands the connector only addresses that passed
+//! the checks, so a name cannot resolve to a public address when checked and to a
+//! private one when connected to.
+//!
+//! Requests sent through a proxy configured by an
+//! [`HttpTransport`](crate::HttpTransport) are the exception: the proxy resolves
+//! the target itself, so its addresses are only checked beforehand, as far as
+//! they resolve locally, and the proxy's own addresses are not checked at all.
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::Url;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::Arc;
+use tracing::warn;
+use url::Host;
+
+use crate::errors::HttpError;
+
+pub use ratchet_config::domains::http::EgressPolicy;
+
+/// Why a request was refused by an egress policy
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{reason}")]
+pub struct EgressViolation {
+    pub reason: String,
+}
+
+impl EgressViolation {
+    fn new(reason: impl Into<String>) -> Self {
+        Self { reason: reason.into() }
+    }
+}
+
+/// An address range in CIDR notation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Cidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    pub(crate) fn parse(cidr: &str) -> Option<Self> {
+        let (address, prefix) = match cidr.split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix)),
+            None => (cidr, None),
+        };
+        let network: IpAddr = address.trim().parse().ok()?;
+        let network = network.to_canonical();
+        let max_prefix = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix.trim().parse().ok().filter(|prefix| *prefix <= max_prefix)?,
+            None => max_prefix,
+        };
+        Some(Self { network, prefix })
+    }
+
+    pub(crate) fn contains(&self, address: IpAddr) -> bool {
+        match (self.network, address.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(address)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(network) & mask == u32::from(address) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(address)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(network) & mask == u128::from(address) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Whether an address is outside the publicly routable ranges
+fn is_non_public(address: IpAddr) -> bool {
+    match address.to_canonical() {
+        IpAddr::V4(address) => is_non_public_v4(address),
+        IpAddr::V6(address) => is_non_public_v6(address),
+    }
+}
+
+fn is_non_public_v4(address: Ipv4Addr) -> bool {
+    let [a, b, c, _] = address.octets();
+    address.is_private()
+        || address.is_loopback()
+        || address.is_link_local()
+        || address.is_unspecified()
+        || address.is_broadcast()
+        || address.is_documentation()
+        || address.is_multicast()
+        || a == 0 // "this" network
+        || (a == 100 && (64..128).contains(&b)) // shared address space
+        || (a == 192 && b == 0 && c == 0) // IETF protocol assignments
+        || (a == 198 && (18..20).contains(&b)) // benchmarking
+        || a >= 240 // reserved
+}
+
+fn is_non_public_v6(address: Ipv6Addr) -> bool {
+    let segments = address.segments();
+    address.is_loopback()
+        || address.is_unspecified()
+        || address.is_multicast()
+        || (segments[0] & 0xfe00) == 0xfc00 // unique local
+        || (segments[0] & 0xffc0) == 0xfe80 // link-local
+        || (segments[0] == 0x2001 && segments[1] == 0x0db8) // documentation
+        || (segments[0] == 0x0064 && segments[1] == 0xff9b) // NAT64, embeds an IPv4 address
+        || embedded_v4(address).into_iter().any(is_non_public_v4)
+}
+
+/// IPv4 addresses a tunnelling IPv6 address forwards to
+fn embedded_v4(address: Ipv6Addr) -> Vec<Ipv4Addr> {
+    let octets = address.octets();
+    let v4 = |bytes: &[u8]| Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]);
+    match address.segments() {
+        // 6to4 (2002::/16): the relay's address follows the prefix
+        [0x2002, ..] => vec![v4(&octets[2..6])],
+        // Teredo (2001::/32): the server's address, then the client's with its bits flipped
+        [0x2001, 0x0000, ..] => {
+            let client: Vec<u8> = octets[12..16].iter().map(|byte| !byte).collect();
+            vec![v4(&octets[4..8]), v4(&client)]
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Normalize a host name for matching
+pub(crate) fn normalize_host(host: &str) -> String {
+    host.trim_end_matches('.')
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_ascii_lowercase()
+}
+
+/// Whether `host` matches a host pattern; `*.example.com` matches subdomains of `example.com`
+pub(crate) fn host_matches(pattern: &str, host: &str) -> bool {
+    match pattern.strip_prefix("*.") {
+        Some(domain) => host.strip_suffix(domain).is_some_and(|prefix| prefix.ends_with('.')),
+        None => pattern == host,
+    }
+}
+
+/// A parsed [`EgressPolicy`]
+#[derive(Debug, Clone)]
+pub struct EgressGuard {
+    allow_hosts: Vec<String>,
+    deny_hosts: Vec<String>,
+    allow_cidrs: Vec<Cidr>,
+    deny_cidrs: Vec<Cidr>,
+    allow_ports: Vec<u16>,
+    deny_ports: Vec<u16>,
+    allow_schemes: Vec<String>,
+    deny_schemes: Vec<String>,
+    block_private_ranges: bool,
+}
+
+impl EgressGuard {
+    pub fn new(policy: &EgressPolicy) -> Result<Self, HttpError> {
+        let parse_cidrs = |cidrs: &[String]| {
+            cidrs
+                .iter()
+                .map(|cidr| {
+                    Cidr::parse(cidr)
+                        .ok_or_else(|| HttpError::ConfigError(format!("'{}' is not a valid CIDR range", cidr)))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let lowercase = |names: &[String]| -> Vec<String> { names.iter().map(|name| normalize_host(name)).collect() };
+
+        Ok(Self {
+            allow_hosts: lowercase(&policy.allow_hosts),
+            deny_hosts: lowercase(&policy.deny_hosts),
+            allow_cidrs: parse_cidrs(&policy.allow_cidrs)?,
+            deny_cidrs: parse_cidrs(&policy.deny_cidrs)?,
+            allow_ports: policy.allow_ports.clone(),
+            deny_ports: policy.deny_ports.clone(),
+            allow_schemes: lowercase(&policy.allow_schemes),
+            deny_schemes: lowercase(&policy.deny_schemes),
+            block_private_ranges: policy.block_private_ranges,
+        })
+    }
+
+    /// Check the scheme, host and port of a URL
+    ///
+    /// Host names are only checked against the host lists here; the addresses
+    /// they resolve to are checked by [`EgressGuard::check_address`].
+    pub fn check_url(&self, url: &Url) -> Result<(), EgressViolation> {
+        let scheme = url.scheme().to_ascii_lowercase();
+        if self.deny_schemes.contains(&scheme) {
+            return Err(EgressViolation::new(format!("scheme '{}' is denied", scheme)));
+        }
+        if !self.allow_schemes.is_empty() && !self.allow_schemes.contains(&scheme) {
+            return Err(EgressViolation::new(format!("scheme '{}' is not allowed", scheme)));
+        }
+
+        let port = url
+            .port_or_known_default()
+            .ok_or_else(|| EgressViolation::new(format!("no port known for scheme '{}'", scheme)))?;
+        if self.deny_ports.contains(&port) {
+            return Err(EgressViolation::new(format!("port {} is denied", port)));
+        }
+        if !self.allow_ports.is_empty() && !self.allow_ports.contains(&port) {
+            return Err(EgressViolation::new(format!("port {} is not allowed", port)));
+        }
+
+        match url.host() {
+            Some(Host::Domain(domain)) => self.check_host(domain),
+            Some(Host::Ipv4(address)) => self.check_address(&address.to_string(), IpAddr::V4(address)),
+            Some(Host::Ipv6(address)) => self.check_address(&address.to_string(), IpAddr::V6(address)),
+            None => Err(EgressViolation::new("URL has no host")),
+        }
+    }
+
+    /// Check a host name against the host lists
+    fn check_host(&self, host: &str) -> Result<(), EgressViolation> {
+        let host = normalize_host(host);
+        if self.deny_hosts.iter().any(|pattern| host_matches(pattern, &host)) {
+            return Err(EgressViolation::new(format!("host '{}' is denied", host)));
+        }
+        // With address ranges allowed too, the host may still pass on its addresses
+        if self.allow_cidrs.is_empty()
+            && !self.allow_hosts.is_empty()
+            && !self.allow_hosts.iter().any(|pattern| host_matches(pattern, &host))
+        {
+            return Err(EgressViolation::new(format!("host '{}' is not allowed", host)));
+        }
+        Ok(())
+    }
+
+    /// Check an address `host` resolved to
+    pub fn check_address(&self, host: &str, address: IpAddr) -> Result<(), EgressViolation> {
+        let host = normalize_host(host);
+        let address = address.to_canonical();
+        if self.deny_hosts.iter().any(|pattern| host_matches(pattern, &host)) {
+            return Err(EgressViolation::new(format!("host '{}' is denied", host)));
+        }
+        if self.deny_cidrs.iter().any(|cidr| cidr.contains(address)) {
+            return Err(EgressViolation::new(format!(
+                "address {} of '{}' is in a denied range",
+                address, host
+            )));
+        }
+
+        let in_allowed_range = self.allow_cidrs.iter().any(|cidr| cidr.contains(address));
+        if self.block_private_ranges && !in_allowed_range && is_non_public(address) {
+            return Err(EgressViolation::new(format!(
+                "address {} of '{}' is in a private range",
+                address, host
+            )));
+        }
+
+        let restricted = !self.allow_hosts.is_empty() || !self.allow_cidrs.is_empty();
+        if restricted && !in_allowed_range && !self.allow_hosts.iter().any(|pattern| host_matches(pattern, &host)) {
+            return Err(EgressViolation::new(format!(
+                "'{}' ({}) is not in the allow list",
+                host, address
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check a host name whose addresses are unknown
+    fn check_unresolved(&self, host: &str) -> Result<(), EgressViolation> {
+        let host = normalize_host(host);
+        if self.block_private_ranges && !self.allow_hosts.iter().any(|pattern| host_matches(pattern, &host)) {
+            return Err(EgressViolation::new(format!(
+                "'{}' does not resolve, so its addresses cannot be checked",
+                host
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Check a URL against every guard
+pub(crate) fn check_url(guards: &[EgressGuard], url: &Url) -> Result<(), EgressViolation> {
+    guards.iter().try_for_each(|guard| guard.check_url(url))
+}
+
+/// Check the addresses the host of a URL resolves to locally
+///
+/// Used for requests sent through a proxy, which resolves and connects to the
+/// host itself. Names that do not resolve locally cannot be checked, so they
+/// are refused while private ranges are, unless `allow_hosts` names them. For
+/// redirects the proxy follows, the proxy has to enforce the policy.
+pub(crate) async fn check_resolved(guards: &[EgressGuard], url: &Url) -> Result<(), EgressViolation> {
+    // Address literals are checked by `check_url`
+    let Some(Host::Domain(host)) = url.host() else {
+        return Ok(());
+    };
+    let addresses: Vec<_> = match tokio::net::lookup_host((host, 0)).await {
+        Ok(addresses) => addresses.collect(),
+        Err(_) => Vec::new(),
+    };
+    if addresses.is_empty() {
+        return guards.iter().try_for_each(|guard| guard.check_unresolved(host));
+    }
+    for address in addresses {
+        for guard in guards {
+            guard.check_address(host, address.ip())?;
+        }
+    }
+    Ok(())
+}
+
+/// Resolves host names for requests subject to egress guards
+///
+/// A name is refused if any of its addresses is, so a record set mixing public
+/// and private addresses cannot be used to reach the private ones.
+pub(crate) struct EgressResolver {
+    guards: Arc<[EgressGuard]>,
+    trusted: Vec<String>,
+}
+
+impl EgressResolver {
+    pub(crate) fn new(guards: Arc<[EgressGuard]>) -> Self {
+        Self {
+            guards,
+            trusted: Vec::new(),
+        }
+    }
+
+    /// Resolve `hosts` without checking their addresses, as for configured proxies
+    pub(crate) fn trusting(mut self, hosts: Vec<String>) -> Self {
+        self.trusted = hosts;
+        self
+    }
+}
+
+impl Resolve for EgressResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let guards = self.guards.clone();
+        let trusted = self.trusted.contains(&normalize_host(name.as_str()));
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addresses: Vec<_> = tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+            for address in addresses.iter().filter(|_| !trusted) {
+                for guard in guards.iter() {
+                    guard.check_address(&host, address.ip())?;
+                }
+            }
+            let addresses: Addrs = Box::new(addresses.into_iter());
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(addresses)
+        })
+    }
+}
+
+/// Redirect policy that checks each hop against the guards
+///
+/// With `pinned_host` set, redirects to other hosts are returned instead of
+/// followed, for clients presenting a client certificate to that host. With
+/// `pinned_origin` set, so are redirects to other origins, for requests
+/// carrying credentials meant for that origin.
+pub(crate) fn redirect_policy(
+    guards: Arc<[EgressGuard]>,
+    max_redirects: usize,
+    pinned_host: Option<String>,
+    pinned_origin: Option<url::Origin>,
+) -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(move |attempt| {
+        if attempt.previous().len() >= max_redirects {
+            return attempt.error("too many redirects");
+        }
+        if let Some(pinned_host) = &pinned_host {
+            if attempt.url().host_str().map(normalize_host).as_ref() != Some(pinned_host) {
+                return attempt.stop();
+            }
+        }
+        if pinned_origin
+            .as_ref()
+            .is_some_and(|origin| &attempt.url().origin() != origin)
+        {
+            return attempt.stop();
+        }
+        match check_url(&guards, attempt.url()) {
+            Ok(()) => attempt.follow(),
+            Err(violation) => attempt.error(violation),
+        }
+    })
+}
+
+/// Find the egress violation that made a request fail, if one did
+pub(crate) fn find_violation(error: &reqwest::Error) -> Option<EgressViolation> {
+    let mut source = std::error::Error::source(error);
+    while let Some(error) = source {
+        if let Some(violation) = error.downcast_ref::<EgressViolation>() {
+            return Some(violation.clone());
+        }
+        source = error.source();
+    }
+    None
+}
+
+/// Record a refused request as an audit event
+///
+/// Only the scheme, host, port and path of the URL are logged, since query
+/// strings and credentials often carry secrets.
+pub(crate) fn audit_violation(url: &str, violation: &EgressViolation) -> HttpError {
+    let logged_url = match Url::parse(url) {
+        Ok(mut parsed) => {
+            parsed.set_query(None);
+            parsed.set_fragment(None);
+            let _ = parsed.set_username("");
+            let _ = parsed.set_password(None);
+            parsed.to_string()
+        }
+        Err(_) => "<invalid url>".to_string(),
+    };
+    warn!(
+        target: "audit",
+        event = "egress_denied",
+        url = %logged_url,
+        reason = %violation.reason,
+        "Refused outbound request to {}: {}",
+        logged_url,
+        violation.reason
+    );
+    HttpError::EgressDenied {
+        url: logged_url,
+        reason: violation.reason.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guard(policy: EgressPolicy) -> EgressGuard {
+        EgressGuard::new(&policy).unwrap()
+    }
+
+    fn url(url: &str) -> Url {
+        Url::parse(url).unwrap()
+    }
+
+    #[test]
+    fn test_default_policy_blocks_private_ranges() {
+        let default = guard(EgressPolicy::default());
+        for address in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "2002:7f00:1::",
+            "2002:a9fe:a9fe::1",
+            "2001:0:4136:e378:8000:63bf:3fff:fdd2",
+            "2001:0:c0a8:101::1",
+        ] {
+            let address: IpAddr = address.parse().unwrap();
+            assert!(
+                default.check_address("internal", address).is_err(),
+                "{} was allowed",
+                address
+            );
+        }
+        assert!(default
+            .check_address("example.com", "93.184.216.34".parse().unwrap())
+            .is_ok());
+        assert!(default
+            .check_address("example.com", "2002:5db8:d822::1".parse().unwrap())
+            .is_ok());
+        assert!(default
+            .check_url(&url("http://169.254.169.254/latest/meta-data"))
+            .is_err());
+        assert!(default.check_url(&url("https://example.com/")).is_ok());
+        assert!(default.check_url(&url("ftp://example.com/")).is_err());
+
+        let unrestricted = guard(EgressPolicy::unrestricted());
+        assert!(unrestricted.check_url(&url("http://127.0.0.1:8080/")).is_ok());
+    }
+
+    #[test]
+    fn test_allowed_ranges_exempt_private_addresses() {
+        let guard = guard(EgressPolicy {
+            allow_cidrs: vec!["10.0.0.0/8".to_string()],
+            allow_hosts: vec!["*.example.com".to_string()],
+            ..EgressPolicy::default()
+        });
+        assert!(guard
+            .check_address("service.internal", "10.20.30.40".parse().unwrap())
+            .is_ok());
+        assert!(guard
+            .check_address("service.internal", "192.168.0.1".parse().unwrap())
+            .is_err());
+        assert!(guard
+            .check_address("api.example.com", "93.184.216.34".parse().unwrap())
+            .is_ok());
+        // Not in an allowed range and not an allowed host
+        assert!(guard
+            .check_address("other.org", "93.184.216.34".parse().unwrap())
+            .is_err());
+        // Matching an allowed host does not exempt private addresses
+        assert!(guard
+            .check_address("api.example.com", "127.0.0.1".parse().unwrap())
+            .is_err());
+    }
+
+    #[test]
+    fn test_host_port_and_scheme_lists() {
+        let guard = guard(EgressPolicy {
+            allow_hosts: vec!["api.example.com".to_string(), "*.trusted.org".to_string()],
+            deny_hosts: vec!["*.internal.trusted.org".to_string()],
+            allow_ports: vec![443],
+            allow_schemes: vec!["https".to_string()],
+            ..EgressPolicy::default()
+        });
+        assert!(guard.check_url(&url("https://API.example.com./v1")).is_ok());
+        assert!(guard.check_url(&url("https://a.trusted.org/")).is_ok());
+        assert!(guard.check_url(&url("https://trusted.org/")).is_err());
+        assert!(guard.check_url(&url("https://x.internal.trusted.org/")).is_err());
+        assert!(guard.check_url(&url("https://evil.com/")).is_err());
+        assert!(guard.check_url(&url("https://api.example.com:8443/")).is_err());
+        assert!(guard.check_url(&url("http://api.example.com/")).is_err());
+
+        let denied = EgressGuard::new(&EgressPolicy {
+            deny_ports: vec![25],
+            deny_schemes: vec!["http".to_string()],
+            ..EgressPolicy::default()
+        })
+        .unwrap();
+        assert!(denied.check_url(&url("https://example.com:25/")).is_err());
+        assert!(denied.check_url(&url("http://example.com/")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_proxied_hosts_must_resolve_while_private_ranges_are_blocked() {
+        let unresolvable = url("https://ratchet-egress-test.invalid/");
+        assert!(check_resolved(&[guard(EgressPolicy::default())], &unresolvable)
+            .await
+            .is_err());
+
+        let named = guard(EgressPolicy {
+            allow_hosts: vec!["*.invalid".to_string()],
+            ..EgressPolicy::default()
+        });
+        assert!(check_resolved(&[named], &unresolvable).await.is_ok());
+        assert!(check_resolved(&[guard(EgressPolicy::unrestricted())], &unresolvable)
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_pinned_clients_do_not_follow_redirects_to_other_hosts_or_origins() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buffer = [0u8; 4096];
+                let read = stream.read(&mut buffer).await.unwrap_or(0);
+                let reply = if String::from_utf8_lossy(&buffer[..read]).starts_with("GET /moved") {
+                    format!(
+                        "HTTP/1.1 302 Found\r\nLocation: http://localhost:{}/landed\r\nContent-Length: 0\r\n\r\n",
+                        port
+                    )
+                } else {
+                    "HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n".to_string()
+                };
+                let _ = stream.write_all(reply.as_bytes()).await;
+            }
+        });
+
+        let moved = format!("http://127.0.0.1:{}/moved", port);
+        let origin = Url::parse(&moved).unwrap().origin();
+        for (pinned_host, pinned_origin, status) in [
+            (None, None, 200),
+            (Some("127.0.0.1".to_string()), None, 302),
+            (None, Some(origin), 302),
+        ] {
+            let client = reqwest::Client::builder()
+                .no_proxy()
+                .redirect(redirect_policy(Vec::new().into(), 10, pinned_host, pinned_origin))
+                .build()
+                .unwrap();
+            assert_eq!(client.get(&moved).send().await.unwrap().status().as_u16(), status);
+        }
+    }
+
+    #[test]
+    fn test_cidr_matching() {
+        let cidr = Cidr::parse("192.168.0.0/16").unwrap();
+        assert!(cidr.contains("192.168.44.1".parse().unwrap()));
+        assert!(cidr.contains("::ffff:192.168.44.1".parse().unwrap()));
+        assert!(!cidr.contains("192.169.0.1".parse().unwrap()));
+        assert!(Cidr::parse("0.0.0.0/0").unwrap().contains("8.8.8.8".parse().unwrap()));
+        assert!(Cidr::parse("2001:db8::/32")
+            .unwrap()
+            .contains("2001:db8::1".parse().unwrap()));
+        assert!(Cidr::parse("10.0.0.0/33").is_none());
+        assert!(EgressGuard::new(&EgressPolicy {
+            deny_cidrs: vec!["nonsense".to_string()],
+            ..EgressPolicy::default()
+        })
+        .is_err());
+    }
+}
@@ -25,4 +25,16 @@ pub enum HttpError {
 
     #[error("Recording error: {0}")]
     RecordingError(String),
+
+    #[error("No recorded response for {0}")]
+    ReplayMismatch(String),
+
+    #[error("Egress policy denied request to {url}: {reason}")]
+    EgressDenied { url: String, reason: String },
+
+    #[error("Circuit breaker open for {host}")]
+    CircuitOpen { host: String },
+
+    #[error("Response from {url} is larger than {limit} bytes")]
+    ResponseTooLarge { url: String, limit: usize },
 }
@@ -1,28 +1,47 @@
 //! HTTP client functionality for Ratchet
 //!
 //! This crate provides HTTP client capabilities with mock support, request recording,
-//! and integration with the Ratchet configuration system.
+//! response caching, retries, circuit breaking, proxies and client certificates, and
+//! integration with the Ratchet configuration system.
 
+pub mod cache;
 pub mod client;
 pub mod config;
+pub mod egress;
 pub mod errors;
+pub mod resilience;
+pub mod state;
+pub mod transport;
 pub mod types;
 
 #[cfg(feature = "recording")]
 pub mod recording;
 
+#[cfg(feature = "recording")]
+pub mod replay;
+
 // Re-export main types for convenience
+pub use cache::HttpCacheMetrics;
 pub use client::{HttpClient, HttpManager};
 pub use config::HttpConfig;
+pub use egress::{EgressGuard, EgressPolicy, EgressViolation};
 pub use errors::HttpError;
-pub use types::{HttpMethod, HttpMethodError};
+pub use ratchet_config::domains::http::HttpClientPolicy;
+pub use ratchet_resilience::CircuitState;
+pub use resilience::HostBreakerMetrics;
+pub use state::{HttpClientMetrics, HttpClientState};
+pub use transport::{HttpTransport, HttpTransportConfig};
+pub use types::{HttpMethod, HttpMethodError, HttpRequest, HttpResponse};
 
 #[cfg(feature = "recording")]
 pub use recording::{
-    finalize_recording, get_recording_dir, is_recording, record_http_request, record_input, record_output,
+    add_recording_redactions, finalize_recording, get_recording_dir, is_recording, record_http_request, record_input, record_output,
     set_recording_dir,
 };
 
+#[cfg(feature = "recording")]
+pub use replay::{RecordedExchange, ReplayClient};
+
 // Backward compatibility function
 pub async fn call_http(
     url: &str,
@@ -16,10 +16,30 @@ use tracing::{debug, info, warn};
 // Global recording state
 static RECORDING_STATE: Lazy<Arc<Mutex<Option<RecordingState>>>> = Lazy::new(|| Arc::new(Mutex::new(None)));
 
+/// Placeholder written to recordings in place of redacted values
+pub const REDACTED: &str = "[REDACTED]";
+
 #[derive(Debug, Clone)]
 struct RecordingState {
     session_dir: PathBuf,
     entries: Vec<JsonValue>,
+    redactions: Vec<String>,
+}
+
+impl RecordingState {
+    /// Replace every registered value in serialized JSON `text` with [`REDACTED`]
+    fn redact(&self, text: String) -> String {
+        self.redactions.iter().fold(text, |text, value| {
+            // Values appear JSON-escaped inside the serialized document
+            let escaped = serde_json::to_string(value).unwrap_or_default();
+            let escaped = escaped.trim_matches('"');
+            if escaped.is_empty() {
+                text
+            } else {
+                text.replace(escaped, REDACTED)
+            }
+        })
+    }
 }
 
 /// Helper function to safely access the recording state
@@ -41,11 +61,27 @@ pub fn set_recording_dir(session_dir: PathBuf) -> Result<()> {
         *state = Some(RecordingState {
             session_dir,
             entries: Vec::new(),
+            redactions: Vec::new(),
         });
         Ok(())
     })
 }
 
+/// Keep `values` out of the files written for the current session
+///
+/// Used for task secrets, which may end up in request headers, URLs or the
+/// output. Has no effect when no session is being recorded.
+pub fn add_recording_redactions(values: Vec<String>) -> Result<()> {
+    with_recording_state(|mut state| {
+        if let Some(recording_state) = state.as_mut() {
+            recording_state
+                .redactions
+                .extend(values.into_iter().filter(|value| !value.is_empty()));
+        }
+        Ok(())
+    })
+}
+
 /// Record an HTTP request/response pair in HAR format
 pub fn record_http_request(
     url: &str,
@@ -184,7 +220,7 @@ pub fn finalize_recording() -> Result<()> {
         });
 
             let har_file = recording_state.session_dir.join("requests.har");
-            let har_json = serde_json::to_string_pretty(&har)?;
+            let har_json = recording_state.redact(serde_json::to_string_pretty(&har)?);
             fs::write(&har_file, har_json)
                 .with_context(|| format!("Failed to write HAR file: {:?}", har_file))?;
 
@@ -223,7 +259,7 @@ pub fn record_input(input_json: &JsonValue) -> Result<()> {
             debug!("Recording task input JSON");
 
             let input_file = recording_state.session_dir.join("input.json");
-            let input_pretty = serde_json::to_string_pretty(input_json)?;
+            let input_pretty = recording_state.redact(serde_json::to_string_pretty(input_json)?);
             fs::write(&input_file, input_pretty)
                 .with_context(|| format!("Failed to write input JSON: {:?}", input_file))?;
 
@@ -240,7 +276,7 @@ pub fn record_output(output_json: &JsonValue) -> Result<()> {
             debug!("Recording task output JSON");
 
             let output_file = recording_state.session_dir.join("output.json");
-            let output_pretty = serde_json::to_string_pretty(output_json)?;
+            let output_pretty = recording_state.redact(serde_json::to_string_pretty(output_json)?);
             fs::write(&output_file, output_pretty)
                 .with_context(|| format!("Failed to write output JSON: {:?}", output_file))?;
 
@@ -0,0 +1,367 @@
+//! HTTP replay from HAR recordings
+//!
+//! Serves responses captured by the [`crate::recording`] module so a task can be
+//! re-executed deterministically without network access. Values redacted from
+//! a recording, such as task secrets, match whatever the replayed task sends
+//! in their place.
+
+use crate::client::HttpClient;
+use crate::errors::HttpError;
+use crate::recording::REDACTED;
+use crate::types::{HttpMethod, HttpRequest, HttpResponse};
+use anyhow::{Context, Result};
+use serde_json::Value as JsonValue;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::{debug, warn};
+
+/// A request/response pair loaded from a HAR entry
+#[derive(Debug, Clone)]
+pub struct RecordedExchange {
+    pub method: HttpMethod,
+    pub url: String,
+    pub request_body: Option<String>,
+    pub response: HttpResponse,
+}
+
+impl RecordedExchange {
+    fn from_har_entry(entry: &JsonValue) -> Result<Self> {
+        let request = &entry["request"];
+        let response = &entry["response"];
+
+        let method: HttpMethod = request["method"]
+            .as_str()
+            .unwrap_or("GET")
+            .parse()
+            .context("Invalid method in HAR entry")?;
+        let url = request["url"]
+            .as_str()
+            .context("HAR entry is missing request.url")?
+            .to_string();
+        let request_body = request["postData"]["text"].as_str().map(str::to_string);
+
+        let content = &response["content"];
+        let text = content["text"].as_str().unwrap_or_default();
+        let body = if content["encoding"].as_str() == Some("base64") {
+            use base64::Engine as _;
+            base64::engine::general_purpose::STANDARD
+                .decode(text)
+                .context("Invalid base64 response body in HAR entry")?
+        } else {
+            text.as_bytes().to_vec()
+        };
+
+        Ok(Self {
+            method,
+            url: url.clone(),
+            request_body,
+            response: HttpResponse {
+                url,
+                status: response["status"].as_u64().unwrap_or(200) as u16,
+                status_text: response["statusText"].as_str().unwrap_or_default().to_string(),
+                headers: har_headers(&response["headers"]),
+                body,
+            },
+        })
+    }
+
+    fn matches(&self, request: &HttpRequest) -> bool {
+        self.method == request.method
+            && text_matches(&self.url, &request.url)
+            && bodies_match(self.request_body.as_deref(), request)
+    }
+}
+
+fn har_headers(headers: &JsonValue) -> Vec<(String, String)> {
+    headers
+        .as_array()
+        .map(|headers| {
+            headers
+                .iter()
+                .filter_map(|header| {
+                    Some((
+                        header["name"].as_str()?.to_string(),
+                        header["value"].as_str()?.to_string(),
+                    ))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Compare request bodies, treating JSON documents as equal regardless of formatting
+fn bodies_match(recorded: Option<&str>, request: &HttpRequest) -> bool {
+    let actual = request.body.as_deref().map(String::from_utf8_lossy);
+    match (recorded, actual.as_deref()) {
+        (None, None) => true,
+        (None, Some(actual)) | (Some(actual), None) => actual.is_empty(),
+        (Some(recorded), Some(actual)) => {
+            if text_matches(recorded, actual) {
+                return true;
+            }
+            match (
+                serde_json::from_str::<JsonValue>(recorded),
+                serde_json::from_str::<JsonValue>(actual),
+            ) {
+                (Ok(recorded), Ok(actual)) => json_matches(&recorded, &actual),
+                _ => false,
+            }
+        }
+    }
+}
+
+/// Compare JSON documents, matching redacted strings as in [`text_matches`]
+fn json_matches(recorded: &JsonValue, actual: &JsonValue) -> bool {
+    match (recorded, actual) {
+        (JsonValue::String(recorded), JsonValue::String(actual)) => text_matches(recorded, actual),
+        (JsonValue::Array(recorded), JsonValue::Array(actual)) => {
+            recorded.len() == actual.len() && recorded.iter().zip(actual).all(|(r, a)| json_matches(r, a))
+        }
+        (JsonValue::Object(recorded), JsonValue::Object(actual)) => {
+            recorded.len() == actual.len()
+                && recorded
+                    .iter()
+                    .all(|(key, r)| actual.get(key).is_some_and(|a| json_matches(r, a)))
+        }
+        _ => recorded == actual,
+    }
+}
+
+/// Whether `actual` equals `recorded` with each [`REDACTED`] placeholder standing for any text
+fn text_matches(recorded: &str, actual: &str) -> bool {
+    let mut parts = recorded.split(REDACTED);
+    let Some(mut rest) = parts.next().and_then(|prefix| actual.strip_prefix(prefix)) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((suffix, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(suffix)
+}
+
+#[derive(Debug, Default)]
+struct ReplayState {
+    used: Vec<bool>,
+    unmatched: Vec<String>,
+}
+
+/// HTTP client that answers requests from a HAR recording
+///
+/// Each recorded exchange is served once, in recording order, so repeated
+/// requests to the same URL receive the responses they received originally.
+/// Requests without a recorded counterpart fail and are remembered so callers
+/// can report them even if the task swallowed the error.
+#[derive(Debug)]
+pub struct ReplayClient {
+    exchanges: Vec<RecordedExchange>,
+    state: Mutex<ReplayState>,
+}
+
+impl ReplayClient {
+    /// Create a replay client from already loaded exchanges
+    pub fn new(exchanges: Vec<RecordedExchange>) -> Self {
+        let state = ReplayState {
+            used: vec![false; exchanges.len()],
+            unmatched: Vec::new(),
+        };
+        Self {
+            exchanges,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Create a replay client from a parsed HAR document
+    pub fn from_har(har: &JsonValue) -> Result<Self> {
+        let entries = har["log"]["entries"]
+            .as_array()
+            .context("HAR document is missing log.entries")?;
+        let exchanges = entries
+            .iter()
+            .map(RecordedExchange::from_har_entry)
+            .collect::<Result<Vec<_>>>()?;
+        debug!("Loaded {} recorded HTTP exchanges", exchanges.len());
+        Ok(Self::new(exchanges))
+    }
+
+    /// Load a replay client from a HAR file
+    pub fn from_har_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).with_context(|| format!("Failed to read HAR file: {:?}", path))?;
+        let har: JsonValue =
+            serde_json::from_str(&content).with_context(|| format!("Invalid HAR file: {:?}", path))?;
+        Self::from_har(&har)
+    }
+
+    /// Requests that had no recorded counterpart, as `METHOD URL`
+    pub fn unmatched_requests(&self) -> Vec<String> {
+        self.state.lock().map(|state| state.unmatched.clone()).unwrap_or_default()
+    }
+
+    /// Recorded exchanges that were never requested during replay
+    pub fn unused_exchanges(&self) -> Vec<&RecordedExchange> {
+        let used = self.state.lock().map(|state| state.used.clone()).unwrap_or_default();
+        self.exchanges
+            .iter()
+            .zip(used)
+            .filter(|(_, used)| !used)
+            .map(|(exchange, _)| exchange)
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpClient for ReplayClient {
+    async fn call_http(
+        &self,
+        url: &str,
+        params: Option<&JsonValue>,
+        body: Option<&JsonValue>,
+    ) -> Result<JsonValue, HttpError> {
+        let response = self.send(crate::client::legacy_request(url, params, body)).await?;
+        Ok(crate::client::legacy_response(&response))
+    }
+
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, HttpError> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|e| HttpError::RecordingError(format!("Replay state mutex is poisoned: {}", e)))?;
+
+        let position = self
+            .exchanges
+            .iter()
+            .enumerate()
+            .position(|(index, exchange)| !state.used[index] && exchange.matches(&request));
+
+        match position {
+            Some(index) => {
+                state.used[index] = true;
+                debug!("Replaying recorded response for {} {}", request.method, request.url);
+                Ok(self.exchanges[index].response.clone())
+            }
+            None => {
+                let description = format!("{} {}", request.method, request.url);
+                warn!("No recorded response for {}", description);
+                state.unmatched.push(description.clone());
+                Err(HttpError::ReplayMismatch(description))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn har() -> JsonValue {
+        json!({
+            "log": {
+                "entries": [
+                    {
+                        "request": { "method": "GET", "url": "http://api/items" },
+                        "response": {
+                            "status": 200,
+                            "statusText": "OK",
+                            "headers": [{ "name": "content-type", "value": "application/json" }],
+                            "content": { "text": "[1]" }
+                        }
+                    },
+                    {
+                        "request": { "method": "GET", "url": "http://api/items" },
+                        "response": { "status": 200, "statusText": "OK", "headers": [], "content": { "text": "[1,2]" } }
+                    },
+                    {
+                        "request": {
+                            "method": "POST",
+                            "url": "http://api/items",
+                            "postData": { "text": "{\"name\":\"a\"}" }
+                        },
+                        "response": { "status": 201, "statusText": "Created", "headers": [], "content": { "text": "{}" } }
+                    }
+                ]
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_replays_in_recording_order() {
+        let client = ReplayClient::from_har(&har()).unwrap();
+
+        let first = client.send(HttpRequest::new(HttpMethod::Get, "http://api/items")).await.unwrap();
+        let second = client.send(HttpRequest::new(HttpMethod::Get, "http://api/items")).await.unwrap();
+
+        assert_eq!(first.text(), "[1]");
+        assert_eq!(first.header("Content-Type"), Some("application/json"));
+        assert_eq!(second.text(), "[1,2]");
+        assert!(client.send(HttpRequest::new(HttpMethod::Get, "http://api/items")).await.is_err());
+        assert_eq!(client.unmatched_requests(), vec!["GET http://api/items".to_string()]);
+        assert_eq!(client.unused_exchanges().len(), 1);
+    }
+
+    #[test]
+    fn test_redacted_text_matches_any_value() {
+        assert!(text_matches(
+            "http://api/items?key=[REDACTED]",
+            "http://api/items?key=s3cret"
+        ));
+        assert!(text_matches("Bearer [REDACTED] for [REDACTED]!", "Bearer abc for def!"));
+        assert!(!text_matches(
+            "http://api/items?key=[REDACTED]",
+            "http://api/users?key=s3cret"
+        ));
+        assert!(!text_matches("[REDACTED]-suffix", "value-other"));
+        assert!(!text_matches("http://api/items", "http://api/items?key=s3cret"));
+    }
+
+    #[tokio::test]
+    async fn test_replays_recordings_made_with_secrets() {
+        let session_dir = std::env::temp_dir().join(format!(
+            "ratchet-replay-{}-{}",
+            std::process::id(),
+            chrono::Utc::now().timestamp_micros()
+        ));
+        fs::create_dir_all(&session_dir).unwrap();
+        crate::recording::set_recording_dir(session_dir.clone()).unwrap();
+        crate::recording::add_recording_redactions(vec!["s3cret".to_string()]).unwrap();
+        crate::recording::record_http_request(
+            "http://api/items?key=s3cret",
+            "POST",
+            None,
+            Some(r#"{"token":"s3cret","name":"a"}"#),
+            201,
+            None,
+            "{}",
+            chrono::Utc::now(),
+            5,
+        )
+        .unwrap();
+        crate::recording::finalize_recording().unwrap();
+
+        let client = ReplayClient::from_har_file(&session_dir.join("requests.har")).unwrap();
+        fs::remove_dir_all(&session_dir).unwrap();
+        assert!(client.exchanges.iter().all(|exchange| !exchange.url.contains("s3cret")));
+
+        let request = HttpRequest::new(HttpMethod::Post, "http://api/items?key=s3cret")
+            .with_body(r#"{ "name": "a", "token": "s3cret" }"#);
+        assert_eq!(client.send(request).await.unwrap().status, 201);
+    }
+
+    #[tokio::test]
+    async fn test_matches_on_request_body() {
+        let client = ReplayClient::from_har(&har()).unwrap();
+
+        let mismatch = HttpRequest::new(HttpMethod::Post, "http://api/items").with_body(r#"{"name":"b"}"#);
+        assert!(matches!(client.send(mismatch).await, Err(HttpError::ReplayMismatch(_))));
+
+        let reformatted = HttpRequest::new(HttpMethod::Post, "http://api/items").with_body(r#"{ "name": "a" }"#);
+        assert_eq!(client.send(reformatted).await.unwrap().status, 201);
+    }
+}
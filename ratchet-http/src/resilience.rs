@@ -0,0 +1,222 @@
+//! Per-host circuit breakers and retries for task HTTP calls
+
+use crate::errors::HttpError;
+use crate::types::{HttpMethod, HttpResponse};
+use ratchet_config::domains::http::{HttpCircuitBreakerConfig, HttpRetryConfig};
+use ratchet_resilience::{BackoffStrategy, CircuitBreaker, CircuitBreakerConfig, CircuitState, RetryPolicy};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// State of the circuit breaker of one host
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostBreakerMetrics {
+    /// Host name, with the port when the URL names one
+    pub host: String,
+    pub state: CircuitState,
+    /// Requests that failed with a network error or a 5xx response
+    pub failures: u64,
+    /// Requests refused while the breaker was open
+    pub rejected: u64,
+}
+
+/// Circuit breakers keyed by host, created on first use
+pub(crate) struct HostBreakers {
+    config: CircuitBreakerConfig,
+    breakers: Mutex<HashMap<String, CircuitBreaker>>,
+}
+
+impl std::fmt::Debug for HostBreakers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HostBreakers")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl HostBreakers {
+    pub(crate) fn new(config: &HttpCircuitBreakerConfig) -> Self {
+        Self {
+            config: CircuitBreakerConfig {
+                failure_threshold: config.failure_threshold,
+                success_threshold: config.success_threshold,
+                timeout: config.open_duration,
+                window: config.window,
+                min_requests: config.min_requests,
+            },
+            breakers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Breaker of `host`; clones share their state
+    pub(crate) fn breaker(&self, host: &str) -> CircuitBreaker {
+        let mut breakers = self.breakers.lock().unwrap_or_else(|e| e.into_inner());
+        breakers
+            .entry(host.to_string())
+            .or_insert_with(|| CircuitBreaker::new(self.config.clone()))
+            .clone()
+    }
+
+    pub(crate) fn metrics(&self) -> Vec<HostBreakerMetrics> {
+        let breakers = self.breakers.lock().unwrap_or_else(|e| e.into_inner());
+        let mut metrics: Vec<HostBreakerMetrics> = breakers
+            .iter()
+            .map(|(host, breaker)| {
+                let counters = breaker.metrics();
+                HostBreakerMetrics {
+                    host: host.clone(),
+                    state: breaker.state(),
+                    failures: counters.total_failures,
+                    rejected: counters.total_rejected,
+                }
+            })
+            .collect();
+        metrics.sort_by(|a, b| a.host.cmp(&b.host));
+        metrics
+    }
+}
+
+/// Host a breaker is kept for, or `None` for URLs without one
+pub(crate) fn breaker_host(url: &str) -> Option<String> {
+    let url = url::Url::parse(url).ok()?;
+    let host = url.host_str()?;
+    Some(match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    })
+}
+
+/// Whether the outcome of a request counts against its host's breaker
+pub(crate) fn is_host_failure(outcome: &Result<HttpResponse, HttpError>) -> bool {
+    match outcome {
+        Ok(response) => response.status >= 500,
+        Err(error) => is_transient(error),
+    }
+}
+
+/// Whether the outcome of an attempt is worth retrying under `config`
+pub(crate) fn should_retry(outcome: &Result<HttpResponse, HttpError>, config: &HttpRetryConfig) -> bool {
+    match outcome {
+        Ok(response) => config.retry_on_status.contains(&response.status),
+        Err(error) => is_transient(error),
+    }
+}
+
+/// Network errors other than malformed requests
+fn is_transient(error: &HttpError) -> bool {
+    matches!(error, HttpError::NetworkError(e) if !e.is_builder())
+}
+
+/// Methods that can be sent again without changing their effect
+pub(crate) fn is_idempotent(method: HttpMethod) -> bool {
+    !matches!(method, HttpMethod::Post | HttpMethod::Patch)
+}
+
+/// Backoff policy described by `config`
+pub(crate) fn retry_policy(config: &HttpRetryConfig) -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: config.max_attempts,
+        initial_delay: Duration::from_millis(config.initial_delay_ms),
+        max_delay: Duration::from_millis(config.max_delay_ms),
+        backoff_strategy: BackoffStrategy::Exponential {
+            base: config.backoff_multiplier,
+        },
+        jitter: config.jitter,
+    }
+}
+
+/// Delay before retrying after `outcome`, honouring `Retry-After` given in seconds
+pub(crate) fn retry_delay(outcome: &Result<HttpResponse, HttpError>, policy: &RetryPolicy, attempt: u32) -> Duration {
+    outcome
+        .as_ref()
+        .ok()
+        .and_then(|response| response.header("retry-after"))
+        .and_then(|seconds| seconds.trim().parse::<u64>().ok())
+        .map(|seconds| Duration::from_secs(seconds).min(policy.max_delay))
+        .unwrap_or_else(|| policy.delay_for_attempt(attempt))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(status: u16, headers: &[(&str, &str)]) -> Result<HttpResponse, HttpError> {
+        Ok(HttpResponse {
+            status,
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_retry_decisions() {
+        let config = HttpRetryConfig {
+            max_attempts: 3,
+            jitter: false,
+            ..Default::default()
+        };
+        assert!(should_retry(&status(503, &[]), &config));
+        assert!(!should_retry(&status(500, &[]), &config));
+        assert!(!should_retry(&status(404, &[]), &config));
+        assert!(!should_retry(
+            &Err(HttpError::EgressDenied {
+                url: "http://10.0.0.1/".to_string(),
+                reason: "private range".to_string(),
+            }),
+            &config
+        ));
+
+        assert!(is_host_failure(&status(500, &[])));
+        assert!(!is_host_failure(&status(429, &[])));
+
+        assert!(is_idempotent(HttpMethod::Get));
+        assert!(is_idempotent(HttpMethod::Put));
+        assert!(!is_idempotent(HttpMethod::Post));
+
+        let policy = retry_policy(&config);
+        assert_eq!(retry_delay(&status(503, &[]), &policy, 1), Duration::from_millis(200));
+        assert_eq!(retry_delay(&status(503, &[]), &policy, 2), Duration::from_millis(400));
+        assert_eq!(
+            retry_delay(&status(429, &[("Retry-After", "3")]), &policy, 1),
+            Duration::from_secs(3)
+        );
+        assert_eq!(
+            retry_delay(&status(429, &[("Retry-After", "3600")]), &policy, 1),
+            Duration::from_secs(10)
+        );
+    }
+
+    #[test]
+    fn test_breakers_are_kept_per_host() {
+        let breakers = HostBreakers::new(&HttpCircuitBreakerConfig {
+            enabled: true,
+            failure_threshold: 2,
+            min_requests: 2,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            breaker_host("https://api.example.com/a").as_deref(),
+            Some("api.example.com")
+        );
+        assert_eq!(
+            breaker_host("http://localhost:8080/").as_deref(),
+            Some("localhost:8080")
+        );
+
+        breakers.breaker("api.example.com").record_failure();
+        breakers.breaker("api.example.com").record_failure();
+        breakers.breaker("other.example.com").record_success();
+
+        let metrics = breakers.metrics();
+        assert_eq!(metrics.len(), 2);
+        assert_eq!(metrics[0].host, "api.example.com");
+        assert_eq!(metrics[0].state, CircuitState::Open);
+        assert_eq!(metrics[0].failures, 2);
+        assert_eq!(metrics[1].state, CircuitState::Closed);
+    }
+}
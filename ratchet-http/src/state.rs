@@ -0,0 +1,97 @@
+//! State shared by the HTTP managers of many task executions
+
+use crate::cache::{HttpCacheMetrics, ResponseCache};
+use crate::resilience::{HostBreakerMetrics, HostBreakers};
+use crate::transport::HttpTransport;
+use ratchet_config::domains::http::HttpClientPolicy;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Snapshot of the response cache, circuit breakers and retries of an [`HttpClientState`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpClientMetrics {
+    pub cache: HttpCacheMetrics,
+    pub circuit_breakers: Vec<HostBreakerMetrics>,
+    /// Requests sent again after a transient failure
+    pub retries: u64,
+}
+
+/// Response cache and per-host circuit breakers shared between HTTP managers
+///
+/// Create one per process and hand it to every [`HttpManager`](crate::HttpManager)
+/// through [`with_client_state`](crate::HttpManager::with_client_state), so
+/// executions reuse each other's responses and see the same breakers. Cache
+/// capacity, retention and breaker thresholds come from the policy given here;
+/// a manager may still turn caching and breaking on or off for its own requests.
+/// Managers also adopt the state's [`HttpTransport`], if it has one.
+#[derive(Debug, Clone)]
+pub struct HttpClientState {
+    inner: Arc<SharedState>,
+    transport: Option<HttpTransport>,
+}
+
+#[derive(Debug)]
+struct SharedState {
+    policy: HttpClientPolicy,
+    cache: ResponseCache,
+    breakers: HostBreakers,
+    retries: AtomicU64,
+}
+
+impl Default for HttpClientState {
+    fn default() -> Self {
+        Self::new(HttpClientPolicy::default())
+    }
+}
+
+impl HttpClientState {
+    pub fn new(policy: HttpClientPolicy) -> Self {
+        Self {
+            inner: Arc::new(SharedState {
+                cache: ResponseCache::new(&policy.cache),
+                breakers: HostBreakers::new(&policy.circuit_breaker),
+                retries: AtomicU64::new(0),
+                policy,
+            }),
+            transport: None,
+        }
+    }
+
+    /// Send requests of managers sharing this state through `transport`
+    pub fn with_transport(mut self, transport: HttpTransport) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Policy managers sharing this state start from
+    pub fn policy(&self) -> &HttpClientPolicy {
+        &self.inner.policy
+    }
+
+    /// Transport managers sharing this state send requests through
+    pub fn transport(&self) -> Option<&HttpTransport> {
+        self.transport.as_ref()
+    }
+
+    /// Current cache, breaker and retry counters
+    pub async fn metrics(&self) -> HttpClientMetrics {
+        HttpClientMetrics {
+            cache: self.inner.cache.metrics().await,
+            circuit_breakers: self.inner.breakers.metrics(),
+            retries: self.inner.retries.load(Ordering::Relaxed),
+        }
+    }
+
+    pub(crate) fn cache(&self) -> &ResponseCache {
+        &self.inner.cache
+    }
+
+    pub(crate) fn breakers(&self) -> &HostBreakers {
+        &self.inner.breakers
+    }
+
+    pub(crate) fn record_retry(&self) {
+        self.inner.retries.fetch_add(1, Ordering::Relaxed);
+    }
+}
@@ -85,6 +85,93 @@ impl From<HttpMethod> for reqwest::Method {
     }
 }
 
+/// A raw HTTP request with an untyped byte body
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpRequest {
+    /// Request method
+    pub method: HttpMethod,
+
+    /// Absolute request URL
+    pub url: String,
+
+    /// Request headers in the order they were supplied
+    pub headers: Vec<(String, String)>,
+
+    /// Request body
+    pub body: Option<Vec<u8>>,
+}
+
+impl HttpRequest {
+    /// Create a request without headers or body
+    pub fn new(method: HttpMethod, url: impl Into<String>) -> Self {
+        Self {
+            method,
+            url: url.into(),
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    /// Add a header to the request
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Set the request body
+    pub fn with_body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Look up a header value, ignoring case
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A raw HTTP response with an untyped byte body
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpResponse {
+    /// Final URL after redirects
+    pub url: String,
+
+    /// Status code
+    pub status: u16,
+
+    /// Canonical reason phrase for the status code
+    pub status_text: String,
+
+    /// Response headers in the order they were received
+    pub headers: Vec<(String, String)>,
+
+    /// Response body
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Whether the status code is in the 2xx range
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Look up a header value, ignoring case
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Response body decoded as UTF-8, replacing invalid sequences
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
 /// Errors that can occur when parsing HTTP methods
 #[derive(Error, Debug, Clone)]
 pub enum HttpMethodError {
@@ -7,8 +7,8 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use ratchet_api_types::{
-    ApiId, ExecutionStatus, JobPriority, JobStatus, ListResponse, PaginationInput, UnifiedApiKey, UnifiedExecution,
-    UnifiedJob, UnifiedSchedule, UnifiedSession, UnifiedTask, UnifiedUser,
+    ApiId, ExecutionLogLevel, ExecutionStatus, JobPriority, JobStatus, ListResponse, PaginationInput, UnifiedApiKey,
+    UnifiedExecution, UnifiedExecutionLog, UnifiedJob, UnifiedSchedule, UnifiedSession, UnifiedTask, UnifiedUser,
 };
 // ApiResult not needed in trait definitions - using DatabaseError instead
 use serde::{Deserialize, Serialize};
@@ -226,6 +226,39 @@ pub trait ExecutionRepository: FilteredRepository<UnifiedExecution, ExecutionFil
 
     /// Update execution progress
     async fn update_progress(&self, id: ApiId, progress: f32) -> Result<(), DatabaseError>;
+
+    /// Record progress reported by a running task, including its message and partial output
+    ///
+    /// Repositories that cannot store messages or partial output only keep the fraction.
+    async fn record_progress(
+        &self,
+        id: ApiId,
+        fraction: Option<f32>,
+        _message: Option<String>,
+        _partial_output: Option<serde_json::Value>,
+    ) -> Result<(), DatabaseError> {
+        match fraction {
+            Some(fraction) => self.update_progress(id, fraction).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Append console output captured from a task to the execution
+    ///
+    /// Repositories keep a bounded number of entries; the default implementation discards them.
+    async fn append_logs(&self, _id: ApiId, _logs: Vec<UnifiedExecutionLog>) -> Result<(), DatabaseError> {
+        Ok(())
+    }
+
+    /// Stored console output at or above `min_level`: the latest `limit` entries, oldest first
+    async fn find_logs(
+        &self,
+        _id: ApiId,
+        _min_level: Option<ExecutionLogLevel>,
+        _limit: Option<usize>,
+    ) -> Result<Vec<UnifiedExecutionLog>, DatabaseError> {
+        Ok(Vec::new())
+    }
 }
 
 // =============================================================================
@@ -277,9 +310,43 @@ pub struct JobFilters {
 /// Job repository interface
 #[async_trait]
 pub trait JobRepository: FilteredRepository<UnifiedJob, JobFilters> {
+    /// Queue a job, or return the earlier job it repeats
+    ///
+    /// A job repeats another when it reuses a recent idempotency key, or when
+    /// its task deduplicates identical input. The default implementation always
+    /// creates the job.
+    async fn create_or_replay(&self, job: UnifiedJob) -> Result<JobCreation, DatabaseError> {
+        self.create(job).await.map(JobCreation::Created)
+    }
+
     /// Find jobs ready for processing (sorted by priority and queue time)
     async fn find_ready_for_processing(&self, limit: u64) -> Result<Vec<UnifiedJob>, DatabaseError>;
 
+    /// Claim up to `limit` ready jobs for the server `owner` for the next `lease`
+    ///
+    /// A claimed job is processing and no other server can claim it while its
+    /// lease is renewed. The default implementation, for stores used by a single
+    /// server, finds the ready jobs without claiming them.
+    async fn claim_ready_for_processing(
+        &self,
+        _owner: &str,
+        _lease: std::time::Duration,
+        limit: u64,
+    ) -> Result<Vec<UnifiedJob>, DatabaseError> {
+        self.find_ready_for_processing(limit).await
+    }
+
+    /// Extend the leases of the jobs `owner` is processing; returns how many were renewed
+    async fn renew_leases(&self, _owner: &str, _lease: std::time::Duration) -> Result<u64, DatabaseError> {
+        Ok(0)
+    }
+
+    /// Return jobs whose owner let their lease expire to the queue, or fail them
+    /// when they are out of retries
+    async fn reclaim_expired_leases(&self) -> Result<Vec<UnifiedJob>, DatabaseError> {
+        Ok(Vec::new())
+    }
+
     /// Find jobs by status
     async fn find_by_status(&self, status: JobStatus) -> Result<Vec<UnifiedJob>, DatabaseError>;
 
@@ -302,6 +369,38 @@ pub trait JobRepository: FilteredRepository<UnifiedJob, JobFilters> {
 
     /// Cancel job
     async fn cancel(&self, id: ApiId) -> Result<(), DatabaseError>;
+
+    /// Find the job a schedule queued last
+    ///
+    /// The default implementation knows of no scheduled jobs.
+    async fn find_latest_for_schedule(&self, _schedule_id: ApiId) -> Result<Option<UnifiedJob>, DatabaseError> {
+        Ok(None)
+    }
+
+    /// Outputs of the finished parents that pass their output on to a job, in declaration order
+    ///
+    /// The default implementation knows of no dependencies.
+    async fn find_dependency_outputs(&self, _id: ApiId) -> Result<Vec<serde_json::Value>, DatabaseError> {
+        Ok(Vec::new())
+    }
+}
+
+/// Outcome of queueing a job
+#[derive(Debug, Clone)]
+pub enum JobCreation {
+    /// A new job was queued
+    Created(UnifiedJob),
+    /// The job repeats an earlier one, which is returned instead
+    Replayed(UnifiedJob),
+}
+
+impl JobCreation {
+    /// The created or replayed job
+    pub fn into_job(self) -> UnifiedJob {
+        match self {
+            JobCreation::Created(job) | JobCreation::Replayed(job) => job,
+        }
+    }
 }
 
 // =============================================================================
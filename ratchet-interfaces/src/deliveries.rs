@@ -0,0 +1,36 @@
+//! Output delivery ledger interfaces
+//!
+//! Every attempt to deliver the output of a job to one of its destinations is
+//! recorded. Failed deliveries are retried in the background until the retry
+//! policy gives up on them, after which they wait in the dead-letter queue for
+//! a manual redelivery.
+
+use async_trait::async_trait;
+use ratchet_api_types::{ApiId, OutputDeliveryStatus, UnifiedDeliveryAttempt};
+
+/// Delivery ledger error types
+#[derive(Debug, thiserror::Error)]
+pub enum DeliveryLedgerError {
+    #[error("Delivery not found: {id}")]
+    NotFound { id: String },
+
+    #[error("Delivery {id} cannot be redelivered while {status:?}")]
+    NotRedeliverable { id: String, status: OutputDeliveryStatus },
+
+    #[error("Storage error: {message}")]
+    Storage { message: String },
+}
+
+/// Inspection and redelivery of failed output deliveries
+#[async_trait]
+pub trait OutputDeliveryStore: Send + Sync {
+    /// Deliveries waiting for a retry, being redelivered or dead-lettered, newest first
+    async fn failed_deliveries(
+        &self,
+        job_id: Option<ApiId>,
+        execution_id: Option<ApiId>,
+    ) -> Result<Vec<UnifiedDeliveryAttempt>, DeliveryLedgerError>;
+
+    /// Deliver the output of a failed delivery again now, returning the new attempt
+    async fn redeliver(&self, delivery_id: ApiId) -> Result<UnifiedDeliveryAttempt, DeliveryLedgerError>;
+}
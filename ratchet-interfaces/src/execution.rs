@@ -4,6 +4,7 @@
 //! execution engines to be used interchangeably.
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
 use std::time::Duration;
@@ -47,6 +48,55 @@ pub trait TaskExecutor: Send + Sync {
     }
 }
 
+/// Cancellation of running executions
+///
+/// Implemented by executors that can stop a task while it runs. Cancellation is
+/// a request: the executor stops the task at its next safe point, or forcibly
+/// after a grace period, and reports the execution as cancelled.
+pub trait ExecutionCanceller: Send + Sync {
+    /// Request cancellation of a running execution
+    ///
+    /// Returns `true` if the execution was running and has been asked to stop.
+    fn cancel_execution(&self, execution_id: &str) -> bool;
+}
+
+/// Progress reported by a running execution
+#[derive(Debug, Clone)]
+pub struct ExecutionProgress {
+    /// Execution UUID as string
+    pub execution_id: String,
+    /// Task that is running, as passed to the executor
+    pub task_id: String,
+    /// Completed fraction between 0.0 and 1.0
+    pub fraction: Option<f32>,
+    /// Description of the current step
+    pub message: Option<String>,
+    /// Partial result emitted by the task
+    pub partial_output: Option<JsonValue>,
+    /// When the progress was received
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Consumer of progress reported by running executions
+///
+/// Implementations typically persist the progress and notify subscribers. They
+/// are called in the order updates were reported and should not block for long.
+#[async_trait]
+pub trait ExecutionProgressSink: Send + Sync {
+    /// Record a progress update
+    async fn record_progress(&self, progress: ExecutionProgress);
+}
+
+/// Receiver of console output captured from finished executions
+///
+/// Implementations are responsible for redacting sensitive values before the
+/// output is stored or shown.
+#[async_trait]
+pub trait ExecutionLogSink: Send + Sync {
+    /// Record the console output of an execution
+    async fn record_logs(&self, execution_id: &str, logs: Vec<ratchet_api_types::UnifiedExecutionLog>);
+}
+
 /// Execution context for task runs
 ///
 /// Provides additional configuration and metadata for task execution.
@@ -19,25 +19,38 @@
 //! - [`StructuredLogger`] - Logging interface for structured events
 
 pub mod database;
+pub mod deliveries;
 pub mod execution;
 pub mod logging;
 pub mod registry;
+pub mod result_cache;
 pub mod scheduler;
+pub mod secrets;
 pub mod service;
 pub mod tasks;
+pub mod triggers;
+pub mod workflows;
 
 // Re-export commonly used types
 pub use database::{
     ApiKeyRepository, CrudRepository, DatabaseError, ExecutionFilters, ExecutionRepository, FilteredRepository,
-    JobFilters, JobRepository, Repository, RepositoryFactory, ScheduleFilters, ScheduleRepository, SessionRepository,
+    JobCreation, JobFilters, JobRepository, Repository, RepositoryFactory, ScheduleFilters, ScheduleRepository, SessionRepository,
     TaskFilters, TaskRepository, TransactionContext, TransactionManager, UserFilters, UserRepository,
 };
-pub use execution::{ExecutionContext, ExecutionResult, TaskExecutor};
+pub use deliveries::{DeliveryLedgerError, OutputDeliveryStore};
+pub use execution::{
+    ExecutionCanceller, ExecutionContext, ExecutionLogSink, ExecutionProgress, ExecutionProgressSink, ExecutionResult,
+    TaskExecutor,
+};
 pub use logging::{LogEvent, LogLevel, StructuredLogger};
 pub use registry::{
     FilesystemRegistry, HttpCredentials, HttpRegistry, RegistryError, RegistryManager, SyncResult, TaskMetadata,
     TaskRegistry, TaskValidator, ValidationResult,
 };
+pub use result_cache::{ResultCacheError, TaskResultCache};
 pub use scheduler::{ScheduleStatus, SchedulerError, SchedulerService};
+pub use secrets::{declared_secrets, SecretError, SecretKey, SecretStore};
 pub use service::{HealthStatus, Service, ServiceHealth, ServiceMetrics};
 pub use tasks::{TaskMetadata as TaskServiceMetadata, TaskService, TaskServiceError, TaskServiceFilters, TaskSource, TaskSourceType};
+pub use triggers::{TriggerError, TriggerStore};
+pub use workflows::{WorkflowError, WorkflowStore};
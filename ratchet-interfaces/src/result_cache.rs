@@ -0,0 +1,28 @@
+//! Task result cache interfaces
+//!
+//! Tasks opt into result memoization through the `cache` key of their metadata.
+//! While a cached result is fresh, the job processor serves it instead of running
+//! the task again and records the execution as a cache hit.
+
+use async_trait::async_trait;
+use ratchet_api_types::{ApiId, UnifiedCachedResult};
+
+/// Result cache error types
+#[derive(Debug, thiserror::Error)]
+pub enum ResultCacheError {
+    #[error("Task not found: {id}")]
+    TaskNotFound { id: String },
+
+    #[error("Storage error: {message}")]
+    Storage { message: String },
+}
+
+/// Inspection and purging of the results cached for each task
+#[async_trait]
+pub trait TaskResultCache: Send + Sync {
+    /// Results currently cached for a task, oldest first
+    async fn entries(&self, task_id: ApiId) -> Result<Vec<UnifiedCachedResult>, ResultCacheError>;
+
+    /// Drop the cached results of a task, returning how many were dropped
+    async fn purge(&self, task_id: ApiId) -> Result<usize, ResultCacheError>;
+}
@@ -0,0 +1,118 @@
+//! Task secret interfaces
+//!
+//! Secrets are named values, such as API keys, that tasks declare in the
+//! `secrets` list of their metadata and read at runtime through
+//! `ratchet.secrets.get(name)`. They are stored encrypted and scoped globally,
+//! to a repository or to a single task; the most specific scope wins when the
+//! same name exists more than once.
+
+use async_trait::async_trait;
+use ratchet_api_types::{SecretScope, UnifiedSecret};
+use std::collections::HashMap;
+
+/// Secret store error types
+#[derive(Debug, thiserror::Error)]
+pub enum SecretError {
+    #[error("Secret not found: {name}")]
+    NotFound { name: String },
+
+    #[error("Invalid secret: {message}")]
+    Invalid { message: String },
+
+    #[error("Encryption error: {message}")]
+    Encryption { message: String },
+
+    #[error("Storage error: {message}")]
+    Storage { message: String },
+}
+
+/// Where a secret lives
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretKey {
+    pub name: String,
+    pub scope: SecretScope,
+    /// Repository ID for repository secrets, task UUID for task secrets
+    pub scope_id: Option<String>,
+}
+
+impl SecretKey {
+    /// Key of a global secret
+    pub fn global(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            scope: SecretScope::Global,
+            scope_id: None,
+        }
+    }
+
+    /// Key of a secret in `scope`, bound to `scope_id` unless the scope is global
+    pub fn new(name: impl Into<String>, scope: SecretScope, scope_id: Option<String>) -> Self {
+        Self {
+            name: name.into(),
+            scope,
+            scope_id: if scope == SecretScope::Global { None } else { scope_id },
+        }
+    }
+}
+
+/// Encrypted storage of task secrets
+#[async_trait]
+pub trait SecretStore: Send + Sync {
+    /// Create or replace a secret
+    async fn set_secret(
+        &self,
+        key: SecretKey,
+        value: &str,
+        description: Option<String>,
+    ) -> Result<UnifiedSecret, SecretError>;
+
+    /// List secret metadata, optionally restricted to one scope
+    async fn list_secrets(&self, scope: Option<SecretScope>) -> Result<Vec<UnifiedSecret>, SecretError>;
+
+    /// Delete a secret, returning whether it existed
+    async fn delete_secret(&self, key: SecretKey) -> Result<bool, SecretError>;
+
+    /// Decrypt the secrets named in `names` that are visible to a task
+    ///
+    /// Fails with [`SecretError::NotFound`] if any of the names is not visible.
+    async fn resolve_secrets(
+        &self,
+        names: &[String],
+        task_uuid: Option<&str>,
+        repository_id: Option<&str>,
+    ) -> Result<HashMap<String, String>, SecretError>;
+}
+
+/// Names of the secrets a task declares in the `secrets` list of its metadata
+pub fn declared_secrets(metadata: &serde_json::Value) -> Vec<String> {
+    metadata
+        .get("secrets")
+        .and_then(|secrets| secrets.as_array())
+        .map(|secrets| {
+            secrets
+                .iter()
+                .filter_map(|name| name.as_str())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_declared_secrets() {
+        let metadata = json!({"name": "weather", "secrets": ["API_KEY", 42, "TOKEN"]});
+        assert_eq!(declared_secrets(&metadata), vec!["API_KEY".to_string(), "TOKEN".to_string()]);
+        assert!(declared_secrets(&json!({"name": "weather"})).is_empty());
+    }
+
+    #[test]
+    fn test_global_key_drops_scope_id() {
+        let key = SecretKey::new("API_KEY", SecretScope::Global, Some("1".to_string()));
+        assert_eq!(key, SecretKey::global("API_KEY"));
+    }
+}
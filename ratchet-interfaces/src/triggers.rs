@@ -0,0 +1,57 @@
+//! Webhook trigger interfaces
+//!
+//! A webhook trigger is a named endpoint, `POST /triggers/{name}`, that lets an
+//! external system queue a job of a task. Deliveries must carry an HMAC
+//! signature made with a secret from the [`SecretStore`](crate::SecretStore);
+//! their payload is mapped to the job input and may be filtered by a JSON
+//! schema, and repeated delivery IDs are ignored.
+
+use async_trait::async_trait;
+use ratchet_api_types::{ApiId, UnifiedTriggerDelivery, UnifiedWebhookTrigger};
+use std::collections::HashMap;
+
+/// Trigger store error types
+#[derive(Debug, thiserror::Error)]
+pub enum TriggerError {
+    #[error("Webhook trigger not found: {name}")]
+    NotFound { name: String },
+
+    #[error("Delivery rejected: {message}")]
+    Unauthorized { message: String },
+
+    #[error("Invalid webhook trigger: {message}")]
+    Invalid { message: String },
+
+    #[error("Storage error: {message}")]
+    Storage { message: String },
+}
+
+/// Storage of webhook triggers and handling of their deliveries
+#[async_trait]
+pub trait TriggerStore: Send + Sync {
+    /// Validate and store a new trigger
+    async fn create_trigger(&self, trigger: UnifiedWebhookTrigger) -> Result<UnifiedWebhookTrigger, TriggerError>;
+
+    /// Validate and replace the settings of an existing trigger
+    async fn update_trigger(&self, trigger: UnifiedWebhookTrigger) -> Result<UnifiedWebhookTrigger, TriggerError>;
+
+    /// Find a trigger by ID
+    async fn find_trigger(&self, id: ApiId) -> Result<Option<UnifiedWebhookTrigger>, TriggerError>;
+
+    /// List all triggers
+    async fn list_triggers(&self) -> Result<Vec<UnifiedWebhookTrigger>, TriggerError>;
+
+    /// Delete a trigger, returning whether it existed
+    async fn delete_trigger(&self, id: ApiId) -> Result<bool, TriggerError>;
+
+    /// Verify a delivery to the trigger called `name` and queue a job for it
+    ///
+    /// Header names are matched case-insensitively. Fails with
+    /// [`TriggerError::Unauthorized`] when the signature does not verify.
+    async fn deliver(
+        &self,
+        name: &str,
+        headers: &HashMap<String, String>,
+        body: &[u8],
+    ) -> Result<UnifiedTriggerDelivery, TriggerError>;
+}
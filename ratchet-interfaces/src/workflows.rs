@@ -0,0 +1,69 @@
+//! Workflow interfaces
+//!
+//! A workflow is a DAG of steps, each running a registered task once the steps
+//! it depends on have completed. Runs are queued through a [`WorkflowStore`]
+//! and picked up by the job processor, which records the state of every step
+//! on the run as it goes.
+
+use async_trait::async_trait;
+use ratchet_api_types::{ApiId, UnifiedWorkflow, UnifiedWorkflowRun};
+
+/// Workflow store error types
+#[derive(Debug, thiserror::Error)]
+pub enum WorkflowError {
+    #[error("Workflow not found: {id}")]
+    NotFound { id: String },
+
+    #[error("Workflow run not found: {id}")]
+    RunNotFound { id: String },
+
+    #[error("Invalid workflow: {message}")]
+    Invalid { message: String },
+
+    #[error("Storage error: {message}")]
+    Storage { message: String },
+}
+
+/// Storage of workflow definitions and their runs
+#[async_trait]
+pub trait WorkflowStore: Send + Sync {
+    /// Validate and store a new workflow
+    async fn create_workflow(&self, workflow: UnifiedWorkflow) -> Result<UnifiedWorkflow, WorkflowError>;
+
+    /// Validate and replace the definition of an existing workflow
+    async fn update_workflow(&self, workflow: UnifiedWorkflow) -> Result<UnifiedWorkflow, WorkflowError>;
+
+    /// Find a workflow by ID
+    async fn find_workflow(&self, id: ApiId) -> Result<Option<UnifiedWorkflow>, WorkflowError>;
+
+    /// List all workflows
+    async fn list_workflows(&self) -> Result<Vec<UnifiedWorkflow>, WorkflowError>;
+
+    /// Delete a workflow and its runs, returning whether it existed
+    async fn delete_workflow(&self, id: ApiId) -> Result<bool, WorkflowError>;
+
+    /// Queue a run of an enabled workflow
+    async fn queue_run(
+        &self,
+        workflow_id: ApiId,
+        input: serde_json::Value,
+        schedule_id: Option<ApiId>,
+    ) -> Result<UnifiedWorkflowRun, WorkflowError>;
+
+    /// Find a run by ID
+    async fn find_run(&self, id: ApiId) -> Result<Option<UnifiedWorkflowRun>, WorkflowError>;
+
+    /// Find the run a schedule queued last
+    async fn find_latest_scheduled_run(&self, schedule_id: ApiId) -> Result<Option<UnifiedWorkflowRun>, WorkflowError>;
+
+    /// List the latest runs, optionally of one workflow, newest first
+    async fn list_runs(&self, workflow_id: Option<ApiId>, limit: u64) -> Result<Vec<UnifiedWorkflowRun>, WorkflowError>;
+
+    /// Mark up to `limit` queued runs as running and return them
+    ///
+    /// Each run is handed to exactly one caller.
+    async fn claim_queued_runs(&self, limit: u64) -> Result<Vec<UnifiedWorkflowRun>, WorkflowError>;
+
+    /// Store the progress of a run
+    async fn save_run(&self, run: UnifiedWorkflowRun) -> Result<UnifiedWorkflowRun, WorkflowError>;
+}
@@ -3,6 +3,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
+use std::collections::HashMap;
 use std::fmt;
 use uuid::Uuid;
 
@@ -10,12 +11,24 @@ use uuid::Uuid;
 pub const IPC_PROTOCOL_VERSION: u32 = 1;
 
 /// Execution context passed to JavaScript tasks
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ExecutionContext {
     pub execution_id: String,   // Execution UUID as string
     pub job_id: Option<String>, // Job UUID as string (optional for direct executions)
     pub task_id: String,        // Task UUID as string
     pub task_version: String,   // Task version
+    /// Decrypted secrets the task declared, by name
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub secrets: HashMap<String, String>,
+    /// Egress policy the task declares in its metadata, as written there
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub egress_policy: Option<JsonValue>,
+    /// HTTP caching, retry and circuit breaker settings the task declares in its metadata
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_policy: Option<JsonValue>,
+    /// Resource limits the task declares in its metadata, overriding the worker's limits
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<JsonValue>,
 }
 
 impl ExecutionContext {
@@ -26,8 +39,52 @@ impl ExecutionContext {
             job_id: job_uuid.map(|uuid| uuid.to_string()),
             task_id: task_uuid.to_string(),
             task_version,
+            secrets: HashMap::new(),
+            egress_policy: None,
+            http_policy: None,
+            limits: None,
         }
     }
+
+    /// Hand `secrets` to the task
+    pub fn with_secrets(mut self, secrets: HashMap<String, String>) -> Self {
+        self.secrets = secrets;
+        self
+    }
+
+    /// Restrict the task's `fetch` calls with the egress policy it declares
+    pub fn with_egress_policy(mut self, policy: Option<JsonValue>) -> Self {
+        self.egress_policy = policy;
+        self
+    }
+
+    /// Override the HTTP client policy for the task's `fetch` calls with the settings it declares
+    pub fn with_http_policy(mut self, policy: Option<JsonValue>) -> Self {
+        self.http_policy = policy;
+        self
+    }
+
+    /// Override the worker's resource limits with the limits the task declares
+    pub fn with_limits(mut self, limits: Option<JsonValue>) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+// Secret values must not end up in logs, so only their names are printed
+impl fmt::Debug for ExecutionContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExecutionContext")
+            .field("execution_id", &self.execution_id)
+            .field("job_id", &self.job_id)
+            .field("task_id", &self.task_id)
+            .field("task_version", &self.task_version)
+            .field("secrets", &self.secrets.keys().collect::<Vec<_>>())
+            .field("egress_policy", &self.egress_policy)
+            .field("http_policy", &self.http_policy)
+            .field("limits", &self.limits)
+            .finish()
+    }
 }
 
 /// Messages sent from coordinator to worker processes
@@ -50,6 +107,12 @@ pub enum WorkerMessage {
     /// Health check ping
     Ping { correlation_id: Uuid },
 
+    /// Cancel a running task
+    ///
+    /// The worker stops the task at its next safe point and answers the original
+    /// `ExecuteTask` with a cancelled result.
+    CancelTask { correlation_id: Uuid },
+
     /// Shutdown signal
     Shutdown,
 }
@@ -65,6 +128,16 @@ pub enum CoordinatorMessage {
         result: TaskExecutionResult,
     },
 
+    /// Progress reported by a running task
+    ///
+    /// Sent any number of times before the `TaskResult` answering the same
+    /// `ExecuteTask`.
+    Progress {
+        correlation_id: Uuid,
+        execution_id: String,
+        progress: TaskProgressUpdate,
+    },
+
     /// Task validation result
     ValidationResult {
         correlation_id: Uuid,
@@ -88,6 +161,26 @@ pub enum CoordinatorMessage {
     Ready { worker_id: String },
 }
 
+/// Progress update reported by a running task
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskProgressUpdate {
+    /// Completed fraction between 0.0 and 1.0
+    pub fraction: Option<f32>,
+    /// Description of the current step
+    pub message: Option<String>,
+    /// Partial result emitted by the task
+    pub partial_output: Option<JsonValue>,
+}
+
+/// Console output written by a task
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskLogEntry {
+    pub timestamp: DateTime<Utc>,
+    /// One of `debug`, `info`, `warn` or `error`
+    pub level: String,
+    pub message: String,
+}
+
 /// Task execution result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaskExecutionResult {
@@ -98,6 +191,9 @@ pub struct TaskExecutionResult {
     pub started_at: DateTime<Utc>,
     pub completed_at: DateTime<Utc>,
     pub duration_ms: i32,
+    /// Console output captured during the execution
+    #[serde(default)]
+    pub logs: Vec<TaskLogEntry>,
 }
 
 impl TaskExecutionResult {
@@ -112,6 +208,7 @@ impl TaskExecutionResult {
             started_at,
             completed_at,
             duration_ms,
+            logs: Vec::new(),
         }
     }
 
@@ -131,6 +228,7 @@ impl TaskExecutionResult {
             started_at,
             completed_at,
             duration_ms,
+            logs: Vec::new(),
         }
     }
 }
@@ -307,6 +405,20 @@ mod tests {
         assert_eq!(context.task_version, "1.0.0");
     }
 
+    #[test]
+    fn test_execution_context_hides_secret_values() {
+        let context = ExecutionContext::new(Uuid::new_v4(), None, Uuid::new_v4(), "1.0.0".to_string())
+            .with_secrets(HashMap::from([("API_KEY".to_string(), "s3cr3t".to_string())]));
+
+        let debug = format!("{:?}", context);
+        assert!(debug.contains("API_KEY"));
+        assert!(!debug.contains("s3cr3t"));
+
+        let json = serde_json::to_string(&context).unwrap();
+        let parsed: ExecutionContext = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.secrets.get("API_KEY").map(String::as_str), Some("s3cr3t"));
+    }
+
     #[test]
     fn test_task_execution_result() {
         let start = Utc::now();
@@ -363,4 +475,40 @@ mod tests {
         let deserialized: MessageEnvelope<WorkerMessage> = serde_json::from_str(&json).unwrap();
         assert_eq!(deserialized.protocol_version, envelope.protocol_version);
     }
+
+    #[test]
+    fn test_cancel_task_message() {
+        let correlation_id = Uuid::new_v4();
+        let json = serde_json::to_value(WorkerMessage::CancelTask { correlation_id }).unwrap();
+
+        assert_eq!(json["type"], "cancel_task");
+        match serde_json::from_value::<WorkerMessage>(json).unwrap() {
+            WorkerMessage::CancelTask { correlation_id: id } => assert_eq!(id, correlation_id),
+            other => panic!("Expected CancelTask, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_progress_message() {
+        let correlation_id = Uuid::new_v4();
+        let message = CoordinatorMessage::Progress {
+            correlation_id,
+            execution_id: "exec-1".to_string(),
+            progress: TaskProgressUpdate {
+                fraction: Some(0.25),
+                message: Some("loading".to_string()),
+                partial_output: None,
+            },
+        };
+        let json = serde_json::to_value(&message).unwrap();
+
+        assert_eq!(json["type"], "progress");
+        match serde_json::from_value::<CoordinatorMessage>(json).unwrap() {
+            CoordinatorMessage::Progress { progress, .. } => {
+                assert_eq!(progress.fraction, Some(0.25));
+                assert_eq!(progress.message.as_deref(), Some("loading"));
+            }
+            other => panic!("Expected Progress, got {:?}", other),
+        }
+    }
 }
@@ -0,0 +1,404 @@
+//! Calls from one task to another
+//!
+//! Tasks call other registered tasks with `ratchet.call(taskName, input, {version})`,
+//! which returns a Promise for the called task's output. Calls are queued on the
+//! JavaScript side; the event loop hands them to the [`TaskCalls`] of the current
+//! execution, whose [`TaskCaller`] resolves and runs the task. Every call runs one
+//! level deeper than its caller, and calls beyond the maximum depth are rejected so
+//! a cycle of tasks calling each other cannot run forever.
+
+use crate::JsExecutionError;
+use async_trait::async_trait;
+use boa_engine::{Context as BoaContext, JsError, Source};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use thiserror::Error;
+use tracing::debug;
+
+/// Call depth used when the resource limits do not set one
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 8;
+
+/// JavaScript side of the task call API
+const CALLS_JS: &str = r#"
+var __ratchet_call_queue = [];
+var __ratchet_call_pending = {};
+var __ratchet_call_next_id = 1;
+var ratchet = typeof ratchet === 'undefined' ? {} : ratchet;
+
+ratchet.call = function(taskName, input, options) {
+    if (typeof taskName !== 'string' || taskName.length === 0) {
+        return Promise.reject(new TypeError("ratchet.call expects the name of a task"));
+    }
+    var version = options && options.version !== undefined && options.version !== null ? String(options.version) : null;
+    return new Promise(function(resolve, reject) {
+        var id = __ratchet_call_next_id++;
+        __ratchet_call_pending[id] = { resolve: resolve, reject: reject };
+        __ratchet_call_queue.push({
+            id: id,
+            task: taskName,
+            input: input === undefined ? null : input,
+            version: version
+        });
+    });
+};
+
+function __ratchet_settle_call(id, outcome) {
+    var pending = __ratchet_call_pending[id];
+    if (!pending) {
+        return;
+    }
+    delete __ratchet_call_pending[id];
+    if (outcome.error !== undefined) {
+        var error = new Error(outcome.error);
+        error.name = "TaskCallError";
+        error.code = outcome.code;
+        pending.reject(error);
+    } else {
+        pending.resolve(outcome.output);
+    }
+}
+"#;
+
+tokio::task_local! {
+    static CURRENT_CALLS: TaskCalls;
+}
+
+/// A call to another task made with `ratchet.call`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskCallRequest {
+    /// Name of the called task
+    pub task: String,
+    /// Input passed to the called task
+    #[serde(default)]
+    pub input: JsonValue,
+    /// Version the caller asked for, if any
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+/// A call waiting in `__ratchet_call_queue`
+#[derive(Debug, Deserialize)]
+pub(crate) struct QueuedCall {
+    /// Identifier of the pending promise in `__ratchet_call_pending`
+    pub id: u64,
+    #[serde(flatten)]
+    pub request: TaskCallRequest,
+}
+
+/// Reasons a task call is rejected
+#[derive(Debug, Error)]
+pub enum TaskCallError {
+    #[error("Task '{task}' not found")]
+    NotFound { task: String },
+
+    #[error("Task '{task}' is not available in version {version}")]
+    VersionNotFound { task: String, version: String },
+
+    #[error("Task call depth limit of {max_depth} exceeded: {chain}")]
+    DepthExceeded { max_depth: usize, chain: String },
+
+    #[error("Invalid input for task '{task}': {message}")]
+    InvalidInput { task: String, message: String },
+
+    #[error("Invalid output from task '{task}': {message}")]
+    InvalidOutput { task: String, message: String },
+
+    #[error("Task '{task}' failed: {message}")]
+    Failed { task: String, message: String },
+
+    #[error("ratchet.call is not available in this runtime")]
+    Unavailable,
+}
+
+impl TaskCallError {
+    /// Stable code exposed to scripts as `error.code`
+    pub fn code(&self) -> &'static str {
+        match self {
+            TaskCallError::NotFound { .. } | TaskCallError::VersionNotFound { .. } => "TASK_NOT_FOUND",
+            TaskCallError::DepthExceeded { .. } => "CALL_DEPTH_EXCEEDED",
+            TaskCallError::InvalidInput { .. } => "INVALID_INPUT",
+            TaskCallError::InvalidOutput { .. } => "INVALID_OUTPUT",
+            TaskCallError::Failed { .. } => "TASK_FAILED",
+            TaskCallError::Unavailable => "CALLS_UNAVAILABLE",
+        }
+    }
+}
+
+/// Resolves and runs the tasks called from scripts
+///
+/// Calls are awaited inside the caller's event loop, so implementations may run
+/// the called task on the same thread. The called task then inherits the
+/// caller's task-local scopes; runners for it should be given their own secrets,
+/// console capture and progress reporter.
+#[async_trait(?Send)]
+pub trait TaskCaller: Send + Sync {
+    /// Run `request` on behalf of the execution described by `calls`
+    ///
+    /// A called task that makes calls itself must be run with `calls.child(..)`.
+    async fn call_task(&self, calls: &TaskCalls, request: TaskCallRequest) -> Result<JsonValue, TaskCallError>;
+}
+
+/// The task caller of an execution together with its position in the call chain
+#[derive(Clone)]
+pub struct TaskCalls {
+    caller: Arc<dyn TaskCaller>,
+    stack: Vec<String>,
+    execution_id: Option<String>,
+    max_depth: usize,
+}
+
+impl fmt::Debug for TaskCalls {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaskCalls")
+            .field("stack", &self.stack)
+            .field("execution_id", &self.execution_id)
+            .field("max_depth", &self.max_depth)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TaskCalls {
+    /// Route calls through `caller`, allowing [`DEFAULT_MAX_CALL_DEPTH`] levels of nesting
+    pub fn new(caller: Arc<dyn TaskCaller>) -> Self {
+        Self {
+            caller,
+            stack: Vec::new(),
+            execution_id: None,
+            max_depth: DEFAULT_MAX_CALL_DEPTH,
+        }
+    }
+
+    /// Limit how deeply calls may be nested
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Calls made by the top-level execution `execution_id` of `task`
+    pub fn for_execution(mut self, task: impl Into<String>, execution_id: Option<String>) -> Self {
+        self.stack = vec![task.into()];
+        self.execution_id = execution_id;
+        self
+    }
+
+    /// Calls made by `task`, running as execution `execution_id` when called from this one
+    pub fn child(&self, task: impl Into<String>, execution_id: Option<String>) -> Self {
+        let mut child = self.clone();
+        child.stack.push(task.into());
+        child.execution_id = execution_id;
+        child
+    }
+
+    /// Number of calls between the top-level execution and this one
+    pub fn depth(&self) -> usize {
+        self.stack.len().saturating_sub(1)
+    }
+
+    /// Maximum nesting of calls
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Execution the calls are made from
+    pub fn execution_id(&self) -> Option<&str> {
+        self.execution_id.as_deref()
+    }
+
+    /// Names of the tasks on the call chain, outermost first
+    pub fn stack(&self) -> &[String] {
+        &self.stack
+    }
+
+    /// Call another task, rejecting the call if it would nest too deeply
+    pub async fn call(&self, request: TaskCallRequest) -> Result<JsonValue, TaskCallError> {
+        if self.depth() >= self.max_depth {
+            let mut chain = self.stack.clone();
+            chain.push(request.task);
+            return Err(TaskCallError::DepthExceeded {
+                max_depth: self.max_depth,
+                chain: chain.join(" -> "),
+            });
+        }
+        self.caller.call_task(self, request).await
+    }
+
+    /// Run `future` with these calls as the current ones
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        CURRENT_CALLS.scope(self.clone(), future).await
+    }
+}
+
+/// Register the `ratchet.call` function
+pub fn register_calls(context: &mut BoaContext) -> Result<(), JsError> {
+    context.eval(Source::from_bytes(CALLS_JS))?;
+    Ok(())
+}
+
+/// Take every call queued since the last call
+pub(crate) fn drain_call_queue(context: &mut BoaContext) -> Result<Vec<QueuedCall>, JsExecutionError> {
+    let queued = context
+        .eval(Source::from_bytes(
+            "typeof __ratchet_call_queue === 'undefined' ? '[]' : JSON.stringify(__ratchet_call_queue.splice(0, __ratchet_call_queue.length))",
+        ))
+        .map_err(|e| JsExecutionError::ExecutionError(format!("Failed to read task call queue: {}", e)))?;
+
+    let queued_str = queued
+        .to_string(context)
+        .map_err(|e| JsExecutionError::ExecutionError(e.to_string()))?
+        .to_std_string_escaped();
+
+    let calls: Vec<QueuedCall> =
+        serde_json::from_str(&queued_str).map_err(|e| JsExecutionError::InvalidOutputFormat(e.to_string()))?;
+
+    if !calls.is_empty() {
+        debug!("Drained {} task call(s) from the JavaScript queue", calls.len());
+    }
+
+    Ok(calls)
+}
+
+/// Run a queued call through the calls of the current execution
+pub(crate) async fn dispatch_call(request: TaskCallRequest) -> Result<JsonValue, TaskCallError> {
+    match CURRENT_CALLS.try_with(TaskCalls::clone) {
+        Ok(calls) => calls.call(request).await,
+        Err(_) => Err(TaskCallError::Unavailable),
+    }
+}
+
+/// Resolve or reject the promise belonging to a dispatched call
+pub(crate) fn settle_call(
+    context: &mut BoaContext,
+    call_id: u64,
+    result: Result<JsonValue, TaskCallError>,
+) -> Result<(), JsExecutionError> {
+    let outcome = match result {
+        Ok(output) => json!({ "output": output }),
+        Err(e) => {
+            debug!("Task call {} failed: {}", call_id, e);
+            json!({ "error": e.to_string(), "code": e.code() })
+        }
+    };
+
+    let outcome_str = serde_json::to_string(&outcome)
+        .map_err(|e| JsExecutionError::ExecutionError(format!("Failed to serialize task call result: {}", e)))?;
+
+    context
+        .eval(Source::from_bytes(&format!(
+            "__ratchet_settle_call({}, {})",
+            call_id, outcome_str
+        )))
+        .map_err(|e| JsExecutionError::ExecutionError(format!("Failed to settle task call: {}", e)))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::JsTaskRunner;
+    use std::collections::HashMap;
+
+    /// Runs tasks from a map of name to source code
+    struct MapCaller {
+        tasks: HashMap<String, String>,
+    }
+
+    #[async_trait(?Send)]
+    impl TaskCaller for MapCaller {
+        async fn call_task(&self, calls: &TaskCalls, request: TaskCallRequest) -> Result<JsonValue, TaskCallError> {
+            let code = self.tasks.get(&request.task).ok_or_else(|| TaskCallError::NotFound {
+                task: request.task.clone(),
+            })?;
+            let runner = JsTaskRunner::new().with_calls(calls.child(request.task.clone(), None));
+            runner
+                .execute_code(code, request.input, None, None, None)
+                .await
+                .map_err(|e| TaskCallError::Failed {
+                    task: request.task,
+                    message: e.to_string(),
+                })
+        }
+    }
+
+    fn calls(tasks: &[(&str, &str)]) -> TaskCalls {
+        let tasks = tasks
+            .iter()
+            .map(|(name, code)| (name.to_string(), code.to_string()))
+            .collect();
+        TaskCalls::new(Arc::new(MapCaller { tasks })).for_execution("parent", None)
+    }
+
+    #[tokio::test]
+    async fn test_call_runs_other_task() {
+        let code = r#"
+            async function main(input) {
+                const results = await Promise.all([
+                    ratchet.call("double", { n: input.n }),
+                    ratchet.call("double", { n: input.n + 1 }, { version: "1.0.0" })
+                ]);
+                try {
+                    await ratchet.call("missing", {});
+                } catch (e) {
+                    return { results: results, code: e.code, name: e.name };
+                }
+            }
+        "#;
+        let calls = calls(&[("double", "function main(input) { return { value: input.n * 2 }; }")]);
+
+        let runner = JsTaskRunner::new().with_calls(calls);
+        let result = runner.execute_code(code, json!({ "n": 2 }), None, None, None).await.unwrap();
+
+        assert_eq!(
+            result,
+            json!({
+                "results": [{ "value": 4 }, { "value": 6 }],
+                "code": "TASK_NOT_FOUND",
+                "name": "TaskCallError"
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_call_depth_is_limited() {
+        let recursive = r#"
+            async function main(input) {
+                try {
+                    return await ratchet.call("loop", {});
+                } catch (e) {
+                    return { code: e.code, message: e.message };
+                }
+            }
+        "#;
+        let calls = calls(&[("loop", recursive)]).with_max_depth(2);
+
+        let runner = JsTaskRunner::new().with_calls(calls);
+        let result = runner.execute_code(recursive, json!({}), None, None, None).await.unwrap();
+
+        assert_eq!(result["code"], json!("CALL_DEPTH_EXCEEDED"));
+        assert!(result["message"]
+            .as_str()
+            .unwrap()
+            .contains("parent -> loop -> loop -> loop"));
+    }
+
+    #[tokio::test]
+    async fn test_call_without_caller_is_rejected() {
+        let code = r#"
+            async function main(input) {
+                try {
+                    await ratchet.call("other", {});
+                    return { rejected: false };
+                } catch (e) {
+                    return { rejected: true, code: e.code };
+                }
+            }
+        "#;
+
+        let result = JsTaskRunner::new().execute_code(code, json!({}), None, None, None).await.unwrap();
+
+        assert_eq!(result, json!({ "rejected": true, "code": "CALLS_UNAVAILABLE" }));
+    }
+}
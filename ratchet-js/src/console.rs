@@ -0,0 +1,251 @@
+//! Console output captured from running scripts
+//!
+//! `console.log`, `console.info`, `console.warn`, `console.error` and
+//! `console.debug` queue entries on the JavaScript side. The event loop moves
+//! them into the [`ConsoleCapture`] of the current execution on each pass and once
+//! more when the script finishes, so output written just before a failure is kept.
+//! Outside a capture scope entries are only traced.
+
+use crate::JsExecutionError;
+use boa_engine::{Context as BoaContext, JsError, Source};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tracing::debug;
+
+/// Maximum number of entries kept per execution; later entries are dropped
+pub const MAX_CONSOLE_ENTRIES: usize = 1000;
+
+/// Maximum length of a single message in bytes; longer messages are truncated
+pub const MAX_CONSOLE_MESSAGE_BYTES: usize = 4096;
+
+/// JavaScript side of the console API
+const CONSOLE_JS: &str = r#"
+var __ratchet_console_queue = [];
+
+(function() {
+    function format(args) {
+        var parts = [];
+        for (var i = 0; i < args.length; i++) {
+            var arg = args[i];
+            if (typeof arg === 'string') {
+                parts.push(arg);
+            } else if (arg instanceof Error) {
+                parts.push(arg.name + ": " + arg.message);
+            } else {
+                var json;
+                try { json = JSON.stringify(arg); } catch (e) { json = undefined; }
+                parts.push(json === undefined ? String(arg) : json);
+            }
+        }
+        return parts.join(" ");
+    }
+
+    function writer(level) {
+        return function() {
+            __ratchet_console_queue.push({ level: level, message: format(arguments), timestamp: Date.now() });
+        };
+    }
+
+    console = {
+        log: writer("info"),
+        info: writer("info"),
+        warn: writer("warn"),
+        error: writer("error"),
+        debug: writer("debug")
+    };
+})();
+"#;
+
+tokio::task_local! {
+    static CURRENT_CONSOLE: ConsoleCapture;
+}
+
+/// Severity of a console entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsoleLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl ConsoleLevel {
+    /// Lowercase name of the level
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConsoleLevel::Debug => "debug",
+            ConsoleLevel::Info => "info",
+            ConsoleLevel::Warn => "warn",
+            ConsoleLevel::Error => "error",
+        }
+    }
+}
+
+/// A single line written to the console by a task
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsoleEntry {
+    pub timestamp: DateTime<Utc>,
+    pub level: ConsoleLevel,
+    pub message: String,
+}
+
+/// Entry as queued by the JavaScript side, with a millisecond timestamp
+#[derive(Deserialize)]
+struct QueuedEntry {
+    level: ConsoleLevel,
+    message: String,
+    timestamp: f64,
+}
+
+#[derive(Debug, Default)]
+struct CaptureState {
+    entries: Vec<ConsoleEntry>,
+    dropped: usize,
+}
+
+/// Bounded buffer collecting the console output of an execution
+#[derive(Debug, Clone, Default)]
+pub struct ConsoleCapture {
+    state: Arc<Mutex<CaptureState>>,
+}
+
+impl ConsoleCapture {
+    /// Create an empty capture
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an entry, truncating long messages and dropping entries past the limit
+    pub fn push(&self, mut entry: ConsoleEntry) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        if state.entries.len() >= MAX_CONSOLE_ENTRIES {
+            state.dropped += 1;
+            return;
+        }
+        if entry.message.len() > MAX_CONSOLE_MESSAGE_BYTES {
+            let mut end = MAX_CONSOLE_MESSAGE_BYTES;
+            while !entry.message.is_char_boundary(end) {
+                end -= 1;
+            }
+            entry.message.truncate(end);
+            entry.message.push_str("... [truncated]");
+        }
+        state.entries.push(entry);
+    }
+
+    /// Take the captured entries, appending a warning if any were dropped
+    pub fn take(&self) -> Vec<ConsoleEntry> {
+        let Ok(mut state) = self.state.lock() else {
+            return Vec::new();
+        };
+        let mut entries = std::mem::take(&mut state.entries);
+        let dropped = std::mem::take(&mut state.dropped);
+        if dropped > 0 {
+            entries.push(ConsoleEntry {
+                timestamp: Utc::now(),
+                level: ConsoleLevel::Warn,
+                message: format!("{} console entries dropped after reaching the limit of {}", dropped, MAX_CONSOLE_ENTRIES),
+            });
+        }
+        entries
+    }
+
+    /// Run `future` with this capture as the current one
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        CURRENT_CONSOLE.scope(self.clone(), future).await
+    }
+}
+
+/// Register the `console` object
+pub fn register_console(context: &mut BoaContext) -> Result<(), JsError> {
+    context.eval(Source::from_bytes(CONSOLE_JS))?;
+    Ok(())
+}
+
+/// Move every entry queued since the last call into the current capture
+pub(crate) fn drain_console_queue(context: &mut BoaContext) -> Result<(), JsExecutionError> {
+    let queued = context
+        .eval(Source::from_bytes(
+            "typeof __ratchet_console_queue === 'undefined' ? '[]' : JSON.stringify(__ratchet_console_queue.splice(0, __ratchet_console_queue.length))",
+        ))
+        .map_err(|e| JsExecutionError::ExecutionError(format!("Failed to read console queue: {}", e)))?;
+
+    let queued_str = queued
+        .to_string(context)
+        .map_err(|e| JsExecutionError::ExecutionError(e.to_string()))?
+        .to_std_string_escaped();
+
+    let entries: Vec<QueuedEntry> =
+        serde_json::from_str(&queued_str).map_err(|e| JsExecutionError::InvalidOutputFormat(e.to_string()))?;
+
+    for queued in entries {
+        let entry = ConsoleEntry {
+            timestamp: DateTime::from_timestamp_millis(queued.timestamp as i64).unwrap_or_else(Utc::now),
+            level: queued.level,
+            message: crate::secrets::redact_current(queued.message),
+        };
+        debug!("console.{}: {}", entry.level.as_str(), entry.message);
+        let _ = CURRENT_CONSOLE.try_with(|capture| capture.push(entry));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::execute_js_with_content;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_console_output_is_captured() {
+        let code = r#"
+            async function main(input) {
+                console.log("starting", { id: input.id });
+                await Promise.resolve();
+                console.warn("slow");
+                console.error(new Error("boom"));
+                throw new Error("failed");
+            }
+        "#;
+        let capture = ConsoleCapture::new();
+        let client = ratchet_http::HttpManager::new();
+
+        let result = capture
+            .scope(execute_js_with_content(code, json!({ "id": 3 }), None, None, &client, None))
+            .await;
+        assert!(result.is_err());
+
+        let entries = capture.take();
+        let lines: Vec<(ConsoleLevel, &str)> = entries.iter().map(|e| (e.level, e.message.as_str())).collect();
+        assert_eq!(
+            lines,
+            vec![
+                (ConsoleLevel::Info, r#"starting {"id":3}"#),
+                (ConsoleLevel::Warn, "slow"),
+                (ConsoleLevel::Error, "Error: boom"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_capture_is_bounded() {
+        let capture = ConsoleCapture::new();
+        for _ in 0..MAX_CONSOLE_ENTRIES + 5 {
+            capture.push(ConsoleEntry {
+                timestamp: Utc::now(),
+                level: ConsoleLevel::Info,
+                message: "x".repeat(MAX_CONSOLE_MESSAGE_BYTES + 1),
+            });
+        }
+
+        let entries = capture.take();
+        assert_eq!(entries.len(), MAX_CONSOLE_ENTRIES + 1);
+        assert!(entries[0].message.ends_with("[truncated]"));
+        assert!(entries.last().unwrap().message.starts_with("5 console entries dropped"));
+    }
+}
@@ -27,6 +27,11 @@ pub const JS_ERROR_CONFIGS: &[JsErrorConfig] = &[
         default_message: "Network error",
         has_status: false,
     },
+    JsErrorConfig {
+        name: "EgressError",
+        default_message: "Egress denied",
+        has_status: false,
+    },
     JsErrorConfig {
         name: "HttpError",
         default_message: "HTTP error",
@@ -131,6 +136,7 @@ pub fn parse_js_error(error_message: &str) -> JsErrorType {
             "AuthenticationError" => JsErrorType::AuthenticationError(message),
             "AuthorizationError" => JsErrorType::AuthorizationError(message),
             "NetworkError" => JsErrorType::NetworkError(message),
+            "EgressError" => JsErrorType::EgressError(message),
             "HttpError" => {
                 // Try to extract status code from message
                 if let Some(status_captures) = regex::Regex::new(r"(\d+)").unwrap().captures(&message) {
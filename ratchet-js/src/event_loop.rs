@@ -0,0 +1,293 @@
+//! Event loop driving promises, fetch requests and task calls to completion
+
+use crate::{
+    calls::{dispatch_call, drain_call_queue, settle_call},
+    console::drain_console_queue,
+    error_handling::parse_js_error,
+    http_integration::{drain_fetch_queue, settle_fetch},
+    interrupt::{check_interrupt, interrupted},
+    progress::drain_progress_queue,
+    JsExecutionError,
+};
+use boa_engine::{property::PropertyKey, Context as BoaContext, JsString, JsValue, Source};
+use futures::future::{join, join_all};
+use tracing::debug;
+
+/// Attaches settlement handlers to the value returned by the task so its state
+/// can be read back once the job queue is empty.
+const TRACK_RESULT_JS: &str = r#"
+var __ratchet_result_state = { status: "pending" };
+Promise.resolve(__ratchet_result).then(
+    function(value) {
+        __ratchet_result_state.status = "fulfilled";
+        __ratchet_result_state.value = value;
+    },
+    function(reason) {
+        __ratchet_result_state.status = "rejected";
+        __ratchet_result_state.reason = reason;
+    }
+);
+__ratchet_result = undefined;
+"#;
+
+/// Run the job queue until `value` settles, dispatching queued fetch requests
+/// and task calls.
+///
+/// Plain values settle immediately. Promises (including the result of an
+/// `async function main`) are driven by alternating between Boa's job queue and
+/// the fetch and call queues: everything queued during one pass is dispatched
+/// concurrently, so `Promise.all` over several `fetch` or `ratchet.call` calls
+/// runs them in parallel.
+///
+/// Each pass is a safe point for cancellation: an interrupted execution stops
+/// with [`JsExecutionError::Cancelled`], abandoning any fetch requests in flight.
+/// Progress updates and console output queued by the script are forwarded at
+/// the same points.
+pub async fn run_until_settled(
+    context: &mut BoaContext,
+    value: JsValue,
+    http_manager: &impl ratchet_http::HttpClient,
+) -> Result<JsValue, JsExecutionError> {
+    context
+        .global_object()
+        .set(PropertyKey::from(JsString::from("__ratchet_result")), value, true, context)
+        .map_err(|e| JsExecutionError::ExecutionError(format!("Failed to set task result: {}", e)))?;
+
+    context
+        .eval(Source::from_bytes(TRACK_RESULT_JS))
+        .map_err(|e| JsExecutionError::ExecutionError(format!("Failed to track task result: {}", e)))?;
+
+    loop {
+        check_interrupt()?;
+        context.run_jobs();
+        check_interrupt()?;
+        drain_progress_queue(context)?;
+        drain_console_queue(context)?;
+
+        let requests = drain_fetch_queue(context)?;
+        let calls = drain_call_queue(context)?;
+        if requests.is_empty() && calls.is_empty() {
+            break;
+        }
+
+        debug!(
+            "Dispatching {} fetch request(s) and {} task call(s)",
+            requests.len(),
+            calls.len()
+        );
+        let fetches = join_all(requests.iter().map(|request| async move {
+            let http_request = request.to_http_request()?;
+            http_manager.send(http_request).await
+        }));
+        let called = join_all(calls.iter().map(|call| dispatch_call(call.request.clone())));
+        let (responses, outputs) = tokio::select! {
+            results = join(fetches, called) => results,
+            _ = interrupted() => return Err(JsExecutionError::Cancelled),
+        };
+
+        for (request, response) in requests.iter().zip(responses) {
+            settle_fetch(context, request.id, response)?;
+        }
+        for (call, output) in calls.iter().zip(outputs) {
+            settle_call(context, call.id, output)?;
+        }
+    }
+
+    let status = context
+        .eval(Source::from_bytes("__ratchet_result_state.status"))
+        .map_err(|e| JsExecutionError::ExecutionError(e.to_string()))?
+        .to_string(context)
+        .map_err(|e| JsExecutionError::ExecutionError(e.to_string()))?
+        .to_std_string_escaped();
+
+    match status.as_str() {
+        "fulfilled" => context
+            .eval(Source::from_bytes("__ratchet_result_state.value"))
+            .map_err(|e| JsExecutionError::ExecutionError(e.to_string())),
+        "rejected" => {
+            let reason = context
+                .eval(Source::from_bytes("String(__ratchet_result_state.reason)"))
+                .map_err(|e| JsExecutionError::ExecutionError(e.to_string()))?
+                .to_string(context)
+                .map_err(|e| JsExecutionError::ExecutionError(e.to_string()))?
+                .to_std_string_escaped();
+            debug!("Task promise rejected: {}", reason);
+            Err(JsExecutionError::TypedJsError(parse_js_error(&reason)))
+        }
+        _ => Err(JsExecutionError::ExecutionError(
+            "Task promise never settled and no fetch requests or task calls are outstanding".to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::execution::execute_js_with_content;
+    use ratchet_http::{HttpClient, HttpError};
+    use serde_json::{json, Value as JsonValue};
+    use std::sync::Mutex;
+
+    /// Answers every request with the requested URL and records the call order
+    #[derive(Default)]
+    struct EchoClient {
+        calls: Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpClient for EchoClient {
+        async fn call_http(
+            &self,
+            url: &str,
+            _params: Option<&JsonValue>,
+            _body: Option<&JsonValue>,
+        ) -> Result<JsonValue, HttpError> {
+            self.calls.lock().unwrap().push(url.to_string());
+            if url.contains("/internal") {
+                return Err(HttpError::EgressDenied {
+                    url: url.to_string(),
+                    reason: "address 10.0.0.1 of 'api' is in a private range".to_string(),
+                });
+            }
+            if url.ends_with("/missing") {
+                return Ok(json!({ "ok": false, "status": 404, "statusText": "Not Found", "headers": {}, "body": null }));
+            }
+            Ok(json!({ "ok": true, "status": 200, "statusText": "OK", "headers": {}, "body": { "url": url } }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sequential_fetches_in_async_main() {
+        let code = r#"
+            async function main(input) {
+                const first = await fetch(input.base + "/page/1");
+                const firstBody = await first.json();
+                const second = await fetch(input.base + "/page/2");
+                const secondBody = await second.json();
+                return { urls: [firstBody.url, secondBody.url] };
+            }
+        "#;
+        let client = EchoClient::default();
+
+        let result = execute_js_with_content(code, json!({ "base": "http://api" }), None, None, &client, None)
+            .await
+            .unwrap();
+
+        assert_eq!(result["urls"], json!(["http://api/page/1", "http://api/page/2"]));
+        assert_eq!(client.calls.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_parallel_fetches_with_promise_all() {
+        let code = r#"
+            function main(input) {
+                return Promise.all(input.ids.map(id => fetch("http://api/items/" + id)))
+                    .then(responses => Promise.all(responses.map(r => r.json())))
+                    .then(bodies => ({ count: bodies.length, last: bodies[bodies.length - 1].url }));
+            }
+        "#;
+        let client = EchoClient::default();
+
+        let result = execute_js_with_content(code, json!({ "ids": [1, 2, 3] }), None, None, &client, None)
+            .await
+            .unwrap();
+
+        assert_eq!(result, json!({ "count": 3, "last": "http://api/items/3" }));
+        assert_eq!(client.calls.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_http_error_status_resolves_response() {
+        let code = r#"
+            async function main(input) {
+                const response = await fetch("http://api/missing");
+                return { ok: response.ok, status: response.status };
+            }
+        "#;
+        let client = EchoClient::default();
+
+        let result = execute_js_with_content(code, json!({}), None, None, &client, None)
+            .await
+            .unwrap();
+
+        assert_eq!(result, json!({ "ok": false, "status": 404 }));
+    }
+
+    #[tokio::test]
+    async fn test_thrown_error_in_async_main_is_typed() {
+        let code = r#"
+            async function main(input) {
+                const response = await fetch("http://api/missing");
+                if (!response.ok) {
+                    throw new HttpError(response.status, "HTTP " + response.status);
+                }
+                return {};
+            }
+        "#;
+        let client = EchoClient::default();
+
+        let result = execute_js_with_content(code, json!({}), None, None, &client, None).await;
+
+        assert!(matches!(
+            result,
+            Err(crate::JsExecutionError::TypedJsError(crate::JsErrorType::HttpError { status: 404, .. }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_egress_denial_rejects_with_egress_error() {
+        let code = r#"
+            async function main(input) {
+                try {
+                    await fetch("http://api/internal/secrets");
+                    return { caught: null };
+                } catch (e) {
+                    return { caught: e.name, egress: e instanceof EgressError, network: e instanceof NetworkError };
+                }
+            }
+        "#;
+        let client = EchoClient::default();
+
+        let result = execute_js_with_content(code, json!({}), None, None, &client, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            result,
+            json!({ "caught": "EgressError", "egress": true, "network": false })
+        );
+
+        // Uncaught, the denial surfaces as a typed error
+        let uncaught = execute_js_with_content(
+            r#"async function main(input) { await fetch("http://api/internal"); return {}; }"#,
+            json!({}),
+            None,
+            None,
+            &client,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(
+            uncaught,
+            crate::JsExecutionError::TypedJsError(crate::JsErrorType::EgressError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_sync_main_without_fetch() {
+        let client = EchoClient::default();
+
+        let result = execute_js_with_content(
+            "function main(input) { return { doubled: input.n * 2 }; }",
+            json!({ "n": 21 }),
+            None,
+            None,
+            &client,
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result, json!({ "doubled": 42 }));
+        assert!(client.calls.lock().unwrap().is_empty());
+    }
+}
@@ -3,14 +3,67 @@
 use crate::{
     conversion::{convert_js_result_to_json, prepare_input_argument},
     error_handling::{parse_js_error, register_error_types},
+    event_loop::run_until_settled,
+    interrupt::interrupted,
+    limits::{apply_runtime_limits, check_output_size, classify_limit_error, ResourceLimits},
     JsExecutionError,
 };
-use boa_engine::{property::PropertyKey, Context as BoaContext, JsString, Script, Source};
+use boa_engine::{property::PropertyKey, Context as BoaContext, JsString, JsValue, Script, Source};
 use ratchet_core::validation::{parse_schema, validate_json};
 use serde_json::Value as JsonValue;
 use std::path::Path;
+use std::time::Duration;
 use tracing::{debug, info};
 
+/// Cost of the VM instructions a script runs before yielding to the executor
+const EVALUATION_BUDGET: u32 = 10_000;
+
+/// Evaluate `script`, yielding periodically so an interrupt or an elapsed
+/// wall-clock limit can stop it even inside a synchronous loop
+async fn evaluate_interruptible(script: &Script, context: &mut BoaContext) -> Result<JsValue, JsExecutionError> {
+    tokio::select! {
+        result = script.evaluate_async_with_budget(context, EVALUATION_BUDGET) => result.map_err(|e| {
+            let parsed_error = parse_js_error(&e.to_string());
+            JsExecutionError::TypedJsError(parsed_error)
+        }),
+        _ = interrupted() => Err(JsExecutionError::Cancelled),
+    }
+}
+
+/// Call `function` with `args` from a script evaluated by [`evaluate_interruptible`]
+///
+/// Calls between JavaScript functions stay inside the script's evaluation, so the
+/// whole synchronous part of the task can be stopped. Callbacks invoked by builtins
+/// (e.g. `Array.prototype.map`) and promise continuations run to completion.
+async fn call_interruptible(
+    context: &mut BoaContext,
+    function: &JsValue,
+    args: &[JsValue],
+) -> Result<JsValue, JsExecutionError> {
+    if !function.is_callable() {
+        return Err(JsExecutionError::RuntimeError("main is not a function".to_string()));
+    }
+
+    let global = context.global_object();
+    let set = |name: &str, value: JsValue, context: &mut BoaContext| {
+        global
+            .set(PropertyKey::from(JsString::from(name)), value, true, context)
+            .map_err(|e| JsExecutionError::ExecutionError(format!("Failed to prepare task call: {}", e)))
+    };
+    set("__ratchet_entry", function.clone(), context)?;
+    let mut names = Vec::with_capacity(args.len());
+    for (index, arg) in args.iter().enumerate() {
+        let name = format!("__ratchet_arg{}", index);
+        set(&name, arg.clone(), context)?;
+        names.push(name);
+    }
+
+    let source = format!("__ratchet_entry({})", names.join(", "));
+    let script = Script::parse(Source::from_bytes(&source), None, context)
+        .map_err(|e| JsExecutionError::ExecutionError(format!("Failed to prepare task call: {}", e)))?;
+    evaluate_interruptible(&script, context).await
+}
+
 /// Call a JavaScript function with input data
 pub async fn call_js_function(
     context: &mut BoaContext,
@@ -33,10 +86,7 @@ async fn call_js_function_with_code(
     let input_arg = prepare_input_argument(context, input_data)?;
 
     // Execute the script first
-    let script_result = script.evaluate(context).map_err(|e| {
-        let parsed_error = parse_js_error(&e.to_string());
-        JsExecutionError::TypedJsError(parsed_error)
-    })?;
+    let script_result = evaluate_interruptible(script, context).await?;
 
     // Try to get the main function from the global context first
     let main_function_result = context
@@ -47,35 +97,10 @@ async fn call_js_function_with_code(
         // Check if main function exists and is callable
         if main_fn.is_callable() {
             debug!("Using named main function");
-            // Call the main function first to allow it to set fetch variables
-            let initial_result = main_fn
-                .as_callable()
-                .ok_or_else(|| JsExecutionError::RuntimeError("main is not a function".to_string()))?
-                .call(&boa_engine::JsValue::undefined(), &[input_arg.clone()], context)
-                .map_err(|e| {
-                    let parsed_error = parse_js_error(&e.to_string());
-                    JsExecutionError::TypedJsError(parsed_error)
-                })?;
-
-            // After function execution, check for HTTP fetch calls
-            if let Some((url, params, body)) = crate::http_integration::check_fetch_call(context)? {
-                debug!("Detected HTTP fetch call to: {} after function execution", url);
-                let js_result = crate::http_integration::handle_fetch_processing(
-                    context,
-                    &main_fn,
-                    &input_arg,
-                    http_manager,
-                    url,
-                    params,
-                    body,
-                )
-                .await?;
-                convert_js_result_to_json(context, js_result)?
-            } else {
-                // No fetch calls detected, use the initial result
-                debug!("No fetch calls detected, using initial result");
-                convert_js_result_to_json(context, initial_result)?
-            }
+            let initial_result = call_interruptible(context, &main_fn, &[input_arg.clone()]).await?;
+
+            let settled = run_until_settled(context, initial_result, http_manager).await?;
+            convert_js_result_to_json(context, settled)?
         } else {
             // main exists but is not callable, fall through to anonymous function handling
             debug!("main exists but is not callable, trying anonymous function handling");
@@ -107,38 +132,10 @@ async fn handle_anonymous_function_with_http(
 
     if script_result.is_callable() {
         debug!("Using anonymous function result");
-        // Call the function first to allow it to set fetch variables
-        let initial_result = script_result
-            .as_callable()
-            .ok_or_else(|| JsExecutionError::RuntimeError("Script result is not callable".to_string()))?
-            .call(&boa_engine::JsValue::undefined(), &[input_arg.clone()], context)
-            .map_err(|e| {
-                let parsed_error = parse_js_error(&e.to_string());
-                JsExecutionError::TypedJsError(parsed_error)
-            })?;
+        let initial_result = call_interruptible(context, &script_result, &[input_arg.clone()]).await?;
 
-        // After function execution, check for HTTP fetch calls
-        if let Some((url, params, body)) = crate::http_integration::check_fetch_call(context)? {
-            debug!(
-                "Detected HTTP fetch call to: {} after anonymous function execution",
-                url
-            );
-            let js_result = crate::http_integration::handle_fetch_processing(
-                context,
-                &script_result,
-                &input_arg,
-                http_manager,
-                url,
-                params,
-                body,
-            )
-            .await?;
-            convert_js_result_to_json(context, js_result)
-        } else {
-            // No fetch calls detected, use the initial result
-            debug!("No fetch calls detected in anonymous function, using initial result");
-            convert_js_result_to_json(context, initial_result)
-        }
+        let settled = run_until_settled(context, initial_result, http_manager).await?;
+        convert_js_result_to_json(context, settled)
     } else if !script_result.is_undefined() && !script_result.is_null() {
         debug!("Using script result directly as value");
         convert_js_result_to_json(context, script_result)
@@ -164,45 +161,14 @@ async fn handle_function_expression_with_http(
         let wrapped_source = Source::from_bytes(&wrapped_code);
         match Script::parse(wrapped_source, None, context) {
             Ok(wrapped_script) => {
-                let wrapped_result = wrapped_script.evaluate(context).map_err(|e| {
-                    let parsed_error = parse_js_error(&e.to_string());
-                    JsExecutionError::TypedJsError(parsed_error)
-                })?;
+                let wrapped_result = evaluate_interruptible(&wrapped_script, context).await?;
 
                 if wrapped_result.is_callable() {
                     debug!("Successfully extracted function from expression");
-                    // Call the function first to allow it to set fetch variables
-                    let initial_result = wrapped_result
-                        .as_callable()
-                        .ok_or_else(|| JsExecutionError::RuntimeError("Wrapped result is not callable".to_string()))?
-                        .call(&boa_engine::JsValue::undefined(), &[input_arg.clone()], context)
-                        .map_err(|e| {
-                            let parsed_error = parse_js_error(&e.to_string());
-                            JsExecutionError::TypedJsError(parsed_error)
-                        })?;
-
-                    // After function execution, check for HTTP fetch calls
-                    if let Some((url, params, body)) = crate::http_integration::check_fetch_call(context)? {
-                        debug!(
-                            "Detected HTTP fetch call to: {} after function expression execution",
-                            url
-                        );
-                        let js_result = crate::http_integration::handle_fetch_processing(
-                            context,
-                            &wrapped_result,
-                            &input_arg,
-                            http_manager,
-                            url,
-                            params,
-                            body,
-                        )
-                        .await?;
-                        convert_js_result_to_json(context, js_result)
-                    } else {
-                        // No fetch calls detected, use the initial result
-                        debug!("No fetch calls detected in function expression, using initial result");
-                        convert_js_result_to_json(context, initial_result)
-                    }
+                    let initial_result = call_interruptible(context, &wrapped_result, &[input_arg.clone()]).await?;
+
+                    let settled = run_until_settled(context, initial_result, http_manager).await?;
+                    convert_js_result_to_json(context, settled)
                 } else {
                     debug!("Wrapped result is not callable");
                     convert_js_result_to_json(context, wrapped_result)
@@ -325,10 +291,7 @@ pub async fn call_js_function_with_context(
     )?;
 
     // Execute the script first to define functions
-    script.evaluate(context).map_err(|e| {
-        let parsed_error = parse_js_error(&e.to_string());
-        JsExecutionError::TypedJsError(parsed_error)
-    })?;
+    evaluate_interruptible(script, context).await?;
 
     // Get the main function from the global context
     let main_function = context
@@ -336,34 +299,11 @@ pub async fn call_js_function_with_context(
         .get(PropertyKey::from(JsString::from("main")), context)
         .map_err(|e| JsExecutionError::RuntimeError(format!("Failed to get main function: {}", e)))?;
 
-    // Check for HTTP fetch calls
-    let result = if let Some((url, params, body)) = crate::http_integration::check_fetch_call(context)? {
-        debug!("Detected HTTP fetch call to: {}", url);
-        let js_result = crate::http_integration::handle_fetch_processing_with_context(
-            context,
-            &main_function,
-            &input_arg,
-            &context_arg,
-            http_manager,
-            url,
-            params,
-            body,
-        )
-        .await?;
-        convert_js_result_to_json(context, js_result)?
-    } else {
-        // Call the main function with both input and context
-        let result = main_function
-            .as_callable()
-            .ok_or_else(|| JsExecutionError::RuntimeError("main is not a function".to_string()))?
-            .call(&boa_engine::JsValue::undefined(), &[input_arg, context_arg], context)
-            .map_err(|e| {
-                let parsed_error = parse_js_error(&e.to_string());
-                JsExecutionError::TypedJsError(parsed_error)
-            })?;
+    // Call the main function with both input and context
+    let initial_result = call_interruptible(context, &main_function, &[input_arg, context_arg]).await?;
 
-        convert_js_result_to_json(context, result)?
-    };
+    let settled = run_until_settled(context, initial_result, http_manager).await?;
+    let result = convert_js_result_to_json(context, settled)?;
 
     Ok(result)
 }
@@ -414,6 +354,28 @@ pub async fn execute_js_with_content(
     output_schema: Option<&JsonValue>,
     http_manager: &impl ratchet_http::HttpClient,
     execution_context: Option<&crate::ExecutionContext>,
+) -> Result<JsonValue, JsExecutionError> {
+    execute_js_with_limits(
+        js_code,
+        input_data,
+        input_schema,
+        output_schema,
+        http_manager,
+        execution_context,
+        &ResourceLimits::default(),
+    )
+    .await
+}
+
+/// Execute JavaScript code directly, enforcing the given resource limits
+pub async fn execute_js_with_limits(
+    js_code: &str,
+    input_data: JsonValue,
+    input_schema: Option<&JsonValue>,
+    output_schema: Option<&JsonValue>,
+    http_manager: &impl ratchet_http::HttpClient,
+    execution_context: Option<&crate::ExecutionContext>,
+    limits: &ResourceLimits,
 ) -> Result<JsonValue, JsExecutionError> {
     info!("Executing JavaScript code directly");
     debug!(
@@ -430,6 +392,7 @@ pub async fn execute_js_with_content(
     debug!("Creating JavaScript execution context");
     // Create a new Boa context for JavaScript execution
     let mut context = BoaContext::default();
+    apply_runtime_limits(&mut context, limits);
 
     debug!("Registering error types");
     // Register custom error types
@@ -441,6 +404,22 @@ pub async fn execute_js_with_content(
     crate::fetch::register_fetch(&mut context)
         .map_err(|e| JsExecutionError::ExecutionError(format!("Failed to register fetch API: {}", e)))?;
 
+    debug!("Registering progress API");
+    crate::progress::register_progress(&mut context)
+        .map_err(|e| JsExecutionError::ExecutionError(format!("Failed to register progress API: {}", e)))?;
+
+    debug!("Registering task calls");
+    crate::calls::register_calls(&mut context)
+        .map_err(|e| JsExecutionError::ExecutionError(format!("Failed to register task calls: {}", e)))?;
+
+    debug!("Registering console");
+    crate::console::register_console(&mut context)
+        .map_err(|e| JsExecutionError::ExecutionError(format!("Failed to register console: {}", e)))?;
+
+    debug!("Registering secrets");
+    crate::secrets::register_secrets(&mut context)
+        .map_err(|e| JsExecutionError::ExecutionError(format!("Failed to register secrets: {}", e)))?;
+
     debug!("Compiling JavaScript code");
     // Parse and compile the JavaScript code
     let source = Source::from_bytes(js_code);
@@ -449,12 +428,28 @@ pub async fn execute_js_with_content(
 
     debug!("Calling JavaScript function");
     // Call the JavaScript function with the input data and execution context
-    let result = if let Some(exec_ctx) = execution_context {
-        call_js_function_with_context(&mut context, &script, &input_data, http_manager, exec_ctx).await?
-    } else {
-        call_js_function_with_code(&mut context, &script, Some(js_code), &input_data, http_manager).await?
+    let call = async {
+        if let Some(exec_ctx) = execution_context {
+            call_js_function_with_context(&mut context, &script, &input_data, http_manager, exec_ctx).await
+        } else {
+            call_js_function_with_code(&mut context, &script, Some(js_code), &input_data, http_manager).await
+        }
+    };
+    let result = match limits.timeout_seconds {
+        Some(seconds) => tokio::time::timeout(Duration::from_secs(seconds), call)
+            .await
+            .unwrap_or(Err(JsExecutionError::Timeout(seconds))),
+        None => call.await,
     };
 
+    // Keep console output written right before a synchronous failure
+    if let Err(e) = crate::console::drain_console_queue(&mut context) {
+        debug!("Failed to drain console output: {}", e);
+    }
+    let result = result.map_err(classify_limit_error)?;
+
+    check_output_size(&result, limits)?;
+
     // Validate output against schema if provided
     if let Some(schema) = output_schema {
         debug!("Validating output against schema");
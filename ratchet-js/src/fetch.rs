@@ -1,117 +1,501 @@
-use boa_engine::{property::PropertyKey, Context, JsError, JsNativeError, JsResult, JsString, JsValue, Source};
-use ratchet_http::call_http;
-use serde_json::Value as JsonValue;
-
-/// Native function to handle fetch calls from JavaScript
-#[allow(dead_code)]
-async fn fetch_native(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
-    // Extract arguments
-    let url = if args.is_empty() {
-        return Err(JsNativeError::error().with_message("URL parameter is required").into());
-    } else {
-        if !args[0].is_string() {
-            return Err(JsNativeError::error().with_message("URL must be a string").into());
+use boa_engine::{Context, JsError, Source};
+
+/// JavaScript side of the fetch API.
+///
+/// Provides WHATWG-style `fetch`, `Headers`, `Request` and `Response`. `fetch`
+/// returns a real Promise; the request itself is only queued here. The Rust
+/// event loop in [`crate::event_loop`] drains `__ratchet_fetch_queue`, performs
+/// the HTTP calls through `ratchet_http::HttpClient::send` and settles the
+/// pending promise via `__ratchet_settle_fetch`. Failed requests reject with a
+/// `NetworkError`, or an `EgressError` when an egress policy refused them.
+/// Responses resolve whatever their status, as in browsers: tasks check
+/// `response.ok` rather than catching the `AuthenticationError`,
+/// `AuthorizationError`, `RateLimitError`, `ServiceUnavailableError` and
+/// `HttpError` that non-2xx responses used to reject with.
+///
+/// For compatibility with older tasks, `fetch(url, params, body)` still accepts
+/// the body as a third argument, and plain object bodies are sent as JSON.
+const FETCH_JS: &str = r#"
+var __ratchet_fetch_queue = [];
+var __ratchet_fetch_pending = {};
+var __ratchet_fetch_next_id = 1;
+
+function __ratchet_utf8_encode(text) {
+    var bytes = [];
+    for (var i = 0; i < text.length; i++) {
+        var code = text.codePointAt(i);
+        if (code > 0xffff) {
+            i++;
         }
-        args[0].to_string(context)?.to_std_string_escaped()
-    };
-
-    // Convert params to JsonValue if provided
-    let params_json = if args.len() > 1 && !args[1].is_null() && !args[1].is_undefined() {
-        // Set a temporary variable in the global context
-        context.global_object().set(
-            PropertyKey::from(JsString::from("__temp_params")),
-            args[1].clone(),
-            true,
-            context,
-        )?;
-
-        // Stringify it using JSON.stringify
-        let params_json_str = context.eval(Source::from_bytes("JSON.stringify(__temp_params)"))?;
-        let params_str = params_json_str.to_string(context)?.to_std_string_escaped();
-
-        // Parse the JSON string into a JsonValue
-        serde_json::from_str::<JsonValue>(&params_str).ok()
-    } else {
-        None
-    };
-
-    // Convert body to JsonValue if provided
-    let body_json = if args.len() > 2 && !args[2].is_null() && !args[2].is_undefined() {
-        // Set a temporary variable in the global context
-        context.global_object().set(
-            PropertyKey::from(JsString::from("__temp_body")),
-            args[2].clone(),
-            true,
-            context,
-        )?;
-
-        // Stringify it using JSON.stringify
-        let body_json_str = context.eval(Source::from_bytes("JSON.stringify(__temp_body)"))?;
-        let body_str = body_json_str.to_string(context)?.to_std_string_escaped();
-
-        // Parse the JSON string into a JsonValue
-        serde_json::from_str::<JsonValue>(&body_str).ok()
-    } else {
-        None
-    };
-
-    // Make the HTTP call
-    let result = match call_http(&url, params_json.as_ref(), body_json.as_ref()).await {
-        Ok(result) => result,
-        Err(e) => {
-            return Err(JsNativeError::error().with_message(format!("HTTP error: {}", e)).into());
+        if (code < 0x80) {
+            bytes.push(code);
+        } else if (code < 0x800) {
+            bytes.push(0xc0 | (code >> 6), 0x80 | (code & 0x3f));
+        } else if (code < 0x10000) {
+            bytes.push(0xe0 | (code >> 12), 0x80 | ((code >> 6) & 0x3f), 0x80 | (code & 0x3f));
+        } else {
+            bytes.push(0xf0 | (code >> 18), 0x80 | ((code >> 12) & 0x3f), 0x80 | ((code >> 6) & 0x3f), 0x80 | (code & 0x3f));
         }
-    };
-
-    // Convert the result back to a JS value
-    let result_str = match serde_json::to_string(&result) {
-        Ok(s) => s,
-        Err(e) => {
-            return Err(JsNativeError::error()
-                .with_message(format!("Failed to serialize result: {}", e))
-                .into());
+    }
+    return new Uint8Array(bytes);
+}
+
+function __ratchet_utf8_decode(bytes) {
+    var text = "";
+    var i = 0;
+    while (i < bytes.length) {
+        var byte = bytes[i++];
+        var code;
+        if (byte < 0x80) {
+            code = byte;
+        } else if (byte >= 0xf0) {
+            code = ((byte & 0x07) << 18) | ((bytes[i++] & 0x3f) << 12) | ((bytes[i++] & 0x3f) << 6) | (bytes[i++] & 0x3f);
+        } else if (byte >= 0xe0) {
+            code = ((byte & 0x0f) << 12) | ((bytes[i++] & 0x3f) << 6) | (bytes[i++] & 0x3f);
+        } else if (byte >= 0xc0) {
+            code = ((byte & 0x1f) << 6) | (bytes[i++] & 0x3f);
+        } else {
+            code = 0xfffd;
         }
-    };
+        text += String.fromCodePoint(code);
+    }
+    return text;
+}
 
-    // Parse the JSON string into a JS value
-    context.eval(Source::from_bytes(&format!(
-        "JSON.parse('{}')",
-        result_str.replace('\'', "\\'")
-    )))
+function __ratchet_base64_to_bytes(base64) {
+    var alphabet = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    var clean = base64.replace(/=+$/, "");
+    var bytes = new Uint8Array(Math.floor(clean.length * 3 / 4));
+    var buffer = 0;
+    var bits = 0;
+    var index = 0;
+    for (var i = 0; i < clean.length; i++) {
+        buffer = ((buffer << 6) | alphabet.indexOf(clean.charAt(i))) & 0xffff;
+        bits += 6;
+        if (bits >= 8) {
+            bits -= 8;
+            bytes[index++] = (buffer >> bits) & 0xff;
+        }
+    }
+    return bytes;
 }
 
-/// Register the fetch function in the JavaScript context
-pub fn register_fetch(context: &mut Context) -> Result<(), JsError> {
-    // Create a direct JavaScript implementation
-    // that will handle the fetch API by calling into Rust
-    context.eval(Source::from_bytes(
-        r#"
-        // Define the fetch API
-        function fetch(url, params, body) {
-            if (typeof url !== 'string') {
-                throw new Error('URL must be a string');
+class Headers {
+    constructor(init) {
+        this._list = [];
+        if (init instanceof Headers) {
+            init._list.forEach(function(entry) { this.append(entry[0], entry[1]); }, this);
+        } else if (Array.isArray(init)) {
+            init.forEach(function(pair) { this.append(pair[0], pair[1]); }, this);
+        } else if (init !== undefined && init !== null && typeof init === 'object') {
+            Object.keys(init).forEach(function(name) { this.append(name, init[name]); }, this);
+        }
+    }
+
+    append(name, value) {
+        this._list.push([String(name).toLowerCase(), String(value)]);
+    }
+
+    set(name, value) {
+        this.delete(name);
+        this.append(name, value);
+    }
+
+    get(name) {
+        var key = String(name).toLowerCase();
+        var values = this._list.filter(function(entry) { return entry[0] === key; })
+            .map(function(entry) { return entry[1]; });
+        return values.length > 0 ? values.join(", ") : null;
+    }
+
+    has(name) {
+        return this.get(name) !== null;
+    }
+
+    delete(name) {
+        var key = String(name).toLowerCase();
+        this._list = this._list.filter(function(entry) { return entry[0] !== key; });
+    }
+
+    entries() {
+        var names = [];
+        this._list.forEach(function(entry) {
+            if (names.indexOf(entry[0]) === -1) {
+                names.push(entry[0]);
             }
-            
-            // Convert params and body to strings so they can be parsed in Rust
-            let paramsStr = params ? JSON.stringify(params) : null;
-            let bodyStr = body ? (typeof body === 'string' ? body : JSON.stringify(body)) : null;
-            
-            // This will be processed by the Rust function call_http directly
-            // We'll capture these values in the execute_js_file function
-            __fetch_url = url;
-            __fetch_params = paramsStr;
-            __fetch_body = bodyStr;
-            
-            // Return a dummy response - we'll replace this with the actual HTTP call in Rust
-            return { 
-                _internal_fetch_call: true,
-                url: url,
-                params: params,
-                body: body
-            };
+        });
+        names.sort();
+        return names.map(function(name) { return [name, this.get(name)]; }, this)[Symbol.iterator]();
+    }
+
+    keys() {
+        return Array.from(this.entries(), function(entry) { return entry[0]; })[Symbol.iterator]();
+    }
+
+    values() {
+        return Array.from(this.entries(), function(entry) { return entry[1]; })[Symbol.iterator]();
+    }
+
+    forEach(callback, thisArg) {
+        Array.from(this.entries()).forEach(function(entry) {
+            callback.call(thisArg, entry[1], entry[0], this);
+        }, this);
+    }
+
+    [Symbol.iterator]() {
+        return this.entries();
+    }
+}
+
+function __ratchet_body_from(value, headers) {
+    if (value === undefined || value === null) {
+        return null;
+    }
+    if (typeof value === 'string') {
+        if (!headers.has('content-type')) {
+            headers.set('content-type', 'text/plain;charset=UTF-8');
+        }
+        return { text: value };
+    }
+    if (value instanceof ArrayBuffer) {
+        return { bytes: new Uint8Array(value.slice(0)) };
+    }
+    if (ArrayBuffer.isView(value)) {
+        return { bytes: new Uint8Array(value.buffer.slice(value.byteOffset, value.byteOffset + value.byteLength)) };
+    }
+    // Plain objects are sent as JSON, as the original Ratchet fetch did
+    if (!headers.has('content-type')) {
+        headers.set('content-type', 'application/json');
+    }
+    return { text: JSON.stringify(value) };
+}
+
+function __ratchet_consume_body(owner) {
+    if (owner._bodyUsed) {
+        return Promise.reject(new TypeError('Body has already been consumed'));
+    }
+    owner._bodyUsed = true;
+    return Promise.resolve(owner._body);
+}
+
+function __ratchet_body_text(body) {
+    if (body === null) {
+        return "";
+    }
+    if (body.text !== undefined) {
+        return body.text;
+    }
+    return __ratchet_utf8_decode(__ratchet_body_bytes(body));
+}
+
+function __ratchet_body_bytes(body) {
+    if (body === null) {
+        return new Uint8Array(0);
+    }
+    if (body.bytes === undefined) {
+        body.bytes = body.base64 !== undefined
+            ? __ratchet_base64_to_bytes(body.base64)
+            : __ratchet_utf8_encode(body.text);
+    }
+    return body.bytes;
+}
+
+var __ratchet_body_mixin = {
+    text: function() {
+        return __ratchet_consume_body(this).then(__ratchet_body_text);
+    },
+    json: function() {
+        return this.text().then(function(text) { return JSON.parse(text); });
+    },
+    arrayBuffer: function() {
+        return __ratchet_consume_body(this).then(function(body) {
+            var bytes = __ratchet_body_bytes(body);
+            return bytes.buffer.slice(bytes.byteOffset, bytes.byteOffset + bytes.byteLength);
+        });
+    }
+};
+
+class Request {
+    constructor(input, init) {
+        init = init || {};
+        var base = input instanceof Request ? input : null;
+
+        this.url = base ? base.url : String(input);
+        this.method = String(init.method || (base ? base.method : 'GET')).toUpperCase();
+        this.headers = new Headers(init.headers !== undefined ? init.headers : (base ? base.headers : undefined));
+        this.redirect = init.redirect || (base ? base.redirect : 'follow');
+        this.signal = init.signal || (base ? base.signal : null);
+        this._body = init.body !== undefined ? __ratchet_body_from(init.body, this.headers) : (base ? base._body : null);
+        this._bodyUsed = false;
+
+        if (this._body !== null && (this.method === 'GET' || this.method === 'HEAD')) {
+            throw new TypeError('Request with ' + this.method + ' method cannot have a body');
+        }
+    }
+
+    get bodyUsed() {
+        return this._bodyUsed;
+    }
+
+    clone() {
+        return new Request(this);
+    }
+}
+
+class Response {
+    constructor(body, init) {
+        init = init || {};
+        this.status = init.status === undefined ? 200 : init.status;
+        this.statusText = init.statusText === undefined ? "" : String(init.statusText);
+        this.headers = new Headers(init.headers);
+        this.ok = this.status >= 200 && this.status < 300;
+        this.url = "";
+        this.redirected = false;
+        this.type = "default";
+        this._body = body === undefined ? null : __ratchet_body_from(body, this.headers);
+        this._bodyUsed = false;
+    }
+
+    get bodyUsed() {
+        return this._bodyUsed;
+    }
+
+    clone() {
+        if (this._bodyUsed) {
+            throw new TypeError('Response body has already been consumed');
+        }
+        var copy = new Response(null, { status: this.status, statusText: this.statusText, headers: this.headers });
+        copy.url = this.url;
+        copy.redirected = this.redirected;
+        copy.type = this.type;
+        copy._body = this._body;
+        return copy;
+    }
+
+    static json(data, init) {
+        var response = new Response(JSON.stringify(data), init);
+        response.headers.set('content-type', 'application/json');
+        return response;
+    }
+
+    static error() {
+        var response = new Response(null, { status: 0 });
+        response.type = "error";
+        return response;
+    }
+}
+
+Object.assign(Request.prototype, __ratchet_body_mixin);
+Object.assign(Response.prototype, __ratchet_body_mixin);
+
+function fetch(input, init, legacyBody) {
+    var request;
+    try {
+        if (legacyBody !== undefined && legacyBody !== null) {
+            init = Object.assign({}, init, { body: legacyBody });
+        }
+        request = new Request(input, init);
+    } catch (e) {
+        return Promise.reject(e);
+    }
+
+    var id = __ratchet_fetch_next_id++;
+    var body = request._body;
+    return new Promise(function(resolve, reject) {
+        __ratchet_fetch_pending[id] = { resolve: resolve, reject: reject, url: request.url };
+        __ratchet_fetch_queue.push({
+            id: id,
+            url: request.url,
+            method: request.method,
+            headers: request.headers._list,
+            bodyText: body !== null && body.bytes === undefined ? body.text : null,
+            bodyBytes: body !== null && body.bytes !== undefined ? Array.from(body.bytes) : null
+        });
+    });
+}
+
+function __ratchet_settle_fetch(id, outcome) {
+    var pending = __ratchet_fetch_pending[id];
+    delete __ratchet_fetch_pending[id];
+    if (!pending) {
+        return;
+    }
+
+    if (outcome.error !== undefined && outcome.error !== null) {
+        if (outcome.kind === "egress") {
+            pending.reject(new EgressError(outcome.error));
+        } else {
+            pending.reject(new NetworkError(outcome.error));
         }
-    "#,
-    ))?;
+        return;
+    }
 
+    var raw = outcome.response;
+    var response = new Response(null, { status: raw.status, statusText: raw.statusText, headers: raw.headers });
+    response.url = raw.url;
+    response.redirected = raw.url !== pending.url;
+    response.type = "basic";
+    response._body = { text: raw.bodyText, base64: raw.bodyBase64 };
+    pending.resolve(response);
+}
+"#;
+
+/// Register the fetch function in the JavaScript context
+pub fn register_fetch(context: &mut Context) -> Result<(), JsError> {
+    context.eval(Source::from_bytes(FETCH_JS))?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::execution::execute_js_with_content;
+    use ratchet_http::{HttpClient, HttpError, HttpRequest, HttpResponse};
+    use serde_json::{json, Value as JsonValue};
+    use std::sync::Mutex;
+
+    /// Returns a plain-text body and records the raw requests it receives
+    #[derive(Default)]
+    struct TextClient {
+        requests: Mutex<Vec<HttpRequest>>,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpClient for TextClient {
+        async fn call_http(
+            &self,
+            _url: &str,
+            _params: Option<&JsonValue>,
+            _body: Option<&JsonValue>,
+        ) -> Result<JsonValue, HttpError> {
+            unreachable!("fetch uses the raw send interface")
+        }
+
+        async fn send(&self, request: HttpRequest) -> Result<HttpResponse, HttpError> {
+            let url = request.url.clone();
+            self.requests.lock().unwrap().push(request);
+            Ok(HttpResponse {
+                url,
+                status: 201,
+                status_text: "Created".to_string(),
+                headers: vec![
+                    ("Content-Type".to_string(), "text/plain".to_string()),
+                    ("X-Trace".to_string(), "a".to_string()),
+                    ("X-Trace".to_string(), "b".to_string()),
+                ],
+                body: "héllo".as_bytes().to_vec(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_response_exposes_status_headers_and_body() {
+        let code = r#"
+            async function main(input) {
+                const response = await fetch("http://api/greeting");
+                const copy = response.clone();
+                const text = await response.text();
+                const bytes = new Uint8Array(await copy.arrayBuffer());
+                return {
+                    ok: response.ok,
+                    status: response.status,
+                    statusText: response.statusText,
+                    contentType: response.headers.get("content-type"),
+                    trace: response.headers.get("x-trace"),
+                    text: text,
+                    byteLength: bytes.length,
+                    bodyUsed: response.bodyUsed
+                };
+            }
+        "#;
+        let client = TextClient::default();
+
+        let result = execute_js_with_content(code, json!({}), None, None, &client, None)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result,
+            json!({
+                "ok": true,
+                "status": 201,
+                "statusText": "Created",
+                "contentType": "text/plain",
+                "trace": "a, b",
+                "text": "héllo",
+                "byteLength": 6,
+                "bodyUsed": true
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_request_init_is_sent_through_client() {
+        let code = r#"
+            async function main(input) {
+                const headers = new Headers({ "X-Api-Key": "secret" });
+                headers.append("Accept", "application/json");
+                const request = new Request("http://api/items", {
+                    method: "post",
+                    headers: headers,
+                    body: JSON.stringify({ name: input.name })
+                });
+                const response = await fetch(request);
+                return { status: response.status, method: request.method };
+            }
+        "#;
+        let client = TextClient::default();
+
+        let result = execute_js_with_content(code, json!({ "name": "widget" }), None, None, &client, None)
+            .await
+            .unwrap();
+
+        assert_eq!(result, json!({ "status": 201, "method": "POST" }));
+        let requests = client.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, ratchet_http::HttpMethod::Post);
+        assert_eq!(requests[0].header("x-api-key"), Some("secret"));
+        assert_eq!(requests[0].header("content-type"), Some("text/plain;charset=UTF-8"));
+        assert_eq!(requests[0].body.as_deref(), Some(br#"{"name":"widget"}"#.as_slice()));
+    }
+
+    #[tokio::test]
+    async fn test_legacy_object_body_is_sent_as_json() {
+        let code = r#"
+            async function main(input) {
+                await fetch("http://api/items", { method: "PUT" }, { id: 7 });
+                return {};
+            }
+        "#;
+        let client = TextClient::default();
+
+        execute_js_with_content(code, json!({}), None, None, &client, None)
+            .await
+            .unwrap();
+
+        let requests = client.requests.lock().unwrap();
+        assert_eq!(requests[0].header("content-type"), Some("application/json"));
+        assert_eq!(requests[0].body.as_deref(), Some(br#"{"id":7}"#.as_slice()));
+    }
+
+    #[tokio::test]
+    async fn test_get_request_with_body_is_rejected() {
+        let code = r#"
+            async function main(input) {
+                try {
+                    await fetch("http://api/items", { body: "nope" });
+                    return { rejected: false };
+                } catch (e) {
+                    return { rejected: e instanceof TypeError };
+                }
+            }
+        "#;
+        let client = TextClient::default();
+
+        let result = execute_js_with_content(code, json!({}), None, None, &client, None)
+            .await
+            .unwrap();
+
+        assert_eq!(result, json!({ "rejected": true }));
+        assert!(client.requests.lock().unwrap().is_empty());
+    }
+}
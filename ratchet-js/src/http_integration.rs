@@ -1,271 +1,111 @@
-use crate::error_handling::parse_js_error;
 use crate::JsExecutionError;
-use boa_engine::{property::PropertyKey, Context as BoaContext, JsString, Source};
-use serde_json::Value as JsonValue;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use boa_engine::{Context as BoaContext, Source};
+use ratchet_http::{HttpError, HttpMethod, HttpRequest, HttpResponse};
+use serde::Deserialize;
+use serde_json::json;
 use tracing::debug;
 
-/// Check if fetch API was called and extract parameters
-pub fn check_fetch_call(
-    context: &mut BoaContext,
-) -> Result<Option<(String, Option<JsonValue>, Option<JsonValue>)>, JsExecutionError> {
-    debug!("Checking for fetch API calls");
-    let fetch_marker = context
-        .eval(Source::from_bytes(
-            "typeof __fetch_url === 'string' && __fetch_url !== null",
-        ))
-        .map_err(|e| JsExecutionError::ExecutionError(e.to_string()))?;
-
-    if !fetch_marker.as_boolean().unwrap_or(false) {
-        return Ok(None);
-    }
-
-    debug!("Detected fetch API call, extracting parameters");
-
-    // Get URL
-    let url_js = context
-        .eval(Source::from_bytes("__fetch_url"))
-        .map_err(|e| JsExecutionError::ExecutionError(e.to_string()))?;
-
-    let url = url_js
-        .to_string(context)
-        .map_err(|e| JsExecutionError::ExecutionError(e.to_string()))?
-        .to_std_string_escaped();
-
-    // Get parameters
-    let params_js = context
-        .eval(Source::from_bytes("__fetch_params"))
-        .map_err(|e| JsExecutionError::ExecutionError(e.to_string()))?;
+/// A fetch request queued by JavaScript and waiting to be dispatched
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchRequest {
+    /// Identifier of the pending promise in `__ratchet_fetch_pending`
+    pub id: u64,
 
-    let params = if !params_js.is_null() && !params_js.is_undefined() {
-        let params_str = params_js
-            .to_string(context)
-            .map_err(|e| JsExecutionError::ExecutionError(e.to_string()))?
-            .to_std_string_escaped();
+    /// Request URL
+    pub url: String,
 
-        Some(serde_json::from_str(&params_str).map_err(|e| JsExecutionError::InvalidOutputFormat(e.to_string()))?)
-    } else {
-        None
-    };
-
-    // Get body
-    let body_js = context
-        .eval(Source::from_bytes("__fetch_body"))
-        .map_err(|e| JsExecutionError::ExecutionError(e.to_string()))?;
+    /// Request method
+    pub method: String,
 
-    let body = if !body_js.is_null() && !body_js.is_undefined() {
-        let body_str = body_js
-            .to_string(context)
-            .map_err(|e| JsExecutionError::ExecutionError(e.to_string()))?
-            .to_std_string_escaped();
+    /// Request headers as name/value pairs
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
 
-        // Try to parse as JSON first, if that fails treat as a string
-        Some(match serde_json::from_str::<JsonValue>(&body_str) {
-            Ok(json_val) => json_val,
-            Err(_) => JsonValue::String(body_str),
-        })
-    } else {
-        None
-    };
+    /// Request body supplied as a string
+    #[serde(default)]
+    pub body_text: Option<String>,
 
-    Ok(Some((url, params, body)))
+    /// Request body supplied as an ArrayBuffer or typed array
+    #[serde(default)]
+    pub body_bytes: Option<Vec<u8>>,
 }
 
-/// Handle HTTP fetch processing and inject result back into context
-pub async fn handle_fetch_processing(
-    context: &mut BoaContext,
-    func: &boa_engine::JsValue,
-    input_arg: &boa_engine::JsValue,
-    http_manager: &impl ratchet_http::HttpClient,
-    url: String,
-    params: Option<JsonValue>,
-    body: Option<JsonValue>,
-) -> Result<boa_engine::JsValue, JsExecutionError> {
-    debug!("Making HTTP call to: {}", url);
-
-    // Perform the HTTP call
-    let http_result = http_manager
-        .call_http(&url, params.as_ref(), body.as_ref())
-        .await
-        .map_err(|e| JsExecutionError::ExecutionError(format!("HTTP error: {}", e)))?;
-
-    debug!("Injecting HTTP result back into JavaScript context");
-
-    // Store the HTTP result in a global variable
-    context
-        .global_object()
-        .set(
-            PropertyKey::from(JsString::from("__http_result")),
-            context
-                .eval(Source::from_bytes(&format!(
-                    "({})",
-                    serde_json::to_string(&http_result).map_err(|e| {
-                        JsExecutionError::ExecutionError(format!("Failed to serialize HTTP result: {}", e))
-                    })?
-                )))
-                .map_err(|e| JsExecutionError::ExecutionError(format!("Failed to parse HTTP result JSON: {}", e)))?,
-            true,
-            context,
-        )
-        .map_err(|e| JsExecutionError::ExecutionError(format!("Failed to set HTTP result: {}", e)))?;
+impl FetchRequest {
+    /// Convert into a request for [`ratchet_http::HttpClient::send`]
+    pub fn to_http_request(&self) -> Result<HttpRequest, HttpError> {
+        let method: HttpMethod = self.method.parse()?;
+        let mut request = HttpRequest::new(method, self.url.clone());
+        request.headers = self.headers.clone();
+        request.body = match (&self.body_bytes, &self.body_text) {
+            (Some(bytes), _) => Some(bytes.clone()),
+            (None, Some(text)) => Some(text.clone().into_bytes()),
+            (None, None) => None,
+        };
+        Ok(request)
+    }
+}
 
-    // Replace the fetch function to return the stored result and throw appropriate errors
-    context
+/// Take every fetch request queued since the last call
+pub fn drain_fetch_queue(context: &mut BoaContext) -> Result<Vec<FetchRequest>, JsExecutionError> {
+    let queued = context
         .eval(Source::from_bytes(
-            r#"
-            fetch = function(url, params, body) {
-                var response = __http_result;
-                
-                // Check if response is OK, throw appropriate errors if not
-                if (!response.ok) {
-                    var status = response.status || 0;
-                    var statusText = response.statusText || "Unknown Status";
-                    
-                    // Map status codes to appropriate error types
-                    if (status === 401) {
-                        throw new AuthenticationError("HTTP " + status + ": " + statusText);
-                    } else if (status === 403) {
-                        throw new AuthorizationError("HTTP " + status + ": " + statusText);
-                    } else if (status === 429) {
-                        throw new RateLimitError("HTTP " + status + ": " + statusText);
-                    } else if (status >= 500 && status < 600) {
-                        throw new ServiceUnavailableError("HTTP " + status + ": " + statusText);
-                    } else if (status >= 400 && status < 500) {
-                        throw new HttpError(status, "HTTP " + status + ": " + statusText);
-                    } else {
-                        throw new NetworkError("HTTP " + status + ": " + statusText);
-                    }
-                }
-                
-                return response;
-            };
-        "#,
+            "typeof __ratchet_fetch_queue === 'undefined' ? '[]' : JSON.stringify(__ratchet_fetch_queue.splice(0, __ratchet_fetch_queue.length))",
         ))
-        .map_err(|e| JsExecutionError::ExecutionError(format!("Failed to replace fetch function: {}", e)))?;
+        .map_err(|e| JsExecutionError::ExecutionError(format!("Failed to read fetch queue: {}", e)))?;
 
-    debug!("Re-calling JavaScript function with updated fetch");
-
-    // Re-call the JavaScript function now that fetch will return the real result
-    let result = func
-        .as_callable()
-        .ok_or_else(|| JsExecutionError::ExecutionError("Function is not callable".to_string()))?
-        .call(&boa_engine::JsValue::undefined(), &[input_arg.clone()], context)
-        .map_err(|e| {
-            let error_message = e.to_string();
-            // Try to parse as a typed JS error first
-            if error_message.contains("Error:") {
-                let parsed_error = parse_js_error(&error_message);
-                JsExecutionError::TypedJsError(parsed_error)
-            } else {
-                JsExecutionError::ExecutionError(error_message)
-            }
-        })?;
+    let queued_str = queued
+        .to_string(context)
+        .map_err(|e| JsExecutionError::ExecutionError(e.to_string()))?
+        .to_std_string_escaped();
 
-    debug!("Clearing fetch state variables");
+    let requests: Vec<FetchRequest> =
+        serde_json::from_str(&queued_str).map_err(|e| JsExecutionError::InvalidOutputFormat(e.to_string()))?;
 
-    // Clear the fetch state
-    context
-        .eval(Source::from_bytes(
-            "__fetch_url = null; __fetch_params = null; __fetch_body = null; __http_result = null;",
-        ))
-        .map_err(|e| JsExecutionError::ExecutionError(e.to_string()))?;
+    if !requests.is_empty() {
+        debug!("Drained {} fetch request(s) from the JavaScript queue", requests.len());
+    }
 
-    Ok(result)
+    Ok(requests)
 }
 
-/// Handle HTTP fetch processing with context and re-execute function
-pub async fn handle_fetch_processing_with_context(
+/// Resolve or reject the promise belonging to a dispatched fetch request
+pub fn settle_fetch(
     context: &mut BoaContext,
-    func: &boa_engine::JsValue,
-    input_arg: &boa_engine::JsValue,
-    context_arg: &boa_engine::JsValue,
-    http_manager: &impl ratchet_http::HttpClient,
-    url: String,
-    params: Option<JsonValue>,
-    body: Option<JsonValue>,
-) -> Result<boa_engine::JsValue, JsExecutionError> {
-    debug!("Processing HTTP fetch request for URL: {}", url);
-
-    // Make the actual HTTP request
-    let response_result = http_manager
-        .call_http(&url, params.as_ref(), body.as_ref())
-        .await
-        .map_err(|e| JsExecutionError::ExecutionError(format!("HTTP request failed: {}", e)))?;
-
-    debug!("HTTP request completed, setting result");
-
-    // Set the HTTP response result in the JavaScript context
-    crate::conversion::set_js_value(
-        context,
-        "__http_result",
-        &serde_json::to_value(response_result)
-            .map_err(|e| JsExecutionError::ExecutionError(format!("Failed to serialize HTTP result: {}", e)))?,
-    )
-    .map_err(|e| JsExecutionError::ExecutionError(format!("Failed to parse HTTP result JSON: {}", e)))?;
-
-    // Replace the fetch function to return the stored result and throw appropriate errors
-    context
-        .eval(Source::from_bytes(
-            r#"
-            fetch = function(url, params, body) {
-                var response = __http_result;
-                
-                // Check if response is OK, throw appropriate errors if not
-                if (!response.ok) {
-                    var status = response.status || 0;
-                    var statusText = response.statusText || "Unknown Status";
-                    
-                    // Map status codes to appropriate error types
-                    if (status === 401) {
-                        throw new AuthenticationError("HTTP " + status + ": " + statusText);
-                    } else if (status === 403) {
-                        throw new AuthorizationError("HTTP " + status + ": " + statusText);
-                    } else if (status === 429) {
-                        throw new RateLimitError("HTTP " + status + ": " + statusText);
-                    } else if (status >= 500 && status < 600) {
-                        throw new ServiceUnavailableError("HTTP " + status + ": " + statusText);
-                    } else if (status >= 400 && status < 500) {
-                        throw new HttpError(status, "HTTP " + status + ": " + statusText);
-                    } else {
-                        throw new NetworkError("HTTP " + status + ": " + statusText);
-                    }
-                }
-                
-                return response;
-            };
-        "#,
-        ))
-        .map_err(|e| JsExecutionError::ExecutionError(format!("Failed to replace fetch function: {}", e)))?;
-
-    debug!("Re-calling JavaScript function with updated fetch and context");
-
-    // Re-call the JavaScript function with both input and context now that fetch will return the real result
-    let result = func
-        .as_callable()
-        .ok_or_else(|| JsExecutionError::ExecutionError("Function is not callable".to_string()))?
-        .call(
-            &boa_engine::JsValue::undefined(),
-            &[input_arg.clone(), context_arg.clone()],
-            context,
-        )
-        .map_err(|e| {
-            let error_message = e.to_string();
-            // Try to parse as a typed JS error first
-            if error_message.contains("Error:") {
-                let parsed_error = parse_js_error(&error_message);
-                JsExecutionError::TypedJsError(parsed_error)
-            } else {
-                JsExecutionError::ExecutionError(error_message)
+    request_id: u64,
+    result: Result<HttpResponse, HttpError>,
+) -> Result<(), JsExecutionError> {
+    let outcome = match result {
+        Ok(response) => json!({
+            "response": {
+                "url": &response.url,
+                "status": response.status,
+                "statusText": &response.status_text,
+                "headers": &response.headers,
+                "bodyText": response.text(),
+                "bodyBase64": BASE64.encode(&response.body),
             }
-        })?;
+        }),
+        Err(e) => {
+            debug!("Fetch request {} failed: {}", request_id, e);
+            let kind = match e {
+                HttpError::EgressDenied { .. } => "egress",
+                _ => "network",
+            };
+            json!({ "error": e.to_string(), "kind": kind })
+        }
+    };
 
-    debug!("Clearing fetch state variables");
+    let outcome_str = serde_json::to_string(&outcome)
+        .map_err(|e| JsExecutionError::ExecutionError(format!("Failed to serialize HTTP result: {}", e)))?;
 
-    // Clear the fetch state
     context
-        .eval(Source::from_bytes(
-            "__fetch_url = null; __fetch_params = null; __fetch_body = null; __http_result = null;",
-        ))
-        .map_err(|e| JsExecutionError::ExecutionError(e.to_string()))?;
+        .eval(Source::from_bytes(&format!(
+            "__ratchet_settle_fetch({}, {})",
+            request_id, outcome_str
+        )))
+        .map_err(|e| JsExecutionError::ExecutionError(format!("Failed to settle fetch request: {}", e)))?;
 
-    Ok(result)
+    Ok(())
 }
@@ -0,0 +1,100 @@
+//! Cooperative interruption of running scripts
+//!
+//! Boa cannot be stopped from another thread, so cancellation is cooperative: task
+//! scripts are evaluated in slices that yield to the executor, and the event loop
+//! checks the interrupt of the current execution between job queue passes and
+//! while fetch requests are in flight. Promise continuations and callbacks invoked
+//! by builtins run to completion once started; callers that need a hard stop for
+//! those must kill the worker running them.
+
+use crate::JsExecutionError;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often in-flight fetch requests check for an interrupt
+const INTERRUPT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+tokio::task_local! {
+    static CURRENT_INTERRUPT: ExecutionInterrupt;
+}
+
+/// Shared flag used to ask a running execution to stop
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionInterrupt {
+    interrupted: Arc<AtomicBool>,
+}
+
+impl ExecutionInterrupt {
+    /// Create a new, untriggered interrupt
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask the execution to stop at its next safe point
+    pub fn interrupt(&self) {
+        self.interrupted.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether the execution has been asked to stop
+    pub fn is_interrupted(&self) -> bool {
+        self.interrupted.load(Ordering::SeqCst)
+    }
+
+    /// Resolve once the execution has been asked to stop
+    pub async fn interrupted(&self) {
+        while !self.is_interrupted() {
+            tokio::time::sleep(INTERRUPT_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Run `future` with this interrupt as the current one
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        CURRENT_INTERRUPT.scope(self.clone(), future).await
+    }
+}
+
+/// Fail with [`JsExecutionError::Cancelled`] if the current execution was interrupted
+pub(crate) fn check_interrupt() -> Result<(), JsExecutionError> {
+    let interrupted = CURRENT_INTERRUPT
+        .try_with(ExecutionInterrupt::is_interrupted)
+        .unwrap_or(false);
+    if interrupted {
+        Err(JsExecutionError::Cancelled)
+    } else {
+        Ok(())
+    }
+}
+
+/// Resolve once the current execution is interrupted; never resolves outside a scope
+pub(crate) async fn interrupted() {
+    match CURRENT_INTERRUPT.try_with(ExecutionInterrupt::clone) {
+        Ok(interrupt) => interrupt.interrupted().await,
+        Err(_) => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_check_interrupt_uses_current_scope() {
+        assert!(check_interrupt().is_ok());
+
+        let interrupt = ExecutionInterrupt::new();
+        interrupt
+            .scope(async {
+                assert!(check_interrupt().is_ok());
+            })
+            .await;
+
+        interrupt.interrupt();
+        interrupt
+            .scope(async {
+                assert!(matches!(check_interrupt(), Err(JsExecutionError::Cancelled)));
+            })
+            .await;
+    }
+}
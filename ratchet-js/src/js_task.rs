@@ -1,4 +1,7 @@
-use crate::{ExecutionContext, JsExecutionError, JsTask};
+use crate::{
+    ConsoleCapture, EgressPolicy, ExecutionContext, ExecutionInterrupt, JsExecutionError, JsTask, ProgressReporter,
+    ResourceLimits, TaskCalls, TaskSecrets,
+};
 use serde_json::Value as JsonValue;
 use thiserror::Error;
 use tracing::debug;
@@ -23,6 +26,16 @@ pub enum JsTaskError {
 pub struct JsTaskRunner {
     #[allow(dead_code)]
     http_enabled: bool,
+    limits: ResourceLimits,
+    interrupt: Option<ExecutionInterrupt>,
+    progress: Option<ProgressReporter>,
+    console: Option<ConsoleCapture>,
+    secrets: Option<TaskSecrets>,
+    calls: Option<TaskCalls>,
+    egress: EgressPolicy,
+    task_egress: Option<EgressPolicy>,
+    http_client: Option<ratchet_http::HttpClientState>,
+    task_http_policy: Option<JsonValue>,
 }
 
 impl Default for JsTaskRunner {
@@ -36,9 +49,106 @@ impl JsTaskRunner {
     pub fn new() -> Self {
         Self {
             http_enabled: cfg!(feature = "http"),
+            limits: ResourceLimits::default(),
+            interrupt: None,
+            progress: None,
+            console: None,
+            secrets: None,
+            calls: None,
+            egress: EgressPolicy::default(),
+            task_egress: None,
+            http_client: None,
+            task_http_policy: None,
         }
     }
 
+    /// Enforce resource limits on every execution
+    pub fn with_limits(mut self, limits: ResourceLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Resource limits applied by this runner
+    pub fn limits(&self) -> &ResourceLimits {
+        &self.limits
+    }
+
+    /// Allow executions started by this runner to be cancelled through `interrupt`
+    pub fn with_interrupt(mut self, interrupt: ExecutionInterrupt) -> Self {
+        self.interrupt = Some(interrupt);
+        self
+    }
+
+    /// Deliver progress reported by executions started by this runner to `reporter`
+    pub fn with_progress(mut self, reporter: ProgressReporter) -> Self {
+        self.progress = Some(reporter);
+        self
+    }
+
+    /// Collect console output of executions started by this runner in `capture`
+    pub fn with_console(mut self, capture: ConsoleCapture) -> Self {
+        self.console = Some(capture);
+        self
+    }
+
+    /// Expose `secrets` to executions started by this runner and redact them from their output
+    pub fn with_secrets(mut self, secrets: TaskSecrets) -> Self {
+        self.secrets = Some(secrets);
+        self
+    }
+
+    /// Let executions started by this runner call other tasks through `calls`
+    pub fn with_calls(mut self, calls: TaskCalls) -> Self {
+        self.calls = Some(calls);
+        self
+    }
+
+    /// Restrict where `fetch` may connect, replacing the default policy that only
+    /// refuses private address ranges
+    pub fn with_egress_policy(mut self, policy: EgressPolicy) -> Self {
+        self.egress = policy;
+        self
+    }
+
+    /// Also apply the egress policy a task declares; requests must pass both policies
+    pub fn with_task_egress_policy(mut self, policy: EgressPolicy) -> Self {
+        self.task_egress = Some(policy);
+        self
+    }
+
+    /// Share `state`'s response cache and circuit breakers with `fetch` calls of
+    /// executions started by this runner
+    pub fn with_http_client(mut self, state: ratchet_http::HttpClientState) -> Self {
+        self.http_client = Some(state);
+        self
+    }
+
+    /// Apply the caching, retry and circuit breaker settings a task declares on
+    /// top of the shared HTTP client policy
+    pub fn with_task_http_policy(mut self, overrides: JsonValue) -> Self {
+        self.task_http_policy = Some(overrides);
+        self
+    }
+
+    /// HTTP client for `fetch`, bound to this runner's egress policies
+    #[cfg(feature = "http")]
+    fn http_manager(&self) -> Result<ratchet_http::HttpManager, JsTaskError> {
+        let invalid = |e: ratchet_http::HttpError| JsTaskError::ConfigError(e.to_string());
+        let mut manager = ratchet_http::HttpManager::new()
+            .with_egress_policy(&self.egress)
+            .map_err(invalid)?;
+        if let Some(policy) = &self.task_egress {
+            manager = manager.with_egress_policy(policy).map_err(invalid)?;
+        }
+        if let Some(state) = &self.http_client {
+            manager = manager.with_client_state(state.clone());
+        }
+        if let Some(overrides) = &self.task_http_policy {
+            manager = manager.with_policy_overrides(overrides).map_err(invalid)?;
+        }
+        Ok(manager)
+    }
+
     /// Execute a JavaScript task with input data
     pub async fn execute_task(
         &self,
@@ -50,22 +160,22 @@ impl JsTaskRunner {
 
         // Create HTTP manager if HTTP feature is enabled
         #[cfg(feature = "http")]
-        let http_manager = ratchet_http::HttpManager::new();
+        let http_manager = self.http_manager()?;
 
         #[cfg(not(feature = "http"))]
         let http_manager = ();
 
         // Execute the task
-        let result = crate::execution::execute_js_with_content(
+        let execution = crate::execution::execute_js_with_limits(
             &task.content,
             input_data,
             task.input_schema.as_ref(),
             task.output_schema.as_ref(),
             &http_manager,
             execution_context.as_ref(),
-        )
-        .await
-        .map_err(JsTaskError::from)?;
+            &self.limits,
+        );
+        let result = self.run_interruptible(execution).await?;
 
         Ok(result)
     }
@@ -83,25 +193,60 @@ impl JsTaskRunner {
 
         // Create HTTP manager if HTTP feature is enabled
         #[cfg(feature = "http")]
-        let http_manager = ratchet_http::HttpManager::new();
+        let http_manager = self.http_manager()?;
 
         #[cfg(not(feature = "http"))]
         let http_manager = ();
 
         // Execute the code
-        let result = crate::execution::execute_js_with_content(
+        let execution = crate::execution::execute_js_with_limits(
             code,
             input_data,
             input_schema,
             output_schema,
             &http_manager,
             execution_context.as_ref(),
-        )
-        .await
-        .map_err(JsTaskError::from)?;
+            &self.limits,
+        );
+        let result = self.run_interruptible(execution).await?;
 
         Ok(result)
     }
+
+    async fn run_interruptible(
+        &self,
+        execution: impl std::future::Future<Output = Result<JsonValue, JsExecutionError>>,
+    ) -> Result<JsonValue, JsTaskError> {
+        let with_secrets = async {
+            match &self.secrets {
+                Some(secrets) => secrets.scope(execution).await.map(|output| secrets.redact_json(output)),
+                None => execution.await,
+            }
+        };
+        let with_calls = async {
+            match &self.calls {
+                Some(calls) => calls.scope(with_secrets).await,
+                None => with_secrets.await,
+            }
+        };
+        let captured = async {
+            match &self.console {
+                Some(capture) => capture.scope(with_calls).await,
+                None => with_calls.await,
+            }
+        };
+        let reported = async {
+            match &self.progress {
+                Some(reporter) => reporter.scope(captured).await,
+                None => captured.await,
+            }
+        };
+        let result = match &self.interrupt {
+            Some(interrupt) => interrupt.scope(reported).await,
+            None => reported.await,
+        };
+        result.map_err(JsTaskError::from)
+    }
 }
 
 /// For unit tests and simple use cases
@@ -166,4 +311,82 @@ mod tests {
         let output = result.unwrap();
         assert_eq!(output["result"], 30);
     }
+
+    #[tokio::test]
+    async fn test_runner_enforces_limits() {
+        let runaway = "function main(input) { while (true) {} }";
+        let runner = JsTaskRunner::new().with_limits(ResourceLimits {
+            max_loop_iterations: Some(1000),
+            ..Default::default()
+        });
+        let result = runner.execute_code(runaway, serde_json::json!({}), None, None, None).await;
+        assert!(matches!(
+            result,
+            Err(JsTaskError::JsExecutionError(JsExecutionError::ResourceExhausted(_)))
+        ));
+
+        let recursive = "function f(n) { return f(n + 1); } function main(input) { return f(0); }";
+        let runner = JsTaskRunner::new().with_limits(ResourceLimits {
+            max_recursion_depth: Some(50),
+            ..Default::default()
+        });
+        let result = runner.execute_code(recursive, serde_json::json!({}), None, None, None).await;
+        assert!(matches!(
+            result,
+            Err(JsTaskError::JsExecutionError(JsExecutionError::ResourceExhausted(_)))
+        ));
+
+        let large = "function main(input) { return { data: 'x'.repeat(1000) }; }";
+        let runner = JsTaskRunner::new().with_limits(ResourceLimits {
+            max_output_bytes: Some(100),
+            ..Default::default()
+        });
+        let result = runner.execute_code(large, serde_json::json!({}), None, None, None).await;
+        assert!(matches!(
+            result,
+            Err(JsTaskError::JsExecutionError(JsExecutionError::ResourceExhausted(_)))
+        ));
+    }
+    #[tokio::test]
+    async fn test_runner_stops_when_interrupted() {
+        let code = "async function main(input) { await Promise.resolve(); return { done: true }; }";
+        let interrupt = ExecutionInterrupt::new();
+        interrupt.interrupt();
+
+        let runner = JsTaskRunner::new().with_interrupt(interrupt);
+        let result = runner.execute_code(code, serde_json::json!({}), None, None, None).await;
+        assert!(matches!(
+            result,
+            Err(JsTaskError::JsExecutionError(JsExecutionError::Cancelled))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_runner_stops_synchronous_loops() {
+        let runaway = "function spin() { while (true) {} } function main(input) { spin(); }";
+        let runner = JsTaskRunner::new().with_limits(ResourceLimits {
+            timeout_seconds: Some(1),
+            ..Default::default()
+        });
+        let result = runner.execute_code(runaway, serde_json::json!({}), None, None, None).await;
+        assert!(matches!(
+            result,
+            Err(JsTaskError::JsExecutionError(JsExecutionError::Timeout(1)))
+        ));
+
+        let interrupt = ExecutionInterrupt::new();
+        let runner = JsTaskRunner::new().with_interrupt(interrupt.clone());
+        let trigger = async {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            interrupt.interrupt();
+        };
+        let (result, _) = tokio::join!(
+            runner.execute_code(runaway, serde_json::json!({}), None, None, None),
+            trigger
+        );
+        assert!(matches!(
+            result,
+            Err(JsTaskError::JsExecutionError(JsExecutionError::Cancelled))
+        ));
+    }
 }
@@ -3,11 +3,18 @@
 //! This crate provides JavaScript execution capabilities using the Boa engine,
 //! including HTTP fetch API integration, error handling, and schema validation.
 
+pub mod calls;
+pub mod console;
 pub mod conversion;
 pub mod error_handling;
+pub mod event_loop;
 pub mod execution;
 pub mod http_integration;
+pub mod interrupt;
 pub mod js_task;
+pub mod limits;
+pub mod progress;
+pub mod secrets;
 pub mod task_loader;
 pub mod types;
 
@@ -15,11 +22,20 @@ pub mod types;
 pub mod fetch;
 
 // Re-export main types for convenience
+pub use calls::{TaskCallError, TaskCallRequest, TaskCaller, TaskCalls, DEFAULT_MAX_CALL_DEPTH};
+pub use console::{ConsoleCapture, ConsoleEntry, ConsoleLevel};
 pub use conversion::{convert_js_result_to_json, prepare_input_argument};
 pub use error_handling::{parse_js_error, register_error_types};
-pub use execution::{execute_js_file, execute_js_with_content};
+pub use event_loop::run_until_settled;
+pub use execution::{execute_js_file, execute_js_with_content, execute_js_with_limits};
+pub use interrupt::ExecutionInterrupt;
 pub use js_task::JsTaskRunner;
-pub use task_loader::{load_and_execute_task, FileSystemTask, TaskLoadError};
+pub use limits::ResourceLimits;
+pub use ratchet_config::domains::http::EgressPolicy;
+pub use ratchet_http::{HttpClientPolicy, HttpClientState, HttpTransport, HttpTransportConfig};
+pub use progress::{ProgressReporter, TaskProgress};
+pub use secrets::TaskSecrets;
+pub use task_loader::{load_and_execute_task, load_and_execute_task_with_limits, FileSystemTask, TaskLoadError};
 pub use types::{ExecutionContext, JsTask};
 
 #[cfg(feature = "http")]
@@ -40,6 +56,10 @@ pub enum JsErrorType {
     #[error("Network error: {0}")]
     NetworkError(String),
 
+    /// A request was refused by an egress policy
+    #[error("Egress denied: {0}")]
+    EgressError(String),
+
     #[error("Validation error: {0}")]
     ValidationError(String),
 
@@ -113,6 +133,15 @@ pub enum JsExecutionError {
     #[error("Typed JavaScript error: {0:?}")]
     TypedJsError(JsErrorType),
 
+    #[error("Resource limit exceeded: {0}")]
+    ResourceExhausted(String),
+
+    #[error("Execution cancelled")]
+    Cancelled,
+
+    #[error("Execution timed out after {0} seconds")]
+    Timeout(u64),
+
     #[error("JavaScript error: {error_type} - {message}")]
     JsError { error_type: JsErrorType, message: String },
 
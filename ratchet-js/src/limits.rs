@@ -0,0 +1,59 @@
+//! Resource limits for JavaScript execution
+//!
+//! Loop, recursion and output limits are enforced here, and the wall-clock limit
+//! by the execution itself. Heap limits cannot be enforced inside the engine and
+//! are left to the worker coordinator.
+
+use crate::JsExecutionError;
+use boa_engine::Context as BoaContext;
+use serde_json::Value as JsonValue;
+
+pub use ratchet_config::domains::execution::ResourceLimits;
+
+/// Messages Boa attaches to `RuntimeLimit` errors
+const RUNTIME_LIMIT_MESSAGES: &[&str] = &[
+    "RuntimeLimit",
+    "Maximum loop iteration limit",
+    "exceeded maximum number of recursive calls",
+    "exceeded maximum call stack length",
+];
+
+/// Configure the engine's runtime limits
+pub fn apply_runtime_limits(context: &mut BoaContext, limits: &ResourceLimits) {
+    let runtime_limits = context.runtime_limits_mut();
+    if let Some(iterations) = limits.max_loop_iterations {
+        runtime_limits.set_loop_iteration_limit(iterations);
+    }
+    if let Some(depth) = limits.max_recursion_depth {
+        runtime_limits.set_recursion_limit(depth);
+    }
+}
+
+/// Reject outputs whose serialized form exceeds `max_output_bytes`
+pub fn check_output_size(output: &JsonValue, limits: &ResourceLimits) -> Result<(), JsExecutionError> {
+    if let Some(max_bytes) = limits.max_output_bytes {
+        let size = serde_json::to_vec(output)
+            .map_err(|e| JsExecutionError::OutputError(e.to_string()))?
+            .len();
+        if size > max_bytes {
+            return Err(JsExecutionError::ResourceExhausted(format!(
+                "output size {} bytes exceeds limit of {} bytes",
+                size, max_bytes
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Turn errors raised by Boa's runtime limits into [`JsExecutionError::ResourceExhausted`]
+pub fn classify_limit_error(error: JsExecutionError) -> JsExecutionError {
+    if matches!(error, JsExecutionError::ResourceExhausted(_)) {
+        return error;
+    }
+    let message = error.to_string();
+    if RUNTIME_LIMIT_MESSAGES.iter().any(|marker| message.contains(marker)) {
+        JsExecutionError::ResourceExhausted(message)
+    } else {
+        error
+    }
+}
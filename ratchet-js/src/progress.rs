@@ -0,0 +1,172 @@
+//! Progress reporting from running scripts
+//!
+//! Tasks call `ratchet.progress(fraction, message)` to report how far they have
+//! got and `ratchet.emit(partialResult)` to publish partial output. Both calls are
+//! queued on the JavaScript side; the event loop forwards them to the
+//! [`ProgressReporter`] of the current execution on each pass, so a script sees
+//! its updates delivered whenever it awaits.
+
+use crate::JsExecutionError;
+use boa_engine::{Context as BoaContext, JsError, Source};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::future::Future;
+use tokio::sync::mpsc;
+use tracing::debug;
+
+/// JavaScript side of the progress API
+const PROGRESS_JS: &str = r#"
+var __ratchet_progress_queue = [];
+var ratchet = typeof ratchet === 'undefined' ? {} : ratchet;
+
+ratchet.progress = function(fraction, message) {
+    var value = Number(fraction);
+    if (!isFinite(value)) {
+        throw new TypeError("ratchet.progress expects a number between 0 and 1");
+    }
+    __ratchet_progress_queue.push({
+        fraction: Math.min(1, Math.max(0, value)),
+        message: message === undefined || message === null ? null : String(message)
+    });
+};
+
+ratchet.emit = function(partialResult) {
+    __ratchet_progress_queue.push({
+        partialOutput: partialResult === undefined ? null : partialResult
+    });
+};
+"#;
+
+tokio::task_local! {
+    static CURRENT_REPORTER: ProgressReporter;
+}
+
+/// A single progress update reported by a task
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskProgress {
+    /// Completed fraction between 0.0 and 1.0
+    #[serde(default)]
+    pub fraction: Option<f32>,
+    /// Human readable description of the current step
+    #[serde(default)]
+    pub message: Option<String>,
+    /// Partial result published with `ratchet.emit`
+    #[serde(default)]
+    pub partial_output: Option<JsonValue>,
+}
+
+/// Receiver side of the progress updates of an execution
+#[derive(Debug, Clone)]
+pub struct ProgressReporter {
+    sender: mpsc::UnboundedSender<TaskProgress>,
+}
+
+impl ProgressReporter {
+    /// Create a reporter together with the channel its updates are delivered on
+    pub fn channel() -> (Self, mpsc::UnboundedReceiver<TaskProgress>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        (Self { sender }, receiver)
+    }
+
+    /// Deliver an update; updates are dropped once the receiver has gone away
+    pub fn report(&self, progress: TaskProgress) {
+        let _ = self.sender.send(progress);
+    }
+
+    /// Run `future` with this reporter as the current one
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        CURRENT_REPORTER.scope(self.clone(), future).await
+    }
+}
+
+/// Register the `ratchet.progress` and `ratchet.emit` functions
+pub fn register_progress(context: &mut BoaContext) -> Result<(), JsError> {
+    context.eval(Source::from_bytes(PROGRESS_JS))?;
+    Ok(())
+}
+
+/// Forward every update queued since the last call to the current reporter
+pub(crate) fn drain_progress_queue(context: &mut BoaContext) -> Result<(), JsExecutionError> {
+    let queued = context
+        .eval(Source::from_bytes(
+            "typeof __ratchet_progress_queue === 'undefined' ? '[]' : JSON.stringify(__ratchet_progress_queue.splice(0, __ratchet_progress_queue.length))",
+        ))
+        .map_err(|e| JsExecutionError::ExecutionError(format!("Failed to read progress queue: {}", e)))?;
+
+    let queued_str = queued
+        .to_string(context)
+        .map_err(|e| JsExecutionError::ExecutionError(e.to_string()))?
+        .to_std_string_escaped();
+
+    let updates: Vec<TaskProgress> =
+        serde_json::from_str(&queued_str).map_err(|e| JsExecutionError::InvalidOutputFormat(e.to_string()))?;
+    if updates.is_empty() {
+        return Ok(());
+    }
+
+    debug!("Drained {} progress update(s) from the JavaScript queue", updates.len());
+    let updates = updates.into_iter().map(|update| TaskProgress {
+        fraction: update.fraction,
+        message: update.message.map(crate::secrets::redact_current),
+        partial_output: update.partial_output.map(crate::secrets::redact_current_json),
+    });
+    // Without a reporter in scope nobody is listening and the updates are dropped
+    let _ = CURRENT_REPORTER.try_with(|reporter| {
+        for update in updates {
+            reporter.report(update);
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::execute_js_with_content;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_progress_and_partial_output_are_reported() {
+        let code = r#"
+            async function main(input) {
+                ratchet.progress(0.5, "halfway");
+                await Promise.resolve();
+                ratchet.emit({ rows: 10 });
+                ratchet.progress(2);
+                return { done: true };
+            }
+        "#;
+        let (reporter, mut updates) = ProgressReporter::channel();
+        let client = ratchet_http::HttpManager::new();
+
+        let result = reporter
+            .scope(execute_js_with_content(code, json!({}), None, None, &client, None))
+            .await
+            .unwrap();
+        assert_eq!(result, json!({ "done": true }));
+
+        let mut received = Vec::new();
+        while let Ok(update) = updates.try_recv() {
+            received.push(update);
+        }
+        assert_eq!(
+            received,
+            vec![
+                TaskProgress {
+                    fraction: Some(0.5),
+                    message: Some("halfway".to_string()),
+                    partial_output: None,
+                },
+                TaskProgress {
+                    partial_output: Some(json!({ "rows": 10 })),
+                    ..Default::default()
+                },
+                TaskProgress {
+                    fraction: Some(1.0),
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,206 @@
+//! Secrets available to running scripts
+//!
+//! Tasks read the secrets declared in their metadata with
+//! `ratchet.secrets.get(name)`. The values of the [`TaskSecrets`] in scope are
+//! installed when the JavaScript context is created, and the same values are
+//! replaced with [`REDACTED`] in console output, progress updates and the task
+//! result so they never reach storage in plaintext.
+
+use boa_engine::{Context as BoaContext, JsError, Source};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+
+/// Replacement for secret values in anything leaving the runtime
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Prefix of the environment variables read by [`TaskSecrets::from_env`]
+pub const SECRET_ENV_PREFIX: &str = "RATCHET_SECRET_";
+
+/// JavaScript side of the secrets API; `__RATCHET_SECRET_VALUES__` is replaced
+/// with a JSON object of the secrets in scope
+const SECRETS_JS: &str = r#"
+var ratchet = typeof ratchet === 'undefined' ? {} : ratchet;
+
+ratchet.secrets = (function(values) {
+    var has = function(name) {
+        return Object.prototype.hasOwnProperty.call(values, String(name));
+    };
+    return Object.freeze({
+        has: has,
+        get: function(name) {
+            if (!has(name)) {
+                throw new Error("Secret '" + String(name) + "' is not available; declare it in the task metadata");
+            }
+            return values[String(name)];
+        }
+    });
+})(__RATCHET_SECRET_VALUES__);
+"#;
+
+tokio::task_local! {
+    static CURRENT_SECRETS: TaskSecrets;
+}
+
+/// Decrypted secrets handed to a single execution
+#[derive(Clone, Default)]
+pub struct TaskSecrets {
+    values: Arc<HashMap<String, String>>,
+}
+
+impl fmt::Debug for TaskSecrets {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaskSecrets").field("names", &self.values.keys().collect::<Vec<_>>()).finish()
+    }
+}
+
+impl TaskSecrets {
+    /// Create a set of secrets from name/value pairs
+    pub fn new(values: HashMap<String, String>) -> Self {
+        Self { values: Arc::new(values) }
+    }
+
+    /// Read the secrets in `names` from `RATCHET_SECRET_<NAME>` environment variables
+    ///
+    /// Used for local runs without a secret store; names without a variable are skipped.
+    pub fn from_env(names: &[String]) -> Self {
+        let values = names
+            .iter()
+            .filter_map(|name| {
+                std::env::var(format!("{}{}", SECRET_ENV_PREFIX, name))
+                    .ok()
+                    .map(|value| (name.clone(), value))
+            })
+            .collect();
+        Self::new(values)
+    }
+
+    /// Whether there are no secrets
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Value of the secret called `name`
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    /// Replace every secret value in `text` with [`REDACTED`]
+    pub fn redact(&self, text: &str) -> String {
+        // Longest first, so a secret containing another one is not left partly visible
+        let mut values: Vec<&String> = self.values.values().filter(|value| !value.is_empty()).collect();
+        values.sort_by_key(|value| std::cmp::Reverse(value.len()));
+
+        let mut redacted = text.to_string();
+        for value in values {
+            if redacted.contains(value.as_str()) {
+                redacted = redacted.replace(value.as_str(), REDACTED);
+            }
+        }
+        redacted
+    }
+
+    /// Replace every secret value in the strings of `value` with [`REDACTED`]
+    pub fn redact_json(&self, value: JsonValue) -> JsonValue {
+        if self.is_empty() {
+            return value;
+        }
+        match value {
+            JsonValue::String(text) => JsonValue::String(self.redact(&text)),
+            JsonValue::Array(items) => JsonValue::Array(items.into_iter().map(|item| self.redact_json(item)).collect()),
+            JsonValue::Object(fields) => JsonValue::Object(
+                fields
+                    .into_iter()
+                    .map(|(key, field)| (key, self.redact_json(field)))
+                    .collect(),
+            ),
+            other => other,
+        }
+    }
+
+    /// All non-empty secret values
+    pub fn values(&self) -> Vec<String> {
+        self.values.values().filter(|value| !value.is_empty()).cloned().collect()
+    }
+
+    /// Run `future` with these secrets as the current ones
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        CURRENT_SECRETS.scope(self.clone(), future).await
+    }
+}
+
+/// Register `ratchet.secrets` with the secrets of the current execution
+pub fn register_secrets(context: &mut BoaContext) -> Result<(), JsError> {
+    let values = CURRENT_SECRETS
+        .try_with(|secrets| serde_json::to_string(secrets.values.as_ref()))
+        .unwrap_or_else(|_| Ok("{}".to_string()))
+        .unwrap_or_else(|_| "{}".to_string());
+    let script = SECRETS_JS.replace("__RATCHET_SECRET_VALUES__", &values);
+    context.eval(Source::from_bytes(&script))?;
+    Ok(())
+}
+
+/// Redact the secrets of the current execution from `text`
+pub(crate) fn redact_current(text: String) -> String {
+    CURRENT_SECRETS.try_with(|secrets| secrets.redact(&text)).unwrap_or(text)
+}
+
+/// Redact the secrets of the current execution from the strings of `value`
+pub(crate) fn redact_current_json(value: JsonValue) -> JsonValue {
+    match CURRENT_SECRETS.try_with(|secrets| secrets.clone()) {
+        Ok(secrets) => secrets.redact_json(value),
+        Err(_) => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConsoleCapture, JsTaskRunner};
+    use serde_json::json;
+
+    fn secrets() -> TaskSecrets {
+        TaskSecrets::new(HashMap::from([("API_KEY".to_string(), "s3cr3t-value".to_string())]))
+    }
+
+    #[test]
+    fn test_redact_json() {
+        let value = json!({"url": "https://api.test/?key=s3cr3t-value", "count": 2, "tags": ["s3cr3t-value"]});
+        assert_eq!(
+            secrets().redact_json(value),
+            json!({"url": "https://api.test/?key=[REDACTED]", "count": 2, "tags": ["[REDACTED]"]})
+        );
+        assert!(!format!("{:?}", secrets()).contains("s3cr3t-value"));
+    }
+
+    #[tokio::test]
+    async fn test_secrets_are_readable_and_redacted() {
+        let code = r#"
+            function main(input) {
+                var key = ratchet.secrets.get("API_KEY");
+                console.log("using key " + key);
+                var missing;
+                try {
+                    ratchet.secrets.get("OTHER");
+                } catch (e) {
+                    missing = e.message;
+                }
+                return { key: key, length: key.length, missing: missing, has: ratchet.secrets.has("API_KEY") };
+            }
+        "#;
+
+        let console = ConsoleCapture::new();
+        let runner = JsTaskRunner::new().with_secrets(secrets()).with_console(console.clone());
+        let result = runner.execute_code(code, json!({}), None, None, None).await.unwrap();
+
+        assert_eq!(result["key"], json!("[REDACTED]"));
+        assert_eq!(result["length"], json!(12));
+        assert_eq!(result["has"], json!(true));
+        assert!(result["missing"].as_str().unwrap().contains("OTHER"));
+
+        let entries = console.take();
+        assert_eq!(entries[0].message, "using key [REDACTED]");
+    }
+}
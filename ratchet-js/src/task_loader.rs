@@ -37,6 +37,19 @@ pub struct TaskMetadata {
     pub core: Option<TaskCore>,
     // Legacy fields for backward compatibility
     pub uuid: Option<String>,
+    /// Per-task overrides of the globally configured resource limits
+    #[serde(default)]
+    pub limits: Option<crate::ResourceLimits>,
+    /// Names of the secrets the task reads through `ratchet.secrets.get`
+    #[serde(default)]
+    pub secrets: Vec<String>,
+    /// Where the task's `fetch` calls may connect, on top of the global policy
+    #[serde(default)]
+    pub egress: Option<crate::EgressPolicy>,
+    /// Caching, retry and circuit breaker settings for the task's `fetch` calls,
+    /// merged over the global HTTP client policy
+    #[serde(default)]
+    pub http: Option<JsonValue>,
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -185,12 +198,43 @@ impl FileSystemTask {
 pub async fn load_and_execute_task<P: AsRef<Path>>(
     path: P,
     input_data: JsonValue,
+) -> Result<JsonValue, Box<dyn std::error::Error + Send + Sync>> {
+    load_and_execute_task_with_limits(path, input_data, &crate::ResourceLimits::default()).await
+}
+
+/// Load and execute a task from filesystem under the given resource limits,
+/// which the task can tighten or relax under `limits` in its metadata
+pub async fn load_and_execute_task_with_limits<P: AsRef<Path>>(
+    path: P,
+    input_data: JsonValue,
+    limits: &crate::ResourceLimits,
 ) -> Result<JsonValue, Box<dyn std::error::Error + Send + Sync>> {
     let fs_task = FileSystemTask::from_fs(path)?;
     fs_task.validate()?;
 
+    let limits = match &fs_task.metadata.limits {
+        Some(task_limits) => limits.with_overrides(task_limits),
+        None => limits.clone(),
+    };
+    limits.check_in_process()?;
+
     let js_task = fs_task.to_js_task();
-    let runner = crate::JsTaskRunner::new();
+    let mut runner = crate::JsTaskRunner::new().with_limits(limits);
+    if let Some(egress) = &fs_task.metadata.egress {
+        runner = runner.with_task_egress_policy(egress.clone());
+    }
+    if let Some(http) = &fs_task.metadata.http {
+        runner = runner.with_task_http_policy(http.clone());
+    }
+
+    // Without a secret store, local runs take declared secrets from the environment
+    if !fs_task.metadata.secrets.is_empty() {
+        let secrets = crate::TaskSecrets::from_env(&fs_task.metadata.secrets);
+        if let Err(e) = ratchet_http::recording::add_recording_redactions(secrets.values()) {
+            tracing::warn!("Failed to register secrets for redaction: {}", e);
+        }
+        runner = runner.with_secrets(secrets);
+    }
 
     let result = runner.execute_task(&js_task, input_data, None).await?;
     Ok(result)
@@ -280,4 +324,59 @@ mod tests {
 
         assert_eq!(result["result"], 8);
     }
+
+    #[tokio::test]
+    async fn test_configured_limits_stop_runaway_tasks() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_task(temp_dir.path()).unwrap();
+        fs::write(
+            temp_dir.path().join("main.js"),
+            "function main(input) { while (true) {} }",
+        )
+        .unwrap();
+
+        let limits = crate::ResourceLimits {
+            max_loop_iterations: Some(10_000),
+            ..Default::default()
+        };
+        let error = load_and_execute_task_with_limits(temp_dir.path(), serde_json::json!({ "a": 1, "b": 2 }), &limits)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("loop iteration limit"), "{}", error);
+    }
+
+    #[test]
+    fn test_metadata_declares_egress_policy() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_task(temp_dir.path()).unwrap();
+        fs::write(
+            temp_dir.path().join("metadata.json"),
+            r#"{"label": "Fetcher", "version": "1.0.0", "egress": {"allow_hosts": ["api.example.com"]}}"#,
+        )
+        .unwrap();
+
+        let task = FileSystemTask::from_fs(temp_dir.path()).unwrap();
+        let egress = task.metadata.egress.unwrap();
+        assert_eq!(egress.allow_hosts, vec!["api.example.com".to_string()]);
+        assert!(egress.block_private_ranges);
+    }
+
+    #[test]
+    fn test_metadata_declares_http_policy() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_task(temp_dir.path()).unwrap();
+        fs::write(
+            temp_dir.path().join("metadata.json"),
+            r#"{"label": "Fetcher", "version": "1.0.0", "http": {"retry": {"max_attempts": 3}}}"#,
+        )
+        .unwrap();
+
+        let task = FileSystemTask::from_fs(temp_dir.path()).unwrap();
+        let policy = crate::HttpClientPolicy::default()
+            .with_overrides(&task.metadata.http.unwrap())
+            .unwrap();
+        assert_eq!(policy.retry.max_attempts, 3);
+        assert_eq!(policy.retry.retry_on_status, vec![429, 502, 503, 504]);
+        assert!(!policy.cache.enabled);
+    }
 }
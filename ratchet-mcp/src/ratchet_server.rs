@@ -18,7 +18,7 @@ use crate::axum_mcp_lib::{
 };
 
 // Import Ratchet's execution types
-use ratchet_api_types::{ApiId, ExecutionStatus as ApiExecutionStatus, PaginationInput};
+use ratchet_api_types::{ApiId, ExecutionLogLevel, ExecutionStatus as ApiExecutionStatus, PaginationInput};
 use ratchet_interfaces::logging::StructuredLogger;
 use ratchet_interfaces::{ExecutionFilters, JobFilters, RepositoryFactory, ScheduleFilters};
 
@@ -104,6 +104,16 @@ impl RatchetToolRegistry {
                     "execution_id": {
                         "type": "string",
                         "description": "ID of the execution to get logs for"
+                    },
+                    "level": {
+                        "type": "string",
+                        "enum": ["debug", "info", "warn", "error"],
+                        "description": "Minimum log level to return"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Maximum number of most recent entries to return",
+                        "default": 100
                     }
                 },
                 "required": ["execution_id"]
@@ -254,17 +264,52 @@ impl RatchetToolRegistry {
             message: "Missing arguments for execution logs".to_string(),
         })?;
         
-        let _execution_id = args.get("execution_id")
+        let execution_id = args.get("execution_id")
             .and_then(|v| v.as_str())
             .ok_or_else(|| McpError::Validation {
                 message: "Missing execution_id parameter".to_string(),
             })?;
         
-        // TODO: Implement actual log retrieval using repository_factory
-        // For now, return a placeholder response
+        let min_level = match args.get("level").and_then(|v| v.as_str()) {
+            Some(level) => Some(ExecutionLogLevel::from_name(level).ok_or_else(|| McpError::Validation {
+                message: format!("Unknown log level: {}", level),
+            })?),
+            None => None,
+        };
+        let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(100) as usize;
+        
+        let error_result = |text: String| ToolsCallResult {
+            content: vec![ToolContent::Text { text }],
+            is_error: true,
+            metadata: HashMap::new(),
+        };
+        
+        // Accept both execution UUIDs and numeric IDs
+        let execution_repo = self.repository_factory.execution_repository();
+        let lookup = match uuid::Uuid::parse_str(execution_id) {
+            Ok(uuid) => execution_repo.find_by_uuid(uuid).await,
+            Err(_) => execution_repo.find_by_id(ApiId::from_string(execution_id.to_string()).as_i32().unwrap_or(0)).await,
+        };
+        let execution = match lookup {
+            Ok(Some(execution)) => execution,
+            Ok(None) => return Ok(error_result(format!("Execution not found: {}", execution_id))),
+            Err(e) => return Ok(error_result(format!("Failed to look up execution: {}", e))),
+        };
+        
+        let logs = match execution_repo.find_logs(execution.id.clone(), min_level, Some(limit)).await {
+            Ok(logs) => logs,
+            Err(e) => return Ok(error_result(format!("Failed to retrieve logs: {}", e))),
+        };
+        
+        let response = serde_json::json!({
+            "execution_id": execution.uuid.to_string(),
+            "status": execution.status,
+            "logs": logs,
+            "total_logs": logs.len(),
+        });
         Ok(ToolsCallResult {
             content: vec![ToolContent::Text {
-                text: "Log retrieval not yet implemented".to_string(),
+                text: serde_json::to_string_pretty(&response).unwrap_or_else(|_| response.to_string()),
             }],
             is_error: false,
             metadata: HashMap::new(),
@@ -6,11 +6,17 @@ use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::PathBuf;
 use std::sync::Arc;
+use tokio::sync::mpsc;
 
+use ratchet_api_types::{ExecutionLogLevel, UnifiedExecutionLog};
+use ratchet_execution::ipc::{TaskLogEntry, TaskProgressUpdate};
 use ratchet_execution::{ExecutionBridge, ExecutionError, ProcessTaskExecutor, TaskExecutionResult};
 use ratchet_interfaces::execution::TaskExecutor as InterfaceTaskExecutor;
 use ratchet_interfaces::logging::{LogEvent, LogLevel};
-use ratchet_interfaces::{TaskService, TaskServiceFilters};
+use ratchet_interfaces::{
+    declared_secrets, ExecutionLogSink, ExecutionProgress, ExecutionProgressSink, SecretStore, TaskService,
+    TaskServiceFilters,
+};
 use ratchet_runtime::executor::TaskExecutor;
 use ratchet_storage::seaorm::entities::ExecutionStatus;
 use ratchet_storage::seaorm::repositories::execution_repository::ExecutionRepository;
@@ -42,6 +48,13 @@ impl ExecutorType {
                     .execute_task_direct(task_id, task_path, input_data, context)
                     .await
             }
+            // The interface-style call below cannot carry secrets to the worker
+            ExecutorType::Bridge(executor) if context.as_ref().is_some_and(|c| !c.secrets.is_empty()) => {
+                executor
+                    .inner()
+                    .execute_task_direct(task_id, task_path, input_data, context)
+                    .await
+            }
             ExecutorType::Bridge(executor) => {
                 // Use the bridge's execute_task method with interface-style parameters
                 use ratchet_interfaces::execution::ExecutionContext;
@@ -72,6 +85,7 @@ impl ExecutorType {
                             started_at,
                             completed_at: now,
                             duration_ms: result.execution_time_ms as i32,
+                            logs: Vec::new(),
                         })
                     }
                     Err(e) => Err(e),
@@ -86,6 +100,34 @@ impl ExecutorType {
             }
         }
     }
+
+    /// Execute task directly, forwarding progress reported by the worker to `progress`
+    ///
+    /// Only the process-backed executors can observe worker progress; the runtime
+    /// executor falls back to [`Self::execute_task_direct`].
+    pub async fn execute_task_direct_with_progress(
+        &self,
+        task_id: i32,
+        task_path: String,
+        input_data: JsonValue,
+        context: Option<ratchet_execution::ipc::ExecutionContext>,
+        progress: mpsc::UnboundedSender<TaskProgressUpdate>,
+    ) -> Result<TaskExecutionResult, ExecutionError> {
+        match self {
+            ExecutorType::Process(executor) => {
+                executor
+                    .execute_task_direct_with_progress(task_id, task_path, input_data, context, Some(progress))
+                    .await
+            }
+            ExecutorType::Bridge(executor) => {
+                executor
+                    .inner()
+                    .execute_task_direct_with_progress(task_id, task_path, input_data, context, Some(progress))
+                    .await
+            }
+            ExecutorType::Runtime(_) => self.execute_task_direct(task_id, task_path, input_data, context).await,
+        }
+    }
 }
 
 /// Adapter that wraps Ratchet's task execution to provide MCP-compatible task execution
@@ -101,6 +143,15 @@ pub struct RatchetMcpAdapter {
 
     /// Optional path to log file for log retrieval
     log_file_path: Option<PathBuf>,
+
+    /// Optional sink that persists and broadcasts execution progress
+    progress_sink: Option<Arc<dyn ExecutionProgressSink>>,
+
+    /// Optional sink that stores console output of finished executions
+    log_sink: Option<Arc<dyn ExecutionLogSink>>,
+
+    /// Optional store the secrets declared by tasks are read from
+    secret_store: Option<Arc<dyn SecretStore>>,
 }
 
 impl RatchetMcpAdapter {
@@ -115,6 +166,9 @@ impl RatchetMcpAdapter {
             task_service,
             execution_repository,
             log_file_path: None,
+            progress_sink: None,
+            log_sink: None,
+            secret_store: None,
         }
     }
 
@@ -129,6 +183,9 @@ impl RatchetMcpAdapter {
             task_service,
             execution_repository,
             log_file_path: None,
+            progress_sink: None,
+            log_sink: None,
+            secret_store: None,
         }
     }
 
@@ -143,6 +200,9 @@ impl RatchetMcpAdapter {
             task_service,
             execution_repository,
             log_file_path: None,
+            progress_sink: None,
+            log_sink: None,
+            secret_store: None,
         }
     }
 
@@ -158,6 +218,9 @@ impl RatchetMcpAdapter {
             task_service,
             execution_repository,
             log_file_path: Some(log_file_path),
+            progress_sink: None,
+            log_sink: None,
+            secret_store: None,
         }
     }
 
@@ -173,6 +236,9 @@ impl RatchetMcpAdapter {
             task_service,
             execution_repository,
             log_file_path: Some(log_file_path),
+            progress_sink: None,
+            log_sink: None,
+            secret_store: None,
         }
     }
 
@@ -188,13 +254,81 @@ impl RatchetMcpAdapter {
             task_service,
             execution_repository,
             log_file_path: Some(log_file_path),
+            progress_sink: None,
+            log_sink: None,
+            secret_store: None,
         }
     }
-}
 
-#[async_trait]
-impl McpTaskExecutor for RatchetMcpAdapter {
-    async fn execute_task(&self, task_path: &str, input: Value) -> Result<Value, String> {
+    /// Persist and broadcast progress of executions started through this adapter
+    pub fn with_progress_sink(mut self, progress_sink: Arc<dyn ExecutionProgressSink>) -> Self {
+        self.progress_sink = Some(progress_sink);
+        self
+    }
+
+    /// Store console output of executions started through this adapter
+    pub fn with_log_sink(mut self, log_sink: Arc<dyn ExecutionLogSink>) -> Self {
+        self.log_sink = Some(log_sink);
+        self
+    }
+
+    /// Hand the secrets declared by tasks to their executions
+    pub fn with_secret_store(mut self, secret_store: Arc<dyn SecretStore>) -> Self {
+        self.secret_store = Some(secret_store);
+        self
+    }
+
+    /// Build the worker context of a new execution, including the secrets, egress
+    /// policy and HTTP client settings the task declares
+    async fn execution_context(
+        &self,
+        task: &ratchet_api_types::UnifiedTask,
+        execution_uuid: uuid::Uuid,
+    ) -> Result<ratchet_execution::ipc::ExecutionContext, String> {
+        let declared = |key: &str| task.metadata.as_ref().and_then(|metadata| metadata.get(key)).cloned();
+        let context =
+            ratchet_execution::ipc::ExecutionContext::new(execution_uuid, None, task.uuid, task.version.clone())
+                .with_egress_policy(declared("egress"))
+                .with_http_policy(declared("http"))
+                .with_limits(declared("limits"));
+
+        let names = task.metadata.as_ref().map(declared_secrets).unwrap_or_default();
+        if names.is_empty() {
+            return Ok(context);
+        }
+
+        let store = self
+            .secret_store
+            .as_ref()
+            .ok_or_else(|| format!("Task {} declares secrets but no secret store is configured", task.name))?;
+        let task_uuid = task.uuid.to_string();
+        let repository_id = task.repository_info.repository_id.to_string();
+        let secrets = store
+            .resolve_secrets(&names, Some(&task_uuid), Some(&repository_id))
+            .await
+            .map_err(|e| format!("Failed to resolve secrets for task {}: {}", task.name, e))?;
+
+        Ok(context.with_secrets(secrets))
+    }
+
+    /// Hand console output captured by the worker to the log sink, if any
+    async fn record_logs(&self, execution_id: &str, logs: Vec<TaskLogEntry>) {
+        let Some(sink) = &self.log_sink else {
+            return;
+        };
+        let logs = logs
+            .into_iter()
+            .map(|entry| UnifiedExecutionLog {
+                timestamp: entry.timestamp,
+                level: ExecutionLogLevel::from_name(&entry.level).unwrap_or(ExecutionLogLevel::Info),
+                message: entry.message,
+            })
+            .collect();
+        sink.record_logs(execution_id, logs).await;
+    }
+
+    /// Resolve a task by name or UUID and derive the ID used by the legacy execution interface
+    async fn resolve_task(&self, task_path: &str) -> Result<(ratchet_api_types::UnifiedTask, i32), String> {
         // Use unified task service to find the task (abstracts storage location)
         let task = match self.task_service.find_by_name(task_path).await {
             Ok(Some(task)) => task,
@@ -213,10 +347,6 @@ impl McpTaskExecutor for RatchetMcpAdapter {
             Err(e) => return Err(format!("Task service error: {}", e)),
         };
 
-        // Create an execution context
-        use ratchet_execution::ipc::ExecutionContext;
-        let context = ExecutionContext::new(uuid::Uuid::new_v4(), None, task.uuid, task.version.clone());
-
         // Convert string ID to i32 for legacy execution interface
         // For registry tasks, we'll use a synthetic ID since they're not stored in DB
         let task_id = if task.registry_source {
@@ -227,6 +357,19 @@ impl McpTaskExecutor for RatchetMcpAdapter {
             task.id.to_string().parse::<i32>().map_err(|e| format!("Invalid task ID format: {}", e))?
         };
 
+        Ok((task, task_id))
+    }
+}
+
+#[async_trait]
+impl McpTaskExecutor for RatchetMcpAdapter {
+    async fn execute_task(&self, task_path: &str, input: Value) -> Result<Value, String> {
+        let (task, task_id) = self.resolve_task(task_path).await?;
+
+        // Create an execution context
+        let execution_uuid = uuid::Uuid::new_v4();
+        let context = self.execution_context(&task, execution_uuid).await?;
+
         // Execute the task using the process executor
         match self
             .executor
@@ -238,9 +381,13 @@ impl McpTaskExecutor for RatchetMcpAdapter {
             )
             .await
         {
-            Ok(task_result) => task_result
-                .output
-                .ok_or_else(|| "No output from task execution".to_string()),
+            Ok(mut task_result) => {
+                self.record_logs(&execution_uuid.to_string(), std::mem::take(&mut task_result.logs))
+                    .await;
+                task_result
+                    .output
+                    .ok_or_else(|| "No output from task execution".to_string())
+            }
             Err(e) => Err(format!("Task execution failed: {}", e)),
         }
     }
@@ -250,33 +397,111 @@ impl McpTaskExecutor for RatchetMcpAdapter {
         task_path: &str,
         input: Value,
         progress_manager: Option<Arc<super::progress::ProgressNotificationManager>>,
-        _connection: Option<Arc<dyn crate::transport::connection::TransportConnection>>,
-        _filter: Option<super::progress::ProgressFilter>,
+        connection: Option<Arc<dyn crate::transport::connection::TransportConnection>>,
+        filter: Option<super::progress::ProgressFilter>,
     ) -> Result<(String, Value), String> {
-        // For now, just execute the task normally and return with a fake execution ID
-        // In the future, this would integrate with the worker process IPC to receive progress updates
-        let result = self.execute_task(task_path, input).await?;
+        let (task, task_id) = self.resolve_task(task_path).await?;
+
+        // The execution ID must be known up front so subscribers can be registered
+        // before the worker starts reporting
+        let execution_uuid = uuid::Uuid::new_v4();
+        let execution_id = execution_uuid.to_string();
+        let context = self.execution_context(&task, execution_uuid).await?;
+
+        if let (Some(manager), Some(connection)) = (&progress_manager, connection) {
+            manager
+                .subscribe_to_execution(execution_id.clone(), connection, filter)
+                .await;
+        }
+
+        let (progress_tx, mut progress_rx) = mpsc::unbounded_channel::<TaskProgressUpdate>();
+        let forwarder = {
+            let manager = progress_manager.clone();
+            let sink = self.progress_sink.clone();
+            let execution_id = execution_id.clone();
+            let task_id = task_path.to_string();
+            tokio::spawn(async move {
+                let mut last_fraction = 0.0;
+                let mut step_number = 0;
+                while let Some(update) = progress_rx.recv().await {
+                    step_number += 1;
+                    last_fraction = update.fraction.unwrap_or(last_fraction);
+                    let timestamp = chrono::Utc::now();
+
+                    if let Some(manager) = &manager {
+                        let progress_update = super::progress::ProgressUpdate {
+                            execution_id: execution_id.clone(),
+                            task_id: task_id.clone(),
+                            progress: last_fraction,
+                            step: None,
+                            step_number: Some(step_number),
+                            total_steps: None,
+                            message: update.message.clone(),
+                            data: update.partial_output.clone(),
+                            timestamp,
+                        };
+                        let _ = manager.send_progress_update(progress_update).await;
+                    }
+
+                    if let Some(sink) = &sink {
+                        sink.record_progress(ExecutionProgress {
+                            execution_id: execution_id.clone(),
+                            task_id: task_id.clone(),
+                            fraction: update.fraction,
+                            message: update.message,
+                            partial_output: update.partial_output,
+                            timestamp,
+                        })
+                        .await;
+                    }
+                }
+            })
+        };
 
-        let execution_id = uuid::Uuid::new_v4().to_string();
+        let execution = self
+            .executor
+            .execute_task_direct_with_progress(
+                task_id,
+                format!("/tasks/{}", task.uuid),
+                input,
+                Some(context),
+                progress_tx,
+            )
+            .await;
+
+        // The sender is dropped with the execution, so the forwarder finishes once
+        // every update reported by the worker has been delivered
+        let _ = forwarder.await;
+
+        let result = match execution {
+            Ok(mut task_result) => {
+                self.record_logs(&execution_id, std::mem::take(&mut task_result.logs)).await;
+                task_result
+                    .output
+                    .ok_or_else(|| "No output from task execution".to_string())
+            }
+            Err(e) => Err(format!("Task execution failed: {}", e)),
+        };
 
-        // If progress manager is provided, send a completion update
         if let Some(manager) = progress_manager {
-            let progress_update = super::progress::ProgressUpdate {
-                execution_id: execution_id.clone(),
-                task_id: task_path.to_string(),
-                progress: 1.0,
-                step: Some("completed".to_string()),
-                step_number: Some(1),
-                total_steps: Some(1),
-                message: Some("Task completed successfully".to_string()),
-                data: Some(result.clone()),
-                timestamp: chrono::Utc::now(),
-            };
-
-            let _ = manager.send_progress_update(progress_update).await;
+            if let Ok(output) = &result {
+                let progress_update = super::progress::ProgressUpdate {
+                    execution_id: execution_id.clone(),
+                    task_id: task_path.to_string(),
+                    progress: 1.0,
+                    step: Some("completed".to_string()),
+                    step_number: None,
+                    total_steps: None,
+                    message: Some("Task completed successfully".to_string()),
+                    data: Some(output.clone()),
+                    timestamp: chrono::Utc::now(),
+                };
+                let _ = manager.send_progress_update(progress_update).await;
+            }
+            manager.cleanup_execution(&execution_id).await;
         }
 
-        Ok((execution_id, result))
+        result.map(|output| (execution_id, output))
     }
 
     async fn list_tasks(&self, filter: Option<&str>) -> Result<Vec<McpTaskInfo>, String> {
@@ -331,7 +556,22 @@ impl McpTaskExecutor for RatchetMcpAdapter {
         if let Ok(exec_uuid) = uuid::Uuid::parse_str(execution_id) {
             match self.execution_repository.find_by_uuid(exec_uuid).await {
                 Ok(Some(execution)) => {
-                    // First check if we have a recording path (most detailed logs)
+                    // Console output captured from the task is the most specific source
+                    let console_logs =
+                        execution.console_logs(ExecutionLogLevel::from_name(min_level.as_str()), Some(limit));
+                    if !console_logs.is_empty() {
+                        let log_info = serde_json::json!({
+                            "execution_id": execution_id,
+                            "task_id": execution.task_id,
+                            "status": execution.status,
+                            "logs": console_logs,
+                            "total_logs": console_logs.len(),
+                            "source": "console"
+                        });
+                        return Ok(serde_json::to_string_pretty(&log_info).unwrap_or_else(|_| log_info.to_string()));
+                    }
+
+                    // Then check if we have a recording path (most detailed logs)
                     if let Some(recording_path) = &execution.recording_path {
                         if let Ok(logs) = self.get_logs_from_recording(recording_path, &min_level, limit).await {
                             return Ok(logs);
@@ -624,6 +864,9 @@ impl RatchetMcpAdapterBuilder {
             task_service,
             execution_repository: exec_repo,
             log_file_path: None,
+            progress_sink: None,
+            log_sink: None,
+            secret_store: None,
         })
     }
 }
@@ -1801,6 +1801,11 @@ impl TaskDevelopmentService {
             duration_ms: request.duration_ms,
             http_requests: request.http_requests,
             recording_path: request.recording_path,
+            progress: None,
+            partial_output: None,
+            logs: None,
+            parent_execution_uuid: None,
+            cache_hit: false,
         };
 
         // Store in database
@@ -2094,6 +2099,8 @@ impl TaskDevelopmentService {
             updated_at: chrono::Utc::now(),
             validated_at: None, // Will be set when validation runs
             source_modified_at: Some(chrono::Utc::now()),
+            signature_status: None,
+            signed_by: None,
         };
 
         // Insert into database
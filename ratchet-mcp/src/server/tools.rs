@@ -11,9 +11,12 @@ use crate::security::SecurityContext;
 use crate::{McpError, McpResult};
 
 // Import Ratchet's execution types
-use ratchet_api_types::{ApiId, ExecutionStatus as ApiExecutionStatus, PaginationInput};
+use ratchet_api_types::{
+    ApiId, ExecutionStatus as ApiExecutionStatus, JobPriority, JobStatus, PaginationInput, UnifiedJob,
+    UnifiedSchedulePolicy,
+};
 use ratchet_interfaces::logging::StructuredLogger;
-use ratchet_interfaces::{ExecutionFilters, JobFilters, RepositoryFactory, ScheduleFilters};
+use ratchet_interfaces::{ExecutionFilters, JobCreation, JobFilters, RepositoryFactory, ScheduleFilters};
 
 /// MCP tool definition with execution capability
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -255,6 +258,33 @@ impl RatchetToolRegistry {
         );
         self.tools.insert("ratchet_execute_task".to_string(), execute_task_tool);
 
+        // Job queueing tool
+        let queue_job_tool = McpTool::new(
+            "ratchet_queue_job",
+            "Queue a Ratchet task as a job for the job processor to run, optionally under an idempotency key",
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "task_id": {
+                        "type": "string",
+                        "description": "ID or name of the task to queue"
+                    },
+                    "input": {
+                        "type": "object",
+                        "description": "Input data for the task"
+                    },
+                    "idempotency_key": {
+                        "type": "string",
+                        "maxLength": 255,
+                        "description": "Calling again with the same key, task and input returns the job first queued with it; reusing the key for another task or input is an error"
+                    }
+                },
+                "required": ["task_id", "input"]
+            }),
+            "execution",
+        );
+        self.tools.insert("ratchet_queue_job".to_string(), queue_job_tool);
+
         // Execution status tool
         let status_tool = McpTool::new(
             "ratchet_get_execution_status",
@@ -648,6 +678,73 @@ impl RatchetToolRegistry {
         );
         self.tools
             .insert("ratchet_list_schedules".to_string(), list_schedules_tool);
+
+        // Schedule policy tool
+        let update_schedule_policy_tool = McpTool::new(
+            "ratchet_update_schedule_policy",
+            "Change the timezone, overlap, misfire, jitter or blackout policy of a schedule",
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "schedule_id": {
+                        "type": "string",
+                        "description": "ID of the schedule to update"
+                    },
+                    "policy": {
+                        "type": "object",
+                        "description": "Policy fields to change; omitted fields are left unchanged",
+                        "properties": {
+                            "timezone": {
+                                "type": "string",
+                                "description": "IANA timezone the cron expression is evaluated in, e.g. Europe/Oslo"
+                            },
+                            "overlap": {
+                                "type": "string",
+                                "enum": ["ALLOW", "SKIP", "QUEUE", "CANCEL_PREVIOUS"],
+                                "description": "What a fire does while the previous run is still going"
+                            },
+                            "misfire": {
+                                "type": "string",
+                                "enum": ["SKIP", "FIRE_ONCE", "CATCH_UP"],
+                                "description": "What happens on startup to fires missed while the server was down"
+                            },
+                            "catchUpLimit": {
+                                "type": "integer",
+                                "minimum": 1,
+                                "description": "Most missed fires to run with CATCH_UP"
+                            },
+                            "jitterSeconds": {
+                                "type": "integer",
+                                "minimum": 0,
+                                "maximum": 3600,
+                                "description": "Longest random delay before each run"
+                            },
+                            "blackoutWindows": {
+                                "type": "array",
+                                "description": "Local time windows in which the schedule does not fire",
+                                "items": {
+                                    "type": "object",
+                                    "properties": {
+                                        "start": {"type": "string", "description": "HH:MM"},
+                                        "end": {"type": "string", "description": "HH:MM"},
+                                        "days": {
+                                            "type": "array",
+                                            "items": {"type": "string"},
+                                            "description": "Days the window starts on, e.g. SAT; every day when empty"
+                                        }
+                                    },
+                                    "required": ["start", "end"]
+                                }
+                            }
+                        }
+                    }
+                },
+                "required": ["schedule_id", "policy"]
+            }),
+            "scheduling",
+        );
+        self.tools
+            .insert("ratchet_update_schedule_policy".to_string(), update_schedule_policy_tool);
     }
 
     /// Configure the registry with task executor
@@ -730,6 +827,7 @@ impl ToolRegistry for RatchetToolRegistry {
         // Execute the tool based on its name
         match name {
             "ratchet_execute_task" => self.execute_task_tool(execution_context).await,
+            "ratchet_queue_job" => self.queue_job_tool(execution_context).await,
             "ratchet_get_execution_status" => self.get_execution_status_tool(execution_context).await,
             "ratchet_get_execution_logs" => self.get_execution_logs_tool(execution_context).await,
             "ratchet_get_execution_trace" => self.get_execution_trace_tool(execution_context).await,
@@ -739,6 +837,7 @@ impl ToolRegistry for RatchetToolRegistry {
             "ratchet_list_executions" => self.list_executions_tool(execution_context).await,
             "ratchet_list_jobs" => self.list_jobs_tool(execution_context).await,
             "ratchet_list_schedules" => self.list_schedules_tool(execution_context).await,
+            "ratchet_update_schedule_policy" => self.update_schedule_policy_tool(execution_context).await,
             // Task development tools
             "ratchet_create_task"
             | "ratchet_validate_task"
@@ -804,6 +903,95 @@ impl RatchetToolRegistry {
         }
     }
 
+    /// Execute the job queueing tool
+    ///
+    /// With an idempotency key, returns the job first queued with it instead of queueing a repeat.
+    async fn queue_job_tool(&self, context: ToolExecutionContext) -> McpResult<ToolsCallResult> {
+        let args = context.arguments.ok_or_else(|| McpError::InvalidParams {
+            method: "ratchet_queue_job".to_string(),
+            details: "Missing arguments".to_string(),
+        })?;
+        let task_id = args
+            .get("task_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| McpError::InvalidParams {
+                method: "ratchet_queue_job".to_string(),
+                details: "Missing or invalid task_id".to_string(),
+            })?;
+        let input = args.get("input").cloned().unwrap_or(serde_json::json!({}));
+        let key = args.get("idempotency_key").and_then(|v| v.as_str());
+
+        let error = |text: String| -> McpResult<ToolsCallResult> {
+            Ok(ToolsCallResult {
+                content: vec![ToolContent::Text { text }],
+                is_error: true,
+                metadata: HashMap::new(),
+            })
+        };
+
+        if key.is_some_and(|key| key.trim().is_empty() || key.len() > 255) {
+            return error("idempotency_key must be between 1 and 255 characters".to_string());
+        }
+        let Some(repositories) = &self.repositories else {
+            return error("Repository factory not configured for MCP server".to_string());
+        };
+
+        let task_repo = repositories.task_repository();
+        let task = match task_id.parse::<i32>() {
+            Ok(id) => task_repo.find_by_id(id).await,
+            Err(_) => task_repo.find_by_name(task_id).await,
+        };
+        let task = match task {
+            Ok(Some(task)) => task,
+            Ok(None) => return error(format!("Task not found: {}", task_id)),
+            Err(e) => return error(format!("Failed to fetch task: {}", e)),
+        };
+
+        let job = UnifiedJob {
+            id: ApiId::from_i32(0), // Will be set by database
+            task_id: task.id,
+            priority: JobPriority::Normal,
+            status: JobStatus::Queued,
+            retry_count: 0,
+            max_retries: 3,
+            queued_at: chrono::Utc::now(),
+            scheduled_for: None,
+            error_message: None,
+            output_destinations: None,
+            input: Some(input),
+            depends_on: Vec::new(),
+            idempotency_key: key.map(str::to_string),
+            schedule_id: None,
+        };
+        let (job, replayed) = match repositories.job_repository().create_or_replay(job).await {
+            Ok(JobCreation::Created(job)) => (job, false),
+            Ok(JobCreation::Replayed(job)) => (job, true),
+            Err(e) => return error(format!("Failed to queue job: {}", e)),
+        };
+
+        Ok(ToolsCallResult {
+            content: vec![ToolContent::Text {
+                text: serde_json::to_string_pretty(&serde_json::json!({
+                    "job_id": job.id.to_string(),
+                    "task_id": job.task_id.to_string(),
+                    "status": job.status,
+                    "queued_at": job.queued_at,
+                    "idempotency_key": job.idempotency_key,
+                    "replayed": replayed,
+                }))
+                .unwrap_or_else(|_| "{}".to_string()),
+            }],
+            is_error: false,
+            metadata: {
+                let mut meta = HashMap::new();
+                meta.insert("task_id".to_string(), Value::String(task_id.to_string()));
+                meta.insert("job_id".to_string(), Value::String(job.id.to_string()));
+                meta.insert("replayed".to_string(), Value::Bool(replayed));
+                meta
+            },
+        })
+    }
+
     /// Execute the task execution tool
     async fn execute_task_tool(&self, context: ToolExecutionContext) -> McpResult<ToolsCallResult> {
         // Extract arguments
@@ -2390,6 +2578,7 @@ impl RatchetToolRegistry {
                             "scheduled_for": job.scheduled_for,
                             "error_message": job.error_message,
                             "output_destinations": job.output_destinations,
+                            "idempotency_key": job.idempotency_key,
                         })
                     })
                     .collect();
@@ -2543,6 +2732,7 @@ impl RatchetToolRegistry {
                             "description": schedule.description,
                             "cron_expression": schedule.cron_expression,
                             "enabled": schedule.enabled,
+                            "policy": schedule.policy,
                             "next_run": schedule.next_run,
                             "last_run": schedule.last_run,
                             "created_at": schedule.created_at,
@@ -2609,6 +2799,75 @@ impl RatchetToolRegistry {
             },
         })
     }
+
+    /// Execute the schedule policy tool
+    ///
+    /// The given fields are merged into the current policy, which is validated as
+    /// a whole when the schedule is stored.
+    async fn update_schedule_policy_tool(&self, context: ToolExecutionContext) -> McpResult<ToolsCallResult> {
+        let args = context.arguments.ok_or_else(|| McpError::InvalidParams {
+            method: "ratchet_update_schedule_policy".to_string(),
+            details: "Missing arguments".to_string(),
+        })?;
+        let schedule_id = args
+            .get("schedule_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| McpError::InvalidParams {
+                method: "ratchet_update_schedule_policy".to_string(),
+                details: "Missing or invalid schedule_id".to_string(),
+            })?;
+        let changes = args
+            .get("policy")
+            .and_then(|v| v.as_object())
+            .ok_or_else(|| McpError::InvalidParams {
+                method: "ratchet_update_schedule_policy".to_string(),
+                details: "Missing or invalid policy".to_string(),
+            })?;
+
+        let error = |text: String| ToolsCallResult {
+            content: vec![ToolContent::Text { text }],
+            is_error: true,
+            metadata: HashMap::new(),
+        };
+
+        let Some(repositories) = &self.repositories else {
+            return Ok(error("Repository factory not configured for MCP server".to_string()));
+        };
+        let schedule_repo = repositories.schedule_repository();
+        let id = ApiId::from_string(schedule_id.to_string());
+        let mut schedule = match schedule_repo.find_by_id(id.as_i32().unwrap_or(0)).await {
+            Ok(Some(schedule)) => schedule,
+            Ok(None) => return Ok(error(format!("Schedule not found: {}", schedule_id))),
+            Err(e) => return Ok(error(format!("Failed to fetch schedule: {}", e))),
+        };
+
+        let mut policy = serde_json::to_value(&schedule.policy).unwrap_or_else(|_| serde_json::json!({}));
+        if let Some(current) = policy.as_object_mut() {
+            current.extend(changes.clone());
+        }
+        schedule.policy = match serde_json::from_value::<UnifiedSchedulePolicy>(policy) {
+            Ok(policy) => policy,
+            Err(e) => return Ok(error(format!("Invalid schedule policy: {}", e))),
+        };
+        schedule.updated_at = chrono::Utc::now();
+
+        match schedule_repo.update(schedule).await {
+            Ok(updated) => Ok(ToolsCallResult {
+                content: vec![ToolContent::Text {
+                    text: serde_json::to_string_pretty(&serde_json::json!({
+                        "id": updated.id.to_string(),
+                        "name": updated.name,
+                        "next_run": updated.next_run,
+                        "policy": updated.policy,
+                    }))
+                    .unwrap_or_else(|_| "{}".to_string()),
+                }],
+                is_error: false,
+                metadata: HashMap::new(),
+            }),
+            Err(e) => Ok(error(format!("Failed to update schedule: {}", e))),
+        }
+    }
 }
 
 impl Default for RatchetToolRegistry {
@@ -2641,6 +2900,7 @@ mod tests {
 
         // Check that built-in tools are registered
         assert!(registry.tools.contains_key("ratchet_execute_task"));
+        assert!(registry.tools.contains_key("ratchet_queue_job"));
         assert!(registry.tools.contains_key("ratchet_get_execution_logs"));
         assert!(registry.tools.contains_key("ratchet_list_available_tasks"));
         assert!(registry.tools.contains_key("ratchet_analyze_execution_error"));
@@ -2651,6 +2911,7 @@ mod tests {
         assert!(registry.tools.contains_key("ratchet_list_executions"));
         assert!(registry.tools.contains_key("ratchet_list_jobs"));
         assert!(registry.tools.contains_key("ratchet_list_schedules"));
+        assert!(registry.tools.contains_key("ratchet_update_schedule_policy"));
 
         // Check that task development tools are registered
         assert!(registry.tools.contains_key("ratchet_create_task"));
@@ -0,0 +1,497 @@
+//! Database output destination implementation
+//!
+//! Writes output into a SQL table. Each configured column takes its value from
+//! a JSON pointer into the output (`/result/total`) or from a template
+//! (`{{job_id}}`). An array output becomes one row per element, inserted in
+//! batches within a single transaction.
+//!
+//! Destinations name one of the databases in [`DatabaseConnections`] rather
+//! than giving a connection string, so they can only write where the server
+//! configuration allows, and there is one connection pool per database.
+
+use async_trait::async_trait;
+use sea_orm::sea_query::{Alias, OnConflict, Query, SimpleExpr};
+use sea_orm::{ConnectOptions, ConnectionTrait, Database, DatabaseConnection, TransactionTrait, Value};
+use serde_json::Value as JsonValue;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use crate::{
+    destination::{DeliveryContext, DeliveryResult, OutputDestination, TaskOutput},
+    errors::{DeliveryError, ValidationError},
+    template::TemplateEngine,
+};
+
+/// A database that database destinations may write to
+#[derive(Clone)]
+pub struct DatabaseTarget {
+    /// Connection string of the database
+    pub url: String,
+    /// Pool size of the connection
+    pub max_connections: u32,
+}
+
+/// The databases database destinations may write to, by name, and their connection pools
+#[derive(Clone, Default)]
+pub struct DatabaseConnections {
+    targets: Arc<HashMap<String, DatabaseTarget>>,
+    pools: Arc<Mutex<HashMap<String, DatabaseConnection>>>,
+}
+
+impl DatabaseConnections {
+    pub fn new(targets: HashMap<String, DatabaseTarget>) -> Self {
+        Self {
+            targets: Arc::new(targets),
+            pools: Arc::default(),
+        }
+    }
+
+    /// Whether a database is configured under `name`
+    pub fn contains(&self, name: &str) -> bool {
+        self.targets.contains_key(name)
+    }
+
+    /// Pooled connection to the database configured under `name`
+    async fn connect(&self, name: &str) -> Result<DatabaseConnection, DeliveryError> {
+        let target = self.targets.get(name).ok_or_else(|| DeliveryError::Database {
+            operation: "connect".to_string(),
+            error: format!("no database connection named {}", name),
+        })?;
+
+        let mut pools = self.pools.lock().await;
+        if let Some(connection) = pools.get(name) {
+            return Ok(connection.clone());
+        }
+
+        let mut options = ConnectOptions::new(target.url.clone());
+        options.max_connections(target.max_connections).sqlx_logging(false);
+        let connection = Database::connect(options).await.map_err(|e| DeliveryError::Database {
+            operation: "connect".to_string(),
+            error: e.to_string(),
+        })?;
+        pools.insert(name.to_string(), connection.clone());
+        Ok(connection)
+    }
+}
+
+// Connection strings carry passwords, so only the names are shown
+impl fmt::Debug for DatabaseConnections {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DatabaseConnections")
+            .field("names", &self.targets.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Configuration for database destination
+#[derive(Debug, Clone)]
+pub struct DatabaseConfig {
+    /// Name of the database in [`DatabaseConnections`] to write to
+    pub connection: String,
+    pub table: String,
+    pub columns: HashMap<String, String>,
+    pub upsert: bool,
+    pub key_columns: Vec<String>,
+    pub batch_size: usize,
+}
+
+/// Where a column takes its value from
+#[derive(Debug, Clone)]
+enum ColumnSource {
+    /// JSON pointer into the row, keeping the JSON type of the value
+    Pointer(String),
+    /// Template rendered with the delivery variables and the row as `row`
+    Template(String),
+}
+
+impl ColumnSource {
+    fn parse(expression: &str) -> Self {
+        if expression.starts_with('/') {
+            Self::Pointer(expression.to_string())
+        } else {
+            Self::Template(expression.to_string())
+        }
+    }
+}
+
+/// Database destination for inserting output into a table
+#[derive(Debug)]
+pub struct DatabaseDestination {
+    config: DatabaseConfig,
+    columns: Vec<(String, ColumnSource)>,
+    connections: DatabaseConnections,
+    template_engine: TemplateEngine,
+}
+
+impl DatabaseDestination {
+    pub fn new(config: DatabaseConfig, connections: DatabaseConnections, template_engine: TemplateEngine) -> Self {
+        // Sorted so that every statement lists the columns in the same order
+        let columns = config
+            .columns
+            .iter()
+            .collect::<BTreeMap<_, _>>()
+            .into_iter()
+            .map(|(name, expression)| (name.clone(), ColumnSource::parse(expression)))
+            .collect();
+        Self {
+            config,
+            columns,
+            connections,
+            template_engine,
+        }
+    }
+
+    /// Column values of one row of output
+    fn row_values(&self, row: &JsonValue, context: &DeliveryContext) -> Result<Vec<SimpleExpr>, DeliveryError> {
+        let mut variables: serde_json::Map<String, JsonValue> = context
+            .template_variables
+            .iter()
+            .map(|(k, v)| (k.clone(), JsonValue::String(v.clone())))
+            .collect();
+        variables.insert("row".to_string(), row.clone());
+        let variables = JsonValue::Object(variables);
+
+        self.columns
+            .iter()
+            .map(|(_, source)| -> Result<SimpleExpr, DeliveryError> {
+                let value = match source {
+                    ColumnSource::Pointer(pointer) => to_sql_value(row.pointer(pointer).unwrap_or(&JsonValue::Null)),
+                    ColumnSource::Template(template) => self.template_engine.render_json(template, &variables)?.into(),
+                };
+                Ok(value.into())
+            })
+            .collect()
+    }
+
+    /// Insert or upsert `rows` in batches, returning the number of rows affected
+    async fn write_rows(&self, rows: &[&JsonValue], context: &DeliveryContext) -> Result<u64, DeliveryError> {
+        let connection = self.connections.connect(&self.config.connection).await?;
+        let backend = connection.get_database_backend();
+        let txn = connection.begin().await.map_err(|e| DeliveryError::Database {
+            operation: "begin".to_string(),
+            error: e.to_string(),
+        })?;
+
+        let mut affected = 0;
+        for batch in rows.chunks(self.config.batch_size.max(1)) {
+            let mut insert = Query::insert();
+            match self.config.table.split_once('.') {
+                Some((schema, table)) => insert.into_table((Alias::new(schema), Alias::new(table))),
+                None => insert.into_table(Alias::new(&self.config.table)),
+            };
+            insert.columns(self.columns.iter().map(|(name, _)| Alias::new(name)));
+            for row in batch {
+                insert
+                    .values(self.row_values(row, context)?)
+                    .map_err(|e| DeliveryError::Database {
+                        operation: "build".to_string(),
+                        error: e.to_string(),
+                    })?;
+            }
+            if self.config.upsert {
+                insert.on_conflict(self.on_conflict());
+            }
+
+            let result = txn
+                .execute(backend.build(&insert))
+                .await
+                .map_err(|e| DeliveryError::Database {
+                    operation: "insert".to_string(),
+                    error: e.to_string(),
+                })?;
+            affected += result.rows_affected();
+        }
+
+        txn.commit().await.map_err(|e| DeliveryError::Database {
+            operation: "commit".to_string(),
+            error: e.to_string(),
+        })?;
+        Ok(affected)
+    }
+
+    /// Update every column but the keys of a row that already exists
+    fn on_conflict(&self) -> OnConflict {
+        let updates: Vec<_> = self
+            .columns
+            .iter()
+            .map(|(name, _)| name)
+            .filter(|name| !self.config.key_columns.contains(*name))
+            .map(Alias::new)
+            .collect();
+        let mut on_conflict = OnConflict::columns(self.config.key_columns.iter().map(Alias::new));
+        if updates.is_empty() {
+            on_conflict.do_nothing();
+        } else {
+            on_conflict.update_columns(updates);
+        }
+        on_conflict
+    }
+}
+
+#[async_trait]
+impl OutputDestination for DatabaseDestination {
+    async fn deliver(&self, output: &TaskOutput, context: &DeliveryContext) -> Result<DeliveryResult, DeliveryError> {
+        let start_time = Instant::now();
+
+        let rows: Vec<&JsonValue> = match &output.output_data {
+            JsonValue::Array(items) => items.iter().collect(),
+            other => vec![other],
+        };
+        let affected = if rows.is_empty() {
+            0
+        } else {
+            self.write_rows(&rows, context).await?
+        };
+
+        let size_bytes = serde_json::to_vec(&output.output_data)
+            .map(|v| v.len() as u64)
+            .unwrap_or(0);
+
+        Ok(DeliveryResult::success(
+            "database".to_string(),
+            start_time.elapsed(),
+            size_bytes,
+            Some(format!("{} rows written to {}", affected, self.config.table)),
+        ))
+    }
+
+    fn validate_config(&self) -> Result<(), ValidationError> {
+        if !self.connections.contains(&self.config.connection) {
+            return Err(ValidationError::InvalidDatabaseConfig {
+                reason: format!("no database connection named {}", self.config.connection),
+            });
+        }
+
+        if self.config.table.is_empty() {
+            return Err(ValidationError::InvalidDatabaseConfig {
+                reason: "table must not be empty".to_string(),
+            });
+        }
+
+        if self.columns.is_empty() {
+            return Err(ValidationError::InvalidDatabaseConfig {
+                reason: "at least one column must be mapped".to_string(),
+            });
+        }
+
+        for (name, source) in &self.columns {
+            if name.is_empty() {
+                return Err(ValidationError::InvalidDatabaseConfig {
+                    reason: "column names must not be empty".to_string(),
+                });
+            }
+            if let ColumnSource::Template(template) = source {
+                self.template_engine
+                    .validate(template)
+                    .map_err(|e| ValidationError::InvalidTemplate(e.to_string()))?;
+            }
+        }
+
+        if self.config.upsert {
+            if self.config.key_columns.is_empty() {
+                return Err(ValidationError::InvalidDatabaseConfig {
+                    reason: "upsert requires key_columns".to_string(),
+                });
+            }
+            if let Some(key) = self
+                .config
+                .key_columns
+                .iter()
+                .find(|key| !self.config.columns.contains_key(*key))
+            {
+                return Err(ValidationError::InvalidDatabaseConfig {
+                    reason: format!("key column {} is not mapped", key),
+                });
+            }
+        }
+
+        if self.config.batch_size == 0 {
+            return Err(ValidationError::InvalidDatabaseConfig {
+                reason: "batch_size must be greater than 0".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn destination_type(&self) -> &'static str {
+        "database"
+    }
+
+    fn supports_retry(&self) -> bool {
+        // A failed delivery rolls back, so it can run again
+        true
+    }
+
+    fn estimated_delivery_time(&self) -> Duration {
+        Duration::from_secs(5)
+    }
+}
+
+/// SQL value of a JSON value; arrays and objects are stored as JSON text
+fn to_sql_value(value: &JsonValue) -> Value {
+    match value {
+        JsonValue::Null => Value::String(None),
+        JsonValue::Bool(b) => (*b).into(),
+        JsonValue::Number(n) => match n.as_i64() {
+            Some(i) => i.into(),
+            None => n.as_f64().into(),
+        },
+        JsonValue::String(s) => s.clone().into(),
+        JsonValue::Array(_) | JsonValue::Object(_) => value.to_string().into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use sea_orm::{FromQueryResult, JsonValue as QueryJson, Statement};
+    use serde_json::json;
+
+    async fn create_db(dir: &tempfile::TempDir) -> (String, DatabaseConnection) {
+        let url = format!("sqlite://{}?mode=rwc", dir.path().join("results.db").display());
+        let db = Database::connect(url.as_str()).await.unwrap();
+        db.execute_unprepared("CREATE TABLE results (city TEXT PRIMARY KEY, temperature REAL, job TEXT, raw TEXT)")
+            .await
+            .unwrap();
+        (url, db)
+    }
+
+    fn test_connections(url: &str) -> DatabaseConnections {
+        let target = DatabaseTarget {
+            url: url.to_string(),
+            max_connections: 2,
+        };
+        DatabaseConnections::new(HashMap::from([("reports".to_string(), target)]))
+    }
+
+    fn test_config() -> DatabaseConfig {
+        let columns = [
+            ("city", "/city"),
+            ("temperature", "/temperature"),
+            ("job", "job-{{job_id}}"),
+            ("raw", "/tags"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        DatabaseConfig {
+            connection: "reports".to_string(),
+            table: "results".to_string(),
+            columns,
+            upsert: false,
+            key_columns: vec![],
+            batch_size: 2,
+        }
+    }
+
+    fn test_output(output_data: JsonValue) -> TaskOutput {
+        TaskOutput {
+            job_id: 7,
+            task_id: 1,
+            execution_id: 1,
+            output_data,
+            metadata: HashMap::new(),
+            completed_at: Utc::now(),
+            execution_duration: Duration::from_secs(1),
+        }
+    }
+
+    fn test_context() -> DeliveryContext {
+        let mut context = DeliveryContext::default();
+        context.template_variables.insert("job_id".to_string(), "7".to_string());
+        context
+    }
+
+    async fn select_all(db: &DatabaseConnection) -> Vec<QueryJson> {
+        QueryJson::find_by_statement(Statement::from_string(
+            db.get_database_backend(),
+            "SELECT city, temperature, job, raw FROM results ORDER BY city",
+        ))
+        .all(db)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_array_output_is_inserted_in_batches() {
+        let dir = tempfile::tempdir().unwrap();
+        let (url, db) = create_db(&dir).await;
+        let destination = DatabaseDestination::new(test_config(), test_connections(&url), TemplateEngine::new());
+        assert!(destination.validate_config().is_ok());
+
+        let output = test_output(json!([
+            {"city": "Bergen", "temperature": 9.5, "tags": ["rain"]},
+            {"city": "Oslo", "temperature": 12},
+            {"city": "Tromsø", "temperature": -3.25},
+        ]));
+        let result = destination.deliver(&output, &test_context()).await.unwrap();
+        assert_eq!(result.response_info.as_deref(), Some("3 rows written to results"));
+
+        let rows = select_all(&db).await;
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0]["city"], "Bergen");
+        assert_eq!(rows[0]["job"], "job-7");
+        assert_eq!(rows[0]["raw"], r#"["rain"]"#);
+        assert_eq!(rows[1]["temperature"], 12.0);
+        assert!(rows[1]["raw"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_upsert_updates_existing_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let (url, db) = create_db(&dir).await;
+        let mut config = test_config();
+        config.upsert = true;
+        config.key_columns = vec!["city".to_string()];
+        let destination = DatabaseDestination::new(config, test_connections(&url), TemplateEngine::new());
+        assert!(destination.validate_config().is_ok());
+
+        for temperature in [10, 14] {
+            let output = test_output(json!({"city": "Oslo", "temperature": temperature}));
+            destination.deliver(&output, &test_context()).await.unwrap();
+        }
+
+        let rows = select_all(&db).await;
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["temperature"], 14.0);
+    }
+
+    #[test]
+    fn test_database_config_validation() {
+        let template_engine = TemplateEngine::new();
+        let connections = test_connections("sqlite::memory:");
+
+        let mut config = test_config();
+        config.connection = "sqlite::memory:".to_string();
+        let destination = DatabaseDestination::new(config.clone(), connections.clone(), template_engine.clone());
+        assert!(matches!(
+            destination.validate_config(),
+            Err(ValidationError::InvalidDatabaseConfig { .. })
+        ));
+
+        config.connection = "reports".to_string();
+        config.upsert = true;
+        let destination = DatabaseDestination::new(config.clone(), connections.clone(), template_engine.clone());
+        assert!(matches!(
+            destination.validate_config(),
+            Err(ValidationError::InvalidDatabaseConfig { .. })
+        ));
+
+        config.key_columns = vec!["missing".to_string()];
+        let destination = DatabaseDestination::new(config.clone(), connections.clone(), template_engine.clone());
+        assert!(destination.validate_config().is_err());
+
+        config.upsert = false;
+        config.columns.insert("bad".to_string(), "{{#if}}".to_string());
+        let destination = DatabaseDestination::new(config, connections, template_engine);
+        assert!(matches!(
+            destination.validate_config(),
+            Err(ValidationError::InvalidTemplate(_))
+        ));
+    }
+}
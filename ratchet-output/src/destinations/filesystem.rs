@@ -102,7 +102,16 @@ impl FilesystemDestination {
 
     /// Format output data according to the configured format
     fn format_output(&self, data: &serde_json::Value) -> Result<Vec<u8>, DeliveryError> {
-        match &self.config.format {
+        Self::format_data(&self.config.format, &self.template_engine, data)
+    }
+
+    /// Serialize output data in the given format; shared with the S3 destination
+    pub(crate) fn format_data(
+        format: &OutputFormat,
+        template_engine: &TemplateEngine,
+        data: &serde_json::Value,
+    ) -> Result<Vec<u8>, DeliveryError> {
+        match format {
             OutputFormat::Json => serde_json::to_vec_pretty(data).map_err(|e| DeliveryError::Serialization {
                 format: "json".to_string(),
                 error: e.to_string(),
@@ -133,7 +142,7 @@ impl FilesystemDestination {
                 }
             }
             OutputFormat::Template(template) => {
-                let rendered = self.template_engine.render_json(template, data).map_err(|e| match e {
+                let rendered = template_engine.render_json(template, data).map_err(|e| match e {
                     DeliveryError::TemplateRender {
                         template: t,
                         error: err,
@@ -149,7 +158,7 @@ impl FilesystemDestination {
                 Ok(rendered.into_bytes())
             }
             #[cfg(feature = "csv")]
-            OutputFormat::Csv => self.convert_to_csv(data),
+            OutputFormat::Csv => Self::convert_to_csv(data),
             #[cfg(not(feature = "csv"))]
             OutputFormat::Csv => Err(DeliveryError::Serialization {
                 format: "csv".to_string(),
@@ -160,7 +169,7 @@ impl FilesystemDestination {
 
     /// Convert JSON data to CSV format
     #[cfg(feature = "csv")]
-    fn convert_to_csv(&self, data: &serde_json::Value) -> Result<Vec<u8>, DeliveryError> {
+    fn convert_to_csv(data: &serde_json::Value) -> Result<Vec<u8>, DeliveryError> {
         match data {
             serde_json::Value::Array(arr) if !arr.is_empty() => {
                 let mut wtr = csv::Writer::from_writer(Vec::new());
@@ -1,9 +1,15 @@
 //! Concrete implementations of output destinations
 
+#[cfg(feature = "database")]
+pub mod database;
 pub mod filesystem;
+pub mod s3;
 pub mod stdio;
 pub mod webhook;
 
+#[cfg(feature = "database")]
+pub use database::{DatabaseConnections, DatabaseDestination, DatabaseTarget};
 pub use filesystem::FilesystemDestination;
+pub use s3::S3Destination;
 pub use stdio::{StdStream, StdioConfig, StdioDestination};
 pub use webhook::WebhookDestination;
@@ -1,6 +1,7 @@
 //! Webhook output destination implementation
 
 use async_trait::async_trait;
+use ratchet_http::HttpTransport;
 use reqwest;
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
@@ -30,6 +31,7 @@ pub struct WebhookDestination {
     config: WebhookConfig,
     client: reqwest::Client,
     template_engine: TemplateEngine,
+    transport: Option<HttpTransport>,
 }
 
 impl WebhookDestination {
@@ -38,11 +40,27 @@ impl WebhookDestination {
             config,
             client,
             template_engine,
+            transport: None,
         }
     }
 
+    /// Present the client certificates of `transport` to the hosts they are configured for
+    ///
+    /// The URL is only known once rendered, so requests to such hosts use a
+    /// client built for the host instead of the one given to [`new`](Self::new),
+    /// which should already use the transport's proxies and CA certificates.
+    pub fn with_transport(mut self, transport: HttpTransport) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
     /// Create a cross-platform HTTP client with appropriate TLS configuration
     pub fn create_default_client() -> Result<reqwest::Client, reqwest::Error> {
+        Self::default_client_builder().build()
+    }
+
+    /// Builder of the client returned by [`create_default_client`](Self::create_default_client)
+    pub fn default_client_builder() -> reqwest::ClientBuilder {
         reqwest::Client::builder()
             // Use rustls-tls for cross-platform TLS support
             .use_rustls_tls()
@@ -52,7 +70,25 @@ impl WebhookDestination {
             // Enable connection pooling
             // Set a reasonable user agent
             .user_agent("ratchet-output/1.0")
+    }
+
+    /// Client for requests to `url`, presenting the client certificate configured for its host
+    fn client_for(&self, url: &str) -> Result<reqwest::Client, DeliveryError> {
+        let parsed = reqwest::Url::parse(url).ok();
+        let Some(transport) = self
+            .transport
+            .as_ref()
+            .filter(|transport| parsed.as_ref().is_some_and(|url| transport.has_identity_for(url)))
+        else {
+            return Ok(self.client.clone());
+        };
+        transport
+            .apply(Self::default_client_builder(), parsed.as_ref())
             .build()
+            .map_err(|e| DeliveryError::Network {
+                url: url.to_string(),
+                error: e.to_string(),
+            })
     }
 
     /// Add authentication to the request
@@ -100,19 +136,20 @@ impl WebhookDestination {
         let mut attempt = 0;
         let mut delay = self.config.retry_policy.initial_delay;
         let start_time = Instant::now();
+        let client = self.client_for(url)?;
 
         loop {
             attempt += 1;
 
             // Build request
             let mut request = match self.config.method {
-                HttpMethod::Get => self.client.get(url),
-                HttpMethod::Post => self.client.post(url),
-                HttpMethod::Put => self.client.put(url),
-                HttpMethod::Patch => self.client.patch(url),
-                HttpMethod::Delete => self.client.delete(url),
-                HttpMethod::Head => self.client.head(url),
-                HttpMethod::Options => self.client.request(reqwest::Method::OPTIONS, url),
+                HttpMethod::Get => client.get(url),
+                HttpMethod::Post => client.post(url),
+                HttpMethod::Put => client.put(url),
+                HttpMethod::Patch => client.patch(url),
+                HttpMethod::Delete => client.delete(url),
+                HttpMethod::Head => client.head(url),
+                HttpMethod::Options => client.request(reqwest::Method::OPTIONS, url),
             };
 
             // Add headers
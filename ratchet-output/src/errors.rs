@@ -78,6 +78,12 @@ pub enum ValidationError {
 
     #[error("Invalid retry policy: {reason}")]
     InvalidRetryPolicy { reason: String },
+
+    #[error("Invalid S3 configuration: {reason}")]
+    InvalidS3Config { reason: String },
+
+    #[error("Invalid database configuration: {reason}")]
+    InvalidDatabaseConfig { reason: String },
 }
 
 /// Configuration errors
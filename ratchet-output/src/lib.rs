@@ -59,7 +59,11 @@ pub mod metrics;
 pub mod template;
 
 pub use destination::{DeliveryContext, DeliveryResult, OutputDestination, TaskOutput};
-pub use destinations::{FilesystemDestination, StdStream, StdioConfig, StdioDestination, WebhookDestination};
+#[cfg(feature = "database")]
+pub use destinations::{DatabaseConnections, DatabaseDestination, DatabaseTarget};
+pub use destinations::{
+    FilesystemDestination, S3Destination, StdStream, StdioConfig, StdioDestination, WebhookDestination,
+};
 pub use errors::{ConfigError, DeliveryError, ValidationError};
 pub use manager::{OutputDeliveryManager, TestResult};
 pub use template::TemplateEngine;
@@ -108,20 +112,34 @@ pub enum OutputDestinationConfig {
     },
     #[serde(rename = "database")]
     Database {
-        connection: String,               // Database connection string
-        table: String,                    // Table name
-        columns: HashMap<String, String>, // Column mapping
+        connection: String,               // Name of a database connection in the server configuration
+        table: String,                    // Table name, optionally schema-qualified
+        columns: HashMap<String, String>, // Column -> JSON pointer (/result/total) or template ({{job_id}})
         #[serde(default)]
         upsert: bool, // Insert or update
+        #[serde(default)]
+        key_columns: Vec<String>, // Columns identifying a row when upserting
+        #[serde(default = "default_db_batch_size")]
+        batch_size: usize, // Rows per INSERT when the output is an array (default: 100)
     },
     #[serde(rename = "s3")]
     S3 {
         bucket: String,                // S3 bucket name
-        key: String,                   // Object key template
+        key: String,                   // Object key template: results/{{job_id}}.json
         region: String,                // AWS region
         storage_class: Option<String>, // Storage class
         #[serde(default)]
-        metadata: HashMap<String, String>, // Object metadata
+        metadata: HashMap<String, String>, // Object metadata, template values
+        endpoint: Option<String>,      // S3-compatible endpoint, e.g. http://localhost:9000 (default: AWS)
+        #[serde(default)]
+        path_style: bool, // Bucket in the path instead of the host name (default: false)
+        #[serde(default)]
+        format: OutputFormat, // json, json_compact, yaml, raw, template
+        credentials: Option<S3Credentials>, // Static credentials; AWS_* environment variables otherwise
+        #[serde(default = "default_s3_part_size")]
+        part_size: u64, // Larger outputs are uploaded in parts of this size (default: 8 MiB)
+        #[serde(default = "default_s3_timeout", with = "duration_serde")]
+        timeout: Duration, // Timeout of each request (default: 60s)
     },
     #[serde(rename = "stdio")]
     Stdio {
@@ -165,6 +183,25 @@ pub enum WebhookAuth {
     Signature { secret: String, algorithm: String }, // HMAC signature
 }
 
+/// Access key of an S3 destination
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct S3Credentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+}
+
+impl S3Credentials {
+    /// Read credentials from `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and `AWS_SESSION_TOKEN`
+    pub fn from_env() -> Option<Self> {
+        Some(Self {
+            access_key_id: std::env::var("AWS_ACCESS_KEY_ID").ok()?,
+            secret_access_key: std::env::var("AWS_SECRET_ACCESS_KEY").ok()?,
+            session_token: std::env::var("AWS_SESSION_TOKEN").ok(),
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RetryPolicy {
     #[serde(default = "default_max_attempts")]
@@ -236,6 +273,15 @@ fn default_retry_status_codes() -> Vec<u16> {
 fn default_stdout_stream() -> String {
     "stdout".to_string()
 }
+fn default_db_batch_size() -> usize {
+    100
+}
+fn default_s3_part_size() -> u64 {
+    8 * 1024 * 1024
+}
+fn default_s3_timeout() -> Duration {
+    Duration::from_secs(60)
+}
 
 // Duration serialization helper
 mod duration_serde {
@@ -4,12 +4,17 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use ratchet_http::HttpTransport;
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 
+#[cfg(feature = "database")]
+use crate::destinations::database::DatabaseConnections;
 use crate::{
     destination::{DeliveryContext, DeliveryResult, OutputDestination, TaskOutput},
-    destinations::{FilesystemDestination, StdStream, StdioConfig, StdioDestination, WebhookDestination},
+    destinations::{
+        FilesystemDestination, S3Destination, StdStream, StdioConfig, StdioDestination, WebhookDestination,
+    },
     errors::{ConfigError, DeliveryError},
     metrics::DeliveryMetrics,
     template::TemplateEngine,
@@ -31,6 +36,9 @@ pub struct OutputDeliveryManager {
     destinations: Arc<RwLock<HashMap<String, Arc<dyn OutputDestination>>>>,
     template_engine: TemplateEngine,
     metrics: DeliveryMetrics,
+    http_transport: Option<HttpTransport>,
+    #[cfg(feature = "database")]
+    database_connections: DatabaseConnections,
 }
 
 impl OutputDeliveryManager {
@@ -40,12 +48,29 @@ impl OutputDeliveryManager {
             destinations: Arc::new(RwLock::new(HashMap::new())),
             template_engine: TemplateEngine::new(),
             metrics: DeliveryMetrics::new(),
+            http_transport: None,
+            #[cfg(feature = "database")]
+            database_connections: DatabaseConnections::default(),
         }
     }
 
+    /// Send webhook and S3 requests of destinations added later through the
+    /// proxies, and with the CA and client certificates, of `transport`
+    pub fn with_http_transport(mut self, transport: HttpTransport) -> Self {
+        self.http_transport = Some(transport);
+        self
+    }
+
+    /// Let database destinations added later write to the databases of `connections`, and no others
+    #[cfg(feature = "database")]
+    pub fn with_database_connections(mut self, connections: DatabaseConnections) -> Self {
+        self.database_connections = connections;
+        self
+    }
+
     /// Add a destination to the manager
     pub async fn add_destination(&self, name: String, config: OutputDestinationConfig) -> Result<(), ConfigError> {
-        let destination = Self::create_destination_static(config, &self.template_engine)?;
+        let destination = self.create_destination(config)?;
 
         // Validate the destination configuration
         destination
@@ -178,7 +203,7 @@ impl OutputDeliveryManager {
         let manager = Self::new();
 
         for (index, config) in configs.iter().enumerate() {
-            let destination = Self::create_destination_static(config.clone(), &manager.template_engine)?;
+            let destination = manager.create_destination(config.clone())?;
             destination
                 .validate_config()
                 .map_err(|e| ConfigError::InvalidDestination {
@@ -200,10 +225,10 @@ impl OutputDeliveryManager {
     /// Test delivery configurations without actually delivering
     pub async fn test_configurations(configs: &[OutputDestinationConfig]) -> Result<Vec<TestResult>, ConfigError> {
         let mut results = Vec::new();
-        let template_engine = TemplateEngine::new();
+        let manager = Self::new();
 
         for (idx, config) in configs.iter().enumerate() {
-            match Self::create_destination_static(config.clone(), &template_engine) {
+            match manager.create_destination(config.clone()) {
                 Ok(destination) => match destination.validate_config() {
                     Ok(()) => {
                         results.push(TestResult {
@@ -239,11 +264,22 @@ impl OutputDeliveryManager {
         Ok(results)
     }
 
-    /// Create a destination from configuration (static version)
-    fn create_destination_static(
-        config: OutputDestinationConfig,
-        template_engine: &TemplateEngine,
-    ) -> Result<Arc<dyn OutputDestination>, ConfigError> {
+    /// Create a destination from configuration
+    fn create_destination(&self, config: OutputDestinationConfig) -> Result<Arc<dyn OutputDestination>, ConfigError> {
+        let template_engine = &self.template_engine;
+        let http_transport = self.http_transport.as_ref();
+
+        // Proxies and CA certificates apply to every client; client certificates
+        // only to clients built for a known host
+        let client = |url: Option<reqwest::Url>| {
+            let builder = WebhookDestination::default_client_builder();
+            let builder = match http_transport {
+                Some(transport) => transport.apply(builder, url.as_ref()),
+                None => builder,
+            };
+            builder.build().map_err(|e| ConfigError::HttpClientCreate { source: e })
+        };
+
         match config {
             OutputDestinationConfig::Filesystem {
                 path,
@@ -283,14 +319,11 @@ impl OutputDeliveryManager {
                     content_type,
                 };
 
-                let client = WebhookDestination::create_default_client()
-                    .map_err(|e| ConfigError::HttpClientCreate { source: e })?;
-
-                Ok(Arc::new(WebhookDestination::new(
-                    webhook_config,
-                    client,
-                    template_engine.clone(),
-                )))
+                let destination = WebhookDestination::new(webhook_config, client(None)?, template_engine.clone());
+                Ok(Arc::new(match http_transport {
+                    Some(transport) => destination.with_transport(transport.clone()),
+                    None => destination,
+                }))
             }
             OutputDestinationConfig::Stdio {
                 stream,
@@ -320,10 +353,70 @@ impl OutputDeliveryManager {
 
                 Ok(Arc::new(StdioDestination::new(stdio_config, template_engine.clone())))
             }
+            #[cfg(feature = "database")]
+            OutputDestinationConfig::Database {
+                connection,
+                table,
+                columns,
+                upsert,
+                key_columns,
+                batch_size,
+            } => {
+                let database_config = crate::destinations::database::DatabaseConfig {
+                    connection,
+                    table,
+                    columns,
+                    upsert,
+                    key_columns,
+                    batch_size,
+                };
+
+                Ok(Arc::new(crate::destinations::DatabaseDestination::new(
+                    database_config,
+                    self.database_connections.clone(),
+                    template_engine.clone(),
+                )))
+            }
+            #[cfg(not(feature = "database"))]
             OutputDestinationConfig::Database { .. } => {
                 Err(ConfigError::UnsupportedDestination("database".to_string()))
             }
-            OutputDestinationConfig::S3 { .. } => Err(ConfigError::UnsupportedDestination("s3".to_string())),
+            OutputDestinationConfig::S3 {
+                bucket,
+                key,
+                region,
+                storage_class,
+                metadata,
+                endpoint,
+                path_style,
+                format,
+                credentials,
+                part_size,
+                timeout,
+            } => {
+                let endpoint_url = endpoint
+                    .as_deref()
+                    .and_then(|endpoint| reqwest::Url::parse(endpoint).ok());
+                let s3_config = crate::destinations::s3::S3Config {
+                    bucket,
+                    key_template: key,
+                    region,
+                    endpoint,
+                    path_style,
+                    format,
+                    storage_class,
+                    metadata,
+                    credentials,
+                    part_size,
+                    timeout,
+                };
+
+                Ok(Arc::new(S3Destination::new(
+                    s3_config,
+                    client(endpoint_url)?,
+                    template_engine.clone(),
+                )))
+            }
         }
     }
 }
@@ -12,6 +12,9 @@ pub struct RegistryConfig {
     pub enable_auto_sync: bool,
     pub enable_validation: bool,
     pub cache_config: CacheConfig,
+    /// Signature trust stores, one per repository
+    #[serde(default)]
+    pub trust: Vec<TrustConfig>,
 }
 
 impl Default for RegistryConfig {
@@ -22,10 +25,39 @@ impl Default for RegistryConfig {
             enable_auto_sync: true,
             enable_validation: true,
             cache_config: CacheConfig::default(),
+            trust: Vec::new(),
         }
     }
 }
 
+/// Keys trusted to sign the tasks of one repository, and what to do with
+/// tasks they did not sign
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustConfig {
+    /// Repository URL or filesystem path, as configured in its [`TaskSource`]
+    pub repository: String,
+    #[serde(default)]
+    pub policy: SignaturePolicy,
+    /// Base64-encoded ed25519 public keys
+    #[serde(default)]
+    pub public_keys: Vec<String>,
+}
+
+/// How registry sync treats tasks that are unsigned or fail verification
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignaturePolicy {
+    /// Do not check signatures
+    #[default]
+    Off,
+    /// Sync the task and log a warning
+    Warn,
+    /// Sync the task disabled, with sync status `quarantined`
+    Quarantine,
+    /// Refuse to sync the task
+    Require,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum TaskSource {
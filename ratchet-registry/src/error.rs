@@ -38,6 +38,16 @@ pub enum RegistryError {
     #[error("Not implemented: {0}")]
     NotImplemented(String),
 
+    #[error("Checksum mismatch for {url}: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        url: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("Signature error: {0}")]
+    Signature(String),
+
     #[error("Git error: {0}")]
     GitError(String),
 
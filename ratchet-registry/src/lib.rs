@@ -2,8 +2,10 @@ pub mod cache;
 pub mod config;
 pub mod error;
 pub mod loaders;
+pub mod poller;
 pub mod registry;
 pub mod service;
+pub mod signing;
 pub mod sync;
 pub mod types;
 pub mod watcher;
@@ -12,8 +14,10 @@ pub mod watcher;
 pub use config::{RegistryConfig, TaskSource, WatcherConfig};
 pub use error::{RegistryError, Result};
 pub use loaders::{filesystem::FilesystemLoader, http::HttpLoader, TaskLoader};
+pub use poller::RegistryPoller;
 pub use registry::{DefaultTaskRegistry, TaskRegistry};
 pub use service::{DefaultRegistryService, RegistryService};
+pub use signing::{SignatureStatus, TaskSignature, TaskSigner, TrustPolicies, TrustStore};
 pub use sync::{ConflictResolver, DatabaseSync};
 pub use types::{
     DiscoveredTask, RegistryEvent, SyncResult, TaskDefinition, TaskMetadata, TaskReference, ValidationResult,
@@ -106,6 +106,8 @@ impl EmbeddedLoader {
             created_at: Utc::now(),
             updated_at: Utc::now(),
             checksum: None,
+            signature: None,
+            repository: None,
         };
 
         // Create TaskDefinition
@@ -184,6 +186,8 @@ impl crate::loaders::TaskLoader for EmbeddedLoader {
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
                 checksum: None,
+                signature: None,
+                repository: None,
             };
 
             tasks.push(DiscoveredTask {
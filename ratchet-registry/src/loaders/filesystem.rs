@@ -8,6 +8,7 @@ use uuid::Uuid;
 use crate::config::TaskSource;
 use crate::error::{RegistryError, Result};
 use crate::loaders::TaskLoader;
+use crate::signing::{read_signature, task_content_digest};
 use crate::types::{DiscoveredTask, TaskDefinition, TaskMetadata, TaskReference};
 
 pub struct FilesystemLoader {
@@ -87,7 +88,9 @@ impl FilesystemLoader {
             tags,
             created_at: now,
             updated_at: now,
-            checksum: None, // TODO: Calculate checksum
+            checksum: Some(task_content_digest(path).await?),
+            signature: read_signature(path).await?,
+            repository: None,
         })
     }
 
@@ -213,7 +216,11 @@ impl TaskLoader for FilesystemLoader {
                     base_path: Some(PathBuf::from(path)),
                     recursive: *recursive,
                 };
-                loader.discover_tasks_in_directory(&PathBuf::from(path), source).await
+                let mut discovered = loader.discover_tasks_in_directory(&PathBuf::from(path), source).await?;
+                for task in &mut discovered {
+                    task.metadata.repository = Some(path.clone());
+                }
+                Ok(discovered)
             }
             _ => Err(RegistryError::Configuration(
                 "FilesystemLoader only supports filesystem sources".to_string(),
@@ -28,6 +28,8 @@ use crate::error::{RegistryError, Result};
 #[cfg(feature = "git")]
 use crate::loaders::TaskLoader;
 #[cfg(feature = "git")]
+use crate::signing::{read_signature, task_content_digest};
+#[cfg(feature = "git")]
 use crate::types::{DiscoveredTask, TaskDefinition, TaskMetadata, TaskReference};
 
 #[cfg(feature = "git")]
@@ -203,7 +205,9 @@ impl GitLoader {
             tags,
             created_at: now,
             updated_at: now,
-            checksum: None, // TODO: Calculate checksum from Git commit
+            checksum: Some(task_content_digest(task_path).await?),
+            signature: read_signature(task_path).await?,
+            repository: None,
         })
     }
 
@@ -266,7 +270,7 @@ impl TaskLoader for GitLoader {
         let config = source.git_config().unwrap();
 
         // Try to use registry index for fast discovery
-        if let Ok(Some(index)) = self.load_registry_index(&repo_path).await {
+        let mut discovered = if let Ok(Some(index)) = self.load_registry_index(&repo_path).await {
             info!("Using registry index for fast task discovery");
             let mut discovered = Vec::new();
 
@@ -279,6 +283,12 @@ impl TaskLoader for GitLoader {
                         source: format!("git://{}", task_path.display()),
                     };
 
+                    // Signatures cover the checked-out files, not the digest the index claims
+                    let checksum = task_content_digest(&task_path).await?;
+                    if task_info.checksum.as_ref().is_some_and(|listed| *listed != checksum) {
+                        warn!("Registry index checksum for {} is stale", task_info.name);
+                    }
+
                     // Convert task info to metadata
                     let metadata = TaskMetadata {
                         uuid: task_info.uuid,
@@ -288,7 +298,9 @@ impl TaskLoader for GitLoader {
                         tags: task_info.tags,
                         created_at: Utc::now(), // TODO: Use actual timestamps
                         updated_at: task_info.last_modified,
-                        checksum: task_info.checksum,
+                        checksum: Some(checksum),
+                        signature: read_signature(&task_path).await?,
+                        repository: None,
                     };
 
                     discovered.push(DiscoveredTask {
@@ -299,13 +311,18 @@ impl TaskLoader for GitLoader {
                 }
             }
 
-            return Ok(discovered);
-        }
+            discovered
+        } else {
+            // Fall back to directory scanning
+            info!("Scanning repository directory for tasks");
+            self.scan_tasks_directory(&repo_path, config.subdirectory.as_deref())
+                .await?
+        };
 
-        // Fall back to directory scanning
-        info!("Scanning repository directory for tasks");
-        self.scan_tasks_directory(&repo_path, config.subdirectory.as_deref())
-            .await
+        for task in &mut discovered {
+            task.metadata.repository = source.git_url().map(str::to_string);
+        }
+        Ok(discovered)
     }
 
     async fn load_task(&self, task_ref: &TaskReference) -> Result<TaskDefinition> {
@@ -979,7 +996,9 @@ impl GitoxideLoader {
     async fn load_task_metadata(&self, task_path: &Path) -> Result<TaskMetadata> {
         let metadata_path = task_path.join("metadata.json");
         let metadata_content = fs::read_to_string(metadata_path).await?;
-        let metadata: TaskMetadata = serde_json::from_str(&metadata_content)?;
+        let mut metadata: TaskMetadata = serde_json::from_str(&metadata_content)?;
+        metadata.checksum = Some(task_content_digest(task_path).await?);
+        metadata.signature = read_signature(task_path).await?;
 
         Ok(metadata)
     }
@@ -1033,8 +1052,13 @@ impl TaskLoader for GitoxideLoader {
 
         // Fallback to directory scanning (registry index support can be added later)
         info!("Scanning directory for tasks with gitoxide");
-        self.scan_tasks_directory(&repo_path, config.subdirectory.as_deref())
-            .await
+        let mut discovered = self
+            .scan_tasks_directory(&repo_path, config.subdirectory.as_deref())
+            .await?;
+        for task in &mut discovered {
+            task.metadata.repository = source.git_url().map(str::to_string);
+        }
+        Ok(discovered)
     }
 
     async fn load_task(&self, task_ref: &TaskReference) -> Result<TaskDefinition> {
@@ -1,14 +1,178 @@
+//! HTTP registry loader.
+//!
+//! An HTTP registry is a static or dynamic web server that publishes a JSON
+//! index document at the source URL configured in [`TaskSource::Http`]:
+//!
+//! ```json
+//! {
+//!   "format_version": 1,
+//!   "generated_at": "2025-10-01T12:00:00Z",
+//!   "tasks": [
+//!     {
+//!       "name": "weather-api",
+//!       "version": "1.2.0",
+//!       "uuid": "550e8400-e29b-41d4-a716-446655440000",
+//!       "description": "Fetch weather data",
+//!       "tags": ["weather"],
+//!       "last_modified": "2025-09-30T08:00:00Z",
+//!       "checksum": "sha256:9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08",
+//!       "url": "bundles/weather-api-1.2.0.tar.gz",
+//!       "format": "tar_gz"
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Every entry describes one version of one task. `url` may be absolute or
+//! relative to the index URL, and `checksum` is the SHA-256 digest of the
+//! downloaded bundle bytes. `format` is one of `json` (the default), `tar`,
+//! `tar_gz` or `zip`. When `uuid` is omitted a stable one is derived from the
+//! index URL and task name.
+//!
+//! A `json` bundle is a single document with `metadata`, `script` and optional
+//! `input_schema`, `output_schema`, `dependencies` and `environment` fields.
+//! Archive bundles use the filesystem task layout (`metadata.json`, `main.js`
+//! and optional `input.schema.json` / `output.schema.json`), either at the
+//! archive root or inside a single top-level directory. Archives of more than
+//! [`MAX_ARCHIVE_ENTRIES`] entries or [`MAX_ARCHIVE_BYTES`] unpacked bytes, or
+//! with a file larger than [`MAX_ARCHIVE_FILE_BYTES`], are refused.
+//!
+//! An entry may also carry a `signature` object (see [`crate::signing`])
+//! signing its `checksum`, which registry sync verifies against the trust
+//! store configured for the index URL.
+//!
+//! The credentials of a source are sent with bundle requests only when the
+//! bundle URL has the same origin (scheme, host and port) as the index URL,
+//! and requests carrying them do not follow redirects to other origins.
+//! Indexes larger than [`MAX_INDEX_BYTES`] and bundles larger than
+//! [`MAX_BUNDLE_BYTES`] are not downloaded.
+//!
+//! Index and bundle requests are conditional: `ETag` and `Last-Modified`
+//! validators from earlier responses are sent back as `If-None-Match` and
+//! `If-Modified-Since`, and a `304 Not Modified` reuses the cached body.
+
 use async_trait::async_trait;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use ratchet_http::{HttpClient, HttpMethod, HttpRequest};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::sync::Arc;
-use tracing::warn;
+use tokio::sync::RwLock;
+use tracing::{debug, info};
+use url::Url;
+use uuid::Uuid;
 
-use crate::config::TaskSource;
+use crate::config::{HttpAuth, HttpAuthType, TaskSource};
 use crate::error::{RegistryError, Result};
 use crate::loaders::TaskLoader;
-use crate::types::{DiscoveredTask, TaskDefinition, TaskReference};
+use crate::signing::TaskSignature;
+use crate::types::{DiscoveredTask, TaskDefinition, TaskMetadata, TaskReference};
+
+/// Highest index `format_version` understood by this loader.
+pub const INDEX_FORMAT_VERSION: u32 = 1;
+
+/// Most entries, directories included, an archive bundle may have.
+pub const MAX_ARCHIVE_ENTRIES: usize = 1_000;
+
+/// Most bytes the files of an archive bundle may unpack to in total.
+pub const MAX_ARCHIVE_BYTES: u64 = 32 * 1024 * 1024;
+
+/// Most bytes a single file of an archive bundle may unpack to.
+pub const MAX_ARCHIVE_FILE_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Most bytes an index document may have.
+pub const MAX_INDEX_BYTES: usize = 8 * 1024 * 1024;
+
+/// Most bytes a bundle may have as downloaded.
+pub const MAX_BUNDLE_BYTES: usize = 32 * 1024 * 1024;
+
+/// Index document published by an HTTP registry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryIndex {
+    pub format_version: u32,
+    #[serde(default)]
+    pub generated_at: Option<DateTime<Utc>>,
+    pub tasks: Vec<IndexEntry>,
+}
+
+/// One task version listed in a [`RegistryIndex`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub uuid: Option<Uuid>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub last_modified: Option<DateTime<Utc>>,
+    /// `sha256:<hex>` digest of the bundle bytes
+    pub checksum: String,
+    /// Bundle download URL, absolute or relative to the index URL
+    pub url: String,
+    #[serde(default)]
+    pub format: BundleFormat,
+    /// Detached signature over `checksum`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<TaskSignature>,
+}
+
+/// Encoding of a downloadable task bundle.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BundleFormat {
+    #[default]
+    Json,
+    Tar,
+    TarGz,
+    Zip,
+}
+
+/// Single-document task bundle served with [`BundleFormat::Json`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonBundle {
+    pub metadata: serde_json::Value,
+    pub script: String,
+    #[serde(default)]
+    pub input_schema: Option<serde_json::Value>,
+    #[serde(default)]
+    pub output_schema: Option<serde_json::Value>,
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+    #[serde(default)]
+    pub environment: HashMap<String, String>,
+}
+
+/// Body and validators of a previously fetched document
+#[derive(Debug, Clone)]
+struct CachedDocument {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    body: Arc<Vec<u8>>,
+}
+
+/// Result of a conditional GET
+struct Fetched {
+    body: Arc<Vec<u8>>,
+    modified: bool,
+}
+
+/// Bundle location learned from an index, keyed by resolved bundle URL
+#[derive(Debug, Clone)]
+struct IndexedBundle {
+    entry: IndexEntry,
+    metadata: TaskMetadata,
+    auth: Option<HttpAuth>,
+}
 
 pub struct HttpLoader {
     client: Arc<ratchet_http::HttpManager>,
+    documents: RwLock<HashMap<String, CachedDocument>>,
+    bundles: RwLock<HashMap<String, IndexedBundle>>,
 }
 
 impl Default for HttpLoader {
@@ -19,37 +183,367 @@ impl Default for HttpLoader {
 
 impl HttpLoader {
     pub fn new() -> Self {
+        Self::with_client(Arc::new(ratchet_http::HttpManager::new()))
+    }
+
+    pub fn with_client(client: Arc<ratchet_http::HttpManager>) -> Self {
         Self {
-            client: Arc::new(ratchet_http::HttpManager::new()),
+            client,
+            documents: RwLock::new(HashMap::new()),
+            bundles: RwLock::new(HashMap::new()),
         }
     }
 
-    pub fn with_client(client: Arc<ratchet_http::HttpManager>) -> Self {
-        Self { client }
+    /// Fetch the index of an HTTP source, returning `None` when the server
+    /// reports it unchanged since the previous fetch.
+    pub async fn poll(&self, source: &TaskSource) -> Result<Option<Vec<DiscoveredTask>>> {
+        let (url, auth) = Self::http_source(source)?;
+        let (discovered, modified) = self.discover_from_index(url, auth).await?;
+        Ok(modified.then_some(discovered))
     }
-}
 
-#[async_trait]
-impl TaskLoader for HttpLoader {
-    async fn discover_tasks(&self, source: &TaskSource) -> Result<Vec<DiscoveredTask>> {
+    /// Drop the cached copy of a document so the next fetch is unconditional.
+    pub async fn invalidate(&self, url: &str) {
+        self.documents.write().await.remove(url);
+    }
+
+    fn http_source(source: &TaskSource) -> Result<(&str, Option<&HttpAuth>)> {
         match source {
-            TaskSource::Http { url, .. } => {
-                warn!("HTTP task discovery not yet implemented for URL: {}", url);
-                // TODO: Implement HTTP discovery
-                // This would typically:
-                // 1. GET /tasks endpoint to list available tasks
-                // 2. Parse response to extract task metadata
-                // 3. Convert to DiscoveredTask structs
-                Err(RegistryError::NotImplemented(
-                    "HTTP task discovery is not yet implemented".to_string(),
-                ))
-            }
+            TaskSource::Http { url, auth, .. } => Ok((url.as_str(), auth.as_ref())),
             _ => Err(RegistryError::Configuration(
                 "HttpLoader only supports HTTP sources".to_string(),
             )),
         }
     }
 
+    async fn discover_from_index(
+        &self,
+        index_url: &str,
+        auth: Option<&HttpAuth>,
+    ) -> Result<(Vec<DiscoveredTask>, bool)> {
+        let fetched = self.fetch(index_url, auth, MAX_INDEX_BYTES).await?;
+        let index: RegistryIndex = serde_json::from_slice(&fetched.body)?;
+
+        if index.format_version > INDEX_FORMAT_VERSION {
+            return Err(RegistryError::ValidationError(format!(
+                "Registry index {} uses format version {}, but only versions up to {} are supported",
+                index_url, index.format_version, INDEX_FORMAT_VERSION
+            )));
+        }
+
+        let base = Url::parse(index_url)
+            .map_err(|e| RegistryError::Configuration(format!("Invalid registry URL {}: {}", index_url, e)))?;
+        let discovered_at = Utc::now();
+        let mut discovered = Vec::with_capacity(index.tasks.len());
+        let mut bundles = self.bundles.write().await;
+
+        for entry in index.tasks {
+            let bundle_url = base.join(&entry.url).map_err(|e| {
+                RegistryError::ValidationError(format!("Invalid bundle URL '{}' in {}: {}", entry.url, index_url, e))
+            })?;
+            parse_checksum(&entry.checksum)?;
+
+            let modified_at = entry.last_modified.or(index.generated_at).unwrap_or(discovered_at);
+            let metadata = TaskMetadata {
+                uuid: entry.uuid.unwrap_or_else(|| {
+                    Uuid::new_v5(&Uuid::NAMESPACE_URL, format!("{}#{}", index_url, entry.name).as_bytes())
+                }),
+                name: entry.name.clone(),
+                version: entry.version.clone(),
+                description: entry.description.clone(),
+                tags: entry.tags.clone(),
+                created_at: modified_at,
+                updated_at: modified_at,
+                checksum: Some(entry.checksum.clone()),
+                signature: entry.signature.clone(),
+                repository: Some(index_url.to_string()),
+            };
+            let task_ref = TaskReference {
+                name: entry.name.clone(),
+                version: entry.version.clone(),
+                source: bundle_url.to_string(),
+            };
+
+            // An index may list bundles on other hosts, which must not see its credentials
+            let same_origin = bundle_url.origin() == base.origin();
+            bundles.insert(
+                task_ref.source.clone(),
+                IndexedBundle {
+                    entry,
+                    metadata: metadata.clone(),
+                    auth: auth.filter(|_| same_origin).cloned(),
+                },
+            );
+            discovered.push(DiscoveredTask {
+                task_ref,
+                metadata,
+                discovered_at,
+            });
+        }
+
+        info!(
+            "Discovered {} tasks from registry index {}",
+            discovered.len(),
+            index_url
+        );
+        Ok((discovered, fetched.modified))
+    }
+
+    async fn fetch(&self, url: &str, auth: Option<&HttpAuth>, max_bytes: usize) -> Result<Fetched> {
+        let cached = self.documents.read().await.get(url).cloned();
+
+        // Credentials stay on their origin, whatever header carries them
+        let mut client = self.client.as_ref().clone().with_max_response_bytes(max_bytes);
+        let mut request = HttpRequest::new(HttpMethod::Get, url);
+        if let Some(auth) = auth {
+            let (name, value) = auth_header(auth);
+            request = request.with_header(name, value);
+            client = client.with_same_origin_redirects();
+        }
+        if let Some(cached) = &cached {
+            if let Some(etag) = &cached.etag {
+                request = request.with_header("If-None-Match", etag.as_str());
+            }
+            if let Some(last_modified) = &cached.last_modified {
+                request = request.with_header("If-Modified-Since", last_modified.as_str());
+            }
+        }
+
+        let response = client.send(request).await?;
+
+        if response.status == 304 {
+            if let Some(cached) = cached {
+                debug!("{} not modified, using cached copy", url);
+                return Ok(Fetched {
+                    body: cached.body,
+                    modified: false,
+                });
+            }
+        }
+        if auth.is_some() && (300..400).contains(&response.status) && response.header("location").is_some() {
+            return Err(RegistryError::LoadError(format!(
+                "GET {} redirects to another origin, which is not sent the credentials of the source",
+                url
+            )));
+        }
+        if !response.is_success() {
+            return Err(RegistryError::LoadError(format!(
+                "GET {} returned {} {}",
+                url, response.status, response.status_text
+            )));
+        }
+
+        let document = CachedDocument {
+            etag: response.header("etag").map(str::to_string),
+            last_modified: response.header("last-modified").map(str::to_string),
+            body: Arc::new(response.body),
+        };
+        let body = document.body.clone();
+        if document.etag.is_some() || document.last_modified.is_some() {
+            self.documents.write().await.insert(url.to_string(), document);
+        }
+
+        Ok(Fetched { body, modified: true })
+    }
+
+    fn parse_bundle(bundle: &IndexedBundle, task_ref: &TaskReference, bytes: &[u8]) -> Result<TaskDefinition> {
+        let json_bundle = match bundle.entry.format {
+            BundleFormat::Json => serde_json::from_slice(bytes)?,
+            format => archive_bundle(format, bytes)?,
+        };
+
+        let name = json_bundle.metadata["name"].as_str();
+        let version = json_bundle.metadata["version"].as_str();
+        if name != Some(task_ref.name.as_str()) || version != Some(task_ref.version.as_str()) {
+            return Err(RegistryError::ValidationError(format!(
+                "Bundle {} contains {} {}, but the index lists {} {}",
+                task_ref.source,
+                name.unwrap_or("<unnamed>"),
+                version.unwrap_or("<unversioned>"),
+                task_ref.name,
+                task_ref.version
+            )));
+        }
+
+        let mut metadata = bundle.metadata.clone();
+        if metadata.description.is_none() {
+            metadata.description = json_bundle.metadata["description"].as_str().map(str::to_string);
+        }
+
+        Ok(TaskDefinition {
+            reference: task_ref.clone(),
+            metadata,
+            script: json_bundle.script,
+            input_schema: json_bundle.input_schema,
+            output_schema: json_bundle.output_schema,
+            dependencies: json_bundle.dependencies,
+            environment: json_bundle.environment,
+        })
+    }
+}
+
+fn auth_header(auth: &HttpAuth) -> (String, String) {
+    match &auth.auth_type {
+        HttpAuthType::Bearer { token } => ("Authorization".to_string(), format!("Bearer {}", token)),
+        HttpAuthType::Basic { username, password } => {
+            let credentials = base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", username, password));
+            ("Authorization".to_string(), format!("Basic {}", credentials))
+        }
+        HttpAuthType::ApiKey { header_name, api_key } => (header_name.clone(), api_key.clone()),
+    }
+}
+
+/// Extract the hex digest from a `sha256:<hex>` checksum
+fn parse_checksum(checksum: &str) -> Result<&str> {
+    match checksum.split_once(':') {
+        Some(("sha256", hex)) if hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()) => Ok(hex),
+        _ => Err(RegistryError::ValidationError(format!(
+            "Unsupported checksum '{}', expected sha256:<hex digest>",
+            checksum
+        ))),
+    }
+}
+
+fn verify_checksum(url: &str, expected: &str, bytes: &[u8]) -> Result<()> {
+    let actual = format!("{:x}", Sha256::digest(bytes));
+    if parse_checksum(expected)?.eq_ignore_ascii_case(&actual) {
+        Ok(())
+    } else {
+        Err(RegistryError::ChecksumMismatch {
+            url: url.to_string(),
+            expected: expected.to_string(),
+            actual: format!("sha256:{}", actual),
+        })
+    }
+}
+
+/// Unpack an archive bundle laid out like a filesystem task directory
+#[cfg(feature = "http")]
+fn archive_bundle(format: BundleFormat, bytes: &[u8]) -> Result<JsonBundle> {
+    use std::io::{Cursor, Read};
+
+    let mut files: HashMap<String, Vec<u8>> = HashMap::new();
+    let mut unpacked = 0;
+    match format {
+        BundleFormat::Tar | BundleFormat::TarGz => {
+            let reader: Box<dyn Read + '_> = if format == BundleFormat::TarGz {
+                Box::new(flate2::read::GzDecoder::new(bytes))
+            } else {
+                Box::new(bytes)
+            };
+            let mut archive = tar::Archive::new(reader);
+            for (index, entry) in archive.entries()?.enumerate() {
+                if index >= MAX_ARCHIVE_ENTRIES {
+                    return Err(too_many_entries());
+                }
+                let mut entry = entry?;
+                if !entry.header().entry_type().is_file() {
+                    continue;
+                }
+                let path = entry.path()?.to_string_lossy().into_owned();
+                let content = read_archive_file(&path, &mut entry, &mut unpacked)?;
+                files.insert(path, content);
+            }
+        }
+        BundleFormat::Zip => {
+            let mut archive = zip::ZipArchive::new(Cursor::new(bytes))
+                .map_err(|e| RegistryError::LoadError(format!("Invalid zip bundle: {}", e)))?;
+            if archive.len() > MAX_ARCHIVE_ENTRIES {
+                return Err(too_many_entries());
+            }
+            for i in 0..archive.len() {
+                let mut file = archive
+                    .by_index(i)
+                    .map_err(|e| RegistryError::LoadError(format!("Invalid zip bundle: {}", e)))?;
+                if file.is_dir() {
+                    continue;
+                }
+                let path = file.name().to_string();
+                let content = read_archive_file(&path, &mut file, &mut unpacked)?;
+                files.insert(path, content);
+            }
+        }
+        BundleFormat::Json => unreachable!("JSON bundles are not archives"),
+    }
+
+    // The task root is wherever the shallowest metadata.json lives
+    let root = files
+        .keys()
+        .filter_map(|path| path.trim_start_matches("./").strip_suffix("metadata.json"))
+        .filter(|prefix| prefix.is_empty() || prefix.ends_with('/'))
+        .min_by_key(|prefix| prefix.len())
+        .map(str::to_string)
+        .ok_or_else(|| RegistryError::ValidationError("Bundle archive has no metadata.json".to_string()))?;
+    let mut take = |name: &str| {
+        let key = files
+            .keys()
+            .find(|path| path.trim_start_matches("./") == format!("{}{}", root, name))
+            .cloned();
+        key.and_then(|key| files.remove(&key))
+    };
+
+    let metadata = serde_json::from_slice(&take("metadata.json").unwrap_or_default())?;
+    let script =
+        take("main.js").ok_or_else(|| RegistryError::ValidationError("Bundle archive has no main.js".to_string()))?;
+    let input_schema = take("input.schema.json")
+        .map(|b| serde_json::from_slice(&b))
+        .transpose()?;
+    let output_schema = take("output.schema.json")
+        .map(|b| serde_json::from_slice(&b))
+        .transpose()?;
+
+    Ok(JsonBundle {
+        metadata,
+        script: String::from_utf8(script)
+            .map_err(|e| RegistryError::ValidationError(format!("main.js is not valid UTF-8: {}", e)))?,
+        input_schema,
+        output_schema,
+        dependencies: Vec::new(),
+        environment: HashMap::new(),
+    })
+}
+
+/// Read one file of an archive bundle, adding its size to `unpacked`
+///
+/// Reading stops one byte past the limit, as archive headers may understate sizes.
+#[cfg(feature = "http")]
+fn read_archive_file(path: &str, file: &mut impl std::io::Read, unpacked: &mut u64) -> Result<Vec<u8>> {
+    use std::io::Read;
+
+    let limit = MAX_ARCHIVE_FILE_BYTES.min(MAX_ARCHIVE_BYTES - *unpacked);
+    let mut content = Vec::new();
+    file.take(limit + 1).read_to_end(&mut content)?;
+    if content.len() as u64 > limit {
+        return Err(RegistryError::ValidationError(format!(
+            "Bundle archive file {} exceeds the limit of {} bytes per file and {} bytes in total",
+            path, MAX_ARCHIVE_FILE_BYTES, MAX_ARCHIVE_BYTES
+        )));
+    }
+    *unpacked += content.len() as u64;
+    Ok(content)
+}
+
+#[cfg(feature = "http")]
+fn too_many_entries() -> RegistryError {
+    RegistryError::ValidationError(format!("Bundle archive has more than {} entries", MAX_ARCHIVE_ENTRIES))
+}
+
+#[cfg(not(feature = "http"))]
+fn archive_bundle(format: BundleFormat, _bytes: &[u8]) -> Result<JsonBundle> {
+    Err(RegistryError::NotImplemented(format!(
+        "{:?} bundles require the 'http' feature",
+        format
+    )))
+}
+
+#[async_trait]
+impl TaskLoader for HttpLoader {
+    async fn discover_tasks(&self, source: &TaskSource) -> Result<Vec<DiscoveredTask>> {
+        let (url, auth) = Self::http_source(source)?;
+        let (discovered, _) = self.discover_from_index(url, auth).await?;
+        Ok(discovered)
+    }
+
     async fn load_task(&self, task_ref: &TaskReference) -> Result<TaskDefinition> {
         if !task_ref.source.starts_with("http://") && !task_ref.source.starts_with("https://") {
             return Err(RegistryError::Configuration(
@@ -57,18 +551,25 @@ impl TaskLoader for HttpLoader {
             ));
         }
 
-        warn!("HTTP task loading not yet implemented for: {}", task_ref.source);
+        let bundle = self
+            .bundles
+            .read()
+            .await
+            .get(&task_ref.source)
+            .cloned()
+            .ok_or_else(|| {
+                RegistryError::TaskNotFound(format!(
+                    "{} is not listed in any fetched registry index",
+                    task_ref.source
+                ))
+            })?;
 
-        // TODO: Implement HTTP loading
-        // This would typically:
-        // 1. GET the task endpoint (e.g., /tasks/{name}/{version})
-        // 2. Download and parse the task definition
-        // 3. Handle different content types (JSON, ZIP, etc.)
-        // 4. Apply authentication if configured
+        let fetched = self
+            .fetch(&task_ref.source, bundle.auth.as_ref(), MAX_BUNDLE_BYTES)
+            .await?;
+        verify_checksum(&task_ref.source, &bundle.entry.checksum, &fetched.body)?;
 
-        Err(RegistryError::NotImplemented(
-            "HTTP task loading is not yet implemented".to_string(),
-        ))
+        Self::parse_bundle(&bundle, task_ref, &fetched.body)
     }
 
     async fn supports_source(&self, source: &TaskSource) -> bool {
@@ -0,0 +1,177 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::time::{interval, timeout, MissedTickBehavior};
+use tracing::{debug, error, info, warn};
+
+use crate::config::TaskSource;
+use crate::error::{RegistryError, Result};
+use crate::loaders::{http::HttpLoader, TaskLoader};
+use crate::registry::DefaultTaskRegistry;
+use crate::signing::TrustPolicies;
+use crate::sync::DatabaseSync;
+use crate::types::SyncResult;
+
+/// Periodically re-reads HTTP registry indexes and syncs new or changed
+/// tasks into the registry and, when configured, the database.
+pub struct RegistryPoller {
+    loader: Arc<HttpLoader>,
+    registry: Arc<DefaultTaskRegistry>,
+    sync_service: Option<Arc<DatabaseSync>>,
+    trust: TrustPolicies,
+    sources: Vec<TaskSource>,
+    shutdown_txs: Vec<oneshot::Sender<()>>,
+    handles: Vec<tokio::task::JoinHandle<()>>,
+}
+
+impl RegistryPoller {
+    pub fn new(
+        loader: Arc<HttpLoader>,
+        registry: Arc<DefaultTaskRegistry>,
+        sync_service: Option<Arc<DatabaseSync>>,
+    ) -> Self {
+        Self {
+            loader,
+            registry,
+            sync_service,
+            trust: TrustPolicies::default(),
+            sources: Vec::new(),
+            shutdown_txs: Vec::new(),
+            handles: Vec::new(),
+        }
+    }
+
+    /// Register only tasks whose signatures satisfy `trust`
+    pub fn with_trust(mut self, trust: TrustPolicies) -> Self {
+        self.trust = trust;
+        self
+    }
+
+    pub fn add_source(&mut self, source: TaskSource) -> Result<()> {
+        match source {
+            TaskSource::Http { .. } => {
+                self.sources.push(source);
+                Ok(())
+            }
+            _ => Err(RegistryError::Configuration(
+                "RegistryPoller only supports HTTP sources".to_string(),
+            )),
+        }
+    }
+
+    pub async fn start(&mut self) -> Result<()> {
+        for source in &self.sources {
+            let TaskSource::Http {
+                url, polling_interval, ..
+            } = source
+            else {
+                continue;
+            };
+            if polling_interval.is_zero() {
+                return Err(RegistryError::Configuration(format!(
+                    "HTTP source {} has a zero polling interval",
+                    url
+                )));
+            }
+
+            let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
+            let loader = self.loader.clone();
+            let registry = self.registry.clone();
+            let sync_service = self.sync_service.clone();
+            let trust = self.trust.clone();
+            let source = source.clone();
+            let mut ticker = interval(*polling_interval);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            info!("Polling HTTP registry {} every {:?}", url, polling_interval);
+            self.handles.push(tokio::spawn(async move {
+                loop {
+                    tokio::select! {
+                        _ = ticker.tick() => {
+                            let sync_service = sync_service.as_deref();
+                            if let Err(e) = Self::poll_source(&loader, &registry, sync_service, &trust, &source).await {
+                                error!("Failed to poll HTTP registry: {}", e);
+                            }
+                        }
+                        _ = &mut shutdown_rx => break,
+                    }
+                }
+            }));
+            self.shutdown_txs.push(shutdown_tx);
+        }
+
+        Ok(())
+    }
+
+    pub async fn stop(&mut self) -> Result<()> {
+        for tx in self.shutdown_txs.drain(..) {
+            let _ = tx.send(());
+        }
+        for handle in self.handles.drain(..) {
+            let _ = timeout(Duration::from_secs(5), handle).await;
+        }
+
+        info!("Registry poller stopped");
+        Ok(())
+    }
+
+    /// Poll one HTTP source, returning `None` when its index is unchanged.
+    ///
+    /// Tasks are synced to the database and, once their signatures satisfy
+    /// `trust`, loaded into the registry. If any task fails, the cached index
+    /// is dropped so the next poll retries it.
+    pub async fn poll_source(
+        loader: &HttpLoader,
+        registry: &DefaultTaskRegistry,
+        sync_service: Option<&DatabaseSync>,
+        trust: &TrustPolicies,
+        source: &TaskSource,
+    ) -> Result<Option<SyncResult>> {
+        let Some(discovered) = loader.poll(source).await? else {
+            debug!("HTTP registry index unchanged");
+            return Ok(None);
+        };
+
+        let mut result = SyncResult::new();
+        let mut definitions = Vec::with_capacity(discovered.len());
+        let mut runnable = Vec::with_capacity(discovered.len());
+        for task in discovered {
+            let checked = loader
+                .load_task(&task.task_ref)
+                .await
+                .and_then(|definition| Ok((trust.admits(&definition.metadata)?, definition)));
+            match checked {
+                Ok((admitted, definition)) => {
+                    // Quarantined tasks are stored disabled, but never registered to run
+                    if admitted {
+                        runnable.push(definition.clone());
+                    }
+                    definitions.push(definition);
+                }
+                Err(e) => {
+                    warn!("Failed to load {} {}: {}", task.task_ref.name, task.task_ref.version, e);
+                    result.add_error(task.task_ref, e.to_string());
+                }
+            }
+        }
+
+        if let Some(sync_service) = sync_service {
+            let synced = sync_service.sync_task_definitions(definitions).await?;
+            result.tasks_added += synced.tasks_added;
+            result.tasks_updated += synced.tasks_updated;
+            result.errors.extend(synced.errors);
+        }
+
+        for definition in runnable {
+            registry.add_task(definition).await?;
+        }
+
+        if !result.errors.is_empty() {
+            if let TaskSource::Http { url, .. } = source {
+                loader.invalidate(url).await;
+            }
+        }
+
+        Ok(Some(result))
+    }
+}
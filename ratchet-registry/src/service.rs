@@ -1,12 +1,14 @@
 use async_trait::async_trait;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 use tracing::{error, info};
 
 use crate::config::{RegistryConfig, TaskSource};
 use crate::error::{RegistryError, Result};
 use crate::loaders::{filesystem::FilesystemLoader, git::GitLoader, http::HttpLoader, TaskLoader};
+use crate::poller::RegistryPoller;
 use crate::registry::DefaultTaskRegistry;
+use crate::signing::TrustPolicies;
 use crate::sync::DatabaseSync;
 use crate::types::{DiscoveredTask, SyncResult, TaskDefinition, TaskReference};
 use crate::watcher::RegistryWatcher;
@@ -24,10 +26,12 @@ pub trait RegistryService: Send + Sync {
 pub struct DefaultRegistryService {
     registry: Arc<DefaultTaskRegistry>,
     filesystem_loader: FilesystemLoader,
-    http_loader: HttpLoader,
+    http_loader: Arc<HttpLoader>,
     git_loader: GitLoader,
     sync_service: Option<Arc<DatabaseSync>>,
+    trust: TrustPolicies,
     watcher: Option<Arc<RwLock<RegistryWatcher>>>,
+    poller: Mutex<Option<RegistryPoller>>,
     config: RegistryConfig,
 }
 
@@ -37,10 +41,12 @@ impl DefaultRegistryService {
         Self {
             registry: Arc::new(DefaultTaskRegistry::with_sources(sources)),
             filesystem_loader: FilesystemLoader::new(),
-            http_loader: HttpLoader::new(),
+            http_loader: Arc::new(HttpLoader::new()),
             git_loader: GitLoader::new(),
             sync_service: None,
+            trust: TrustPolicies::default(),
             watcher: None,
+            poller: Mutex::new(None),
             config,
         }
     }
@@ -50,6 +56,54 @@ impl DefaultRegistryService {
         self
     }
 
+    /// Register only tasks whose signatures satisfy the policies of their repositories
+    pub fn with_trust(mut self, trust: TrustPolicies) -> Self {
+        self.trust = trust;
+        self
+    }
+
+    /// Trust policies tasks are checked against before they are registered
+    pub fn trust(&self) -> &TrustPolicies {
+        &self.trust
+    }
+
+    /// Fetch HTTP sources with `client`, for example one sending requests through a proxy
+    pub fn with_http_client(mut self, client: Arc<ratchet_http::HttpManager>) -> Self {
+        self.http_loader = Arc::new(HttpLoader::with_client(client));
+        self
+    }
+
+    /// Start polling every configured HTTP source at its `polling_interval`.
+    async fn start_polling(&self) -> Result<()> {
+        let mut current = self.poller.lock().await;
+        if current.is_some() {
+            return Ok(());
+        }
+
+        let mut poller = RegistryPoller::new(
+            self.http_loader.clone(),
+            self.registry.clone(),
+            self.sync_service.clone(),
+        )
+        .with_trust(self.trust.clone());
+        let mut sources = 0;
+        for source in &self.config.sources {
+            if let TaskSource::Http { .. } = source {
+                poller.add_source(source.clone())?;
+                sources += 1;
+            }
+        }
+
+        if sources == 0 {
+            info!("No HTTP sources configured for polling");
+            return Ok(());
+        }
+
+        poller.start().await?;
+        *current = Some(poller);
+        Ok(())
+    }
+
     async fn discover_from_source(&self, source: &TaskSource) -> Result<Vec<DiscoveredTask>> {
         info!("Discovering tasks from source: {:?}", source);
 
@@ -65,19 +119,32 @@ impl DefaultRegistryService {
 
     async fn load_discovered_tasks(&self, discovered: Vec<DiscoveredTask>) -> Result<()> {
         for task in discovered {
-            // Load the full task definition
-            let task_def = self.load_task(&task.task_ref).await?;
-
-            // Add to registry
-            if let Err(e) = self.registry.add_task(task_def.clone()).await {
-                error!("Failed to add task to registry: {}", e);
-            } else {
-                // Auto-sync to database if sync service is available
-                if let Some(sync_service) = &self.sync_service {
-                    let discovered_tasks = vec![task];
-                    if let Err(e) = sync_service.sync_discovered_tasks(discovered_tasks).await {
-                        error!("Failed to sync task to database: {}", e);
-                    }
+            // Load the full task definition; loaders only record the repository of tasks they discover
+            let mut task_def = self.load_task(&task.task_ref).await?;
+            if task_def.metadata.repository.is_none() {
+                task_def.metadata.repository = task.metadata.repository.clone();
+            }
+
+            // Only tasks whose signature checks out are registered; quarantined ones are still synced
+            let admitted = match self.trust.admits(&task_def.metadata) {
+                Ok(admitted) => admitted,
+                Err(e) => {
+                    error!("Not loading task {}: {}", task.task_ref.name, e);
+                    continue;
+                }
+            };
+            if admitted {
+                if let Err(e) = self.registry.add_task(task_def.clone()).await {
+                    error!("Failed to add task to registry: {}", e);
+                    continue;
+                }
+            }
+
+            // Auto-sync to database if sync service is available
+            if let Some(sync_service) = &self.sync_service {
+                let discovered_tasks = vec![task];
+                if let Err(e) = sync_service.sync_discovered_tasks(discovered_tasks).await {
+                    error!("Failed to sync task to database: {}", e);
                 }
             }
         }
@@ -139,6 +206,8 @@ impl RegistryService for DefaultRegistryService {
     }
 
     async fn start_watching(&self) -> Result<()> {
+        self.start_polling().await?;
+
         // Collect filesystem sources with watch enabled
         let mut watch_paths = Vec::new();
 
@@ -176,6 +245,9 @@ impl RegistryService for DefaultRegistryService {
     }
 
     async fn stop_watching(&self) -> Result<()> {
+        if let Some(mut poller) = self.poller.lock().await.take() {
+            poller.stop().await?;
+        }
         if let Some(watcher_arc) = &self.watcher {
             let mut watcher = watcher_arc.write().await;
             watcher.stop().await?;
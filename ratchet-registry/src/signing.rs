@@ -0,0 +1,431 @@
+//! Detached ed25519 signatures for registry tasks.
+//!
+//! A signed task directory carries a `signature.json` next to its
+//! `metadata.json`:
+//!
+//! ```json
+//! {
+//!   "algorithm": "ed25519",
+//!   "key_id": "3f9a0c1d2b4e5f60",
+//!   "signature": "<base64>"
+//! }
+//! ```
+//!
+//! The signature covers the task's content digest, the `sha256:<hex>` value
+//! loaders store in [`TaskMetadata::checksum`](crate::types::TaskMetadata).
+//! For directory tasks the digest is computed over `metadata.json`, `main.js`
+//! and the optional schema files; for HTTP registries it is the bundle
+//! checksum listed in the index, so an index entry can carry the signature
+//! instead of the bundle.
+//!
+//! Public keys are configured per repository in
+//! [`RegistryConfig::trust`](crate::config::RegistryConfig) as base64-encoded
+//! raw 32-byte ed25519 keys. A key ID is the first 16 hex digits of the
+//! SHA-256 digest of the public key.
+
+use base64::Engine;
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+use tokio::fs;
+
+use tracing::warn;
+
+use crate::config::{SignaturePolicy, TrustConfig};
+use crate::error::{RegistryError, Result};
+use crate::types::TaskMetadata;
+
+/// File holding the detached signature of a task directory
+pub const SIGNATURE_FILE: &str = "signature.json";
+
+/// Only signature algorithm understood by this registry
+pub const SIGNATURE_ALGORITHM: &str = "ed25519";
+
+/// Files covered by a task directory's content digest, in digest order
+const SIGNED_FILES: [&str; 4] = ["metadata.json", "main.js", "input.schema.json", "output.schema.json"];
+
+/// Prefix of every signed message, so a task signature cannot be replayed
+/// as a signature over anything else
+const SIGNATURE_CONTEXT: &str = "ratchet-task-signature-v1\n";
+
+/// Detached signature over a task's content digest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskSignature {
+    pub algorithm: String,
+    pub key_id: String,
+    /// Base64-encoded signature bytes
+    pub signature: String,
+}
+
+/// Outcome of checking a task's signature against a trust store
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// Signed by a trusted key
+    Verified { key_id: String },
+    /// No signature, or no digest to check it against
+    Unsigned,
+    /// Signed by a key the trust store does not contain
+    Untrusted { key_id: String },
+    /// The signature is malformed or does not match the digest
+    Invalid { reason: String },
+}
+
+impl SignatureStatus {
+    /// Status name as stored with a task
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignatureStatus::Verified { .. } => "verified",
+            SignatureStatus::Unsigned => "unsigned",
+            SignatureStatus::Untrusted { .. } => "untrusted",
+            SignatureStatus::Invalid { .. } => "invalid",
+        }
+    }
+
+    /// ID of the key the task claims to be signed with
+    pub fn key_id(&self) -> Option<&str> {
+        match self {
+            SignatureStatus::Verified { key_id } | SignatureStatus::Untrusted { key_id } => Some(key_id),
+            _ => None,
+        }
+    }
+
+    pub fn is_verified(&self) -> bool {
+        matches!(self, SignatureStatus::Verified { .. })
+    }
+}
+
+impl std::fmt::Display for SignatureStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignatureStatus::Verified { key_id } => write!(f, "verified by key {}", key_id),
+            SignatureStatus::Unsigned => write!(f, "unsigned"),
+            SignatureStatus::Untrusted { key_id } => write!(f, "signed by untrusted key {}", key_id),
+            SignatureStatus::Invalid { reason } => write!(f, "invalid signature: {}", reason),
+        }
+    }
+}
+
+/// Compute the `sha256:<hex>` content digest of a task directory.
+///
+/// Each present file contributes its name, a NUL byte, its length as a
+/// big-endian `u64` and its bytes, so moving content between files changes
+/// the digest.
+pub async fn task_content_digest(task_dir: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    for file in SIGNED_FILES {
+        let path = task_dir.join(file);
+        if !fs::try_exists(&path).await? {
+            continue;
+        }
+        let content = fs::read(&path).await?;
+        hasher.update(file.as_bytes());
+        hasher.update([0u8]);
+        hasher.update((content.len() as u64).to_be_bytes());
+        hasher.update(&content);
+    }
+    Ok(format!("sha256:{:x}", hasher.finalize()))
+}
+
+/// Read the detached signature of a task directory, if it has one
+pub async fn read_signature(task_dir: &Path) -> Result<Option<TaskSignature>> {
+    let path = task_dir.join(SIGNATURE_FILE);
+    if !fs::try_exists(&path).await? {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path).await?;
+    Ok(Some(serde_json::from_str(&content)?))
+}
+
+/// ID of an ed25519 public key
+pub fn key_id(public_key: &[u8]) -> String {
+    let digest = format!("{:x}", Sha256::digest(public_key));
+    digest[..16].to_string()
+}
+
+fn signed_message(digest: &str) -> Vec<u8> {
+    format!("{}{}", SIGNATURE_CONTEXT, digest).into_bytes()
+}
+
+/// Signs task digests with an ed25519 private key
+pub struct TaskSigner {
+    key_pair: Ed25519KeyPair,
+}
+
+impl TaskSigner {
+    /// Load a signer from a PKCS#8 (v1 or v2) encoded private key
+    pub fn from_pkcs8(pkcs8: &[u8]) -> Result<Self> {
+        let key_pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(pkcs8)
+            .map_err(|e| RegistryError::Signature(format!("Invalid ed25519 private key: {}", e)))?;
+        Ok(Self { key_pair })
+    }
+
+    /// Generate a new private key, returned PKCS#8 encoded
+    pub fn generate() -> Result<Vec<u8>> {
+        let document = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+            .map_err(|_| RegistryError::Signature("Failed to generate ed25519 key".to_string()))?;
+        Ok(document.as_ref().to_vec())
+    }
+
+    pub fn key_id(&self) -> String {
+        key_id(self.key_pair.public_key().as_ref())
+    }
+
+    /// Public key in the form expected by a trust store
+    pub fn public_key_base64(&self) -> String {
+        base64::engine::general_purpose::STANDARD.encode(self.key_pair.public_key().as_ref())
+    }
+
+    /// Sign a `sha256:<hex>` task digest
+    pub fn sign(&self, digest: &str) -> TaskSignature {
+        let signature = self.key_pair.sign(&signed_message(digest));
+        TaskSignature {
+            algorithm: SIGNATURE_ALGORITHM.to_string(),
+            key_id: self.key_id(),
+            signature: base64::engine::general_purpose::STANDARD.encode(signature.as_ref()),
+        }
+    }
+
+    /// Sign a task directory, writing its `signature.json`
+    pub async fn sign_task_directory(&self, task_dir: &Path) -> Result<TaskSignature> {
+        let digest = task_content_digest(task_dir).await?;
+        let signature = self.sign(&digest);
+        fs::write(task_dir.join(SIGNATURE_FILE), serde_json::to_vec_pretty(&signature)?).await?;
+        Ok(signature)
+    }
+}
+
+/// Public keys trusted to sign the tasks of one repository
+#[derive(Debug, Clone, Default)]
+pub struct TrustStore {
+    keys: HashMap<String, Vec<u8>>,
+}
+
+impl TrustStore {
+    /// Build a trust store from base64-encoded ed25519 public keys
+    pub fn from_public_keys(public_keys: &[String]) -> Result<Self> {
+        let mut keys = HashMap::with_capacity(public_keys.len());
+        for encoded in public_keys {
+            let key = base64::engine::general_purpose::STANDARD
+                .decode(encoded.trim())
+                .map_err(|e| RegistryError::Configuration(format!("Invalid trusted public key: {}", e)))?;
+            if key.len() != 32 {
+                return Err(RegistryError::Configuration(format!(
+                    "Trusted public key must be 32 bytes, got {}",
+                    key.len()
+                )));
+            }
+            keys.insert(key_id(&key), key);
+        }
+        Ok(Self { keys })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Check a signature over a task digest
+    pub fn verify(&self, digest: Option<&str>, signature: Option<&TaskSignature>) -> SignatureStatus {
+        let (Some(digest), Some(signature)) = (digest, signature) else {
+            return SignatureStatus::Unsigned;
+        };
+        if signature.algorithm != SIGNATURE_ALGORITHM {
+            return SignatureStatus::Invalid {
+                reason: format!("unsupported algorithm '{}'", signature.algorithm),
+            };
+        }
+        let Some(key) = self.keys.get(&signature.key_id) else {
+            return SignatureStatus::Untrusted {
+                key_id: signature.key_id.clone(),
+            };
+        };
+        let bytes = match base64::engine::general_purpose::STANDARD.decode(&signature.signature) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                return SignatureStatus::Invalid {
+                    reason: format!("signature is not base64: {}", e),
+                }
+            }
+        };
+
+        match UnparsedPublicKey::new(&ED25519, key).verify(&signed_message(digest), &bytes) {
+            Ok(()) => SignatureStatus::Verified {
+                key_id: signature.key_id.clone(),
+            },
+            Err(_) => SignatureStatus::Invalid {
+                reason: format!("signature by key {} does not match the task contents", signature.key_id),
+            },
+        }
+    }
+}
+
+/// Trust stores and signature policies of repositories, by repository
+#[derive(Debug, Clone, Default)]
+pub struct TrustPolicies {
+    repositories: HashMap<String, (SignaturePolicy, TrustStore)>,
+}
+
+/// Signature check applied to a task before it is registered or stored
+#[derive(Debug, Clone)]
+pub struct Verification {
+    pub status: SignatureStatus,
+    /// Whether the task is kept disabled until it verifies
+    pub quarantined: bool,
+}
+
+impl TrustPolicies {
+    /// Build the policies of the given repositories.
+    ///
+    /// Tasks from repositories without a trust config are not checked.
+    pub fn from_config(trust: &[TrustConfig]) -> Result<Self> {
+        let mut repositories = HashMap::with_capacity(trust.len());
+        for config in trust {
+            let store = TrustStore::from_public_keys(&config.public_keys)?;
+            if store.is_empty() && config.policy != SignaturePolicy::Off {
+                warn!(
+                    "No public keys trusted for {}; none of its tasks will verify",
+                    config.repository
+                );
+            }
+            repositories.insert(config.repository.clone(), (config.policy, store));
+        }
+        Ok(Self { repositories })
+    }
+
+    /// Check a task's signature against its repository's trust store.
+    ///
+    /// Returns `None` when the repository has no trust config or its policy is
+    /// off, and an error when the policy requires a signature that did not verify.
+    pub fn verify(&self, metadata: &TaskMetadata) -> Result<Option<Verification>> {
+        let Some((policy, store)) = metadata
+            .repository
+            .as_ref()
+            .and_then(|repository| self.repositories.get(repository))
+        else {
+            return Ok(None);
+        };
+
+        if *policy == SignaturePolicy::Off {
+            return Ok(None);
+        }
+
+        let status = store.verify(metadata.checksum.as_deref(), metadata.signature.as_ref());
+        if status.is_verified() {
+            return Ok(Some(Verification {
+                status,
+                quarantined: false,
+            }));
+        }
+
+        match policy {
+            SignaturePolicy::Require => Err(RegistryError::Signature(format!(
+                "Refusing task {} v{}: {}",
+                metadata.name, metadata.version, status
+            ))),
+            SignaturePolicy::Quarantine => {
+                warn!("Quarantining task {} v{}: {}", metadata.name, metadata.version, status);
+                Ok(Some(Verification {
+                    status,
+                    quarantined: true,
+                }))
+            }
+            _ => {
+                warn!("Task {} v{} is {}", metadata.name, metadata.version, status);
+                Ok(Some(Verification {
+                    status,
+                    quarantined: false,
+                }))
+            }
+        }
+    }
+
+    /// Whether a task may be registered to run.
+    ///
+    /// Quarantined tasks may not; tasks the policy refuses are an error.
+    pub fn admits(&self, metadata: &TaskMetadata) -> Result<bool> {
+        match self.verify(metadata)? {
+            Some(verification) => Ok(!verification.quarantined),
+            None => Ok(true),
+        }
+    }
+
+    /// Whether tasks of `repository` may only run once their signature verified
+    pub fn requires_verification(&self, repository: &str) -> bool {
+        self.repositories
+            .get(repository)
+            .is_some_and(|(policy, _)| matches!(policy, SignaturePolicy::Quarantine | SignaturePolicy::Require))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    async fn task_dir() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("metadata.json"),
+            r#"{"name": "signed", "version": "1.0.0"}"#,
+        )
+        .await
+        .unwrap();
+        fs::write(dir.path().join("main.js"), "(function(input) { return input; })")
+            .await
+            .unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_sign_and_verify_task_directory() {
+        let dir = task_dir().await;
+        let signer = TaskSigner::from_pkcs8(&TaskSigner::generate().unwrap()).unwrap();
+        let trust = TrustStore::from_public_keys(&[signer.public_key_base64()]).unwrap();
+
+        let signature = signer.sign_task_directory(dir.path()).await.unwrap();
+        assert_eq!(read_signature(dir.path()).await.unwrap(), Some(signature.clone()));
+
+        let digest = task_content_digest(dir.path()).await.unwrap();
+        let status = trust.verify(Some(&digest), Some(&signature));
+        assert_eq!(
+            status,
+            SignatureStatus::Verified {
+                key_id: signer.key_id()
+            }
+        );
+
+        // Any change to the signed files invalidates the signature
+        fs::write(dir.path().join("main.js"), "(function(input) { return {}; })")
+            .await
+            .unwrap();
+        let digest = task_content_digest(dir.path()).await.unwrap();
+        assert_eq!(trust.verify(Some(&digest), Some(&signature)).as_str(), "invalid");
+    }
+
+    #[tokio::test]
+    async fn test_verify_reports_untrusted_and_unsigned() {
+        let dir = task_dir().await;
+        let digest = task_content_digest(dir.path()).await.unwrap();
+        let trusted = TaskSigner::from_pkcs8(&TaskSigner::generate().unwrap()).unwrap();
+        let stranger = TaskSigner::from_pkcs8(&TaskSigner::generate().unwrap()).unwrap();
+        let trust = TrustStore::from_public_keys(&[trusted.public_key_base64()]).unwrap();
+
+        let status = trust.verify(Some(&digest), Some(&stranger.sign(&digest)));
+        assert_eq!(
+            status,
+            SignatureStatus::Untrusted {
+                key_id: stranger.key_id()
+            }
+        );
+        assert_eq!(trust.verify(Some(&digest), None), SignatureStatus::Unsigned);
+        assert_eq!(read_signature(dir.path()).await.unwrap(), None);
+    }
+
+    #[test]
+    fn test_trust_store_rejects_malformed_keys() {
+        assert!(TrustStore::from_public_keys(&["not base64!".to_string()]).is_err());
+        assert!(TrustStore::from_public_keys(&["AAAA".to_string()]).is_err());
+    }
+}
@@ -3,9 +3,11 @@ use std::sync::Arc;
 use sha2::{Digest, Sha256};
 use tracing::{error, info};
 
+use crate::config::TrustConfig;
 use crate::error::{RegistryError, Result};
+use crate::signing::{TrustPolicies, Verification};
 use crate::sync::ConflictResolver;
-use crate::types::{DiscoveredTask, SyncError, SyncResult, TaskReference};
+use crate::types::{DiscoveredTask, SyncError, SyncResult, TaskDefinition, TaskReference};
 
 // SeaORM repository imports
 use ratchet_storage::seaorm::entities::tasks;
@@ -14,6 +16,7 @@ use ratchet_storage::seaorm::repositories::RepositoryFactory;
 pub struct DatabaseSync {
     repository_factory: Arc<RepositoryFactory>,
     conflict_resolver: ConflictResolver,
+    trust: TrustPolicies,
 }
 
 impl DatabaseSync {
@@ -21,6 +24,7 @@ impl DatabaseSync {
         Self {
             repository_factory,
             conflict_resolver: ConflictResolver::new(),
+            trust: TrustPolicies::default(),
         }
     }
 
@@ -29,8 +33,44 @@ impl DatabaseSync {
         self
     }
 
+    /// Verify task signatures of the given repositories before syncing them.
+    ///
+    /// Tasks from repositories without a trust config are synced unchecked.
+    pub fn with_trust_config(mut self, trust: &[TrustConfig]) -> Result<Self> {
+        self.trust = TrustPolicies::from_config(trust)?;
+        Ok(self)
+    }
+
+    /// Trust policies tasks are checked against before they are stored
+    pub fn trust(&self) -> &TrustPolicies {
+        &self.trust
+    }
+
     pub async fn sync_discovered_tasks(&self, tasks: Vec<DiscoveredTask>) -> Result<SyncResult> {
         info!("Starting database sync of {} discovered tasks", tasks.len());
+        self.sync_tasks(tasks.into_iter().map(|task| (task, None)).collect())
+            .await
+    }
+
+    /// Sync fully loaded task definitions, storing their source code and schemas.
+    pub async fn sync_task_definitions(&self, definitions: Vec<TaskDefinition>) -> Result<SyncResult> {
+        info!("Starting database sync of {} task definitions", definitions.len());
+        let now = chrono::Utc::now();
+        let tasks = definitions
+            .into_iter()
+            .map(|definition| {
+                let discovered = DiscoveredTask {
+                    task_ref: definition.reference.clone(),
+                    metadata: definition.metadata.clone(),
+                    discovered_at: now,
+                };
+                (discovered, Some(definition))
+            })
+            .collect();
+        self.sync_tasks(tasks).await
+    }
+
+    async fn sync_tasks(&self, tasks: Vec<(DiscoveredTask, Option<TaskDefinition>)>) -> Result<SyncResult> {
         let mut sync_result = SyncResult {
             tasks_added: 0,
             tasks_updated: 0,
@@ -38,8 +78,8 @@ impl DatabaseSync {
             errors: Vec::new(),
         };
 
-        for discovered_task in tasks {
-            match self.sync_single_task(&discovered_task).await {
+        for (discovered_task, definition) in tasks {
+            match self.sync_single_task(&discovered_task, definition.as_ref()).await {
                 Ok(sync_type) => {
                     match sync_type {
                         SyncType::Added => sync_result.tasks_added += 1,
@@ -72,7 +112,13 @@ impl DatabaseSync {
         Ok(sync_result)
     }
 
-    async fn sync_single_task(&self, discovered_task: &DiscoveredTask) -> Result<SyncType> {
+    async fn sync_single_task(
+        &self,
+        discovered_task: &DiscoveredTask,
+        definition: Option<&TaskDefinition>,
+    ) -> Result<SyncType> {
+        let verification = self.trust.verify(&discovered_task.metadata)?;
+        let verification = verification.as_ref();
         let task_repo = self.repository_factory.task_repository();
 
         // Check if task already exists by finding all tasks with matching name and version
@@ -94,7 +140,12 @@ impl DatabaseSync {
                 match resolution {
                     ConflictResolution::UseRegistry => {
                         // Update the existing task with registry data
-                        let updated_task = self.convert_discovered_to_task_model(discovered_task, Some(existing.id))?;
+                        let updated_task = self.convert_discovered_to_task_model(
+                            discovered_task,
+                            definition,
+                            verification,
+                            Some(existing.id),
+                        )?;
                         task_repo
                             .update(updated_task)
                             .await
@@ -115,7 +166,12 @@ impl DatabaseSync {
                     }
                     ConflictResolution::Merge => {
                         // For now, merge is the same as UseRegistry
-                        let updated_task = self.convert_discovered_to_task_model(discovered_task, Some(existing.id))?;
+                        let updated_task = self.convert_discovered_to_task_model(
+                            discovered_task,
+                            definition,
+                            verification,
+                            Some(existing.id),
+                        )?;
                         task_repo
                             .update(updated_task)
                             .await
@@ -131,7 +187,8 @@ impl DatabaseSync {
             }
             None => {
                 // Task doesn't exist, add it
-                let new_task = self.convert_discovered_to_task_model(discovered_task, None)?;
+                let new_task =
+                    self.convert_discovered_to_task_model(discovered_task, definition, verification, None)?;
                 task_repo
                     .create(new_task)
                     .await
@@ -149,9 +206,16 @@ impl DatabaseSync {
     fn convert_discovered_to_task_model(
         &self,
         discovered: &DiscoveredTask,
+        definition: Option<&TaskDefinition>,
+        verification: Option<&Verification>,
         existing_id: Option<i32>,
     ) -> Result<tasks::Model> {
         let now = chrono::Utc::now();
+        let source_code = definition
+            .map(|definition| definition.script.clone())
+            .unwrap_or_else(|| "// TODO: Load source code from registry".to_string());
+        let schema = |schema: Option<&serde_json::Value>| schema.cloned().unwrap_or_else(|| serde_json::json!({}));
+        let quarantined = verification.is_some_and(|verification| verification.quarantined);
 
         // Generate a path from the task reference and source
         let path = format!(
@@ -169,19 +233,19 @@ impl DatabaseSync {
             version: discovered.metadata.version.clone(),
             path: Some(path.clone()),
             metadata: serde_json::to_value(&discovered.metadata).map_err(|e| RegistryError::Json(e))?,
-            input_schema: serde_json::json!({}), // Default empty schema - TODO: Load from task definition
-            output_schema: serde_json::json!({}), // Default empty schema - TODO: Load from task definition
-            enabled: true,                       // New tasks are enabled by default
+            input_schema: schema(definition.and_then(|definition| definition.input_schema.as_ref())),
+            output_schema: schema(definition.and_then(|definition| definition.output_schema.as_ref())),
+            enabled: !quarantined, // Quarantined tasks stay disabled until they verify
             // New required fields for full task storage
-            source_code: "// TODO: Load source code from registry".to_string(), // Will be loaded in Phase 2
+            checksum: format!("{:x}", Sha256::digest(source_code.as_bytes())),
+            source_code,
             source_type: "javascript".to_string(),
             storage_type: "registry".to_string(),
             file_path: Some(path.clone()),
-            checksum: format!("{:x}", Sha256::digest("// TODO: Load source code from registry".as_bytes())), // Placeholder checksum
             repository_id: 1, // Default repository - TODO: make configurable
             repository_path: path.clone(),
             last_synced_at: Some(now),
-            sync_status: "synced".to_string(),
+            sync_status: if quarantined { "quarantined" } else { "synced" }.to_string(),
             is_editable: false, // Registry tasks are read-only
             created_from: "registry".to_string(),
             needs_push: false,
@@ -191,6 +255,8 @@ impl DatabaseSync {
             updated_at: discovered.metadata.updated_at,
             validated_at: Some(now), // Mark as validated since it came from registry
             source_modified_at: Some(discovered.metadata.updated_at),
+            signature_status: verification.map(|verification| verification.status.as_str().to_string()),
+            signed_by: verification.and_then(|verification| verification.status.key_id().map(str::to_string)),
         })
     }
 
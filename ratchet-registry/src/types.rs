@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+use crate::signing::TaskSignature;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiscoveredTask {
     pub task_ref: TaskReference,
@@ -27,6 +29,12 @@ pub struct TaskMetadata {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub checksum: Option<String>,
+    /// Detached signature over `checksum`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<TaskSignature>,
+    /// Configured source the task was discovered in, used to pick its trust store
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repository: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,474 @@
+use axum::extract::{Path, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use ratchet_registry::config::{HttpAuth, HttpAuthType};
+use ratchet_registry::prelude::*;
+use ratchet_registry::{DatabaseSync, HttpLoader, RegistryPoller, TaskLoader};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+const TOKEN: &str = "registry-token";
+const INDEX_ETAG: &str = "\"index-v1\"";
+
+/// Minimal HTTP registry serving an index and its bundles
+struct StandIn {
+    index: Vec<u8>,
+    bundles: HashMap<String, Vec<u8>>,
+    index_requests: AtomicUsize,
+    not_modified: AtomicUsize,
+}
+
+async fn serve_index(State(state): State<Arc<StandIn>>, headers: HeaderMap) -> Response {
+    state.index_requests.fetch_add(1, Ordering::SeqCst);
+    let expected = format!("Bearer {}", TOKEN);
+    if headers.get(header::AUTHORIZATION).and_then(|v| v.to_str().ok()) != Some(expected.as_str()) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    if headers.get(header::IF_NONE_MATCH).and_then(|v| v.to_str().ok()) == Some(INDEX_ETAG) {
+        state.not_modified.fetch_add(1, Ordering::SeqCst);
+        return StatusCode::NOT_MODIFIED.into_response();
+    }
+    ([(header::ETAG, INDEX_ETAG)], state.index.clone()).into_response()
+}
+
+async fn serve_bundle(State(state): State<Arc<StandIn>>, Path(file): Path<String>) -> Response {
+    match state.bundles.get(&file) {
+        Some(bytes) => bytes.clone().into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+fn sha256(bytes: &[u8]) -> String {
+    format!("sha256:{:x}", Sha256::digest(bytes))
+}
+
+fn metadata(name: &str, version: &str) -> Vec<u8> {
+    serde_json::to_vec(&serde_json::json!({ "name": name, "version": version })).unwrap()
+}
+
+fn tar_gz_bundle(name: &str, version: &str) -> Vec<u8> {
+    let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    let files: [(&str, Vec<u8>); 3] = [
+        ("metadata.json", metadata(name, version)),
+        ("main.js", b"(function(input) { return { archived: true }; })".to_vec()),
+        ("input.schema.json", br#"{"type": "object"}"#.to_vec()),
+    ];
+    for (file, content) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, format!("{}/{}", name, file), content.as_slice())
+            .unwrap();
+    }
+    builder.into_inner().unwrap().finish().unwrap()
+}
+
+fn zip_bundle(name: &str, version: &str) -> Vec<u8> {
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = zip::write::FileOptions::default();
+    writer.start_file("metadata.json", options).unwrap();
+    writer.write_all(&metadata(name, version)).unwrap();
+    writer.start_file("main.js", options).unwrap();
+    writer
+        .write_all(b"(function(input) { return { zipped: true }; })")
+        .unwrap();
+    writer.finish().unwrap().into_inner()
+}
+
+/// Start the stand-in registry and return the index URL
+async fn start_registry(tamper_with: Option<&str>) -> (String, Arc<StandIn>) {
+    let json_bundle = serde_json::to_vec(&serde_json::json!({
+        "metadata": { "name": "hello", "version": "1.0.0", "description": "Says hello" },
+        "script": "(function(input) { return { greeting: 'hello ' + input.name }; })",
+        "input_schema": { "type": "object", "required": ["name"] },
+        "output_schema": { "type": "object" }
+    }))
+    .unwrap();
+    let mut bundles = HashMap::from([
+        ("hello-1.0.0.json".to_string(), json_bundle),
+        ("archived-2.0.0.tar.gz".to_string(), tar_gz_bundle("archived", "2.0.0")),
+        ("zipped-1.0.0.zip".to_string(), zip_bundle("zipped", "1.0.0")),
+    ]);
+
+    let index = serde_json::json!({
+        "format_version": 1,
+        "generated_at": "2025-10-01T12:00:00Z",
+        "tasks": [
+            {
+                "name": "hello",
+                "version": "1.0.0",
+                "tags": ["demo"],
+                "checksum": sha256(&bundles["hello-1.0.0.json"]),
+                "url": "bundles/hello-1.0.0.json"
+            },
+            {
+                "name": "archived",
+                "version": "2.0.0",
+                "checksum": sha256(&bundles["archived-2.0.0.tar.gz"]),
+                "url": "bundles/archived-2.0.0.tar.gz",
+                "format": "tar_gz"
+            },
+            {
+                "name": "zipped",
+                "version": "1.0.0",
+                "checksum": sha256(&bundles["zipped-1.0.0.zip"]),
+                "url": "bundles/zipped-1.0.0.zip",
+                "format": "zip"
+            }
+        ]
+    });
+
+    // Serve different bytes than the index advertises
+    if let Some(file) = tamper_with {
+        bundles.get_mut(file).unwrap().extend_from_slice(b" ");
+    }
+
+    let state = Arc::new(StandIn {
+        index: serde_json::to_vec(&index).unwrap(),
+        bundles,
+        index_requests: AtomicUsize::new(0),
+        not_modified: AtomicUsize::new(0),
+    });
+    let app = Router::new()
+        .route("/registry/index.json", get(serve_index))
+        .route("/registry/bundles/{file}", get(serve_bundle))
+        .with_state(state.clone());
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    (format!("http://{}/registry/index.json", addr), state)
+}
+
+/// Registry serving one bundle under every URL its index lists, recording the
+/// Authorization header of each bundle request
+struct OneBundle {
+    index: OnceLock<Vec<u8>>,
+    bundle: Vec<u8>,
+    bundle_auth: Mutex<Vec<Option<String>>>,
+}
+
+async fn serve_one_index(State(state): State<Arc<OneBundle>>) -> Response {
+    state.index.get().cloned().unwrap_or_default().into_response()
+}
+
+async fn serve_one_bundle(State(state): State<Arc<OneBundle>>, headers: HeaderMap) -> Response {
+    let auth = headers.get(header::AUTHORIZATION).and_then(|v| v.to_str().ok());
+    state.bundle_auth.lock().unwrap().push(auth.map(str::to_string));
+    state.bundle.clone().into_response()
+}
+
+/// Start a registry whose index lists `bundle` as `name` 1.0.0 under each of
+/// `urls`, where `{host}` stands for the registry's address, and return the index URL
+async fn start_one_bundle_registry(
+    name: &str,
+    format: &str,
+    bundle: Vec<u8>,
+    urls: &[&str],
+) -> (String, Arc<OneBundle>) {
+    let state = Arc::new(OneBundle {
+        index: OnceLock::new(),
+        bundle,
+        bundle_auth: Mutex::new(Vec::new()),
+    });
+    let app = Router::new()
+        .route("/registry/index.json", get(serve_one_index))
+        .route("/registry/bundles/{file}", get(serve_one_bundle))
+        .with_state(state.clone());
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let tasks: Vec<_> = urls
+        .iter()
+        .map(|url| {
+            serde_json::json!({
+                "name": name,
+                "version": "1.0.0",
+                "checksum": sha256(&state.bundle),
+                "url": url.replace("{host}", &format!("localhost:{}", port)),
+                "format": format
+            })
+        })
+        .collect();
+    let index = serde_json::json!({ "format_version": 1, "tasks": tasks });
+    state.index.set(serde_json::to_vec(&index).unwrap()).unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    (format!("http://127.0.0.1:{}/registry/index.json", port), state)
+}
+
+fn http_source(url: &str) -> TaskSource {
+    TaskSource::Http {
+        url: url.to_string(),
+        auth: Some(HttpAuth {
+            auth_type: HttpAuthType::Bearer {
+                token: TOKEN.to_string(),
+            },
+        }),
+        polling_interval: Duration::from_secs(60),
+    }
+}
+
+#[tokio::test]
+async fn test_http_loader_discovers_and_loads_bundles() {
+    let (url, _state) = start_registry(None).await;
+    let loader = HttpLoader::new();
+
+    let discovered = loader.discover_tasks(&http_source(&url)).await.unwrap();
+    assert_eq!(discovered.len(), 3);
+    assert_eq!(discovered[0].metadata.name, "hello");
+    assert_eq!(discovered[0].metadata.tags, vec!["demo".to_string()]);
+    assert!(discovered[0]
+        .task_ref
+        .source
+        .ends_with("/registry/bundles/hello-1.0.0.json"));
+
+    let hello = loader.load_task(&discovered[0].task_ref).await.unwrap();
+    assert!(hello.script.contains("greeting"));
+    assert_eq!(hello.metadata.description.as_deref(), Some("Says hello"));
+    assert_eq!(hello.input_schema.unwrap()["required"][0], "name");
+
+    let archived = loader.load_task(&discovered[1].task_ref).await.unwrap();
+    assert!(archived.script.contains("archived: true"));
+    assert_eq!(archived.input_schema.unwrap()["type"], "object");
+
+    let zipped = loader.load_task(&discovered[2].task_ref).await.unwrap();
+    assert!(zipped.script.contains("zipped: true"));
+    assert!(zipped.output_schema.is_none());
+}
+
+#[tokio::test]
+async fn test_http_loader_rejects_checksum_mismatch() {
+    let (url, _state) = start_registry(Some("archived-2.0.0.tar.gz")).await;
+    let loader = HttpLoader::new();
+
+    let discovered = loader.discover_tasks(&http_source(&url)).await.unwrap();
+    let err = loader.load_task(&discovered[1].task_ref).await.unwrap_err();
+    assert!(
+        matches!(err, RegistryError::ChecksumMismatch { .. }),
+        "unexpected error: {}",
+        err
+    );
+
+    // Other bundles in the same index are unaffected
+    assert!(loader.load_task(&discovered[0].task_ref).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_http_loader_requires_auth() {
+    let (url, _state) = start_registry(None).await;
+    let loader = HttpLoader::new();
+    let source = TaskSource::Http {
+        url,
+        auth: None,
+        polling_interval: Duration::from_secs(60),
+    };
+
+    let err = loader.discover_tasks(&source).await.unwrap_err();
+    assert!(err.to_string().contains("401"), "unexpected error: {}", err);
+}
+
+#[tokio::test]
+async fn test_poller_syncs_to_database_and_skips_unchanged_index() {
+    let (url, state) = start_registry(None).await;
+    let source = http_source(&url);
+
+    let db = ratchet_storage::seaorm::DatabaseConnection::new(ratchet_storage::seaorm::DatabaseConfig {
+        url: "sqlite::memory:".to_string(),
+        max_connections: 5,
+        connection_timeout: Duration::from_secs(10),
+    })
+    .await
+    .unwrap();
+    db.migrate().await.unwrap();
+    let factory = Arc::new(ratchet_storage::seaorm::repositories::RepositoryFactory::new(db));
+    let sync = DatabaseSync::new(factory.clone());
+    let registry = DefaultTaskRegistry::new();
+    let loader = HttpLoader::new();
+
+    let result = RegistryPoller::poll_source(&loader, &registry, Some(&sync), sync.trust(), &source)
+        .await
+        .unwrap()
+        .expect("first poll should see a new index");
+    assert_eq!(result.tasks_added, 3);
+    assert!(result.errors.is_empty());
+    assert_eq!(registry.list_tasks().await.unwrap().len(), 3);
+
+    let tasks = factory.task_repository().find_all().await.unwrap();
+    let hello = tasks.iter().find(|task| task.name == "hello").unwrap();
+    assert!(hello.source_code.contains("greeting"));
+    assert_eq!(hello.input_schema["required"][0], "name");
+
+    // The second poll revalidates with If-None-Match and gets a 304
+    let unchanged = RegistryPoller::poll_source(&loader, &registry, Some(&sync), sync.trust(), &source)
+        .await
+        .unwrap();
+    assert!(unchanged.is_none());
+    assert_eq!(state.index_requests.load(Ordering::SeqCst), 2);
+    assert_eq!(state.not_modified.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_http_loader_keeps_credentials_to_the_index_origin() {
+    let bundle = serde_json::to_vec(&serde_json::json!({
+        "metadata": { "name": "hello", "version": "1.0.0" },
+        "script": "(function(input) { return {}; })"
+    }))
+    .unwrap();
+    // The same server, but reached under another host name it is another origin
+    let (url, state) = start_one_bundle_registry(
+        "hello",
+        "json",
+        bundle,
+        &["bundles/hello.json", "http://{host}/registry/bundles/hello.json"],
+    )
+    .await;
+    let loader = HttpLoader::new();
+
+    let discovered = loader.discover_tasks(&http_source(&url)).await.unwrap();
+    for task in &discovered {
+        loader.load_task(&task.task_ref).await.unwrap();
+    }
+
+    let expected = format!("Bearer {}", TOKEN);
+    assert_eq!(*state.bundle_auth.lock().unwrap(), vec![Some(expected), None]);
+}
+
+#[tokio::test]
+async fn test_http_loader_refuses_oversized_archives() {
+    let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    let files: [(&str, Vec<u8>); 2] = [
+        ("metadata.json", metadata("huge", "1.0.0")),
+        ("main.js", vec![b' '; 17 * 1024 * 1024]),
+    ];
+    for (file, content) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, file, content.as_slice()).unwrap();
+    }
+    let bundle = builder.into_inner().unwrap().finish().unwrap();
+
+    let (url, _state) = start_one_bundle_registry("huge", "tar_gz", bundle, &["bundles/huge.tar.gz"]).await;
+    let loader = HttpLoader::new();
+
+    let discovered = loader.discover_tasks(&http_source(&url)).await.unwrap();
+    let err = loader.load_task(&discovered[0].task_ref).await.unwrap_err();
+    assert!(
+        err.to_string().contains("exceeds the limit"),
+        "unexpected error: {}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn test_http_loader_refuses_archives_with_too_many_entries() {
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = zip::write::FileOptions::default();
+    writer.start_file("metadata.json", options).unwrap();
+    writer.write_all(&metadata("crowded", "1.0.0")).unwrap();
+    for i in 0..ratchet_registry::loaders::http::MAX_ARCHIVE_ENTRIES {
+        writer.start_file(format!("filler/{}.txt", i), options).unwrap();
+    }
+    let bundle = writer.finish().unwrap().into_inner();
+
+    let (url, _state) = start_one_bundle_registry("crowded", "zip", bundle, &["bundles/crowded.zip"]).await;
+    let loader = HttpLoader::new();
+
+    let discovered = loader.discover_tasks(&http_source(&url)).await.unwrap();
+    let err = loader.load_task(&discovered[0].task_ref).await.unwrap_err();
+    assert!(err.to_string().contains("more than"), "unexpected error: {}", err);
+}
+
+/// Index requests that reached the other origin, with their API key header
+type SeenKeys = Arc<Mutex<Vec<Option<String>>>>;
+
+async fn serve_moved_index(State(seen): State<SeenKeys>, headers: HeaderMap) -> Response {
+    let key = headers.get("x-api-key").and_then(|v| v.to_str().ok());
+    seen.lock().unwrap().push(key.map(str::to_string));
+    br#"{"format_version": 1, "tasks": []}"#.to_vec().into_response()
+}
+
+#[tokio::test]
+async fn test_http_loader_does_not_follow_authenticated_requests_to_other_origins() {
+    let seen = SeenKeys::default();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    // The same server, but reached under another host name it is another origin
+    let moved = format!("http://localhost:{}/registry/moved.json", port);
+    let redirect = move || {
+        let moved = moved.clone();
+        async move { (StatusCode::FOUND, [(header::LOCATION, moved)]).into_response() }
+    };
+    let app = Router::new()
+        .route("/registry/index.json", get(redirect))
+        .route("/registry/moved.json", get(serve_moved_index))
+        .with_state(seen.clone());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let source = TaskSource::Http {
+        url: format!("http://127.0.0.1:{}/registry/index.json", port),
+        auth: Some(HttpAuth {
+            auth_type: HttpAuthType::ApiKey {
+                header_name: "X-Api-Key".to_string(),
+                api_key: TOKEN.to_string(),
+            },
+        }),
+        polling_interval: Duration::from_secs(60),
+    };
+    let err = HttpLoader::new().discover_tasks(&source).await.unwrap_err();
+    assert!(err.to_string().contains("another origin"), "unexpected error: {}", err);
+    assert!(seen.lock().unwrap().is_empty());
+
+    // Without credentials the redirect is followed
+    let source = TaskSource::Http {
+        url: format!("http://127.0.0.1:{}/registry/index.json", port),
+        auth: None,
+        polling_interval: Duration::from_secs(60),
+    };
+    assert!(HttpLoader::new().discover_tasks(&source).await.unwrap().is_empty());
+    assert_eq!(*seen.lock().unwrap(), vec![None]);
+}
+
+#[tokio::test]
+async fn test_http_loader_refuses_oversized_downloads() {
+    let limit = ratchet_registry::loaders::http::MAX_INDEX_BYTES;
+    let mut index = br#"{"format_version": 1, "tasks": []}"#.to_vec();
+    index.resize(limit + 1, b' ');
+    let serve = move || {
+        let index = index.clone();
+        async move { index }
+    };
+    let app = Router::new().route("/registry/index.json", get(serve));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let source = TaskSource::Http {
+        url: format!("http://{}/registry/index.json", addr),
+        auth: None,
+        polling_interval: Duration::from_secs(60),
+    };
+    let err = HttpLoader::new().discover_tasks(&source).await.unwrap_err();
+    assert!(err.to_string().contains("larger than"), "unexpected error: {}", err);
+}
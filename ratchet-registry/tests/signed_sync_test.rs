@@ -0,0 +1,183 @@
+use ratchet_registry::config::{SignaturePolicy, TrustConfig};
+use ratchet_registry::prelude::*;
+use ratchet_registry::{DatabaseSync, FilesystemLoader, TaskLoader, TaskSigner, TrustPolicies};
+use ratchet_storage::seaorm::repositories::RepositoryFactory;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn write_task(root: &Path, name: &str) {
+    let dir = root.join(name);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("metadata.json"),
+        serde_json::to_vec(&serde_json::json!({ "name": name, "version": "1.0.0" })).unwrap(),
+    )
+    .unwrap();
+    std::fs::write(dir.join("main.js"), "(function(input) { return input; })").unwrap();
+}
+
+fn signer() -> TaskSigner {
+    TaskSigner::from_pkcs8(&TaskSigner::generate().unwrap()).unwrap()
+}
+
+/// A repository with a trusted, an untrusted and an unsigned task
+async fn signed_repository(trusted: &TaskSigner) -> TempDir {
+    let root = TempDir::new().unwrap();
+    for name in ["trusted", "untrusted", "unsigned"] {
+        write_task(root.path(), name);
+    }
+    trusted.sign_task_directory(&root.path().join("trusted")).await.unwrap();
+    signer()
+        .sign_task_directory(&root.path().join("untrusted"))
+        .await
+        .unwrap();
+    root
+}
+
+async fn repository_factory() -> Arc<RepositoryFactory> {
+    let db = ratchet_storage::seaorm::DatabaseConnection::new(ratchet_storage::seaorm::DatabaseConfig {
+        url: "sqlite::memory:".to_string(),
+        max_connections: 5,
+        connection_timeout: Duration::from_secs(10),
+    })
+    .await
+    .unwrap();
+    db.migrate().await.unwrap();
+    Arc::new(RepositoryFactory::new(db))
+}
+
+async fn discover(root: &TempDir) -> Vec<DiscoveredTask> {
+    let source = TaskSource::Filesystem {
+        path: root.path().display().to_string(),
+        recursive: true,
+        watch: false,
+    };
+    FilesystemLoader::new().discover_tasks(&source).await.unwrap()
+}
+
+fn trust(root: &TempDir, policy: SignaturePolicy, trusted: &TaskSigner) -> Vec<TrustConfig> {
+    vec![TrustConfig {
+        repository: root.path().display().to_string(),
+        policy,
+        public_keys: vec![trusted.public_key_base64()],
+    }]
+}
+
+#[tokio::test]
+async fn test_require_policy_refuses_unverified_tasks() {
+    let trusted = signer();
+    let root = signed_repository(&trusted).await;
+    let factory = repository_factory().await;
+    let sync = DatabaseSync::new(factory.clone())
+        .with_trust_config(&trust(&root, SignaturePolicy::Require, &trusted))
+        .unwrap();
+
+    let result = sync.sync_discovered_tasks(discover(&root).await).await.unwrap();
+    assert_eq!(result.tasks_added, 1);
+    let mut refused: Vec<_> = result.errors.iter().map(|e| e.task_ref.name.as_str()).collect();
+    refused.sort();
+    assert_eq!(refused, vec!["unsigned", "untrusted"]);
+
+    let tasks = factory.task_repository().find_all().await.unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].name, "trusted");
+    assert_eq!(tasks[0].signature_status.as_deref(), Some("verified"));
+    assert_eq!(tasks[0].signed_by, Some(trusted.key_id()));
+    assert!(tasks[0].enabled);
+}
+
+#[tokio::test]
+async fn test_quarantine_policy_disables_unverified_tasks() {
+    let trusted = signer();
+    let root = signed_repository(&trusted).await;
+    let factory = repository_factory().await;
+    let sync = DatabaseSync::new(factory.clone())
+        .with_trust_config(&trust(&root, SignaturePolicy::Quarantine, &trusted))
+        .unwrap();
+
+    let result = sync.sync_discovered_tasks(discover(&root).await).await.unwrap();
+    assert_eq!(result.tasks_added, 3);
+    assert!(result.errors.is_empty());
+
+    let tasks = factory.task_repository().find_all().await.unwrap();
+    let task = |name: &str| tasks.iter().find(|task| task.name == name).unwrap();
+    assert!(task("trusted").enabled);
+    assert_eq!(task("trusted").sync_status, "synced");
+
+    let untrusted = task("untrusted");
+    assert!(!untrusted.enabled);
+    assert_eq!(untrusted.sync_status, "quarantined");
+    assert_eq!(untrusted.signature_status.as_deref(), Some("untrusted"));
+    assert!(untrusted.signed_by.is_some());
+
+    let unsigned = task("unsigned");
+    assert!(!unsigned.enabled);
+    assert_eq!(unsigned.signature_status.as_deref(), Some("unsigned"));
+    assert_eq!(unsigned.signed_by, None);
+}
+
+#[tokio::test]
+async fn test_tampered_task_fails_verification() {
+    let trusted = signer();
+    let root = signed_repository(&trusted).await;
+    std::fs::write(
+        root.path().join("trusted").join("main.js"),
+        "(function(input) { return { exfiltrated: input }; })",
+    )
+    .unwrap();
+
+    let factory = repository_factory().await;
+    let sync = DatabaseSync::new(factory.clone())
+        .with_trust_config(&trust(&root, SignaturePolicy::Require, &trusted))
+        .unwrap();
+
+    let result = sync.sync_discovered_tasks(discover(&root).await).await.unwrap();
+    assert_eq!(result.tasks_added, 0);
+    assert!(result.errors.iter().any(|e| e.error.contains("does not match")));
+}
+
+#[tokio::test]
+async fn test_repositories_without_trust_config_are_not_checked() {
+    let trusted = signer();
+    let root = signed_repository(&trusted).await;
+    let factory = repository_factory().await;
+    let sync = DatabaseSync::new(factory.clone());
+
+    let result = sync.sync_discovered_tasks(discover(&root).await).await.unwrap();
+    assert_eq!(result.tasks_added, 3);
+
+    let tasks = factory.task_repository().find_all().await.unwrap();
+    assert!(tasks.iter().all(|task| task.enabled && task.signature_status.is_none()));
+}
+
+#[tokio::test]
+async fn test_registry_service_registers_only_admitted_tasks() {
+    let trusted = signer();
+    let root = signed_repository(&trusted).await;
+    let config = RegistryConfig {
+        sources: vec![TaskSource::Filesystem {
+            path: root.path().display().to_string(),
+            recursive: true,
+            watch: false,
+        }],
+        trust: trust(&root, SignaturePolicy::Quarantine, &trusted),
+        ..RegistryConfig::default()
+    };
+    let factory = repository_factory().await;
+    let sync = DatabaseSync::new(factory.clone())
+        .with_trust_config(&config.trust)
+        .unwrap();
+    let service = DefaultRegistryService::new(config.clone())
+        .with_trust(TrustPolicies::from_config(&config.trust).unwrap())
+        .with_sync_service(Arc::new(sync));
+
+    service.sync_to_database().await.unwrap();
+
+    // Quarantined tasks are stored disabled, but only the verified one can run
+    let registered = service.registry().await.list_tasks().await.unwrap();
+    let names: Vec<_> = registered.iter().map(|task| task.metadata.name.as_str()).collect();
+    assert_eq!(names, vec!["trusted"]);
+    assert_eq!(factory.task_repository().find_all().await.unwrap().len(), 3);
+}
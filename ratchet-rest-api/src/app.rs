@@ -2,7 +2,7 @@
 
 use axum::{
     response::{Html, IntoResponse, Json},
-    routing::{get, post},
+    routing::{delete, get, post},
     Router,
 };
 use ratchet_interfaces::{RegistryManager, RepositoryFactory, TaskRegistry, TaskValidator};
@@ -358,6 +358,10 @@ fn create_api_router() -> Router<TasksContext> {
         )
         .route("/tasks/{id}/enable", post(handlers::tasks::enable_task))
         .route("/tasks/{id}/disable", post(handlers::tasks::disable_task))
+        .route(
+            "/tasks/{id}/cache",
+            get(handlers::task_cache::list_task_cache).delete(handlers::task_cache::purge_task_cache),
+        )
         // Execution endpoints
         .route(
             "/executions",
@@ -384,6 +388,9 @@ fn create_api_router() -> Router<TasksContext> {
         )
         .route("/jobs/{id}/cancel", post(handlers::jobs::cancel_job))
         .route("/jobs/{id}/retry", post(handlers::jobs::retry_job))
+        // Output delivery ledger endpoints
+        .route("/deliveries/failed", get(handlers::deliveries::list_failed_deliveries))
+        .route("/deliveries/{id}/redeliver", post(handlers::deliveries::redeliver_output))
         // Schedule endpoints
         .route(
             "/schedules",
@@ -410,6 +417,35 @@ fn create_api_router() -> Router<TasksContext> {
         .route("/mcp/tasks/{name}/test", post(handlers::mcp_test_task))
         .route("/mcp/results", post(handlers::mcp_store_result))
         .route("/mcp/results/{name}", get(handlers::mcp_get_results))
+        // Secret endpoints
+        .route("/secrets", get(handlers::secrets::list_secrets).post(handlers::secrets::set_secret))
+        .route("/secrets/{name}", delete(handlers::secrets::delete_secret))
+        // Workflow endpoints
+        .route(
+            "/workflows",
+            get(handlers::workflows::list_workflows).post(handlers::workflows::create_workflow),
+        )
+        .route(
+            "/workflows/{id}",
+            get(handlers::workflows::get_workflow)
+                .patch(handlers::workflows::update_workflow)
+                .delete(handlers::workflows::delete_workflow),
+        )
+        .route("/workflows/{id}/run", post(handlers::workflows::run_workflow))
+        .route("/workflows/{id}/runs", get(handlers::workflows::list_workflow_runs))
+        .route("/workflow-runs/{id}", get(handlers::workflows::get_workflow_run))
+        // Webhook trigger endpoints; deliveries are authenticated by their signature
+        .route(
+            "/webhook-triggers",
+            get(handlers::triggers::list_webhook_triggers).post(handlers::triggers::create_webhook_trigger),
+        )
+        .route(
+            "/webhook-triggers/{id}",
+            get(handlers::triggers::get_webhook_trigger)
+                .patch(handlers::triggers::update_webhook_trigger)
+                .delete(handlers::triggers::delete_webhook_trigger),
+        )
+        .route("/triggers/{name}", post(handlers::triggers::deliver_to_trigger))
         // Worker endpoints
         .route("/workers", get(handlers::workers::list_workers))
         .route("/workers/stats", get(handlers::workers::get_worker_stats))
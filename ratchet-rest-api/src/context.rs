@@ -4,7 +4,11 @@
 //! for each REST API endpoint group. This enables clean dependency injection
 //! and makes testing easier with mock implementations.
 
-use ratchet_interfaces::{RegistryManager, RepositoryFactory, SchedulerService, TaskRegistry, TaskValidator};
+use ratchet_http::HttpClientState;
+use ratchet_interfaces::{
+    ExecutionCanceller, OutputDeliveryStore, RegistryManager, RepositoryFactory, SchedulerService, SecretStore,
+    TaskRegistry, TaskResultCache, TaskValidator, TriggerStore, WorkflowStore,
+};
 use ratchet_mcp::server::task_dev_tools::TaskDevelopmentService;
 use std::sync::Arc;
 
@@ -25,6 +29,20 @@ pub struct TasksContext {
     pub mcp_task_service: Option<Arc<TaskDevelopmentService>>,
     /// Optional scheduler service for schedule management integration
     pub scheduler_service: Option<Arc<dyn SchedulerService>>,
+    /// Optional executor hook used to stop running executions on cancel
+    pub execution_canceller: Option<Arc<dyn ExecutionCanceller>>,
+    /// Optional encrypted store for task secrets
+    pub secret_store: Option<Arc<dyn SecretStore>>,
+    /// Optional store of workflow definitions and runs
+    pub workflow_store: Option<Arc<dyn WorkflowStore>>,
+    /// Optional store of inbound webhook triggers
+    pub trigger_store: Option<Arc<dyn TriggerStore>>,
+    /// Optional cache of memoized task results
+    pub result_cache: Option<Arc<dyn TaskResultCache>>,
+    /// Optional ledger of output deliveries
+    pub delivery_store: Option<Arc<dyn OutputDeliveryStore>>,
+    /// Optional response cache and circuit breakers of task `fetch` calls
+    pub http_client: Option<HttpClientState>,
 }
 
 impl TasksContext {
@@ -41,6 +59,13 @@ impl TasksContext {
             validator,
             mcp_task_service: None,
             scheduler_service: None,
+            execution_canceller: None,
+            secret_store: None,
+            workflow_store: None,
+            trigger_store: None,
+            result_cache: None,
+            delivery_store: None,
+            http_client: None,
         }
     }
 
@@ -59,6 +84,13 @@ impl TasksContext {
             validator,
             mcp_task_service: Some(mcp_task_service),
             scheduler_service: None,
+            execution_canceller: None,
+            secret_store: None,
+            workflow_store: None,
+            trigger_store: None,
+            result_cache: None,
+            delivery_store: None,
+            http_client: None,
         }
     }
 
@@ -77,6 +109,13 @@ impl TasksContext {
             validator,
             mcp_task_service: None,
             scheduler_service: Some(scheduler_service),
+            execution_canceller: None,
+            secret_store: None,
+            workflow_store: None,
+            trigger_store: None,
+            result_cache: None,
+            delivery_store: None,
+            http_client: None,
         }
     }
 
@@ -96,8 +135,57 @@ impl TasksContext {
             validator,
             mcp_task_service: Some(mcp_task_service),
             scheduler_service: Some(scheduler_service),
+            execution_canceller: None,
+            secret_store: None,
+            workflow_store: None,
+            trigger_store: None,
+            result_cache: None,
+            delivery_store: None,
+            http_client: None,
         }
     }
+
+    /// Stop running executions through `canceller` when they are cancelled
+    pub fn with_execution_canceller(mut self, canceller: Arc<dyn ExecutionCanceller>) -> Self {
+        self.execution_canceller = Some(canceller);
+        self
+    }
+
+    /// Manage task secrets through `store`
+    pub fn with_secret_store(mut self, store: Arc<dyn SecretStore>) -> Self {
+        self.secret_store = Some(store);
+        self
+    }
+
+    /// Manage and run workflows through `store`
+    pub fn with_workflow_store(mut self, store: Arc<dyn WorkflowStore>) -> Self {
+        self.workflow_store = Some(store);
+        self
+    }
+
+    /// Manage and deliver to webhook triggers through `store`
+    pub fn with_trigger_store(mut self, store: Arc<dyn TriggerStore>) -> Self {
+        self.trigger_store = Some(store);
+        self
+    }
+
+    /// Inspect and purge memoized task results through `cache`
+    pub fn with_result_cache(mut self, cache: Arc<dyn TaskResultCache>) -> Self {
+        self.result_cache = Some(cache);
+        self
+    }
+
+    /// List and redeliver failed output deliveries through `store`
+    pub fn with_delivery_store(mut self, store: Arc<dyn OutputDeliveryStore>) -> Self {
+        self.delivery_store = Some(store);
+        self
+    }
+
+    /// Report the response cache, retries and circuit breakers of task `fetch` calls in metrics
+    pub fn with_http_client_state(mut self, state: HttpClientState) -> Self {
+        self.http_client = Some(state);
+        self
+    }
 }
 
 /// Context for execution-related endpoints
@@ -0,0 +1,89 @@
+//! Output delivery ledger endpoints
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Json,
+};
+use ratchet_api_types::ApiId;
+use ratchet_interfaces::{DeliveryLedgerError, OutputDeliveryStore};
+use ratchet_web::ApiResponse;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::{
+    context::TasksContext,
+    errors::{RestError, RestResult},
+    models::jobs::FailedDeliveriesQuery,
+};
+
+fn delivery_store(ctx: &TasksContext) -> RestResult<&Arc<dyn OutputDeliveryStore>> {
+    ctx.delivery_store
+        .as_ref()
+        .ok_or_else(|| RestError::ServiceUnavailable("The delivery ledger is not configured".to_string()))
+}
+
+fn delivery_error(error: DeliveryLedgerError) -> RestError {
+    match error {
+        DeliveryLedgerError::NotFound { id } => RestError::not_found("Delivery", &id),
+        error @ DeliveryLedgerError::NotRedeliverable { .. } => RestError::conflict(error.to_string()),
+        DeliveryLedgerError::Storage { message } => RestError::InternalError(message),
+    }
+}
+
+/// List output deliveries that have not succeeded
+#[utoipa::path(
+    get,
+    path = "/api/v1/deliveries/failed",
+    tag = "jobs",
+    summary = "List failed output deliveries",
+    description = "List deliveries of job outputs that are waiting for a retry, being redelivered or dead-lettered after the retry policy gave up, newest first",
+    params(
+        ("jobId" = Option<String>, Query, description = "Only deliveries of this job"),
+        ("executionId" = Option<String>, Query, description = "Only deliveries of this execution")
+    ),
+    responses(
+        (status = 200, description = "Failed deliveries retrieved successfully"),
+        (status = 503, description = "The delivery ledger is not configured")
+    )
+)]
+pub async fn list_failed_deliveries(
+    State(ctx): State<TasksContext>,
+    Query(query): Query<FailedDeliveriesQuery>,
+) -> RestResult<impl IntoResponse> {
+    let deliveries = delivery_store(&ctx)?
+        .failed_deliveries(query.job_id, query.execution_id)
+        .await
+        .map_err(delivery_error)?;
+    Ok(Json(ApiResponse::new(deliveries)))
+}
+
+/// Deliver the output of a failed delivery again
+#[utoipa::path(
+    post,
+    path = "/api/v1/deliveries/{id}/redeliver",
+    tag = "jobs",
+    summary = "Redeliver an output",
+    description = "Deliver the output of a retrying or dead-lettered delivery to its destination again now. The outcome is recorded as a new attempt",
+    params(
+        ("id" = String, Path, description = "Delivery ID")
+    ),
+    responses(
+        (status = 200, description = "Redelivery attempted; the new attempt is returned"),
+        (status = 404, description = "Delivery not found"),
+        (status = 409, description = "The delivery succeeded or is being redelivered"),
+        (status = 503, description = "The delivery ledger is not configured")
+    )
+)]
+pub async fn redeliver_output(
+    State(ctx): State<TasksContext>,
+    Path(id): Path<String>,
+) -> RestResult<impl IntoResponse> {
+    info!("Redelivering output delivery: {}", id);
+
+    let attempt = delivery_store(&ctx)?
+        .redeliver(ApiId::from_string(id))
+        .await
+        .map_err(delivery_error)?;
+    Ok(Json(ApiResponse::new(attempt)))
+}
@@ -1,12 +1,12 @@
 //! Execution management endpoints
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
     Json,
 };
-use ratchet_api_types::ApiId;
+use ratchet_api_types::{ApiId, ExecutionLogLevel};
 use ratchet_core::validation::{ErrorSanitizer, InputValidator};
 use ratchet_web::{extract_execution_filters, ApiResponse, QueryParams};
 use tracing::{info, warn};
@@ -16,7 +16,9 @@ use crate::{
     errors::{RestError, RestResult},
     models::{
         common::StatsResponse,
-        executions::{CreateExecutionRequest, ExecutionStats, RetryExecutionRequest, UpdateExecutionRequest},
+        executions::{
+            CreateExecutionRequest, ExecutionLogsQuery, ExecutionStats, RetryExecutionRequest, UpdateExecutionRequest,
+        },
     },
 };
 
@@ -128,9 +130,13 @@ pub async fn create_execution(
         duration_ms: None,
         http_requests: None,
         recording_path: None,
+        parent_execution_uuid: None,
+        cache_hit: false,
         can_retry: false,
         can_cancel: true,
         progress: None,
+        progress_message: None,
+        partial_output: None,
     };
 
     // Create the execution using the repository
@@ -301,14 +307,40 @@ pub async fn cancel_execution(
     let api_id = ApiId::from_string(execution_id.clone());
     let execution_repo = ctx.repositories.execution_repository();
 
+    let execution = execution_repo
+        .find_by_id(api_id.as_i32().unwrap_or(0))
+        .await
+        .map_err(RestError::Database)?
+        .ok_or_else(|| RestError::not_found("Execution", &execution_id))?;
+
+    if !matches!(
+        execution.status,
+        ratchet_api_types::ExecutionStatus::Pending | ratchet_api_types::ExecutionStatus::Running
+    ) {
+        return Err(RestError::Conflict(format!(
+            "Execution {} has already finished",
+            execution_id
+        )));
+    }
+
+    // Record the cancellation before stopping the task, so whatever runs it sees why it stopped
     execution_repo
-        .mark_failed(api_id, "Cancelled by user".to_string(), None)
+        .mark_cancelled(api_id)
         .await
         .map_err(RestError::Database)?;
 
+    // Ask the executor to stop the task; a pending execution never reaches a worker
+    let stopped = ctx
+        .execution_canceller
+        .as_ref()
+        .map(|canceller| canceller.cancel_execution(&execution.uuid.to_string()))
+        .unwrap_or(false);
+
     Ok(Json(serde_json::json!({
         "success": true,
-        "message": format!("Execution {} cancelled", execution_id)
+        "message": format!("Execution {} cancelled", execution_id),
+        "status": ratchet_api_types::ExecutionStatus::Cancelled,
+        "workerNotified": stopped
     })))
 }
 
@@ -370,9 +402,13 @@ pub async fn retry_execution(
         duration_ms: None,
         http_requests: None,
         recording_path: None,
+        parent_execution_uuid: None,
+        cache_hit: false,
         can_retry: false,
         can_cancel: true,
         progress: None,
+        progress_message: None,
+        partial_output: None,
     };
 
     // Create the new execution
@@ -386,38 +422,48 @@ pub async fn retry_execution(
     Ok(Json(ApiResponse::new(created_execution)))
 }
 
-/// Get execution logs
-
+/// Get console output captured from an execution
+///
+/// Entries are returned oldest first; `level` filters out less severe entries and
+/// `limit` keeps only the most recent ones.
 pub async fn get_execution_logs(
-    State(_ctx): State<TasksContext>,
+    State(ctx): State<TasksContext>,
     Path(execution_id): Path<String>,
+    Query(query): Query<ExecutionLogsQuery>,
 ) -> RestResult<impl IntoResponse> {
     info!("Getting logs for execution: {}", execution_id);
 
-    // For now, return placeholder logs
-    // In a full implementation, this would:
-    // 1. Validate execution exists
-    // 2. Retrieve logs from logging system
-    // 3. Support real-time streaming if requested
-    // 4. Return formatted log entries
+    let min_level = match query.level.as_deref() {
+        Some(level) => Some(
+            ExecutionLogLevel::from_name(level)
+                .ok_or_else(|| RestError::BadRequest(format!("Unknown log level: {}", level)))?,
+        ),
+        None => None,
+    };
+
+    let api_id = ApiId::from_string(execution_id.clone());
+    let execution_repo = ctx.repositories.execution_repository();
+
+    execution_repo
+        .find_by_id(api_id.as_i32().unwrap_or(0))
+        .await
+        .map_err(RestError::Database)?
+        .ok_or_else(|| RestError::not_found("Execution", &execution_id))?;
+
+    // Fetch one extra entry to tell whether older entries were left out
+    let mut logs = execution_repo
+        .find_logs(api_id, min_level, query.limit.map(|limit| limit + 1))
+        .await
+        .map_err(RestError::Database)?;
+    let has_more = query.limit.is_some_and(|limit| logs.len() > limit);
+    if has_more {
+        logs.remove(0);
+    }
 
     Ok(Json(serde_json::json!({
         "execution_id": execution_id,
-        "logs": [
-            {
-                "timestamp": "2023-12-07T14:30:15.123Z",
-                "level": "info",
-                "message": "Starting task execution",
-                "source": "task_executor"
-            },
-            {
-                "timestamp": "2023-12-07T14:30:15.145Z",
-                "level": "info",
-                "message": "Processing input data",
-                "source": "task_executor"
-            }
-        ],
-        "has_more": false
+        "logs": logs,
+        "has_more": has_more
     })))
 }
 
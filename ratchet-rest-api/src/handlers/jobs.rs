@@ -2,12 +2,13 @@
 
 use axum::{
     extract::{Path, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::IntoResponse,
     Json,
 };
-use ratchet_api_types::ApiId;
+use ratchet_api_types::{ApiId, UnifiedJobDependency};
 use ratchet_core::validation::{ErrorSanitizer, InputValidator};
+use ratchet_interfaces::{DatabaseError, JobCreation};
 use ratchet_web::{extract_job_filters, ApiResponse, QueryParams};
 use tracing::{info, warn};
 
@@ -77,14 +78,46 @@ pub async fn get_job(State(ctx): State<TasksContext>, Path(job_id): Path<String>
     Ok(Json(ApiResponse::new(job)))
 }
 
+/// Header carrying the idempotency key of a job creation request
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Longest accepted idempotency key
+const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 255;
+
 /// Create a new job
+///
+/// A request carrying an idempotency key that was used recently, through the
+/// `Idempotency-Key` header or the `idempotencyKey` field, returns the job first
+/// created with it with status 200 instead of queueing another one. So does a
+/// request repeating the input of a recent job of a task that deduplicates input.
+/// Reusing a recent key for another task or input is a bad request.
 
 pub async fn create_job(
     State(ctx): State<TasksContext>,
+    headers: HeaderMap,
     Json(request): Json<CreateJobRequest>,
 ) -> RestResult<impl IntoResponse> {
     info!("Creating job for task: {:?}", request.task_id);
 
+    // The header takes precedence over the body field
+    let idempotency_key = match headers.get(IDEMPOTENCY_KEY_HEADER) {
+        Some(value) => Some(
+            value
+                .to_str()
+                .map_err(|_| RestError::BadRequest("Idempotency-Key must be visible ASCII".to_string()))?
+                .to_string(),
+        ),
+        None => request.idempotency_key.clone(),
+    };
+    if let Some(key) = &idempotency_key {
+        if key.trim().is_empty() || key.len() > MAX_IDEMPOTENCY_KEY_LENGTH {
+            return Err(RestError::BadRequest(format!(
+                "Idempotency key must be between 1 and {} characters",
+                MAX_IDEMPOTENCY_KEY_LENGTH
+            )));
+        }
+    }
+
     // Validate the request input
     let _validator = InputValidator::new();
     let sanitizer = ErrorSanitizer::default();
@@ -100,6 +133,25 @@ pub async fn create_job(
         })?
         .ok_or_else(|| RestError::not_found("Task", &request.task_id.to_string()))?;
 
+    // Validate that the jobs it depends on exist
+    let job_repo = ctx.repositories.job_repository();
+    let depends_on = UnifiedJobDependency::dedupe(request.depends_on.unwrap_or_default()).map_err(|job_id| {
+        RestError::BadRequest(format!(
+            "Dependency job {} is listed more than once with different settings",
+            job_id
+        ))
+    })?;
+    for dependency in &depends_on {
+        job_repo
+            .find_by_id(dependency.job_id.as_i32().unwrap_or(0))
+            .await
+            .map_err(|db_err| {
+                let sanitized_error = sanitizer.sanitize_error(&db_err);
+                RestError::InternalError(sanitized_error.message)
+            })?
+            .ok_or_else(|| RestError::BadRequest(format!("Dependency job {} not found", dependency.job_id)))?;
+    }
+
     // Create UnifiedJob from request
     let unified_job = ratchet_api_types::UnifiedJob {
         id: ratchet_api_types::ApiId::from_i32(0), // Will be set by database
@@ -112,16 +164,25 @@ pub async fn create_job(
         scheduled_for: request.scheduled_for,
         error_message: None,
         output_destinations: request.output_destinations,
+        input: Some(request.input),
+        depends_on,
+        idempotency_key,
+        schedule_id: None,
     };
 
-    // Create the job using the repository
-    let job_repo = ctx.repositories.job_repository();
-    let created_job = job_repo
-        .create(unified_job)
-        .await
-        .map_err(|e| RestError::InternalError(format!("Failed to create job: {}", e)))?;
+    // Create the job using the repository, unless it repeats an earlier one
+    let creation = job_repo.create_or_replay(unified_job).await.map_err(|e| match e {
+        DatabaseError::Validation { message } => RestError::BadRequest(message),
+        e => RestError::InternalError(format!("Failed to create job: {}", e)),
+    })?;
 
-    Ok((StatusCode::CREATED, Json(ApiResponse::new(created_job))))
+    Ok(match creation {
+        JobCreation::Created(job) => (StatusCode::CREATED, Json(ApiResponse::new(job))),
+        JobCreation::Replayed(job) => {
+            info!("Returning job {} instead of queueing a repeat", job.id);
+            (StatusCode::OK, Json(ApiResponse::new(job)))
+        }
+    })
 }
 
 /// Update an existing job
@@ -219,12 +280,20 @@ pub async fn delete_job(State(ctx): State<TasksContext>, Path(job_id): Path<Stri
         })?
         .ok_or_else(|| RestError::not_found("Job", &job_id))?;
 
-    // Delete the job
-    job_repo.delete(api_id.as_i32().unwrap_or(0)).await.map_err(|db_err| {
-        let sanitizer = ErrorSanitizer::default();
-        let sanitized_error = sanitizer.sanitize_error(&db_err);
-        RestError::InternalError(sanitized_error.message)
-    })?;
+    // Delete the job; jobs that depend on it have to go first
+    job_repo
+        .delete(api_id.as_i32().unwrap_or(0))
+        .await
+        .map_err(|db_err| match db_err {
+            DatabaseError::Constraint { .. } => {
+                RestError::Conflict(format!("Job {} has dependent jobs; delete them first", job_id))
+            }
+            db_err => {
+                let sanitizer = ErrorSanitizer::default();
+                let sanitized_error = sanitizer.sanitize_error(&db_err);
+                RestError::InternalError(sanitized_error.message)
+            }
+        })?;
 
     Ok(Json(serde_json::json!({
         "success": true,
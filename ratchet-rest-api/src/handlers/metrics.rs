@@ -5,6 +5,8 @@ use serde::{Deserialize, Serialize};
 use tracing::info;
 use utoipa::ToSchema;
 
+use ratchet_http::CircuitState;
+
 use crate::{context::TasksContext, errors::RestResult, models::common::StatsResponse};
 
 /// System metrics response
@@ -68,6 +70,8 @@ pub struct ApplicationMetrics {
     pub executions: ExecutionMetrics,
     pub jobs: JobMetrics,
     pub schedules: ScheduleMetrics,
+    /// Absent when task `fetch` calls do not share a client
+    pub http_client: Option<HttpClientMetrics>,
 }
 
 /// Database metrics
@@ -133,6 +137,30 @@ pub struct ScheduleMetrics {
     pub failed_triggers: u64,
 }
 
+/// Response cache, retry and circuit breaker metrics of task `fetch` calls
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpClientMetrics {
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    pub cache_revalidations: u64,
+    pub cache_stores: u64,
+    pub cache_entries: u64,
+    pub retries: u64,
+    pub circuit_breakers: Vec<CircuitBreakerMetrics>,
+}
+
+/// Circuit breaker of one host contacted by task `fetch` calls
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CircuitBreakerMetrics {
+    pub host: String,
+    /// `closed`, `half_open` or `open`
+    pub state: String,
+    pub failures: u64,
+    pub rejected: u64,
+}
+
 /// Get comprehensive system metrics
 ///
 /// Returns detailed system and application metrics for monitoring and observability.
@@ -254,6 +282,7 @@ async fn collect_application_metrics(ctx: &TasksContext) -> ApplicationMetrics {
     let executions = collect_execution_metrics(ctx).await;
     let jobs = collect_job_metrics(ctx).await;
     let schedules = collect_schedule_metrics(ctx).await;
+    let http_client = collect_http_client_metrics(ctx).await;
 
     ApplicationMetrics {
         database,
@@ -261,6 +290,7 @@ async fn collect_application_metrics(ctx: &TasksContext) -> ApplicationMetrics {
         executions,
         jobs,
         schedules,
+        http_client,
     }
 }
 
@@ -335,6 +365,36 @@ async fn collect_schedule_metrics(ctx: &TasksContext) -> ScheduleMetrics {
     }
 }
 
+async fn collect_http_client_metrics(ctx: &TasksContext) -> Option<HttpClientMetrics> {
+    let metrics = ctx.http_client.as_ref()?.metrics().await;
+    Some(HttpClientMetrics {
+        cache_hits: metrics.cache.hits,
+        cache_misses: metrics.cache.misses,
+        cache_revalidations: metrics.cache.revalidations,
+        cache_stores: metrics.cache.stores,
+        cache_entries: metrics.cache.entries,
+        retries: metrics.retries,
+        circuit_breakers: metrics
+            .circuit_breakers
+            .into_iter()
+            .map(|breaker| CircuitBreakerMetrics {
+                host: breaker.host,
+                state: circuit_state_name(breaker.state).to_string(),
+                failures: breaker.failures,
+                rejected: breaker.rejected,
+            })
+            .collect(),
+    })
+}
+
+fn circuit_state_name(state: CircuitState) -> &'static str {
+    match state {
+        CircuitState::Closed => "closed",
+        CircuitState::HalfOpen => "half_open",
+        CircuitState::Open => "open",
+    }
+}
+
 fn format_prometheus_metrics(metrics: &ApplicationMetrics) -> String {
     let mut output = String::new();
 
@@ -368,5 +428,48 @@ fn format_prometheus_metrics(metrics: &ApplicationMetrics) -> String {
         metrics.database.active_connections
     ));
 
+    if let Some(http) = &metrics.http_client {
+        output.push_str("# HELP ratchet_http_cache_hits_total Task HTTP requests answered from the response cache\n");
+        output.push_str("# TYPE ratchet_http_cache_hits_total counter\n");
+        output.push_str(&format!("ratchet_http_cache_hits_total {}\n", http.cache_hits));
+
+        output.push_str("# HELP ratchet_http_cache_misses_total Task HTTP requests answered by the server\n");
+        output.push_str("# TYPE ratchet_http_cache_misses_total counter\n");
+        output.push_str(&format!("ratchet_http_cache_misses_total {}\n", http.cache_misses));
+
+        output.push_str(
+            "# HELP ratchet_http_cache_revalidations_total Cached task HTTP responses confirmed current by the server\n",
+        );
+        output.push_str("# TYPE ratchet_http_cache_revalidations_total counter\n");
+        output.push_str(&format!(
+            "ratchet_http_cache_revalidations_total {}\n",
+            http.cache_revalidations
+        ));
+
+        output.push_str("# HELP ratchet_http_cache_entries Responses held in the task HTTP response cache\n");
+        output.push_str("# TYPE ratchet_http_cache_entries gauge\n");
+        output.push_str(&format!("ratchet_http_cache_entries {}\n", http.cache_entries));
+
+        output.push_str("# HELP ratchet_http_retries_total Task HTTP requests sent again after a transient failure\n");
+        output.push_str("# TYPE ratchet_http_retries_total counter\n");
+        output.push_str(&format!("ratchet_http_retries_total {}\n", http.retries));
+
+        output.push_str(
+            "# HELP ratchet_http_circuit_breaker_state Circuit breaker state by host (0 closed, 1 half-open, 2 open)\n",
+        );
+        output.push_str("# TYPE ratchet_http_circuit_breaker_state gauge\n");
+        for breaker in &http.circuit_breakers {
+            let state = match breaker.state.as_str() {
+                "open" => 2,
+                "half_open" => 1,
+                _ => 0,
+            };
+            output.push_str(&format!(
+                "ratchet_http_circuit_breaker_state{{host=\"{}\"}} {}\n",
+                breaker.host, state
+            ));
+        }
+    }
+
     output
 }
@@ -1,18 +1,28 @@
 pub mod auth;
+pub mod deliveries;
 pub mod executions;
 pub mod health;
 pub mod jobs;
 pub mod metrics;
 pub mod schedules;
+pub mod secrets;
+pub mod task_cache;
 pub mod tasks;
+pub mod triggers;
 pub mod workers;
+pub mod workflows;
 
 // Re-export handler functions
 pub use auth::*;
+pub use deliveries::*;
 pub use executions::*;
 pub use health::*;
 pub use jobs::*;
 pub use metrics::*;
 pub use schedules::*;
+pub use secrets::*;
+pub use task_cache::*;
 pub use tasks::*;
+pub use triggers::*;
 pub use workers::*;
+pub use workflows::*;
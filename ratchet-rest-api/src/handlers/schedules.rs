@@ -8,9 +8,11 @@ use axum::{
 };
 use ratchet_api_types::ApiId;
 use ratchet_core::validation::{ErrorSanitizer, InputValidator};
+use ratchet_interfaces::DatabaseError;
 use ratchet_web::{extract_schedule_filters, ApiResponse, QueryParams};
 use tracing::{info, warn};
 
+use super::workflows::workflow_store;
 use crate::{
     context::TasksContext,
     errors::{RestError, RestResult},
@@ -21,6 +23,14 @@ use crate::{
 };
 use ratchet_api_types::UnifiedOutputDestination;
 
+/// Invalid schedule policies are the caller's fault; other storage errors are not
+fn schedule_write_error(action: &'static str) -> impl Fn(DatabaseError) -> RestError {
+    move |error| match error {
+        DatabaseError::Validation { message } => RestError::BadRequest(message),
+        error => RestError::InternalError(format!("Failed to {} schedule: {}", action, error)),
+    }
+}
+
 /// Validate output destinations configuration
 fn validate_output_destinations(destinations: &[UnifiedOutputDestination]) -> Result<(), RestError> {
     if destinations.is_empty() {
@@ -258,12 +268,87 @@ fn validate_output_destinations(destinations: &[UnifiedOutputDestination]) -> Re
                     )));
                 }
             }
+            "s3" => {
+                if let Some(s3) = &dest.s3 {
+                    if s3.bucket.is_empty() || s3.key.is_empty() || s3.region.is_empty() {
+                        return Err(RestError::BadRequest(format!(
+                            "{}: S3 bucket, key and region cannot be empty",
+                            context
+                        )));
+                    }
+
+                    if let Some(endpoint) = &s3.endpoint {
+                        if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+                            return Err(RestError::BadRequest(format!(
+                                "{}: S3 endpoint must be a valid HTTP/HTTPS URL",
+                                context
+                            )));
+                        }
+                    }
+
+                    // Credentials are taken from the environment unless both halves are given
+                    if s3.access_key_id.is_some() != s3.secret_access_key_secret.is_some() {
+                        return Err(RestError::BadRequest(format!(
+                            "{}: S3 access key ID and secret access key secret must be given together",
+                            context
+                        )));
+                    }
+                } else {
+                    return Err(RestError::BadRequest(format!(
+                        "{}: S3 destination must include s3 configuration",
+                        context
+                    )));
+                }
+            }
             "database" => {
-                // Basic validation for database destinations
-                return Err(RestError::BadRequest(format!(
-                    "{}: Database destinations not yet supported",
-                    context
-                )));
+                if let Some(db) = &dest.database {
+                    if db.connection.is_empty() || db.table.is_empty() {
+                        return Err(RestError::BadRequest(format!(
+                            "{}: Database connection and table cannot be empty",
+                            context
+                        )));
+                    }
+
+                    // Destinations write only to databases the server configuration names
+                    if !db
+                        .connection
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+                    {
+                        return Err(RestError::BadRequest(format!(
+                            "{}: Database connection must be the name of a configured connection",
+                            context
+                        )));
+                    }
+
+                    if db.columns.is_empty() {
+                        return Err(RestError::BadRequest(format!(
+                            "{}: Database destination must map at least one column",
+                            context
+                        )));
+                    }
+
+                    if db.upsert
+                        && (db.key_columns.is_empty() || db.key_columns.iter().any(|key| !db.columns.contains_key(key)))
+                    {
+                        return Err(RestError::BadRequest(format!(
+                            "{}: Database upsert requires key columns that are mapped",
+                            context
+                        )));
+                    }
+
+                    if db.batch_size.is_some_and(|size| size <= 0) {
+                        return Err(RestError::BadRequest(format!(
+                            "{}: Database batch size must be positive",
+                            context
+                        )));
+                    }
+                } else {
+                    return Err(RestError::BadRequest(format!(
+                        "{}: Database destination must include database configuration",
+                        context
+                    )));
+                }
             }
             _ => {
                 return Err(RestError::BadRequest(format!(
@@ -398,21 +483,44 @@ pub async fn create_schedule(
         }
     }
 
+    // Workflow schedules are stored with the task of the workflow's first step
+    let (task_id, workflow_id) = match request.workflow_id {
+        Some(workflow_id) => {
+            let workflow = workflow_store(&ctx)?
+                .find_workflow(workflow_id.clone())
+                .await
+                .map_err(|e| RestError::InternalError(e.to_string()))?
+                .ok_or_else(|| RestError::not_found("Workflow", &workflow_id.to_string()))?;
+            let first_step = workflow
+                .steps
+                .first()
+                .ok_or_else(|| RestError::BadRequest("Workflow has no steps".to_string()))?;
+            (first_step.task_id.clone(), Some(workflow.id))
+        }
+        None => (
+            request
+                .task_id
+                .ok_or_else(|| RestError::BadRequest("Either taskId or workflowId is required".to_string()))?,
+            None,
+        ),
+    };
+
     // Validate that task exists
     let task_repo = ctx.repositories.task_repository();
     let _task = task_repo
-        .find_by_id(request.task_id.as_i32().unwrap_or(0))
+        .find_by_id(task_id.as_i32().unwrap_or(0))
         .await
         .map_err(|db_err| {
             let sanitized_error = sanitizer.sanitize_error(&db_err);
             RestError::InternalError(sanitized_error.message)
         })?
-        .ok_or_else(|| RestError::not_found("Task", &request.task_id.to_string()))?;
+        .ok_or_else(|| RestError::not_found("Task", &task_id.to_string()))?;
 
     // Create UnifiedSchedule from request
     let unified_schedule = ratchet_api_types::UnifiedSchedule {
         id: ratchet_api_types::ApiId::from_i32(0), // Will be set by database
-        task_id: request.task_id,
+        task_id,
+        workflow_id,
         name: request.name,
         description: request.description,
         cron_expression: request.cron_expression,
@@ -422,6 +530,7 @@ pub async fn create_schedule(
         created_at: chrono::Utc::now(),
         updated_at: chrono::Utc::now(),
         output_destinations: request.output_destinations,
+        policy: request.policy.unwrap_or_default(),
     };
 
     // Create the schedule using the repository
@@ -429,7 +538,7 @@ pub async fn create_schedule(
     let created_schedule = schedule_repo
         .create(unified_schedule)
         .await
-        .map_err(|e| RestError::InternalError(format!("Failed to create schedule: {}", e)))?;
+        .map_err(schedule_write_error("create"))?;
 
     // Add schedule to running scheduler if available and enabled
     if let Some(scheduler) = &ctx.scheduler_service {
@@ -535,6 +644,9 @@ pub async fn update_schedule(
         }
         existing_schedule.output_destinations = Some(destinations);
     }
+    if let Some(policy) = request.policy {
+        existing_schedule.policy = policy;
+    }
 
     // Update timestamp
     existing_schedule.updated_at = chrono::Utc::now();
@@ -543,7 +655,7 @@ pub async fn update_schedule(
     let updated_schedule = schedule_repo
         .update(existing_schedule)
         .await
-        .map_err(|e| RestError::InternalError(format!("Failed to update schedule: {}", e)))?;
+        .map_err(schedule_write_error("update"))?;
 
     // Update schedule in running scheduler if available
     if let Some(scheduler) = &ctx.scheduler_service {
@@ -703,6 +815,28 @@ pub async fn trigger_schedule(
         return Err(RestError::BadRequest("Cannot trigger disabled schedule".to_string()));
     }
 
+    // Workflow schedules queue a run of the workflow instead of a job
+    if let Some(workflow_id) = schedule.workflow_id.clone() {
+        let run = workflow_store(&ctx)?
+            .queue_run(workflow_id, serde_json::json!({}), Some(schedule.id.clone()))
+            .await
+            .map_err(|e| RestError::BadRequest(e.to_string()))?;
+
+        let mut updated_schedule = schedule;
+        updated_schedule.last_run = Some(chrono::Utc::now());
+        schedule_repo
+            .update(updated_schedule)
+            .await
+            .map_err(|e| RestError::InternalError(format!("Failed to update schedule last_run: {}", e)))?;
+
+        info!("Queued workflow run {} for triggered schedule {}", run.id, schedule_id);
+        return Ok(Json(ApiResponse::new(serde_json::json!({
+            "success": true,
+            "message": "Schedule triggered successfully",
+            "workflowRun": run
+        }))));
+    }
+
     // Validate that the associated task exists
     let task_repo = ctx.repositories.task_repository();
     let _task = task_repo
@@ -730,6 +864,10 @@ pub async fn trigger_schedule(
         scheduled_for: None, // Immediate execution
         error_message: None,
         output_destinations: output_destinations_clone,
+        input: None,
+        depends_on: Vec::new(),
+        idempotency_key: None,
+        schedule_id: Some(schedule.id.clone()),
     };
 
     // Create the job
@@ -0,0 +1,139 @@
+//! Task secret management endpoints
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use ratchet_api_types::SecretScope;
+use ratchet_interfaces::{SecretError, SecretKey, SecretStore};
+use ratchet_web::ApiResponse;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::{
+    context::TasksContext,
+    errors::{RestError, RestResult},
+    models::secrets::{DeleteSecretQuery, SecretsQuery, SetSecretRequest},
+};
+
+fn secret_store(ctx: &TasksContext) -> RestResult<&Arc<dyn SecretStore>> {
+    ctx.secret_store
+        .as_ref()
+        .ok_or_else(|| RestError::ServiceUnavailable("Secret storage is not configured".to_string()))
+}
+
+fn parse_scope(scope: Option<&str>) -> RestResult<Option<SecretScope>> {
+    scope
+        .map(|name| {
+            SecretScope::from_name(name).ok_or_else(|| {
+                RestError::BadRequest(format!(
+                    "Unknown secret scope '{}'; expected global, repository or task",
+                    name
+                ))
+            })
+        })
+        .transpose()
+}
+
+fn secret_error(error: SecretError) -> RestError {
+    match error {
+        SecretError::NotFound { name } => RestError::NotFound(format!("Secret '{}' not found", name)),
+        SecretError::Invalid { message } => RestError::BadRequest(message),
+        other => RestError::InternalError(other.to_string()),
+    }
+}
+
+/// List secret names and scopes; values are never returned
+#[utoipa::path(
+    get,
+    path = "/api/v1/secrets",
+    tag = "secrets",
+    summary = "List secrets",
+    description = "List the names, scopes and descriptions of stored secrets",
+    params(
+        ("scope" = Option<String>, Query, description = "Only list secrets in this scope")
+    ),
+    responses(
+        (status = 200, description = "Secrets retrieved successfully"),
+        (status = 400, description = "Unknown scope"),
+        (status = 503, description = "Secret storage is not configured")
+    )
+)]
+pub async fn list_secrets(
+    State(ctx): State<TasksContext>,
+    Query(query): Query<SecretsQuery>,
+) -> RestResult<impl IntoResponse> {
+    let store = secret_store(&ctx)?;
+    let scope = parse_scope(query.scope.as_deref())?;
+
+    let secrets = store.list_secrets(scope).await.map_err(secret_error)?;
+    Ok(Json(ApiResponse::new(secrets)))
+}
+
+/// Create or replace a secret
+#[utoipa::path(
+    post,
+    path = "/api/v1/secrets",
+    tag = "secrets",
+    summary = "Set a secret",
+    description = "Encrypt and store a secret, replacing any secret with the same name and scope",
+    request_body = SetSecretRequest,
+    responses(
+        (status = 201, description = "Secret stored successfully"),
+        (status = 400, description = "Invalid secret name or scope"),
+        (status = 503, description = "Secret storage is not configured")
+    )
+)]
+pub async fn set_secret(
+    State(ctx): State<TasksContext>,
+    Json(request): Json<SetSecretRequest>,
+) -> RestResult<impl IntoResponse> {
+    info!("Setting secret: {:?}", request);
+
+    let store = secret_store(&ctx)?;
+    let scope = parse_scope(request.scope.as_deref())?.unwrap_or(SecretScope::Global);
+    let key = SecretKey::new(request.name, scope, request.scope_id);
+
+    let secret = store
+        .set_secret(key, &request.value, request.description)
+        .await
+        .map_err(secret_error)?;
+    Ok((StatusCode::CREATED, Json(ApiResponse::new(secret))))
+}
+
+/// Delete a secret
+#[utoipa::path(
+    delete,
+    path = "/api/v1/secrets/{name}",
+    tag = "secrets",
+    summary = "Delete a secret",
+    params(
+        ("name" = String, Path, description = "Secret name"),
+        ("scope" = Option<String>, Query, description = "Scope of the secret, global by default"),
+        ("scopeId" = Option<String>, Query, description = "Repository ID or task UUID of a scoped secret")
+    ),
+    responses(
+        (status = 204, description = "Secret deleted successfully"),
+        (status = 404, description = "Secret not found"),
+        (status = 503, description = "Secret storage is not configured")
+    )
+)]
+pub async fn delete_secret(
+    State(ctx): State<TasksContext>,
+    Path(name): Path<String>,
+    Query(query): Query<DeleteSecretQuery>,
+) -> RestResult<impl IntoResponse> {
+    info!("Deleting secret: {}", name);
+
+    let store = secret_store(&ctx)?;
+    let scope = parse_scope(query.scope.as_deref())?.unwrap_or(SecretScope::Global);
+    let key = SecretKey::new(name.clone(), scope, query.scope_id);
+
+    if store.delete_secret(key).await.map_err(secret_error)? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(RestError::NotFound(format!("Secret '{}' not found", name)))
+    }
+}
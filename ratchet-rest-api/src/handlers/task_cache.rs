@@ -0,0 +1,81 @@
+//! Task result cache endpoints
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use ratchet_api_types::ApiId;
+use ratchet_interfaces::{ResultCacheError, TaskResultCache};
+use ratchet_web::ApiResponse;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::{
+    context::TasksContext,
+    errors::{RestError, RestResult},
+    models::tasks::PurgeTaskCacheResponse,
+};
+
+fn result_cache(ctx: &TasksContext) -> RestResult<&Arc<dyn TaskResultCache>> {
+    ctx.result_cache
+        .as_ref()
+        .ok_or_else(|| RestError::ServiceUnavailable("The result cache is not configured".to_string()))
+}
+
+fn result_cache_error(error: ResultCacheError) -> RestError {
+    match error {
+        ResultCacheError::TaskNotFound { id } => RestError::not_found("Task", &id),
+        ResultCacheError::Storage { message } => RestError::InternalError(message),
+    }
+}
+
+/// List the results cached for a task
+#[utoipa::path(
+    get,
+    path = "/api/v1/tasks/{id}/cache",
+    tag = "tasks",
+    summary = "List cached task results",
+    description = "List the results served to jobs of the task instead of running it, oldest first. Tasks opt into result caching with a `cache` entry in their metadata",
+    params(
+        ("id" = String, Path, description = "Task ID")
+    ),
+    responses(
+        (status = 200, description = "Cached results retrieved successfully"),
+        (status = 404, description = "Task not found"),
+        (status = 503, description = "The result cache is not configured")
+    )
+)]
+pub async fn list_task_cache(State(ctx): State<TasksContext>, Path(id): Path<String>) -> RestResult<impl IntoResponse> {
+    let entries = result_cache(&ctx)?
+        .entries(ApiId::from_string(id))
+        .await
+        .map_err(result_cache_error)?;
+    Ok(Json(ApiResponse::new(entries)))
+}
+
+/// Drop the results cached for a task
+#[utoipa::path(
+    delete,
+    path = "/api/v1/tasks/{id}/cache",
+    tag = "tasks",
+    summary = "Purge cached task results",
+    description = "Drop the cached results of the task so its next jobs run it again",
+    params(
+        ("id" = String, Path, description = "Task ID")
+    ),
+    responses(
+        (status = 200, description = "Cached results purged", body = PurgeTaskCacheResponse),
+        (status = 404, description = "Task not found"),
+        (status = 503, description = "The result cache is not configured")
+    )
+)]
+pub async fn purge_task_cache(State(ctx): State<TasksContext>, Path(id): Path<String>) -> RestResult<impl IntoResponse> {
+    info!("Purging cached results of task: {}", id);
+
+    let purged = result_cache(&ctx)?
+        .purge(ApiId::from_string(id))
+        .await
+        .map_err(result_cache_error)?;
+    Ok(Json(ApiResponse::new(PurgeTaskCacheResponse { purged })))
+}
@@ -210,6 +210,8 @@ pub async fn create_task(
         sync_status: "local".to_string(),
         needs_push: false,
         last_synced_at: None,
+        signature_status: None,
+        signed_by: None,
         input_schema: request.input_schema,
         output_schema: request.output_schema,
         metadata: request.metadata,
@@ -0,0 +1,256 @@
+//! Webhook trigger management and delivery endpoints
+
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use ratchet_api_types::{ApiId, TriggerDeliveryStatus, UnifiedWebhookTrigger};
+use ratchet_interfaces::{TriggerError, TriggerStore};
+use ratchet_web::ApiResponse;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::{
+    context::TasksContext,
+    errors::{RestError, RestResult},
+    models::triggers::{CreateWebhookTriggerRequest, UpdateWebhookTriggerRequest},
+};
+
+fn trigger_store(ctx: &TasksContext) -> RestResult<&Arc<dyn TriggerStore>> {
+    ctx.trigger_store
+        .as_ref()
+        .ok_or_else(|| RestError::ServiceUnavailable("Webhook triggers are not configured".to_string()))
+}
+
+fn trigger_error(error: TriggerError) -> RestError {
+    match error {
+        TriggerError::NotFound { name } => RestError::not_found("Webhook trigger", &name),
+        TriggerError::Unauthorized { message } => RestError::Unauthorized(message),
+        TriggerError::Invalid { message } => RestError::BadRequest(message),
+        TriggerError::Storage { message } => RestError::InternalError(message),
+    }
+}
+
+async fn find_trigger(store: &Arc<dyn TriggerStore>, id: &str) -> RestResult<UnifiedWebhookTrigger> {
+    store
+        .find_trigger(ApiId::from_string(id))
+        .await
+        .map_err(trigger_error)?
+        .ok_or_else(|| RestError::not_found("Webhook trigger", id))
+}
+
+/// List all webhook triggers
+#[utoipa::path(
+    get,
+    path = "/api/v1/webhook-triggers",
+    tag = "triggers",
+    summary = "List webhook triggers",
+    responses(
+        (status = 200, description = "Webhook triggers retrieved successfully"),
+        (status = 503, description = "Webhook triggers are not configured")
+    )
+)]
+pub async fn list_webhook_triggers(State(ctx): State<TasksContext>) -> RestResult<impl IntoResponse> {
+    let triggers = trigger_store(&ctx)?.list_triggers().await.map_err(trigger_error)?;
+    Ok(Json(ApiResponse::new(triggers)))
+}
+
+/// Create a webhook trigger
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhook-triggers",
+    tag = "triggers",
+    summary = "Create a webhook trigger",
+    description = "Create a named endpoint at /api/v1/triggers/{name} that queues a job of a task for every signed delivery",
+    request_body = CreateWebhookTriggerRequest,
+    responses(
+        (status = 201, description = "Webhook trigger created successfully"),
+        (status = 400, description = "Invalid settings, unknown task or duplicate name"),
+        (status = 503, description = "Webhook triggers are not configured")
+    )
+)]
+pub async fn create_webhook_trigger(
+    State(ctx): State<TasksContext>,
+    Json(request): Json<CreateWebhookTriggerRequest>,
+) -> RestResult<impl IntoResponse> {
+    info!("Creating webhook trigger: {}", request.name);
+
+    let now = chrono::Utc::now();
+    let trigger = UnifiedWebhookTrigger {
+        id: ApiId::from_i32(0), // Will be set by database
+        name: request.name,
+        description: request.description,
+        task_id: request.task_id,
+        enabled: request.enabled.unwrap_or(true),
+        signature: request.signature,
+        input_mapping: request.input_mapping,
+        filter_schema: request.filter_schema,
+        delivery_id_header: request.delivery_id_header,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let created = trigger_store(&ctx)?
+        .create_trigger(trigger)
+        .await
+        .map_err(trigger_error)?;
+    Ok((StatusCode::CREATED, Json(ApiResponse::new(created))))
+}
+
+/// Get a webhook trigger by ID
+#[utoipa::path(
+    get,
+    path = "/api/v1/webhook-triggers/{id}",
+    tag = "triggers",
+    summary = "Get a webhook trigger",
+    params(
+        ("id" = String, Path, description = "Webhook trigger ID")
+    ),
+    responses(
+        (status = 200, description = "Webhook trigger retrieved successfully"),
+        (status = 404, description = "Webhook trigger not found")
+    )
+)]
+pub async fn get_webhook_trigger(
+    State(ctx): State<TasksContext>,
+    Path(id): Path<String>,
+) -> RestResult<impl IntoResponse> {
+    let trigger = find_trigger(trigger_store(&ctx)?, &id).await?;
+    Ok(Json(ApiResponse::new(trigger)))
+}
+
+/// Update a webhook trigger
+#[utoipa::path(
+    patch,
+    path = "/api/v1/webhook-triggers/{id}",
+    tag = "triggers",
+    summary = "Update a webhook trigger",
+    params(
+        ("id" = String, Path, description = "Webhook trigger ID")
+    ),
+    request_body = UpdateWebhookTriggerRequest,
+    responses(
+        (status = 200, description = "Webhook trigger updated successfully"),
+        (status = 400, description = "Invalid settings, unknown task or duplicate name"),
+        (status = 404, description = "Webhook trigger not found")
+    )
+)]
+pub async fn update_webhook_trigger(
+    State(ctx): State<TasksContext>,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateWebhookTriggerRequest>,
+) -> RestResult<impl IntoResponse> {
+    info!("Updating webhook trigger: {}", id);
+
+    let store = trigger_store(&ctx)?;
+    let mut trigger = find_trigger(store, &id).await?;
+    if let Some(name) = request.name {
+        trigger.name = name;
+    }
+    if let Some(description) = request.description {
+        trigger.description = Some(description);
+    }
+    if let Some(task_id) = request.task_id {
+        trigger.task_id = task_id;
+    }
+    if let Some(enabled) = request.enabled {
+        trigger.enabled = enabled;
+    }
+    if let Some(signature) = request.signature {
+        trigger.signature = signature;
+    }
+    if let Some(input_mapping) = request.input_mapping {
+        trigger.input_mapping = Some(input_mapping);
+    }
+    if let Some(filter_schema) = request.filter_schema {
+        trigger.filter_schema = Some(filter_schema);
+    }
+    if let Some(delivery_id_header) = request.delivery_id_header {
+        trigger.delivery_id_header = Some(delivery_id_header);
+    }
+
+    let updated = store.update_trigger(trigger).await.map_err(trigger_error)?;
+    Ok(Json(ApiResponse::new(updated)))
+}
+
+/// Delete a webhook trigger
+#[utoipa::path(
+    delete,
+    path = "/api/v1/webhook-triggers/{id}",
+    tag = "triggers",
+    summary = "Delete a webhook trigger",
+    params(
+        ("id" = String, Path, description = "Webhook trigger ID")
+    ),
+    responses(
+        (status = 204, description = "Webhook trigger deleted successfully"),
+        (status = 404, description = "Webhook trigger not found")
+    )
+)]
+pub async fn delete_webhook_trigger(
+    State(ctx): State<TasksContext>,
+    Path(id): Path<String>,
+) -> RestResult<impl IntoResponse> {
+    info!("Deleting webhook trigger: {}", id);
+
+    if trigger_store(&ctx)?
+        .delete_trigger(ApiId::from_string(id.clone()))
+        .await
+        .map_err(trigger_error)?
+    {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(RestError::not_found("Webhook trigger", &id))
+    }
+}
+
+/// Deliver a payload to a webhook trigger
+#[utoipa::path(
+    post,
+    path = "/api/v1/triggers/{name}",
+    tag = "triggers",
+    summary = "Deliver to a webhook trigger",
+    description = "Verify the HMAC signature of the payload and queue a job of the trigger's task. Payloads that do not match the trigger's filter schema and repeated delivery IDs are accepted without queueing a job.",
+    params(
+        ("name" = String, Path, description = "Webhook trigger name")
+    ),
+    request_body(content = serde_json::Value, description = "Payload as sent by the external system"),
+    responses(
+        (status = 202, description = "Job queued"),
+        (status = 200, description = "Delivery filtered out or already seen"),
+        (status = 400, description = "Payload is not JSON or cannot be mapped to the job input"),
+        (status = 401, description = "Missing or invalid signature"),
+        (status = 404, description = "Webhook trigger not found or disabled")
+    )
+)]
+pub async fn deliver_to_trigger(
+    State(ctx): State<TasksContext>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> RestResult<impl IntoResponse> {
+    let headers: HashMap<String, String> = headers
+        .iter()
+        .filter_map(|(name, value)| Some((name.as_str().to_string(), value.to_str().ok()?.to_string())))
+        .collect();
+
+    let delivery = trigger_store(&ctx)?
+        .deliver(&name, &headers, &body)
+        .await
+        .map_err(|e| {
+            if matches!(e, TriggerError::Unauthorized { .. }) {
+                warn!("Rejected delivery to webhook trigger {}: {}", name, e);
+            }
+            trigger_error(e)
+        })?;
+
+    let status = match delivery.status {
+        TriggerDeliveryStatus::Queued => StatusCode::ACCEPTED,
+        TriggerDeliveryStatus::Duplicate | TriggerDeliveryStatus::Filtered => StatusCode::OK,
+    };
+    Ok((status, Json(ApiResponse::new(delivery))))
+}
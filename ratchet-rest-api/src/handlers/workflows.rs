@@ -0,0 +1,274 @@
+//! Workflow management and run endpoints
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use ratchet_api_types::{ApiId, UnifiedWorkflow};
+use ratchet_interfaces::{WorkflowError, WorkflowStore};
+use ratchet_web::ApiResponse;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::{
+    context::TasksContext,
+    errors::{RestError, RestResult},
+    models::workflows::{CreateWorkflowRequest, RunWorkflowRequest, UpdateWorkflowRequest, WorkflowRunsQuery},
+};
+
+/// Number of runs listed when no limit is given
+const DEFAULT_RUN_LIMIT: u64 = 50;
+
+pub(crate) fn workflow_store(ctx: &TasksContext) -> RestResult<&Arc<dyn WorkflowStore>> {
+    ctx.workflow_store
+        .as_ref()
+        .ok_or_else(|| RestError::ServiceUnavailable("Workflows are not configured".to_string()))
+}
+
+fn workflow_error(error: WorkflowError) -> RestError {
+    match error {
+        WorkflowError::NotFound { id } => RestError::not_found("Workflow", &id),
+        WorkflowError::RunNotFound { id } => RestError::not_found("Workflow run", &id),
+        WorkflowError::Invalid { message } => RestError::BadRequest(message),
+        WorkflowError::Storage { message } => RestError::InternalError(message),
+    }
+}
+
+async fn find_workflow(store: &Arc<dyn WorkflowStore>, id: &str) -> RestResult<UnifiedWorkflow> {
+    store
+        .find_workflow(ApiId::from_string(id))
+        .await
+        .map_err(workflow_error)?
+        .ok_or_else(|| RestError::not_found("Workflow", id))
+}
+
+/// List all workflows
+#[utoipa::path(
+    get,
+    path = "/api/v1/workflows",
+    tag = "workflows",
+    summary = "List workflows",
+    responses(
+        (status = 200, description = "Workflows retrieved successfully"),
+        (status = 503, description = "Workflows are not configured")
+    )
+)]
+pub async fn list_workflows(State(ctx): State<TasksContext>) -> RestResult<impl IntoResponse> {
+    let workflows = workflow_store(&ctx)?.list_workflows().await.map_err(workflow_error)?;
+    Ok(Json(ApiResponse::new(workflows)))
+}
+
+/// Create a workflow
+#[utoipa::path(
+    post,
+    path = "/api/v1/workflows",
+    tag = "workflows",
+    summary = "Create a workflow",
+    description = "Create a workflow from steps that run registered tasks once the steps they depend on have completed",
+    request_body = CreateWorkflowRequest,
+    responses(
+        (status = 201, description = "Workflow created successfully"),
+        (status = 400, description = "Invalid step graph, unknown task or duplicate name"),
+        (status = 503, description = "Workflows are not configured")
+    )
+)]
+pub async fn create_workflow(
+    State(ctx): State<TasksContext>,
+    Json(request): Json<CreateWorkflowRequest>,
+) -> RestResult<impl IntoResponse> {
+    info!("Creating workflow: {}", request.name);
+
+    let now = chrono::Utc::now();
+    let workflow = UnifiedWorkflow {
+        id: ApiId::from_i32(0), // Will be set by database
+        name: request.name,
+        description: request.description,
+        enabled: request.enabled.unwrap_or(true),
+        steps: request.steps,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let created = workflow_store(&ctx)?
+        .create_workflow(workflow)
+        .await
+        .map_err(workflow_error)?;
+    Ok((StatusCode::CREATED, Json(ApiResponse::new(created))))
+}
+
+/// Get a workflow by ID
+#[utoipa::path(
+    get,
+    path = "/api/v1/workflows/{id}",
+    tag = "workflows",
+    summary = "Get a workflow",
+    params(
+        ("id" = String, Path, description = "Workflow ID")
+    ),
+    responses(
+        (status = 200, description = "Workflow retrieved successfully"),
+        (status = 404, description = "Workflow not found")
+    )
+)]
+pub async fn get_workflow(State(ctx): State<TasksContext>, Path(id): Path<String>) -> RestResult<impl IntoResponse> {
+    let workflow = find_workflow(workflow_store(&ctx)?, &id).await?;
+    Ok(Json(ApiResponse::new(workflow)))
+}
+
+/// Update a workflow
+#[utoipa::path(
+    patch,
+    path = "/api/v1/workflows/{id}",
+    tag = "workflows",
+    summary = "Update a workflow",
+    description = "Update a workflow; runs that are already queued use the new definition",
+    params(
+        ("id" = String, Path, description = "Workflow ID")
+    ),
+    request_body = UpdateWorkflowRequest,
+    responses(
+        (status = 200, description = "Workflow updated successfully"),
+        (status = 400, description = "Invalid step graph, unknown task or duplicate name"),
+        (status = 404, description = "Workflow not found")
+    )
+)]
+pub async fn update_workflow(
+    State(ctx): State<TasksContext>,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateWorkflowRequest>,
+) -> RestResult<impl IntoResponse> {
+    info!("Updating workflow: {}", id);
+
+    let store = workflow_store(&ctx)?;
+    let mut workflow = find_workflow(store, &id).await?;
+    if let Some(name) = request.name {
+        workflow.name = name;
+    }
+    if let Some(description) = request.description {
+        workflow.description = Some(description);
+    }
+    if let Some(enabled) = request.enabled {
+        workflow.enabled = enabled;
+    }
+    if let Some(steps) = request.steps {
+        workflow.steps = steps;
+    }
+
+    let updated = store.update_workflow(workflow).await.map_err(workflow_error)?;
+    Ok(Json(ApiResponse::new(updated)))
+}
+
+/// Delete a workflow and its runs
+#[utoipa::path(
+    delete,
+    path = "/api/v1/workflows/{id}",
+    tag = "workflows",
+    summary = "Delete a workflow",
+    params(
+        ("id" = String, Path, description = "Workflow ID")
+    ),
+    responses(
+        (status = 204, description = "Workflow deleted successfully"),
+        (status = 404, description = "Workflow not found")
+    )
+)]
+pub async fn delete_workflow(State(ctx): State<TasksContext>, Path(id): Path<String>) -> RestResult<impl IntoResponse> {
+    info!("Deleting workflow: {}", id);
+
+    if workflow_store(&ctx)?
+        .delete_workflow(ApiId::from_string(id.clone()))
+        .await
+        .map_err(workflow_error)?
+    {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(RestError::not_found("Workflow", &id))
+    }
+}
+
+/// Queue a run of a workflow
+#[utoipa::path(
+    post,
+    path = "/api/v1/workflows/{id}/run",
+    tag = "workflows",
+    summary = "Run a workflow",
+    description = "Queue a run of the workflow; the job processor picks it up and runs its steps in dependency order",
+    params(
+        ("id" = String, Path, description = "Workflow ID")
+    ),
+    request_body = RunWorkflowRequest,
+    responses(
+        (status = 202, description = "Workflow run queued"),
+        (status = 400, description = "Workflow is disabled"),
+        (status = 404, description = "Workflow not found")
+    )
+)]
+pub async fn run_workflow(
+    State(ctx): State<TasksContext>,
+    Path(id): Path<String>,
+    Json(request): Json<RunWorkflowRequest>,
+) -> RestResult<impl IntoResponse> {
+    info!("Queueing run of workflow: {}", id);
+
+    let input = request.input.unwrap_or_else(|| serde_json::json!({}));
+    let run = workflow_store(&ctx)?
+        .queue_run(ApiId::from_string(id), input, None)
+        .await
+        .map_err(workflow_error)?;
+    Ok((StatusCode::ACCEPTED, Json(ApiResponse::new(run))))
+}
+
+/// List the runs of a workflow
+#[utoipa::path(
+    get,
+    path = "/api/v1/workflows/{id}/runs",
+    tag = "workflows",
+    summary = "List workflow runs",
+    params(
+        ("id" = String, Path, description = "Workflow ID"),
+        ("limit" = Option<u64>, Query, description = "Maximum number of runs to return, newest first")
+    ),
+    responses(
+        (status = 200, description = "Workflow runs retrieved successfully"),
+        (status = 404, description = "Workflow not found")
+    )
+)]
+pub async fn list_workflow_runs(
+    State(ctx): State<TasksContext>,
+    Path(id): Path<String>,
+    Query(query): Query<WorkflowRunsQuery>,
+) -> RestResult<impl IntoResponse> {
+    let store = workflow_store(&ctx)?;
+    let workflow = find_workflow(store, &id).await?;
+
+    let runs = store
+        .list_runs(Some(workflow.id), query.limit.unwrap_or(DEFAULT_RUN_LIMIT))
+        .await
+        .map_err(workflow_error)?;
+    Ok(Json(ApiResponse::new(runs)))
+}
+
+/// Get a workflow run with the state of each of its steps
+#[utoipa::path(
+    get,
+    path = "/api/v1/workflow-runs/{id}",
+    tag = "workflows",
+    summary = "Get a workflow run",
+    params(
+        ("id" = String, Path, description = "Workflow run ID")
+    ),
+    responses(
+        (status = 200, description = "Workflow run retrieved successfully"),
+        (status = 404, description = "Workflow run not found")
+    )
+)]
+pub async fn get_workflow_run(State(ctx): State<TasksContext>, Path(id): Path<String>) -> RestResult<impl IntoResponse> {
+    let run = workflow_store(&ctx)?
+        .find_run(ApiId::from_string(id.clone()))
+        .await
+        .map_err(workflow_error)?
+        .ok_or_else(|| RestError::not_found("Workflow run", &id))?;
+    Ok(Json(ApiResponse::new(run)))
+}
@@ -85,17 +85,44 @@ use utoipa::OpenApi;
         handlers::tasks::list_tasks,
         handlers::tasks::create_task,
         handlers::tasks::get_task,
+        handlers::task_cache::list_task_cache,
+        handlers::task_cache::purge_task_cache,
 
         // Execution endpoints
         handlers::executions::list_executions,
 
         // Job endpoints
         handlers::jobs::list_jobs,
+        handlers::deliveries::list_failed_deliveries,
+        handlers::deliveries::redeliver_output,
 
         // Schedule endpoints (only annotated ones)
         handlers::schedules::list_schedules,
         handlers::schedules::create_schedule,
 
+        // Secret endpoints
+        handlers::secrets::list_secrets,
+        handlers::secrets::set_secret,
+        handlers::secrets::delete_secret,
+
+        // Workflow endpoints
+        handlers::workflows::list_workflows,
+        handlers::workflows::create_workflow,
+        handlers::workflows::get_workflow,
+        handlers::workflows::update_workflow,
+        handlers::workflows::delete_workflow,
+        handlers::workflows::run_workflow,
+        handlers::workflows::list_workflow_runs,
+        handlers::workflows::get_workflow_run,
+
+        // Webhook trigger endpoints
+        handlers::triggers::list_webhook_triggers,
+        handlers::triggers::create_webhook_trigger,
+        handlers::triggers::get_webhook_trigger,
+        handlers::triggers::update_webhook_trigger,
+        handlers::triggers::delete_webhook_trigger,
+        handlers::triggers::deliver_to_trigger,
+
         // Monitoring and metrics  
         handlers::metrics::get_metrics,
         handlers::metrics::get_prometheus_metrics,
@@ -112,6 +139,7 @@ use utoipa::OpenApi;
             models::tasks::SyncTasksResponse,
             models::tasks::TaskSyncError,
             models::tasks::TaskStats,
+            models::tasks::PurgeTaskCacheResponse,
 
             // Execution request/response models
             models::executions::CreateExecutionRequest,
@@ -123,13 +151,42 @@ use utoipa::OpenApi;
             models::jobs::CreateJobRequest,
             models::jobs::UpdateJobRequest,
             models::jobs::JobStats,
+            models::jobs::FailedDeliveriesQuery,
 
             // Schedule request/response models
             models::schedules::CreateScheduleRequest,
             models::schedules::UpdateScheduleRequest,
             models::schedules::ScheduleStats,
 
+            // Secret request models
+            models::secrets::SetSecretRequest,
+            models::secrets::SecretsQuery,
+            models::secrets::DeleteSecretQuery,
+
+            // Workflow request models
+            models::workflows::CreateWorkflowRequest,
+            models::workflows::UpdateWorkflowRequest,
+            models::workflows::RunWorkflowRequest,
+            models::workflows::WorkflowRunsQuery,
+
+            // Webhook trigger request models
+            models::triggers::CreateWebhookTriggerRequest,
+            models::triggers::UpdateWebhookTriggerRequest,
+
             // Domain types from ratchet-api-types
+            ratchet_api_types::UnifiedJobDependency,
+            ratchet_api_types::UnifiedSchedulePolicy,
+            ratchet_api_types::UnifiedBlackoutWindow,
+            ratchet_api_types::UnifiedSecret,
+            ratchet_api_types::UnifiedCachedResult,
+            ratchet_api_types::UnifiedDeliveryAttempt,
+            ratchet_api_types::UnifiedWorkflow,
+            ratchet_api_types::UnifiedWorkflowStep,
+            ratchet_api_types::UnifiedWorkflowRun,
+            ratchet_api_types::UnifiedWorkflowStepRun,
+            ratchet_api_types::UnifiedWebhookTrigger,
+            ratchet_api_types::UnifiedTriggerSignature,
+            ratchet_api_types::UnifiedTriggerDelivery,
             ratchet_api_types::UnifiedOutputDestination,
             ratchet_api_types::UnifiedWebhookConfig,
             ratchet_api_types::UnifiedRetryPolicy,
@@ -138,11 +195,24 @@ use utoipa::OpenApi;
             ratchet_api_types::UnifiedBasicAuth,
             ratchet_api_types::UnifiedApiKeyAuth,
             ratchet_api_types::UnifiedFilesystemConfig,
+            ratchet_api_types::UnifiedS3Config,
+            ratchet_api_types::UnifiedDatabaseConfig,
 
             // Enum types from ratchet-api-types
             ratchet_api_types::OutputFormat,
             ratchet_api_types::CompressionType,
             ratchet_api_types::HttpMethod,
+            ratchet_api_types::JobDependencyCondition,
+            ratchet_api_types::ScheduleOverlapPolicy,
+            ratchet_api_types::ScheduleMisfirePolicy,
+            ratchet_api_types::SecretScope,
+            ratchet_api_types::WorkflowFailurePolicy,
+            ratchet_api_types::WorkflowRunStatus,
+            ratchet_api_types::WorkflowStepStatus,
+            ratchet_api_types::TriggerSignatureAlgorithm,
+            ratchet_api_types::TriggerDeliveryStatus,
+            ratchet_api_types::OutputDeliveryStatus,
+            ratchet_api_types::TaskSignatureStatus,
 
             // Metrics and monitoring models
             handlers::metrics::SystemMetrics,
@@ -155,6 +225,8 @@ use utoipa::OpenApi;
             handlers::metrics::ExecutionMetrics,
             handlers::metrics::JobMetrics,
             handlers::metrics::ScheduleMetrics,
+            handlers::metrics::HttpClientMetrics,
+            handlers::metrics::CircuitBreakerMetrics,
         )
     ),
     tags(
@@ -163,6 +235,9 @@ use utoipa::OpenApi;
         (name = "jobs", description = "Job queue management"),
         (name = "schedules", description = "Task scheduling operations"),
         (name = "workers", description = "Worker monitoring and management"),
+        (name = "secrets", description = "Encrypted task secret management"),
+        (name = "workflows", description = "Multi-step workflows and their runs"),
+        (name = "triggers", description = "Inbound webhook triggers that queue jobs"),
         (name = "mcp", description = "MCP (Model Context Protocol) development tools"),
         (name = "health", description = "System health and monitoring"),
         (name = "monitoring", description = "System metrics and observability")
@@ -49,6 +49,17 @@ pub struct RetryExecutionRequest {
     pub input: Option<serde_json::Value>,
 }
 
+/// Query parameters for execution logs
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionLogsQuery {
+    /// Minimum level to return (debug, info, warn, error)
+    pub level: Option<String>,
+
+    /// Maximum number of entries to return, counted from the most recent
+    pub limit: Option<usize>,
+}
+
 /// Execution statistics
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
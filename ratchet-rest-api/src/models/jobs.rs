@@ -25,6 +25,13 @@ pub struct CreateJobRequest {
 
     /// Optional output destinations for job results
     pub output_destinations: Option<Vec<ratchet_api_types::UnifiedOutputDestination>>,
+
+    /// Jobs that must finish before this one runs; a parent that ends the wrong way cancels it
+    pub depends_on: Option<Vec<ratchet_api_types::UnifiedJobDependency>>,
+
+    /// Key under which to queue the job; repeating a recent key returns the first job.
+    /// The `Idempotency-Key` header takes precedence.
+    pub idempotency_key: Option<String>,
 }
 
 /// Request to update job status
@@ -78,3 +85,13 @@ pub struct JobStats {
     /// Number of jobs processed in the last 24 hours
     pub jobs_last_24h: u64,
 }
+
+/// Query parameters for listing failed output deliveries
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedDeliveriesQuery {
+    /// Only deliveries of this job
+    pub job_id: Option<ApiId>,
+    /// Only deliveries of this execution
+    pub execution_id: Option<ApiId>,
+}
@@ -2,13 +2,19 @@ pub mod common;
 pub mod executions;
 pub mod jobs;
 pub mod schedules;
+pub mod secrets;
 pub mod tasks;
+pub mod triggers;
 pub mod workers;
+pub mod workflows;
 
 // Re-export commonly used types
 pub use common::{ApiResponse, FilterQuery, ListQuery, PaginationQuery, SortQuery};
 pub use executions::*;
 pub use jobs::*;
 pub use schedules::*;
+pub use secrets::*;
 pub use tasks::*;
+pub use triggers::*;
 pub use workers::*;
+pub use workflows::*;
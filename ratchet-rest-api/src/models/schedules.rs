@@ -2,14 +2,17 @@
 
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
-use ratchet_api_types::{ApiId, UnifiedOutputDestination};
+use ratchet_api_types::{ApiId, UnifiedOutputDestination, UnifiedSchedulePolicy};
 
 /// Request to create a new schedule
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateScheduleRequest {
-    /// ID of the task to schedule
-    pub task_id: ApiId,
+    /// ID of the task to schedule; required unless `workflow_id` is set
+    pub task_id: Option<ApiId>,
+
+    /// ID of a workflow to run instead of a single task
+    pub workflow_id: Option<ApiId>,
 
     /// Human-readable name for the schedule
     pub name: String,
@@ -26,6 +29,10 @@ pub struct CreateScheduleRequest {
     /// Optional output destinations for execution results (webhooks, files, etc.)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub output_destinations: Option<Vec<UnifiedOutputDestination>>,
+
+    /// Timezone, overlap, misfire, jitter and blackout policy; UTC with no restrictions by default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policy: Option<UnifiedSchedulePolicy>,
 }
 
 /// Request to update a schedule
@@ -47,6 +54,10 @@ pub struct UpdateScheduleRequest {
     /// Updated output destinations for execution results (webhooks, files, etc.)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub output_destinations: Option<Vec<UnifiedOutputDestination>>,
+
+    /// Replacement policy; fields it omits take their defaults
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policy: Option<UnifiedSchedulePolicy>,
 }
 
 /// Schedule statistics
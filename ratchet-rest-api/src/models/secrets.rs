@@ -0,0 +1,55 @@
+//! Secret-related request and response models
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use utoipa::ToSchema;
+
+/// Request to create or replace a secret
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetSecretRequest {
+    /// Name tasks use with `ratchet.secrets.get`
+    pub name: String,
+
+    /// Plaintext value; stored encrypted and never returned
+    pub value: String,
+
+    /// Scope of the secret: global (default), repository or task
+    pub scope: Option<String>,
+
+    /// Repository ID for repository secrets, task UUID for task secrets
+    pub scope_id: Option<String>,
+
+    /// Optional description
+    pub description: Option<String>,
+}
+
+impl fmt::Debug for SetSecretRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SetSecretRequest")
+            .field("name", &self.name)
+            .field("scope", &self.scope)
+            .field("scope_id", &self.scope_id)
+            .field("description", &self.description)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Query parameters for listing secrets
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretsQuery {
+    /// Only list secrets in this scope
+    pub scope: Option<String>,
+}
+
+/// Query parameters identifying the secret to delete
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteSecretQuery {
+    /// Scope of the secret: global (default), repository or task
+    pub scope: Option<String>,
+
+    /// Repository ID for repository secrets, task UUID for task secrets
+    pub scope_id: Option<String>,
+}
@@ -105,6 +105,14 @@ pub struct ValidationWarningDetail {
     pub code: String,
 }
 
+/// Result of purging the cached results of a task
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeTaskCacheResponse {
+    /// Number of cached results that were dropped
+    pub purged: usize,
+}
+
 /// Task synchronization response
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
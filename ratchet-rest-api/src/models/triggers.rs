@@ -0,0 +1,63 @@
+//! Webhook trigger request and response models
+
+use ratchet_api_types::{ApiId, UnifiedTriggerSignature};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Request to create a webhook trigger
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWebhookTriggerRequest {
+    /// Unique trigger name, used in `/triggers/{name}`
+    pub name: String,
+
+    /// Optional description of the trigger
+    pub description: Option<String>,
+
+    /// Task to queue; may be referenced by ID, UUID or name
+    pub task_id: ApiId,
+
+    /// Whether deliveries are accepted (default true)
+    pub enabled: Option<bool>,
+
+    /// How deliveries are signed
+    pub signature: UnifiedTriggerSignature,
+
+    /// Input template with `$from` references to `payload` and `headers`
+    pub input_mapping: Option<serde_json::Value>,
+
+    /// JSON schema payloads must match to queue a job
+    pub filter_schema: Option<serde_json::Value>,
+
+    /// Header carrying a unique delivery ID for replay protection
+    pub delivery_id_header: Option<String>,
+}
+
+/// Request to update a webhook trigger; omitted fields are left unchanged
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWebhookTriggerRequest {
+    /// Updated name
+    pub name: Option<String>,
+
+    /// Updated description
+    pub description: Option<String>,
+
+    /// Updated task
+    pub task_id: Option<ApiId>,
+
+    /// Updated enabled status
+    pub enabled: Option<bool>,
+
+    /// Replacement signature settings
+    pub signature: Option<UnifiedTriggerSignature>,
+
+    /// Replacement input template
+    pub input_mapping: Option<serde_json::Value>,
+
+    /// Replacement filter schema
+    pub filter_schema: Option<serde_json::Value>,
+
+    /// Replacement delivery ID header
+    pub delivery_id_header: Option<String>,
+}
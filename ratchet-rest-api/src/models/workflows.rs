@@ -0,0 +1,55 @@
+//! Workflow-related request and response models
+
+use ratchet_api_types::UnifiedWorkflowStep;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Request to create a workflow
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWorkflowRequest {
+    /// Unique workflow name
+    pub name: String,
+
+    /// Optional description of the workflow
+    pub description: Option<String>,
+
+    /// Whether the workflow can be run (default true)
+    pub enabled: Option<bool>,
+
+    /// Steps of the workflow; tasks may be referenced by ID, UUID or name
+    pub steps: Vec<UnifiedWorkflowStep>,
+}
+
+/// Request to update a workflow; omitted fields are left unchanged
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWorkflowRequest {
+    /// Updated name
+    pub name: Option<String>,
+
+    /// Updated description
+    pub description: Option<String>,
+
+    /// Updated enabled status
+    pub enabled: Option<bool>,
+
+    /// Replacement steps
+    pub steps: Option<Vec<UnifiedWorkflowStep>>,
+}
+
+/// Request to run a workflow
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RunWorkflowRequest {
+    /// Workflow input, available to steps as `input` (default `{}`)
+    pub input: Option<serde_json::Value>,
+}
+
+/// Query parameters for listing workflow runs
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowRunsQuery {
+    /// Maximum number of runs to return, newest first (default 50)
+    pub limit: Option<u64>,
+}
@@ -10,6 +10,7 @@ pub mod worker;
 // Re-export commonly used types
 pub use executor::{ExecutionEngine, ExecutionStats, InMemoryTaskExecutor, TaskExecutor};
 pub use process::{
-    WorkerConfig, WorkerProcess, WorkerProcessError, WorkerProcessManager, WorkerProcessStatus, WorkerToManagerMessage,
+    TaskCanceller, WorkerConfig, WorkerProcess, WorkerProcessError, WorkerProcessManager, WorkerProcessStatus,
+    WorkerToManagerMessage,
 };
 pub use worker::{worker_main, Worker};
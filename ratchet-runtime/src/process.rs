@@ -12,9 +12,25 @@ use tokio::sync::{mpsc, oneshot, Mutex};
 use uuid::Uuid;
 
 use ratchet_ipc::{
-    CoordinatorMessage, IpcError, MessageEnvelope, TaskExecutionResult, TaskValidationResult, WorkerMessage,
-    WorkerStatus,
+    CoordinatorMessage, IpcError, MessageEnvelope, TaskExecutionResult, TaskProgressUpdate, TaskValidationResult,
+    WorkerMessage, WorkerStatus,
 };
+use ratchet_js::{EgressPolicy, HttpClientPolicy, HttpTransportConfig, ResourceLimits};
+
+/// Environment variable used to pass resource limits to worker processes as JSON
+pub const WORKER_LIMITS_ENV: &str = "RATCHET_WORKER_LIMITS";
+
+/// Environment variable used to pass the egress policy to worker processes as JSON
+pub const WORKER_EGRESS_ENV: &str = "RATCHET_WORKER_EGRESS";
+
+/// Environment variable used to pass the HTTP client policy to worker processes as JSON
+pub const WORKER_HTTP_ENV: &str = "RATCHET_WORKER_HTTP";
+
+/// Environment variable used to pass proxy and certificate settings to worker processes as JSON
+pub const WORKER_TRANSPORT_ENV: &str = "RATCHET_WORKER_TRANSPORT";
+
+/// How often a busy worker's memory usage is sampled when a heap limit is set
+const MEMORY_POLL_INTERVAL: Duration = Duration::from_millis(250);
 
 /// Configuration for worker processes
 #[derive(Debug, Clone)]
@@ -26,6 +42,15 @@ pub struct WorkerConfig {
     pub health_check_interval_seconds: u64,
     pub task_timeout_seconds: u64,
     pub worker_idle_timeout_seconds: Option<u64>,
+    pub limits: ResourceLimits,
+    /// Destinations task HTTP calls may reach
+    pub egress: EgressPolicy,
+    /// Caching, retries and circuit breaking of task HTTP calls; each worker keeps its own cache and breakers
+    pub http_client: HttpClientPolicy,
+    /// Proxies and certificates of task HTTP calls; client certificates kept in secrets are not passed on
+    pub http_transport: HttpTransportConfig,
+    /// How long a cancelled task may take to stop before its worker is killed
+    pub cancel_grace_period_seconds: u64,
 }
 
 impl Default for WorkerConfig {
@@ -38,6 +63,11 @@ impl Default for WorkerConfig {
             health_check_interval_seconds: 30,
             task_timeout_seconds: 300,               // 5 minutes
             worker_idle_timeout_seconds: Some(3600), // 1 hour
+            limits: ResourceLimits::default(),
+            egress: EgressPolicy::default(),
+            http_client: HttpClientPolicy::default(),
+            http_transport: HttpTransportConfig::default(),
+            cancel_grace_period_seconds: 5,
         }
     }
 }
@@ -69,7 +99,7 @@ impl WorkerProcess {
     /// Spawn a new worker process
     pub async fn spawn(
         worker_id: String,
-        _config: &WorkerConfig,
+        config: &WorkerConfig,
         message_tx: mpsc::UnboundedSender<WorkerToManagerMessage>,
     ) -> Result<Self, WorkerProcessError> {
         debug!("Spawning worker process: {}", worker_id);
@@ -87,6 +117,24 @@ impl WorkerProcess {
             .stderr(std::process::Stdio::piped())
             .kill_on_drop(true);
 
+        if !config.limits.is_unlimited() {
+            let limits = serde_json::to_string(&config.limits)
+                .map_err(|e| WorkerProcessError::SpawnError(format!("Failed to encode limits: {}", e)))?;
+            cmd.env(WORKER_LIMITS_ENV, limits);
+        }
+
+        let egress = serde_json::to_string(&config.egress)
+            .map_err(|e| WorkerProcessError::SpawnError(format!("Failed to encode egress policy: {}", e)))?;
+        cmd.env(WORKER_EGRESS_ENV, egress);
+
+        let http_client = serde_json::to_string(&config.http_client)
+            .map_err(|e| WorkerProcessError::SpawnError(format!("Failed to encode HTTP client policy: {}", e)))?;
+        cmd.env(WORKER_HTTP_ENV, http_client);
+
+        let http_transport = serde_json::to_string(&config.http_transport)
+            .map_err(|e| WorkerProcessError::SpawnError(format!("Failed to encode HTTP transport: {}", e)))?;
+        cmd.env(WORKER_TRANSPORT_ENV, http_transport);
+
         let mut child = cmd
             .spawn()
             .map_err(|e| WorkerProcessError::SpawnError(format!("Failed to spawn worker: {}", e)))?;
@@ -186,6 +234,7 @@ impl WorkerProcess {
             started_at: chrono::Utc::now(),
             completed_at: chrono::Utc::now(),
             duration_ms: 100,
+            logs: Vec::new(),
         })
     }
 
@@ -255,6 +304,26 @@ impl WorkerProcess {
         Ok(())
     }
 
+    /// Kill the worker process without waiting for it to finish its current task
+    pub async fn kill(&mut self) -> Result<(), WorkerProcessError> {
+        warn!("Killing worker process: {}", self.id);
+
+        self.stdin_tx = None;
+        if let Some(mut child) = self.child.take() {
+            if let Err(e) = child.kill().await {
+                debug!("Failed to kill worker process {}: {}", self.id, e);
+            }
+        }
+
+        self.status = WorkerProcessStatus::Failed;
+        Ok(())
+    }
+
+    /// Resident memory of the worker process in megabytes, where the platform exposes it
+    pub fn memory_usage_mb(&self) -> Option<u64> {
+        self.pid.and_then(process_memory_mb)
+    }
+
     /// Check if the worker is available for work
     pub fn is_available(&self) -> bool {
         matches!(self.status, WorkerProcessStatus::Ready)
@@ -356,6 +425,20 @@ impl WorkerProcess {
     }
 }
 
+/// Read the resident set size of a process from procfs
+#[cfg(target_os = "linux")]
+fn process_memory_mb(pid: u32) -> Option<u64> {
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kilobytes: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kilobytes / 1024)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_memory_mb(_pid: u32) -> Option<u64> {
+    None
+}
+
 /// Message from worker to manager
 #[derive(Debug)]
 pub struct WorkerToManagerMessage {
@@ -363,6 +446,42 @@ pub struct WorkerToManagerMessage {
     pub message: CoordinatorMessage,
 }
 
+/// Handle for cancelling tasks running on a [`WorkerProcessManager`]
+///
+/// The handle does not borrow the manager, so it can cancel a task while
+/// [`WorkerProcessManager::send_task`] is waiting for that task's result.
+#[derive(Debug, Clone, Default)]
+pub struct TaskCanceller {
+    requests: Arc<std::sync::Mutex<HashMap<Uuid, oneshot::Sender<()>>>>,
+}
+
+impl TaskCanceller {
+    /// Request cancellation of a running task
+    ///
+    /// Returns `false` if no task with this correlation ID is running.
+    pub fn cancel(&self, correlation_id: Uuid) -> bool {
+        let sender = self
+            .requests
+            .lock()
+            .ok()
+            .and_then(|mut requests| requests.remove(&correlation_id));
+        sender.map(|sender| sender.send(()).is_ok()).unwrap_or(false)
+    }
+
+    fn register(&self, correlation_id: Uuid) -> Option<oneshot::Receiver<()>> {
+        let (tx, rx) = oneshot::channel();
+        let mut requests = self.requests.lock().ok()?;
+        requests.insert(correlation_id, tx);
+        Some(rx)
+    }
+
+    fn unregister(&self, correlation_id: Uuid) {
+        if let Ok(mut requests) = self.requests.lock() {
+            requests.remove(&correlation_id);
+        }
+    }
+}
+
 /// Worker process manager
 pub struct WorkerProcessManager {
     config: WorkerConfig,
@@ -371,8 +490,12 @@ pub struct WorkerProcessManager {
     _pending_validations: Arc<Mutex<HashMap<Uuid, oneshot::Sender<TaskValidationResult>>>>,
     _pending_health_checks: Arc<Mutex<HashMap<Uuid, oneshot::Sender<WorkerStatus>>>>,
     message_tx: mpsc::UnboundedSender<WorkerToManagerMessage>,
+    canceller: TaskCanceller,
+    progress_listeners: ProgressListeners,
 }
 
+type ProgressListeners = Arc<Mutex<HashMap<Uuid, mpsc::UnboundedSender<TaskProgressUpdate>>>>;
+
 impl WorkerProcessManager {
     /// Create a new worker process manager
     pub fn new(config: WorkerConfig) -> Self {
@@ -381,18 +504,21 @@ impl WorkerProcessManager {
         let pending_tasks = Arc::new(Mutex::new(HashMap::new()));
         let _pending_validations = Arc::new(Mutex::new(HashMap::new()));
         let _pending_health_checks = Arc::new(Mutex::new(HashMap::new()));
+        let progress_listeners: ProgressListeners = Arc::new(Mutex::new(HashMap::new()));
 
         // Start message processing task
         let workers_clone = workers.clone();
         let pending_tasks_clone = pending_tasks.clone();
         let pending_validations_clone = _pending_validations.clone();
         let pending_health_checks_clone = _pending_health_checks.clone();
+        let progress_listeners_clone = progress_listeners.clone();
         tokio::spawn(async move {
             Self::process_worker_messages(
                 workers_clone,
                 pending_tasks_clone,
                 pending_validations_clone,
                 pending_health_checks_clone,
+                progress_listeners_clone,
                 message_rx,
             )
             .await;
@@ -405,9 +531,16 @@ impl WorkerProcessManager {
             _pending_validations,
             _pending_health_checks,
             message_tx,
+            canceller: TaskCanceller::default(),
+            progress_listeners,
         }
     }
 
+    /// Handle for cancelling tasks sent through this manager
+    pub fn canceller(&self) -> TaskCanceller {
+        self.canceller.clone()
+    }
+
     /// Start all worker processes
     pub async fn start(&mut self) -> Result<(), WorkerProcessError> {
         info!("Starting {} worker processes", self.config.worker_count);
@@ -456,6 +589,7 @@ impl WorkerProcessManager {
         pending_tasks: Arc<Mutex<HashMap<Uuid, oneshot::Sender<TaskExecutionResult>>>>,
         pending_validations: Arc<Mutex<HashMap<Uuid, oneshot::Sender<TaskValidationResult>>>>,
         _pending_health_checks: Arc<Mutex<HashMap<Uuid, oneshot::Sender<WorkerStatus>>>>,
+        progress_listeners: ProgressListeners,
         mut message_rx: mpsc::UnboundedReceiver<WorkerToManagerMessage>,
     ) {
         while let Some(worker_msg) = message_rx.recv().await {
@@ -480,6 +614,9 @@ impl WorkerProcessManager {
                             debug!("Worker {} reported error", worker.id);
                             worker.status = WorkerProcessStatus::Ready;
                         }
+                        CoordinatorMessage::Progress { .. } => {
+                            debug!("Worker {} reported progress", worker.id);
+                        }
                         _ => {
                             debug!("Unhandled message from worker {}: {:?}", worker.id, worker_msg.message);
                         }
@@ -503,6 +640,15 @@ impl WorkerProcessManager {
                         }
                     }
                 }
+                CoordinatorMessage::Progress {
+                    correlation_id, progress, ..
+                } => {
+                    let listeners = progress_listeners.lock().await;
+                    if let Some(listener) = listeners.get(&correlation_id) {
+                        // The caller may have stopped listening; progress is best effort
+                        let _ = listener.send(progress);
+                    }
+                }
                 CoordinatorMessage::ValidationResult { correlation_id, result } => {
                     if let Ok(mut pending) = pending_validations.try_lock() {
                         if let Some(sender) = pending.remove(&correlation_id) {
@@ -542,6 +688,7 @@ impl WorkerProcessManager {
                                     started_at: chrono::Utc::now(),
                                     completed_at: chrono::Utc::now(),
                                     duration_ms: 0,
+                                    logs: Vec::new(),
                                 };
                                 if sender.send(error_result).is_err() {
                                     warn!("Failed to send error result - receiver may have been dropped");
@@ -588,13 +735,27 @@ impl WorkerProcessManager {
         message: WorkerMessage,
         timeout_duration: Duration,
     ) -> Result<CoordinatorMessage, WorkerProcessError> {
-        use tokio::time::timeout;
+        self.send_task_with_progress(message, timeout_duration, None).await
+    }
+
+    /// Send a task to an available worker, forwarding its progress to `progress`
+    pub async fn send_task_with_progress(
+        &mut self,
+        message: WorkerMessage,
+        timeout_duration: Duration,
+        progress: Option<mpsc::UnboundedSender<TaskProgressUpdate>>,
+    ) -> Result<CoordinatorMessage, WorkerProcessError> {
 
         // Extract correlation ID from message
         let correlation_id = match &message {
             WorkerMessage::ExecuteTask { correlation_id, .. } => *correlation_id,
             WorkerMessage::ValidateTask { correlation_id, .. } => *correlation_id,
             WorkerMessage::Ping { correlation_id } => *correlation_id,
+            WorkerMessage::CancelTask { .. } => {
+                return Err(WorkerProcessError::CommunicationError(
+                    "Tasks are cancelled through TaskCanceller".to_string(),
+                ))
+            }
             WorkerMessage::Shutdown => {
                 return Err(WorkerProcessError::CommunicationError(
                     "Cannot send shutdown to specific worker".to_string(),
@@ -602,13 +763,26 @@ impl WorkerProcessManager {
             }
         };
 
+        // Limits the task declares override the configured ones; the worker refuses
+        // to run a task whose limits cannot be read
+        let limits = match &message {
+            WorkerMessage::ExecuteTask { execution_context, .. } => execution_context
+                .limits
+                .clone()
+                .and_then(|limits| serde_json::from_value::<ResourceLimits>(limits).ok())
+                .map(|task_limits| self.config.limits.with_overrides(&task_limits)),
+            _ => None,
+        }
+        .unwrap_or_else(|| self.config.limits.clone());
+        let timeout_duration = limits.timeout_seconds.map_or(timeout_duration, Duration::from_secs);
+
         // Find available worker ID
-        let worker_id = {
+        let (worker_id, worker_pid) = {
             let workers = self.workers.lock().await;
             workers
                 .iter()
                 .find(|w| w.is_available())
-                .map(|w| w.id.clone())
+                .map(|w| (w.id.clone(), w.pid))
                 .ok_or_else(|| WorkerProcessError::CommunicationError("No available workers".to_string()))?
         };
 
@@ -616,10 +790,15 @@ impl WorkerProcessManager {
         let (tx, rx) = oneshot::channel();
 
         // Store the response channel based on message type
+        let mut cancel_rx = None;
         match &message {
             WorkerMessage::ExecuteTask { .. } => {
                 let mut pending = self.pending_tasks.lock().await;
                 pending.insert(correlation_id, tx);
+                cancel_rx = self.canceller.register(correlation_id);
+                if let Some(progress) = progress {
+                    self.progress_listeners.lock().await.insert(correlation_id, progress);
+                }
             }
             WorkerMessage::ValidateTask { .. } => {
                 // For validation tasks, we need a different channel type
@@ -645,9 +824,23 @@ impl WorkerProcessManager {
             }
         }
 
-        // Wait for response with timeout
-        match timeout(timeout_duration, rx).await {
-            Ok(Ok(result)) => {
+        // Wait for response with timeout, watching the worker's memory usage
+        let waited = self
+            .wait_for_result(
+                rx,
+                cancel_rx,
+                &worker_id,
+                worker_pid,
+                correlation_id,
+                timeout_duration,
+                limits.max_heap_mb,
+            )
+            .await;
+        self.canceller.unregister(correlation_id);
+        self.progress_listeners.lock().await.remove(&correlation_id);
+
+        match waited {
+            Ok(result) => {
                 // Convert TaskExecutionResult to CoordinatorMessage
                 Ok(CoordinatorMessage::TaskResult {
                     job_id: 0, // We'll need to track this properly
@@ -655,18 +848,122 @@ impl WorkerProcessManager {
                     result,
                 })
             }
-            Ok(Err(_)) => Err(WorkerProcessError::CommunicationError(
-                "Response channel closed".to_string(),
-            )),
-            Err(_) => {
-                // Clean up pending task on timeout
-                if let Ok(mut pending) = self.pending_tasks.try_lock() {
-                    pending.remove(&correlation_id);
+            Err(
+                e @ (WorkerProcessError::Timeout
+                | WorkerProcessError::ResourceExhausted(_)
+                | WorkerProcessError::Cancelled),
+            ) => {
+                // Clean up the pending task and replace the worker, which may still be running the task
+                self.pending_tasks.lock().await.remove(&correlation_id);
+                warn!("Stopping task on worker {}: {}", worker_id, e);
+                if let Err(restart_err) = self.restart_worker(&worker_id).await {
+                    error!("Failed to restart worker {}: {}", worker_id, restart_err);
                 }
-                Err(WorkerProcessError::Timeout)
+                Err(e)
             }
+            Err(e) => Err(e),
         }
     }
+
+    /// Wait for a task result, enforcing the wall-clock and heap limits
+    ///
+    /// A cancellation request is forwarded to the worker, which then has the
+    /// configured grace period to report the cancelled result before it is killed.
+    #[allow(clippy::too_many_arguments)]
+    async fn wait_for_result(
+        &self,
+        mut rx: oneshot::Receiver<TaskExecutionResult>,
+        mut cancel_rx: Option<oneshot::Receiver<()>>,
+        worker_id: &str,
+        worker_pid: Option<u32>,
+        correlation_id: Uuid,
+        timeout_duration: Duration,
+        max_heap_mb: Option<u64>,
+    ) -> Result<TaskExecutionResult, WorkerProcessError> {
+        let deadline = tokio::time::sleep(timeout_duration);
+        tokio::pin!(deadline);
+
+        let mut memory_poll = tokio::time::interval(MEMORY_POLL_INTERVAL);
+        let mut cancelled = false;
+
+        loop {
+            tokio::select! {
+                result = &mut rx => {
+                    return result.map_err(|_| {
+                        WorkerProcessError::CommunicationError("Response channel closed".to_string())
+                    });
+                }
+                requested = async { cancel_rx.as_mut().expect("guarded by is_some").await }, if cancel_rx.is_some() => {
+                    cancel_rx = None;
+                    if requested.is_ok() {
+                        info!("Cancelling task {} on worker {}", correlation_id, worker_id);
+                        cancelled = true;
+                        self.send_to_worker(worker_id, WorkerMessage::CancelTask { correlation_id }).await;
+                        let grace = Duration::from_secs(self.config.cancel_grace_period_seconds);
+                        deadline.as_mut().reset(tokio::time::Instant::now() + grace);
+                    }
+                }
+                _ = &mut deadline => {
+                    return Err(if cancelled {
+                        WorkerProcessError::Cancelled
+                    } else {
+                        WorkerProcessError::Timeout
+                    });
+                }
+                _ = memory_poll.tick(), if max_heap_mb.is_some() => {
+                    let used = worker_pid.and_then(process_memory_mb);
+                    if let (Some(used), Some(limit)) = (used, max_heap_mb) {
+                        if used > limit {
+                            return Err(WorkerProcessError::ResourceExhausted(format!(
+                                "worker memory usage {} MB exceeds limit of {} MB",
+                                used, limit
+                            )));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Send a message to a specific worker, logging failures
+    async fn send_to_worker(&self, worker_id: &str, message: WorkerMessage) {
+        let mut workers = self.workers.lock().await;
+        match workers.iter_mut().find(|w| w.id == worker_id) {
+            Some(worker) => {
+                if let Err(e) = worker.send_message(message).await {
+                    warn!("Failed to send message to worker {}: {}", worker_id, e);
+                }
+            }
+            None => warn!("Worker {} not found", worker_id),
+        }
+    }
+
+    /// Kill a worker and, if restarts are enabled, replace it with a fresh process
+    pub async fn restart_worker(&mut self, worker_id: &str) -> Result<(), WorkerProcessError> {
+        let mut workers = self.workers.lock().await;
+        let index = workers
+            .iter()
+            .position(|w| w.id == worker_id)
+            .ok_or_else(|| WorkerProcessError::CommunicationError(format!("Worker {} not found", worker_id)))?;
+
+        let mut old = workers.remove(index);
+        old.kill().await?;
+
+        if !self.config.restart_on_crash || old.restart_count >= self.config.max_restart_attempts {
+            warn!(
+                "Not restarting worker {} after {} restart(s)",
+                worker_id, old.restart_count
+            );
+            workers.push(old);
+            return Ok(());
+        }
+
+        info!("Restarting worker {}", worker_id);
+        let mut replacement = WorkerProcess::spawn(worker_id.to_string(), &self.config, self.message_tx.clone()).await?;
+        replacement.restart_count = old.restart_count + 1;
+        workers.push(replacement);
+        Ok(())
+    }
 }
 
 /// Worker process errors
@@ -684,6 +981,12 @@ pub enum WorkerProcessError {
     #[error("Worker timeout")]
     Timeout,
 
+    #[error("Worker exceeded a resource limit: {0}")]
+    ResourceExhausted(String),
+
+    #[error("Task cancelled")]
+    Cancelled,
+
     #[error("Worker process crashed")]
     WorkerCrashed,
 
@@ -719,6 +1022,26 @@ mod tests {
         assert_eq!(worker.id, "test-worker");
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_process_memory_mb() {
+        assert!(process_memory_mb(std::process::id()).is_some());
+        assert!(process_memory_mb(u32::MAX).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_task_canceller() {
+        let canceller = TaskCanceller::default();
+        let correlation_id = Uuid::new_v4();
+
+        assert!(!canceller.cancel(correlation_id));
+
+        let rx = canceller.register(correlation_id).unwrap();
+        assert!(canceller.cancel(correlation_id));
+        assert!(rx.await.is_ok());
+        assert!(!canceller.cancel(correlation_id));
+    }
+
     #[tokio::test]
     async fn test_worker_manager_creation() {
         let config = WorkerConfig::default();
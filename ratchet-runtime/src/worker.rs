@@ -3,21 +3,28 @@
 //! This module provides the worker process implementation that executes tasks
 //! in isolated processes for thread safety and fault tolerance.
 
-use log::{debug, error, info};
-use std::collections::HashMap;
+use log::{debug, error, info, warn};
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc, RwLock};
 use serde_json::Value as JsonValue;
 
 use ratchet_core::{
+    error::ExecutionError,
     task::{Task, TaskBuilder},
     RatchetError,
 };
 use ratchet_ipc::{
-    CoordinatorMessage, IpcTransport, MessageEnvelope, StdioTransport, TaskExecutionResult, TaskValidationResult,
-    WorkerError, WorkerMessage, WorkerStatus,
+    CoordinatorMessage, IpcTransport, MessageEnvelope, StdioTransport, TaskExecutionResult, TaskLogEntry,
+    TaskProgressUpdate, TaskValidationResult, WorkerError, WorkerMessage, WorkerStatus,
 };
-use ratchet_js::{JsTask, JsTaskRunner, ExecutionContext as JsExecutionContext};
+use ratchet_js::{
+    js_task::JsTaskError, ConsoleCapture, EgressPolicy, ExecutionContext as JsExecutionContext, ExecutionInterrupt,
+    HttpClientPolicy, HttpClientState, HttpTransport, HttpTransportConfig, JsExecutionError, JsTask, JsTaskRunner,
+    ProgressReporter, ResourceLimits, TaskProgress, TaskSecrets,
+};
+
+use crate::process::{WORKER_EGRESS_ENV, WORKER_HTTP_ENV, WORKER_LIMITS_ENV, WORKER_TRANSPORT_ENV};
 
 /// Worker process main entry point
 pub async fn worker_main(worker_id: String) -> Result<(), WorkerError> {
@@ -36,12 +43,119 @@ pub async fn worker_main(worker_id: String) -> Result<(), WorkerError> {
     Ok(())
 }
 
+/// Read the resource limits the coordinator passed through the environment
+fn limits_from_env() -> ResourceLimits {
+    match std::env::var(WORKER_LIMITS_ENV) {
+        Ok(value) => serde_json::from_str(&value).unwrap_or_else(|e| {
+            warn!("Ignoring invalid {}: {}", WORKER_LIMITS_ENV, e);
+            ResourceLimits::default()
+        }),
+        Err(_) => ResourceLimits::default(),
+    }
+}
+
+/// Read the egress policy the coordinator passed through the environment
+fn egress_from_env() -> EgressPolicy {
+    match std::env::var(WORKER_EGRESS_ENV) {
+        Ok(value) => serde_json::from_str(&value).unwrap_or_else(|e| {
+            warn!("Ignoring invalid {}: {}", WORKER_EGRESS_ENV, e);
+            EgressPolicy::default()
+        }),
+        Err(_) => EgressPolicy::default(),
+    }
+}
+
+/// Read the HTTP client policy the coordinator passed through the environment
+fn http_policy_from_env() -> HttpClientPolicy {
+    match std::env::var(WORKER_HTTP_ENV) {
+        Ok(value) => serde_json::from_str(&value).unwrap_or_else(|e| {
+            warn!("Ignoring invalid {}: {}", WORKER_HTTP_ENV, e);
+            HttpClientPolicy::default()
+        }),
+        Err(_) => HttpClientPolicy::default(),
+    }
+}
+
+/// Read the proxy and certificate settings the coordinator passed through the environment
+///
+/// Workers cannot read the secret store, so client certificates configured by
+/// secret name are left out.
+fn http_transport_from_env() -> Option<HttpTransport> {
+    let mut config: HttpTransportConfig = match std::env::var(WORKER_TRANSPORT_ENV) {
+        Ok(value) => match serde_json::from_str(&value) {
+            Ok(config) => config,
+            Err(e) => {
+                warn!("Ignoring invalid {}: {}", WORKER_TRANSPORT_ENV, e);
+                return None;
+            }
+        },
+        Err(_) => return None,
+    };
+    config.tls.client_identities.retain(|identity| match &identity.secret {
+        Some(secret) => {
+            warn!("Client certificate secret '{}' is not available to workers", secret);
+            false
+        }
+        None => true,
+    });
+    HttpTransport::new(&config, &HashMap::new())
+        .map_err(|e| warn!("Ignoring invalid {}: {}", WORKER_TRANSPORT_ENV, e))
+        .ok()
+}
+
+type ReceivedMessage = Result<MessageEnvelope<WorkerMessage>, WorkerError>;
+
+/// Convert progress reported by a script into its IPC representation
+fn progress_update(progress: TaskProgress) -> TaskProgressUpdate {
+    TaskProgressUpdate {
+        fraction: progress.fraction,
+        message: progress.message,
+        partial_output: progress.partial_output,
+    }
+}
+
+/// Convert captured console output into its IPC representation
+fn task_log_entries(console: &ConsoleCapture) -> Vec<TaskLogEntry> {
+    console
+        .take()
+        .into_iter()
+        .map(|entry| TaskLogEntry {
+            timestamp: entry.timestamp,
+            level: entry.level.as_str().to_string(),
+            message: entry.message,
+        })
+        .collect()
+}
+
+/// Read coordinator messages on a separate task so they can arrive while a task runs
+fn spawn_message_reader() -> mpsc::UnboundedReceiver<ReceivedMessage> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        let mut transport = StdioTransport::new();
+        loop {
+            let received = transport
+                .receive()
+                .await
+                .map_err(|e| WorkerError::CommunicationError { error: e.to_string() });
+            let failed = received.is_err();
+            if tx.send(received).is_err() || failed {
+                break;
+            }
+        }
+    });
+    rx
+}
+
 /// Worker process implementation
 pub struct Worker {
     worker_id: String,
     transport: StdioTransport,
     status: Arc<RwLock<WorkerStatus>>,
     task_cache: HashMap<String, Task>,
+    limits: ResourceLimits,
+    egress: EgressPolicy,
+    /// Response cache and circuit breakers shared by the tasks this worker runs
+    http_client: HttpClientState,
 }
 
 impl Worker {
@@ -63,6 +177,12 @@ impl Worker {
             transport: StdioTransport::new(),
             status,
             task_cache: HashMap::new(),
+            limits: limits_from_env(),
+            egress: egress_from_env(),
+            http_client: match http_transport_from_env() {
+                Some(transport) => HttpClientState::new(http_policy_from_env()).with_transport(transport),
+                None => HttpClientState::new(http_policy_from_env()),
+            },
         })
     }
 
@@ -77,12 +197,54 @@ impl Worker {
 
     /// Main worker loop
     pub async fn run(&mut self) -> Result<(), WorkerError> {
+        let mut inbox = spawn_message_reader();
+        let mut deferred = VecDeque::new();
+
         loop {
-            match self.receive_message().await {
+            let received = match deferred.pop_front() {
+                Some(message) => Ok(MessageEnvelope::new(message)),
+                None => inbox.recv().await.unwrap_or_else(|| {
+                    Err(WorkerError::CommunicationError {
+                        error: "Coordinator connection closed".to_string(),
+                    })
+                }),
+            };
+
+            match received {
                 Ok(envelope) => {
                     self.update_last_activity().await;
 
-                    match self.handle_message(envelope.message).await {
+                    let handled = match envelope.message {
+                        WorkerMessage::ExecuteTask {
+                            job_id,
+                            task_id,
+                            task_path,
+                            input_data,
+                            execution_context,
+                            correlation_id,
+                        } => {
+                            let result = self
+                                .execute_cancellable(
+                                    job_id,
+                                    task_id,
+                                    &task_path,
+                                    input_data,
+                                    execution_context,
+                                    correlation_id,
+                                    &mut inbox,
+                                    &mut deferred,
+                                )
+                                .await;
+                            Ok(Some(CoordinatorMessage::TaskResult {
+                                job_id,
+                                correlation_id,
+                                result,
+                            }))
+                        }
+                        message => self.handle_message(message).await,
+                    };
+
+                    match handled {
                         Ok(Some(response)) => {
                             if let Err(e) = self.send_message(response).await {
                                 error!("Failed to send response: {}", e);
@@ -111,6 +273,86 @@ impl Worker {
         Ok(())
     }
 
+    /// Execute a task while listening for a matching `CancelTask`
+    ///
+    /// Progress reported by the task is forwarded to the coordinator as it
+    /// arrives. Other messages that arrive during execution are queued in
+    /// `deferred` and handled once the task has finished.
+    #[allow(clippy::too_many_arguments)]
+    async fn execute_cancellable(
+        &mut self,
+        job_id: i32,
+        task_id: i32,
+        task_path: &str,
+        input_data: JsonValue,
+        execution_context: ratchet_ipc::ExecutionContext,
+        correlation_id: uuid::Uuid,
+        inbox: &mut mpsc::UnboundedReceiver<ReceivedMessage>,
+        deferred: &mut VecDeque<WorkerMessage>,
+    ) -> TaskExecutionResult {
+        let interrupt = ExecutionInterrupt::new();
+        let (reporter, mut progress_rx) = ProgressReporter::channel();
+        let execution_id = execution_context.execution_id.clone();
+        // The worker's own transport is borrowed by the running task
+        let mut progress_transport = StdioTransport::new();
+        let progress_envelope = |progress: TaskProgress| {
+            let message = CoordinatorMessage::Progress {
+                correlation_id,
+                execution_id: execution_id.clone(),
+                progress: progress_update(progress),
+            };
+            MessageEnvelope::new(message)
+        };
+
+        let execution = self.execute_task_impl(
+            job_id,
+            task_id,
+            task_path,
+            input_data,
+            execution_context,
+            interrupt.clone(),
+            reporter,
+        );
+        tokio::pin!(execution);
+
+        let mut inbox_open = true;
+        loop {
+            tokio::select! {
+                result = &mut execution => {
+                    // Flush updates reported just before the task finished
+                    while let Ok(progress) = progress_rx.try_recv() {
+                        if let Err(e) = progress_transport.send(&progress_envelope(progress)).await {
+                            warn!("Failed to send progress for task {}: {}", correlation_id, e);
+                        }
+                    }
+                    return result;
+                }
+                Some(progress) = progress_rx.recv() => {
+                    if let Err(e) = progress_transport.send(&progress_envelope(progress)).await {
+                        warn!("Failed to send progress for task {}: {}", correlation_id, e);
+                    }
+                }
+                received = inbox.recv(), if inbox_open => match received {
+                    Some(Ok(envelope)) => match envelope.message {
+                        WorkerMessage::CancelTask { correlation_id: id } if id == correlation_id => {
+                            info!("Cancelling task {}", correlation_id);
+                            interrupt.interrupt();
+                        }
+                        WorkerMessage::CancelTask { correlation_id: id } => {
+                            debug!("Ignoring cancellation for task {} which is not running", id);
+                        }
+                        message => deferred.push_back(message),
+                    },
+                    Some(Err(e)) => {
+                        error!("Failed to receive message: {}", e);
+                        inbox_open = false;
+                    }
+                    None => inbox_open = false,
+                },
+            }
+        }
+    }
+
     /// Handle incoming messages
     async fn handle_message(&mut self, message: WorkerMessage) -> Result<Option<CoordinatorMessage>, WorkerError> {
         match message {
@@ -123,7 +365,15 @@ impl Worker {
                 correlation_id,
             } => {
                 let result = self
-                    .execute_task_impl(job_id, task_id, &task_path, input_data, execution_context)
+                    .execute_task_impl(
+                        job_id,
+                        task_id,
+                        &task_path,
+                        input_data,
+                        execution_context,
+                        ExecutionInterrupt::new(),
+                        ProgressReporter::channel().0,
+                    )
                     .await;
                 Ok(Some(CoordinatorMessage::TaskResult {
                     job_id,
@@ -132,6 +382,11 @@ impl Worker {
                 }))
             }
 
+            WorkerMessage::CancelTask { correlation_id } => {
+                debug!("Ignoring cancellation for task {} which is not running", correlation_id);
+                Ok(None)
+            }
+
             WorkerMessage::ValidateTask {
                 task_path,
                 correlation_id,
@@ -163,13 +418,31 @@ impl Worker {
         _task_id: i32,
         task_path: &str,
         input_data: serde_json::Value,
-        _execution_context: ratchet_ipc::ExecutionContext,
+        execution_context: ratchet_ipc::ExecutionContext,
+        interrupt: ExecutionInterrupt,
+        progress: ProgressReporter,
     ) -> TaskExecutionResult {
         let started_at = chrono::Utc::now();
+        let console = ConsoleCapture::new();
+        let secrets = TaskSecrets::new(execution_context.secrets);
 
         debug!("Executing JavaScript task at path: {}", task_path);
 
-        match self.execute_javascript_task(task_path, input_data.clone(), job_id).await {
+        match self
+            .execute_javascript_task(
+                task_path,
+                input_data.clone(),
+                job_id,
+                interrupt,
+                progress,
+                console.clone(),
+                secrets.clone(),
+                execution_context.egress_policy,
+                execution_context.http_policy,
+                execution_context.limits,
+            )
+            .await
+        {
             Ok(output) => {
                 let completed_at = chrono::Utc::now();
                 let duration_ms = (completed_at - started_at).num_milliseconds() as i32;
@@ -184,6 +457,7 @@ impl Worker {
                     started_at,
                     completed_at,
                     duration_ms,
+                    logs: task_log_entries(&console),
                 }
             }
             Err(e) => {
@@ -192,21 +466,31 @@ impl Worker {
 
                 self.increment_failed_tasks().await;
 
-                error!("Task execution failed: {}", e);
+                let message = secrets.redact(&e.to_string());
+                error!("Task execution failed: {}", message);
+
+                let error_type = match &e {
+                    RatchetError::Execution(ExecutionError::ResourceExhausted(_)) => "resource_exhausted",
+                    RatchetError::Execution(ExecutionError::Cancelled) => "cancelled",
+                    RatchetError::Execution(ExecutionError::Timeout(_)) => "timeout",
+                    _ => "javascript_execution_error",
+                };
 
                 TaskExecutionResult {
                     success: false,
                     output: None,
-                    error_message: Some(format!("JavaScript execution failed: {}", e)),
+                    error_message: Some(format!("JavaScript execution failed: {}", message)),
                     error_details: Some(serde_json::json!({
                         "job_id": job_id,
                         "task_path": task_path,
-                        "error_type": "javascript_execution_error",
-                        "error": e.to_string()
+                        "error_type": error_type,
+                        "error_code": e.error_code(),
+                        "error": message
                     })),
                     started_at,
                     completed_at,
                     duration_ms,
+                    logs: task_log_entries(&console),
                 }
             }
         }
@@ -238,18 +522,54 @@ impl Worker {
 
     /// Execute a JavaScript task using the ratchet-js engine
     /// This runs in a separate thread to avoid Send issues with Boa engine
+    #[allow(clippy::too_many_arguments)]
     async fn execute_javascript_task(
         &mut self,
         task_path: &str,
         input_data: JsonValue,
         job_id: i32,
+        interrupt: ExecutionInterrupt,
+        progress: ProgressReporter,
+        console: ConsoleCapture,
+        secrets: TaskSecrets,
+        task_egress: Option<JsonValue>,
+        task_http: Option<JsonValue>,
+        task_limits: Option<JsonValue>,
     ) -> Result<JsonValue, RatchetError> {
         // Resolve the actual task content from the task path
         let (js_task, js_context) = self.resolve_task_content(task_path, job_id).await?;
 
+        let limits = match task_limits {
+            Some(task_limits) => serde_json::from_value::<ResourceLimits>(task_limits)
+                .map(|task_limits| self.limits.with_overrides(&task_limits))
+                .map_err(|e| RatchetError::ExecutionError(format!("Invalid resource limits: {}", e)))?,
+            None => self.limits.clone(),
+        };
+        let egress = self.egress.clone();
+        let http_client = self.http_client.clone();
+        let task_egress = task_egress
+            .map(serde_json::from_value::<EgressPolicy>)
+            .transpose()
+            .map_err(|e| RatchetError::ExecutionError(format!("Invalid egress policy: {}", e)))?;
+
         // Execute JavaScript in a blocking thread since Boa is not Send-safe
         let result = tokio::task::spawn_blocking(move || {
-            let runner = JsTaskRunner::new();
+            let runner = JsTaskRunner::new()
+                .with_limits(limits)
+                .with_interrupt(interrupt)
+                .with_progress(progress)
+                .with_console(console)
+                .with_secrets(secrets)
+                .with_egress_policy(egress)
+                .with_http_client(http_client);
+            let runner = match task_egress {
+                Some(policy) => runner.with_task_egress_policy(policy),
+                None => runner,
+            };
+            let runner = match task_http {
+                Some(policy) => runner.with_task_http_policy(policy),
+                None => runner,
+            };
             // Use block_on to handle the async execution within the blocking context
             tokio::runtime::Handle::current().block_on(async move {
                 runner.execute_task(&js_task, input_data, Some(js_context)).await
@@ -257,7 +577,16 @@ impl Worker {
         })
         .await
         .map_err(|e| RatchetError::ExecutionError(format!("Task execution thread failed: {}", e)))?
-        .map_err(|e| RatchetError::ExecutionError(format!("JavaScript execution failed: {}", e)))?;
+        .map_err(|e| match e {
+            JsTaskError::JsExecutionError(JsExecutionError::ResourceExhausted(message)) => {
+                RatchetError::Execution(ExecutionError::ResourceExhausted(message))
+            }
+            JsTaskError::JsExecutionError(JsExecutionError::Cancelled) => RatchetError::Execution(ExecutionError::Cancelled),
+            JsTaskError::JsExecutionError(JsExecutionError::Timeout(seconds)) => {
+                RatchetError::Execution(ExecutionError::Timeout(seconds))
+            }
+            e => RatchetError::ExecutionError(format!("JavaScript execution failed: {}", e)),
+        })?;
 
         Ok(result)
     }
@@ -409,14 +738,6 @@ impl Worker {
             .map_err(|e| WorkerError::CommunicationError { error: e.to_string() })
     }
 
-    /// Receive a message from the coordinator
-    async fn receive_message(&mut self) -> Result<MessageEnvelope<WorkerMessage>, WorkerError> {
-        self.transport
-            .receive()
-            .await
-            .map_err(|e| WorkerError::CommunicationError { error: e.to_string() })
-    }
-
     /// Update last activity timestamp
     async fn update_last_activity(&self) {
         let mut status = self.status.write().await;
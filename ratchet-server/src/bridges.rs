@@ -29,7 +29,10 @@ pub struct BridgeTaskRegistry {
 use ratchet_registry::RegistryService;
 
 impl BridgeTaskRegistry {
-    pub async fn new(_config: &crate::config::ServerConfig) -> anyhow::Result<Self> {
+    pub async fn new(
+        config: &crate::config::ServerConfig,
+        http_transport: ratchet_http::HttpTransport,
+    ) -> anyhow::Result<Self> {
         // Create a Git source pointing to the default repository
         let git_source = ratchet_registry::TaskSource::Git {
             url: "https://github.com/ratchet-runner/ratchet-repo-samples.git".to_string(),
@@ -57,9 +60,17 @@ impl BridgeTaskRegistry {
             enable_auto_sync: false,
             enable_validation: true,
             cache_config: ratchet_registry::config::CacheConfig::default(),
+            trust: config.registry.trust.clone(),
         };
 
-        let service = Arc::new(ratchet_registry::DefaultRegistryService::new(registry_config));
+        // HTTP sources are fetched through the configured proxies and certificates
+        let http_client = ratchet_http::HttpManager::new().with_transport(http_transport);
+        let trust = ratchet_registry::TrustPolicies::from_config(&config.registry.trust)?;
+        let service = Arc::new(
+            ratchet_registry::DefaultRegistryService::new(registry_config)
+                .with_http_client(Arc::new(http_client))
+                .with_trust(trust),
+        );
         let embedded_registry = EmbeddedTaskRegistry::new();
 
         // Load embedded tasks first
@@ -93,7 +104,25 @@ impl BridgeTaskRegistry {
 
                     // Try to load the full task definition and add it to the registry
                     match service.load_task(&discovered.task_ref).await {
-                        Ok(task_def) => {
+                        Ok(mut task_def) => {
+                            if task_def.metadata.repository.is_none() {
+                                task_def.metadata.repository = discovered.metadata.repository.clone();
+                            }
+                            // Unsigned or unverified tasks stay out of the registry under quarantine or require
+                            match service.trust().admits(&task_def.metadata) {
+                                Ok(true) => {}
+                                Ok(false) => {
+                                    tracing::warn!(
+                                        "Task {} is quarantined, not registering it",
+                                        discovered.metadata.name
+                                    );
+                                    continue;
+                                }
+                                Err(e) => {
+                                    tracing::warn!("Not registering task {}: {}", discovered.metadata.name, e);
+                                    continue;
+                                }
+                            }
                             if let Err(e) = registry.add_task(task_def.clone()).await {
                                 tracing::warn!("Failed to add task {} to registry: {}", discovered.metadata.name, e);
                             } else {
@@ -231,8 +260,11 @@ pub struct BridgeRegistryManager {
 }
 
 impl BridgeRegistryManager {
-    pub async fn new(config: &crate::config::ServerConfig) -> anyhow::Result<Self> {
-        let primary_registry = Arc::new(BridgeTaskRegistry::new(config).await?);
+    pub async fn new(
+        config: &crate::config::ServerConfig,
+        http_transport: ratchet_http::HttpTransport,
+    ) -> anyhow::Result<Self> {
+        let primary_registry = Arc::new(BridgeTaskRegistry::new(config, http_transport).await?);
         Ok(Self {
             registries: vec![primary_registry],
         })
@@ -408,6 +440,14 @@ fn convert_registry_error(err: ratchet_registry::RegistryError) -> ratchet_inter
         ratchet_registry::RegistryError::GitError(msg) => {
             ratchet_interfaces::RegistryError::Unavailable { message: msg }
         }
+        err @ ratchet_registry::RegistryError::ChecksumMismatch { .. } => {
+            ratchet_interfaces::RegistryError::InvalidFormat {
+                message: err.to_string(),
+            }
+        }
+        ratchet_registry::RegistryError::Signature(msg) => {
+            ratchet_interfaces::RegistryError::InvalidFormat { message: msg }
+        }
     }
 }
 
@@ -455,6 +495,8 @@ fn convert_task_definition_to_unified(task_def: &ratchet_registry::TaskDefinitio
         sync_status: "synced".to_string(),
         needs_push: false,
         last_synced_at: Some(chrono::Utc::now()),
+        signature_status: None,
+        signed_by: None,
         input_schema: task_def.input_schema.clone(),
         output_schema: task_def.output_schema.clone(),
         metadata: Some(serde_json::json!({
@@ -513,6 +555,8 @@ async fn load_embedded_task_into_registry(
         created_at: chrono::Utc::now(),
         updated_at: chrono::Utc::now(),
         checksum: None,
+        signature: None,
+        repository: None,
     };
 
     // Create task reference for embedded tasks
@@ -0,0 +1,137 @@
+//! Coordination of several servers sharing one database
+//!
+//! Every server has an instance ID. Jobs are claimed under it with a lease the
+//! job processor keeps renewing, and roles only one server may hold, such as
+//! firing cron schedules, go to whichever server holds the matching leader lease.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use ratchet_storage::seaorm::repositories::LeaseRepository;
+
+/// Lease name of the cron scheduler
+pub const SCHEDULER_LEASE: &str = "scheduler";
+
+/// Lease name of the repository sync scheduler
+pub const REPOSITORY_SYNC_LEASE: &str = "repository-sync";
+
+/// Generate an instance ID for a server that was not given one
+pub fn generate_instance_id() -> String {
+    let suffix = uuid::Uuid::new_v4().simple().to_string();
+    format!("ratchet-{}-{}", std::process::id(), &suffix[..8])
+}
+
+/// A role held by at most one server at a time
+///
+/// Call [`renew`](Self::renew) at least every [`renew_interval`](Self::renew_interval);
+/// the server keeps the role for as long as it does. When the server cannot
+/// reach the database it steps down as soon as its lease may have expired, so it
+/// never acts alongside a new leader.
+pub struct LeaderLease {
+    name: String,
+    owner: String,
+    ttl: Duration,
+    repository: LeaseRepository,
+    held_until: Mutex<Option<Instant>>,
+}
+
+impl LeaderLease {
+    /// Create a lease `name` that `owner` takes for `ttl` at a time
+    pub fn new(name: impl Into<String>, owner: impl Into<String>, ttl: Duration, repository: LeaseRepository) -> Self {
+        Self {
+            name: name.into(),
+            owner: owner.into(),
+            ttl,
+            repository,
+            held_until: Mutex::new(None),
+        }
+    }
+
+    /// Name of the role
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Instance ID of this server
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    /// How often to renew the lease
+    pub fn renew_interval(&self) -> Duration {
+        self.ttl / 3
+    }
+
+    /// Take or renew the lease; returns true when this server just became leader
+    pub async fn renew(&self) -> bool {
+        // The local deadline starts before the query so it never outlasts the stored one
+        let started = Instant::now();
+        let was_leader = self.is_leader();
+        let held = match self.repository.try_acquire(&self.name, &self.owner, self.ttl).await {
+            Ok(held) => held,
+            Err(e) => {
+                warn!("Failed to renew the {} lease: {}", self.name, e);
+                return false;
+            }
+        };
+
+        *self.held_until.lock().unwrap() = held.then(|| started + self.ttl);
+        if held && !was_leader {
+            info!("Instance {} is now the {} leader", self.owner, self.name);
+        } else if !held && was_leader {
+            info!("Instance {} is no longer the {} leader", self.owner, self.name);
+        }
+        held && !was_leader
+    }
+
+    /// Whether this server holds the role right now
+    pub fn is_leader(&self) -> bool {
+        self.held_until
+            .lock()
+            .unwrap()
+            .is_some_and(|until| Instant::now() < until)
+    }
+
+    /// Give up the role so another server can take it without waiting for the lease to expire
+    pub async fn release(&self) {
+        *self.held_until.lock().unwrap() = None;
+        if let Err(e) = self.repository.release(&self.name, &self.owner).await {
+            warn!("Failed to release the {} lease: {}", self.name, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratchet_storage::seaorm::{config::DatabaseConfig, connection::DatabaseConnection};
+
+    #[tokio::test]
+    async fn test_only_one_server_leads() {
+        let config = DatabaseConfig {
+            url: "sqlite::memory:".to_string(),
+            max_connections: 1,
+            connection_timeout: Duration::from_secs(10),
+        };
+        let db = DatabaseConnection::new(config).await.unwrap();
+        db.migrate().await.unwrap();
+
+        let ttl = Duration::from_millis(300);
+        let a = LeaderLease::new(SCHEDULER_LEASE, "a", ttl, LeaseRepository::new(db.clone()));
+        let b = LeaderLease::new(SCHEDULER_LEASE, "b", ttl, LeaseRepository::new(db));
+
+        assert!(a.renew().await);
+        assert!(!b.renew().await);
+        assert!(a.is_leader() && !b.is_leader());
+
+        // Renewing keeps the role without reporting a new leadership
+        assert!(!a.renew().await);
+        assert!(a.is_leader());
+
+        a.release().await;
+        assert!(!a.is_leader());
+        assert!(b.renew().await);
+        assert!(b.is_leader());
+    }
+}
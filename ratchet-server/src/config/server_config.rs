@@ -14,6 +14,26 @@ pub struct ServerConfig {
     pub database: DatabaseConfig,
     pub registry: RegistryConfig,
     pub heartbeat: HeartbeatConfig,
+    #[serde(default)]
+    pub jobs: JobsConfig,
+    #[serde(default)]
+    pub cluster: ClusterConfig,
+    #[serde(default)]
+    pub result_cache: ResultCacheConfig,
+    #[serde(default)]
+    pub deliveries: DeliveryConfig,
+    /// Where task `fetch` calls may connect
+    #[serde(default)]
+    pub egress: ratchet_config::domains::http::EgressPolicy,
+    /// Response caching, retries and circuit breaking of task `fetch` calls
+    #[serde(default)]
+    pub http_client: ratchet_config::domains::http::HttpClientPolicy,
+    /// Proxies and certificates of task `fetch` calls, registry sources and webhooks
+    #[serde(default)]
+    pub http_transport: ratchet_config::domains::http::HttpTransportConfig,
+    /// Resource limits of every task execution, which tasks can tighten or relax in their metadata
+    #[serde(default)]
+    pub execution_limits: ratchet_config::domains::execution::ResourceLimits,
 }
 
 /// HTTP server configuration
@@ -111,6 +131,9 @@ pub struct RegistryConfig {
     pub sync_interval_seconds: u64,
     pub enable_auto_sync: bool,
     pub enable_validation: bool,
+    /// Keys trusted to sign each repository's tasks; tasks that fail the policy are not registered
+    #[serde(default)]
+    pub trust: Vec<ratchet_registry::config::TrustConfig>,
 }
 
 /// Heartbeat configuration
@@ -121,6 +144,63 @@ pub struct HeartbeatConfig {
     pub output_destinations: Vec<String>,
 }
 
+/// Job queue configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobsConfig {
+    /// Seconds an idempotency key keeps returning the job it was first used for
+    pub idempotency_retention_seconds: u64,
+}
+
+/// Settings for running several servers against one database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterConfig {
+    /// ID this server claims jobs and leader leases under; generated at startup when unset
+    pub instance_id: Option<String>,
+    /// Seconds a claimed job or leader role stays with a server that stops renewing it
+    pub lease_ttl_seconds: u64,
+}
+
+/// Memoization of the results of tasks that opt into it through their metadata
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultCacheConfig {
+    pub enabled: bool,
+    /// Maximum number of cached results across all tasks
+    pub max_entries: usize,
+    /// Longest a result is cached, whatever TTL its task asks for
+    pub max_ttl_seconds: u64,
+}
+
+/// Retries of job output deliveries that fail
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryConfig {
+    /// Attempts per destination, the first included, before a delivery is dead-lettered
+    pub max_attempts: u32,
+    /// Seconds between the first and second attempt
+    pub initial_delay_seconds: u64,
+    /// Longest wait between two attempts, in seconds
+    pub max_delay_seconds: u64,
+    /// Factor the wait grows by with every further attempt
+    pub backoff_multiplier: f64,
+    /// Seconds a server has to record a redelivery before another server may take it over
+    pub claim_timeout_seconds: u64,
+    /// Databases that database destinations may write to, by the name destinations use
+    #[serde(default)]
+    pub database_connections:
+        std::collections::HashMap<String, ratchet_config::domains::output::OutputDatabaseConnection>,
+}
+
+impl DeliveryConfig {
+    /// Wait after the failed attempt number `attempt` before the next one
+    pub fn retry_delay(&self, attempt: u32) -> std::time::Duration {
+        let factor = self
+            .backoff_multiplier
+            .max(1.0)
+            .powi(attempt.saturating_sub(1).min(64) as i32);
+        let seconds = (self.initial_delay_seconds as f64 * factor).min(self.max_delay_seconds as f64);
+        std::time::Duration::from_secs(seconds as u64)
+    }
+}
+
 impl Default for HttpServerConfig {
     fn default() -> Self {
         Self {
@@ -222,6 +302,7 @@ impl Default for RegistryConfig {
             sync_interval_seconds: 300,
             enable_auto_sync: true,
             enable_validation: true,
+            trust: Vec::new(),
         }
     }
 }
@@ -236,6 +317,75 @@ impl Default for HeartbeatConfig {
     }
 }
 
+impl Default for JobsConfig {
+    fn default() -> Self {
+        Self {
+            idempotency_retention_seconds: 24 * 60 * 60, // 24 hours
+        }
+    }
+}
+
+impl Default for ClusterConfig {
+    fn default() -> Self {
+        Self {
+            instance_id: None,
+            lease_ttl_seconds: 30,
+        }
+    }
+}
+
+impl Default for ResultCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_entries: 1000,
+            max_ttl_seconds: 30 * 60, // 30 minutes
+        }
+    }
+}
+
+impl Default for DeliveryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_delay_seconds: 30,
+            max_delay_seconds: 60 * 60, // 1 hour
+            backoff_multiplier: 2.0,
+            claim_timeout_seconds: 5 * 60, // 5 minutes
+            database_connections: Default::default(),
+        }
+    }
+}
+
+/// Signing trust of the registry sources in the unified configuration
+fn registry_trust(
+    registry: Option<&ratchet_config::RegistryConfig>,
+) -> anyhow::Result<Vec<ratchet_registry::config::TrustConfig>> {
+    use ratchet_registry::config::SignaturePolicy;
+
+    let Some(registry) = registry else {
+        return Ok(Vec::new());
+    };
+    registry
+        .trust
+        .iter()
+        .map(|trust| {
+            let policy = match trust.policy.to_ascii_lowercase().as_str() {
+                "off" => SignaturePolicy::Off,
+                "warn" => SignaturePolicy::Warn,
+                "quarantine" => SignaturePolicy::Quarantine,
+                "require" => SignaturePolicy::Require,
+                other => anyhow::bail!("Invalid signature policy '{}' for {}", other, trust.repository),
+            };
+            Ok(ratchet_registry::config::TrustConfig {
+                repository: trust.repository.clone(),
+                policy,
+                public_keys: trust.public_keys.clone(),
+            })
+        })
+        .collect()
+}
+
 impl ServerConfig {
     /// Convert from ratchet-config RatchetConfig to ServerConfig
     pub fn from_ratchet_config(config: ratchet_config::RatchetConfig) -> anyhow::Result<Self> {
@@ -317,8 +467,26 @@ impl ServerConfig {
                 sync_interval_seconds: 300,                    // Default 5 minutes
                 enable_auto_sync: true,                        // Default enabled
                 enable_validation: true,                       // Default enabled
+                trust: registry_trust(config.registry.as_ref())?,
             },
             heartbeat: HeartbeatConfig::default(),
+            jobs: JobsConfig {
+                idempotency_retention_seconds: config.execution.idempotency_retention.as_secs(),
+            },
+            cluster: ClusterConfig::default(),
+            result_cache: ResultCacheConfig {
+                enabled: config.cache.enabled && config.cache.result_cache.enabled,
+                max_entries: config.cache.result_cache.max_entries,
+                max_ttl_seconds: config.cache.result_cache.ttl.as_secs(),
+            },
+            deliveries: DeliveryConfig {
+                database_connections: config.output.security.database_connections.clone(),
+                ..DeliveryConfig::default()
+            },
+            egress: config.http.egress.clone(),
+            http_client: config.http.client_policy(),
+            http_transport: config.http.transport_config(),
+            execution_limits: config.execution.limits.clone(),
         })
     }
 }
\ No newline at end of file
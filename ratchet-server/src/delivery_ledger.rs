@@ -0,0 +1,444 @@
+//! Ledger of job output deliveries
+//!
+//! Every attempt to deliver the output of a job to one of its destinations is
+//! recorded. A failed delivery is retried with backoff until the configured
+//! number of attempts is used up, after which it is dead-lettered and only
+//! tried again on request. Retries are due in the database rather than in
+//! memory, so the job processor's sweep picks them up after a restart too.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value as JsonValue;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+use ratchet_api_types::{ApiId, OutputDeliveryStatus, UnifiedDeliveryAttempt, UnifiedOutputDestination};
+use ratchet_interfaces::{DeliveryLedgerError, OutputDeliveryStore, RepositoryFactory, SecretStore};
+use ratchet_output::OutputDeliveryManager;
+use ratchet_storage::seaorm::entities::{DeliveryResult, DeliveryStatus};
+use ratchet_storage::seaorm::repositories::DeliveryResultRepository;
+
+use crate::config::DeliveryConfig;
+use crate::job_processor::{deliver_to_destination, delivery_input};
+
+/// Delivers job outputs, records every attempt and retries the failed ones
+pub struct DeliveryLedger {
+    config: DeliveryConfig,
+    output_manager: Arc<OutputDeliveryManager>,
+    repositories: Arc<dyn RepositoryFactory>,
+    deliveries: DeliveryResultRepository,
+    secret_store: Option<Arc<dyn SecretStore>>,
+}
+
+impl DeliveryLedger {
+    /// Create a ledger that delivers through `output_manager`
+    pub fn new(
+        config: &DeliveryConfig,
+        output_manager: Arc<OutputDeliveryManager>,
+        repositories: Arc<dyn RepositoryFactory>,
+        deliveries: DeliveryResultRepository,
+    ) -> Self {
+        Self {
+            config: config.clone(),
+            output_manager,
+            repositories,
+            deliveries,
+            secret_store: None,
+        }
+    }
+
+    /// Read the credentials destinations refer to from `store`
+    pub fn with_secret_store(mut self, store: Arc<dyn SecretStore>) -> Self {
+        self.secret_store = Some(store);
+        self
+    }
+
+    /// Make the first delivery of the output of a job to each of its destinations
+    pub async fn deliver(
+        &self,
+        job_id: &ApiId,
+        execution_id: &ApiId,
+        output: JsonValue,
+        completed_at: DateTime<Utc>,
+        destinations: &[UnifiedOutputDestination],
+    ) {
+        for (index, destination) in destinations.iter().enumerate() {
+            if let Err(e) = self
+                .attempt(job_id, execution_id, &output, completed_at, index, destination, 1)
+                .await
+            {
+                error!(
+                    "Failed to record the delivery of job {} to destination {}: {}",
+                    job_id, index, e
+                );
+            }
+        }
+    }
+
+    /// Redeliver up to `limit` deliveries whose retry is due, returning how many were attempted
+    ///
+    /// Redeliveries a server claimed but never recorded, for instance because it
+    /// restarted, are due again once their claim lapses.
+    pub async fn sweep(&self, limit: u64) -> usize {
+        let claimed = match self.deliveries.claim_due(self.claim_timeout(), limit).await {
+            Ok(claimed) => claimed,
+            Err(e) => {
+                error!("Failed to claim due output deliveries: {}", e);
+                return 0;
+            }
+        };
+
+        let count = claimed.len();
+        for delivery in claimed {
+            let id = delivery.id;
+            if let Err(e) = self.redeliver_claimed(delivery).await {
+                error!("Failed to redeliver output delivery {}: {}", id, e);
+            }
+        }
+        if count > 0 {
+            info!("Retried {} output deliveries", count);
+        }
+        count
+    }
+
+    fn claim_timeout(&self) -> Duration {
+        Duration::from_secs(self.config.claim_timeout_seconds)
+    }
+
+    /// Deliver to one destination and record the outcome as attempt number `attempt`
+    #[allow(clippy::too_many_arguments)]
+    async fn attempt(
+        &self,
+        job_id: &ApiId,
+        execution_id: &ApiId,
+        output: &JsonValue,
+        completed_at: DateTime<Utc>,
+        index: usize,
+        destination: &UnifiedOutputDestination,
+        attempt: u32,
+    ) -> Result<DeliveryResult, DeliveryLedgerError> {
+        let (task_output, delivery_context) = delivery_input(job_id, execution_id, output.clone(), completed_at);
+        let name = format!("job_{}_dest_{}_attempt_{}", job_id, index, attempt);
+        let started = std::time::Instant::now();
+        let outcome = deliver_to_destination(
+            &self.output_manager,
+            self.secret_store.as_deref(),
+            name,
+            destination,
+            &task_output,
+            &delivery_context,
+        )
+        .await;
+
+        let mut record = DeliveryResult::new(
+            task_output.job_id,
+            task_output.execution_id,
+            destination.destination_type.clone(),
+            destination_id(destination),
+            outcome.is_ok(),
+            started.elapsed().as_millis() as i32,
+            0,
+            None,
+            None,
+        );
+        record.destination_index = index as i32;
+        record.attempt = attempt as i32;
+        match outcome {
+            Ok(result) => {
+                info!(
+                    "Delivered output of job {} to destination {} (attempt {})",
+                    job_id, index, attempt
+                );
+                record.delivery_time_ms = result.delivery_time.as_millis() as i32;
+                record.size_bytes = result.size_bytes as i32;
+                record.response_info = result.response_info;
+            }
+            Err(e) if attempt < self.config.max_attempts => {
+                let delay = self.config.retry_delay(attempt);
+                warn!(
+                    "Failed to deliver output of job {} to destination {} (attempt {}), retrying in {:?}: {}",
+                    job_id, index, attempt, delay, e
+                );
+                record.status = DeliveryStatus::Retrying;
+                record.next_attempt_at = Some(Utc::now() + chrono::Duration::seconds(delay.as_secs() as i64));
+                record.error_message = Some(e);
+            }
+            Err(e) => {
+                error!(
+                    "Failed to deliver output of job {} to destination {} (attempt {}), giving up: {}",
+                    job_id, index, attempt, e
+                );
+                record.error_message = Some(e);
+            }
+        }
+
+        self.deliveries.record(record).await.map_err(storage_error)
+    }
+
+    /// Deliver the output of a claimed delivery again and close the claim
+    async fn redeliver_claimed(&self, claimed: DeliveryResult) -> Result<DeliveryResult, DeliveryLedgerError> {
+        let job = self
+            .repositories
+            .job_repository()
+            .find_by_id(claimed.job_id)
+            .await
+            .map_err(storage_error)?;
+        let execution = self
+            .repositories
+            .execution_repository()
+            .find_by_id(claimed.execution_id)
+            .await
+            .map_err(storage_error)?;
+
+        let destination = job.as_ref().and_then(|job| {
+            job.output_destinations
+                .as_ref()
+                .and_then(|destinations| destinations.get(claimed.destination_index as usize))
+        });
+        let (destination, execution) = match (destination, execution) {
+            (Some(destination), Some(execution)) if execution.output.is_some() => (destination, execution),
+            _ => {
+                // Nothing left to deliver; keep the delivery for inspection
+                let reason = "the job, its destination or the execution output no longer exists".to_string();
+                warn!("Dead-lettering output delivery {}: {}", claimed.id, reason);
+                self.deliveries
+                    .dead_letter(claimed.id, reason)
+                    .await
+                    .map_err(storage_error)?;
+                return self.find(claimed.id).await;
+            }
+        };
+
+        let attempt = self
+            .attempt(
+                &ApiId::from_i32(claimed.job_id),
+                &ApiId::from_i32(claimed.execution_id),
+                execution.output.as_ref().unwrap_or(&JsonValue::Null),
+                execution.completed_at.unwrap_or_else(Utc::now),
+                claimed.destination_index as usize,
+                destination,
+                claimed.attempt as u32 + 1,
+            )
+            .await?;
+        self.deliveries.supersede(claimed.id).await.map_err(storage_error)?;
+        Ok(attempt)
+    }
+
+    async fn find(&self, id: i32) -> Result<DeliveryResult, DeliveryLedgerError> {
+        self.deliveries
+            .find_by_id(id)
+            .await
+            .map_err(storage_error)?
+            .ok_or_else(|| DeliveryLedgerError::NotFound { id: id.to_string() })
+    }
+}
+
+#[async_trait]
+impl OutputDeliveryStore for DeliveryLedger {
+    async fn failed_deliveries(
+        &self,
+        job_id: Option<ApiId>,
+        execution_id: Option<ApiId>,
+    ) -> Result<Vec<UnifiedDeliveryAttempt>, DeliveryLedgerError> {
+        // Jobs and executions without a numeric ID have no deliveries
+        let (job_id, execution_id) = match (job_id.map(|id| id.as_i32()), execution_id.map(|id| id.as_i32())) {
+            (Some(None), _) | (_, Some(None)) => return Ok(Vec::new()),
+            (job_id, execution_id) => (job_id.flatten(), execution_id.flatten()),
+        };
+
+        let deliveries = self
+            .deliveries
+            .find_failed(job_id, execution_id)
+            .await
+            .map_err(storage_error)?;
+        Ok(deliveries.into_iter().map(to_unified).collect())
+    }
+
+    async fn redeliver(&self, delivery_id: ApiId) -> Result<UnifiedDeliveryAttempt, DeliveryLedgerError> {
+        let id = delivery_id.as_i32().ok_or_else(|| DeliveryLedgerError::NotFound {
+            id: delivery_id.to_string(),
+        })?;
+        let current = self.find(id).await?;
+
+        let claimed = self
+            .deliveries
+            .claim(id, self.claim_timeout())
+            .await
+            .map_err(storage_error)?
+            .ok_or_else(|| DeliveryLedgerError::NotRedeliverable {
+                id: id.to_string(),
+                status: to_api_status(current.status),
+            })?;
+        info!("Redelivering output delivery {} on request", id);
+        self.redeliver_claimed(claimed).await.map(to_unified)
+    }
+}
+
+/// Where a destination delivers to, for the ledger
+fn destination_id(destination: &UnifiedOutputDestination) -> String {
+    let target = match destination.destination_type.as_str() {
+        "webhook" => destination.webhook.as_ref().map(|webhook| webhook.url.clone()),
+        "filesystem" => destination.filesystem.as_ref().map(|fs| fs.path.clone()),
+        "s3" => destination
+            .s3
+            .as_ref()
+            .map(|s3| format!("s3://{}/{}", s3.bucket, s3.key)),
+        "database" => destination.database.as_ref().map(|db| db.table.clone()),
+        "stdio" => destination.stdio.as_ref().map(|stdio| stdio.stream.clone()),
+        _ => None,
+    };
+    target.unwrap_or_else(|| destination.destination_type.clone())
+}
+
+fn to_unified(delivery: DeliveryResult) -> UnifiedDeliveryAttempt {
+    UnifiedDeliveryAttempt {
+        id: ApiId::from_i32(delivery.id),
+        job_id: ApiId::from_i32(delivery.job_id),
+        execution_id: ApiId::from_i32(delivery.execution_id),
+        destination_type: delivery.destination_type,
+        destination_id: delivery.destination_id,
+        destination_index: delivery.destination_index,
+        attempt: delivery.attempt,
+        status: to_api_status(delivery.status),
+        delivery_time_ms: delivery.delivery_time_ms,
+        size_bytes: delivery.size_bytes,
+        response_info: delivery.response_info,
+        error_message: delivery.error_message,
+        next_attempt_at: delivery.next_attempt_at,
+        created_at: delivery.created_at,
+    }
+}
+
+fn to_api_status(status: DeliveryStatus) -> OutputDeliveryStatus {
+    match status {
+        DeliveryStatus::Delivered => OutputDeliveryStatus::Delivered,
+        DeliveryStatus::Failed => OutputDeliveryStatus::Failed,
+        DeliveryStatus::Retrying => OutputDeliveryStatus::Retrying,
+        DeliveryStatus::Redelivering => OutputDeliveryStatus::Redelivering,
+        DeliveryStatus::DeadLetter => OutputDeliveryStatus::DeadLetter,
+    }
+}
+
+fn storage_error(error: impl std::fmt::Display) -> DeliveryLedgerError {
+    DeliveryLedgerError::Storage {
+        message: error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::DirectRepositoryFactory;
+    use ratchet_api_types::{OutputFormat, UnifiedFilesystemConfig};
+    use ratchet_storage::seaorm::entities::{Execution, Job, JobPriority};
+    use ratchet_storage::seaorm::repositories::{
+        ExecutionRepository, JobRepository, RepositoryFactory as StorageFactory, TaskRepository,
+    };
+    use ratchet_storage::seaorm::{config::DatabaseConfig, connection::DatabaseConnection};
+    use ratchet_storage::testing::TaskBuilder;
+    use serde_json::json;
+
+    /// A job that delivers its output to `destination`, and its completed execution
+    async fn create_job(db: &DatabaseConnection, destination: &UnifiedOutputDestination) -> (ApiId, ApiId) {
+        let now = Utc::now();
+        let task = TaskRepository::new(db.clone())
+            .create(TaskBuilder::new().with_name("delivering").build())
+            .await
+            .unwrap();
+
+        let mut job = Job::new(task.id, json!({}), JobPriority::Normal);
+        job.output_destinations = Some(serde_json::to_value(vec![destination]).unwrap());
+        let job = JobRepository::new(db.clone()).create(job).await.unwrap();
+
+        let mut execution = Execution::new(task.id, json!({}));
+        execution.output = Some(json!({"rows": 3}));
+        execution.completed_at = Some(now);
+        let execution = ExecutionRepository::new(db.clone()).create(execution).await.unwrap();
+        (ApiId::from_i32(job.id), ApiId::from_i32(execution.id))
+    }
+
+    #[tokio::test]
+    async fn test_failed_deliveries_are_retried_until_dead_lettered() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DatabaseConnection::new(DatabaseConfig {
+            url: format!("sqlite://{}", dir.path().join("ratchet.db").display()),
+            max_connections: 5,
+            connection_timeout: Duration::from_secs(10),
+        })
+        .await
+        .unwrap();
+        db.migrate().await.unwrap();
+
+        // A file where the output directory should be makes every delivery fail
+        let blocker = dir.path().join("outputs");
+        std::fs::write(&blocker, "").unwrap();
+        let destination = UnifiedOutputDestination {
+            destination_type: "filesystem".to_string(),
+            template: None,
+            filesystem: Some(UnifiedFilesystemConfig {
+                path: blocker.join("result.json").display().to_string(),
+                format: OutputFormat::Json,
+                compression: None,
+                permissions: None,
+            }),
+            webhook: None,
+            stdio: None,
+            s3: None,
+            database: None,
+        };
+        let (job_id, execution_id) = create_job(&db, &destination).await;
+
+        let config = DeliveryConfig {
+            max_attempts: 2,
+            initial_delay_seconds: 0,
+            ..DeliveryConfig::default()
+        };
+        let storage = Arc::new(StorageFactory::new(db));
+        let ledger = DeliveryLedger::new(
+            &config,
+            Arc::new(OutputDeliveryManager::new()),
+            Arc::new(DirectRepositoryFactory::new(storage.clone())),
+            storage.delivery_result_repository(),
+        );
+
+        ledger
+            .deliver(&job_id, &execution_id, json!({"rows": 3}), Utc::now(), &[destination])
+            .await;
+        let failed = ledger.failed_deliveries(Some(job_id.clone()), None).await.unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(
+            (failed[0].attempt, failed[0].status),
+            (1, OutputDeliveryStatus::Retrying)
+        );
+
+        // The retry is due at once and fails too, which uses up the attempts
+        assert_eq!(ledger.sweep(10).await, 1);
+        assert_eq!(ledger.sweep(10).await, 0);
+        let failed = ledger
+            .failed_deliveries(None, Some(execution_id.clone()))
+            .await
+            .unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(
+            (failed[0].attempt, failed[0].status),
+            (2, OutputDeliveryStatus::DeadLetter)
+        );
+
+        std::fs::remove_file(&blocker).unwrap();
+        let redelivered = ledger.redeliver(failed[0].id.clone()).await.unwrap();
+        assert_eq!(
+            (redelivered.attempt, redelivered.status),
+            (3, OutputDeliveryStatus::Delivered)
+        );
+        assert!(blocker.join("result.json").exists());
+        assert!(ledger.failed_deliveries(Some(job_id), None).await.unwrap().is_empty());
+        assert!(matches!(
+            ledger.redeliver(failed[0].id.clone()).await,
+            Err(DeliveryLedgerError::NotRedeliverable {
+                status: OutputDeliveryStatus::Failed,
+                ..
+            })
+        ));
+    }
+}
@@ -262,12 +262,15 @@ impl HeartbeatService {
                 line_buffered: true,
                 prefix: Some("[HEARTBEAT] ".to_string()),
             }),
+            s3: None,
+            database: None,
         };
 
         // Create the heartbeat schedule using the API types
         let heartbeat_schedule = ratchet_api_types::UnifiedSchedule {
             id: ratchet_api_types::ApiId::from_i32(0), // Will be set by database
             task_id: heartbeat_task.id,
+            workflow_id: None,
             name: HEARTBEAT_SCHEDULE_NAME.to_string(),
             description: Some("System heartbeat health monitoring".to_string()),
             cron_expression: normalized_cron,
@@ -277,6 +280,7 @@ impl HeartbeatService {
             created_at: Utc::now(),
             updated_at: Utc::now(),
             output_destinations: Some(vec![stdout_destination]),
+            policy: Default::default(),
         };
 
         let created_schedule = schedule_repo
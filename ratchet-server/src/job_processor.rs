@@ -8,11 +8,23 @@ use tokio::time::sleep;
 use tracing::{debug, error, info, warn};
 
 use chrono::Utc;
-use ratchet_api_types::{ApiId, ExecutionStatus, UnifiedExecution, UnifiedOutputDestination};
-use ratchet_interfaces::{DatabaseError, RepositoryFactory};
+use ratchet_api_types::{
+    ApiId, ExecutionStatus, JobStatus, TaskSignatureStatus, UnifiedExecution, UnifiedJob, UnifiedOutputDestination,
+    UnifiedTask,
+};
+use ratchet_caching::result_cache::CachedResult;
+use ratchet_execution::{execution_logs, ConsoleCapture, ProgressReporter, RunningExecutions, TaskServiceCaller};
+use ratchet_interfaces::{
+    DatabaseError, ExecutionLogSink, ExecutionProgress, ExecutionProgressSink, RepositoryFactory, SecretStore,
+};
 use ratchet_output::{DeliveryContext, OutputDeliveryManager, OutputDestinationConfig, TaskOutput};
+use ratchet_registry::TrustPolicies;
 use std::collections::HashMap;
 
+use crate::delivery_ledger::DeliveryLedger;
+use crate::result_cache::TaskResultCacheManager;
+use crate::workflows::WorkflowEngine;
+
 /// Configuration for the job processor service
 #[derive(Debug, Clone)]
 pub struct JobProcessorConfig {
@@ -22,6 +34,10 @@ pub struct JobProcessorConfig {
     pub batch_size: u64,
     /// Enable automatic job processing
     pub enabled: bool,
+    /// ID this server claims jobs under
+    pub instance_id: String,
+    /// Seconds a claimed job stays with this server without a lease renewal
+    pub lease_seconds: u64,
 }
 
 impl Default for JobProcessorConfig {
@@ -30,16 +46,34 @@ impl Default for JobProcessorConfig {
             poll_interval_seconds: 5,
             batch_size: 10,
             enabled: true,
+            instance_id: crate::cluster::generate_instance_id(),
+            lease_seconds: 30,
         }
     }
 }
 
 /// Job processor service that polls for queued jobs and creates executions
+///
+/// Jobs are claimed with a lease under the configured instance ID and the lease
+/// is renewed while the processor runs, so several servers can share a queue.
+/// Jobs left behind by a server that stopped renewing are reclaimed.
+///
+/// Tasks run in-process through the task caller, tracked in the registry of
+/// running executions, so cancelling an execution stops the task it runs.
 pub struct JobProcessorService {
     repositories: Arc<dyn RepositoryFactory>,
     output_manager: Arc<OutputDeliveryManager>,
     config: JobProcessorConfig,
-    is_running: AtomicBool,
+    task_caller: Option<Arc<TaskServiceCaller>>,
+    running: RunningExecutions,
+    progress_sink: Option<Arc<dyn ExecutionProgressSink>>,
+    log_sink: Option<Arc<dyn ExecutionLogSink>>,
+    workflow_engine: Option<Arc<WorkflowEngine>>,
+    result_cache: Option<Arc<TaskResultCacheManager>>,
+    delivery_ledger: Option<Arc<DeliveryLedger>>,
+    secret_store: Option<Arc<dyn SecretStore>>,
+    trust: TrustPolicies,
+    is_running: Arc<AtomicBool>,
 }
 
 impl JobProcessorService {
@@ -53,10 +87,73 @@ impl JobProcessorService {
             repositories,
             output_manager,
             config,
-            is_running: AtomicBool::new(false),
+            task_caller: None,
+            running: RunningExecutions::default(),
+            progress_sink: None,
+            log_sink: None,
+            workflow_engine: None,
+            result_cache: None,
+            delivery_ledger: None,
+            secret_store: None,
+            trust: TrustPolicies::default(),
+            is_running: Arc::new(AtomicBool::new(false)),
         }
     }
 
+    /// Run the tasks of jobs through `caller`; jobs fail while there is none
+    pub fn with_task_caller(mut self, caller: Arc<TaskServiceCaller>) -> Self {
+        self.task_caller = Some(caller);
+        self
+    }
+
+    /// Track running jobs in `running`, so cancelling their executions through it stops them
+    pub fn with_running_executions(mut self, running: RunningExecutions) -> Self {
+        self.running = running;
+        self
+    }
+
+    /// Persist and broadcast the progress reported by the tasks of jobs through `sink`
+    pub fn with_progress_sink(mut self, sink: Arc<dyn ExecutionProgressSink>) -> Self {
+        self.progress_sink = Some(sink);
+        self
+    }
+
+    /// Store the console output of the tasks of jobs through `sink`
+    pub fn with_log_sink(mut self, sink: Arc<dyn ExecutionLogSink>) -> Self {
+        self.log_sink = Some(sink);
+        self
+    }
+
+    /// Also pick up queued workflow runs and execute them with `engine`
+    pub fn with_workflow_engine(mut self, engine: Arc<WorkflowEngine>) -> Self {
+        self.workflow_engine = Some(engine);
+        self
+    }
+
+    /// Serve jobs of tasks that cache their results from `cache` when it has a fresh result
+    pub fn with_result_cache(mut self, cache: Arc<TaskResultCacheManager>) -> Self {
+        self.result_cache = Some(cache);
+        self
+    }
+
+    /// Record output deliveries in `ledger`, and retry the failed ones with every batch
+    pub fn with_delivery_ledger(mut self, ledger: Arc<DeliveryLedger>) -> Self {
+        self.delivery_ledger = Some(ledger);
+        self
+    }
+
+    /// Read the credentials output destinations refer to from `store`
+    pub fn with_secret_store(mut self, store: Arc<dyn SecretStore>) -> Self {
+        self.secret_store = Some(store);
+        self
+    }
+
+    /// Only run registry tasks whose signature verified when `trust` quarantines or requires it
+    pub fn with_trust(mut self, trust: TrustPolicies) -> Self {
+        self.trust = trust;
+        self
+    }
+
     /// Start the job processor service
     pub async fn start(&self) -> Result<(), DatabaseError> {
         if !self.config.enabled {
@@ -71,9 +168,10 @@ impl JobProcessorService {
 
         self.is_running.store(true, Ordering::Relaxed);
         info!(
-            "Starting job processor service with {} second poll interval",
-            self.config.poll_interval_seconds
+            "Starting job processor service {} with {} second poll interval",
+            self.config.instance_id, self.config.poll_interval_seconds
         );
+        self.spawn_lease_renewal();
 
         // Main processing loop
         while self.is_running.load(Ordering::Relaxed) {
@@ -100,15 +198,57 @@ impl JobProcessorService {
         self.is_running.load(Ordering::Relaxed)
     }
 
+    fn lease(&self) -> Duration {
+        Duration::from_secs(self.config.lease_seconds)
+    }
+
+    /// Keep renewing the leases of claimed jobs until the processor stops
+    fn spawn_lease_renewal(&self) {
+        let repositories = self.repositories.clone();
+        let is_running = self.is_running.clone();
+        let owner = self.config.instance_id.clone();
+        let lease = self.lease();
+        tokio::spawn(async move {
+            while is_running.load(Ordering::Relaxed) {
+                sleep(lease / 3).await;
+                if let Err(e) = repositories.job_repository().renew_leases(&owner, lease).await {
+                    warn!("Failed to renew job leases of {}: {}", owner, e);
+                }
+            }
+        });
+    }
+
     /// Process a batch of ready jobs
     async fn process_batch(&self) -> Result<(), DatabaseError> {
+        if let Some(engine) = &self.workflow_engine {
+            match engine.start_queued_runs(self.config.batch_size).await {
+                Ok(0) => debug!("No workflow runs queued"),
+                Ok(started) => info!("Started {} queued workflow runs", started),
+                Err(e) => error!("Failed to start queued workflow runs: {}", e),
+            }
+        }
+
+        if let Some(ledger) = &self.delivery_ledger {
+            ledger.sweep(self.config.batch_size).await;
+        }
+
+        // Jobs of servers that died while processing them go back to the queue
+        match self.repositories.job_repository().reclaim_expired_leases().await {
+            Ok(reclaimed) => {
+                for job in reclaimed {
+                    warn!("Reclaimed job {} after its lease expired ({:?})", job.id, job.status);
+                }
+            }
+            Err(e) => error!("Failed to reclaim jobs with expired leases: {}", e),
+        }
+
         debug!("Checking for ready jobs to process");
 
-        // Get ready jobs from the repository
+        // Claim ready jobs, so no other server processes them too
         let jobs = self
             .repositories
             .job_repository()
-            .find_ready_for_processing(self.config.batch_size)
+            .claim_ready_for_processing(&self.config.instance_id, self.lease(), self.config.batch_size)
             .await?;
 
         if jobs.is_empty() {
@@ -116,7 +256,7 @@ impl JobProcessorService {
             return Ok(());
         }
 
-        info!("Found {} jobs ready for processing", jobs.len());
+        info!("Claimed {} jobs for processing", jobs.len());
 
         // Process each job
         for job in jobs {
@@ -139,8 +279,7 @@ impl JobProcessorService {
         Ok(())
     }
 
-    /// Process a single job by creating an execution and marking it as completed
-    /// For now, this is a simplified implementation that doesn't actually execute tasks
+    /// Process a single job by creating an execution for it and running its task
     async fn process_job(&self, job_id: &ApiId) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         debug!("Processing job {}", job_id);
 
@@ -151,6 +290,16 @@ impl JobProcessorService {
             .find_by_id(job_id.as_i32().ok_or("Invalid job ID")?)
             .await?
             .ok_or("Job not found")?;
+        let task = self.runnable_task(&job).await?;
+        let input = self.job_input(&job).await?;
+
+        let cache_lookup = match &self.result_cache {
+            Some(cache) => cache.lookup(&job.task_id, &input).await,
+            None => None,
+        };
+        if let Some(cached) = cache_lookup.as_ref().and_then(|lookup| lookup.hit.clone()) {
+            return self.complete_from_cache(&job, input, &cached).await;
+        }
 
         // Create an execution for this job
         let execution = UnifiedExecution {
@@ -158,7 +307,7 @@ impl JobProcessorService {
             uuid: uuid::Uuid::new_v4(),
             task_id: job.task_id.clone(),
             status: ExecutionStatus::Pending,
-            input: serde_json::json!({}), // TODO: Get input from job metadata
+            input: input.clone(),
             output: None,
             error_message: None,
             error_details: None,
@@ -168,7 +317,11 @@ impl JobProcessorService {
             duration_ms: None,
             http_requests: None,
             recording_path: None,
+            parent_execution_uuid: None,
+            cache_hit: false,
             progress: None,
+            progress_message: None,
+            partial_output: None,
             can_retry: false,
             can_cancel: false,
         };
@@ -178,6 +331,7 @@ impl JobProcessorService {
 
         // Store IDs before they get moved
         let execution_id = created_execution.id.clone();
+        let execution_uuid = created_execution.uuid;
         let job_id_for_processing = job.id.clone();
 
         // Mark job as processing and link to execution
@@ -188,10 +342,6 @@ impl JobProcessorService {
 
         info!("Created execution {} for job {}", execution_id, job_id);
 
-        // For now, we'll simulate task execution with a simple success
-        // In a full implementation, this would delegate to a task executor
-        // TODO: Integrate with actual task execution system
-
         // Mark execution as started
         self.repositories
             .execution_repository()
@@ -200,34 +350,36 @@ impl JobProcessorService {
             .map_err(|e| error!("Failed to mark execution {} as started: {}", execution_id, e))
             .ok();
 
-        // Simulate some processing time
-        tokio::time::sleep(Duration::from_millis(100)).await;
+        let started = std::time::Instant::now();
+        let result = self.run_task(&job, task, execution_uuid, input).await;
+        let duration_ms = started.elapsed().as_millis().min(i32::MAX as u128) as i32;
 
-        // For heartbeat tasks, create a simple success response
-        let output = if job.task_id.to_string().contains("heartbeat") {
-            serde_json::json!({
-                "status": "success",
-                "message": "Heartbeat completed successfully",
-                "timestamp": chrono::Utc::now().to_rfc3339(),
-                "system_healthy": true
-            })
-        } else {
-            serde_json::json!({
-                "status": "success",
-                "message": "Job completed successfully",
-                "job_id": job_id.to_string()
-            })
+        // A cancel request may have arrived while the job was running
+        if self.was_cancelled(job_id, &execution_id).await {
+            info!("Job {} was cancelled during execution {}", job_id, execution_id);
+            return Ok(());
+        }
+
+        let output = match result {
+            Ok(output) => output,
+            Err(message) => {
+                if let Err(e) = self
+                    .repositories
+                    .execution_repository()
+                    .mark_failed(execution_id.clone(), message.clone(), None)
+                    .await
+                {
+                    error!("Failed to mark execution {} as failed: {}", execution_id, e);
+                }
+                return Err(message.into());
+            }
         };
 
         // Mark execution as completed
         if let Err(e) = self
             .repositories
             .execution_repository()
-            .mark_completed(
-                execution_id.clone(),
-                output.clone(),
-                Some(100), // 100ms duration
-            )
+            .mark_completed(execution_id.clone(), output.clone(), Some(duration_ms))
             .await
         {
             error!("Failed to mark execution {} as completed: {}", execution_id, e);
@@ -238,6 +390,12 @@ impl JobProcessorService {
             error!("Failed to mark job {} as completed: {}", job_id, e);
         }
 
+        if let (Some(cache), Some(lookup)) = (&self.result_cache, cache_lookup) {
+            cache
+                .store(lookup, execution_uuid, output.clone(), duration_ms as u64)
+                .await;
+        }
+
         // Process output destinations if any are configured
         if let Some(ref output_destinations) = job.output_destinations {
             self.deliver_job_output(job_id.clone(), execution_id.clone(), output, output_destinations)
@@ -248,6 +406,204 @@ impl JobProcessorService {
         Ok(())
     }
 
+    /// The task of `job`, unless it is disabled, or unverified while its
+    /// repository's policy is quarantine or require
+    async fn runnable_task(&self, job: &UnifiedJob) -> Result<UnifiedTask, String> {
+        let task = self
+            .repositories
+            .task_repository()
+            .find_by_id(job.task_id.as_i32().ok_or("Invalid task ID")?)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("task {} no longer exists", job.task_id))?;
+        if !task.enabled {
+            return Err(format!("task {} is disabled", task.name));
+        }
+        let repository = task
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get("repository"))
+            .and_then(|repository| repository.as_str());
+        if repository.is_some_and(|repository| self.trust.requires_verification(repository))
+            && task.signature_status != Some(TaskSignatureStatus::Verified)
+        {
+            return Err(format!("task {} has not passed signature verification", task.name));
+        }
+        Ok(task)
+    }
+
+    /// Run the task of `job` as the execution `uuid` on a blocking thread, as the
+    /// JavaScript runtime cannot move between threads
+    async fn run_task(
+        &self,
+        job: &UnifiedJob,
+        task: UnifiedTask,
+        uuid: uuid::Uuid,
+        input: serde_json::Value,
+    ) -> Result<serde_json::Value, String> {
+        let caller = self
+            .task_caller
+            .clone()
+            .ok_or_else(|| "no task executor is configured".to_string())?;
+        let running = self.running.clone();
+        let handle = tokio::runtime::Handle::current();
+
+        let (progress, mut updates) = ProgressReporter::channel();
+        let forwarder = self.progress_sink.clone().map(|sink| {
+            let execution_id = uuid.to_string();
+            let task_id = job.task_id.to_string();
+            tokio::spawn(async move {
+                while let Some(update) = updates.recv().await {
+                    sink.record_progress(ExecutionProgress {
+                        execution_id: execution_id.clone(),
+                        task_id: task_id.clone(),
+                        fraction: update.fraction,
+                        message: update.message,
+                        partial_output: update.partial_output,
+                        timestamp: Utc::now(),
+                    })
+                    .await;
+                }
+            })
+        });
+
+        let console = ConsoleCapture::new();
+        let task_console = console.clone();
+        let joined = tokio::task::spawn_blocking(move || {
+            handle.block_on(async move {
+                caller
+                    .run_execution(&task, uuid, input, &running, progress, task_console)
+                    .await
+                    .map_err(|e| e.to_string())
+            })
+        })
+        .await;
+
+        // The reporter is dropped with the run, so the forwarder finishes once every
+        // update has been recorded, before the execution is marked as finished
+        if let Some(forwarder) = forwarder {
+            let _ = forwarder.await;
+        }
+        if let Some(sink) = &self.log_sink {
+            sink.record_logs(&uuid.to_string(), execution_logs(&console)).await;
+        }
+        joined.unwrap_or_else(|e| Err(format!("task panicked: {}", e)))
+    }
+
+    /// Complete a job with a cached result instead of running its task
+    ///
+    /// The job still gets an execution of its own, marked as a cache hit, and its
+    /// output is delivered as usual.
+    async fn complete_from_cache(
+        &self,
+        job: &UnifiedJob,
+        input: serde_json::Value,
+        cached: &CachedResult,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let now = Utc::now();
+        let execution = UnifiedExecution {
+            id: ApiId::from_uuid(uuid::Uuid::new_v4()),
+            uuid: uuid::Uuid::new_v4(),
+            task_id: job.task_id.clone(),
+            status: ExecutionStatus::Completed,
+            input,
+            output: Some(cached.output.clone()),
+            error_message: None,
+            error_details: None,
+            queued_at: now,
+            started_at: Some(now),
+            completed_at: Some(now),
+            duration_ms: Some(0),
+            http_requests: None,
+            recording_path: None,
+            parent_execution_uuid: None,
+            cache_hit: true,
+            progress: None,
+            progress_message: None,
+            partial_output: None,
+            can_retry: false,
+            can_cancel: false,
+        };
+        let execution_id = self.repositories.execution_repository().create(execution).await?.id;
+
+        self.repositories
+            .job_repository()
+            .mark_processing(job.id.clone(), execution_id.clone())
+            .await?;
+        if let Err(e) = self.repositories.job_repository().mark_completed(job.id.clone()).await {
+            error!("Failed to mark job {} as completed: {}", job.id, e);
+        }
+
+        if let Some(ref output_destinations) = job.output_destinations {
+            self.deliver_job_output(
+                job.id.clone(),
+                execution_id.clone(),
+                cached.output.clone(),
+                output_destinations,
+            )
+            .await;
+        }
+
+        info!(
+            "Served job {} from the result of execution {} as execution {}",
+            job.id, cached.execution_id, execution_id
+        );
+        Ok(())
+    }
+
+    /// Input of a job: the outputs its parents pass on, overlaid with the job's own input
+    async fn job_input(&self, job: &UnifiedJob) -> Result<serde_json::Value, DatabaseError> {
+        let outputs = self
+            .repositories
+            .job_repository()
+            .find_dependency_outputs(job.id.clone())
+            .await?;
+        Ok(outputs
+            .into_iter()
+            .chain(job.input.clone())
+            .fold(serde_json::json!({}), overlay))
+    }
+
+    /// Check whether the job or its execution was cancelled, settling the other side
+    async fn was_cancelled(&self, job_id: &ApiId, execution_id: &ApiId) -> bool {
+        let job_cancelled = match self
+            .repositories
+            .job_repository()
+            .find_by_id(job_id.as_i32().unwrap_or(0))
+            .await
+        {
+            Ok(Some(job)) => job.status == JobStatus::Cancelled,
+            _ => false,
+        };
+        let execution_cancelled = match self
+            .repositories
+            .execution_repository()
+            .find_by_id(execution_id.as_i32().unwrap_or(0))
+            .await
+        {
+            Ok(Some(execution)) => execution.status == ExecutionStatus::Cancelled,
+            _ => false,
+        };
+
+        if job_cancelled && !execution_cancelled {
+            if let Err(e) = self
+                .repositories
+                .execution_repository()
+                .mark_cancelled(execution_id.clone())
+                .await
+            {
+                error!("Failed to mark execution {} as cancelled: {}", execution_id, e);
+            }
+        }
+        if execution_cancelled && !job_cancelled {
+            if let Err(e) = self.repositories.job_repository().cancel(job_id.clone()).await {
+                error!("Failed to mark job {} as cancelled: {}", job_id, e);
+            }
+        }
+
+        job_cancelled || execution_cancelled
+    }
+
     /// Deliver job output to configured destinations
     async fn deliver_job_output(
         &self,
@@ -262,64 +618,39 @@ impl JobProcessorService {
             destinations.len()
         );
 
-        // Create task output for delivery
-        let task_output = TaskOutput {
-            job_id: job_id.as_i32().unwrap_or(0),
-            task_id: 0, // Would need to get from job/execution
-            execution_id: execution_id.as_i32().unwrap_or(0),
-            output_data: output,
-            metadata: HashMap::new(),
-            completed_at: Utc::now(),
-            execution_duration: std::time::Duration::from_millis(100), // Default duration
-        };
-
-        let delivery_context = DeliveryContext::default();
+        // Attempts are recorded and failed ones retried when there is a ledger
+        if let Some(ledger) = &self.delivery_ledger {
+            ledger
+                .deliver(&job_id, &execution_id, output, Utc::now(), destinations)
+                .await;
+            return;
+        }
 
-        // Process each destination
+        let (task_output, delivery_context) = delivery_input(&job_id, &execution_id, output, Utc::now());
         for (index, destination) in destinations.iter().enumerate() {
             let destination_id = format!("job_{}_dest_{}", job_id, index);
-
-            // Convert UnifiedOutputDestination to OutputDestinationConfig
-            if let Ok(config) = self.convert_unified_to_output_config(destination) {
-                // Add destination to output manager
-                if let Err(e) = self
-                    .output_manager
-                    .add_destination(destination_id.clone(), config)
-                    .await
-                {
-                    error!("Failed to add destination {} for job {}: {}", destination_id, job_id, e);
-                    continue;
+            match deliver_to_destination(
+                &self.output_manager,
+                self.secret_store.as_deref(),
+                destination_id.clone(),
+                destination,
+                &task_output,
+                &delivery_context,
+            )
+            .await
+            {
+                Ok(_) => {
+                    info!(
+                        "Successfully delivered output for job {} to destination {}",
+                        job_id, destination_id
+                    );
                 }
-
-                // Deliver output
-                match self
-                    .output_manager
-                    .deliver_output(&destination_id, &task_output, &delivery_context)
-                    .await
-                {
-                    Ok(_) => {
-                        info!(
-                            "Successfully delivered output for job {} to destination {}",
-                            job_id, destination_id
-                        );
-                    }
-                    Err(e) => {
-                        error!(
-                            "Failed to deliver output for job {} to destination {}: {}",
-                            job_id, destination_id, e
-                        );
-                    }
+                Err(e) => {
+                    error!(
+                        "Failed to deliver output for job {} to destination {}: {}",
+                        job_id, destination_id, e
+                    );
                 }
-
-                // Clean up destination after delivery
-                if !self.output_manager.remove_destination(&destination_id).await {
-                    warn!("Failed to clean up destination {} for job {}", destination_id, job_id);
-                }
-            } else {
-                error!(
-                    "Failed to convert output destination for job {}: unsupported destination type {}",
-                    job_id, destination.destination_type
-                );
             }
         }
     }
@@ -345,9 +676,11 @@ impl JobProcessorService {
     }
 
     /// Convert UnifiedOutputDestination to OutputDestinationConfig
+    ///
+    /// `secret_access_key` is the value of the secret an S3 destination refers to.
     fn convert_unified_to_output_config(
-        &self,
         destination: &UnifiedOutputDestination,
+        secret_access_key: Option<String>,
     ) -> Result<OutputDestinationConfig, String> {
         match destination.destination_type.as_str() {
             "stdio" => {
@@ -438,6 +771,48 @@ impl JobProcessorService {
                     Err("filesystem destination missing configuration".to_string())
                 }
             }
+            "s3" => {
+                if let Some(ref s3_config) = destination.s3 {
+                    let credentials = match (&s3_config.access_key_id, secret_access_key) {
+                        (Some(access_key_id), Some(secret_access_key)) => Some(ratchet_output::S3Credentials {
+                            access_key_id: access_key_id.clone(),
+                            secret_access_key,
+                            session_token: None,
+                        }),
+                        _ => None,
+                    };
+
+                    Ok(OutputDestinationConfig::S3 {
+                        bucket: s3_config.bucket.clone(),
+                        key: s3_config.key.clone(),
+                        region: s3_config.region.clone(),
+                        storage_class: s3_config.storage_class.clone(),
+                        metadata: s3_config.metadata.clone().unwrap_or_default(),
+                        endpoint: s3_config.endpoint.clone(),
+                        path_style: s3_config.path_style,
+                        format: Self::convert_output_format(&s3_config.format),
+                        credentials,
+                        part_size: 8 * 1024 * 1024,
+                        timeout: std::time::Duration::from_secs(60),
+                    })
+                } else {
+                    Err("s3 destination missing configuration".to_string())
+                }
+            }
+            "database" => {
+                if let Some(ref db_config) = destination.database {
+                    Ok(OutputDestinationConfig::Database {
+                        connection: db_config.connection.clone(),
+                        table: db_config.table.clone(),
+                        columns: db_config.columns.clone(),
+                        upsert: db_config.upsert,
+                        key_columns: db_config.key_columns.clone(),
+                        batch_size: db_config.batch_size.map(|size| size.max(1) as usize).unwrap_or(100),
+                    })
+                } else {
+                    Err("database destination missing configuration".to_string())
+                }
+            }
             _ => Err(format!(
                 "unsupported destination type: {}",
                 destination.destination_type
@@ -446,6 +821,110 @@ impl JobProcessorService {
     }
 }
 
+/// The output of a job as destinations receive it, and the variables their templates can use
+pub(crate) fn delivery_input(
+    job_id: &ApiId,
+    execution_id: &ApiId,
+    output: serde_json::Value,
+    completed_at: chrono::DateTime<Utc>,
+) -> (TaskOutput, DeliveryContext) {
+    let task_output = TaskOutput {
+        job_id: job_id.as_i32().unwrap_or(0),
+        task_id: 0, // Would need to get from job/execution
+        execution_id: execution_id.as_i32().unwrap_or(0),
+        output_data: output,
+        metadata: HashMap::new(),
+        completed_at,
+        execution_duration: std::time::Duration::from_millis(100), // Default duration
+    };
+
+    // Variables available to path, URL and key templates of the destinations
+    let mut delivery_context = DeliveryContext {
+        job_id: task_output.job_id,
+        timestamp: completed_at,
+        ..DeliveryContext::default()
+    };
+    delivery_context.template_variables.extend([
+        ("job_id".to_string(), job_id.to_string()),
+        ("execution_id".to_string(), execution_id.to_string()),
+        (
+            "timestamp".to_string(),
+            completed_at.format("%Y%m%dT%H%M%SZ").to_string(),
+        ),
+        ("date".to_string(), completed_at.format("%Y-%m-%d").to_string()),
+    ]);
+    (task_output, delivery_context)
+}
+
+/// Deliver to one destination of a job, registered with `output_manager` as `name` for the delivery only
+///
+/// Destinations that cannot be set up and deliveries that fail are both an error.
+/// Secrets the destination refers to are read from `secrets` for this delivery.
+pub(crate) async fn deliver_to_destination(
+    output_manager: &OutputDeliveryManager,
+    secrets: Option<&dyn SecretStore>,
+    name: String,
+    destination: &UnifiedOutputDestination,
+    task_output: &TaskOutput,
+    delivery_context: &DeliveryContext,
+) -> Result<ratchet_output::DeliveryResult, String> {
+    let secret_access_key = s3_secret_access_key(destination, secrets).await?;
+    let config = JobProcessorService::convert_unified_to_output_config(destination, secret_access_key)?;
+    output_manager
+        .add_destination(name.clone(), config)
+        .await
+        .map_err(|e| format!("invalid {} destination: {}", destination.destination_type, e))?;
+
+    let result = output_manager
+        .deliver_output(&name, task_output, delivery_context)
+        .await;
+
+    // Clean up destination after delivery
+    if !output_manager.remove_destination(&name).await {
+        warn!("Failed to clean up destination {}", name);
+    }
+
+    match result {
+        Ok(result) if result.success => Ok(result),
+        Ok(result) => Err(result
+            .error
+            .map(|e| e.to_string())
+            .unwrap_or_else(|| "delivery failed".to_string())),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Read the secret access key an S3 destination refers to, if it refers to one
+async fn s3_secret_access_key(
+    destination: &UnifiedOutputDestination,
+    secrets: Option<&dyn SecretStore>,
+) -> Result<Option<String>, String> {
+    let Some(name) = destination
+        .s3
+        .as_ref()
+        .and_then(|s3| s3.secret_access_key_secret.as_ref())
+    else {
+        return Ok(None);
+    };
+    let secrets = secrets.ok_or_else(|| format!("no secret store to read S3 secret '{}' from", name))?;
+    let mut values = secrets
+        .resolve_secrets(std::slice::from_ref(name), None, None)
+        .await
+        .map_err(|e| format!("failed to read S3 secret '{}': {}", name, e))?;
+    Ok(values.remove(name))
+}
+
+/// Merge the fields of `top` over those of `base`; anything but two objects is replaced by `top`
+fn overlay(base: serde_json::Value, top: serde_json::Value) -> serde_json::Value {
+    match (base, top) {
+        (serde_json::Value::Object(mut base), serde_json::Value::Object(top)) => {
+            base.extend(top);
+            serde_json::Value::Object(base)
+        }
+        (_, top) => top,
+    }
+}
+
 /// Job processor service trait for dependency injection
 #[async_trait]
 pub trait JobProcessor: Send + Sync {
@@ -473,3 +952,505 @@ impl JobProcessor for JobProcessorService {
         JobProcessorService::is_running(self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::{DirectRepositoryFactory, StubRegistryManager, StubTaskRegistry, StubTaskValidator};
+    use crate::task_service::UnifiedTaskService;
+    use axum::extract::{Path, State};
+    use ratchet_execution::{ExecutionBridge, ProcessExecutorConfig};
+    use ratchet_interfaces::TaskResultCache;
+    use ratchet_rest_api::context::TasksContext;
+    use ratchet_storage::seaorm::entities::{Job, JobPriority, Task};
+    use ratchet_storage::seaorm::repositories::{
+        ExecutionRepository, JobRepository, RepositoryFactory as StorageFactory, TaskRepository,
+    };
+    use ratchet_storage::seaorm::{config::DatabaseConfig, connection::DatabaseConnection};
+    use ratchet_storage::testing::TaskBuilder;
+    use serde_json::json;
+
+    /// A migrated SQLite file database and its URL
+    async fn create_file_db() -> (DatabaseConnection, String) {
+        let path = std::env::temp_dir().join(format!("ratchet-processor-{}.db", uuid::Uuid::new_v4()));
+        let url = format!("sqlite://{}", path.display());
+        let db = DatabaseConnection::new(DatabaseConfig {
+            url: url.clone(),
+            max_connections: 5,
+            connection_timeout: Duration::from_secs(10),
+        })
+        .await
+        .unwrap();
+        db.migrate().await.unwrap();
+        (db, url)
+    }
+
+    /// A processor with its own connection pool to the SQLite file at `url`, as on another server
+    async fn processor(url: &str, instance_id: &str) -> JobProcessorService {
+        processor_with_caller(url, instance_id, |caller| caller).await
+    }
+
+    /// A processor whose task caller is set up by `configure`
+    async fn processor_with_caller(
+        url: &str,
+        instance_id: &str,
+        configure: impl FnOnce(TaskServiceCaller) -> TaskServiceCaller,
+    ) -> JobProcessorService {
+        let db = DatabaseConnection::new(DatabaseConfig {
+            url: url.to_string(),
+            max_connections: 5,
+            connection_timeout: Duration::from_secs(10),
+        })
+        .await
+        .unwrap();
+        let repositories: Arc<dyn RepositoryFactory> =
+            Arc::new(DirectRepositoryFactory::new(Arc::new(StorageFactory::new(db))));
+        let config = JobProcessorConfig {
+            batch_size: 2,
+            instance_id: instance_id.to_string(),
+            ..Default::default()
+        };
+        let tasks = Arc::new(UnifiedTaskService::new(
+            repositories.clone(),
+            Arc::new(StubTaskRegistry::new()),
+        ));
+        JobProcessorService::new(repositories, Arc::new(OutputDeliveryManager::new()), config)
+            .with_task_caller(Arc::new(configure(TaskServiceCaller::new(tasks))))
+    }
+
+    async fn create_task(db: &DatabaseConnection, name: &str, metadata: serde_json::Value) -> Task {
+        create_task_with_source(db, name, metadata, "(function(input) { return input; })").await
+    }
+
+    async fn create_task_with_source(
+        db: &DatabaseConnection,
+        name: &str,
+        metadata: serde_json::Value,
+        source: &str,
+    ) -> Task {
+        TaskRepository::new(db.clone())
+            .create(
+                TaskBuilder::new()
+                    .with_name(name)
+                    .with_metadata(metadata)
+                    .with_source_code(source)
+                    .build(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_two_processors_share_one_queue() {
+        let (db, url) = create_file_db().await;
+        let task = create_task(&db, "shared-queue", json!({})).await;
+        let jobs = JobRepository::new(db.clone());
+        for i in 0..8 {
+            jobs.create(Job::new(task.id, json!({ "i": i }), JobPriority::Normal))
+                .await
+                .unwrap();
+        }
+
+        let (a, b) = (processor(&url, "server-a").await, processor(&url, "server-b").await);
+        for _ in 0..4 {
+            let (from_a, from_b) = tokio::join!(a.process_batch(), b.process_batch());
+            from_a.unwrap();
+            from_b.unwrap();
+        }
+
+        // Every job ran exactly once
+        assert_eq!(
+            jobs.count_by_status(ratchet_storage::seaorm::entities::JobStatus::Completed)
+                .await
+                .unwrap(),
+            8
+        );
+        assert_eq!(ExecutionRepository::new(db).count().await.unwrap(), 8);
+    }
+
+    #[tokio::test]
+    async fn test_jobs_of_quarantined_and_unverified_tasks_do_not_run() {
+        use ratchet_registry::config::{SignaturePolicy, TrustConfig};
+
+        let (db, url) = create_file_db().await;
+        let repository = "https://registry.example.com/tasks";
+        let quarantined = create_task(&db, "quarantined", json!({ "repository": repository })).await;
+        let unverified = create_task(&db, "unverified", json!({ "repository": repository })).await;
+        let jobs = JobRepository::new(db.clone());
+        let mut queued = Vec::new();
+        for task in [&quarantined, &unverified] {
+            let job = jobs.create(Job::new(task.id, json!({}), JobPriority::Normal)).await;
+            queued.push(job.unwrap());
+        }
+
+        // Registry sync quarantines the task after its job was queued
+        let tasks = TaskRepository::new(db.clone());
+        let mut task = tasks.find_by_id(quarantined.id).await.unwrap().unwrap();
+        task.enabled = false;
+        task.sync_status = "quarantined".to_string();
+        task.signature_status = Some("unsigned".to_string());
+        tasks.update(task).await.unwrap();
+        let again = jobs.create(Job::new(quarantined.id, json!({"again": true}), JobPriority::Normal));
+        assert!(again.await.is_err());
+
+        let trust = ratchet_registry::TrustPolicies::from_config(&[TrustConfig {
+            repository: repository.to_string(),
+            policy: SignaturePolicy::Require,
+            public_keys: Vec::new(),
+        }])
+        .unwrap();
+        let processor = processor(&url, "server-a").await.with_trust(trust);
+        processor.process_batch().await.unwrap();
+
+        assert_eq!(ExecutionRepository::new(db).count().await.unwrap(), 0);
+        for (job, reason) in queued.iter().zip(["is disabled", "signature verification"]) {
+            let job = jobs.find_by_id(job.id).await.unwrap().unwrap();
+            let message = job.error_message.unwrap_or_default();
+            assert!(message.contains(reason), "{}", message);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_configured_limits_stop_runaway_tasks() {
+        let (db, url) = create_file_db().await;
+        let task = create_task_with_source(&db, "runaway", json!({}), "(function(input) { while (true) {} })").await;
+        let job = JobRepository::new(db.clone())
+            .create(Job::new(task.id, json!({}), JobPriority::Normal))
+            .await
+            .unwrap();
+
+        let mut config = ratchet_config::RatchetConfig {
+            server: Some(Default::default()),
+            ..Default::default()
+        };
+        config.execution.limits.max_loop_iterations = Some(10_000);
+        let limits = crate::config::ServerConfig::from_ratchet_config(config)
+            .unwrap()
+            .execution_limits;
+        let processor = processor_with_caller(&url, "server-a", |caller| caller.with_limits(limits)).await;
+        tokio::time::timeout(Duration::from_secs(30), processor.process_batch())
+            .await
+            .expect("the loop limit stops the task")
+            .unwrap();
+
+        let job = JobRepository::new(db).find_by_id(job.id).await.unwrap().unwrap();
+        let message = job.error_message.unwrap_or_default();
+        assert!(message.contains("loop iteration limit"), "{}", message);
+    }
+
+    #[tokio::test]
+    async fn test_heap_limits_are_refused_for_jobs() {
+        let (db, url) = create_file_db().await;
+        let task = create_task(&db, "heap-limited", json!({ "limits": { "max_heap_mb": 64 } })).await;
+        let job = JobRepository::new(db.clone())
+            .create(Job::new(task.id, json!({}), JobPriority::Normal))
+            .await
+            .unwrap();
+
+        processor(&url, "server-a").await.process_batch().await.unwrap();
+
+        let job = JobRepository::new(db).find_by_id(job.id).await.unwrap().unwrap();
+        let message = job.error_message.unwrap_or_default();
+        assert!(message.contains("heap limit"), "{}", message);
+    }
+
+    #[tokio::test]
+    async fn test_called_task_errors_do_not_reveal_its_secrets() {
+        use crate::config::EncryptionAlgorithm;
+        use crate::security::{AesEncryptionService, EncryptionService, SecretManager};
+        use ratchet_interfaces::SecretKey;
+        use ratchet_storage::seaorm::repositories::TaskSecretRepository;
+
+        let (db, url) = create_file_db().await;
+        create_task_with_source(
+            &db,
+            "leaky",
+            json!({ "secrets": ["API_KEY"] }),
+            "(function(input) { throw new Error('rejected key ' + ratchet.secrets.get('API_KEY')); })",
+        )
+        .await;
+        let task = create_task_with_source(
+            &db,
+            "caller",
+            json!({}),
+            "(async function(input) { return ratchet.call('leaky', {}).catch(e => ({ error: e.message })); })",
+        )
+        .await;
+        JobRepository::new(db.clone())
+            .create(Job::new(task.id, json!({}), JobPriority::Normal))
+            .await
+            .unwrap();
+
+        let encryption = AesEncryptionService::new(EncryptionAlgorithm::AES256);
+        encryption.initialize().await.unwrap();
+        let manager = SecretManager::new(TaskSecretRepository::new(db.clone()), Arc::new(encryption));
+        manager
+            .set_secret(SecretKey::global("API_KEY"), "sk-live-1234", None)
+            .await
+            .unwrap();
+        let store: Arc<dyn SecretStore> = Arc::new(manager);
+        processor_with_caller(&url, "server-a", |caller| caller.with_secret_store(store))
+            .await
+            .process_batch()
+            .await
+            .unwrap();
+
+        let execution = ExecutionRepository::new(db)
+            .find_by_task_id(task.id)
+            .await
+            .unwrap()
+            .pop()
+            .unwrap();
+        let output = execution.output.unwrap();
+        let error = output["error"].as_str().unwrap();
+        assert!(error.contains("rejected key [REDACTED]"), "{}", error);
+        assert!(!error.contains("sk-live-1234"));
+    }
+
+    #[tokio::test]
+    async fn test_console_output_of_called_tasks_is_sanitized() {
+        let (db, url) = create_file_db().await;
+        let called = create_task_with_source(
+            &db,
+            "chatty",
+            json!({}),
+            "(function(input) { console.log('connecting with password=hunter2secret'); return {}; })",
+        )
+        .await;
+        let task = create_task_with_source(
+            &db,
+            "parent",
+            json!({}),
+            "(async function(input) { return ratchet.call('chatty', {}); })",
+        )
+        .await;
+        JobRepository::new(db.clone())
+            .create(Job::new(task.id, json!({}), JobPriority::Normal))
+            .await
+            .unwrap();
+
+        let repositories: Arc<dyn RepositoryFactory> =
+            Arc::new(DirectRepositoryFactory::new(Arc::new(StorageFactory::new(db.clone()))));
+        let recorder = Arc::new(crate::progress::ExecutionProgressRecorder::new(
+            repositories.clone(),
+            Arc::new(ratchet_graphql_api::events::EventBroadcaster::new()),
+        ));
+        processor_with_caller(&url, "server-a", |caller| {
+            caller.with_repositories(repositories).with_log_sink(recorder)
+        })
+        .await
+        .process_batch()
+        .await
+        .unwrap();
+
+        let execution = ExecutionRepository::new(db)
+            .find_by_task_id(called.id)
+            .await
+            .unwrap()
+            .pop()
+            .unwrap();
+        let logs = execution.logs.unwrap();
+        let message = logs[0]["message"].as_str().unwrap();
+        assert!(message.starts_with("connecting with"), "{}", message);
+        assert!(!message.contains("hunter2secret"), "{}", message);
+    }
+
+    #[tokio::test]
+    async fn test_cached_results_are_served_without_running() {
+        let (db, url) = create_file_db().await;
+        let task = create_task_with_source(
+            &db,
+            "cached",
+            json!({"cache": {"ttl_seconds": 60, "key_fields": ["city"]}}),
+            "(function(input) { return { greeting: 'Hello, ' + input.city, n: input.n }; })",
+        )
+        .await;
+        let cache = Arc::new(TaskResultCacheManager::new(
+            &crate::config::ResultCacheConfig::default(),
+            TaskRepository::new(db.clone()),
+        ));
+        let processor = processor(&url, "server-a").await.with_result_cache(cache.clone());
+
+        let jobs = JobRepository::new(db.clone());
+        jobs.create(Job::new(task.id, json!({"city": "Oslo", "n": 1}), JobPriority::Normal))
+            .await
+            .unwrap();
+        processor.process_batch().await.unwrap();
+        for input in [json!({"city": "Oslo", "n": 2}), json!({"city": "Bergen", "n": 3})] {
+            jobs.create(Job::new(task.id, input, JobPriority::Normal))
+                .await
+                .unwrap();
+        }
+        processor.process_batch().await.unwrap();
+
+        // Only the job with a new city ran; the other got the first result
+        let executions = ExecutionRepository::new(db).find_by_task_id(task.id).await.unwrap();
+        assert_eq!(executions.len(), 3);
+        let first = executions.iter().find(|e| e.input["n"] == 1).unwrap();
+        let hit = executions.iter().find(|e| e.input["n"] == 2).unwrap();
+        let miss = executions.iter().find(|e| e.input["n"] == 3).unwrap();
+        assert!(hit.cache_hit && !first.cache_hit && !miss.cache_hit);
+        // The cached result is what the task returned for the first job
+        assert_eq!(first.output, Some(json!({"greeting": "Hello, Oslo", "n": 1})));
+        assert_eq!(hit.output, first.output);
+        assert_eq!(miss.output, Some(json!({"greeting": "Hello, Bergen", "n": 3})));
+
+        let task_id = ApiId::from_i32(task.id);
+        let entries = cache.entries(task_id.clone()).await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].execution_uuid, first.uuid);
+        assert_eq!(cache.purge(task_id.clone()).await.unwrap(), 2);
+        assert!(cache.entries(task_id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cancelling_an_execution_stops_its_task() {
+        let (db, url) = create_file_db().await;
+        let task = create_task_with_source(&db, "spin", json!({}), "(function(input) { while (true) {} })").await;
+        let job = JobRepository::new(db.clone())
+            .create(Job::new(task.id, json!({}), JobPriority::Normal))
+            .await
+            .unwrap();
+
+        // The API cancels through the bridge, which shares its registry with the processor
+        let bridge = Arc::new(ExecutionBridge::new(ProcessExecutorConfig::default()));
+        let running = bridge.inner().running_executions();
+        let processor = Arc::new(
+            processor(&url, "server-a")
+                .await
+                .with_running_executions(running.clone()),
+        );
+        let batch = tokio::spawn({
+            let processor = processor.clone();
+            async move { processor.process_batch().await }
+        });
+
+        let executions = ExecutionRepository::new(db.clone());
+        let execution = tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                if let Some(execution) = executions.find_by_task_id(task.id).await.unwrap().pop() {
+                    if running.is_running(&execution.uuid.to_string()) {
+                        return execution;
+                    }
+                }
+                sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await
+        .expect("the task never started");
+
+        let context = TasksContext::new(
+            processor.repositories.clone(),
+            Arc::new(StubTaskRegistry::new()),
+            Arc::new(StubRegistryManager::new()),
+            Arc::new(StubTaskValidator::new()),
+        )
+        .with_execution_canceller(bridge);
+        let cancelled =
+            ratchet_rest_api::handlers::executions::cancel_execution(State(context), Path(execution.id.to_string()))
+                .await;
+        assert!(cancelled.is_ok());
+
+        // The endless loop stops, and both the execution and the job end up cancelled
+        tokio::time::timeout(Duration::from_secs(10), batch)
+            .await
+            .expect("the task kept running after it was cancelled")
+            .unwrap()
+            .unwrap();
+        assert!(!running.is_running(&execution.uuid.to_string()));
+        let execution = executions.find_by_id(execution.id).await.unwrap().unwrap();
+        assert_eq!(
+            execution.status,
+            ratchet_storage::seaorm::entities::ExecutionStatus::Cancelled
+        );
+        let job = JobRepository::new(db).find_by_id(job.id).await.unwrap().unwrap();
+        assert_eq!(job.status, ratchet_storage::seaorm::entities::JobStatus::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_progress_and_console_output_of_jobs_are_recorded() {
+        let (db, url) = create_file_db().await;
+        let task = create_task_with_source(
+            &db,
+            "progress",
+            json!({}),
+            "(function(input) { ratchet.progress(0.5, 'halfway'); ratchet.emit({rows: 1}); console.warn('slow'); return input; })",
+        )
+        .await;
+        JobRepository::new(db.clone())
+            .create(Job::new(task.id, json!({}), JobPriority::Normal))
+            .await
+            .unwrap();
+
+        let processor = processor(&url, "server-a").await;
+        let recorder = Arc::new(crate::progress::ExecutionProgressRecorder::new(
+            processor.repositories.clone(),
+            Arc::new(ratchet_graphql_api::events::EventBroadcaster::new()),
+        ));
+        let processor = processor.with_progress_sink(recorder.clone()).with_log_sink(recorder);
+        processor.process_batch().await.unwrap();
+
+        let execution = ExecutionRepository::new(db)
+            .find_by_task_id(task.id)
+            .await
+            .unwrap()
+            .pop()
+            .unwrap();
+        let progress = execution.progress.unwrap();
+        assert_eq!(progress["fraction"], json!(0.5));
+        assert_eq!(progress["message"], json!("halfway"));
+        assert_eq!(execution.partial_output, Some(json!({"rows": 1})));
+        let logs = execution.logs.unwrap();
+        assert_eq!(logs[0]["level"], json!("WARN"));
+        assert_eq!(logs[0]["message"], json!("slow"));
+    }
+
+    #[test]
+    fn test_overlay_merges_objects() {
+        let merged = [json!({"rows": [1, 2], "source": "fetch"}), json!({"source": "manual"})]
+            .into_iter()
+            .fold(json!({}), overlay);
+        assert_eq!(merged, json!({"rows": [1, 2], "source": "manual"}));
+
+        assert_eq!(overlay(json!({"a": 1}), json!([1, 2])), json!([1, 2]));
+        assert_eq!(overlay(json!("text"), json!({"a": 1})), json!({"a": 1}));
+    }
+
+    #[tokio::test]
+    async fn test_s3_secret_access_key_is_read_from_the_secret_store() {
+        use crate::config::EncryptionAlgorithm;
+        use crate::security::{AesEncryptionService, EncryptionService, SecretManager};
+        use ratchet_interfaces::SecretKey;
+        use ratchet_storage::seaorm::repositories::TaskSecretRepository;
+
+        let (db, _) = create_file_db().await;
+        let encryption = AesEncryptionService::new(EncryptionAlgorithm::AES256);
+        encryption.initialize().await.unwrap();
+        let manager = SecretManager::new(TaskSecretRepository::new(db), Arc::new(encryption));
+        manager
+            .set_secret(SecretKey::global("MINIO_SECRET_KEY"), "minio-secret", None)
+            .await
+            .unwrap();
+
+        let destination: UnifiedOutputDestination = serde_json::from_value(json!({
+            "destinationType": "s3",
+            "s3": {
+                "bucket": "reports",
+                "key": "{{job_id}}.json",
+                "region": "us-east-1",
+                "format": "JSON",
+                "accessKeyId": "minio",
+                "secretAccessKeySecret": "MINIO_SECRET_KEY"
+            }
+        }))
+        .unwrap();
+
+        let secret = s3_secret_access_key(&destination, Some(&manager)).await.unwrap();
+        assert_eq!(secret.as_deref(), Some("minio-secret"));
+        assert!(s3_secret_access_key(&destination, None).await.is_err());
+
+        // The stored destination only names the secret
+        let stored = serde_json::to_string(&destination).unwrap();
+        assert!(!stored.contains("minio-secret"));
+    }
+}
@@ -0,0 +1,89 @@
+//! JSON templates that copy parts of another JSON value
+//!
+//! A template is any JSON value in which `{"$from": "a.b.0"}` objects are
+//! replaced by the value at that dotted path of a root value. Object fields are
+//! addressed by key and array elements by index. Workflow step inputs and
+//! webhook trigger input mappings are both templates.
+
+use serde_json::Value as JsonValue;
+
+/// Key of a template value that is replaced by a value of the root
+pub const FROM_KEY: &str = "$from";
+
+/// Build a value from `template`, reading `$from` paths from `root`
+pub fn resolve(template: &JsonValue, root: &JsonValue) -> Result<JsonValue, String> {
+    if let Some(path) = from_path(template) {
+        return lookup(path, root);
+    }
+
+    match template {
+        JsonValue::Object(fields) => fields
+            .iter()
+            .map(|(key, value)| Ok((key.clone(), resolve(value, root)?)))
+            .collect::<Result<serde_json::Map<_, _>, String>>()
+            .map(JsonValue::Object),
+        JsonValue::Array(items) => items
+            .iter()
+            .map(|item| resolve(item, root))
+            .collect::<Result<Vec<_>, String>>()
+            .map(JsonValue::Array),
+        other => Ok(other.clone()),
+    }
+}
+
+/// All `$from` paths in a template
+pub fn references(template: &JsonValue) -> Vec<String> {
+    if let Some(path) = from_path(template) {
+        return vec![path.to_string()];
+    }
+    match template {
+        JsonValue::Object(fields) => fields.values().flat_map(references).collect(),
+        JsonValue::Array(items) => items.iter().flat_map(references).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn lookup(path: &str, root: &JsonValue) -> Result<JsonValue, String> {
+    let mut value = root;
+    for segment in path.split('.') {
+        value = match value {
+            JsonValue::Object(fields) => fields.get(segment),
+            JsonValue::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        }
+        .ok_or_else(|| format!("'{}' does not resolve to a value", path))?;
+    }
+    Ok(value.clone())
+}
+
+/// The path of a `{"$from": path}` value
+fn from_path(value: &JsonValue) -> Option<&str> {
+    match value {
+        JsonValue::Object(fields) if fields.len() == 1 => fields.get(FROM_KEY)?.as_str(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_resolve_and_references() {
+        let root = json!({"payload": {"ref": "main", "commits": [{"id": "a1"}]}});
+        let template = json!({
+            "branch": {"$from": "payload.ref"},
+            "first": [{"$from": "payload.commits.0.id"}],
+            "source": "github",
+            "nested": {"$from": "payload.ref", "other": 1}
+        });
+
+        assert_eq!(
+            resolve(&template, &root).unwrap(),
+            json!({"branch": "main", "first": ["a1"], "source": "github", "nested": {"$from": "payload.ref", "other": 1}})
+        );
+        assert_eq!(references(&template), vec!["payload.ref", "payload.commits.0.id"]);
+        assert!(resolve(&json!({"$from": "payload.missing"}), &root).is_err());
+    }
+}
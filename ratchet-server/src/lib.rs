@@ -4,19 +4,26 @@
 //! along with all necessary services, demonstrating the new modular architecture.
 
 pub mod bridges;
+pub mod cluster;
 pub mod config;
+pub mod delivery_ledger;
 pub mod embedded;
 pub mod heartbeat;
 pub mod job_processor;
+pub mod json_template;
 pub mod mcp_handler;
 pub mod monitoring;
+pub mod progress;
 pub mod repository_services;
+pub mod result_cache;
 pub mod scheduler;
 pub mod security;
 pub mod services;
 pub mod startup;
 pub mod task_service;
+pub mod triggers;
 pub mod watchers;
+pub mod workflows;
 
 // Re-export main components
 pub use config::*;
@@ -20,7 +20,7 @@ use ratchet_mcp::{
     security::{AuditLogger, McpAuth, McpAuthManager, SecurityContext, SecurityConfig, ClientContext, permissions::ClientPermissions},
     server::McpServerConfig,
 };
-use ratchet_interfaces::RepositoryFactory;
+use ratchet_interfaces::{ExecutionLogSink, ExecutionProgressSink, RepositoryFactory, SecretStore};
 use ratchet_execution::ExecutionBridge;
 
 /// MCP endpoint state for handling both SSE and StreamableHTTP
@@ -92,12 +92,16 @@ impl McpEndpointState {
         mcp_task_service: Option<Arc<TaskDevelopmentService>>,
         storage_factory: Option<Arc<ratchet_storage::seaorm::repositories::RepositoryFactory>>,
         task_service: Option<Arc<dyn ratchet_interfaces::TaskService>>,
+        progress_sink: Option<Arc<dyn ExecutionProgressSink>>,
+        log_sink: Option<Arc<dyn ExecutionLogSink>>,
+        secret_store: Option<Arc<dyn SecretStore>>,
+        execution_bridge: Arc<ExecutionBridge>,
     ) -> anyhow::Result<Self> {
         // Create MCP server
         let mcp_server_config = McpServerConfig::sse_with_host(config.port, &config.host);
         let tool_registry = Arc::new(
             RatchetToolRegistry::new()
-                .with_repositories(repositories)
+                .with_repositories(repositories.clone())
         );
         
         // Configure tool registry with task development service if available
@@ -113,11 +117,9 @@ impl McpEndpointState {
         
         // Create MCP task executor if storage factory and task service are available
         let tool_registry = if let (Some(storage_fact), Some(task_svc)) = (storage_factory, task_service) {
-            // Create an ExecutionBridge as the task executor
-            let executor_config = ratchet_execution::ProcessExecutorConfig::default();
-            let execution_bridge = Arc::new(ExecutionBridge::new(executor_config));
-            
-            // Start the worker processes
+            // The server's ExecutionBridge is the task executor; tasks it runs can call
+            // other tasks through ratchet.call, and the APIs can cancel them.
+            // Start its worker processes
             if let Err(e) = execution_bridge.start().await {
                 tracing::warn!("Failed to start worker processes: {}", e);
                 tracing::info!("Task execution will not be available until workers are started");
@@ -131,6 +133,18 @@ impl McpEndpointState {
                 task_svc,
                 Arc::new(storage_fact.execution_repository()),
             );
+            let mcp_adapter = match progress_sink {
+                Some(sink) => mcp_adapter.with_progress_sink(sink),
+                None => mcp_adapter,
+            };
+            let mcp_adapter = match log_sink {
+                Some(sink) => mcp_adapter.with_log_sink(sink),
+                None => mcp_adapter,
+            };
+            let mcp_adapter = match secret_store {
+                Some(store) => mcp_adapter.with_secret_store(store),
+                None => mcp_adapter,
+            };
             
             // Configure tool registry with the MCP adapter as task executor
             Arc::new(
@@ -0,0 +1,112 @@
+//! Persistence and broadcasting of execution progress and console output
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use ratchet_api_types::UnifiedExecutionLog;
+use ratchet_core::validation::ErrorSanitizer;
+use ratchet_graphql_api::events::EventBroadcaster;
+use ratchet_interfaces::{ExecutionLogSink, ExecutionProgress, ExecutionProgressSink, RepositoryFactory};
+
+/// Stores progress on the execution row and publishes the updated execution
+/// to GraphQL subscribers
+///
+/// Console output is stored on the same row after redaction by [`ErrorSanitizer`].
+pub struct ExecutionProgressRecorder {
+    repositories: Arc<dyn RepositoryFactory>,
+    event_broadcaster: Arc<EventBroadcaster>,
+    sanitizer: ErrorSanitizer,
+}
+
+impl ExecutionProgressRecorder {
+    /// Create a new progress recorder
+    pub fn new(repositories: Arc<dyn RepositoryFactory>, event_broadcaster: Arc<EventBroadcaster>) -> Self {
+        Self {
+            repositories,
+            event_broadcaster,
+            sanitizer: ErrorSanitizer::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl ExecutionProgressSink for ExecutionProgressRecorder {
+    async fn record_progress(&self, progress: ExecutionProgress) {
+        let Ok(uuid) = Uuid::parse_str(&progress.execution_id) else {
+            warn!("Ignoring progress for invalid execution ID {}", progress.execution_id);
+            return;
+        };
+
+        let execution_repo = self.repositories.execution_repository();
+        let execution = match execution_repo.find_by_uuid(uuid).await {
+            Ok(Some(execution)) => execution,
+            Ok(None) => {
+                // Ad-hoc executions (e.g. from MCP) have no execution row to update
+                debug!("No execution row for progress of execution {}", progress.execution_id);
+                return;
+            }
+            Err(e) => {
+                warn!("Failed to look up execution {}: {}", progress.execution_id, e);
+                return;
+            }
+        };
+
+        if let Err(e) = execution_repo
+            .record_progress(
+                execution.id.clone(),
+                progress.fraction,
+                progress.message.clone(),
+                progress.partial_output.clone(),
+            )
+            .await
+        {
+            warn!("Failed to record progress for execution {}: {}", progress.execution_id, e);
+            return;
+        }
+
+        match execution_repo.find_by_uuid(uuid).await {
+            Ok(Some(updated)) => self.event_broadcaster.broadcast_execution(updated),
+            Ok(None) => {}
+            Err(e) => warn!("Failed to reload execution {}: {}", progress.execution_id, e),
+        }
+    }
+}
+
+#[async_trait]
+impl ExecutionLogSink for ExecutionProgressRecorder {
+    async fn record_logs(&self, execution_id: &str, logs: Vec<UnifiedExecutionLog>) {
+        if logs.is_empty() {
+            return;
+        }
+        let Ok(uuid) = Uuid::parse_str(execution_id) else {
+            warn!("Ignoring logs for invalid execution ID {}", execution_id);
+            return;
+        };
+
+        let execution_repo = self.repositories.execution_repository();
+        let execution = match execution_repo.find_by_uuid(uuid).await {
+            Ok(Some(execution)) => execution,
+            Ok(None) => {
+                debug!("No execution row for logs of execution {}", execution_id);
+                return;
+            }
+            Err(e) => {
+                warn!("Failed to look up execution {}: {}", execution_id, e);
+                return;
+            }
+        };
+
+        let redacted = logs
+            .into_iter()
+            .map(|log| UnifiedExecutionLog {
+                message: self.sanitizer.redact(&log.message),
+                ..log
+            })
+            .collect();
+        if let Err(e) = execution_repo.append_logs(execution.id, redacted).await {
+            warn!("Failed to store logs for execution {}: {}", execution_id, e);
+        }
+    }
+}
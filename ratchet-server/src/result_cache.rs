@@ -0,0 +1,142 @@
+//! Memoization of task results
+//!
+//! A task opts in with a `cache` entry in its metadata, read as a
+//! [`ResultCachePolicy`]. Before running a job of such a task the job processor
+//! looks up its input here and, while a result is fresh, serves it instead of
+//! running the task. Results are keyed on the task UUID.
+
+use async_trait::async_trait;
+use serde_json::Value as JsonValue;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use ratchet_api_types::{ApiId, UnifiedCachedResult};
+use ratchet_caching::config::{CacheType, ResultCacheConfig as CacheStoreConfig};
+use ratchet_caching::result_cache::{CachedResult, ResultCacheKey, ResultCachePolicy};
+use ratchet_caching::ResultCache;
+use ratchet_interfaces::{ResultCacheError, TaskResultCache};
+use ratchet_storage::seaorm::entities::Task;
+use ratchet_storage::seaorm::repositories::TaskRepository;
+
+use crate::config::ResultCacheConfig;
+
+/// Largest output that is cached
+const MAX_RESULT_SIZE: usize = 1024 * 1024;
+
+/// The cache slot of a job of a task that caches its results
+pub struct CacheLookup {
+    key: ResultCacheKey,
+    ttl: Duration,
+    /// Fresh result of an earlier run on the same input, if any
+    pub hit: Option<Arc<CachedResult>>,
+}
+
+/// Result cache shared by the job processor and the cache endpoints
+pub struct TaskResultCacheManager {
+    cache: ResultCache,
+    tasks: TaskRepository,
+}
+
+impl TaskResultCacheManager {
+    /// Create an empty cache
+    pub fn new(config: &ResultCacheConfig, tasks: TaskRepository) -> Self {
+        let cache = ResultCache::from_config(CacheStoreConfig {
+            enabled: config.enabled,
+            max_entries: config.max_entries,
+            max_result_size: MAX_RESULT_SIZE,
+            ttl_seconds: config.max_ttl_seconds,
+            cache_only_success: true,
+            cache_type: CacheType::Moka,
+        });
+        Self { cache, tasks }
+    }
+
+    /// Look up the result of running the task `task_id` on `input`
+    ///
+    /// Returns `None` when the task does not cache its results. Lookup failures
+    /// are logged and treated as a miss, so the job still runs.
+    pub async fn lookup(&self, task_id: &ApiId, input: &JsonValue) -> Option<CacheLookup> {
+        let task = match self.find_task(task_id).await {
+            Ok(task) => task?,
+            Err(e) => {
+                warn!("Failed to look up the cache policy of task {}: {}", task_id, e);
+                return None;
+            }
+        };
+        let policy = ResultCachePolicy::from_task_metadata(&task.metadata)?;
+        let key = policy.key(task.uuid.to_string(), &task.version, input);
+
+        let hit = match self.cache.get(&key).await {
+            Ok(hit) => hit,
+            Err(e) => {
+                warn!("Failed to read the result cache of task {}: {}", task_id, e);
+                None
+            }
+        };
+        Some(CacheLookup {
+            key,
+            ttl: policy.ttl(),
+            hit,
+        })
+    }
+
+    /// Cache the output of a successful run for the TTL of its task
+    pub async fn store(&self, lookup: CacheLookup, execution_uuid: uuid::Uuid, output: JsonValue, duration_ms: u64) {
+        let result = CachedResult::success(execution_uuid, output, duration_ms);
+        match self.cache.put_with_ttl(lookup.key, result, lookup.ttl).await {
+            Ok(()) => debug!("Cached the result of execution {}", execution_uuid),
+            Err(e) => warn!("Failed to cache the result of execution {}: {}", execution_uuid, e),
+        }
+    }
+
+    /// Find a task by ID, UUID or name
+    async fn find_task(&self, task_id: &ApiId) -> Result<Option<Task>, ResultCacheError> {
+        match (task_id.as_i32(), task_id.as_uuid()) {
+            (Some(id), _) => self.tasks.find_by_id(id).await,
+            (None, Some(uuid)) => self.tasks.find_by_uuid(uuid).await,
+            (None, None) => self.tasks.find_by_name(task_id.as_str()).await,
+        }
+        .map_err(|e| ResultCacheError::Storage { message: e.to_string() })
+    }
+
+    async fn require_task(&self, task_id: &ApiId) -> Result<Task, ResultCacheError> {
+        self.find_task(task_id)
+            .await?
+            .ok_or_else(|| ResultCacheError::TaskNotFound {
+                id: task_id.to_string(),
+            })
+    }
+}
+
+#[async_trait]
+impl TaskResultCache for TaskResultCacheManager {
+    async fn entries(&self, task_id: ApiId) -> Result<Vec<UnifiedCachedResult>, ResultCacheError> {
+        let task = self.require_task(&task_id).await?;
+        let entries = self
+            .cache
+            .entries(&task.uuid.to_string())
+            .await
+            .map_err(|e| ResultCacheError::Storage { message: e.to_string() })?;
+        Ok(entries
+            .into_iter()
+            .map(|(key, result)| UnifiedCachedResult {
+                task_version: key.task_version,
+                input_hash: key.input_hash,
+                execution_uuid: result.execution_id,
+                output: result.output.clone(),
+                duration_ms: result.duration_ms as i64,
+                cached_at: result.cached_at,
+                expires_at: result.expires_at,
+            })
+            .collect())
+    }
+
+    async fn purge(&self, task_id: ApiId) -> Result<usize, ResultCacheError> {
+        let task = self.require_task(&task_id).await?;
+        self.cache
+            .clear_task(&task.uuid.to_string())
+            .await
+            .map_err(|e| ResultCacheError::Storage { message: e.to_string() })
+    }
+}
@@ -1,5 +1,6 @@
 //! Scheduler module for task scheduling and execution
 
+pub mod policy;
 pub mod repository_bridge;
 // TODO: Re-enable when tokio-cron-scheduler storage API is properly implemented
 // pub mod sqlite_storage;
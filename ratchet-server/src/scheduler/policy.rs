@@ -0,0 +1,294 @@
+//! Timezone, misfire, jitter and blackout rules of schedules
+//!
+//! Cron expressions are evaluated in the schedule's timezone, so a schedule
+//! that fires at 09:00 keeps doing so across daylight saving changes. Fire
+//! times that fall in a blackout window are dropped.
+
+use chrono::{DateTime, Datelike, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use cron::Schedule;
+use rand::Rng;
+use std::str::FromStr;
+
+use ratchet_api_types::{
+    ScheduleMisfirePolicy, ScheduleOverlapPolicy, UnifiedBlackoutWindow, UnifiedSchedule, UnifiedSchedulePolicy,
+};
+
+/// Missed runs fired by the catch-up misfire policy when the schedule sets no limit
+pub const DEFAULT_CATCH_UP_LIMIT: i32 = 10;
+
+/// Longest random delay a schedule may ask for
+pub const MAX_JITTER_SECONDS: i32 = 3600;
+
+/// Fire times inspected when looking for one outside the blackout windows
+const MAX_BLACKED_OUT_FIRES: usize = 10_000;
+
+/// A schedule's cron expression bound to its timezone and blackout windows
+pub struct ScheduleClock {
+    cron: Schedule,
+    timezone: Tz,
+    blackouts: Vec<Blackout>,
+}
+
+struct Blackout {
+    start: NaiveTime,
+    end: NaiveTime,
+    days: Vec<Weekday>,
+}
+
+impl ScheduleClock {
+    /// Parse the cron expression, timezone and blackout windows of a schedule
+    pub fn new(
+        cron_expression: &str,
+        timezone: &str,
+        blackout_windows: &[UnifiedBlackoutWindow],
+    ) -> Result<Self, String> {
+        let cron =
+            Schedule::from_str(cron_expression).map_err(|e| format!("Invalid cron expression '{}': {}", cron_expression, e))?;
+        let timezone = timezone
+            .parse::<Tz>()
+            .map_err(|_| format!("Unknown timezone '{}'", timezone))?;
+        let blackouts = blackout_windows
+            .iter()
+            .map(Blackout::parse)
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self {
+            cron,
+            timezone,
+            blackouts,
+        })
+    }
+
+    /// Clock of a schedule
+    pub fn for_schedule(schedule: &UnifiedSchedule) -> Result<Self, String> {
+        Self::new(
+            &schedule.cron_expression,
+            &schedule.policy.timezone,
+            &schedule.policy.blackout_windows,
+        )
+    }
+
+    /// Timezone the cron expression is evaluated in
+    pub fn timezone(&self) -> Tz {
+        self.timezone
+    }
+
+    /// Whether `at` falls in one of the blackout windows
+    pub fn is_blacked_out(&self, at: DateTime<Utc>) -> bool {
+        let local = at.with_timezone(&self.timezone);
+        self.blackouts
+            .iter()
+            .any(|blackout| blackout.contains(local.time(), local.weekday()))
+    }
+
+    /// First fire time after `after` that is not blacked out
+    pub fn next_fire(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.cron
+            .after(&after.with_timezone(&self.timezone))
+            .take(MAX_BLACKED_OUT_FIRES)
+            .map(|time| time.with_timezone(&Utc))
+            .find(|time| !self.is_blacked_out(*time))
+    }
+
+    /// Fire times from `from` up to but excluding `until` that are not blacked out, oldest first
+    ///
+    /// Fire times are inspected backwards from `until`, so a long gap yields its latest fires.
+    pub fn fires_between(&self, from: DateTime<Utc>, until: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        let mut fires: Vec<_> = self
+            .cron
+            .after(&until.with_timezone(&self.timezone))
+            .rev()
+            .map(|time| time.with_timezone(&Utc))
+            .take_while(|time| *time >= from)
+            .take(MAX_BLACKED_OUT_FIRES)
+            .filter(|time| !self.is_blacked_out(*time))
+            .collect();
+        fires.reverse();
+        fires
+    }
+}
+
+impl Blackout {
+    fn parse(window: &UnifiedBlackoutWindow) -> Result<Self, String> {
+        let time = |value: &str| {
+            NaiveTime::parse_from_str(value, "%H:%M")
+                .map_err(|_| format!("Blackout window time '{}' is not HH:MM", value))
+        };
+        let days = window
+            .days
+            .iter()
+            .map(|day| Weekday::from_str(day).map_err(|_| format!("Unknown blackout window day '{}'", day)))
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self {
+            start: time(&window.start)?,
+            end: time(&window.end)?,
+            days,
+        })
+    }
+
+    fn starts_on(&self, day: Weekday) -> bool {
+        self.days.is_empty() || self.days.contains(&day)
+    }
+
+    fn contains(&self, time: NaiveTime, day: Weekday) -> bool {
+        if self.start <= self.end {
+            self.starts_on(day) && time >= self.start && time < self.end
+        } else {
+            // The window runs past midnight into the next day
+            (self.starts_on(day) && time >= self.start) || (self.starts_on(day.pred()) && time < self.end)
+        }
+    }
+}
+
+/// Check the policy of a schedule before it is stored
+pub fn validate(schedule: &UnifiedSchedule) -> Result<(), String> {
+    ScheduleClock::for_schedule(schedule)?;
+
+    let policy = &schedule.policy;
+    if !(0..=MAX_JITTER_SECONDS).contains(&policy.jitter_seconds) {
+        return Err(format!("Jitter must be between 0 and {} seconds", MAX_JITTER_SECONDS));
+    }
+    if policy.catch_up_limit.is_some_and(|limit| limit < 1) {
+        return Err("Catch-up limit must be at least 1".to_string());
+    }
+    // Workflow runs cannot wait for or cancel one another
+    if schedule.workflow_id.is_some()
+        && matches!(
+            policy.overlap,
+            ScheduleOverlapPolicy::Queue | ScheduleOverlapPolicy::CancelPrevious
+        )
+    {
+        return Err("Workflow schedules only support the ALLOW and SKIP overlap policies".to_string());
+    }
+    Ok(())
+}
+
+/// Fire times to run on startup for those missed since `next_run`
+pub fn missed_fires(
+    policy: &UnifiedSchedulePolicy,
+    clock: &ScheduleClock,
+    next_run: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Vec<DateTime<Utc>> {
+    let Some(next_run) = next_run.filter(|next_run| *next_run < now) else {
+        return Vec::new();
+    };
+    let mut missed = clock.fires_between(next_run, now);
+    match policy.misfire {
+        ScheduleMisfirePolicy::Skip => Vec::new(),
+        ScheduleMisfirePolicy::FireOnce => missed.pop().into_iter().collect(),
+        ScheduleMisfirePolicy::CatchUp => {
+            let limit = policy.catch_up_limit.unwrap_or(DEFAULT_CATCH_UP_LIMIT).max(1) as usize;
+            missed.split_off(missed.len().saturating_sub(limit))
+        }
+    }
+}
+
+/// Random delay to apply to a run
+pub fn jitter(policy: &UnifiedSchedulePolicy) -> std::time::Duration {
+    if policy.jitter_seconds <= 0 {
+        return std::time::Duration::ZERO;
+    }
+    let millis = rand::thread_rng().gen_range(0..=policy.jitter_seconds as u64 * 1000);
+    std::time::Duration::from_millis(millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn window(start: &str, end: &str, days: &[&str]) -> UnifiedBlackoutWindow {
+        UnifiedBlackoutWindow {
+            start: start.to_string(),
+            end: end.to_string(),
+            days: days.iter().map(|day| day.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_fire_times_follow_daylight_saving() {
+        let clock = ScheduleClock::new("0 0 9 * * *", "Europe/Oslo", &[]).unwrap();
+
+        let winter = clock.next_fire(Utc.with_ymd_and_hms(2025, 1, 15, 0, 0, 0).unwrap()).unwrap();
+        assert_eq!(winter, Utc.with_ymd_and_hms(2025, 1, 15, 8, 0, 0).unwrap());
+
+        let summer = clock.next_fire(Utc.with_ymd_and_hms(2025, 7, 15, 0, 0, 0).unwrap()).unwrap();
+        assert_eq!(summer, Utc.with_ymd_and_hms(2025, 7, 15, 7, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_blackout_windows_skip_fire_times() {
+        // Hourly, but not overnight and not at weekends; 2025-01-17 is a Friday
+        let windows = [window("22:00", "06:00", &[]), window("00:00", "23:59", &["SAT", "SUN"])];
+        let clock = ScheduleClock::new("0 0 * * * *", "UTC", &windows).unwrap();
+
+        assert!(clock.is_blacked_out(Utc.with_ymd_and_hms(2025, 1, 16, 23, 0, 0).unwrap()));
+        assert!(clock.is_blacked_out(Utc.with_ymd_and_hms(2025, 1, 17, 3, 0, 0).unwrap()));
+        assert!(!clock.is_blacked_out(Utc.with_ymd_and_hms(2025, 1, 17, 12, 0, 0).unwrap()));
+
+        let after_friday_evening = Utc.with_ymd_and_hms(2025, 1, 17, 21, 30, 0).unwrap();
+        assert_eq!(
+            clock.next_fire(after_friday_evening),
+            Some(Utc.with_ymd_and_hms(2025, 1, 20, 6, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_missed_fires_follow_misfire_policy() {
+        let clock = ScheduleClock::new("0 0 * * * *", "UTC", &[]).unwrap();
+        let next_run = Some(Utc.with_ymd_and_hms(2025, 1, 15, 0, 0, 0).unwrap());
+        let now = Utc.with_ymd_and_hms(2025, 1, 15, 5, 30, 0).unwrap();
+        let policy = |misfire, catch_up_limit| UnifiedSchedulePolicy {
+            misfire,
+            catch_up_limit,
+            ..UnifiedSchedulePolicy::default()
+        };
+
+        assert!(missed_fires(&policy(ScheduleMisfirePolicy::Skip, None), &clock, next_run, now).is_empty());
+        assert_eq!(
+            missed_fires(&policy(ScheduleMisfirePolicy::FireOnce, None), &clock, next_run, now),
+            vec![Utc.with_ymd_and_hms(2025, 1, 15, 5, 0, 0).unwrap()]
+        );
+        let caught_up = missed_fires(&policy(ScheduleMisfirePolicy::CatchUp, Some(2)), &clock, next_run, now);
+        assert_eq!(
+            caught_up,
+            vec![
+                Utc.with_ymd_and_hms(2025, 1, 15, 4, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 1, 15, 5, 0, 0).unwrap()
+            ]
+        );
+        assert_eq!(
+            missed_fires(&policy(ScheduleMisfirePolicy::CatchUp, None), &clock, next_run, now).len(),
+            6
+        );
+    }
+
+    #[test]
+    fn test_long_outages_catch_up_on_the_latest_fires() {
+        // Two years of hourly fires, more than are inspected
+        let clock = ScheduleClock::new("0 0 * * * *", "UTC", &[]).unwrap();
+        let next_run = Some(Utc.with_ymd_and_hms(2023, 1, 15, 0, 0, 0).unwrap());
+        let now = Utc.with_ymd_and_hms(2025, 1, 15, 5, 30, 0).unwrap();
+        let policy = UnifiedSchedulePolicy {
+            misfire: ScheduleMisfirePolicy::CatchUp,
+            catch_up_limit: Some(2),
+            ..UnifiedSchedulePolicy::default()
+        };
+
+        assert_eq!(
+            missed_fires(&policy, &clock, next_run, now),
+            vec![
+                Utc.with_ymd_and_hms(2025, 1, 15, 4, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 1, 15, 5, 0, 0).unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_policies_are_rejected() {
+        assert!(ScheduleClock::new("0 0 9 * * *", "Mars/Olympus", &[]).is_err());
+        assert!(ScheduleClock::new("0 0 9 * * *", "UTC", &[window("25:00", "06:00", &[])]).is_err());
+        assert!(ScheduleClock::new("0 0 9 * * *", "UTC", &[window("22:00", "06:00", &["FUNDAY"])]).is_err());
+    }
+}
@@ -5,20 +5,35 @@ use chrono::{DateTime, Utc};
 use std::sync::Arc;
 use tracing::{debug, info};
 
-use ratchet_api_types::{ApiId, JobPriority, JobStatus, UnifiedJob, UnifiedSchedule};
+use ratchet_api_types::{
+    ApiId, JobDependencyCondition, JobPriority, JobStatus, UnifiedJob, UnifiedJobDependency, UnifiedSchedule,
+    UnifiedWorkflowRun, WorkflowRunStatus,
+};
 use ratchet_interfaces::RepositoryFactory;
 use ratchet_interfaces::SchedulerError;
+use ratchet_interfaces::WorkflowStore;
 
 /// Bridge between scheduler and repository layer
 /// This ensures the scheduler only accesses data through repository interfaces
+#[derive(Clone)]
 pub struct RepositoryBridge {
     repositories: Arc<dyn RepositoryFactory>,
+    workflows: Option<Arc<dyn WorkflowStore>>,
 }
 
 impl RepositoryBridge {
     /// Create a new repository bridge
     pub fn new(repositories: Arc<dyn RepositoryFactory>) -> Self {
-        Self { repositories }
+        Self {
+            repositories,
+            workflows: None,
+        }
+    }
+
+    /// Queue workflow runs for schedules that target a workflow
+    pub fn with_workflow_store(mut self, workflows: Arc<dyn WorkflowStore>) -> Self {
+        self.workflows = Some(workflows);
+        self
     }
 
     /// Load all enabled schedules from the repository
@@ -37,30 +52,29 @@ impl RepositoryBridge {
     }
 
     /// Create a job for a scheduled execution
+    ///
+    /// With `after` set the job waits until that job has finished, whatever its outcome.
     pub async fn create_job_for_schedule(
         &self,
-        schedule_id: ApiId,
+        schedule: &UnifiedSchedule,
         execution_time: DateTime<Utc>,
+        after: Option<ApiId>,
     ) -> Result<UnifiedJob, SchedulerError> {
-        debug!("Creating job for schedule {}", schedule_id);
-
-        // First, get the schedule to determine the task
-        let schedule = self
-            .repositories
-            .schedule_repository()
-            .find_by_id(schedule_id.as_i32().unwrap_or(0))
-            .await
-            .map_err(|e| SchedulerError::Repository(e.to_string()))?
-            .ok_or(SchedulerError::ScheduleNotFound(schedule_id))?;
+        debug!("Creating job for schedule {}", schedule.id);
 
-        // Extract values before moving schedule
-        let task_id = schedule.task_id.clone();
-        let schedule_name = schedule.name.clone();
+        let depends_on = after
+            .map(|job_id| UnifiedJobDependency {
+                job_id,
+                condition: JobDependencyCondition::Always,
+                pass_output: false,
+            })
+            .into_iter()
+            .collect();
 
         // Create a job for this scheduled execution
         let job = UnifiedJob {
             id: ApiId::from_uuid(uuid::Uuid::new_v4()),
-            task_id,
+            task_id: schedule.task_id.clone(),
             priority: JobPriority::Normal,
             status: JobStatus::Queued,
             retry_count: 0,
@@ -69,6 +83,10 @@ impl RepositoryBridge {
             scheduled_for: Some(execution_time),
             error_message: None,
             output_destinations: schedule.output_destinations.clone(),
+            input: None,
+            depends_on,
+            idempotency_key: None,
+            schedule_id: Some(schedule.id.clone()),
         };
 
         // Store the job through the repository
@@ -81,12 +99,80 @@ impl RepositoryBridge {
 
         info!(
             "Created job {} for schedule {} (task {})",
-            created_job.id, schedule_name, created_job.task_id
+            created_job.id, schedule.name, created_job.task_id
         );
 
         Ok(created_job)
     }
 
+    /// The job a schedule queued last, if it is still waiting to run or running
+    pub async fn find_active_job_for_schedule(&self, schedule_id: &ApiId) -> Result<Option<ApiId>, SchedulerError> {
+        let job = self
+            .repositories
+            .job_repository()
+            .find_latest_for_schedule(schedule_id.clone())
+            .await
+            .map_err(|e| SchedulerError::Repository(e.to_string()))?;
+        Ok(job
+            .filter(|job| {
+                matches!(
+                    job.status,
+                    JobStatus::Queued | JobStatus::Processing | JobStatus::Retrying
+                )
+            })
+            .map(|job| job.id))
+    }
+
+    /// Cancel a job that has not finished
+    pub async fn cancel_job(&self, job_id: ApiId) -> Result<(), SchedulerError> {
+        self.repositories
+            .job_repository()
+            .cancel(job_id)
+            .await
+            .map_err(|e| SchedulerError::Repository(format!("Failed to cancel job: {}", e)))
+    }
+
+    /// The workflow run a schedule queued last, if it is still queued or running
+    pub async fn find_active_workflow_run_for_schedule(
+        &self,
+        schedule_id: &ApiId,
+    ) -> Result<Option<ApiId>, SchedulerError> {
+        let Some(workflows) = self.workflows.as_ref() else {
+            return Ok(None);
+        };
+        let run = workflows
+            .find_latest_scheduled_run(schedule_id.clone())
+            .await
+            .map_err(|e| SchedulerError::Repository(e.to_string()))?;
+        Ok(run
+            .filter(|run| matches!(run.status, WorkflowRunStatus::Queued | WorkflowRunStatus::Running))
+            .map(|run| run.id))
+    }
+
+    /// Queue a run of the workflow a schedule targets
+    pub async fn queue_workflow_run_for_schedule(
+        &self,
+        schedule: &UnifiedSchedule,
+    ) -> Result<UnifiedWorkflowRun, SchedulerError> {
+        let workflow_id = schedule.workflow_id.clone().ok_or_else(|| {
+            SchedulerError::Internal(format!("schedule {} does not target a workflow", schedule.id))
+        })?;
+        let workflows = self.workflows.as_ref().ok_or_else(|| {
+            SchedulerError::Internal("workflow schedules need a workflow store".to_string())
+        })?;
+
+        let run = workflows
+            .queue_run(workflow_id, serde_json::json!({}), Some(schedule.id.clone()))
+            .await
+            .map_err(|e| SchedulerError::Repository(format!("Failed to queue workflow run: {}", e)))?;
+
+        info!(
+            "Queued workflow run {} for schedule {} (workflow {})",
+            run.id, schedule.name, run.workflow_id
+        );
+        Ok(run)
+    }
+
     /// Update schedule execution metadata
     pub async fn update_schedule_execution(
         &self,
@@ -122,6 +208,15 @@ impl RepositoryBridge {
         Ok(())
     }
 
+    /// Store when a schedule fires next without recording a run
+    pub async fn update_next_run(&self, schedule_id: ApiId, next_run: DateTime<Utc>) -> Result<(), SchedulerError> {
+        self.repositories
+            .schedule_repository()
+            .update_next_run(schedule_id, next_run)
+            .await
+            .map_err(|e| SchedulerError::Repository(format!("Failed to update schedule: {}", e)))
+    }
+
     /// Find a schedule by ID
     pub async fn find_schedule(&self, schedule_id: ApiId) -> Result<Option<UnifiedSchedule>, SchedulerError> {
         self.repositories
@@ -33,6 +33,7 @@ impl SqliteMetadataStore {
                 "name": schedule.name,
                 "description": schedule.description,
                 "task_id": schedule.task_id,
+                "workflow_id": schedule.workflow_id,
                 "enabled": schedule.enabled,
                 "last_run": schedule.last_run,
                 "next_run": schedule.next_run,
@@ -52,6 +53,9 @@ impl SqliteMetadataStore {
                 .and_then(|v| v.as_str())
                 .map(|s| ApiId::from_string(s))
                 .ok_or_else(|| SchedulerError::Internal("Missing task_id in job data".to_string()))?,
+            workflow_id: extra.get("workflow_id")
+                .and_then(|v| v.as_str())
+                .map(|s| ApiId::from_string(s)),
             name: extra.get("name")
                 .and_then(|v| v.as_str())
                 .unwrap_or("unknown")
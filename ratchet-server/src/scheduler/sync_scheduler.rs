@@ -12,6 +12,7 @@ use tokio::time::{interval, Duration as TokioDuration, Instant};
 use tracing::{debug, error, info, warn};
 use anyhow::{Context, Result, anyhow};
 
+use crate::cluster::LeaderLease;
 use crate::repository_services::EnhancedRepositoryService;
 use ratchet_storage::repositories::{SyncResult, RepositoryHealth};
 
@@ -104,6 +105,8 @@ pub struct SyncScheduler {
     running: Arc<Mutex<bool>>,
     /// Shutdown signal
     shutdown_tx: Arc<Mutex<Option<tokio::sync::broadcast::Sender<()>>>>,
+    /// Lease deciding which of the servers sharing the database runs scheduled syncs
+    leader: Option<Arc<LeaderLease>>,
 }
 
 impl SyncScheduler {
@@ -120,9 +123,16 @@ impl SyncScheduler {
             sync_history: Arc::new(Mutex::new(Vec::new())),
             running: Arc::new(Mutex::new(false)),
             shutdown_tx: Arc::new(Mutex::new(None)),
+            leader: None,
         }
     }
 
+    /// Only run scheduled syncs while holding `lease`, for servers sharing a database
+    pub fn with_leader_lease(mut self, lease: Arc<LeaderLease>) -> Self {
+        self.leader = Some(lease);
+        self
+    }
+
     /// Start the sync scheduler
     pub async fn start(&self) -> Result<()> {
         {
@@ -145,12 +155,22 @@ impl SyncScheduler {
         // Initialize repository schedules
         self.initialize_schedules().await?;
 
+        if let Some(leader) = &self.leader {
+            leader.renew().await;
+        }
+
         // Start the main scheduler loop
         let scheduler = self.clone();
         let mut shutdown_rx = shutdown_tx.subscribe();
 
         tokio::spawn(async move {
             let mut interval_timer = interval(TokioDuration::from_secs(60)); // Check every minute
+            let mut lease_timer = interval(
+                scheduler
+                    .leader
+                    .as_ref()
+                    .map_or(TokioDuration::from_secs(60), |leader| leader.renew_interval()),
+            );
 
             loop {
                 tokio::select! {
@@ -159,6 +179,11 @@ impl SyncScheduler {
                             error!("Error processing scheduled syncs: {}", e);
                         }
                     }
+                    _ = lease_timer.tick(), if scheduler.leader.is_some() => {
+                        if let Some(leader) = &scheduler.leader {
+                            leader.renew().await;
+                        }
+                    }
                     _ = shutdown_rx.recv() => {
                         info!("Sync scheduler received shutdown signal");
                         break;
@@ -171,6 +196,9 @@ impl SyncScheduler {
                 let mut running = scheduler.running.lock().await;
                 *running = false;
             }
+            if let Some(leader) = &scheduler.leader {
+                leader.release().await;
+            }
 
             info!("Sync scheduler stopped");
         });
@@ -316,6 +344,11 @@ impl SyncScheduler {
 
     /// Process scheduled syncs
     async fn process_scheduled_syncs(&self) -> Result<()> {
+        if self.leader.as_ref().is_some_and(|leader| !leader.is_leader()) {
+            debug!("Not running scheduled syncs: another server is the repository sync leader");
+            return Ok(());
+        }
+
         let now = Utc::now();
         let mut due_syncs = Vec::new();
 
@@ -519,6 +552,7 @@ impl Clone for SyncScheduler {
             sync_history: self.sync_history.clone(),
             running: self.running.clone(),
             shutdown_tx: self.shutdown_tx.clone(),
+            leader: self.leader.clone(),
         }
     }
 }
@@ -1,17 +1,21 @@
 //! tokio-cron-scheduler implementation of the SchedulerService trait
 
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
 use tokio_cron_scheduler::{Job, JobScheduler};
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
+use super::policy::{self, ScheduleClock};
 use super::RepositoryBridge;
-use ratchet_api_types::{ApiId, UnifiedSchedule};
-use ratchet_interfaces::{RepositoryFactory, ScheduleStatus, SchedulerError, SchedulerService};
+use crate::cluster::LeaderLease;
+use ratchet_api_types::{ApiId, ScheduleOverlapPolicy, UnifiedSchedule};
+use ratchet_interfaces::{RepositoryFactory, ScheduleStatus, SchedulerError, SchedulerService, WorkflowStore};
 
 /// Configuration for the tokio-cron-scheduler service
 #[derive(Debug, Clone)]
@@ -34,12 +38,53 @@ impl Default for TokioCronSchedulerConfig {
     }
 }
 
-/// tokio-cron-scheduler implementation of the SchedulerService
-pub struct TokioCronSchedulerService {
+/// What a fire of a schedule started
+#[derive(Debug, Clone)]
+enum ScheduledRun {
+    Job(ApiId),
+    Workflow(ApiId),
+}
+
+/// Lock of each schedule, held by a fire from its overlap check until it has queued its run
+type FireLocks = Arc<Mutex<HashMap<ApiId, Arc<Mutex<()>>>>>;
+
+/// tokio-cron-scheduler job of an active schedule, with the settings it was registered with
+#[derive(Debug, Clone)]
+struct RegisteredJob {
+    uuid: Uuid,
+    cron_expression: String,
+    timezone: String,
+}
+
+impl RegisteredJob {
+    fn matches(&self, schedule: &UnifiedSchedule) -> bool {
+        self.cron_expression == schedule.cron_expression && self.timezone == schedule.policy.timezone
+    }
+}
+
+/// State shared by the service, its fire handlers and its leadership loop
+#[derive(Clone)]
+struct SchedulerState {
     scheduler: Arc<Mutex<JobScheduler>>,
     repository_bridge: Arc<RepositoryBridge>,
+    /// tokio-cron-scheduler job of each active schedule
+    jobs: Arc<Mutex<HashMap<ApiId, RegisteredJob>>>,
+    fire_locks: FireLocks,
+    /// Lease deciding which of the servers sharing the database fires schedules
+    leader: Option<Arc<LeaderLease>>,
+}
+
+/// tokio-cron-scheduler implementation of the SchedulerService
+///
+/// With a leader lease, every server registers every schedule but only the
+/// leader fires them. The servers keep their registrations in line with the
+/// database, so a schedule changed through any of them fires on the leader,
+/// and a server that takes over leadership first runs the fires it missed.
+pub struct TokioCronSchedulerService {
+    state: SchedulerState,
     config: TokioCronSchedulerConfig,
     is_running: AtomicBool,
+    leadership: Mutex<Option<JoinHandle<()>>>,
 }
 
 impl TokioCronSchedulerService {
@@ -63,23 +108,75 @@ impl TokioCronSchedulerService {
         info!("Successfully created tokio-cron-scheduler service");
 
         Ok(Self {
-            scheduler: Arc::new(Mutex::new(scheduler)),
-            repository_bridge,
+            state: SchedulerState {
+                scheduler: Arc::new(Mutex::new(scheduler)),
+                repository_bridge,
+                jobs: Arc::new(Mutex::new(HashMap::new())),
+                fire_locks: Arc::new(Mutex::new(HashMap::new())),
+                leader: None,
+            },
             config,
             is_running: AtomicBool::new(false),
+            leadership: Mutex::new(None),
+        })
+    }
+
+    /// Queue workflow runs for schedules that target a workflow
+    pub fn with_workflow_store(mut self, workflows: Arc<dyn WorkflowStore>) -> Self {
+        let bridge = self.state.repository_bridge.as_ref().clone().with_workflow_store(workflows);
+        self.state.repository_bridge = Arc::new(bridge);
+        self
+    }
+
+    /// Only fire schedules while holding `lease`, for servers sharing a database
+    pub fn with_leader_lease(mut self, lease: Arc<LeaderLease>) -> Self {
+        self.state.leader = Some(lease);
+        self
+    }
+
+    /// Keep renewing the leader lease and the registered schedules until the service stops
+    fn spawn_leadership_loop(&self, leader: Arc<LeaderLease>) -> JoinHandle<()> {
+        let state = self.state.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(leader.renew_interval()).await;
+                let gained = leader.renew().await;
+                if let Err(e) = state.reconcile_schedules().await {
+                    error!("Failed to reload schedules: {}", e);
+                }
+                if gained {
+                    state.run_all_missed_fires().await;
+                }
+            }
         })
     }
+}
+
+impl SchedulerState {
+    /// Whether this server fires schedules
+    fn is_leader(&self) -> bool {
+        self.leader.as_ref().is_none_or(|leader| leader.is_leader())
+    }
 
     /// Create a job execution handler for schedule execution
     fn create_job_execution_handler(&self, schedule_id: ApiId) -> impl Fn(Uuid) + Send + Sync + Clone {
         let bridge = self.repository_bridge.clone();
+        let fire_locks = self.fire_locks.clone();
+        let leader = self.leader.clone();
 
         move |_job_id: Uuid| {
+            if leader.as_ref().is_some_and(|leader| !leader.is_leader()) {
+                debug!("Not firing schedule {}: another server is the scheduler leader", schedule_id);
+                return;
+            }
             let bridge = bridge.clone();
+            let fire_locks = fire_locks.clone();
             let schedule_id_for_exec = schedule_id.clone();
             let schedule_id_for_log = schedule_id.clone();
             tokio::spawn(async move {
-                if let Err(e) = Self::execute_scheduled_job(bridge, schedule_id_for_exec).await {
+                if let Err(e) =
+                    Self::execute_scheduled_job(bridge, fire_locks, schedule_id_for_exec, Utc::now(), true).await
+                {
                     error!(
                         "Failed to execute scheduled job for schedule {}: {}",
                         schedule_id_for_log, e
@@ -90,37 +187,258 @@ impl TokioCronSchedulerService {
     }
 
     /// Execute a scheduled job by creating a job in the repository
-    async fn execute_scheduled_job(bridge: Arc<RepositoryBridge>, schedule_id: ApiId) -> Result<(), SchedulerError> {
-        let execution_time = Utc::now();
-
+    ///
+    /// Fires in a blackout window are dropped, and the overlap policy decides
+    /// what happens while the run of the previous fire is still going. That run
+    /// is the latest the schedule queued, as recorded in the database.
+    async fn execute_scheduled_job(
+        bridge: Arc<RepositoryBridge>,
+        fire_locks: FireLocks,
+        schedule_id: ApiId,
+        execution_time: DateTime<Utc>,
+        apply_jitter: bool,
+    ) -> Result<(), SchedulerError> {
         debug!("Executing scheduled job for schedule: {}", schedule_id);
 
-        // Create job through repository pattern
-        let created_job = bridge
-            .create_job_for_schedule(schedule_id.clone(), execution_time)
-            .await?;
+        let schedule = bridge
+            .find_schedule(schedule_id.clone())
+            .await?
+            .ok_or_else(|| SchedulerError::ScheduleNotFound(schedule_id.clone()))?;
+        if !schedule.enabled {
+            debug!("Schedule {} is disabled, not running it", schedule.name);
+            return Ok(());
+        }
+        let clock = ScheduleClock::for_schedule(&schedule).map_err(SchedulerError::InvalidCron)?;
+        let next_run = clock.next_fire(execution_time);
+
+        if clock.is_blacked_out(execution_time) {
+            info!("Skipping run of schedule {} in a blackout window", schedule.name);
+            if let Some(next_run) = next_run {
+                bridge.update_next_run(schedule_id, next_run).await?;
+            }
+            return Ok(());
+        }
+
+        if apply_jitter {
+            let delay = policy::jitter(&schedule.policy);
+            if !delay.is_zero() {
+                debug!("Delaying run of schedule {} by {:?}", schedule.name, delay);
+                tokio::time::sleep(delay).await;
+            }
+        }
+
+        // Fires of the same schedule must not race each other past the overlap check,
+        // while fires of other schedules go ahead
+        let fire_lock = fire_locks.lock().await.entry(schedule_id.clone()).or_default().clone();
+        let fire = fire_lock.lock().await;
+        let previous = if schedule.workflow_id.is_some() {
+            bridge
+                .find_active_workflow_run_for_schedule(&schedule_id)
+                .await?
+                .map(ScheduledRun::Workflow)
+        } else {
+            bridge
+                .find_active_job_for_schedule(&schedule_id)
+                .await?
+                .map(ScheduledRun::Job)
+        };
+
+        let mut after = None;
+        match (schedule.policy.overlap, previous) {
+            (_, None) | (ScheduleOverlapPolicy::Allow, _) => {}
+            (ScheduleOverlapPolicy::Skip, Some(_)) => {
+                info!("Skipping run of schedule {}: its previous run is still going", schedule.name);
+                drop(fire);
+                if let Some(next_run) = next_run {
+                    bridge.update_next_run(schedule_id, next_run).await?;
+                }
+                return Ok(());
+            }
+            (ScheduleOverlapPolicy::Queue, Some(ScheduledRun::Job(id))) => after = Some(id),
+            (ScheduleOverlapPolicy::CancelPrevious, Some(ScheduledRun::Job(id))) => {
+                info!("Cancelling job {} of schedule {} for a new run", id, schedule.name);
+                bridge.cancel_job(id).await?;
+            }
+            (_, Some(ScheduledRun::Workflow(id))) => {
+                warn!(
+                    "Workflow run {} of schedule {} is still going; overlapping it as workflow runs cannot be queued or cancelled",
+                    id, schedule.name
+                );
+            }
+        }
+
+        // Workflow schedules queue a run of the workflow instead of a job
+        let run = if schedule.workflow_id.is_some() {
+            ScheduledRun::Workflow(bridge.queue_workflow_run_for_schedule(&schedule).await?.id)
+        } else {
+            ScheduledRun::Job(bridge.create_job_for_schedule(&schedule, execution_time, after).await?.id)
+        };
+        drop(fire);
 
-        // Update schedule execution metadata
-        // Note: We don't have next_run info here, tokio-cron-scheduler handles that internally
         bridge
-            .update_schedule_execution(schedule_id.clone(), execution_time, None)
+            .update_schedule_execution(schedule_id.clone(), execution_time, next_run)
             .await?;
 
         info!(
-            "Successfully executed scheduled job for schedule {}, created_job_id={}",
-            schedule_id, created_job.id
+            "Successfully executed scheduled job for schedule {}, created {:?}",
+            schedule_id, run
         );
 
         Ok(())
     }
 
+    /// Register a schedule with tokio-cron-scheduler, evaluating its cron expression in its timezone
+    async fn schedule_job(&self, schedule: &UnifiedSchedule) -> Result<(), SchedulerError> {
+        let clock = ScheduleClock::for_schedule(schedule).map_err(|e| {
+            error!("Failed to create job for schedule {}: {}", schedule.name, e);
+            SchedulerError::InvalidCron(e)
+        })?;
+
+        // Create job with our execution handler
+        let cron_expression = schedule.cron_expression.clone();
+        let execution_handler = self.create_job_execution_handler(schedule.id.clone());
+
+        let job = Job::new_async_tz(cron_expression.as_str(), clock.timezone(), move |uuid, _| {
+            execution_handler(uuid);
+            Box::pin(async {})
+        })
+        .map_err(|e| {
+            error!("Failed to create job for schedule {}: {}", schedule.name, e);
+            SchedulerError::InvalidCron(format!("Invalid cron expression '{}': {}", cron_expression, e))
+        })?;
+
+        // Add job to scheduler
+        let scheduler = self.scheduler.lock().await;
+        let job_uuid = scheduler.add(job).await.map_err(|e| {
+            error!("Failed to add job to scheduler: {}", e);
+            SchedulerError::Internal(format!("Failed to add job to scheduler: {}", e))
+        })?;
+        let registered = RegisteredJob {
+            uuid: job_uuid,
+            cron_expression: schedule.cron_expression.clone(),
+            timezone: schedule.policy.timezone.clone(),
+        };
+
+        // A concurrent reload may have registered the schedule in the meantime
+        if let Some(previous) = self.jobs.lock().await.insert(schedule.id.clone(), registered) {
+            if let Err(e) = scheduler.remove(&previous.uuid).await {
+                warn!("Failed to remove duplicate job of schedule {}: {}", schedule.name, e);
+            }
+        }
+
+        info!("Successfully added schedule to scheduler: {}", schedule.name);
+        Ok(())
+    }
+
+    /// Remove the tokio-cron-scheduler job of a schedule, if it has one
+    async fn unschedule_job(&self, schedule_id: &ApiId) -> Result<bool, SchedulerError> {
+        let Some(registered) = self.jobs.lock().await.remove(schedule_id) else {
+            return Ok(false);
+        };
+        self.fire_locks.lock().await.remove(schedule_id);
+
+        let scheduler = self.scheduler.lock().await;
+        scheduler.remove(&registered.uuid).await.map_err(|e| {
+            error!("Failed to remove job from scheduler: {}", e);
+            SchedulerError::Internal(format!("Failed to remove job from scheduler: {}", e))
+        })?;
+        Ok(true)
+    }
+
+    /// Run the fires a schedule missed while the server was down, as its misfire policy allows
+    async fn run_missed_fires(&self, schedule: &UnifiedSchedule) -> Result<(), SchedulerError> {
+        let clock = ScheduleClock::for_schedule(schedule).map_err(SchedulerError::InvalidCron)?;
+        let now = Utc::now();
+
+        let missed = policy::missed_fires(&schedule.policy, &clock, schedule.next_run, now);
+        if !missed.is_empty() {
+            info!(
+                "Running {} missed fire(s) of schedule {} ({:?})",
+                missed.len(),
+                schedule.name,
+                schedule.policy.misfire
+            );
+        }
+        for fire_time in missed {
+            Self::execute_scheduled_job(
+                self.repository_bridge.clone(),
+                self.fire_locks.clone(),
+                schedule.id.clone(),
+                fire_time,
+                false,
+            )
+            .await?;
+        }
+
+        if let Some(next_run) = clock.next_fire(now) {
+            self.repository_bridge
+                .update_next_run(schedule.id.clone(), next_run)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Run the missed fires of every enabled schedule, when taking over as leader
+    async fn run_all_missed_fires(&self) {
+        let schedules = match self.repository_bridge.load_all_schedules().await {
+            Ok(schedules) => schedules,
+            Err(e) => {
+                error!("Failed to load schedules to catch up on: {}", e);
+                return;
+            }
+        };
+        for schedule in schedules.iter().filter(|schedule| schedule.enabled) {
+            if let Err(e) = self.run_missed_fires(schedule).await {
+                error!("Failed to run missed fires of schedule {}: {}", schedule.name, e);
+            }
+        }
+    }
+
+    /// Bring the registered schedules in line with the repository
+    ///
+    /// Picks up schedules created, changed or deleted through other servers.
+    async fn reconcile_schedules(&self) -> Result<(), SchedulerError> {
+        let schedules: HashMap<ApiId, UnifiedSchedule> = self
+            .repository_bridge
+            .load_all_schedules()
+            .await?
+            .into_iter()
+            .filter(|schedule| schedule.enabled)
+            .map(|schedule| (schedule.id.clone(), schedule))
+            .collect();
+
+        let stale: Vec<ApiId> = self
+            .jobs
+            .lock()
+            .await
+            .iter()
+            .filter(|(id, registered)| !schedules.get(*id).is_some_and(|schedule| registered.matches(schedule)))
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in stale {
+            debug!("Schedule {} changed or was removed elsewhere", id);
+            self.unschedule_job(&id).await?;
+        }
+
+        for schedule in schedules.values() {
+            if !self.jobs.lock().await.contains_key(&schedule.id) {
+                debug!("Registering schedule {} changed elsewhere", schedule.name);
+                self.schedule_job(schedule).await?;
+            }
+        }
+        Ok(())
+    }
+
     /// Load existing schedules from the repository and add them to the scheduler
+    ///
+    /// Only the leader runs the fires they missed.
     async fn load_existing_schedules(&self) -> Result<(), SchedulerError> {
         info!("Loading existing schedules from repository");
 
         let schedules = self.repository_bridge.load_all_schedules().await?;
         info!("Found {} existing schedules to load", schedules.len());
 
+        self.jobs.lock().await.clear();
         for schedule in schedules {
             if !schedule.enabled {
                 debug!("Skipping disabled schedule: {}", schedule.name);
@@ -128,32 +446,16 @@ impl TokioCronSchedulerService {
             }
 
             debug!(
-                "Adding schedule to tokio-cron-scheduler: {} ({})",
-                schedule.name, schedule.cron_expression
+                "Adding schedule to tokio-cron-scheduler: {} ({}, {})",
+                schedule.name, schedule.cron_expression, schedule.policy.timezone
             );
 
-            // Create job with our execution handler
-            let schedule_id = schedule.id.clone();
-            let cron_expression = schedule.cron_expression.clone();
-            let execution_handler = self.create_job_execution_handler(schedule_id);
-
-            let job = Job::new_async(cron_expression.as_str(), move |uuid, _| {
-                execution_handler(uuid);
-                Box::pin(async {})
-            })
-            .map_err(|e| {
-                error!("Failed to create job for schedule {}: {}", schedule.name, e);
-                SchedulerError::InvalidCron(format!("Invalid cron expression '{}': {}", cron_expression, e))
-            })?;
-
-            // Add job to scheduler
-            let scheduler = self.scheduler.lock().await;
-            scheduler.add(job).await.map_err(|e| {
-                error!("Failed to add job to scheduler: {}", e);
-                SchedulerError::Internal(format!("Failed to add job to scheduler: {}", e))
-            })?;
-
-            info!("Successfully added schedule to scheduler: {}", schedule.name);
+            if self.is_leader() {
+                if let Err(e) = self.run_missed_fires(&schedule).await {
+                    error!("Failed to run missed fires of schedule {}: {}", schedule.name, e);
+                }
+            }
+            self.schedule_job(&schedule).await?;
         }
 
         Ok(())
@@ -175,7 +477,7 @@ impl SchedulerService for TokioCronSchedulerService {
         // This approach avoids the "Error receiving Closed" messages that occur
         // when shutting down an existing scheduler
         {
-            let mut scheduler_guard = self.scheduler.lock().await;
+            let mut scheduler_guard = self.state.scheduler.lock().await;
 
             // Check if the current scheduler is initialized
             if scheduler_guard.inited().await {
@@ -196,12 +498,18 @@ impl SchedulerService for TokioCronSchedulerService {
             }
         }
 
+        // Find out whether this server fires the schedules before catching up on missed fires
+        if let Some(leader) = &self.state.leader {
+            leader.renew().await;
+            *self.leadership.lock().await = Some(self.spawn_leadership_loop(leader.clone()));
+        }
+
         // Load existing schedules from repository
-        self.load_existing_schedules().await?;
+        self.state.load_existing_schedules().await?;
 
         // Start the scheduler
         {
-            let scheduler = self.scheduler.lock().await;
+            let scheduler = self.state.scheduler.lock().await;
             scheduler.start().await.map_err(|e| {
                 error!("Failed to start scheduler: {}", e);
                 SchedulerError::Internal(format!("Failed to start scheduler: {}", e))
@@ -223,7 +531,14 @@ impl SchedulerService for TokioCronSchedulerService {
 
         info!("Stopping tokio-cron-scheduler service");
 
-        let mut scheduler = self.scheduler.lock().await;
+        if let Some(handle) = self.leadership.lock().await.take() {
+            handle.abort();
+        }
+        if let Some(leader) = &self.state.leader {
+            leader.release().await;
+        }
+
+        let mut scheduler = self.state.scheduler.lock().await;
         scheduler.shutdown().await.map_err(|e| {
             error!("Failed to stop scheduler: {}", e);
             SchedulerError::Internal(format!("Failed to stop scheduler: {}", e))
@@ -250,44 +565,19 @@ impl SchedulerService for TokioCronSchedulerService {
             return Ok(());
         }
 
-        // Create job with our execution handler
-        let schedule_id = schedule.id.clone();
-        let cron_expression = schedule.cron_expression.clone();
-        let execution_handler = self.create_job_execution_handler(schedule_id);
-
-        let job = Job::new_async(cron_expression.as_str(), move |uuid, _| {
-            execution_handler(uuid);
-            Box::pin(async {})
-        })
-        .map_err(|e| {
-            error!("Failed to create job for schedule {}: {}", schedule.name, e);
-            SchedulerError::InvalidCron(format!("Invalid cron expression '{}': {}", cron_expression, e))
-        })?;
-
-        // Add job to scheduler
-        let scheduler = self.scheduler.lock().await;
-        scheduler.add(job).await.map_err(|e| {
-            error!("Failed to add job to scheduler: {}", e);
-            SchedulerError::Internal(format!("Failed to add job to scheduler: {}", e))
-        })?;
-
-        info!("Successfully added schedule to scheduler: {}", schedule.name);
-        Ok(())
+        // Enabling a schedule twice must not make it fire twice
+        self.remove_schedule(schedule.id.clone()).await?;
+        self.state.schedule_job(&schedule).await
     }
 
     /// Remove a schedule from the scheduler
     async fn remove_schedule(&self, schedule_id: ApiId) -> Result<(), SchedulerError> {
         info!("Removing schedule from scheduler: {}", schedule_id);
 
-        let job_uuid = schedule_id
-            .as_uuid()
-            .ok_or_else(|| SchedulerError::Internal(format!("Cannot convert schedule_id to UUID: {}", schedule_id)))?;
-
-        let scheduler = self.scheduler.lock().await;
-        scheduler.remove(&job_uuid).await.map_err(|e| {
-            error!("Failed to remove job from scheduler: {}", e);
-            SchedulerError::Internal(format!("Failed to remove job from scheduler: {}", e))
-        })?;
+        if !self.state.unschedule_job(&schedule_id).await? {
+            debug!("Schedule {} has no job in the scheduler", schedule_id);
+            return Ok(());
+        }
 
         info!("Successfully removed schedule from scheduler: {}", schedule_id);
         Ok(())
@@ -313,6 +603,7 @@ impl SchedulerService for TokioCronSchedulerService {
         let schedule_id_clone = schedule_id.clone();
         // Get schedule from repository
         let schedule = self
+            .state
             .repository_bridge
             .find_schedule(schedule_id)
             .await?
@@ -339,7 +630,98 @@ impl SchedulerService for TokioCronSchedulerService {
     /// Get the number of active schedules
     async fn schedule_count(&self) -> Result<usize, SchedulerError> {
         // Get count from repository since tokio-cron-scheduler doesn't expose this directly
-        let schedules = self.repository_bridge.load_all_schedules().await?;
+        let schedules = self.state.repository_bridge.load_all_schedules().await?;
         Ok(schedules.len())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::DirectRepositoryFactory;
+    use ratchet_api_types::{JobStatus, UnifiedSchedulePolicy};
+    use ratchet_storage::seaorm::repositories::{RepositoryFactory as StorageFactory, TaskRepository};
+    use ratchet_storage::seaorm::{config::DatabaseConfig, connection::DatabaseConnection};
+    use ratchet_storage::testing::TaskBuilder;
+    use std::time::Duration;
+
+    async fn create_schedule(
+        db: &DatabaseConnection,
+        bridge: &RepositoryBridge,
+        overlap: ScheduleOverlapPolicy,
+    ) -> ApiId {
+        let now = Utc::now();
+        let task = TaskRepository::new(db.clone())
+            .create(TaskBuilder::new().with_name("scheduled").build())
+            .await
+            .unwrap();
+
+        bridge
+            .create_schedule(UnifiedSchedule {
+                id: ApiId::from_i32(0),
+                task_id: ApiId::from_i32(task.id),
+                workflow_id: None,
+                name: "overlapping".to_string(),
+                description: None,
+                cron_expression: "0 0 * * * *".to_string(),
+                enabled: true,
+                next_run: None,
+                last_run: None,
+                created_at: now,
+                updated_at: now,
+                output_destinations: None,
+                policy: UnifiedSchedulePolicy {
+                    overlap,
+                    ..Default::default()
+                },
+            })
+            .await
+            .unwrap()
+            .id
+    }
+
+    /// Fire a schedule as a freshly started server would, knowing nothing of earlier fires
+    async fn fire(bridge: &Arc<RepositoryBridge>, schedule_id: &ApiId) {
+        let fire_locks: FireLocks = Arc::new(Mutex::new(HashMap::new()));
+        SchedulerState::execute_scheduled_job(bridge.clone(), fire_locks, schedule_id.clone(), Utc::now(), false)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_overlap_policy_sees_runs_queued_before_a_restart() {
+        let db = DatabaseConnection::new(DatabaseConfig {
+            url: "sqlite::memory:".to_string(),
+            max_connections: 1,
+            connection_timeout: Duration::from_secs(10),
+        })
+        .await
+        .unwrap();
+        db.migrate().await.unwrap();
+        let repositories: Arc<dyn RepositoryFactory> =
+            Arc::new(DirectRepositoryFactory::new(Arc::new(StorageFactory::new(db.clone()))));
+        let bridge = Arc::new(RepositoryBridge::new(repositories.clone()));
+        let jobs = repositories.job_repository();
+
+        let skipping = create_schedule(&db, &bridge, ScheduleOverlapPolicy::Skip).await;
+        fire(&bridge, &skipping).await;
+        fire(&bridge, &skipping).await;
+        let first = jobs.find_latest_for_schedule(skipping.clone()).await.unwrap().unwrap();
+        assert_eq!(jobs.find_by_status(JobStatus::Queued).await.unwrap().len(), 1);
+
+        let queueing = create_schedule(&db, &bridge, ScheduleOverlapPolicy::Queue).await;
+        fire(&bridge, &queueing).await;
+        let previous = jobs.find_latest_for_schedule(queueing.clone()).await.unwrap().unwrap();
+        fire(&bridge, &queueing).await;
+        let queued = jobs.find_latest_for_schedule(queueing).await.unwrap().unwrap();
+        assert_ne!(queued.id, previous.id);
+        assert_eq!(queued.depends_on.len(), 1);
+        assert_eq!(queued.depends_on[0].job_id, previous.id);
+
+        // Once the previous run has finished, the schedule fires again
+        jobs.mark_completed(first.id.clone()).await.unwrap();
+        fire(&bridge, &skipping).await;
+        let next = jobs.find_latest_for_schedule(skipping).await.unwrap().unwrap();
+        assert_ne!(next.id, first.id);
+    }
+}
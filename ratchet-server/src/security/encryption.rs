@@ -120,6 +120,49 @@ impl AesEncryptionService {
         Ok(())
     }
 
+    /// Initialize with an externally managed key instead of a generated one
+    ///
+    /// The key ID is derived from the key itself, so data encrypted by an earlier
+    /// process with the same key can still be decrypted.
+    pub async fn initialize_with_key(&self, key: Vec<u8>) -> Result<()> {
+        let expected_len = match self.default_algorithm {
+            EncryptionAlgorithm::AES128 => 16,
+            EncryptionAlgorithm::AES256 | EncryptionAlgorithm::ChaCha20 => 32,
+            EncryptionAlgorithm::RSA2048 | EncryptionAlgorithm::RSA4096 => {
+                return Err(anyhow::anyhow!("RSA keys cannot be imported"));
+            }
+        };
+        if key.len() != expected_len {
+            return Err(anyhow::anyhow!(
+                "Expected a {} byte key for {:?}, got {} bytes",
+                expected_len,
+                self.default_algorithm,
+                key.len()
+            ));
+        }
+
+        let digest = format!("{:x}", Sha256::digest(&key));
+        let key_id = format!("key_{}", &digest[..16]);
+
+        let metadata = EncryptionKeyMetadata {
+            key_id: key_id.clone(),
+            algorithm: self.default_algorithm.clone(),
+            created_at: Utc::now(),
+            last_used_at: None,
+            rotated_at: None,
+            status: KeyStatus::Active,
+            usage_count: 0,
+        };
+
+        let mut keys = self.keys.write().await;
+        keys.insert(self.default_algorithm.clone(), metadata);
+
+        let mut storage = self.key_storage.write().await;
+        storage.insert(key_id, key);
+
+        Ok(())
+    }
+
     /// Get the current active key for an algorithm
     async fn get_active_key(&self, algorithm: &EncryptionAlgorithm) -> Result<(String, Vec<u8>)> {
         let keys = self.keys.read().await;
@@ -490,6 +533,21 @@ mod tests {
         assert_eq!(data, decrypted.as_slice());
     }
 
+    #[tokio::test]
+    async fn test_imported_key_survives_restart() {
+        let key = vec![7u8; 32];
+
+        let first = AesEncryptionService::new(EncryptionAlgorithm::AES256);
+        first.initialize_with_key(key.clone()).await.unwrap();
+        let encrypted = first.encrypt(b"persisted").await.unwrap();
+
+        let second = AesEncryptionService::new(EncryptionAlgorithm::AES256);
+        second.initialize_with_key(key).await.unwrap();
+        assert_eq!(second.decrypt(&encrypted).await.unwrap(), b"persisted");
+
+        assert!(second.initialize_with_key(vec![0u8; 8]).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_chacha20_encryption_service() {
         let service = AesEncryptionService::new(EncryptionAlgorithm::ChaCha20);
@@ -7,6 +7,7 @@ pub mod credential_manager;
 pub mod encryption;
 pub mod audit_logger;
 pub mod access_control;
+pub mod secret_manager;
 
 #[cfg(test)]
 pub mod tests;
@@ -15,6 +16,7 @@ pub use credential_manager::*;
 pub use encryption::*;
 pub use audit_logger::*;
 pub use access_control::*;
+pub use secret_manager::*;
 
 use anyhow::Result;
 use chrono::{DateTime, Utc};
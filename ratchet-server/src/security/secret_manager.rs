@@ -0,0 +1,289 @@
+//! Encrypted task secrets
+//!
+//! This module stores the secrets tasks read through `ratchet.secrets.get`.
+//! Values are encrypted with an [`EncryptionService`] before they reach the
+//! database and are only decrypted when an execution that declared them starts.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use ratchet_api_types::{ApiId, SecretScope, UnifiedSecret};
+use ratchet_interfaces::{SecretError, SecretKey, SecretStore};
+use ratchet_storage::seaorm::entities::{SecretScope as StoredSecretScope, TaskSecret};
+use ratchet_storage::seaorm::repositories::TaskSecretRepository;
+
+use super::{AesEncryptionService, EncryptionService};
+use crate::config::EncryptionAlgorithm;
+
+/// Environment variable holding the base64 encoded 256-bit key secrets are encrypted with
+pub const SECRETS_KEY_ENV: &str = "RATCHET_SECRETS_KEY";
+
+/// Maximum length of a secret name
+const MAX_SECRET_NAME_LEN: usize = 128;
+
+/// Create the encryption service for task secrets
+///
+/// Uses the key in [`SECRETS_KEY_ENV`] when set. Otherwise a random key is
+/// generated, and secrets stored by this process cannot be read after a restart.
+pub async fn secrets_encryption_service() -> Result<Arc<AesEncryptionService>> {
+    let service = AesEncryptionService::new(EncryptionAlgorithm::AES256);
+
+    match std::env::var(SECRETS_KEY_ENV) {
+        Ok(encoded) => {
+            let key = general_purpose::STANDARD
+                .decode(encoded.trim())
+                .with_context(|| format!("{} is not valid base64", SECRETS_KEY_ENV))?;
+            service
+                .initialize_with_key(key)
+                .await
+                .with_context(|| format!("{} is not a valid key", SECRETS_KEY_ENV))?;
+        }
+        Err(_) => {
+            tracing::warn!(
+                "{} is not set; task secrets are encrypted with a temporary key and will be unreadable after a restart",
+                SECRETS_KEY_ENV
+            );
+            service.initialize().await?;
+        }
+    }
+
+    Ok(Arc::new(service))
+}
+
+/// Secret store backed by the `task_secrets` table
+pub struct SecretManager {
+    repository: TaskSecretRepository,
+    encryption: Arc<dyn EncryptionService>,
+}
+
+impl SecretManager {
+    /// Create a new secret manager
+    pub fn new(repository: TaskSecretRepository, encryption: Arc<dyn EncryptionService>) -> Self {
+        Self { repository, encryption }
+    }
+
+    /// Check that a secret name can be used as a lookup key from JavaScript
+    fn validate_name(name: &str) -> Result<(), SecretError> {
+        let valid = !name.is_empty()
+            && name.len() <= MAX_SECRET_NAME_LEN
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+        if valid {
+            Ok(())
+        } else {
+            Err(SecretError::Invalid {
+                message: format!(
+                    "secret names must be 1-{} characters of letters, digits, '_', '-' or '.'",
+                    MAX_SECRET_NAME_LEN
+                ),
+            })
+        }
+    }
+
+    /// Storage representation of a key: the stored scope and a non-null scope ID
+    fn storage_key(key: &SecretKey) -> Result<(StoredSecretScope, String), SecretError> {
+        Self::validate_name(&key.name)?;
+
+        let scope_id = key.scope_id.clone().unwrap_or_default();
+        let (scope, required) = match key.scope {
+            SecretScope::Global => return Ok((StoredSecretScope::Global, String::new())),
+            SecretScope::Repository => (StoredSecretScope::Repository, "repository secrets need a repository ID"),
+            SecretScope::Task => (StoredSecretScope::Task, "task secrets need a task UUID"),
+        };
+        if scope_id.is_empty() {
+            return Err(SecretError::Invalid {
+                message: required.to_string(),
+            });
+        }
+        Ok((scope, scope_id))
+    }
+
+    fn to_unified(secret: TaskSecret) -> UnifiedSecret {
+        UnifiedSecret {
+            id: ApiId::from_i32(secret.id),
+            name: secret.name,
+            scope: match secret.scope {
+                StoredSecretScope::Global => SecretScope::Global,
+                StoredSecretScope::Repository => SecretScope::Repository,
+                StoredSecretScope::Task => SecretScope::Task,
+            },
+            scope_id: Some(secret.scope_id).filter(|scope_id| !scope_id.is_empty()),
+            description: secret.description,
+            created_at: secret.created_at,
+            updated_at: secret.updated_at,
+        }
+    }
+
+    async fn encrypt(&self, value: &str) -> Result<String, SecretError> {
+        let encrypted = self
+            .encryption
+            .encrypt(value.as_bytes())
+            .await
+            .map_err(|e| SecretError::Encryption { message: e.to_string() })?;
+        Ok(general_purpose::STANDARD.encode(encrypted))
+    }
+
+    async fn decrypt(&self, secret: &TaskSecret) -> Result<String, SecretError> {
+        let encrypted = general_purpose::STANDARD
+            .decode(&secret.encrypted_value)
+            .map_err(|e| SecretError::Encryption { message: e.to_string() })?;
+        let decrypted = self
+            .encryption
+            .decrypt(&encrypted)
+            .await
+            .map_err(|e| SecretError::Encryption {
+                message: format!("cannot decrypt secret {}: {}", secret.name, e),
+            })?;
+        String::from_utf8(decrypted).map_err(|e| SecretError::Encryption { message: e.to_string() })
+    }
+}
+
+fn storage_error(e: ratchet_storage::seaorm::DatabaseError) -> SecretError {
+    SecretError::Storage { message: e.to_string() }
+}
+
+#[async_trait]
+impl SecretStore for SecretManager {
+    async fn set_secret(
+        &self,
+        key: SecretKey,
+        value: &str,
+        description: Option<String>,
+    ) -> Result<UnifiedSecret, SecretError> {
+        let (scope, scope_id) = Self::storage_key(&key)?;
+        let encrypted = self.encrypt(value).await?;
+
+        let secret = self
+            .repository
+            .upsert(&key.name, scope, &scope_id, encrypted, description)
+            .await
+            .map_err(storage_error)?;
+        Ok(Self::to_unified(secret))
+    }
+
+    async fn list_secrets(&self, scope: Option<SecretScope>) -> Result<Vec<UnifiedSecret>, SecretError> {
+        let scope = scope.map(|scope| match scope {
+            SecretScope::Global => StoredSecretScope::Global,
+            SecretScope::Repository => StoredSecretScope::Repository,
+            SecretScope::Task => StoredSecretScope::Task,
+        });
+        let secrets = self.repository.list(scope).await.map_err(storage_error)?;
+        Ok(secrets.into_iter().map(Self::to_unified).collect())
+    }
+
+    async fn delete_secret(&self, key: SecretKey) -> Result<bool, SecretError> {
+        let (scope, scope_id) = Self::storage_key(&key)?;
+        self.repository
+            .delete(scope, &scope_id, &key.name)
+            .await
+            .map_err(storage_error)
+    }
+
+    async fn resolve_secrets(
+        &self,
+        names: &[String],
+        task_uuid: Option<&str>,
+        repository_id: Option<&str>,
+    ) -> Result<HashMap<String, String>, SecretError> {
+        let visible = self
+            .repository
+            .find_visible(names, task_uuid, repository_id)
+            .await
+            .map_err(storage_error)?;
+
+        // The most specific scope wins when a name exists in several scopes
+        let mut selected: HashMap<String, TaskSecret> = HashMap::new();
+        for secret in visible {
+            let replace = selected
+                .get(&secret.name)
+                .is_none_or(|current| secret.scope.precedence() > current.scope.precedence());
+            if replace {
+                selected.insert(secret.name.clone(), secret);
+            }
+        }
+
+        let missing: Vec<&str> = names
+            .iter()
+            .filter(|name| !selected.contains_key(name.as_str()))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return Err(SecretError::NotFound { name: missing.join(", ") });
+        }
+
+        let mut values = HashMap::with_capacity(selected.len());
+        for (name, secret) in selected {
+            values.insert(name, self.decrypt(&secret).await?);
+        }
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratchet_storage::seaorm::{config::DatabaseConfig, connection::DatabaseConnection};
+    use std::time::Duration;
+
+    async fn create_manager() -> SecretManager {
+        let db = DatabaseConnection::new(DatabaseConfig {
+            url: "sqlite::memory:".to_string(),
+            max_connections: 5,
+            connection_timeout: Duration::from_secs(10),
+        })
+        .await
+        .unwrap();
+        db.migrate().await.unwrap();
+
+        let encryption = AesEncryptionService::new(EncryptionAlgorithm::AES256);
+        encryption.initialize().await.unwrap();
+        SecretManager::new(TaskSecretRepository::new(db), Arc::new(encryption))
+    }
+
+    #[tokio::test]
+    async fn test_values_are_encrypted_and_resolved_by_scope() {
+        let manager = create_manager().await;
+        manager
+            .set_secret(SecretKey::global("API_KEY"), "global-value", None)
+            .await
+            .unwrap();
+        manager
+            .set_secret(
+                SecretKey::new("API_KEY", SecretScope::Task, Some("task-1".to_string())),
+                "task-value",
+                Some("Override for one task".to_string()),
+            )
+            .await
+            .unwrap();
+
+        let stored = manager.repository.list(None).await.unwrap();
+        assert!(stored.iter().all(|secret| !secret.encrypted_value.contains("value")));
+
+        let names = vec!["API_KEY".to_string()];
+        let resolved = manager.resolve_secrets(&names, Some("task-1"), None).await.unwrap();
+        assert_eq!(resolved["API_KEY"], "task-value");
+        let resolved = manager.resolve_secrets(&names, Some("task-2"), None).await.unwrap();
+        assert_eq!(resolved["API_KEY"], "global-value");
+
+        let missing = manager
+            .resolve_secrets(&["OTHER".to_string()], Some("task-1"), None)
+            .await;
+        assert!(matches!(missing, Err(SecretError::NotFound { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_keys_are_rejected() {
+        let manager = create_manager().await;
+        let bad_name = manager.set_secret(SecretKey::global("has space"), "value", None).await;
+        assert!(matches!(bad_name, Err(SecretError::Invalid { .. })));
+
+        let missing_scope_id = manager
+            .set_secret(SecretKey::new("TOKEN", SecretScope::Repository, None), "value", None)
+            .await;
+        assert!(matches!(missing_scope_id, Err(SecretError::Invalid { .. })));
+    }
+}
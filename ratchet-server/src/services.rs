@@ -1,33 +1,46 @@
 //! Service implementations and dependency injection setup
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use ratchet_interfaces::{
-    CrudRepository, DatabaseError, ExecutionFilters, ExecutionRepository, FilteredRepository, JobFilters,
+    CrudRepository, DatabaseError, ExecutionFilters, ExecutionRepository, FilteredRepository, JobCreation, JobFilters,
     JobRepository, RegistryError, RegistryManager, Repository, RepositoryFactory, ScheduleFilters, ScheduleRepository,
-    SyncResult, TaskFilters, TaskMetadata, TaskRegistry, TaskRepository, TaskService, TaskValidator, ValidationResult,
+    SecretStore, SyncResult, TaskFilters, TaskMetadata, TaskRegistry, TaskRepository, TaskService, TaskValidator, ValidationResult,
+    OutputDeliveryStore, TaskResultCache, TriggerStore, WorkflowStore,
 };
 // Import storage repository trait for health checks (unused for now)
 // use ratchet_storage::seaorm::repositories::Repository as StorageRepositoryTrait;
 use ratchet_api_types::{
-    ApiId, ListResponse, PaginationInput, UnifiedExecution, UnifiedJob, UnifiedSchedule, UnifiedTask,
+    ApiId, ExecutionLogLevel, ListResponse, PaginationInput, ScheduleMisfirePolicy, ScheduleOverlapPolicy,
+    TaskSignatureStatus, UnifiedExecution, UnifiedExecutionLog, UnifiedJob, UnifiedSchedule, UnifiedSchedulePolicy,
+    UnifiedTask,
 };
 use ratchet_graphql_api::context::GraphQLContext;
-use ratchet_http::HttpManager;
+use ratchet_graphql_api::events::EventBroadcaster;
+use ratchet_http::{HttpClientState, HttpManager, HttpTransport};
 use ratchet_mcp::server::task_dev_tools::TaskDevelopmentService;
 use ratchet_rest_api::context::TasksContext;
 use uuid::Uuid;
 
 use crate::bridges::{BridgeRegistryManager, BridgeTaskRegistry, BridgeTaskValidator};
 use crate::config::ServerConfig;
+use crate::delivery_ledger::DeliveryLedger;
 use crate::heartbeat::HeartbeatService;
+use crate::cluster::{LeaderLease, REPOSITORY_SYNC_LEASE, SCHEDULER_LEASE};
 use crate::job_processor::{JobProcessor, JobProcessorConfig, JobProcessorService};
+use crate::scheduler::policy::{self, ScheduleClock};
 use crate::scheduler::{SchedulerService, TokioCronSchedulerConfig, TokioCronSchedulerService};
+use ratchet_storage::seaorm::entities::{MisfirePolicy, OverlapPolicy};
+use crate::result_cache::TaskResultCacheManager;
 use crate::task_service::UnifiedTaskService;
-use ratchet_output::OutputDeliveryManager;
+use crate::triggers::TriggerManager;
+use crate::workflows::{WorkflowEngine, WorkflowManager};
+use ratchet_execution::{ExecutionBridge, ProcessExecutorConfig, TaskCalls, TaskServiceCaller};
+use ratchet_output::{DatabaseConnections, DatabaseTarget, OutputDeliveryManager};
 
 // Enhanced services for repository management
 use crate::repository_services::{EnhancedRepositoryService, TaskAssignmentService, SeaOrmDatabaseInterface};
@@ -38,7 +51,7 @@ use crate::watchers::{FilesystemWatcher, FilesystemWatcherConfig};
 use crate::monitoring::{SyncHealthMonitor, SyncHealthConfig};
 
 // Security and configuration services (Phase 6)
-use crate::security::{SecurityManager, CredentialManager, AuditLogger, AccessControlService, EncryptionService, AesEncryptionService};
+use crate::security::{SecurityManager, CredentialManager, AuditLogger, AccessControlService, EncryptionService, AesEncryptionService, SecretManager, secrets_encryption_service};
 use crate::config::{ConfigManager, EncryptionAlgorithm};
 use crate::security::audit_logger::FileAuditStorage;
 use std::path::PathBuf;
@@ -70,6 +83,17 @@ pub struct ServiceContainer {
     pub credential_manager: Option<Arc<CredentialManager>>,
     pub audit_logger: Option<Arc<AuditLogger>>,
     pub access_control: Option<Arc<AccessControlService>>,
+    pub secret_manager: Option<Arc<SecretManager>>,
+    pub workflow_manager: Option<Arc<WorkflowManager>>,
+    pub trigger_manager: Option<Arc<TriggerManager>>,
+    pub result_cache_manager: Option<Arc<TaskResultCacheManager>>,
+    pub delivery_ledger: Option<Arc<DeliveryLedger>>,
+    /// Response cache and circuit breakers shared by every task `fetch` call
+    pub http_client: HttpClientState,
+    /// Runs tasks on worker processes, and cancels executions wherever they run
+    pub execution_bridge: Arc<ExecutionBridge>,
+    // Shared event broadcaster for GraphQL subscriptions
+    pub event_broadcaster: Arc<EventBroadcaster>,
 }
 
 impl ServiceContainer {
@@ -80,8 +104,18 @@ impl ServiceContainer {
 
         // This is a bridge implementation during the migration
         let (repositories, mcp_task_service, seaorm_factory) = create_repository_factory_with_mcp(config).await?;
-        let registry = create_task_registry(config, repositories.clone()).await?;
-        let registry_manager = create_registry_manager(config).await?;
+
+        // Task secrets use their own key so they stay readable across restarts
+        let secret_manager = Arc::new(SecretManager::new(
+            seaorm_factory.task_secret_repository(),
+            secrets_encryption_service().await? as Arc<dyn EncryptionService>,
+        ));
+
+        // Outbound HTTP clients connect through the configured proxies and certificates
+        let http_transport = create_http_transport(config, &secret_manager).await?;
+
+        let registry = create_task_registry(config, repositories.clone(), http_transport.clone()).await?;
+        let registry_manager = create_registry_manager(config, http_transport.clone()).await?;
         let validator = create_task_validator(config).await?;
         
         // Create unified task service
@@ -91,22 +125,44 @@ impl ServiceContainer {
         ));
 
         // Create output delivery manager
-        let output_manager = Arc::new(OutputDeliveryManager::new());
+        let output_manager = Arc::new(
+            OutputDeliveryManager::new()
+                .with_http_transport(http_transport.clone())
+                .with_database_connections(create_database_connections(config)?),
+        );
+
+        // Servers sharing the database claim jobs and leader roles under their instance ID
+        let instance_id = config
+            .cluster
+            .instance_id
+            .clone()
+            .unwrap_or_else(crate::cluster::generate_instance_id);
+        let lease_ttl = std::time::Duration::from_secs(config.cluster.lease_ttl_seconds);
+        let leader_lease = |name: &str| {
+            Arc::new(LeaderLease::new(
+                name,
+                instance_id.clone(),
+                lease_ttl,
+                seaorm_factory.lease_repository(),
+            ))
+        };
+        tracing::info!("Running as instance {}", instance_id);
+
+        // Workflow definitions and runs, shared by the APIs, the scheduler and the job processor
+        let workflow_manager = Arc::new(WorkflowManager::new(
+            seaorm_factory.workflow_repository(),
+            seaorm_factory.task_repository(),
+        ));
 
         // Create scheduler service (using new tokio-cron-scheduler implementation)
         let scheduler_config = TokioCronSchedulerConfig::default();
         let scheduler_service: Option<Arc<dyn SchedulerService>> = Some(Arc::new(
-            TokioCronSchedulerService::new(repositories.clone(), scheduler_config).await?,
+            TokioCronSchedulerService::new(repositories.clone(), scheduler_config)
+                .await?
+                .with_workflow_store(workflow_manager.clone())
+                .with_leader_lease(leader_lease(SCHEDULER_LEASE)),
         ));
 
-        // Create job processor service
-        let job_processor_config = JobProcessorConfig::default();
-        let job_processor_service: Option<Arc<dyn JobProcessor>> = Some(Arc::new(JobProcessorService::new(
-            repositories.clone(),
-            output_manager.clone(),
-            job_processor_config,
-        )));
-
         // Create heartbeat service
         let heartbeat_service = Arc::new(HeartbeatService::new(
             config.heartbeat.clone(),
@@ -140,10 +196,10 @@ impl ServiceContainer {
             
             // Create sync scheduler
             let sync_sched_config = SyncSchedulerConfig::default();
-            let sync_sched = Arc::new(SyncScheduler::new(
-                sync_sched_config,
-                enhanced_repo_service.clone(),
-            ));
+            let sync_sched = Arc::new(
+                SyncScheduler::new(sync_sched_config, enhanced_repo_service.clone())
+                    .with_leader_lease(leader_lease(REPOSITORY_SYNC_LEASE)),
+            );
 
             // Create filesystem watcher
             let fs_watcher_config = FilesystemWatcherConfig::default();
@@ -208,6 +264,93 @@ impl ServiceContainer {
             (Some(sec_manager), Some(conf_manager), Some(cred_manager), Some(audit_log), Some(access_ctrl))
         };
 
+        // Inbound webhook triggers verify deliveries with task secrets and queue jobs
+        let trigger_manager = Arc::new(TriggerManager::new(
+            seaorm_factory.webhook_trigger_repository(),
+            seaorm_factory.task_repository(),
+            repositories.clone(),
+            secret_manager.clone(),
+        ));
+
+        // Tasks that opt in through their metadata get their results memoized
+        let result_cache_manager = Arc::new(TaskResultCacheManager::new(
+            &config.result_cache,
+            seaorm_factory.task_repository(),
+        ));
+
+        // Output deliveries are recorded, and failed ones retried until they are dead-lettered
+        let delivery_ledger = Arc::new(
+            DeliveryLedger::new(
+                &config.deliveries,
+                output_manager.clone(),
+                repositories.clone(),
+                seaorm_factory.delivery_result_repository(),
+            )
+            .with_secret_store(secret_manager.clone()),
+        );
+
+        // Task `fetch` calls share one response cache and one set of circuit breakers
+        let http_client = HttpClientState::new(config.http_client.clone()).with_transport(http_transport);
+
+        // Progress and console output of job executions and task calls are stored like those of MCP executions
+        let event_broadcaster = Arc::new(EventBroadcaster::new());
+        let progress_recorder = Arc::new(crate::progress::ExecutionProgressRecorder::new(
+            repositories.clone(),
+            event_broadcaster.clone(),
+        ));
+
+        // Jobs, workflow steps and tasks called by other tasks run in-process through the task caller,
+        // which cannot enforce heap limits
+        config
+            .execution_limits
+            .check_in_process()
+            .context("Invalid execution limits")?;
+        let executor_config = ProcessExecutorConfig {
+            limits: config.execution_limits.clone(),
+            egress: config.egress.clone(),
+            http_client: http_client.clone(),
+            ..Default::default()
+        };
+        let task_caller = Arc::new(
+            TaskServiceCaller::new(task_service.clone())
+                .with_repositories(repositories.clone())
+                .with_secret_store(secret_manager.clone())
+                .with_log_sink(progress_recorder.clone())
+                .with_limits(executor_config.limits.clone())
+                .with_egress_policy(config.egress.clone())
+                .with_http_client(http_client.clone()),
+        );
+
+        // Executions on worker processes and in-process ones are tracked in one registry,
+        // so cancelling through the bridge stops either
+        let execution_bridge = Arc::new(ExecutionBridge::new(ProcessExecutorConfig {
+            calls: Some(TaskCalls::new(task_caller.clone())),
+            ..executor_config
+        }));
+
+        let workflow_engine = Arc::new(WorkflowEngine::new(
+            workflow_manager.clone(),
+            task_caller.clone(),
+            repositories.clone(),
+        ));
+        let job_processor_config = JobProcessorConfig {
+            instance_id: instance_id.clone(),
+            lease_seconds: config.cluster.lease_ttl_seconds,
+            ..Default::default()
+        };
+        let job_processor_service: Option<Arc<dyn JobProcessor>> = Some(Arc::new(
+            JobProcessorService::new(repositories.clone(), output_manager.clone(), job_processor_config)
+                .with_task_caller(task_caller)
+                .with_running_executions(execution_bridge.inner().running_executions())
+                .with_progress_sink(progress_recorder.clone())
+                .with_log_sink(progress_recorder)
+                .with_workflow_engine(workflow_engine)
+                .with_result_cache(result_cache_manager.clone())
+                .with_delivery_ledger(delivery_ledger.clone())
+                .with_secret_store(secret_manager.clone())
+                .with_trust(ratchet_registry::TrustPolicies::from_config(&config.registry.trust)?),
+        ));
+
         let mut container = Self {
             repositories,
             registry,
@@ -230,6 +373,14 @@ impl ServiceContainer {
             credential_manager,
             audit_logger,
             access_control,
+            secret_manager: Some(secret_manager),
+            workflow_manager: Some(workflow_manager),
+            trigger_manager: Some(trigger_manager),
+            result_cache_manager: Some(result_cache_manager),
+            delivery_ledger: Some(delivery_ledger),
+            http_client,
+            execution_bridge,
+            event_broadcaster,
         };
 
         // Initialize service integrations after container creation
@@ -248,7 +399,7 @@ impl ServiceContainer {
 
     /// Create REST API context from service container
     pub fn rest_context(&self) -> TasksContext {
-        if let (Some(mcp), Some(scheduler)) = (&self.mcp_task_service, &self.scheduler_service) {
+        let context = if let (Some(mcp), Some(scheduler)) = (&self.mcp_task_service, &self.scheduler_service) {
             TasksContext::with_all_services(
                 self.repositories.clone(),
                 self.registry.clone(),
@@ -280,17 +431,103 @@ impl ServiceContainer {
                 self.registry_manager.clone(),
                 self.validator.clone(),
             )
+        };
+
+        let context = match self.secret_store() {
+            Some(store) => context.with_secret_store(store),
+            None => context,
+        };
+        let context = match self.workflow_store() {
+            Some(store) => context.with_workflow_store(store),
+            None => context,
+        };
+        let context = match self.trigger_store() {
+            Some(store) => context.with_trigger_store(store),
+            None => context,
+        };
+        let context = match self.task_result_cache() {
+            Some(cache) => context.with_result_cache(cache),
+            None => context,
+        };
+        let context = context
+            .with_http_client_state(self.http_client.clone())
+            .with_execution_canceller(self.execution_bridge.clone());
+        match self.output_delivery_store() {
+            Some(store) => context.with_delivery_store(store),
+            None => context,
         }
     }
 
     /// Create GraphQL context from service container
     pub fn graphql_context(&self) -> GraphQLContext {
-        GraphQLContext::new(
+        let context = GraphQLContext::with_event_broadcaster(
             self.repositories.clone(),
             self.registry.clone(),
             self.registry_manager.clone(),
             self.validator.clone(),
+            self.event_broadcaster.clone(),
         )
+        .with_execution_canceller(self.execution_bridge.clone());
+
+        let context = match self.secret_store() {
+            Some(store) => context.with_secret_store(store),
+            None => context,
+        };
+        let context = match self.workflow_store() {
+            Some(store) => context.with_workflow_store(store),
+            None => context,
+        };
+        let context = match self.task_result_cache() {
+            Some(cache) => context.with_result_cache(cache),
+            None => context,
+        };
+        match self.output_delivery_store() {
+            Some(store) => context.with_delivery_store(store),
+            None => context,
+        }
+    }
+
+    /// Encrypted store for task secrets, shared by the APIs and task execution
+    pub fn secret_store(&self) -> Option<Arc<dyn SecretStore>> {
+        self.secret_manager
+            .clone()
+            .map(|manager| manager as Arc<dyn SecretStore>)
+    }
+
+    /// Store of workflow definitions and runs
+    pub fn workflow_store(&self) -> Option<Arc<dyn WorkflowStore>> {
+        self.workflow_manager
+            .clone()
+            .map(|manager| manager as Arc<dyn WorkflowStore>)
+    }
+
+    /// Store of inbound webhook triggers
+    pub fn trigger_store(&self) -> Option<Arc<dyn TriggerStore>> {
+        self.trigger_manager
+            .clone()
+            .map(|manager| manager as Arc<dyn TriggerStore>)
+    }
+
+    /// Results cached for tasks that opt into memoization
+    pub fn task_result_cache(&self) -> Option<Arc<dyn TaskResultCache>> {
+        self.result_cache_manager
+            .clone()
+            .map(|manager| manager as Arc<dyn TaskResultCache>)
+    }
+
+    /// Ledger of job output deliveries, for listing and redelivering failed ones
+    pub fn output_delivery_store(&self) -> Option<Arc<dyn OutputDeliveryStore>> {
+        self.delivery_ledger
+            .clone()
+            .map(|ledger| ledger as Arc<dyn OutputDeliveryStore>)
+    }
+
+    /// Create a sink that persists execution progress and publishes it to GraphQL subscribers
+    pub fn progress_recorder(&self) -> Arc<crate::progress::ExecutionProgressRecorder> {
+        Arc::new(crate::progress::ExecutionProgressRecorder::new(
+            self.repositories.clone(),
+            self.event_broadcaster.clone(),
+        ))
     }
 
     /// Initialize service integrations after container creation
@@ -612,6 +849,11 @@ impl CrudRepository<UnifiedExecution> for DirectExecutionRepository {
             duration_ms: entity.duration_ms,
             http_requests: entity.http_requests,
             recording_path: entity.recording_path,
+            progress: storage_progress(entity.progress, entity.progress_message),
+            partial_output: entity.partial_output,
+            logs: None,
+            parent_execution_uuid: entity.parent_execution_uuid,
+            cache_hit: entity.cache_hit,
         };
 
         let created = self
@@ -660,6 +902,11 @@ impl CrudRepository<UnifiedExecution> for DirectExecutionRepository {
             duration_ms: entity.duration_ms,
             http_requests: entity.http_requests,
             recording_path: entity.recording_path,
+            progress: storage_progress(entity.progress, entity.progress_message),
+            partial_output: entity.partial_output,
+            logs: None,
+            parent_execution_uuid: entity.parent_execution_uuid,
+            cache_hit: entity.cache_hit,
         };
 
         let updated = self
@@ -831,6 +1078,51 @@ impl ExecutionRepository for DirectExecutionRepository {
             .await
             .map_err(|e| DatabaseError::Internal { message: e.to_string() })
     }
+
+    async fn record_progress(
+        &self,
+        id: ApiId,
+        fraction: Option<f32>,
+        message: Option<String>,
+        partial_output: Option<serde_json::Value>,
+    ) -> Result<(), DatabaseError> {
+        let storage_id = id.as_i32().unwrap_or(0);
+        self.storage_repo
+            .record_progress(storage_id, fraction, message, partial_output)
+            .await
+            .map_err(|e| DatabaseError::Internal { message: e.to_string() })
+    }
+
+    async fn append_logs(&self, id: ApiId, logs: Vec<UnifiedExecutionLog>) -> Result<(), DatabaseError> {
+        let storage_id = id.as_i32().unwrap_or(0);
+        let entries = logs
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| DatabaseError::Internal { message: e.to_string() })?;
+        self.storage_repo
+            .append_logs(storage_id, entries)
+            .await
+            .map_err(|e| DatabaseError::Internal { message: e.to_string() })
+    }
+
+    async fn find_logs(
+        &self,
+        id: ApiId,
+        min_level: Option<ExecutionLogLevel>,
+        limit: Option<usize>,
+    ) -> Result<Vec<UnifiedExecutionLog>, DatabaseError> {
+        let storage_id = id.as_i32().unwrap_or(0);
+        let execution = self
+            .storage_repo
+            .find_by_id(storage_id)
+            .await
+            .map_err(|e| DatabaseError::Internal { message: e.to_string() })?;
+
+        Ok(execution
+            .map(|execution| execution.console_logs(min_level, limit))
+            .unwrap_or_default())
+    }
 }
 
 pub struct DirectJobRepository {
@@ -841,6 +1133,42 @@ impl DirectJobRepository {
     pub fn new(storage_repo: Arc<ratchet_storage::seaorm::repositories::JobRepository>) -> Self {
         Self { storage_repo }
     }
+
+    /// Convert storage jobs, loading their dependencies
+    async fn with_dependencies(
+        &self,
+        jobs: Vec<ratchet_storage::seaorm::entities::Job>,
+    ) -> Result<Vec<UnifiedJob>, DatabaseError> {
+        let ids: Vec<i32> = jobs.iter().map(|job| job.id).collect();
+        let dependencies = self
+            .storage_repo
+            .find_dependencies(&ids)
+            .await
+            .map_err(convert_storage_error)?;
+
+        Ok(jobs
+            .into_iter()
+            .map(|job| {
+                let mut unified = convert_storage_job_to_unified(job);
+                unified.depends_on = dependencies
+                    .iter()
+                    .filter(|dependency| Some(dependency.job_id) == unified.id.as_i32())
+                    .map(convert_storage_job_dependency_to_api)
+                    .collect();
+                unified
+            })
+            .collect())
+    }
+
+    async fn with_dependencies_one(
+        &self,
+        job: Option<ratchet_storage::seaorm::entities::Job>,
+    ) -> Result<Option<UnifiedJob>, DatabaseError> {
+        match job {
+            Some(job) => Ok(self.with_dependencies(vec![job]).await?.pop()),
+            None => Ok(None),
+        }
+    }
 }
 
 #[async_trait]
@@ -858,33 +1186,23 @@ impl Repository for DirectJobRepository {
 #[async_trait]
 impl CrudRepository<UnifiedJob> for DirectJobRepository {
     async fn create(&self, entity: UnifiedJob) -> Result<UnifiedJob, DatabaseError> {
-        let storage_job = convert_unified_job_to_storage(entity);
-        match self.storage_repo.create(storage_job).await {
-            Ok(created_job) => Ok(convert_storage_job_to_unified(created_job)),
-            Err(e) => Err(DatabaseError::Internal { message: e.to_string() }),
-        }
+        self.create_or_replay(entity).await.map(JobCreation::into_job)
     }
 
     async fn find_by_id(&self, id: i32) -> Result<Option<UnifiedJob>, DatabaseError> {
-        match self.storage_repo.find_by_id(id).await {
-            Ok(Some(job)) => Ok(Some(convert_storage_job_to_unified(job))),
-            Ok(None) => Ok(None),
-            Err(e) => Err(convert_storage_error(e)),
-        }
+        let job = self.storage_repo.find_by_id(id).await.map_err(convert_storage_error)?;
+        self.with_dependencies_one(job).await
     }
 
     async fn find_by_uuid(&self, uuid: Uuid) -> Result<Option<UnifiedJob>, DatabaseError> {
-        match self.storage_repo.find_by_uuid(uuid).await {
-            Ok(Some(job)) => Ok(Some(convert_storage_job_to_unified(job))),
-            Ok(None) => Ok(None),
-            Err(e) => Err(convert_storage_error(e)),
-        }
+        let job = self.storage_repo.find_by_uuid(uuid).await.map_err(convert_storage_error)?;
+        self.with_dependencies_one(job).await
     }
 
     async fn update(&self, entity: UnifiedJob) -> Result<UnifiedJob, DatabaseError> {
         let storage_job = convert_unified_job_to_storage(entity);
         match self.storage_repo.update(storage_job).await {
-            Ok(updated_job) => Ok(convert_storage_job_to_unified(updated_job)),
+            Ok(updated_job) => Ok(self.with_dependencies(vec![updated_job]).await?.remove(0)),
             Err(e) => Err(DatabaseError::Internal { message: e.to_string() }),
         }
     }
@@ -923,7 +1241,7 @@ impl FilteredRepository<UnifiedJob, JobFilters> for DirectJobRepository {
             .await
             .map_err(convert_storage_error)?;
 
-        let unified_jobs: Vec<UnifiedJob> = jobs.into_iter().map(convert_storage_job_to_unified).collect();
+        let unified_jobs = self.with_dependencies(jobs).await?;
 
         Ok(ListResponse {
             items: unified_jobs,
@@ -958,13 +1276,66 @@ impl FilteredRepository<UnifiedJob, JobFilters> for DirectJobRepository {
 
 #[async_trait]
 impl JobRepository for DirectJobRepository {
+    async fn create_or_replay(&self, job: UnifiedJob) -> Result<JobCreation, DatabaseError> {
+        use ratchet_storage::seaorm::repositories::JobCreation as StorageJobCreation;
+
+        let dependencies = job
+            .depends_on
+            .iter()
+            .map(convert_api_job_dependency_to_storage)
+            .collect::<Result<Vec<_>, _>>()?;
+        let storage_job = convert_unified_job_to_storage(job);
+        let creation = self
+            .storage_repo
+            .create_or_replay(storage_job, dependencies)
+            .await
+            .map_err(|e| DatabaseError::Internal { message: e.to_string() })?;
+        let replayed = matches!(creation, StorageJobCreation::Replayed(_));
+        let job = self.with_dependencies(vec![creation.into_job()]).await?.remove(0);
+        Ok(if replayed {
+            JobCreation::Replayed(job)
+        } else {
+            JobCreation::Created(job)
+        })
+    }
+
     async fn find_ready_for_processing(&self, limit: u64) -> Result<Vec<UnifiedJob>, DatabaseError> {
         let jobs = self
             .storage_repo
             .find_ready_for_processing(limit)
             .await
             .map_err(convert_storage_error)?;
-        Ok(jobs.into_iter().map(convert_storage_job_to_unified).collect())
+        self.with_dependencies(jobs).await
+    }
+
+    async fn claim_ready_for_processing(
+        &self,
+        owner: &str,
+        lease: std::time::Duration,
+        limit: u64,
+    ) -> Result<Vec<UnifiedJob>, DatabaseError> {
+        let jobs = self
+            .storage_repo
+            .claim_ready_for_processing(owner, lease, limit)
+            .await
+            .map_err(convert_storage_error)?;
+        self.with_dependencies(jobs).await
+    }
+
+    async fn renew_leases(&self, owner: &str, lease: std::time::Duration) -> Result<u64, DatabaseError> {
+        self.storage_repo
+            .renew_leases(owner, lease)
+            .await
+            .map_err(convert_storage_error)
+    }
+
+    async fn reclaim_expired_leases(&self) -> Result<Vec<UnifiedJob>, DatabaseError> {
+        let jobs = self
+            .storage_repo
+            .reclaim_expired_leases()
+            .await
+            .map_err(convert_storage_error)?;
+        self.with_dependencies(jobs).await
     }
 
     async fn find_by_status(&self, status: ratchet_api_types::JobStatus) -> Result<Vec<UnifiedJob>, DatabaseError> {
@@ -974,7 +1345,7 @@ impl JobRepository for DirectJobRepository {
             .find_by_status(storage_status)
             .await
             .map_err(convert_storage_error)?;
-        Ok(jobs.into_iter().map(convert_storage_job_to_unified).collect())
+        self.with_dependencies(jobs).await
     }
 
     async fn mark_processing(&self, id: ApiId, execution_id: ApiId) -> Result<(), DatabaseError> {
@@ -1034,6 +1405,28 @@ impl JobRepository for DirectJobRepository {
             .await
             .map_err(convert_storage_error)
     }
+
+    async fn find_latest_for_schedule(&self, schedule_id: ApiId) -> Result<Option<UnifiedJob>, DatabaseError> {
+        let storage_id = schedule_id.as_i32().ok_or_else(|| DatabaseError::Validation {
+            message: "Invalid schedule ID".to_string(),
+        })?;
+        let job = self
+            .storage_repo
+            .find_latest_for_schedule(storage_id)
+            .await
+            .map_err(convert_storage_error)?;
+        self.with_dependencies_one(job).await
+    }
+
+    async fn find_dependency_outputs(&self, id: ApiId) -> Result<Vec<serde_json::Value>, DatabaseError> {
+        let storage_id = id.as_i32().ok_or_else(|| DatabaseError::Validation {
+            message: "Invalid job ID".to_string(),
+        })?;
+        self.storage_repo
+            .find_dependency_outputs(storage_id)
+            .await
+            .map_err(convert_storage_error)
+    }
 }
 
 pub struct DirectScheduleRepository {
@@ -1060,7 +1453,8 @@ impl Repository for DirectScheduleRepository {
 
 #[async_trait]
 impl CrudRepository<UnifiedSchedule> for DirectScheduleRepository {
-    async fn create(&self, entity: UnifiedSchedule) -> Result<UnifiedSchedule, DatabaseError> {
+    async fn create(&self, mut entity: UnifiedSchedule) -> Result<UnifiedSchedule, DatabaseError> {
+        entity.next_run = schedule_next_run(&entity)?;
         let storage_schedule = convert_unified_schedule_to_storage(entity);
 
         match self.storage_repo.create(storage_schedule).await {
@@ -1093,8 +1487,37 @@ impl CrudRepository<UnifiedSchedule> for DirectScheduleRepository {
         }
     }
 
-    async fn update(&self, entity: UnifiedSchedule) -> Result<UnifiedSchedule, DatabaseError> {
-        let storage_schedule = convert_unified_schedule_to_storage(entity);
+    async fn update(&self, mut entity: UnifiedSchedule) -> Result<UnifiedSchedule, DatabaseError> {
+        let id = entity.id.as_i32().unwrap_or(0);
+        let existing = self
+            .storage_repo
+            .find_by_id(id)
+            .await
+            .map_err(|e| DatabaseError::Internal { message: e.to_string() })?
+            .ok_or_else(|| DatabaseError::NotFound {
+                entity: "Schedule".to_string(),
+                id: entity.id.to_string(),
+            })?;
+
+        // A new cron expression, timezone or blackout window moves the next run
+        let next_run = schedule_next_run(&entity)?;
+        let current = convert_storage_schedule_to_unified(existing.clone());
+        if entity.cron_expression != current.cron_expression
+            || entity.policy.timezone != current.policy.timezone
+            || entity.policy.blackout_windows != current.policy.blackout_windows
+            || entity.next_run.is_none()
+        {
+            entity.next_run = next_run;
+        }
+
+        // Keep the columns the unified schedule does not carry
+        let storage_schedule = ratchet_storage::seaorm::entities::Schedule {
+            uuid: existing.uuid,
+            input_data: existing.input_data,
+            execution_count: existing.execution_count,
+            max_executions: existing.max_executions,
+            ..convert_unified_schedule_to_storage(entity)
+        };
 
         match self.storage_repo.update(storage_schedule).await {
             Ok(updated_schedule) => Ok(convert_storage_schedule_to_unified(updated_schedule)),
@@ -1212,6 +1635,13 @@ impl ScheduleRepository for DirectScheduleRepository {
     }
 }
 
+/// Validate the policy of a schedule and work out when it next fires
+fn schedule_next_run(schedule: &UnifiedSchedule) -> Result<Option<chrono::DateTime<chrono::Utc>>, DatabaseError> {
+    policy::validate(schedule).map_err(|message| DatabaseError::Validation { message })?;
+    let clock = ScheduleClock::for_schedule(schedule).map_err(|message| DatabaseError::Validation { message })?;
+    Ok(clock.next_fire(chrono::Utc::now()))
+}
+
 // Conversion functions (simplified - reuse from bridges for now)
 fn convert_unified_task_to_storage(task: UnifiedTask) -> ratchet_storage::seaorm::entities::Task {
     ratchet_storage::seaorm::entities::Task {
@@ -1242,6 +1672,8 @@ fn convert_unified_task_to_storage(task: UnifiedTask) -> ratchet_storage::seaorm
         updated_at: task.updated_at,
         validated_at: task.validated_at,
         source_modified_at: Some(task.updated_at),
+        signature_status: task.signature_status.map(|status| status.as_str().to_string()),
+        signed_by: task.signed_by,
     }
 }
 
@@ -1255,6 +1687,7 @@ fn convert_unified_schedule_to_storage(schedule: UnifiedSchedule) -> ratchet_sto
         id: schedule.id.as_i32().unwrap_or(0),
         uuid: schedule.id.as_uuid().unwrap_or_else(uuid::Uuid::new_v4), // Use schedule id as UUID or generate new one
         task_id: schedule.task_id.as_i32().unwrap_or(0),
+        workflow_id: schedule.workflow_id.as_ref().and_then(ApiId::as_i32),
         name: schedule.name,
         cron_expression: schedule.cron_expression,
         input_data: serde_json::Value::Null, // Default empty input
@@ -1267,6 +1700,25 @@ fn convert_unified_schedule_to_storage(schedule: UnifiedSchedule) -> ratchet_sto
             "description": schedule.description
         })),
         output_destinations: output_destinations_json,
+        timezone: schedule.policy.timezone,
+        overlap_policy: match schedule.policy.overlap {
+            ScheduleOverlapPolicy::Allow => OverlapPolicy::Allow,
+            ScheduleOverlapPolicy::Skip => OverlapPolicy::Skip,
+            ScheduleOverlapPolicy::Queue => OverlapPolicy::Queue,
+            ScheduleOverlapPolicy::CancelPrevious => OverlapPolicy::CancelPrevious,
+        },
+        misfire_policy: match schedule.policy.misfire {
+            ScheduleMisfirePolicy::Skip => MisfirePolicy::Skip,
+            ScheduleMisfirePolicy::FireOnce => MisfirePolicy::FireOnce,
+            ScheduleMisfirePolicy::CatchUp => MisfirePolicy::CatchUp,
+        },
+        catch_up_limit: schedule.policy.catch_up_limit,
+        jitter_seconds: schedule.policy.jitter_seconds,
+        blackout_windows: if schedule.policy.blackout_windows.is_empty() {
+            None
+        } else {
+            serde_json::to_value(&schedule.policy.blackout_windows).ok()
+        },
         created_at: schedule.created_at,
         updated_at: schedule.updated_at,
     }
@@ -1284,6 +1736,7 @@ fn convert_storage_schedule_to_unified(schedule: ratchet_storage::seaorm::entiti
     UnifiedSchedule {
         id: ApiId::from_i32(schedule.id),
         task_id: ApiId::from_i32(schedule.task_id),
+        workflow_id: schedule.workflow_id.map(ApiId::from_i32),
         name: schedule.name,
         description: schedule
             .metadata
@@ -1298,6 +1751,26 @@ fn convert_storage_schedule_to_unified(schedule: ratchet_storage::seaorm::entiti
         created_at: schedule.created_at,
         updated_at: schedule.updated_at,
         output_destinations,
+        policy: UnifiedSchedulePolicy {
+            timezone: schedule.timezone,
+            overlap: match schedule.overlap_policy {
+                OverlapPolicy::Allow => ScheduleOverlapPolicy::Allow,
+                OverlapPolicy::Skip => ScheduleOverlapPolicy::Skip,
+                OverlapPolicy::Queue => ScheduleOverlapPolicy::Queue,
+                OverlapPolicy::CancelPrevious => ScheduleOverlapPolicy::CancelPrevious,
+            },
+            misfire: match schedule.misfire_policy {
+                MisfirePolicy::Skip => ScheduleMisfirePolicy::Skip,
+                MisfirePolicy::FireOnce => ScheduleMisfirePolicy::FireOnce,
+                MisfirePolicy::CatchUp => ScheduleMisfirePolicy::CatchUp,
+            },
+            catch_up_limit: schedule.catch_up_limit,
+            jitter_seconds: schedule.jitter_seconds,
+            blackout_windows: schedule
+                .blackout_windows
+                .and_then(|json| serde_json::from_value(json).ok())
+                .unwrap_or_default(),
+        },
     }
 }
 
@@ -1332,6 +1805,8 @@ fn convert_storage_task_to_unified(task: ratchet_storage::seaorm::entities::Task
         sync_status: task.sync_status,
         needs_push: task.needs_push,
         last_synced_at: task.last_synced_at,
+        signature_status: task.signature_status.as_deref().and_then(TaskSignatureStatus::from_name),
+        signed_by: task.signed_by,
         input_schema: Some(task.input_schema),
         output_schema: Some(task.output_schema),
         metadata: Some(task.metadata),
@@ -1344,10 +1819,10 @@ fn convert_unified_job_to_storage(job: UnifiedJob) -> ratchet_storage::seaorm::e
         uuid: job.id.as_uuid().unwrap_or_else(uuid::Uuid::new_v4),
         task_id: job.task_id.as_i32().unwrap_or(0),
         execution_id: None, // Not set until execution starts
-        schedule_id: None,  // Would need to be provided if job is from a schedule
+        schedule_id: job.schedule_id.as_ref().and_then(ApiId::as_i32),
         priority: convert_api_job_priority_to_storage(job.priority),
         status: convert_api_job_status_to_storage(job.status),
-        input_data: serde_json::Value::Null, // Default empty input
+        input_data: job.input.unwrap_or(serde_json::Value::Null),
         retry_count: job.retry_count,
         max_retries: job.max_retries,
         retry_delay_seconds: 60, // Default 60 seconds
@@ -1361,6 +1836,10 @@ fn convert_unified_job_to_storage(job: UnifiedJob) -> ratchet_storage::seaorm::e
         output_destinations: job
             .output_destinations
             .map(|destinations| serde_json::to_value(destinations).unwrap_or(serde_json::Value::Null)),
+        idempotency_key: job.idempotency_key,
+        input_hash: None, // Computed by the storage repository
+        lease_owner: None,
+        lease_expires_at: None,
     }
 }
 
@@ -1376,6 +1855,45 @@ fn convert_storage_job_to_unified(job: ratchet_storage::seaorm::entities::Job) -
         scheduled_for: job.process_at,
         error_message: job.error_message,
         output_destinations: job.output_destinations.and_then(|v| serde_json::from_value(v).ok()),
+        input: Some(job.input_data).filter(|input| !input.is_null()),
+        depends_on: Vec::new(), // Loaded separately by DirectJobRepository
+        idempotency_key: job.idempotency_key,
+        schedule_id: job.schedule_id.map(ApiId::from_i32),
+    }
+}
+
+fn convert_api_job_dependency_to_storage(
+    dependency: &ratchet_api_types::UnifiedJobDependency,
+) -> Result<ratchet_storage::seaorm::repositories::NewJobDependency, DatabaseError> {
+    use ratchet_storage::seaorm::entities::DependencyCondition;
+
+    let depends_on_job_id = dependency.job_id.as_i32().ok_or_else(|| DatabaseError::Validation {
+        message: format!("Invalid dependency job ID: {}", dependency.job_id),
+    })?;
+    Ok(ratchet_storage::seaorm::repositories::NewJobDependency {
+        depends_on_job_id,
+        condition: match dependency.condition {
+            ratchet_api_types::JobDependencyCondition::OnSuccess => DependencyCondition::OnSuccess,
+            ratchet_api_types::JobDependencyCondition::OnFailure => DependencyCondition::OnFailure,
+            ratchet_api_types::JobDependencyCondition::Always => DependencyCondition::Always,
+        },
+        pass_output: dependency.pass_output,
+    })
+}
+
+fn convert_storage_job_dependency_to_api(
+    dependency: &ratchet_storage::seaorm::entities::JobDependency,
+) -> ratchet_api_types::UnifiedJobDependency {
+    use ratchet_storage::seaorm::entities::DependencyCondition;
+
+    ratchet_api_types::UnifiedJobDependency {
+        job_id: ApiId::from_i32(dependency.depends_on_job_id),
+        condition: match dependency.condition {
+            DependencyCondition::OnSuccess => ratchet_api_types::JobDependencyCondition::OnSuccess,
+            DependencyCondition::OnFailure => ratchet_api_types::JobDependencyCondition::OnFailure,
+            DependencyCondition::Always => ratchet_api_types::JobDependencyCondition::Always,
+        },
+        pass_output: dependency.pass_output,
     }
 }
 
@@ -1431,6 +1949,9 @@ fn convert_storage_error(err: ratchet_storage::seaorm::connection::DatabaseError
     use ratchet_storage::seaorm::connection::DatabaseError as StorageError;
     match err {
         StorageError::DbError(db_err) => {
+            if let Some(sea_orm::SqlErr::ForeignKeyConstraintViolation(message)) = db_err.sql_err() {
+                return DatabaseError::Constraint { message };
+            }
             // Convert SeaORM database errors to appropriate interface errors
             match db_err {
                 sea_orm::DbErr::RecordNotFound(_) => DatabaseError::NotFound {
@@ -1523,6 +2044,13 @@ fn convert_interface_execution_pagination_to_storage(
 }
 
 // Execution conversion functions
+fn storage_progress(fraction: Option<f32>, message: Option<String>) -> Option<serde_json::Value> {
+    if fraction.is_none() && message.is_none() {
+        return None;
+    }
+    Some(serde_json::json!({ "fraction": fraction, "message": message }))
+}
+
 fn convert_execution_from_storage(execution: ratchet_storage::seaorm::entities::executions::Model) -> UnifiedExecution {
     let (can_retry, can_cancel) = ratchet_api_types::conversions::compute_execution_capabilities(
         convert_storage_execution_status_to_api(execution.status),
@@ -1543,9 +2071,23 @@ fn convert_execution_from_storage(execution: ratchet_storage::seaorm::entities::
         duration_ms: execution.duration_ms,
         http_requests: execution.http_requests,
         recording_path: execution.recording_path,
+        parent_execution_uuid: execution.parent_execution_uuid,
+        cache_hit: execution.cache_hit,
         can_retry,
         can_cancel,
-        progress: None, // Progress would be extracted from output JSON if available
+        progress: execution
+            .progress
+            .as_ref()
+            .and_then(|progress| progress.get("fraction"))
+            .and_then(serde_json::Value::as_f64)
+            .map(|fraction| fraction as f32),
+        progress_message: execution
+            .progress
+            .as_ref()
+            .and_then(|progress| progress.get("message"))
+            .and_then(serde_json::Value::as_str)
+            .map(str::to_string),
+        partial_output: execution.partial_output,
     }
 }
 
@@ -1614,9 +2156,11 @@ async fn create_repository_factory_with_mcp(
     };
 
     let db_connection = ratchet_storage::seaorm::connection::DatabaseConnection::new(storage_config).await?;
-    let storage_factory = Arc::new(ratchet_storage::seaorm::repositories::RepositoryFactory::new(
-        db_connection,
-    ));
+    let mut storage_factory = ratchet_storage::seaorm::repositories::RepositoryFactory::new(db_connection);
+    storage_factory.job_repo = storage_factory
+        .job_repo
+        .with_idempotency_retention(chrono::Duration::seconds(config.jobs.idempotency_retention_seconds as i64));
+    let storage_factory = Arc::new(storage_factory);
 
     // Create the DirectRepositoryFactory
     let direct_factory = DirectRepositoryFactory::new(storage_factory.clone());
@@ -1660,9 +2204,10 @@ async fn create_repository_factory_with_mcp(
 async fn create_task_registry(
     config: &ServerConfig,
     repositories: Arc<dyn RepositoryFactory>,
+    http_transport: HttpTransport,
 ) -> Result<Arc<dyn TaskRegistry>> {
     // Create functional task registry using ratchet-registry
-    let mut bridge_registry = BridgeTaskRegistry::new(config).await?;
+    let mut bridge_registry = BridgeTaskRegistry::new(config, http_transport).await?;
     bridge_registry.set_repositories(repositories);
 
     // Sync discovered tasks to database
@@ -1672,12 +2217,66 @@ async fn create_task_registry(
 }
 
 /// Create registry manager from configuration
-async fn create_registry_manager(config: &ServerConfig) -> Result<Arc<dyn RegistryManager>> {
+async fn create_registry_manager(
+    config: &ServerConfig,
+    http_transport: HttpTransport,
+) -> Result<Arc<dyn RegistryManager>> {
     // Create functional registry manager using ratchet-registry
-    let bridge_manager = BridgeRegistryManager::new(config).await?;
+    let bridge_manager = BridgeRegistryManager::new(config, http_transport).await?;
     Ok(Arc::new(bridge_manager))
 }
 
+/// Create the proxy and certificate settings shared by outbound HTTP clients
+///
+/// Client certificates configured by secret name are read from global secrets,
+/// so a missing secret or an unreadable certificate fails startup.
+async fn create_http_transport(config: &ServerConfig, secrets: &SecretManager) -> Result<HttpTransport> {
+    let names = config.http_transport.secret_names();
+    let resolved = if names.is_empty() {
+        HashMap::new()
+    } else {
+        secrets
+            .resolve_secrets(&names, None, None)
+            .await
+            .context("Failed to resolve client certificate secrets")?
+    };
+    HttpTransport::new(&config.http_transport, &resolved).context("Invalid HTTP transport configuration")
+}
+
+/// The databases database output destinations may write to
+///
+/// Ratchet's own database is refused, so that no destination can write into its tables.
+fn create_database_connections(config: &ServerConfig) -> Result<DatabaseConnections> {
+    let mut targets = HashMap::new();
+    for (name, connection) in &config.deliveries.database_connections {
+        if same_database(&connection.url, &config.database.url) {
+            anyhow::bail!(
+                "Output database connection '{}' must not be Ratchet's own database",
+                name
+            );
+        }
+        let target = DatabaseTarget {
+            url: connection.url.clone(),
+            max_connections: connection.pool_config.max_connections,
+        };
+        targets.insert(name.clone(), target);
+    }
+    Ok(DatabaseConnections::new(targets))
+}
+
+/// Whether two connection strings name the same database, whatever their options
+/// and however a SQLite path is written
+fn same_database(a: &str, b: &str) -> bool {
+    let normalize = |url: &str| {
+        let url = url.split('?').next().unwrap_or(url).trim_end_matches('/');
+        match url.strip_prefix("sqlite://") {
+            Some(path) => format!("sqlite:{}", path),
+            None => url.to_string(),
+        }
+    };
+    normalize(a) == normalize(b)
+}
+
 /// Create task validator from configuration
 async fn create_task_validator(_config: &ServerConfig) -> Result<Arc<dyn TaskValidator>> {
     // Create functional task validator using ratchet-registry
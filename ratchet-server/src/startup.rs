@@ -81,13 +81,8 @@ impl Server {
         if self.config.graphql_api.enabled {
             tracing::info!("GraphQL API enabled, creating schema and routes");
 
-            // Create GraphQL context
-            let graphql_context = GraphQLContext::new(
-                self.services.repositories.clone(),
-                self.services.registry.clone(),
-                self.services.registry_manager.clone(),
-                self.services.validator.clone(),
-            );
+            // Create GraphQL context sharing the service container's event broadcaster
+            let graphql_context: GraphQLContext = self.services.graphql_context();
 
             // Create GraphQL configuration
             let graphql_config = GraphQLConfig {
@@ -130,6 +125,9 @@ impl Server {
             {
                 use crate::mcp_handler::{mcp_get_handler, mcp_post_handler, mcp_delete_handler, mcp_health_handler, McpEndpointState};
 
+                // Progress and console output of MCP executions share one recorder
+                let recorder = self.services.progress_recorder();
+
                 // Create MCP endpoint state with dependencies
                 let mcp_state = match McpEndpointState::new_with_dependencies(
                     self.config.mcp_api.clone(),
@@ -137,6 +135,10 @@ impl Server {
                     self.services.mcp_task_service.clone(),
                     self.services.storage_factory.clone(),
                     Some(self.services.task_service.clone()),
+                    Some(recorder.clone()),
+                    Some(recorder),
+                    self.services.secret_store(),
+                    self.services.execution_bridge.clone(),
                 ).await {
                     Ok(state) => state,
                     Err(e) => {
@@ -274,6 +276,7 @@ impl Server {
         let heartbeat_schedule = UnifiedSchedule {
             id: ApiId::from_i32(0), // Will be set by database
             task_id: heartbeat_task.id,
+            workflow_id: None,
             name: "system_heartbeat".to_string(),
             description: Some("System health monitoring heartbeat - managed by scheduler".to_string()),
             cron_expression: "0 */5 * * * *".to_string(), // Every 5 minutes
@@ -283,6 +286,7 @@ impl Server {
             created_at: Utc::now(),
             updated_at: Utc::now(),
             output_destinations: None,
+            policy: Default::default(),
         };
 
         // Create the schedule in the repository
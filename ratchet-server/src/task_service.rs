@@ -105,6 +105,8 @@ impl TaskService for UnifiedTaskService {
                             sync_status: "synced".to_string(),
                             needs_push: false,
                             last_synced_at: Some(chrono::Utc::now()),
+                            signature_status: None,
+                            signed_by: None,
                             input_schema: task_meta.input_schema,
                             output_schema: task_meta.output_schema,
                             metadata: task_meta.metadata,
@@ -173,6 +175,8 @@ impl TaskService for UnifiedTaskService {
                             sync_status: "synced".to_string(),
                             needs_push: false,
                             last_synced_at: Some(chrono::Utc::now()),
+                            signature_status: None,
+                            signed_by: None,
                             input_schema: task_meta.input_schema,
                             output_schema: task_meta.output_schema,
                             metadata: task_meta.metadata,
@@ -278,6 +282,8 @@ impl TaskService for UnifiedTaskService {
                             sync_status: "synced".to_string(),
                             needs_push: false,
                             last_synced_at: Some(chrono::Utc::now()),
+                            signature_status: None,
+                            signed_by: None,
                             input_schema: task_meta.input_schema,
                             output_schema: task_meta.output_schema,
                             metadata: task_meta.metadata,
@@ -0,0 +1,620 @@
+//! Webhook trigger store backed by the `webhook_triggers` table
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use ratchet_api_types::{
+    ApiId, JobPriority, JobStatus, TriggerDeliveryStatus, UnifiedJob, UnifiedTriggerDelivery, UnifiedTriggerSignature,
+    UnifiedWebhookTrigger,
+};
+use ratchet_interfaces::{RepositoryFactory, SecretError, SecretStore, TriggerError, TriggerStore};
+use ratchet_storage::seaorm::entities::{WebhookTrigger, WebhookTriggerDelivery};
+use ratchet_storage::seaorm::repositories::{DeliveryClaim, TaskRepository, WebhookTriggerRepository};
+
+use super::signature;
+use crate::json_template;
+
+/// How long deliveries are remembered for replay protection
+///
+/// Signatures of triggers without a timestamp header never expire, so those
+/// triggers remember the signatures of their deliveries for as long as they exist.
+const DELIVERY_RETENTION_DAYS: i64 = 7;
+
+/// Prefix of the replay keys that record delivery signatures
+const SIGNATURE_KEY_PREFIX: &str = "signature:";
+
+/// Retries of jobs queued by a trigger
+const TRIGGERED_JOB_MAX_RETRIES: i32 = 3;
+
+/// Validates webhook triggers and turns verified deliveries into jobs
+pub struct TriggerManager {
+    repository: WebhookTriggerRepository,
+    tasks: TaskRepository,
+    repositories: Arc<dyn RepositoryFactory>,
+    secrets: Arc<dyn SecretStore>,
+}
+
+impl TriggerManager {
+    /// Create a new trigger manager
+    pub fn new(
+        repository: WebhookTriggerRepository,
+        tasks: TaskRepository,
+        repositories: Arc<dyn RepositoryFactory>,
+        secrets: Arc<dyn SecretStore>,
+    ) -> Self {
+        Self {
+            repository,
+            tasks,
+            repositories,
+            secrets,
+        }
+    }
+
+    /// Check the settings of a trigger and point it at the storage ID of its task
+    ///
+    /// The task may be named by ID, UUID or name.
+    async fn validate(&self, trigger: &mut UnifiedWebhookTrigger) -> Result<(), TriggerError> {
+        let name_is_valid = !trigger.name.is_empty()
+            && trigger
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !name_is_valid {
+            return Err(invalid("trigger names may only contain letters, digits, '-' and '_'"));
+        }
+        if trigger.signature.secret_name.trim().is_empty() || trigger.signature.header.trim().is_empty() {
+            return Err(invalid("the signature needs a secret name and a header"));
+        }
+        // Timestamped deliveries must expire before they are forgotten, or they could be replayed
+        let retention = chrono::Duration::days(DELIVERY_RETENTION_DAYS).num_seconds();
+        if trigger
+            .signature
+            .max_age_seconds
+            .is_some_and(|age| age <= 0 || age > retention)
+        {
+            return Err(invalid(format!(
+                "the signature max age must be between 1 and {} seconds",
+                retention
+            )));
+        }
+        if let Some(schema) = &trigger.filter_schema {
+            jsonschema::validator_for(schema).map_err(|e| invalid(format!("invalid filter schema: {}", e)))?;
+        }
+        if let Some(mapping) = &trigger.input_mapping {
+            for path in json_template::references(mapping) {
+                if !matches!(path.split('.').next(), Some("payload" | "headers")) {
+                    return Err(invalid(format!(
+                        "input mapping reads '{}', which is neither the payload nor a header",
+                        path
+                    )));
+                }
+            }
+        }
+
+        let reference = trigger.task_id.as_str();
+        let task = match (trigger.task_id.as_i32(), trigger.task_id.as_uuid()) {
+            (Some(id), _) => self.tasks.find_by_id(id).await,
+            (None, Some(uuid)) => self.tasks.find_by_uuid(uuid).await,
+            (None, None) => self.tasks.find_by_name(reference).await,
+        }
+        .map_err(storage_error)?
+        .ok_or_else(|| invalid(format!("unknown task '{}'", reference)))?;
+        trigger.task_id = ApiId::from_i32(task.id);
+        Ok(())
+    }
+
+    async fn ensure_name_is_free(&self, name: &str, id: Option<i32>) -> Result<(), TriggerError> {
+        let existing = self.repository.find_by_name(name).await.map_err(storage_error)?;
+        match existing {
+            Some(existing) if Some(existing.id) != id => Err(invalid(format!(
+                "a webhook trigger named '{}' already exists",
+                name
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    fn to_unified(trigger: WebhookTrigger) -> Result<UnifiedWebhookTrigger, TriggerError> {
+        let signature: UnifiedTriggerSignature = serde_json::from_value(trigger.signature).map_err(storage_error)?;
+        Ok(UnifiedWebhookTrigger {
+            id: ApiId::from_i32(trigger.id),
+            name: trigger.name,
+            description: trigger.description,
+            task_id: ApiId::from_i32(trigger.task_id),
+            enabled: trigger.enabled,
+            signature,
+            input_mapping: trigger.input_mapping,
+            filter_schema: trigger.filter_schema,
+            delivery_id_header: trigger.delivery_id_header,
+            created_at: trigger.created_at,
+            updated_at: trigger.updated_at,
+        })
+    }
+
+    /// Decrypt the signing key, resolved as if the trigger's task asked for it
+    async fn signing_secret(&self, trigger: &WebhookTrigger, secret_name: &str) -> Result<String, TriggerError> {
+        let task = self
+            .tasks
+            .find_by_id(trigger.task_id)
+            .await
+            .map_err(storage_error)?
+            .ok_or_else(|| storage_error(format!("task {} of trigger '{}' no longer exists", trigger.task_id, trigger.name)))?;
+
+        let names = vec![secret_name.to_string()];
+        let mut values = self
+            .secrets
+            .resolve_secrets(&names, Some(&task.uuid.to_string()), Some(&task.repository_id.to_string()))
+            .await
+            .map_err(|e| match e {
+                SecretError::NotFound { name } => invalid(format!("signing secret '{}' is not configured", name)),
+                other => storage_error(other),
+            })?;
+        values
+            .remove(secret_name)
+            .ok_or_else(|| invalid(format!("signing secret '{}' is not configured", secret_name)))
+    }
+
+    /// Forget deliveries claimed for a delivery that queued no job
+    async fn release_deliveries(&self, deliveries: &[WebhookTriggerDelivery]) -> Result<(), TriggerError> {
+        for delivery in deliveries {
+            self.repository
+                .release_delivery(delivery.id)
+                .await
+                .map_err(storage_error)?;
+        }
+        Ok(())
+    }
+
+    /// Queue the job of a delivery and link it to the delivery record
+    async fn queue_job(&self, trigger: &WebhookTrigger, input: JsonValue) -> Result<ApiId, TriggerError> {
+        let job = self
+            .repositories
+            .job_repository()
+            .create(UnifiedJob {
+                id: ApiId::from_i32(0), // Will be set by database
+                task_id: ApiId::from_i32(trigger.task_id),
+                priority: JobPriority::Normal,
+                status: JobStatus::Queued,
+                retry_count: 0,
+                max_retries: TRIGGERED_JOB_MAX_RETRIES,
+                queued_at: Utc::now(),
+                scheduled_for: None,
+                error_message: None,
+                output_destinations: None,
+                input: Some(input),
+                depends_on: Vec::new(),
+                idempotency_key: None,
+                schedule_id: None,
+            })
+            .await
+            .map_err(storage_error)?;
+        Ok(job.id)
+    }
+}
+
+#[async_trait]
+impl TriggerStore for TriggerManager {
+    async fn create_trigger(&self, mut trigger: UnifiedWebhookTrigger) -> Result<UnifiedWebhookTrigger, TriggerError> {
+        self.validate(&mut trigger).await?;
+        self.ensure_name_is_free(&trigger.name, None).await?;
+
+        let now = Utc::now();
+        let created = self
+            .repository
+            .create(WebhookTrigger {
+                id: 0,
+                uuid: Uuid::new_v4(),
+                name: trigger.name,
+                description: trigger.description,
+                task_id: trigger.task_id.as_i32().unwrap_or(0),
+                enabled: trigger.enabled,
+                signature: serde_json::to_value(trigger.signature).map_err(storage_error)?,
+                input_mapping: trigger.input_mapping,
+                filter_schema: trigger.filter_schema,
+                delivery_id_header: trigger.delivery_id_header,
+                created_at: now,
+                updated_at: now,
+            })
+            .await
+            .map_err(storage_error)?;
+        Self::to_unified(created)
+    }
+
+    async fn update_trigger(&self, mut trigger: UnifiedWebhookTrigger) -> Result<UnifiedWebhookTrigger, TriggerError> {
+        let existing = match trigger.id.as_i32() {
+            Some(id) => self.repository.find_by_id(id).await.map_err(storage_error)?,
+            None => None,
+        }
+        .ok_or_else(|| TriggerError::NotFound {
+            name: trigger.id.to_string(),
+        })?;
+        self.validate(&mut trigger).await?;
+        self.ensure_name_is_free(&trigger.name, Some(existing.id)).await?;
+
+        let updated = self
+            .repository
+            .update(WebhookTrigger {
+                name: trigger.name,
+                description: trigger.description,
+                task_id: trigger.task_id.as_i32().unwrap_or(existing.task_id),
+                enabled: trigger.enabled,
+                signature: serde_json::to_value(trigger.signature).map_err(storage_error)?,
+                input_mapping: trigger.input_mapping,
+                filter_schema: trigger.filter_schema,
+                delivery_id_header: trigger.delivery_id_header,
+                ..existing
+            })
+            .await
+            .map_err(storage_error)?;
+        Self::to_unified(updated)
+    }
+
+    async fn find_trigger(&self, id: ApiId) -> Result<Option<UnifiedWebhookTrigger>, TriggerError> {
+        let Some(id) = id.as_i32() else {
+            return Ok(None);
+        };
+        self.repository
+            .find_by_id(id)
+            .await
+            .map_err(storage_error)?
+            .map(Self::to_unified)
+            .transpose()
+    }
+
+    async fn list_triggers(&self) -> Result<Vec<UnifiedWebhookTrigger>, TriggerError> {
+        self.repository
+            .list()
+            .await
+            .map_err(storage_error)?
+            .into_iter()
+            .map(Self::to_unified)
+            .collect()
+    }
+
+    async fn delete_trigger(&self, id: ApiId) -> Result<bool, TriggerError> {
+        let Some(id) = id.as_i32() else {
+            return Ok(false);
+        };
+        self.repository.delete(id).await.map_err(storage_error)
+    }
+
+    async fn deliver(
+        &self,
+        name: &str,
+        headers: &HashMap<String, String>,
+        body: &[u8],
+    ) -> Result<UnifiedTriggerDelivery, TriggerError> {
+        let trigger = self
+            .repository
+            .find_by_name(name)
+            .await
+            .map_err(storage_error)?
+            .filter(|trigger| trigger.enabled)
+            .ok_or_else(|| TriggerError::NotFound { name: name.to_string() })?;
+        let settings = Self::to_unified(trigger.clone())?;
+        let headers: HashMap<String, String> = headers
+            .iter()
+            .map(|(name, value)| (name.to_lowercase(), value.clone()))
+            .collect();
+
+        let secret = self.signing_secret(&trigger, &settings.signature.secret_name).await?;
+        let signature_digest = signature::verify(&settings.signature, &secret, &headers, body, Utc::now())
+            .map_err(|message| TriggerError::Unauthorized { message })?;
+
+        let payload: JsonValue =
+            serde_json::from_slice(body).map_err(|e| invalid(format!("payload is not valid JSON: {}", e)))?;
+        let outcome = |status, job_id| UnifiedTriggerDelivery {
+            trigger: trigger.name.clone(),
+            status,
+            job_id,
+        };
+
+        if let Some(schema) = &settings.filter_schema {
+            let validator = jsonschema::validator_for(schema).map_err(|e| invalid(format!("invalid filter schema: {}", e)))?;
+            if !validator.is_valid(&payload) {
+                debug!("Delivery to trigger {} did not match its filter", trigger.name);
+                return Ok(outcome(TriggerDeliveryStatus::Filtered, None));
+            }
+        }
+
+        let input = match &settings.input_mapping {
+            Some(mapping) => {
+                let header_values = headers
+                    .iter()
+                    .map(|(name, value)| (name.clone(), JsonValue::String(value.clone())))
+                    .collect();
+                let root = serde_json::json!({"payload": payload, "headers": JsonValue::Object(header_values)});
+                json_template::resolve(mapping, &root).map_err(|message| invalid(format!("input mapping failed: {}", message)))?
+            }
+            None => payload,
+        };
+
+        // A signed delivery sent again carries the same signature, so replays are
+        // recognised by it; the unsigned delivery ID only catches retries the
+        // sender signed anew
+        let mut replay_keys = vec![format!("{}{}", SIGNATURE_KEY_PREFIX, signature_digest)];
+        if let Some(header) = &settings.delivery_id_header {
+            let delivery_id = headers
+                .get(&header.to_lowercase())
+                .ok_or_else(|| invalid(format!("missing {} header", header)))?;
+            replay_keys.push(format!("delivery:{}", delivery_id));
+        }
+        let mut claimed = Vec::with_capacity(replay_keys.len());
+        for key in &replay_keys {
+            match self
+                .repository
+                .claim_delivery(trigger.id, key)
+                .await
+                .map_err(storage_error)?
+            {
+                DeliveryClaim::New(delivery) => claimed.push(delivery),
+                DeliveryClaim::Seen(seen) => {
+                    debug!("Ignoring repeated delivery to trigger {}", trigger.name);
+                    self.release_deliveries(&claimed).await?;
+                    return Ok(outcome(
+                        TriggerDeliveryStatus::Duplicate,
+                        seen.job_id.map(ApiId::from_i32),
+                    ));
+                }
+            }
+        }
+
+        let job_id = match self.queue_job(&trigger, input).await {
+            Ok(job_id) => job_id,
+            Err(e) => {
+                // Let the sender retry the delivery
+                self.release_deliveries(&claimed).await?;
+                return Err(e);
+            }
+        };
+        if let Some(id) = job_id.as_i32() {
+            for delivery in &claimed {
+                self.repository
+                    .set_delivery_job(delivery.id, id)
+                    .await
+                    .map_err(storage_error)?;
+            }
+        }
+        let cutoff = Utc::now() - chrono::Duration::days(DELIVERY_RETENTION_DAYS);
+        // Signatures without a timestamp would verify forever, so they are never forgotten
+        let keep = match settings.signature.timestamp_header {
+            Some(_) => None,
+            None => Some(SIGNATURE_KEY_PREFIX),
+        };
+        self.repository
+            .prune_deliveries(trigger.id, cutoff, keep)
+            .await
+            .map_err(storage_error)?;
+
+        info!("Trigger {} queued job {}", trigger.name, job_id);
+        Ok(outcome(TriggerDeliveryStatus::Queued, Some(job_id)))
+    }
+}
+
+fn invalid(message: impl Into<String>) -> TriggerError {
+    TriggerError::Invalid {
+        message: message.into(),
+    }
+}
+
+fn storage_error(error: impl std::fmt::Display) -> TriggerError {
+    TriggerError::Storage {
+        message: error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::EncryptionAlgorithm;
+    use crate::security::{AesEncryptionService, EncryptionService, SecretManager};
+    use crate::services::DirectRepositoryFactory;
+    use hmac::{Hmac, KeyInit, Mac};
+    use ratchet_api_types::TriggerSignatureAlgorithm;
+    use ratchet_interfaces::SecretKey;
+    use ratchet_storage::seaorm::repositories::{RepositoryFactory as StorageFactory, TaskSecretRepository};
+    use ratchet_storage::seaorm::{config::DatabaseConfig, connection::DatabaseConnection};
+    use ratchet_storage::testing::TaskBuilder;
+    use serde_json::json;
+    use std::time::Duration;
+
+    async fn create_manager() -> (TriggerManager, Arc<dyn RepositoryFactory>, DatabaseConnection) {
+        let db = DatabaseConnection::new(DatabaseConfig {
+            url: "sqlite::memory:".to_string(),
+            max_connections: 5,
+            connection_timeout: Duration::from_secs(10),
+        })
+        .await
+        .unwrap();
+        db.migrate().await.unwrap();
+
+        TaskRepository::new(db.clone())
+            .create(TaskBuilder::new().with_name("deploy").build())
+            .await
+            .unwrap();
+
+        let encryption = AesEncryptionService::new(EncryptionAlgorithm::AES256);
+        encryption.initialize().await.unwrap();
+        let secrets = SecretManager::new(TaskSecretRepository::new(db.clone()), Arc::new(encryption));
+        secrets
+            .set_secret(SecretKey::global("DEPLOY_HOOK_SECRET"), "s3cret", None)
+            .await
+            .unwrap();
+
+        let storage = Arc::new(StorageFactory::new(db.clone()));
+        let repositories: Arc<dyn RepositoryFactory> = Arc::new(DirectRepositoryFactory::new(storage));
+        let manager = TriggerManager::new(
+            WebhookTriggerRepository::new(db.clone()),
+            TaskRepository::new(db.clone()),
+            repositories.clone(),
+            Arc::new(secrets),
+        );
+        (manager, repositories, db)
+    }
+
+    fn trigger() -> UnifiedWebhookTrigger {
+        UnifiedWebhookTrigger {
+            id: ApiId::from_i32(0),
+            name: "deploy-hook".to_string(),
+            description: None,
+            task_id: ApiId::from_string("deploy"),
+            enabled: true,
+            signature: UnifiedTriggerSignature {
+                secret_name: "DEPLOY_HOOK_SECRET".to_string(),
+                algorithm: TriggerSignatureAlgorithm::Sha256,
+                header: "X-Signature".to_string(),
+                prefix: Some("sha256=".to_string()),
+                timestamp_header: None,
+                max_age_seconds: None,
+            },
+            input_mapping: Some(json!({"branch": {"$from": "payload.ref"}, "event": {"$from": "headers.x-event"}})),
+            filter_schema: Some(json!({"type": "object", "required": ["ref"]})),
+            delivery_id_header: Some("X-Delivery".to_string()),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn signed_headers(body: &[u8], delivery_id: &str) -> HashMap<String, String> {
+        let mut mac = <Hmac<sha2::Sha256> as KeyInit>::new_from_slice(b"s3cret").unwrap();
+        mac.update(body);
+        HashMap::from([
+            (
+                "X-Signature".to_string(),
+                format!("sha256={}", hex::encode(mac.finalize().into_bytes())),
+            ),
+            ("X-Delivery".to_string(), delivery_id.to_string()),
+            ("X-Event".to_string(), "push".to_string()),
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_verified_deliveries_queue_one_job() {
+        let (manager, repositories, _) = create_manager().await;
+        manager.create_trigger(trigger()).await.unwrap();
+
+        let body = br#"{"ref": "main"}"#;
+        let queued = manager
+            .deliver("deploy-hook", &signed_headers(body, "d-1"), body)
+            .await
+            .unwrap();
+        assert_eq!(queued.status, TriggerDeliveryStatus::Queued);
+        let job_id = queued.job_id.unwrap();
+        let job = repositories
+            .job_repository()
+            .find_by_id(job_id.as_i32().unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(job.input, Some(json!({"branch": "main", "event": "push"})));
+
+        let repeated = manager
+            .deliver("deploy-hook", &signed_headers(body, "d-1"), body)
+            .await
+            .unwrap();
+        assert_eq!(repeated.status, TriggerDeliveryStatus::Duplicate);
+        assert_eq!(repeated.job_id, Some(job_id));
+
+        // The delivery ID is not signed, so a replay under a new one is caught by its signature
+        let replayed = manager
+            .deliver("deploy-hook", &signed_headers(body, "d-9"), body)
+            .await
+            .unwrap();
+        assert_eq!(replayed.status, TriggerDeliveryStatus::Duplicate);
+        assert_eq!(replayed.job_id, Some(job_id));
+
+        let filtered_body = br#"{"zen": "hi"}"#;
+        let filtered = manager
+            .deliver("deploy-hook", &signed_headers(filtered_body, "d-2"), filtered_body)
+            .await
+            .unwrap();
+        assert_eq!(filtered.status, TriggerDeliveryStatus::Filtered);
+        assert!(filtered.job_id.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_deliveries_older_than_the_retention_cannot_be_replayed() {
+        use ratchet_storage::seaorm::entities::{webhook_trigger_deliveries, WebhookTriggerDeliveries};
+        use sea_orm::{sea_query::Expr, EntityTrait};
+
+        let (manager, _, db) = create_manager().await;
+        manager.create_trigger(trigger()).await.unwrap();
+        let body = br#"{"ref": "main"}"#;
+        let queued = manager
+            .deliver("deploy-hook", &signed_headers(body, "d-1"), body)
+            .await
+            .unwrap();
+
+        // The delivery was received before the retention window, and the next one prunes
+        let received_at = Utc::now() - chrono::Duration::days(DELIVERY_RETENTION_DAYS + 1);
+        WebhookTriggerDeliveries::update_many()
+            .col_expr(webhook_trigger_deliveries::Column::ReceivedAt, Expr::value(received_at))
+            .exec(db.get_connection())
+            .await
+            .unwrap();
+        let other = br#"{"ref": "release"}"#;
+        manager
+            .deliver("deploy-hook", &signed_headers(other, "d-2"), other)
+            .await
+            .unwrap();
+
+        // Without a timestamp its signature still verifies, so it is still remembered
+        let replayed = manager
+            .deliver("deploy-hook", &signed_headers(body, "d-9"), body)
+            .await
+            .unwrap();
+        assert_eq!(replayed.status, TriggerDeliveryStatus::Duplicate);
+        assert_eq!(replayed.job_id, queued.job_id);
+    }
+
+    #[tokio::test]
+    async fn test_unsigned_deliveries_are_rejected() {
+        let (manager, _, _) = create_manager().await;
+        manager.create_trigger(trigger()).await.unwrap();
+
+        let mut headers = signed_headers(b"{}", "d-1");
+        headers.insert("X-Signature".to_string(), "sha256=00".to_string());
+        let result = manager.deliver("deploy-hook", &headers, br#"{"ref": "main"}"#).await;
+        assert!(matches!(result, Err(TriggerError::Unauthorized { .. })));
+
+        let result = manager.deliver("unknown", &headers, b"{}").await;
+        assert!(matches!(result, Err(TriggerError::NotFound { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_triggers_are_rejected() {
+        let (manager, _, _) = create_manager().await;
+
+        let bad_mapping = UnifiedWebhookTrigger {
+            input_mapping: Some(json!({"$from": "steps.a"})),
+            ..trigger()
+        };
+        assert!(matches!(
+            manager.create_trigger(bad_mapping).await,
+            Err(TriggerError::Invalid { .. })
+        ));
+
+        let bad_name = UnifiedWebhookTrigger {
+            name: "deploy hook".to_string(),
+            ..trigger()
+        };
+        assert!(matches!(
+            manager.create_trigger(bad_name).await,
+            Err(TriggerError::Invalid { .. })
+        ));
+
+        // Deliveries must expire before they are forgotten
+        let mut outlives_retention = trigger();
+        outlives_retention.signature.timestamp_header = Some("X-Timestamp".to_string());
+        outlives_retention.signature.max_age_seconds = Some(30 * 24 * 3600);
+        assert!(matches!(
+            manager.create_trigger(outlives_retention).await,
+            Err(TriggerError::Invalid { .. })
+        ));
+    }
+}
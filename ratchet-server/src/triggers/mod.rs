@@ -0,0 +1,11 @@
+//! Inbound webhook triggers
+//!
+//! External systems queue jobs by posting to `/triggers/{name}`.
+//! [`TriggerManager`] stores the triggers, verifies the HMAC signature of each
+//! delivery, filters and maps its payload and queues the job. A delivery sent
+//! again with the same signature queues no second job.
+
+pub mod manager;
+pub mod signature;
+
+pub use manager::TriggerManager;
@@ -0,0 +1,145 @@
+//! HMAC verification of webhook trigger deliveries
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, KeyInit, Mac};
+use sha2::{Digest, Sha256, Sha512};
+use std::collections::HashMap;
+
+use ratchet_api_types::{TriggerSignatureAlgorithm, UnifiedTriggerSignature};
+
+/// How old a timestamped delivery may be when the trigger sets no limit
+pub const DEFAULT_MAX_AGE_SECONDS: i64 = 300;
+
+/// Check the signature of a delivery
+///
+/// `headers` must have lowercase names. The signature is the hex encoded HMAC
+/// of the body, or of `{timestamp}.{body}` when the trigger names a timestamp
+/// header, and is compared in constant time.
+///
+/// Returns a digest of the verified signature. It only repeats when a signed
+/// delivery is sent again, so it identifies the delivery for replay protection.
+pub fn verify(
+    signature: &UnifiedTriggerSignature,
+    secret: &str,
+    headers: &HashMap<String, String>,
+    body: &[u8],
+    now: DateTime<Utc>,
+) -> Result<String, String> {
+    let header = |name: &str| {
+        headers
+            .get(&name.to_lowercase())
+            .map(|value| value.trim())
+            .ok_or_else(|| format!("missing {} header", name))
+    };
+
+    let provided = header(&signature.header)?;
+    let provided = match &signature.prefix {
+        Some(prefix) => provided
+            .strip_prefix(prefix.as_str())
+            .ok_or_else(|| format!("{} header does not start with '{}'", signature.header, prefix))?,
+        None => provided,
+    };
+    let provided = hex::decode(provided).map_err(|_| format!("{} header is not hex encoded", signature.header))?;
+
+    let timestamp = match &signature.timestamp_header {
+        Some(name) => {
+            let value = header(name)?;
+            let sent: i64 = value.parse().map_err(|_| format!("{} header is not a Unix timestamp", name))?;
+            let max_age = signature.max_age_seconds.unwrap_or(DEFAULT_MAX_AGE_SECONDS);
+            if (now.timestamp() - sent).abs() > max_age {
+                return Err(format!("delivery timestamp is more than {} seconds off", max_age));
+            }
+            Some(value)
+        }
+        None => None,
+    };
+
+    let mut parts: Vec<&[u8]> = Vec::with_capacity(3);
+    if let Some(timestamp) = timestamp {
+        parts.push(timestamp.as_bytes());
+        parts.push(b".");
+    }
+    parts.push(body);
+
+    let valid = match signature.algorithm {
+        TriggerSignatureAlgorithm::Sha256 => verify_mac::<Hmac<Sha256>>(secret.as_bytes(), &parts, &provided),
+        TriggerSignatureAlgorithm::Sha512 => verify_mac::<Hmac<Sha512>>(secret.as_bytes(), &parts, &provided),
+    };
+    if valid {
+        Ok(hex::encode(Sha256::digest(&provided)))
+    } else {
+        Err("signature does not match".to_string())
+    }
+}
+
+fn verify_mac<M: Mac + KeyInit>(key: &[u8], parts: &[&[u8]], expected: &[u8]) -> bool {
+    let Ok(mut mac) = <M as KeyInit>::new_from_slice(key) else {
+        return false;
+    };
+    for part in parts {
+        mac.update(part);
+    }
+    mac.verify_slice(expected).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sign(secret: &str, content: &[u8]) -> String {
+        let mut mac = <Hmac<Sha256> as KeyInit>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(content);
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn github_signature() -> UnifiedTriggerSignature {
+        UnifiedTriggerSignature {
+            secret_name: "GITHUB_SECRET".to_string(),
+            algorithm: TriggerSignatureAlgorithm::Sha256,
+            header: "X-Hub-Signature-256".to_string(),
+            prefix: Some("sha256=".to_string()),
+            timestamp_header: None,
+            max_age_seconds: None,
+        }
+    }
+
+    #[test]
+    fn test_verify_body_signature() {
+        let body = br#"{"ref":"main"}"#;
+        let headers = HashMap::from([(
+            "x-hub-signature-256".to_string(),
+            format!("sha256={}", sign("s3cret", body)),
+        )]);
+
+        let digest = verify(&github_signature(), "s3cret", &headers, body, Utc::now()).unwrap();
+        assert_eq!(
+            digest,
+            verify(&github_signature(), "s3cret", &headers, body, Utc::now()).unwrap()
+        );
+        assert!(verify(&github_signature(), "other", &headers, body, Utc::now()).is_err());
+        assert!(verify(&github_signature(), "s3cret", &headers, b"{}", Utc::now()).is_err());
+        assert!(verify(&github_signature(), "s3cret", &HashMap::new(), body, Utc::now()).is_err());
+    }
+
+    #[test]
+    fn test_verify_timestamped_signature() {
+        let signature = UnifiedTriggerSignature {
+            prefix: None,
+            header: "X-Signature".to_string(),
+            timestamp_header: Some("X-Timestamp".to_string()),
+            max_age_seconds: Some(60),
+            ..github_signature()
+        };
+        let now = Utc::now();
+        let body = b"{}";
+        let headers_at = |sent: i64| {
+            HashMap::from([
+                ("x-timestamp".to_string(), sent.to_string()),
+                ("x-signature".to_string(), sign("s3cret", format!("{}.{{}}", sent).as_bytes())),
+            ])
+        };
+
+        assert!(verify(&signature, "s3cret", &headers_at(now.timestamp() - 30), body, now).is_ok());
+        assert!(verify(&signature, "s3cret", &headers_at(now.timestamp() - 120), body, now).is_err());
+    }
+}
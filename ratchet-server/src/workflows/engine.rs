@@ -0,0 +1,268 @@
+//! Execution of workflow runs
+
+use chrono::Utc;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+use ratchet_api_types::{
+    ApiId, UnifiedWorkflow, UnifiedWorkflowRun, UnifiedWorkflowStep, UnifiedWorkflowStepRun, WorkflowFailurePolicy,
+    WorkflowRunStatus, WorkflowStepStatus,
+};
+use ratchet_execution::{TaskCallRequest, TaskCalls, TaskServiceCaller};
+use ratchet_interfaces::{RepositoryFactory, WorkflowError, WorkflowStore};
+
+use super::graph;
+
+/// Runs claimed workflow runs step by step
+///
+/// Steps run one at a time in dependency order, each as a recorded execution of
+/// its task. The run is saved after every step, so its record always shows how
+/// far it got.
+pub struct WorkflowEngine {
+    store: Arc<dyn WorkflowStore>,
+    caller: Arc<TaskServiceCaller>,
+    repositories: Arc<dyn RepositoryFactory>,
+}
+
+impl WorkflowEngine {
+    /// Create a new workflow engine
+    pub fn new(
+        store: Arc<dyn WorkflowStore>,
+        caller: Arc<TaskServiceCaller>,
+        repositories: Arc<dyn RepositoryFactory>,
+    ) -> Self {
+        Self {
+            store,
+            caller,
+            repositories,
+        }
+    }
+
+    /// Claim up to `limit` queued runs and start each of them in the background
+    pub async fn start_queued_runs(self: &Arc<Self>, limit: u64) -> Result<usize, WorkflowError> {
+        let runs = self.store.claim_queued_runs(limit).await?;
+        let started = runs.len();
+        for run in runs {
+            let engine = self.clone();
+            tokio::spawn(async move {
+                let id = run.id.clone();
+                if let Err(e) = engine.execute_run(run).await {
+                    error!("Failed to execute workflow run {}: {}", id, e);
+                }
+            });
+        }
+        Ok(started)
+    }
+
+    /// Run every step of a claimed run and store the outcome
+    pub async fn execute_run(&self, mut run: UnifiedWorkflowRun) -> Result<UnifiedWorkflowRun, WorkflowError> {
+        run.status = WorkflowRunStatus::Running;
+        run.started_at.get_or_insert_with(Utc::now);
+
+        let workflow = match self.store.find_workflow(run.workflow_id.clone()).await? {
+            Some(workflow) => workflow,
+            None => return self.fail_run(run, "the workflow no longer exists".to_string()).await,
+        };
+        let order = match graph::execution_order(&workflow.steps) {
+            Ok(order) => order,
+            Err(e) => return self.fail_run(run, e.to_string()).await,
+        };
+        info!("Running workflow {} as run {}", workflow.name, run.id);
+
+        // Runs queued before the definition changed only know about the old steps
+        for step in &workflow.steps {
+            if run.step(&step.id).is_none() {
+                run.steps.push(pending_step(&step.id));
+            }
+        }
+
+        let mut outputs: HashMap<String, JsonValue> = HashMap::new();
+        let mut aborted_by: Option<String> = None;
+        for index in order {
+            let step = &workflow.steps[index];
+
+            let blocked = match &aborted_by {
+                Some(failed) => Some(format!("the workflow was aborted after step '{}' failed", failed)),
+                None => step
+                    .depends_on
+                    .iter()
+                    .find(|dependency| !outputs.contains_key(dependency.as_str()))
+                    .map(|dependency| format!("step '{}' did not complete", dependency)),
+            };
+            if let Some(reason) = blocked {
+                let state = step_state(&mut run, &step.id);
+                state.status = WorkflowStepStatus::Skipped;
+                state.error_message = Some(reason);
+                run = self.store.save_run(run).await?;
+                continue;
+            }
+
+            match self.execute_step(&workflow, step, &mut run, &outputs).await? {
+                Ok(output) => {
+                    outputs.insert(step.id.clone(), output);
+                }
+                Err(message) => {
+                    warn!("Step {} of workflow run {} failed: {}", step.id, run.id, message);
+                    if step.on_failure == WorkflowFailurePolicy::Abort {
+                        run.error_message = Some(format!("step '{}' failed: {}", step.id, message));
+                        aborted_by = Some(step.id.clone());
+                    }
+                }
+            }
+            run = self.store.save_run(run).await?;
+        }
+
+        run.status = if aborted_by.is_some() {
+            WorkflowRunStatus::Failed
+        } else {
+            WorkflowRunStatus::Completed
+        };
+        let final_outputs: serde_json::Map<String, JsonValue> = graph::final_steps(&workflow.steps)
+            .into_iter()
+            .filter_map(|id| outputs.get(id).map(|output| (id.to_string(), output.clone())))
+            .collect();
+        run.output = Some(JsonValue::Object(final_outputs));
+        run.completed_at = Some(Utc::now());
+
+        info!("Workflow run {} finished as {:?}", run.id, run.status);
+        self.store.save_run(run).await
+    }
+
+    /// Run one step, retrying it as its policy allows
+    ///
+    /// The outer result is a storage failure; the inner one the outcome of the step.
+    async fn execute_step(
+        &self,
+        workflow: &UnifiedWorkflow,
+        step: &UnifiedWorkflowStep,
+        run: &mut UnifiedWorkflowRun,
+        outputs: &HashMap<String, JsonValue>,
+    ) -> Result<Result<JsonValue, String>, WorkflowError> {
+        let state = step_state(run, &step.id);
+        state.status = WorkflowStepStatus::Running;
+        state.started_at = Some(Utc::now());
+
+        let prepared = match graph::resolve_input(step.input.as_ref(), &run.input, outputs) {
+            Ok(input) => self.task_reference(&step.task_id).await.map(|task| (task, input)),
+            Err(e) => Err(e),
+        };
+        let (task, input) = match prepared {
+            Ok(prepared) => prepared,
+            Err(message) => return Ok(finish_step(run, &step.id, Err(message))),
+        };
+
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let state = step_state(run, &step.id);
+            state.attempts = attempt;
+            *run = self.store.save_run(run.clone()).await?;
+
+            debug!("Running step {} of workflow run {} (attempt {})", step.id, run.id, attempt);
+            let (execution_id, result) = self.run_task(&workflow.name, &task, input.clone()).await;
+            step_state(run, &step.id).execution_id = execution_id;
+
+            match result {
+                Ok(output) => return Ok(finish_step(run, &step.id, Ok(output))),
+                Err(message) if attempt > step.max_retries => {
+                    return Ok(finish_step(run, &step.id, Err(message)));
+                }
+                Err(message) => {
+                    debug!("Retrying step {} of workflow run {}: {}", step.id, run.id, message);
+                    step_state(run, &step.id).error_message = Some(message);
+                    tokio::time::sleep(Duration::from_secs(step.retry_delay_seconds as u64)).await;
+                }
+            }
+        }
+    }
+
+    /// UUID of the task a step runs; workflows store steps with storage task IDs
+    async fn task_reference(&self, task_id: &ApiId) -> Result<String, String> {
+        let Some(id) = task_id.as_i32() else {
+            return Ok(task_id.to_string());
+        };
+        match self.repositories.task_repository().find_by_id(id).await {
+            Ok(Some(task)) => Ok(task.uuid.to_string()),
+            Ok(None) => Err(format!("task {} no longer exists", task_id)),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    /// Run a task on a blocking thread, as the JavaScript runtime cannot move between threads
+    async fn run_task(&self, workflow: &str, task: &str, input: JsonValue) -> (Option<ApiId>, Result<JsonValue, String>) {
+        let caller = self.caller.clone();
+        let calls = TaskCalls::new(caller.clone()).for_execution(workflow, None);
+        let request = TaskCallRequest {
+            task: task.to_string(),
+            input,
+            version: None,
+        };
+        let handle = tokio::runtime::Handle::current();
+
+        let joined = tokio::task::spawn_blocking(move || {
+            handle.block_on(async move {
+                let (execution_id, result) = caller.execute(&calls, request).await;
+                (execution_id, result.map_err(|e| e.to_string()))
+            })
+        })
+        .await;
+        joined.unwrap_or_else(|e| (None, Err(format!("step panicked: {}", e))))
+    }
+
+    async fn fail_run(&self, mut run: UnifiedWorkflowRun, message: String) -> Result<UnifiedWorkflowRun, WorkflowError> {
+        warn!("Workflow run {} failed: {}", run.id, message);
+        run.status = WorkflowRunStatus::Failed;
+        run.error_message = Some(message);
+        run.completed_at = Some(Utc::now());
+        self.store.save_run(run).await
+    }
+}
+
+fn pending_step(step_id: &str) -> UnifiedWorkflowStepRun {
+    UnifiedWorkflowStepRun {
+        step_id: step_id.to_string(),
+        status: WorkflowStepStatus::Pending,
+        execution_id: None,
+        attempts: 0,
+        output: None,
+        error_message: None,
+        started_at: None,
+        completed_at: None,
+    }
+}
+
+fn step_state<'a>(run: &'a mut UnifiedWorkflowRun, step_id: &str) -> &'a mut UnifiedWorkflowStepRun {
+    let index = match run.steps.iter().position(|state| state.step_id == step_id) {
+        Some(index) => index,
+        None => {
+            run.steps.push(pending_step(step_id));
+            run.steps.len() - 1
+        }
+    };
+    &mut run.steps[index]
+}
+
+/// Record the outcome of a step and hand it back
+fn finish_step(
+    run: &mut UnifiedWorkflowRun,
+    step_id: &str,
+    outcome: Result<JsonValue, String>,
+) -> Result<JsonValue, String> {
+    let state = step_state(run, step_id);
+    state.completed_at = Some(Utc::now());
+    match &outcome {
+        Ok(output) => {
+            state.status = WorkflowStepStatus::Completed;
+            state.output = Some(output.clone());
+            state.error_message = None;
+        }
+        Err(message) => {
+            state.status = WorkflowStepStatus::Failed;
+            state.error_message = Some(message.clone());
+        }
+    }
+    outcome
+}
@@ -0,0 +1,204 @@
+//! Validation and input mapping of workflow step graphs
+
+use serde_json::Value as JsonValue;
+use std::collections::{HashMap, HashSet};
+
+use ratchet_api_types::UnifiedWorkflowStep;
+use ratchet_interfaces::WorkflowError;
+
+use crate::json_template;
+
+/// Check that `steps` form a valid DAG and return their indices in execution order
+///
+/// Every step comes after the steps it depends on; independent steps keep their
+/// declaration order.
+pub fn execution_order(steps: &[UnifiedWorkflowStep]) -> Result<Vec<usize>, WorkflowError> {
+    if steps.is_empty() {
+        return Err(invalid("a workflow needs at least one step"));
+    }
+
+    let mut index = HashMap::with_capacity(steps.len());
+    for (i, step) in steps.iter().enumerate() {
+        if step.id.is_empty() {
+            return Err(invalid("step IDs cannot be empty"));
+        }
+        if index.insert(step.id.as_str(), i).is_some() {
+            return Err(invalid(format!("step ID '{}' is used more than once", step.id)));
+        }
+    }
+
+    for step in steps {
+        if step.max_retries < 0 || step.retry_delay_seconds < 0 {
+            return Err(invalid(format!(
+                "step '{}' has a negative retry count or delay",
+                step.id
+            )));
+        }
+        for dependency in &step.depends_on {
+            if dependency == &step.id {
+                return Err(invalid(format!("step '{}' depends on itself", step.id)));
+            }
+            if !index.contains_key(dependency.as_str()) {
+                return Err(invalid(format!(
+                    "step '{}' depends on unknown step '{}'",
+                    step.id, dependency
+                )));
+            }
+        }
+        if let Some(template) = &step.input {
+            for path in json_template::references(template) {
+                check_reference(step, &path)?;
+            }
+        }
+    }
+
+    // Kahn's algorithm, always taking the earliest declared ready step
+    let mut remaining: Vec<usize> = steps.iter().map(|step| step.depends_on.len()).collect();
+    let mut done = vec![false; steps.len()];
+    let mut order = Vec::with_capacity(steps.len());
+    while order.len() < steps.len() {
+        let Some(next) = (0..steps.len()).find(|&i| !done[i] && remaining[i] == 0) else {
+            let cycle: Vec<&str> = (0..steps.len())
+                .filter(|&i| !done[i])
+                .map(|i| steps[i].id.as_str())
+                .collect();
+            return Err(invalid(format!("steps {} form a cycle", cycle.join(", "))));
+        };
+        done[next] = true;
+        order.push(next);
+        for (i, step) in steps.iter().enumerate() {
+            remaining[i] -= step.depends_on.iter().filter(|d| **d == steps[next].id).count();
+        }
+    }
+    Ok(order)
+}
+
+/// IDs of the steps no other step depends on
+pub fn final_steps(steps: &[UnifiedWorkflowStep]) -> Vec<&str> {
+    let depended_on: HashSet<&str> = steps
+        .iter()
+        .flat_map(|step| step.depends_on.iter().map(String::as_str))
+        .collect();
+    steps
+        .iter()
+        .map(|step| step.id.as_str())
+        .filter(|id| !depended_on.contains(id))
+        .collect()
+}
+
+/// Build the input of a step from its template
+///
+/// Without a template the step receives the workflow input. `{"$from": "input.a"}`
+/// values are replaced by a field of the workflow input and `{"$from": "steps.fetch.b"}`
+/// values by a field of the output of step `fetch`; array elements are addressed
+/// by index.
+pub fn resolve_input(
+    template: Option<&JsonValue>,
+    input: &JsonValue,
+    outputs: &HashMap<String, JsonValue>,
+) -> Result<JsonValue, String> {
+    match template {
+        Some(template) => {
+            let steps = outputs.iter().map(|(id, output)| (id.clone(), output.clone())).collect();
+            let root = serde_json::json!({"input": input, "steps": JsonValue::Object(steps)});
+            json_template::resolve(template, &root)
+        }
+        None => Ok(input.clone()),
+    }
+}
+
+/// A step may only read the workflow input and the outputs of its dependencies
+fn check_reference(step: &UnifiedWorkflowStep, path: &str) -> Result<(), WorkflowError> {
+    let mut segments = path.split('.');
+    let valid = match segments.next() {
+        Some("input") => true,
+        Some("steps") => segments
+            .next()
+            .is_some_and(|source| step.depends_on.iter().any(|dependency| dependency == source)),
+        _ => false,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(invalid(format!(
+            "step '{}' reads '{}', which is neither the workflow input nor the output of a step it depends on",
+            step.id, path
+        )))
+    }
+}
+
+fn invalid(message: impl Into<String>) -> WorkflowError {
+    WorkflowError::Invalid {
+        message: message.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratchet_api_types::{ApiId, WorkflowFailurePolicy};
+    use serde_json::json;
+
+    fn step(id: &str, depends_on: &[&str], input: Option<JsonValue>) -> UnifiedWorkflowStep {
+        UnifiedWorkflowStep {
+            id: id.to_string(),
+            task_id: ApiId::from_i32(1),
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+            input,
+            max_retries: 0,
+            retry_delay_seconds: 0,
+            on_failure: WorkflowFailurePolicy::Abort,
+        }
+    }
+
+    #[test]
+    fn test_execution_order_follows_dependencies() {
+        let steps = vec![
+            step("report", &["fetch", "enrich"], None),
+            step("fetch", &[], None),
+            step("enrich", &["fetch"], Some(json!({"rows": {"$from": "steps.fetch.rows"}}))),
+        ];
+        let order: Vec<&str> = execution_order(&steps)
+            .unwrap()
+            .into_iter()
+            .map(|i| steps[i].id.as_str())
+            .collect();
+        assert_eq!(order, vec!["fetch", "enrich", "report"]);
+        assert_eq!(final_steps(&steps), vec!["report"]);
+    }
+
+    #[test]
+    fn test_invalid_graphs_are_rejected() {
+        let cycle = vec![step("a", &["b"], None), step("b", &["a"], None)];
+        assert!(matches!(execution_order(&cycle), Err(WorkflowError::Invalid { .. })));
+
+        let unknown = vec![step("a", &["missing"], None)];
+        assert!(matches!(execution_order(&unknown), Err(WorkflowError::Invalid { .. })));
+
+        let duplicate = vec![step("a", &[], None), step("a", &[], None)];
+        assert!(matches!(execution_order(&duplicate), Err(WorkflowError::Invalid { .. })));
+
+        let undeclared = vec![
+            step("a", &[], None),
+            step("b", &[], Some(json!({"$from": "steps.a.value"}))),
+        ];
+        assert!(matches!(execution_order(&undeclared), Err(WorkflowError::Invalid { .. })));
+    }
+
+    #[test]
+    fn test_resolve_input() {
+        let input = json!({"city": "Oslo", "days": [1, 2]});
+        let outputs = HashMap::from([("fetch".to_string(), json!({"temp": 4}))]);
+        let template = json!({
+            "city": {"$from": "input.city"},
+            "first": {"$from": "input.days.0"},
+            "readings": [{"$from": "steps.fetch.temp"}],
+            "unit": "C"
+        });
+
+        let resolved = resolve_input(Some(&template), &input, &outputs).unwrap();
+        assert_eq!(resolved, json!({"city": "Oslo", "first": 1, "readings": [4], "unit": "C"}));
+        assert_eq!(resolve_input(None, &input, &outputs).unwrap(), input);
+        assert!(resolve_input(Some(&json!({"$from": "input.missing"})), &input, &outputs).is_err());
+    }
+}
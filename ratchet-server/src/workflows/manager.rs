@@ -0,0 +1,381 @@
+//! Workflow store backed by the `workflows` and `workflow_runs` tables
+
+use async_trait::async_trait;
+use chrono::Utc;
+use uuid::Uuid;
+
+use ratchet_api_types::{
+    ApiId, UnifiedWorkflow, UnifiedWorkflowRun, UnifiedWorkflowStep, UnifiedWorkflowStepRun, WorkflowRunStatus,
+    WorkflowStepStatus,
+};
+use ratchet_interfaces::{WorkflowError, WorkflowStore};
+use ratchet_storage::seaorm::entities::{Workflow, WorkflowRun, WorkflowRunStatus as StoredRunStatus};
+use ratchet_storage::seaorm::repositories::{TaskRepository, WorkflowRepository};
+
+use super::graph;
+
+/// Validates workflows before they are stored and keeps track of their runs
+pub struct WorkflowManager {
+    repository: WorkflowRepository,
+    tasks: TaskRepository,
+}
+
+impl WorkflowManager {
+    /// Create a new workflow manager
+    pub fn new(repository: WorkflowRepository, tasks: TaskRepository) -> Self {
+        Self { repository, tasks }
+    }
+
+    /// Check the step graph and point every step at the storage ID of its task
+    ///
+    /// Steps may name their task by ID, UUID or name.
+    async fn validate(&self, workflow: &mut UnifiedWorkflow) -> Result<(), WorkflowError> {
+        if workflow.name.trim().is_empty() {
+            return Err(WorkflowError::Invalid {
+                message: "workflow name cannot be empty".to_string(),
+            });
+        }
+        graph::execution_order(&workflow.steps)?;
+
+        for step in &mut workflow.steps {
+            let reference = step.task_id.as_str();
+            let task = match (step.task_id.as_i32(), step.task_id.as_uuid()) {
+                (Some(id), _) => self.tasks.find_by_id(id).await,
+                (None, Some(uuid)) => self.tasks.find_by_uuid(uuid).await,
+                (None, None) => self.tasks.find_by_name(reference).await,
+            }
+            .map_err(storage_error)?
+            .ok_or_else(|| WorkflowError::Invalid {
+                message: format!("step '{}' uses unknown task '{}'", step.id, reference),
+            })?;
+            step.task_id = ApiId::from_i32(task.id);
+        }
+        Ok(())
+    }
+
+    async fn ensure_name_is_free(&self, name: &str, id: Option<i32>) -> Result<(), WorkflowError> {
+        let existing = self.repository.find_by_name(name).await.map_err(storage_error)?;
+        match existing {
+            Some(existing) if Some(existing.id) != id => Err(WorkflowError::Invalid {
+                message: format!("a workflow named '{}' already exists", name),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn storage_id(id: &ApiId, not_found: fn(String) -> WorkflowError) -> Result<i32, WorkflowError> {
+        id.as_i32().ok_or_else(|| not_found(id.to_string()))
+    }
+
+    fn to_unified(workflow: Workflow) -> Result<UnifiedWorkflow, WorkflowError> {
+        let steps: Vec<UnifiedWorkflowStep> = serde_json::from_value(workflow.steps).map_err(storage_error)?;
+        Ok(UnifiedWorkflow {
+            id: ApiId::from_i32(workflow.id),
+            name: workflow.name,
+            description: workflow.description,
+            enabled: workflow.enabled,
+            steps,
+            created_at: workflow.created_at,
+            updated_at: workflow.updated_at,
+        })
+    }
+
+    fn to_unified_run(run: WorkflowRun) -> Result<UnifiedWorkflowRun, WorkflowError> {
+        let steps: Vec<UnifiedWorkflowStepRun> = serde_json::from_value(run.steps).map_err(storage_error)?;
+        Ok(UnifiedWorkflowRun {
+            id: ApiId::from_i32(run.id),
+            uuid: run.uuid,
+            workflow_id: ApiId::from_i32(run.workflow_id),
+            schedule_id: run.schedule_id.map(ApiId::from_i32),
+            status: match run.status {
+                StoredRunStatus::Queued => WorkflowRunStatus::Queued,
+                StoredRunStatus::Running => WorkflowRunStatus::Running,
+                StoredRunStatus::Completed => WorkflowRunStatus::Completed,
+                StoredRunStatus::Failed => WorkflowRunStatus::Failed,
+            },
+            input: run.input,
+            output: run.output,
+            error_message: run.error_message,
+            steps,
+            queued_at: run.queued_at,
+            started_at: run.started_at,
+            completed_at: run.completed_at,
+        })
+    }
+
+    fn to_stored_run(run: UnifiedWorkflowRun) -> Result<WorkflowRun, WorkflowError> {
+        Ok(WorkflowRun {
+            id: Self::storage_id(&run.id, |id| WorkflowError::RunNotFound { id })?,
+            uuid: run.uuid,
+            workflow_id: Self::storage_id(&run.workflow_id, |id| WorkflowError::NotFound { id })?,
+            schedule_id: run.schedule_id.as_ref().and_then(ApiId::as_i32),
+            status: match run.status {
+                WorkflowRunStatus::Queued => StoredRunStatus::Queued,
+                WorkflowRunStatus::Running => StoredRunStatus::Running,
+                WorkflowRunStatus::Completed => StoredRunStatus::Completed,
+                WorkflowRunStatus::Failed => StoredRunStatus::Failed,
+            },
+            input: run.input,
+            output: run.output,
+            error_message: run.error_message,
+            steps: serde_json::to_value(run.steps).map_err(storage_error)?,
+            queued_at: run.queued_at,
+            started_at: run.started_at,
+            completed_at: run.completed_at,
+        })
+    }
+}
+
+#[async_trait]
+impl WorkflowStore for WorkflowManager {
+    async fn create_workflow(&self, mut workflow: UnifiedWorkflow) -> Result<UnifiedWorkflow, WorkflowError> {
+        self.validate(&mut workflow).await?;
+        self.ensure_name_is_free(&workflow.name, None).await?;
+
+        let now = Utc::now();
+        let created = self
+            .repository
+            .create(Workflow {
+                id: 0,
+                uuid: Uuid::new_v4(),
+                name: workflow.name,
+                description: workflow.description,
+                steps: serde_json::to_value(workflow.steps).map_err(storage_error)?,
+                enabled: workflow.enabled,
+                created_at: now,
+                updated_at: now,
+            })
+            .await
+            .map_err(storage_error)?;
+        Self::to_unified(created)
+    }
+
+    async fn update_workflow(&self, mut workflow: UnifiedWorkflow) -> Result<UnifiedWorkflow, WorkflowError> {
+        let id = Self::storage_id(&workflow.id, |id| WorkflowError::NotFound { id })?;
+        let existing = self
+            .repository
+            .find_by_id(id)
+            .await
+            .map_err(storage_error)?
+            .ok_or_else(|| WorkflowError::NotFound {
+                id: workflow.id.to_string(),
+            })?;
+        self.validate(&mut workflow).await?;
+        self.ensure_name_is_free(&workflow.name, Some(id)).await?;
+
+        let updated = self
+            .repository
+            .update(Workflow {
+                name: workflow.name,
+                description: workflow.description,
+                steps: serde_json::to_value(workflow.steps).map_err(storage_error)?,
+                enabled: workflow.enabled,
+                ..existing
+            })
+            .await
+            .map_err(storage_error)?;
+        Self::to_unified(updated)
+    }
+
+    async fn find_workflow(&self, id: ApiId) -> Result<Option<UnifiedWorkflow>, WorkflowError> {
+        let Some(id) = id.as_i32() else {
+            return Ok(None);
+        };
+        self.repository
+            .find_by_id(id)
+            .await
+            .map_err(storage_error)?
+            .map(Self::to_unified)
+            .transpose()
+    }
+
+    async fn list_workflows(&self) -> Result<Vec<UnifiedWorkflow>, WorkflowError> {
+        self.repository
+            .list()
+            .await
+            .map_err(storage_error)?
+            .into_iter()
+            .map(Self::to_unified)
+            .collect()
+    }
+
+    async fn delete_workflow(&self, id: ApiId) -> Result<bool, WorkflowError> {
+        let Some(id) = id.as_i32() else {
+            return Ok(false);
+        };
+        self.repository.delete(id).await.map_err(storage_error)
+    }
+
+    async fn queue_run(
+        &self,
+        workflow_id: ApiId,
+        input: serde_json::Value,
+        schedule_id: Option<ApiId>,
+    ) -> Result<UnifiedWorkflowRun, WorkflowError> {
+        let workflow = self
+            .find_workflow(workflow_id.clone())
+            .await?
+            .ok_or_else(|| WorkflowError::NotFound {
+                id: workflow_id.to_string(),
+            })?;
+        if !workflow.enabled {
+            return Err(WorkflowError::Invalid {
+                message: format!("workflow '{}' is disabled", workflow.name),
+            });
+        }
+
+        let steps: Vec<UnifiedWorkflowStepRun> = workflow
+            .steps
+            .iter()
+            .map(|step| UnifiedWorkflowStepRun {
+                step_id: step.id.clone(),
+                status: WorkflowStepStatus::Pending,
+                execution_id: None,
+                attempts: 0,
+                output: None,
+                error_message: None,
+                started_at: None,
+                completed_at: None,
+            })
+            .collect();
+
+        let created = self
+            .repository
+            .create_run(WorkflowRun {
+                id: 0,
+                uuid: Uuid::new_v4(),
+                workflow_id: Self::storage_id(&workflow.id, |id| WorkflowError::NotFound { id })?,
+                schedule_id: schedule_id.as_ref().and_then(ApiId::as_i32),
+                status: StoredRunStatus::Queued,
+                input,
+                output: None,
+                error_message: None,
+                steps: serde_json::to_value(steps).map_err(storage_error)?,
+                queued_at: Utc::now(),
+                started_at: None,
+                completed_at: None,
+            })
+            .await
+            .map_err(storage_error)?;
+        Self::to_unified_run(created)
+    }
+
+    async fn find_run(&self, id: ApiId) -> Result<Option<UnifiedWorkflowRun>, WorkflowError> {
+        let Some(id) = id.as_i32() else {
+            return Ok(None);
+        };
+        self.repository
+            .find_run_by_id(id)
+            .await
+            .map_err(storage_error)?
+            .map(Self::to_unified_run)
+            .transpose()
+    }
+
+    async fn find_latest_scheduled_run(&self, schedule_id: ApiId) -> Result<Option<UnifiedWorkflowRun>, WorkflowError> {
+        let Some(schedule_id) = schedule_id.as_i32() else {
+            return Ok(None);
+        };
+        self.repository
+            .find_latest_run_for_schedule(schedule_id)
+            .await
+            .map_err(storage_error)?
+            .map(Self::to_unified_run)
+            .transpose()
+    }
+
+    async fn list_runs(&self, workflow_id: Option<ApiId>, limit: u64) -> Result<Vec<UnifiedWorkflowRun>, WorkflowError> {
+        let workflow_id = match workflow_id {
+            Some(id) => Some(Self::storage_id(&id, |id| WorkflowError::NotFound { id })?),
+            None => None,
+        };
+        self.repository
+            .list_runs(workflow_id, limit)
+            .await
+            .map_err(storage_error)?
+            .into_iter()
+            .map(Self::to_unified_run)
+            .collect()
+    }
+
+    async fn claim_queued_runs(&self, limit: u64) -> Result<Vec<UnifiedWorkflowRun>, WorkflowError> {
+        self.repository
+            .claim_queued_runs(limit)
+            .await
+            .map_err(storage_error)?
+            .into_iter()
+            .map(Self::to_unified_run)
+            .collect()
+    }
+
+    async fn save_run(&self, run: UnifiedWorkflowRun) -> Result<UnifiedWorkflowRun, WorkflowError> {
+        let saved = self
+            .repository
+            .update_run(Self::to_stored_run(run)?)
+            .await
+            .map_err(storage_error)?;
+        Self::to_unified_run(saved)
+    }
+}
+
+fn storage_error(error: impl std::fmt::Display) -> WorkflowError {
+    WorkflowError::Storage {
+        message: error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratchet_api_types::WorkflowFailurePolicy;
+    use ratchet_storage::seaorm::{config::DatabaseConfig, connection::DatabaseConnection};
+    use std::time::Duration;
+
+    async fn create_manager() -> WorkflowManager {
+        let db = DatabaseConnection::new(DatabaseConfig {
+            url: "sqlite::memory:".to_string(),
+            max_connections: 5,
+            connection_timeout: Duration::from_secs(10),
+        })
+        .await
+        .unwrap();
+        db.migrate().await.unwrap();
+
+        WorkflowManager::new(WorkflowRepository::new(db.clone()), TaskRepository::new(db))
+    }
+
+    fn workflow(task: &str) -> UnifiedWorkflow {
+        UnifiedWorkflow {
+            id: ApiId::from_i32(0),
+            name: "nightly-report".to_string(),
+            description: None,
+            enabled: true,
+            steps: vec![UnifiedWorkflowStep {
+                id: "fetch".to_string(),
+                task_id: ApiId::from_string(task),
+                depends_on: Vec::new(),
+                input: None,
+                max_retries: 0,
+                retry_delay_seconds: 0,
+                on_failure: WorkflowFailurePolicy::Abort,
+            }],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_steps_must_use_known_tasks() {
+        let manager = create_manager().await;
+        let result = manager.create_workflow(workflow("missing-task")).await;
+        assert!(matches!(result, Err(WorkflowError::Invalid { .. })));
+        assert!(manager.list_workflows().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_workflows_cannot_be_run() {
+        let manager = create_manager().await;
+        let result = manager.queue_run(ApiId::from_i32(42), serde_json::json!({}), None).await;
+        assert!(matches!(result, Err(WorkflowError::NotFound { .. })));
+    }
+}
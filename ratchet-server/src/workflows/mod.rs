@@ -0,0 +1,12 @@
+//! Multi-step workflows
+//!
+//! Workflows chain registered tasks into a DAG. [`WorkflowManager`] validates
+//! and stores definitions and runs, and [`WorkflowEngine`] executes the runs the
+//! job processor claims, one step at a time in dependency order.
+
+pub mod engine;
+pub mod graph;
+pub mod manager;
+
+pub use engine::WorkflowEngine;
+pub use manager::WorkflowManager;
@@ -1,7 +1,32 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
+/// State of one delivery attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+pub enum DeliveryStatus {
+    /// The output was delivered
+    #[sea_orm(string_value = "delivered")]
+    Delivered,
+    /// The attempt failed and a later attempt replaced it
+    #[sea_orm(string_value = "failed")]
+    Failed,
+    /// The attempt failed and is due for another at `next_attempt_at`
+    #[sea_orm(string_value = "retrying")]
+    Retrying,
+    /// A server is redelivering; the claim lapses at `next_attempt_at`
+    #[sea_orm(string_value = "redelivering")]
+    Redelivering,
+    /// Every attempt the retry policy allows failed
+    #[sea_orm(string_value = "dead_letter")]
+    DeadLetter,
+}
+
 /// Delivery result entity for tracking output delivery status
+///
+/// Every attempt to deliver the output of an execution to one of its job's
+/// destinations is a row. Only the latest attempt of a destination is ever
+/// `retrying`, `redelivering` or `dead_letter`.
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
 #[sea_orm(table_name = "delivery_results")]
 pub struct Model {
@@ -21,6 +46,18 @@ pub struct Model {
     /// Unique identifier for the destination
     pub destination_id: String,
 
+    /// Position of the destination in the output destinations of the job
+    pub destination_index: i32,
+
+    /// Number of the attempt, starting at 1
+    pub attempt: i32,
+
+    /// State of the attempt
+    pub status: DeliveryStatus,
+
+    /// When a retrying attempt is due, or a redelivery claim lapses
+    pub next_attempt_at: Option<ChronoDateTimeUtc>,
+
     /// Whether delivery was successful
     pub success: bool,
 
@@ -90,6 +127,14 @@ impl Model {
             execution_id,
             destination_type,
             destination_id,
+            destination_index: 0,
+            attempt: 1,
+            status: if success {
+                DeliveryStatus::Delivered
+            } else {
+                DeliveryStatus::DeadLetter
+            },
+            next_attempt_at: None,
             success,
             delivery_time_ms,
             size_bytes,
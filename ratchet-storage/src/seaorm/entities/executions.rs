@@ -1,3 +1,4 @@
+use ratchet_api_types::{ExecutionLogLevel, UnifiedExecutionLog};
 use sea_orm::entity::prelude::*;
 use sea_orm::sea_query::StringLen;
 use serde::{Deserialize, Serialize};
@@ -65,6 +66,21 @@ pub struct Model {
 
     /// Recording directory path if recording was enabled
     pub recording_path: Option<String>,
+
+    /// Latest progress reported by the task as JSON (fraction, message, updatedAt)
+    pub progress: Option<Json>,
+
+    /// Latest partial result emitted by the task
+    pub partial_output: Option<Json>,
+
+    /// Console output captured from the task as a JSON array (timestamp, level, message)
+    pub logs: Option<Json>,
+
+    /// Execution of the task that started this one with `ratchet.call`
+    pub parent_execution_uuid: Option<Uuid>,
+
+    /// Whether the output was served from the task's result cache instead of running the task
+    pub cache_hit: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -103,6 +119,11 @@ impl Model {
             duration_ms: None,
             http_requests: None,
             recording_path: None,
+            progress: None,
+            partial_output: None,
+            logs: None,
+            parent_execution_uuid: None,
+            cache_hit: false,
         }
     }
 
@@ -138,4 +159,22 @@ impl Model {
             self.duration_ms = Some(duration.num_milliseconds() as i32);
         }
     }
+
+    /// Stored console output at or above `min_level`: the latest `limit` entries, oldest first
+    pub fn console_logs(&self, min_level: Option<ExecutionLogLevel>, limit: Option<usize>) -> Vec<UnifiedExecutionLog> {
+        let mut logs: Vec<UnifiedExecutionLog> = match &self.logs {
+            Some(serde_json::Value::Array(entries)) => entries
+                .iter()
+                .filter_map(|entry| serde_json::from_value::<UnifiedExecutionLog>(entry.clone()).ok())
+                .filter(|log| min_level.map_or(true, |min| log.level >= min))
+                .collect(),
+            _ => Vec::new(),
+        };
+        if let Some(limit) = limit {
+            if logs.len() > limit {
+                logs.drain(..logs.len() - limit);
+            }
+        }
+        logs
+    }
 }
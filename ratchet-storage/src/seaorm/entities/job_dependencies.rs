@@ -0,0 +1,71 @@
+//! Job dependency entity: a job waiting on the outcome of another job
+
+use sea_orm::entity::prelude::*;
+use sea_query::StringLen;
+use serde::{Deserialize, Serialize};
+
+use super::jobs::JobStatus;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "job_dependencies")]
+pub struct Model {
+    /// Primary key
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// The dependent job
+    pub job_id: i32,
+
+    /// The parent job
+    pub depends_on_job_id: i32,
+
+    /// Outcome of the parent that lets the dependent run
+    pub condition: DependencyCondition,
+
+    /// Whether the parent's output is merged into the dependent's input
+    pub pass_output: bool,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::jobs::Entity",
+        from = "Column::JobId",
+        to = "super::jobs::Column::Id"
+    )]
+    Job,
+
+    #[sea_orm(
+        belongs_to = "super::jobs::Entity",
+        from = "Column::DependsOnJobId",
+        to = "super::jobs::Column::Id"
+    )]
+    Parent,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Dependency condition enum
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+#[derive(Default)]
+pub enum DependencyCondition {
+    #[sea_orm(string_value = "on_success")]
+    #[default]
+    OnSuccess,
+    #[sea_orm(string_value = "on_failure")]
+    OnFailure,
+    #[sea_orm(string_value = "always")]
+    Always,
+}
+
+impl DependencyCondition {
+    /// Parent statuses that satisfy the condition
+    pub fn satisfied_by(&self) -> &'static [JobStatus] {
+        match self {
+            DependencyCondition::OnSuccess => &[JobStatus::Completed],
+            DependencyCondition::OnFailure => &[JobStatus::Failed],
+            DependencyCondition::Always => &[JobStatus::Completed, JobStatus::Failed, JobStatus::Cancelled],
+        }
+    }
+}
@@ -100,6 +100,19 @@ pub struct Model {
 
     /// Output destinations configuration as JSON
     pub output_destinations: Option<Json>,
+
+    /// Client-supplied key under which retried creation requests return this job
+    #[sea_orm(unique)]
+    pub idempotency_key: Option<String>,
+
+    /// SHA-256 of the input, to find jobs queued with identical input
+    pub input_hash: Option<String>,
+
+    /// Instance ID of the server processing the job
+    pub lease_owner: Option<String>,
+
+    /// When the job may be reclaimed unless its owner renews the lease
+    pub lease_expires_at: Option<ChronoDateTimeUtc>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -222,6 +235,10 @@ impl Model {
             completed_at: None,
             metadata: None,
             output_destinations: None,
+            idempotency_key: None,
+            input_hash: None,
+            lease_owner: None,
+            lease_expires_at: None,
         }
     }
 
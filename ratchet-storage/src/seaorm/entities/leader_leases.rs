@@ -0,0 +1,26 @@
+//! Leader lease entity: the server currently holding a role that only one server may hold
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "leader_leases")]
+pub struct Model {
+    /// Name of the role, e.g. "scheduler"
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub name: String,
+
+    /// Instance ID of the server holding the role
+    pub owner: String,
+
+    /// When the current owner took the role
+    pub acquired_at: ChronoDateTimeUtc,
+
+    /// When the role is up for grabs unless the owner renews it
+    pub expires_at: ChronoDateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,38 +1,72 @@
 pub mod api_keys;
 pub mod delivery_results;
 pub mod executions;
+pub mod job_dependencies;
 pub mod jobs;
+pub mod leader_leases;
 pub mod schedules;
 pub mod sessions;
 pub mod task_repositories;
+pub mod task_secrets;
 pub mod task_versions;
 pub mod tasks;
 pub mod users;
+pub mod webhook_trigger_deliveries;
+pub mod webhook_triggers;
+pub mod workflow_runs;
+pub mod workflows;
 
 pub use api_keys::{
     ActiveModel as ApiKeyActiveModel, ApiKeyPermissions, Column as ApiKeyColumn, Entity as ApiKeys, Model as ApiKey,
 };
 pub use delivery_results::{
-    ActiveModel as DeliveryResultActiveModel, Column as DeliveryResultColumn, Entity as DeliveryResults,
-    Model as DeliveryResult,
+    ActiveModel as DeliveryResultActiveModel, Column as DeliveryResultColumn, DeliveryStatus,
+    Entity as DeliveryResults, Model as DeliveryResult,
 };
 pub use executions::{
     ActiveModel as ExecutionActiveModel, Column as ExecutionColumn, Entity as Executions, ExecutionStatus,
     Model as Execution,
 };
+pub use job_dependencies::{
+    ActiveModel as JobDependencyActiveModel, Column as JobDependencyColumn, DependencyCondition,
+    Entity as JobDependencies, Model as JobDependency,
+};
 pub use jobs::{
     ActiveModel as JobActiveModel, Column as JobColumn, Entity as Jobs, JobPriority, JobStatus, Model as Job,
 };
+pub use leader_leases::{
+    ActiveModel as LeaderLeaseActiveModel, Column as LeaderLeaseColumn, Entity as LeaderLeases, Model as LeaderLease,
+};
 pub use schedules::{
-    ActiveModel as ScheduleActiveModel, Column as ScheduleColumn, Entity as Schedules, Model as Schedule,
+    ActiveModel as ScheduleActiveModel, Column as ScheduleColumn, Entity as Schedules, MisfirePolicy, Model as Schedule,
+    OverlapPolicy,
 };
 pub use sessions::{ActiveModel as SessionActiveModel, Column as SessionColumn, Entity as Sessions, Model as Session};
 pub use task_repositories::{
     ActiveModel as TaskRepositoryActiveModel, Column as TaskRepositoryColumn, Entity as TaskRepositories,
     Model as TaskRepository,
 };
+pub use task_secrets::{
+    ActiveModel as TaskSecretActiveModel, Column as TaskSecretColumn, Entity as TaskSecrets, Model as TaskSecret,
+    SecretScope,
+};
 pub use task_versions::{
     ActiveModel as TaskVersionActiveModel, Column as TaskVersionColumn, Entity as TaskVersions, Model as TaskVersion,
 };
 pub use tasks::{ActiveModel as TaskActiveModel, Column as TaskColumn, Entity as Tasks, Model as Task};
 pub use users::{ActiveModel as UserActiveModel, Column as UserColumn, Entity as Users, Model as User, UserRole};
+pub use webhook_trigger_deliveries::{
+    ActiveModel as WebhookTriggerDeliveryActiveModel, Column as WebhookTriggerDeliveryColumn,
+    Entity as WebhookTriggerDeliveries, Model as WebhookTriggerDelivery,
+};
+pub use webhook_triggers::{
+    ActiveModel as WebhookTriggerActiveModel, Column as WebhookTriggerColumn, Entity as WebhookTriggers,
+    Model as WebhookTrigger,
+};
+pub use workflow_runs::{
+    ActiveModel as WorkflowRunActiveModel, Column as WorkflowRunColumn, Entity as WorkflowRuns, Model as WorkflowRun,
+    WorkflowRunStatus,
+};
+pub use workflows::{
+    ActiveModel as WorkflowActiveModel, Column as WorkflowColumn, Entity as Workflows, Model as Workflow,
+};
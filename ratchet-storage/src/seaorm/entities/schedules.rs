@@ -16,6 +16,9 @@ pub struct Model {
     /// Foreign key to tasks table
     pub task_id: i32,
 
+    /// Workflow run instead of the task, if any
+    pub workflow_id: Option<i32>,
+
     /// Schedule name
     pub name: String,
 
@@ -46,6 +49,24 @@ pub struct Model {
     /// Output destinations configuration as JSON
     pub output_destinations: Option<Json>,
 
+    /// IANA timezone the cron expression is evaluated in
+    pub timezone: String,
+
+    /// What to do when the previous run is still going at fire time
+    pub overlap_policy: OverlapPolicy,
+
+    /// What to do about fire times missed while the server was down
+    pub misfire_policy: MisfirePolicy,
+
+    /// Most missed runs fired on startup by the catch-up misfire policy
+    pub catch_up_limit: Option<i32>,
+
+    /// Upper bound of the random delay added to every run, in seconds
+    pub jitter_seconds: i32,
+
+    /// Daily windows during which the schedule does not fire, as JSON
+    pub blackout_windows: Option<Json>,
+
     /// When the schedule was created
     pub created_at: ChronoDateTimeUtc,
 
@@ -71,6 +92,36 @@ impl Related<super::tasks::Entity> for Entity {
 
 impl ActiveModelBehavior for ActiveModel {}
 
+/// Overlap policy enum
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+#[derive(Default)]
+pub enum OverlapPolicy {
+    #[sea_orm(string_value = "allow")]
+    #[default]
+    Allow,
+    #[sea_orm(string_value = "skip")]
+    Skip,
+    #[sea_orm(string_value = "queue")]
+    Queue,
+    #[sea_orm(string_value = "cancel_previous")]
+    CancelPrevious,
+}
+
+/// Misfire policy enum
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+#[derive(Default)]
+pub enum MisfirePolicy {
+    #[sea_orm(string_value = "skip")]
+    #[default]
+    Skip,
+    #[sea_orm(string_value = "fire_once")]
+    FireOnce,
+    #[sea_orm(string_value = "catch_up")]
+    CatchUp,
+}
+
 impl Model {
     /// Create a new schedule
     pub fn new(task_id: i32, name: String, cron_expression: String, input_data: serde_json::Value) -> Self {
@@ -78,6 +129,7 @@ impl Model {
             id: 0, // Will be set by database
             uuid: Uuid::new_v4(),
             task_id,
+            workflow_id: None,
             name,
             cron_expression,
             input_data,
@@ -88,6 +140,12 @@ impl Model {
             max_executions: None,
             metadata: None,
             output_destinations: None,
+            timezone: "UTC".to_string(),
+            overlap_policy: OverlapPolicy::Allow,
+            misfire_policy: MisfirePolicy::Skip,
+            catch_up_limit: None,
+            jitter_seconds: 0,
+            blackout_windows: None,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         }
@@ -0,0 +1,67 @@
+//! Task secret entity holding encrypted values injected into task executions
+
+use sea_orm::entity::prelude::*;
+use sea_query::StringLen;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "task_secrets")]
+pub struct Model {
+    /// Primary key
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// Name tasks use to look the secret up
+    pub name: String,
+
+    /// Which executions can see the secret
+    pub scope: SecretScope,
+
+    /// Repository ID or task UUID the secret is bound to, empty for global secrets
+    pub scope_id: String,
+
+    /// Base64 encoded envelope produced by the encryption service
+    #[serde(skip_serializing)]
+    pub encrypted_value: String,
+
+    /// Optional human readable description
+    pub description: Option<String>,
+
+    /// When the secret was created
+    pub created_at: ChronoDateTimeUtc,
+
+    /// When the secret value was last changed
+    pub updated_at: ChronoDateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Visibility of a secret
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+#[serde(rename_all = "lowercase")]
+pub enum SecretScope {
+    /// Visible to every task
+    #[sea_orm(string_value = "global")]
+    Global,
+    /// Visible to tasks loaded from one repository
+    #[sea_orm(string_value = "repository")]
+    Repository,
+    /// Visible to a single task
+    #[sea_orm(string_value = "task")]
+    Task,
+}
+
+impl SecretScope {
+    /// Lookup precedence, higher values shadow lower ones with the same name
+    pub fn precedence(&self) -> u8 {
+        match self {
+            SecretScope::Global => 0,
+            SecretScope::Repository => 1,
+            SecretScope::Task => 2,
+        }
+    }
+}
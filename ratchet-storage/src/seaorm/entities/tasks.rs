@@ -86,6 +86,12 @@ pub struct Model {
 
     /// When the task was last validated
     pub validated_at: Option<ChronoDateTimeUtc>,
+
+    /// Signature verification outcome: "verified", "unsigned", "untrusted", "invalid"
+    pub signature_status: Option<String>,
+
+    /// Key ID of the trusted key that signed the task
+    pub signed_by: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -207,6 +213,8 @@ impl Model {
             updated_at: task.updated_at,
             source_modified_at: None,
             validated_at: task.validated_at,
+            signature_status: None,
+            signed_by: None,
         }
     }
 
@@ -265,6 +273,8 @@ impl Model {
             updated_at: now,
             source_modified_at: Some(now),
             validated_at: None,
+            signature_status: None,
+            signed_by: None,
         }
     }
 
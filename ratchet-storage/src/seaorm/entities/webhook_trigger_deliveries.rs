@@ -0,0 +1,42 @@
+//! Webhook trigger delivery entity: a delivery ID a trigger has accepted
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "webhook_trigger_deliveries")]
+pub struct Model {
+    /// Primary key
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// Foreign key to webhook_triggers table
+    pub trigger_id: i32,
+
+    /// Delivery ID sent by the caller
+    pub delivery_id: String,
+
+    /// Job queued for the delivery (null if none was queued)
+    pub job_id: Option<i32>,
+
+    /// When the delivery was received
+    pub received_at: ChronoDateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::webhook_triggers::Entity",
+        from = "Column::TriggerId",
+        to = "super::webhook_triggers::Column::Id"
+    )]
+    Trigger,
+}
+
+impl Related<super::webhook_triggers::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Trigger.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,74 @@
+//! Webhook trigger entity: a named endpoint that queues jobs of a task
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "webhook_triggers")]
+pub struct Model {
+    /// Primary key
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// Unique identifier for the trigger
+    #[sea_orm(unique)]
+    pub uuid: Uuid,
+
+    /// Unique trigger name, used in the trigger URL
+    #[sea_orm(unique)]
+    pub name: String,
+
+    /// Optional human readable description
+    pub description: Option<String>,
+
+    /// Foreign key to tasks table
+    pub task_id: i32,
+
+    /// Whether deliveries are accepted
+    pub enabled: bool,
+
+    /// Signature verification settings as JSON
+    pub signature: Json,
+
+    /// Template mapping the payload to the job input
+    pub input_mapping: Option<Json>,
+
+    /// JSON schema deliveries must match to queue a job
+    pub filter_schema: Option<Json>,
+
+    /// Header carrying the delivery ID used for replay protection
+    pub delivery_id_header: Option<String>,
+
+    /// When the trigger was created
+    pub created_at: ChronoDateTimeUtc,
+
+    /// When the trigger was last updated
+    pub updated_at: ChronoDateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::tasks::Entity",
+        from = "Column::TaskId",
+        to = "super::tasks::Column::Id"
+    )]
+    Task,
+
+    #[sea_orm(has_many = "super::webhook_trigger_deliveries::Entity")]
+    Deliveries,
+}
+
+impl Related<super::tasks::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Task.def()
+    }
+}
+
+impl Related<super::webhook_trigger_deliveries::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Deliveries.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,79 @@
+//! Workflow run entity: one run of a workflow and the state of its steps
+
+use sea_orm::entity::prelude::*;
+use sea_query::StringLen;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "workflow_runs")]
+pub struct Model {
+    /// Primary key
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// Unique identifier for the run
+    #[sea_orm(unique)]
+    pub uuid: Uuid,
+
+    /// Foreign key to workflows table
+    pub workflow_id: i32,
+
+    /// Schedule that started the run, if any
+    pub schedule_id: Option<i32>,
+
+    /// Run status
+    pub status: WorkflowRunStatus,
+
+    /// Workflow input as JSON
+    pub input: Json,
+
+    /// Outputs of the final steps keyed by step ID (null until completed)
+    pub output: Option<Json>,
+
+    /// Why the run failed
+    pub error_message: Option<String>,
+
+    /// State of each step as a JSON array
+    pub steps: Json,
+
+    /// When the run was queued
+    pub queued_at: ChronoDateTimeUtc,
+
+    /// When the first step started
+    pub started_at: Option<ChronoDateTimeUtc>,
+
+    /// When the run finished
+    pub completed_at: Option<ChronoDateTimeUtc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::workflows::Entity",
+        from = "Column::WorkflowId",
+        to = "super::workflows::Column::Id"
+    )]
+    Workflow,
+}
+
+impl Related<super::workflows::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Workflow.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Workflow run status enum
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+pub enum WorkflowRunStatus {
+    #[sea_orm(string_value = "queued")]
+    Queued,
+    #[sea_orm(string_value = "running")]
+    Running,
+    #[sea_orm(string_value = "completed")]
+    Completed,
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
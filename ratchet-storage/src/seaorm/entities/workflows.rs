@@ -0,0 +1,49 @@
+//! Workflow entity: a named graph of task steps
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "workflows")]
+pub struct Model {
+    /// Primary key
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// Unique identifier for the workflow
+    #[sea_orm(unique)]
+    pub uuid: Uuid,
+
+    /// Unique workflow name
+    #[sea_orm(unique)]
+    pub name: String,
+
+    /// Optional human readable description
+    pub description: Option<String>,
+
+    /// Step definitions as a JSON array
+    pub steps: Json,
+
+    /// Whether the workflow can be run
+    pub enabled: bool,
+
+    /// When the workflow was created
+    pub created_at: ChronoDateTimeUtc,
+
+    /// When the workflow was last updated
+    pub updated_at: ChronoDateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::workflow_runs::Entity")]
+    WorkflowRuns,
+}
+
+impl Related<super::workflow_runs::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WorkflowRuns.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
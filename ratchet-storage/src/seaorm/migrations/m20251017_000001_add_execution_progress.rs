@@ -0,0 +1,60 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Add progress column holding the latest progress reported by the task
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Executions::Table)
+                    .add_column(ColumnDef::new(Executions::Progress).json().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Add partial_output column holding the latest partial result emitted by the task
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Executions::Table)
+                    .add_column(ColumnDef::new(Executions::PartialOutput).json().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Executions::Table)
+                    .drop_column(Executions::PartialOutput)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Executions::Table)
+                    .drop_column(Executions::Progress)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Executions {
+    Table,
+    Progress,
+    PartialOutput,
+}
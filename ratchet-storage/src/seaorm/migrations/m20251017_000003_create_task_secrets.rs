@@ -0,0 +1,75 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TaskSecrets::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TaskSecrets::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(TaskSecrets::Name).string().not_null())
+                    .col(ColumnDef::new(TaskSecrets::Scope).string_len(20).not_null())
+                    // Empty for global secrets so the unique index below also covers them
+                    .col(ColumnDef::new(TaskSecrets::ScopeId).string().not_null().default(""))
+                    .col(ColumnDef::new(TaskSecrets::EncryptedValue).text().not_null())
+                    .col(ColumnDef::new(TaskSecrets::Description).text())
+                    .col(
+                        ColumnDef::new(TaskSecrets::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(TaskSecrets::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_task_secrets_scope_name")
+                    .table(TaskSecrets::Table)
+                    .col(TaskSecrets::Scope)
+                    .col(TaskSecrets::ScopeId)
+                    .col(TaskSecrets::Name)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TaskSecrets::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TaskSecrets {
+    Table,
+    Id,
+    Name,
+    Scope,
+    ScopeId,
+    EncryptedValue,
+    Description,
+    CreatedAt,
+    UpdatedAt,
+}
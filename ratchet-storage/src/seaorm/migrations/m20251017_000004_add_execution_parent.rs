@@ -0,0 +1,59 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Add parent_execution_uuid column linking executions started with ratchet.call to their caller
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Executions::Table)
+                    .add_column(ColumnDef::new(Executions::ParentExecutionUuid).uuid().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_executions_parent_execution_uuid")
+                    .table(Executions::Table)
+                    .col(Executions::ParentExecutionUuid)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_executions_parent_execution_uuid")
+                    .table(Executions::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Executions::Table)
+                    .drop_column(Executions::ParentExecutionUuid)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Executions {
+    Table,
+    ParentExecutionUuid,
+}
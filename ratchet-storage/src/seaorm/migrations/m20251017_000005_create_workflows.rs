@@ -0,0 +1,160 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Workflows::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Workflows::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Workflows::Uuid).string().not_null().unique_key())
+                    .col(ColumnDef::new(Workflows::Name).string().not_null().unique_key())
+                    .col(ColumnDef::new(Workflows::Description).text())
+                    .col(ColumnDef::new(Workflows::Steps).json().not_null())
+                    .col(ColumnDef::new(Workflows::Enabled).boolean().not_null().default(true))
+                    .col(
+                        ColumnDef::new(Workflows::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Workflows::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(WorkflowRuns::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WorkflowRuns::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(WorkflowRuns::Uuid).string().not_null().unique_key())
+                    .col(ColumnDef::new(WorkflowRuns::WorkflowId).integer().not_null())
+                    .col(ColumnDef::new(WorkflowRuns::ScheduleId).integer())
+                    .col(ColumnDef::new(WorkflowRuns::Status).string_len(20).not_null().default("queued"))
+                    .col(ColumnDef::new(WorkflowRuns::Input).json().not_null())
+                    .col(ColumnDef::new(WorkflowRuns::Output).json())
+                    .col(ColumnDef::new(WorkflowRuns::ErrorMessage).text())
+                    // Step states as a JSON array (stepId, status, executionId, attempts, output, ...)
+                    .col(ColumnDef::new(WorkflowRuns::Steps).json().not_null())
+                    .col(
+                        ColumnDef::new(WorkflowRuns::QueuedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(ColumnDef::new(WorkflowRuns::StartedAt).timestamp_with_time_zone())
+                    .col(ColumnDef::new(WorkflowRuns::CompletedAt).timestamp_with_time_zone())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_workflow_runs_workflow_id")
+                            .from(WorkflowRuns::Table, WorkflowRuns::WorkflowId)
+                            .to(Workflows::Table, Workflows::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_workflow_runs_status")
+                    .table(WorkflowRuns::Table)
+                    .col(WorkflowRuns::Status)
+                    .col(WorkflowRuns::QueuedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Schedules can start a workflow instead of a single task
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Schedules::Table)
+                    .add_column(ColumnDef::new(Schedules::WorkflowId).integer().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Schedules::Table)
+                    .drop_column(Schedules::WorkflowId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(WorkflowRuns::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Workflows::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Workflows {
+    Table,
+    Id,
+    Uuid,
+    Name,
+    Description,
+    Steps,
+    Enabled,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum WorkflowRuns {
+    Table,
+    Id,
+    Uuid,
+    WorkflowId,
+    ScheduleId,
+    Status,
+    Input,
+    Output,
+    ErrorMessage,
+    Steps,
+    QueuedAt,
+    StartedAt,
+    CompletedAt,
+}
+
+#[derive(DeriveIden)]
+enum Schedules {
+    Table,
+    WorkflowId,
+}
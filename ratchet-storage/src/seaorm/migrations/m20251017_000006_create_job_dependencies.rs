@@ -0,0 +1,100 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(JobDependencies::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(JobDependencies::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(JobDependencies::JobId).integer().not_null())
+                    .col(ColumnDef::new(JobDependencies::DependsOnJobId).integer().not_null())
+                    .col(
+                        ColumnDef::new(JobDependencies::Condition)
+                            .string_len(20)
+                            .not_null()
+                            .default("on_success"),
+                    )
+                    .col(
+                        ColumnDef::new(JobDependencies::PassOutput)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_job_dependencies_job_id")
+                            .from(JobDependencies::Table, JobDependencies::JobId)
+                            .to(Jobs::Table, Jobs::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_job_dependencies_depends_on_job_id")
+                            .from(JobDependencies::Table, JobDependencies::DependsOnJobId)
+                            .to(Jobs::Table, Jobs::Id)
+                            // Deleting a parent must not silently release the jobs waiting for it
+                            .on_delete(ForeignKeyAction::Restrict),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_job_dependencies_job_parent")
+                    .table(JobDependencies::Table)
+                    .col(JobDependencies::JobId)
+                    .col(JobDependencies::DependsOnJobId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_job_dependencies_depends_on_job_id")
+                    .table(JobDependencies::Table)
+                    .col(JobDependencies::DependsOnJobId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(JobDependencies::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum JobDependencies {
+    Table,
+    Id,
+    JobId,
+    DependsOnJobId,
+    Condition,
+    PassOutput,
+}
+
+#[derive(DeriveIden)]
+enum Jobs {
+    Table,
+    Id,
+}
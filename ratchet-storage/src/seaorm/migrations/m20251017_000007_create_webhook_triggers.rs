@@ -0,0 +1,154 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(WebhookTriggers::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WebhookTriggers::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(WebhookTriggers::Uuid).string().not_null().unique_key())
+                    .col(ColumnDef::new(WebhookTriggers::Name).string().not_null().unique_key())
+                    .col(ColumnDef::new(WebhookTriggers::Description).text())
+                    .col(ColumnDef::new(WebhookTriggers::TaskId).integer().not_null())
+                    .col(ColumnDef::new(WebhookTriggers::Enabled).boolean().not_null().default(true))
+                    // Signature settings as JSON (secretName, algorithm, header, prefix, ...)
+                    .col(ColumnDef::new(WebhookTriggers::Signature).json().not_null())
+                    .col(ColumnDef::new(WebhookTriggers::InputMapping).json())
+                    .col(ColumnDef::new(WebhookTriggers::FilterSchema).json())
+                    .col(ColumnDef::new(WebhookTriggers::DeliveryIdHeader).string())
+                    .col(
+                        ColumnDef::new(WebhookTriggers::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(WebhookTriggers::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_webhook_triggers_task_id")
+                            .from(WebhookTriggers::Table, WebhookTriggers::TaskId)
+                            .to(Tasks::Table, Tasks::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(WebhookTriggerDeliveries::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WebhookTriggerDeliveries::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(WebhookTriggerDeliveries::TriggerId).integer().not_null())
+                    .col(ColumnDef::new(WebhookTriggerDeliveries::DeliveryId).string().not_null())
+                    .col(ColumnDef::new(WebhookTriggerDeliveries::JobId).integer())
+                    .col(
+                        ColumnDef::new(WebhookTriggerDeliveries::ReceivedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_webhook_trigger_deliveries_trigger_id")
+                            .from(WebhookTriggerDeliveries::Table, WebhookTriggerDeliveries::TriggerId)
+                            .to(WebhookTriggers::Table, WebhookTriggers::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // A delivery ID is only accepted once per trigger
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_webhook_trigger_deliveries_delivery")
+                    .table(WebhookTriggerDeliveries::Table)
+                    .col(WebhookTriggerDeliveries::TriggerId)
+                    .col(WebhookTriggerDeliveries::DeliveryId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_webhook_trigger_deliveries_received_at")
+                    .table(WebhookTriggerDeliveries::Table)
+                    .col(WebhookTriggerDeliveries::ReceivedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WebhookTriggerDeliveries::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(WebhookTriggers::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum WebhookTriggers {
+    Table,
+    Id,
+    Uuid,
+    Name,
+    Description,
+    TaskId,
+    Enabled,
+    Signature,
+    InputMapping,
+    FilterSchema,
+    DeliveryIdHeader,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum WebhookTriggerDeliveries {
+    Table,
+    Id,
+    TriggerId,
+    DeliveryId,
+    JobId,
+    ReceivedAt,
+}
+
+#[derive(DeriveIden)]
+enum Tasks {
+    Table,
+    Id,
+}
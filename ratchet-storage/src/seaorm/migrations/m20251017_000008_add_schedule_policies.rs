@@ -0,0 +1,75 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite adds one column per ALTER TABLE statement
+        let columns = [
+            ColumnDef::new(Schedules::Timezone)
+                .string_len(64)
+                .not_null()
+                .default("UTC")
+                .to_owned(),
+            ColumnDef::new(Schedules::OverlapPolicy)
+                .string_len(20)
+                .not_null()
+                .default("allow")
+                .to_owned(),
+            ColumnDef::new(Schedules::MisfirePolicy)
+                .string_len(20)
+                .not_null()
+                .default("skip")
+                .to_owned(),
+            ColumnDef::new(Schedules::CatchUpLimit).integer().null().to_owned(),
+            ColumnDef::new(Schedules::JitterSeconds)
+                .integer()
+                .not_null()
+                .default(0)
+                .to_owned(),
+            ColumnDef::new(Schedules::BlackoutWindows).json().null().to_owned(),
+        ];
+        for mut column in columns {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Schedules::Table)
+                        .add_column(&mut column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            Schedules::BlackoutWindows,
+            Schedules::JitterSeconds,
+            Schedules::CatchUpLimit,
+            Schedules::MisfirePolicy,
+            Schedules::OverlapPolicy,
+            Schedules::Timezone,
+        ] {
+            manager
+                .alter_table(Table::alter().table(Schedules::Table).drop_column(column).to_owned())
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Schedules {
+    Table,
+    Timezone,
+    OverlapPolicy,
+    MisfirePolicy,
+    CatchUpLimit,
+    JitterSeconds,
+    BlackoutWindows,
+}
@@ -0,0 +1,68 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite adds one column per ALTER TABLE statement
+        let columns = [
+            ColumnDef::new(Jobs::IdempotencyKey).string_len(255).null().to_owned(),
+            ColumnDef::new(Jobs::InputHash).string_len(64).null().to_owned(),
+        ];
+        for mut column in columns {
+            manager
+                .alter_table(Table::alter().table(Jobs::Table).add_column(&mut column).to_owned())
+                .await?;
+        }
+
+        // Jobs without a key leave the column NULL, which the unique index allows any number of
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_jobs_idempotency_key")
+                    .table(Jobs::Table)
+                    .col(Jobs::IdempotencyKey)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_jobs_task_input_hash")
+                    .table(Jobs::Table)
+                    .col(Jobs::TaskId)
+                    .col(Jobs::InputHash)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for index in ["idx_jobs_task_input_hash", "idx_jobs_idempotency_key"] {
+            manager
+                .drop_index(Index::drop().name(index).table(Jobs::Table).to_owned())
+                .await?;
+        }
+        for column in [Jobs::InputHash, Jobs::IdempotencyKey] {
+            manager
+                .alter_table(Table::alter().table(Jobs::Table).drop_column(column).to_owned())
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Jobs {
+    Table,
+    TaskId,
+    IdempotencyKey,
+    InputHash,
+}
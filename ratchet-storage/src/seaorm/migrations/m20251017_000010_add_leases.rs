@@ -0,0 +1,93 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite adds one column per ALTER TABLE statement
+        let columns = [
+            ColumnDef::new(Jobs::LeaseOwner).string_len(255).null().to_owned(),
+            ColumnDef::new(Jobs::LeaseExpiresAt).timestamp_with_time_zone().null().to_owned(),
+        ];
+        for mut column in columns {
+            manager
+                .alter_table(Table::alter().table(Jobs::Table).add_column(&mut column).to_owned())
+                .await?;
+        }
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_jobs_status_lease_expires_at")
+                    .table(Jobs::Table)
+                    .col(Jobs::Status)
+                    .col(Jobs::LeaseExpiresAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        // One row per role that only one server may hold at a time
+        manager
+            .create_table(
+                Table::create()
+                    .table(LeaderLeases::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(LeaderLeases::Name).string_len(255).not_null().primary_key())
+                    .col(ColumnDef::new(LeaderLeases::Owner).string_len(255).not_null())
+                    .col(
+                        ColumnDef::new(LeaderLeases::AcquiredAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(LeaderLeases::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LeaderLeases::Table).to_owned())
+            .await?;
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_jobs_status_lease_expires_at")
+                    .table(Jobs::Table)
+                    .to_owned(),
+            )
+            .await?;
+        for column in [Jobs::LeaseExpiresAt, Jobs::LeaseOwner] {
+            manager
+                .alter_table(Table::alter().table(Jobs::Table).drop_column(column).to_owned())
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Jobs {
+    Table,
+    Status,
+    LeaseOwner,
+    LeaseExpiresAt,
+}
+
+#[derive(DeriveIden)]
+enum LeaderLeases {
+    Table,
+    Name,
+    Owner,
+    AcquiredAt,
+    ExpiresAt,
+}
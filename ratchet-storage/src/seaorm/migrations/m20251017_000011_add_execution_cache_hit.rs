@@ -0,0 +1,40 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Add cache_hit column marking executions answered from a task's result cache
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Executions::Table)
+                    .add_column(ColumnDef::new(Executions::CacheHit).boolean().not_null().default(false))
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Executions::Table)
+                    .drop_column(Executions::CacheHit)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Executions {
+    Table,
+    CacheHit,
+}
@@ -0,0 +1,105 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Turn delivery_results into a ledger of attempts; SQLite adds one column per ALTER TABLE statement
+        let columns = [
+            ColumnDef::new(DeliveryResults::DestinationIndex)
+                .integer()
+                .not_null()
+                .default(0)
+                .to_owned(),
+            ColumnDef::new(DeliveryResults::Attempt)
+                .integer()
+                .not_null()
+                .default(1)
+                .to_owned(),
+            ColumnDef::new(DeliveryResults::Status)
+                .string_len(20)
+                .not_null()
+                .default("delivered")
+                .to_owned(),
+            ColumnDef::new(DeliveryResults::NextAttemptAt)
+                .timestamp_with_time_zone()
+                .null()
+                .to_owned(),
+        ];
+        for mut column in columns {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(DeliveryResults::Table)
+                        .add_column(&mut column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        // Failed deliveries recorded before the ledger were never retried
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(DeliveryResults::Table)
+                    .value(DeliveryResults::Status, "dead_letter")
+                    .and_where(Expr::col(DeliveryResults::Success).eq(false))
+                    .to_owned(),
+            )
+            .await?;
+
+        // The redelivery sweep looks for due attempts by status
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_delivery_results_status_next_attempt")
+                    .table(DeliveryResults::Table)
+                    .col(DeliveryResults::Status)
+                    .col(DeliveryResults::NextAttemptAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_delivery_results_status_next_attempt")
+                    .table(DeliveryResults::Table)
+                    .to_owned(),
+            )
+            .await?;
+        for column in [
+            DeliveryResults::NextAttemptAt,
+            DeliveryResults::Status,
+            DeliveryResults::Attempt,
+            DeliveryResults::DestinationIndex,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(DeliveryResults::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum DeliveryResults {
+    Table,
+    Success,
+    DestinationIndex,
+    Attempt,
+    Status,
+    NextAttemptAt,
+}
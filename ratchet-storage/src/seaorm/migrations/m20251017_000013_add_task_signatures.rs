@@ -0,0 +1,39 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Signature verification outcome for registry-synced tasks; SQLite adds one column per ALTER TABLE statement
+        let columns = [
+            ColumnDef::new(Tasks::SignatureStatus).string_len(20).null().to_owned(),
+            ColumnDef::new(Tasks::SignedBy).string_len(64).null().to_owned(),
+        ];
+        for mut column in columns {
+            manager
+                .alter_table(Table::alter().table(Tasks::Table).add_column(&mut column).to_owned())
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [Tasks::SignedBy, Tasks::SignatureStatus] {
+            manager
+                .alter_table(Table::alter().table(Tasks::Table).drop_column(column).to_owned())
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Tasks {
+    Table,
+    SignatureStatus,
+    SignedBy,
+}
@@ -7,6 +7,19 @@ mod m20241201_000004_create_jobs_table;
 mod m20241201_000005_create_indexes;
 mod m20250106_000001_add_output_destinations;
 mod m20250702_000001_full_task_storage;
+mod m20251017_000001_add_execution_progress;
+mod m20251017_000002_add_execution_logs;
+mod m20251017_000003_create_task_secrets;
+mod m20251017_000004_add_execution_parent;
+mod m20251017_000005_create_workflows;
+mod m20251017_000006_create_job_dependencies;
+mod m20251017_000007_create_webhook_triggers;
+mod m20251017_000008_add_schedule_policies;
+mod m20251017_000009_add_job_idempotency;
+mod m20251017_000010_add_leases;
+mod m20251017_000011_add_execution_cache_hit;
+mod m20251017_000012_add_delivery_ledger;
+mod m20251017_000013_add_task_signatures;
 
 pub struct Migrator;
 
@@ -21,6 +34,19 @@ impl MigratorTrait for Migrator {
             Box::new(m20241201_000005_create_indexes::Migration),
             Box::new(m20250106_000001_add_output_destinations::Migration),
             Box::new(m20250702_000001_full_task_storage::Migration),
+            Box::new(m20251017_000001_add_execution_progress::Migration),
+            Box::new(m20251017_000002_add_execution_logs::Migration),
+            Box::new(m20251017_000003_create_task_secrets::Migration),
+            Box::new(m20251017_000004_add_execution_parent::Migration),
+            Box::new(m20251017_000005_create_workflows::Migration),
+            Box::new(m20251017_000006_create_job_dependencies::Migration),
+            Box::new(m20251017_000007_create_webhook_triggers::Migration),
+            Box::new(m20251017_000008_add_schedule_policies::Migration),
+            Box::new(m20251017_000009_add_job_idempotency::Migration),
+            Box::new(m20251017_000010_add_leases::Migration),
+            Box::new(m20251017_000011_add_execution_cache_hit::Migration),
+            Box::new(m20251017_000012_add_delivery_ledger::Migration),
+            Box::new(m20251017_000013_add_task_signatures::Migration),
         ]
     }
 }
@@ -0,0 +1,347 @@
+use crate::database::{
+    entities::{delivery_results, DeliveryResult, DeliveryResultActiveModel, DeliveryResults, DeliveryStatus},
+    DatabaseConnection, DatabaseError,
+};
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, Condition, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+};
+use std::time::Duration;
+
+/// Repository for the output delivery ledger
+///
+/// Every attempt to deliver an output is a row. A failed attempt that is due
+/// again is claimed for redelivery with a conditional update, so servers sharing
+/// the database never redeliver the same output at the same time.
+#[derive(Clone)]
+pub struct DeliveryResultRepository {
+    db: DatabaseConnection,
+}
+
+impl DeliveryResultRepository {
+    /// Create a new delivery result repository
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Record a delivery attempt
+    pub async fn record(&self, result: DeliveryResult) -> Result<DeliveryResult, DatabaseError> {
+        let active_model = DeliveryResultActiveModel {
+            job_id: Set(result.job_id),
+            execution_id: Set(result.execution_id),
+            destination_type: Set(result.destination_type),
+            destination_id: Set(result.destination_id),
+            destination_index: Set(result.destination_index),
+            attempt: Set(result.attempt),
+            status: Set(result.status),
+            next_attempt_at: Set(result.next_attempt_at),
+            success: Set(result.success),
+            delivery_time_ms: Set(result.delivery_time_ms),
+            size_bytes: Set(result.size_bytes),
+            response_info: Set(result.response_info),
+            error_message: Set(result.error_message),
+            created_at: Set(result.created_at),
+            ..Default::default()
+        };
+        Ok(active_model.insert(self.db.get_connection()).await?)
+    }
+
+    /// Find a delivery attempt by ID
+    pub async fn find_by_id(&self, id: i32) -> Result<Option<DeliveryResult>, DatabaseError> {
+        let result = DeliveryResults::find_by_id(id).one(self.db.get_connection()).await?;
+        Ok(result)
+    }
+
+    /// Find all delivery attempts of an execution, oldest first
+    pub async fn find_by_execution_id(&self, execution_id: i32) -> Result<Vec<DeliveryResult>, DatabaseError> {
+        let results = DeliveryResults::find()
+            .filter(delivery_results::Column::ExecutionId.eq(execution_id))
+            .order_by_asc(delivery_results::Column::Id)
+            .all(self.db.get_connection())
+            .await?;
+        Ok(results)
+    }
+
+    /// Find the deliveries that have not succeeded yet, optionally of one job or execution
+    ///
+    /// These are the latest attempts of their destinations that are waiting for
+    /// a retry, being redelivered or dead-lettered, newest first.
+    pub async fn find_failed(
+        &self,
+        job_id: Option<i32>,
+        execution_id: Option<i32>,
+    ) -> Result<Vec<DeliveryResult>, DatabaseError> {
+        let mut query = DeliveryResults::find().filter(delivery_results::Column::Status.is_in([
+            DeliveryStatus::Retrying,
+            DeliveryStatus::Redelivering,
+            DeliveryStatus::DeadLetter,
+        ]));
+        if let Some(job_id) = job_id {
+            query = query.filter(delivery_results::Column::JobId.eq(job_id));
+        }
+        if let Some(execution_id) = execution_id {
+            query = query.filter(delivery_results::Column::ExecutionId.eq(execution_id));
+        }
+        let results = query
+            .order_by_desc(delivery_results::Column::Id)
+            .all(self.db.get_connection())
+            .await?;
+        Ok(results)
+    }
+
+    /// Claim up to `limit` deliveries that are due for another attempt
+    ///
+    /// A retrying delivery is due once its `next_attempt_at` has passed. So is a
+    /// redelivery whose claim lapsed because the server making it stopped before
+    /// recording the outcome. Claimed deliveries are held for `claim`.
+    pub async fn claim_due(&self, claim: Duration, limit: u64) -> Result<Vec<DeliveryResult>, DatabaseError> {
+        let now = Utc::now();
+        let due = DeliveryResults::find()
+            .filter(due_condition(now))
+            .order_by_asc(delivery_results::Column::NextAttemptAt)
+            .limit(limit)
+            .all(self.db.get_connection())
+            .await?;
+
+        let claimed_until = claim_expiry(now, claim);
+        let mut claimed = Vec::with_capacity(due.len());
+        for mut result in due {
+            let update = DeliveryResults::update_many()
+                .col_expr(
+                    delivery_results::Column::Status,
+                    Expr::value(DeliveryStatus::Redelivering),
+                )
+                .col_expr(delivery_results::Column::NextAttemptAt, Expr::value(claimed_until))
+                .filter(delivery_results::Column::Id.eq(result.id))
+                .filter(due_condition(now))
+                .exec(self.db.get_connection())
+                .await?;
+            if update.rows_affected == 1 {
+                result.status = DeliveryStatus::Redelivering;
+                result.next_attempt_at = Some(claimed_until);
+                claimed.push(result);
+            }
+        }
+        Ok(claimed)
+    }
+
+    /// Claim the delivery `id` for an immediate redelivery, held for `claim`
+    ///
+    /// Retrying and dead-lettered deliveries can be claimed, as can redeliveries
+    /// whose claim lapsed. Returns `None` when the delivery is in any other state.
+    pub async fn claim(&self, id: i32, claim: Duration) -> Result<Option<DeliveryResult>, DatabaseError> {
+        let now = Utc::now();
+        let claimable = Condition::any()
+            .add(delivery_results::Column::Status.is_in([DeliveryStatus::Retrying, DeliveryStatus::DeadLetter]))
+            .add(
+                Condition::all()
+                    .add(delivery_results::Column::Status.eq(DeliveryStatus::Redelivering))
+                    .add(delivery_results::Column::NextAttemptAt.lte(now)),
+            );
+        let update = DeliveryResults::update_many()
+            .col_expr(
+                delivery_results::Column::Status,
+                Expr::value(DeliveryStatus::Redelivering),
+            )
+            .col_expr(
+                delivery_results::Column::NextAttemptAt,
+                Expr::value(claim_expiry(now, claim)),
+            )
+            .filter(delivery_results::Column::Id.eq(id))
+            .filter(claimable)
+            .exec(self.db.get_connection())
+            .await?;
+        if update.rows_affected != 1 {
+            return Ok(None);
+        }
+        self.find_by_id(id).await
+    }
+
+    /// Close a claimed delivery once the outcome of its redelivery is recorded
+    ///
+    /// The new attempt takes over, so the claimed one becomes a plain failure.
+    pub async fn supersede(&self, id: i32) -> Result<bool, DatabaseError> {
+        let update = DeliveryResults::update_many()
+            .col_expr(delivery_results::Column::Status, Expr::value(DeliveryStatus::Failed))
+            .col_expr(
+                delivery_results::Column::NextAttemptAt,
+                Expr::value(Option::<DateTime<Utc>>::None),
+            )
+            .filter(delivery_results::Column::Id.eq(id))
+            .filter(delivery_results::Column::Status.eq(DeliveryStatus::Redelivering))
+            .exec(self.db.get_connection())
+            .await?;
+        Ok(update.rows_affected == 1)
+    }
+
+    /// Dead-letter a claimed delivery that cannot be redelivered at all
+    ///
+    /// Used when the job or output the delivery was for no longer exists.
+    pub async fn dead_letter(&self, id: i32, reason: String) -> Result<bool, DatabaseError> {
+        let update = DeliveryResults::update_many()
+            .col_expr(
+                delivery_results::Column::Status,
+                Expr::value(DeliveryStatus::DeadLetter),
+            )
+            .col_expr(
+                delivery_results::Column::NextAttemptAt,
+                Expr::value(Option::<DateTime<Utc>>::None),
+            )
+            .col_expr(delivery_results::Column::ErrorMessage, Expr::value(reason))
+            .filter(delivery_results::Column::Id.eq(id))
+            .filter(delivery_results::Column::Status.eq(DeliveryStatus::Redelivering))
+            .exec(self.db.get_connection())
+            .await?;
+        Ok(update.rows_affected == 1)
+    }
+}
+
+/// Retrying deliveries and lapsed redeliveries whose time has come
+fn due_condition(now: DateTime<Utc>) -> Condition {
+    Condition::all()
+        .add(delivery_results::Column::Status.is_in([DeliveryStatus::Retrying, DeliveryStatus::Redelivering]))
+        .add(delivery_results::Column::NextAttemptAt.lte(now))
+}
+
+fn claim_expiry(now: DateTime<Utc>, claim: Duration) -> DateTime<Utc> {
+    now + chrono::Duration::milliseconds(claim.as_millis() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::entities::{Execution, Job, JobPriority};
+    use crate::database::repositories::{ExecutionRepository, JobRepository, TaskRepository};
+    use crate::seaorm::config::DatabaseConfig;
+    use crate::testing::TaskBuilder;
+    use serde_json::json;
+
+    async fn create_test_db() -> DatabaseConnection {
+        let config = DatabaseConfig {
+            url: "sqlite::memory:".to_string(),
+            max_connections: 5,
+            connection_timeout: Duration::from_secs(10),
+        };
+
+        let db = DatabaseConnection::new(config).await.unwrap();
+        db.migrate().await.unwrap();
+        db
+    }
+
+    /// Create a job and its execution, which delivery results refer to
+    async fn create_execution(db: &DatabaseConnection) -> (i32, i32) {
+        let task = TaskBuilder::new().with_name("delivery-task").build();
+        let task_id = TaskRepository::new(db.clone()).create(task).await.unwrap().id;
+        let job = JobRepository::new(db.clone())
+            .create(Job::new(task_id, json!({}), JobPriority::Normal))
+            .await
+            .unwrap();
+        let execution = ExecutionRepository::new(db.clone())
+            .create(Execution::new(task_id, json!({})))
+            .await
+            .unwrap();
+        (job.id, execution.id)
+    }
+
+    fn failed_attempt(job_id: i32, execution_id: i32, status: DeliveryStatus) -> DeliveryResult {
+        let mut result = DeliveryResult::new(
+            job_id,
+            execution_id,
+            "webhook".to_string(),
+            "https://example.com/hook".to_string(),
+            false,
+            12,
+            64,
+            None,
+            Some("503 Service Unavailable".to_string()),
+        );
+        result.status = status;
+        if status == DeliveryStatus::Retrying {
+            result.next_attempt_at = Some(Utc::now() - chrono::Duration::seconds(1));
+        }
+        result
+    }
+
+    #[tokio::test]
+    async fn test_due_delivery_is_claimed_once() {
+        let db = create_test_db().await;
+        let (job_id, execution_id) = create_execution(&db).await;
+        let repo = DeliveryResultRepository::new(db);
+
+        let retrying = repo
+            .record(failed_attempt(job_id, execution_id, DeliveryStatus::Retrying))
+            .await
+            .unwrap();
+        let mut later = failed_attempt(job_id, execution_id, DeliveryStatus::Retrying);
+        later.next_attempt_at = Some(Utc::now() + chrono::Duration::hours(1));
+        repo.record(later).await.unwrap();
+
+        let claimed = repo.claim_due(Duration::from_secs(60), 10).await.unwrap();
+        assert_eq!(claimed.len(), 1);
+        assert_eq!(claimed[0].id, retrying.id);
+        assert_eq!(claimed[0].status, DeliveryStatus::Redelivering);
+        assert!(repo.claim_due(Duration::from_secs(60), 10).await.unwrap().is_empty());
+        assert!(repo
+            .claim(retrying.id, Duration::from_secs(60))
+            .await
+            .unwrap()
+            .is_none());
+
+        assert!(repo.supersede(retrying.id).await.unwrap());
+        let superseded = repo.find_by_id(retrying.id).await.unwrap().unwrap();
+        assert_eq!(superseded.status, DeliveryStatus::Failed);
+        assert_eq!(superseded.next_attempt_at, None);
+    }
+
+    #[tokio::test]
+    async fn test_lapsed_redelivery_is_due_again() {
+        let db = create_test_db().await;
+        let (job_id, execution_id) = create_execution(&db).await;
+        let repo = DeliveryResultRepository::new(db);
+
+        let retrying = repo
+            .record(failed_attempt(job_id, execution_id, DeliveryStatus::Retrying))
+            .await
+            .unwrap();
+        assert_eq!(repo.claim_due(Duration::ZERO, 10).await.unwrap().len(), 1);
+
+        // The claim lapsed without an outcome, as if the server had restarted
+        let reclaimed = repo.claim_due(Duration::from_secs(60), 10).await.unwrap();
+        assert_eq!(reclaimed.len(), 1);
+        assert_eq!(reclaimed[0].id, retrying.id);
+    }
+
+    #[tokio::test]
+    async fn test_failed_deliveries_can_be_claimed_for_redelivery() {
+        let db = create_test_db().await;
+        let (job_id, execution_id) = create_execution(&db).await;
+        let repo = DeliveryResultRepository::new(db);
+
+        let mut delivered = failed_attempt(job_id, execution_id, DeliveryStatus::Delivered);
+        delivered.success = true;
+        let delivered = repo.record(delivered).await.unwrap();
+        let dead = repo
+            .record(failed_attempt(job_id, execution_id, DeliveryStatus::DeadLetter))
+            .await
+            .unwrap();
+
+        let failed = repo.find_failed(Some(job_id), None).await.unwrap();
+        assert_eq!(failed.iter().map(|r| r.id).collect::<Vec<_>>(), vec![dead.id]);
+        assert!(repo.find_failed(None, Some(execution_id + 1)).await.unwrap().is_empty());
+
+        assert!(repo
+            .claim(delivered.id, Duration::from_secs(60))
+            .await
+            .unwrap()
+            .is_none());
+        let claimed = repo.claim(dead.id, Duration::from_secs(60)).await.unwrap().unwrap();
+        assert_eq!(claimed.status, DeliveryStatus::Redelivering);
+        assert!(repo.claim(dead.id, Duration::from_secs(60)).await.unwrap().is_none());
+
+        assert!(repo.dead_letter(dead.id, "job deleted".to_string()).await.unwrap());
+        let dead = repo.find_by_id(dead.id).await.unwrap().unwrap();
+        assert_eq!(dead.status, DeliveryStatus::DeadLetter);
+        assert_eq!(dead.error_message.as_deref(), Some("job deleted"));
+        assert_eq!(repo.find_by_execution_id(execution_id).await.unwrap().len(), 2);
+    }
+}
@@ -9,6 +9,9 @@ use sea_orm::{
 };
 use uuid::Uuid;
 
+/// Maximum number of console entries stored per execution; the oldest are discarded first
+pub const MAX_STORED_LOG_ENTRIES: usize = 1000;
+
 /// Filters for execution queries
 #[derive(Debug, Clone, Default)]
 pub struct ExecutionFilters {
@@ -55,6 +58,11 @@ impl ExecutionRepository {
             duration_ms: Set(execution.duration_ms),
             http_requests: Set(execution.http_requests),
             recording_path: Set(execution.recording_path),
+            progress: Set(execution.progress),
+            partial_output: Set(execution.partial_output),
+            logs: Set(execution.logs),
+            parent_execution_uuid: Set(execution.parent_execution_uuid),
+            cache_hit: Set(execution.cache_hit),
             ..Default::default()
         };
 
@@ -239,30 +247,70 @@ impl ExecutionRepository {
 
     /// Update execution progress (for progress tracking)
     pub async fn update_progress(&self, id: i32, progress: f32) -> Result<(), DatabaseError> {
-        // Note: Progress tracking would require adding a progress column to the execution table
-        // For now, we'll store progress in the output JSON as a temporary solution
-        if let Ok(Some(execution)) = self.find_by_id(id).await {
-            let mut output = execution.output.unwrap_or_else(|| serde_json::json!({}));
-            if let Some(output_obj) = output.as_object_mut() {
-                output_obj.insert(
-                    "progress".to_string(),
-                    serde_json::Value::Number(
-                        serde_json::Number::from_f64(progress as f64).unwrap_or(serde_json::Number::from(0)),
-                    ),
-                );
-            }
-
-            let active_model = ExecutionActiveModel {
-                id: Set(id),
-                output: Set(Some(output)),
-                ..Default::default()
-            };
+        self.record_progress(id, Some(progress), None, None).await
+    }
 
+    /// Record progress reported by a running task
+    ///
+    /// The message is kept next to the fraction it was reported with; a partial
+    /// output replaces the previously emitted one.
+    pub async fn record_progress(
+        &self,
+        id: i32,
+        fraction: Option<f32>,
+        message: Option<String>,
+        partial_output: Option<serde_json::Value>,
+    ) -> Result<(), DatabaseError> {
+        let mut active_model = ExecutionActiveModel {
+            id: Set(id),
+            ..Default::default()
+        };
+        if fraction.is_some() || message.is_some() {
+            active_model.progress = Set(Some(serde_json::json!({
+                "fraction": fraction,
+                "message": message,
+                "updatedAt": chrono::Utc::now().to_rfc3339(),
+            })));
+        }
+        if partial_output.is_some() {
+            active_model.partial_output = Set(partial_output);
+        }
+        if active_model.is_changed() {
             active_model.update(self.db.get_connection()).await?;
         }
         Ok(())
     }
 
+    /// Append console entries to the stored output of an execution
+    ///
+    /// At most [`MAX_STORED_LOG_ENTRIES`] entries are kept.
+    pub async fn append_logs(&self, id: i32, entries: Vec<serde_json::Value>) -> Result<(), DatabaseError> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let execution = self
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| sea_orm::DbErr::RecordNotFound(format!("Execution {} not found", id)))?;
+
+        let mut logs = match execution.logs {
+            Some(serde_json::Value::Array(existing)) => existing,
+            _ => Vec::new(),
+        };
+        logs.extend(entries);
+        if logs.len() > MAX_STORED_LOG_ENTRIES {
+            logs.drain(..logs.len() - MAX_STORED_LOG_ENTRIES);
+        }
+
+        let active_model = ExecutionActiveModel {
+            id: Set(id),
+            logs: Set(Some(serde_json::Value::Array(logs))),
+            ..Default::default()
+        };
+        active_model.update(self.db.get_connection()).await?;
+        Ok(())
+    }
+
     /// Find executions with advanced filtering
     pub async fn find_with_filters(
         &self,
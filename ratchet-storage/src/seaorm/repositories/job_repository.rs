@@ -1,12 +1,26 @@
 use crate::database::{
-    entities::{jobs, Job, JobActiveModel, JobPriority, JobStatus, Jobs},
+    entities::{
+        executions, job_dependencies, jobs, DependencyCondition, ExecutionStatus, Executions, Job, JobActiveModel, JobDependencies,
+        JobDependency, JobDependencyActiveModel, JobPriority, JobStatus, Jobs, Tasks,
+    },
+    filters::validation::ValidationError,
     DatabaseConnection, DatabaseError,
 };
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, EntityTrait, Order, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+    sea_query::{Alias, Condition, Expr, JoinType, Query},
+    ActiveEnum, ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, Order, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect, Set, SqlErr, TransactionTrait,
 };
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+
+/// Hours an idempotency key keeps returning the job it created
+pub const DEFAULT_IDEMPOTENCY_RETENTION_HOURS: i64 = 24;
+
+/// Task metadata key with the number of minutes within which jobs with identical input are deduplicated
+pub const DEDUPE_WINDOW_METADATA_KEY: &str = "dedupe_window_minutes";
 
 /// Filters for job queries
 #[derive(Debug, Clone, Default)]
@@ -27,44 +41,274 @@ pub struct JobPagination {
     pub order_desc: Option<bool>,
 }
 
+/// A dependency of a job that is being created
+#[derive(Debug, Clone)]
+pub struct NewJobDependency {
+    pub depends_on_job_id: i32,
+    pub condition: DependencyCondition,
+    pub pass_output: bool,
+}
+
+/// Outcome of queueing a job
+#[derive(Debug, Clone)]
+pub enum JobCreation {
+    /// A new job was queued
+    Created(Job),
+    /// The job repeats an earlier one, which is returned instead
+    Replayed(Job),
+}
+
+impl JobCreation {
+    /// The created or replayed job
+    pub fn into_job(self) -> Job {
+        match self {
+            JobCreation::Created(job) | JobCreation::Replayed(job) => job,
+        }
+    }
+}
+
 /// Repository for job-related database operations
 #[derive(Clone)]
 pub struct JobRepository {
     db: DatabaseConnection,
+    idempotency_retention: chrono::Duration,
 }
 
 impl JobRepository {
     /// Create a new job repository
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self {
+            db,
+            idempotency_retention: chrono::Duration::hours(DEFAULT_IDEMPOTENCY_RETENTION_HOURS),
+        }
     }
 
-    /// Create a new job
+    /// Set how long an idempotency key keeps returning the job it created
+    pub fn with_idempotency_retention(mut self, retention: chrono::Duration) -> Self {
+        self.idempotency_retention = retention;
+        self
+    }
+
+    /// Create a new job, or return the earlier job it repeats
     pub async fn create(&self, job: Job) -> Result<Job, DatabaseError> {
-        let active_model = JobActiveModel {
-            uuid: Set(job.uuid),
-            task_id: Set(job.task_id),
-            execution_id: Set(job.execution_id),
-            schedule_id: Set(job.schedule_id),
-            priority: Set(job.priority),
-            status: Set(job.status),
-            input_data: Set(job.input_data),
-            retry_count: Set(job.retry_count),
-            max_retries: Set(job.max_retries),
-            retry_delay_seconds: Set(job.retry_delay_seconds),
-            error_message: Set(job.error_message),
-            error_details: Set(job.error_details),
-            queued_at: Set(job.queued_at),
-            process_at: Set(job.process_at),
-            started_at: Set(job.started_at),
-            completed_at: Set(job.completed_at),
-            metadata: Set(job.metadata),
-            output_destinations: Set(job.output_destinations),
-            ..Default::default()
+        Ok(self.create_or_replay(job, Vec::new()).await?.into_job())
+    }
+
+    /// Create a job that is only picked up once its dependencies are met
+    ///
+    /// If a parent has already finished in a way that can never satisfy its
+    /// condition, the new job is cancelled straight away.
+    pub async fn create_with_dependencies(
+        &self,
+        job: Job,
+        dependencies: Vec<NewJobDependency>,
+    ) -> Result<Job, DatabaseError> {
+        Ok(self.create_or_replay(job, dependencies).await?.into_job())
+    }
+
+    /// Create a job unless it repeats an earlier one
+    ///
+    /// A job repeats another when it carries an idempotency key that was used
+    /// within the retention window, or when its task sets `dedupe_window_minutes`
+    /// in its metadata and a job with identical input was queued within that many
+    /// minutes and has not failed or been cancelled. The earlier job is returned
+    /// then and `dependencies` are ignored. Reusing a live idempotency key for
+    /// another task or input is an error.
+    ///
+    /// The job and its dependencies are written in one transaction, so the job is
+    /// never ready to run without the dependencies that hold it back.
+    pub async fn create_or_replay(
+        &self,
+        mut job: Job,
+        dependencies: Vec<NewJobDependency>,
+    ) -> Result<JobCreation, DatabaseError> {
+        self.check_task_enabled(job.task_id).await?;
+        job.input_hash = Some(input_hash(&job.input_data));
+        if let Some(earlier) = self.find_repeated(&job).await? {
+            return Ok(JobCreation::Replayed(earlier));
+        }
+
+        let idempotency_key = job.idempotency_key.clone();
+        let (task_id, hash) = (job.task_id, job.input_hash.clone().unwrap_or_default());
+        let txn = self.db.get_connection().begin().await?;
+        let created = match insert(&txn, job).await {
+            Ok(created) => created,
+            // Another request with the same key got in first
+            Err(e) if matches!(e.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+                txn.rollback().await?;
+                if let Some(key) = idempotency_key {
+                    if let Some(earlier) = self.find_by_idempotency_key(&key).await? {
+                        return Ok(JobCreation::Replayed(same_request(earlier, &key, task_id, &hash)?));
+                    }
+                }
+                return Err(e.into());
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        for dependency in &dependencies {
+            let active_model = JobDependencyActiveModel {
+                job_id: Set(created.id),
+                depends_on_job_id: Set(dependency.depends_on_job_id),
+                condition: Set(dependency.condition),
+                pass_output: Set(dependency.pass_output),
+                ..Default::default()
+            };
+            active_model.insert(&txn).await?;
+        }
+        txn.commit().await?;
+        if dependencies.is_empty() {
+            return Ok(JobCreation::Created(created));
+        }
+
+        for dependency in &dependencies {
+            self.cancel_unreachable_dependents(dependency.depends_on_job_id).await?;
+        }
+        let created = self.find_by_id(created.id).await?.unwrap_or(created);
+        Ok(JobCreation::Created(created))
+    }
+
+    /// Find the earlier job a new job repeats, releasing its idempotency key if it has expired
+    async fn find_repeated(&self, job: &Job) -> Result<Option<Job>, DatabaseError> {
+        let now = Utc::now();
+        if let Some(key) = &job.idempotency_key {
+            if let Some(earlier) = self.find_by_idempotency_key(key).await? {
+                if earlier.queued_at > now - self.idempotency_retention {
+                    let hash = job.input_hash.clone().unwrap_or_default();
+                    return same_request(earlier, key, job.task_id, &hash).map(Some);
+                }
+                let active_model = JobActiveModel {
+                    id: Set(earlier.id),
+                    idempotency_key: Set(None),
+                    ..Default::default()
+                };
+                active_model.update(self.db.get_connection()).await?;
+            }
+        }
+
+        let Some(window) = self.dedupe_window(job.task_id).await? else {
+            return Ok(None);
         };
+        let earlier = Jobs::find()
+            .filter(jobs::Column::TaskId.eq(job.task_id))
+            .filter(jobs::Column::InputHash.eq(job.input_hash.clone()))
+            .filter(jobs::Column::QueuedAt.gt(now - window))
+            .filter(jobs::Column::Status.is_not_in(vec![JobStatus::Failed, JobStatus::Cancelled]))
+            .order_by(jobs::Column::QueuedAt, Order::Desc)
+            .one(self.db.get_connection())
+            .await?;
+        Ok(earlier)
+    }
+
+    /// Refuse jobs for disabled tasks, which include tasks registry sync quarantined
+    async fn check_task_enabled(&self, task_id: i32) -> Result<(), DatabaseError> {
+        let task = Tasks::find_by_id(task_id).one(self.db.get_connection()).await?;
+        match task {
+            Some(task) if !task.enabled => {
+                Err(ValidationError::InvalidInput(format!("task {} is disabled", task.name)).into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Window within which a task deduplicates jobs with identical input, if it does
+    async fn dedupe_window(&self, task_id: i32) -> Result<Option<chrono::Duration>, DatabaseError> {
+        let task = Tasks::find_by_id(task_id).one(self.db.get_connection()).await?;
+        Ok(task
+            .and_then(|task| task.metadata.get(DEDUPE_WINDOW_METADATA_KEY).and_then(|v| v.as_i64()))
+            .filter(|minutes| *minutes > 0)
+            .map(chrono::Duration::minutes))
+    }
+
+    /// Find the dependencies of the given jobs
+    pub async fn find_dependencies(&self, job_ids: &[i32]) -> Result<Vec<JobDependency>, DatabaseError> {
+        if job_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let dependencies = JobDependencies::find()
+            .filter(job_dependencies::Column::JobId.is_in(job_ids.iter().copied()))
+            .order_by(job_dependencies::Column::Id, Order::Asc)
+            .all(self.db.get_connection())
+            .await?;
+        Ok(dependencies)
+    }
+
+    /// Outputs of the parents that pass their output on to a job, in the order the dependencies were declared
+    ///
+    /// Parents that finished without output are left out.
+    pub async fn find_dependency_outputs(&self, job_id: i32) -> Result<Vec<JsonValue>, DatabaseError> {
+        let mut outputs = Vec::new();
+        for dependency in self.find_dependencies(&[job_id]).await? {
+            if !dependency.pass_output {
+                continue;
+            }
+            let Some(execution_id) = self
+                .find_by_id(dependency.depends_on_job_id)
+                .await?
+                .and_then(|parent| parent.execution_id)
+            else {
+                continue;
+            };
+            if let Some(output) = Executions::find_by_id(execution_id)
+                .one(self.db.get_connection())
+                .await?
+                .and_then(|execution| execution.output)
+            {
+                outputs.push(output);
+            }
+        }
+        Ok(outputs)
+    }
+
+    /// Cancel the waiting dependents of a finished job whose condition it did not meet
+    ///
+    /// Cancellation cascades: the dependents of a cancelled job are settled in
+    /// turn. Returns the IDs of the cancelled jobs.
+    pub async fn cancel_unreachable_dependents(&self, job_id: i32) -> Result<Vec<i32>, DatabaseError> {
+        let mut cancelled = Vec::new();
+        let mut finished = vec![job_id];
+        while let Some(parent_id) = finished.pop() {
+            let Some(parent) = self.find_by_id(parent_id).await? else {
+                continue;
+            };
+            if !matches!(
+                parent.status,
+                JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled
+            ) {
+                continue;
+            }
 
-        let result = active_model.insert(self.db.get_connection()).await?;
-        Ok(result)
+            let dependencies = JobDependencies::find()
+                .filter(job_dependencies::Column::DependsOnJobId.eq(parent_id))
+                .all(self.db.get_connection())
+                .await?;
+            for dependency in dependencies {
+                if dependency.condition.satisfied_by().contains(&parent.status) {
+                    continue;
+                }
+                let Some(dependent) = self.find_by_id(dependency.job_id).await? else {
+                    continue;
+                };
+                if !matches!(dependent.status, JobStatus::Queued | JobStatus::Retrying) {
+                    continue;
+                }
+
+                let active_model = JobActiveModel {
+                    id: Set(dependent.id),
+                    status: Set(JobStatus::Cancelled),
+                    error_message: Set(Some(format!(
+                        "Dependency not met: job {} ended as {:?}",
+                        parent.id, parent.status
+                    ))),
+                    completed_at: Set(Some(chrono::Utc::now())),
+                    ..Default::default()
+                };
+                active_model.update(self.db.get_connection()).await?;
+                cancelled.push(dependent.id);
+                finished.push(dependent.id);
+            }
+        }
+        Ok(cancelled)
     }
 
     /// Find job by ID
@@ -82,12 +326,35 @@ impl JobRepository {
         Ok(job)
     }
 
+    /// Find the job created with an idempotency key
+    pub async fn find_by_idempotency_key(&self, key: &str) -> Result<Option<Job>, DatabaseError> {
+        let job = Jobs::find()
+            .filter(jobs::Column::IdempotencyKey.eq(key))
+            .one(self.db.get_connection())
+            .await?;
+        Ok(job)
+    }
+
+    /// Find the job a schedule queued last
+    pub async fn find_latest_for_schedule(&self, schedule_id: i32) -> Result<Option<Job>, DatabaseError> {
+        let job = Jobs::find()
+            .filter(jobs::Column::ScheduleId.eq(schedule_id))
+            .order_by_desc(jobs::Column::QueuedAt)
+            .order_by_desc(jobs::Column::Id)
+            .one(self.db.get_connection())
+            .await?;
+        Ok(job)
+    }
+
     /// Find jobs ready for processing (prioritized queue)
+    ///
+    /// Jobs with a dependency whose condition is not met yet are left waiting.
     pub async fn find_ready_for_processing(&self, limit: u64) -> Result<Vec<Job>, DatabaseError> {
         let now = chrono::Utc::now();
         let jobs = Jobs::find()
             .filter(jobs::Column::Status.is_in(vec![JobStatus::Queued, JobStatus::Retrying]))
             .filter(jobs::Column::ProcessAt.is_null().or(jobs::Column::ProcessAt.lte(now)))
+            .filter(Expr::exists(Self::unmet_dependencies()).not())
             .order_by(jobs::Column::Priority, Order::Desc) // Higher priority first
             .order_by(jobs::Column::QueuedAt, Order::Asc) // FIFO within same priority
             .limit(limit)
@@ -96,6 +363,135 @@ impl JobRepository {
         Ok(jobs)
     }
 
+    /// Claim up to `limit` ready jobs for `owner` for the next `lease`
+    ///
+    /// Each job is taken with a conditional update, so when several servers share
+    /// the database every job is claimed by exactly one of them. Claimed jobs are
+    /// processing and stay with `owner` for as long as it renews the lease.
+    pub async fn claim_ready_for_processing(
+        &self,
+        owner: &str,
+        lease: std::time::Duration,
+        limit: u64,
+    ) -> Result<Vec<Job>, DatabaseError> {
+        let ready = self.find_ready_for_processing(limit).await?;
+
+        let now = chrono::Utc::now();
+        let expires_at = lease_expiry(now, lease);
+        let mut claimed = Vec::with_capacity(ready.len());
+        for mut job in ready {
+            let result = Jobs::update_many()
+                .col_expr(jobs::Column::Status, Expr::value(JobStatus::Processing))
+                .col_expr(jobs::Column::StartedAt, Expr::value(now))
+                .col_expr(jobs::Column::LeaseOwner, Expr::value(owner))
+                .col_expr(jobs::Column::LeaseExpiresAt, Expr::value(expires_at))
+                .filter(jobs::Column::Id.eq(job.id))
+                .filter(jobs::Column::Status.eq(job.status))
+                .exec(self.db.get_connection())
+                .await?;
+            if result.rows_affected == 1 {
+                job.status = JobStatus::Processing;
+                job.started_at = Some(now);
+                job.lease_owner = Some(owner.to_string());
+                job.lease_expires_at = Some(expires_at);
+                claimed.push(job);
+            }
+        }
+        Ok(claimed)
+    }
+
+    /// Extend the leases of all jobs `owner` is processing to `lease` from now
+    pub async fn renew_leases(&self, owner: &str, lease: std::time::Duration) -> Result<u64, DatabaseError> {
+        let result = Jobs::update_many()
+            .col_expr(jobs::Column::LeaseExpiresAt, Expr::value(lease_expiry(chrono::Utc::now(), lease)))
+            .filter(jobs::Column::LeaseOwner.eq(owner))
+            .filter(jobs::Column::Status.eq(JobStatus::Processing))
+            .exec(self.db.get_connection())
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    /// Take back processing jobs whose owner stopped renewing their lease
+    ///
+    /// The owner is presumed dead: each job is failed as if its attempt failed, so
+    /// it is retried while it has retries left, and its execution is marked failed.
+    /// Returns the jobs this call reclaimed.
+    pub async fn reclaim_expired_leases(&self) -> Result<Vec<Job>, DatabaseError> {
+        let now = chrono::Utc::now();
+        let expired = Jobs::find()
+            .filter(jobs::Column::Status.eq(JobStatus::Processing))
+            .filter(jobs::Column::LeaseExpiresAt.lt(now))
+            .all(self.db.get_connection())
+            .await?;
+
+        let mut reclaimed = Vec::with_capacity(expired.len());
+        for job in expired {
+            // Only one server gets to reclaim each job
+            let result = Jobs::update_many()
+                .col_expr(jobs::Column::LeaseOwner, Expr::value(Option::<String>::None))
+                .col_expr(jobs::Column::LeaseExpiresAt, Expr::value(Option::<DateTime<Utc>>::None))
+                .filter(jobs::Column::Id.eq(job.id))
+                .filter(jobs::Column::Status.eq(JobStatus::Processing))
+                .filter(jobs::Column::LeaseExpiresAt.lt(now))
+                .exec(self.db.get_connection())
+                .await?;
+            if result.rows_affected != 1 {
+                continue;
+            }
+
+            let reason = format!("lease of {} expired", job.lease_owner.as_deref().unwrap_or("unknown owner"));
+            self.mark_failed(job.id, reason.clone(), None).await?;
+            if let Some(execution_id) = job.execution_id {
+                Executions::update_many()
+                    .col_expr(executions::Column::Status, Expr::value(ExecutionStatus::Failed))
+                    .col_expr(executions::Column::ErrorMessage, Expr::value(reason))
+                    .col_expr(executions::Column::CompletedAt, Expr::value(now))
+                    .filter(executions::Column::Id.eq(execution_id))
+                    .filter(executions::Column::Status.is_in(vec![ExecutionStatus::Pending, ExecutionStatus::Running]))
+                    .exec(self.db.get_connection())
+                    .await?;
+            }
+            if let Some(job) = self.find_by_id(job.id).await? {
+                reclaimed.push(job);
+            }
+        }
+        Ok(reclaimed)
+    }
+
+    /// Subquery selecting the dependencies of the outer job whose condition is not met
+    fn unmet_dependencies() -> sea_orm::sea_query::SelectStatement {
+        let parent = Alias::new("parent");
+        let mut met = Condition::any();
+        for condition in [
+            DependencyCondition::OnSuccess,
+            DependencyCondition::OnFailure,
+            DependencyCondition::Always,
+        ] {
+            met = met.add(
+                Condition::all()
+                    .add(Expr::col((JobDependencies, job_dependencies::Column::Condition)).eq(condition.to_value()))
+                    .add(
+                        Expr::col((parent.clone(), jobs::Column::Status))
+                            .is_in(condition.satisfied_by().iter().map(|status| status.to_value())),
+                    ),
+            );
+        }
+
+        Query::select()
+            .expr(Expr::val(1))
+            .from(JobDependencies)
+            .join_as(
+                JoinType::InnerJoin,
+                Jobs,
+                parent.clone(),
+                Expr::col((parent.clone(), jobs::Column::Id))
+                    .equals((JobDependencies, job_dependencies::Column::DependsOnJobId)),
+            )
+            .and_where(Expr::col((JobDependencies, job_dependencies::Column::JobId)).equals((Jobs, jobs::Column::Id)))
+            .cond_where(met.not())
+            .to_owned()
+    }
+
     /// Find jobs by status
     pub async fn find_by_status(&self, status: JobStatus) -> Result<Vec<Job>, DatabaseError> {
         let jobs = Jobs::find()
@@ -120,6 +516,7 @@ impl JobRepository {
     pub async fn update(&self, job: Job) -> Result<Job, DatabaseError> {
         let active_model: JobActiveModel = job.into();
         let updated_job = active_model.update(self.db.get_connection()).await?;
+        self.cancel_unreachable_dependents(updated_job.id).await?;
         Ok(updated_job)
     }
 
@@ -138,11 +535,14 @@ impl JobRepository {
             }
             JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled => {
                 active_model.completed_at = Set(Some(chrono::Utc::now()));
+                active_model.lease_owner = Set(None);
+                active_model.lease_expires_at = Set(None);
             }
             _ => {}
         }
 
         active_model.update(self.db.get_connection()).await?;
+        self.cancel_unreachable_dependents(id).await?;
         Ok(())
     }
 
@@ -185,10 +585,15 @@ impl JobRepository {
                 error_details: Set(job.error_details),
                 process_at: Set(job.process_at),
                 completed_at: Set(job.completed_at),
+                lease_owner: Set(None),
+                lease_expires_at: Set(None),
                 ..Default::default()
             };
 
             active_model.update(self.db.get_connection()).await?;
+            if !will_retry {
+                self.cancel_unreachable_dependents(id).await?;
+            }
             Ok(will_retry)
         } else {
             Ok(false)
@@ -240,6 +645,8 @@ impl JobRepository {
             id: Set(id),
             status: Set(JobStatus::Retrying),
             process_at: Set(Some(retry_at)),
+            lease_owner: Set(None),
+            lease_expires_at: Set(None),
             ..Default::default()
         };
 
@@ -363,6 +770,56 @@ impl JobRepository {
     }
 }
 
+/// SHA-256 of the input of a job; object keys serialize in sorted order, so equal inputs hash alike
+fn lease_expiry(now: DateTime<Utc>, lease: std::time::Duration) -> DateTime<Utc> {
+    now + chrono::Duration::milliseconds(lease.as_millis() as i64)
+}
+
+/// Insert a new job row through `db`
+async fn insert(db: &impl ConnectionTrait, job: Job) -> Result<Job, DbErr> {
+    let active_model = JobActiveModel {
+        uuid: Set(job.uuid),
+        task_id: Set(job.task_id),
+        execution_id: Set(job.execution_id),
+        schedule_id: Set(job.schedule_id),
+        priority: Set(job.priority),
+        status: Set(job.status),
+        input_data: Set(job.input_data),
+        retry_count: Set(job.retry_count),
+        max_retries: Set(job.max_retries),
+        retry_delay_seconds: Set(job.retry_delay_seconds),
+        error_message: Set(job.error_message),
+        error_details: Set(job.error_details),
+        queued_at: Set(job.queued_at),
+        process_at: Set(job.process_at),
+        started_at: Set(job.started_at),
+        completed_at: Set(job.completed_at),
+        metadata: Set(job.metadata),
+        output_destinations: Set(job.output_destinations),
+        idempotency_key: Set(job.idempotency_key),
+        input_hash: Set(job.input_hash),
+        ..Default::default()
+    };
+
+    active_model.insert(db).await
+}
+
+fn input_hash(input: &JsonValue) -> String {
+    hex::encode(Sha256::digest(input.to_string().as_bytes()))
+}
+
+/// The job first queued with an idempotency key, if it was for the same task and input
+fn same_request(earlier: Job, key: &str, task_id: i32, hash: &str) -> Result<Job, DatabaseError> {
+    if earlier.task_id != task_id || input_hash(&earlier.input_data) != hash {
+        return Err(ValidationError::InvalidInput(format!(
+            "idempotency key '{}' was already used for another task or input",
+            key
+        ))
+        .into());
+    }
+    Ok(earlier)
+}
+
 /// Job queue statistics
 #[derive(Debug, Clone)]
 pub struct JobQueueStats {
@@ -381,3 +838,433 @@ impl super::Repository for JobRepository {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::repositories::TaskRepository;
+    use crate::seaorm::config::DatabaseConfig;
+    use crate::testing::TaskBuilder;
+    use serde_json::json;
+    use std::time::Duration;
+
+    async fn create_test_db() -> DatabaseConnection {
+        let config = DatabaseConfig {
+            url: "sqlite::memory:".to_string(),
+            max_connections: 5,
+            connection_timeout: Duration::from_secs(10),
+        };
+
+        let db = DatabaseConnection::new(config).await.unwrap();
+        db.migrate().await.unwrap();
+        db
+    }
+
+    async fn create_task(db: &DatabaseConnection, metadata: JsonValue) -> i32 {
+        let task = TaskBuilder::new()
+            .with_name("dependency-task")
+            .with_metadata(metadata)
+            .build();
+        TaskRepository::new(db.clone()).create(task).await.unwrap().id
+    }
+
+    /// Two connections to one SQLite file, as two servers would have
+    async fn create_shared_test_db() -> (DatabaseConnection, DatabaseConnection) {
+        let path = std::env::temp_dir().join(format!("ratchet-jobs-{}.db", uuid::Uuid::new_v4()));
+        let config = DatabaseConfig {
+            url: format!("sqlite://{}", path.display()),
+            max_connections: 5,
+            connection_timeout: Duration::from_secs(10),
+        };
+
+        let first = DatabaseConnection::new(config.clone()).await.unwrap();
+        first.migrate().await.unwrap();
+        let second = DatabaseConnection::new(config).await.unwrap();
+        (first, second)
+    }
+
+    /// Servers claiming the same queue at once never get the same job
+    async fn check_claims_are_disjoint(first: DatabaseConnection, second: DatabaseConnection) {
+        let task_id = create_task(&first, json!({})).await;
+        let (a, b) = (JobRepository::new(first), JobRepository::new(second));
+        for i in 0..20 {
+            a.create(Job::new(task_id, json!({ "i": i }), JobPriority::Normal))
+                .await
+                .unwrap();
+        }
+
+        let lease = Duration::from_secs(30);
+        let mut claimed = Vec::new();
+        loop {
+            let (from_a, from_b) = tokio::join!(
+                a.claim_ready_for_processing("server-a", lease, 3),
+                b.claim_ready_for_processing("server-b", lease, 3)
+            );
+            let (from_a, from_b) = (from_a.unwrap(), from_b.unwrap());
+            if from_a.is_empty() && from_b.is_empty() {
+                break;
+            }
+            assert!(from_a.iter().all(|job| job.lease_owner.as_deref() == Some("server-a")));
+            assert!(from_b.iter().all(|job| job.lease_owner.as_deref() == Some("server-b")));
+            claimed.extend(ready_ids(from_a));
+            claimed.extend(ready_ids(from_b));
+        }
+
+        claimed.sort();
+        let mut unique = claimed.clone();
+        unique.dedup();
+        assert_eq!(claimed.len(), 20);
+        assert_eq!(unique, claimed);
+        assert_eq!(a.count_by_status(JobStatus::Processing).await.unwrap(), 20);
+    }
+
+    /// Jobs of a server that stopped renewing are retried, once, by whoever notices first
+    async fn check_expired_leases_are_reclaimed(first: DatabaseConnection, second: DatabaseConnection) {
+        let task_id = create_task(&first, json!({})).await;
+        let (a, b) = (JobRepository::new(first), JobRepository::new(second));
+        let kept = a
+            .create(Job::new(task_id, json!({ "kept": true }), JobPriority::High))
+            .await
+            .unwrap();
+        let dropped = a
+            .create(Job::new(task_id, json!({ "kept": false }), JobPriority::Normal))
+            .await
+            .unwrap();
+
+        let by_a = a.claim_ready_for_processing("server-a", Duration::from_secs(30), 1);
+        assert_eq!(ready_ids(by_a.await.unwrap()), vec![kept.id]);
+        let by_b = b.claim_ready_for_processing("server-b", Duration::from_millis(100), 1);
+        assert_eq!(ready_ids(by_b.await.unwrap()), vec![dropped.id]);
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(a.renew_leases("server-a", Duration::from_secs(30)).await.unwrap(), 1);
+
+        let (by_a, by_b) = tokio::join!(a.reclaim_expired_leases(), b.reclaim_expired_leases());
+        let mut reclaimed = by_a.unwrap();
+        reclaimed.extend(by_b.unwrap());
+        assert_eq!(reclaimed.len(), 1);
+        let job = &reclaimed[0];
+        assert_eq!(job.id, dropped.id);
+        assert_eq!(job.status, JobStatus::Retrying);
+        assert_eq!(job.retry_count, 1);
+        assert_eq!(job.lease_owner, None);
+        assert_eq!(job.error_message.as_deref(), Some("lease of server-b expired"));
+
+        let kept = a.find_by_id(kept.id).await.unwrap().unwrap();
+        assert_eq!(kept.status, JobStatus::Processing);
+        assert_eq!(kept.lease_owner.as_deref(), Some("server-a"));
+
+        a.mark_completed(kept.id).await.unwrap();
+        let kept = a.find_by_id(kept.id).await.unwrap().unwrap();
+        assert_eq!((kept.lease_owner, kept.lease_expires_at), (None, None));
+    }
+
+    /// Empty the job queue of a Postgres scratch database
+    #[cfg(feature = "postgres")]
+    async fn clear_postgres_jobs(db: &DatabaseConnection) {
+        sea_orm::ConnectionTrait::execute_unprepared(db.get_connection(), "DELETE FROM job_dependencies; DELETE FROM jobs")
+            .await
+            .unwrap();
+    }
+
+    fn dependency(parent: &Job, condition: DependencyCondition) -> NewJobDependency {
+        NewJobDependency {
+            depends_on_job_id: parent.id,
+            condition,
+            pass_output: false,
+        }
+    }
+
+    fn ready_ids(jobs: Vec<Job>) -> Vec<i32> {
+        jobs.into_iter().map(|job| job.id).collect()
+    }
+
+    /// Dependents are not ready until their parent ends the way they wait for
+    async fn check_dependents_wait_for_their_condition(db: DatabaseConnection) {
+        let task_id = create_task(&db, json!({})).await;
+        let repo = JobRepository::new(db);
+
+        let parent = repo.create(Job::new(task_id, json!({}), JobPriority::Normal)).await.unwrap();
+        let on_success = repo
+            .create_with_dependencies(
+                Job::new(task_id, json!({}), JobPriority::Normal),
+                vec![dependency(&parent, DependencyCondition::OnSuccess)],
+            )
+            .await
+            .unwrap();
+        let always = repo
+            .create_with_dependencies(
+                Job::new(task_id, json!({}), JobPriority::Normal),
+                vec![dependency(&parent, DependencyCondition::Always)],
+            )
+            .await
+            .unwrap();
+        assert_eq!(repo.find_dependencies(&[on_success.id]).await.unwrap().len(), 1);
+
+        // Only the parent can run while it is unfinished
+        assert_eq!(ready_ids(repo.find_ready_for_processing(10).await.unwrap()), vec![parent.id]);
+
+        repo.mark_completed(parent.id).await.unwrap();
+        let mut ready = ready_ids(repo.find_ready_for_processing(10).await.unwrap());
+        ready.sort();
+        assert_eq!(ready, vec![on_success.id, always.id]);
+    }
+
+    #[tokio::test]
+    async fn test_dependents_wait_for_their_condition() {
+        check_dependents_wait_for_their_condition(create_test_db().await).await;
+    }
+
+    /// A failed parent cancels the dependents it can no longer satisfy, transitively
+    async fn check_failure_cascades_cancellation(db: DatabaseConnection) {
+        let task_id = create_task(&db, json!({})).await;
+        let repo = JobRepository::new(db);
+
+        let mut job = Job::new(task_id, json!({}), JobPriority::Normal);
+        job.max_retries = 0;
+        let parent = repo.create(job).await.unwrap();
+        let child = repo
+            .create_with_dependencies(
+                Job::new(task_id, json!({}), JobPriority::Normal),
+                vec![dependency(&parent, DependencyCondition::OnSuccess)],
+            )
+            .await
+            .unwrap();
+        let grandchild = repo
+            .create_with_dependencies(
+                Job::new(task_id, json!({}), JobPriority::Normal),
+                vec![dependency(&child, DependencyCondition::OnSuccess)],
+            )
+            .await
+            .unwrap();
+        let cleanup = repo
+            .create_with_dependencies(
+                Job::new(task_id, json!({}), JobPriority::Normal),
+                vec![dependency(&parent, DependencyCondition::OnFailure)],
+            )
+            .await
+            .unwrap();
+
+        let will_retry = repo.mark_failed(parent.id, "boom".to_string(), None).await.unwrap();
+        assert!(!will_retry);
+
+        for id in [child.id, grandchild.id] {
+            let job = repo.find_by_id(id).await.unwrap().unwrap();
+            assert_eq!(job.status, JobStatus::Cancelled);
+            assert!(job.error_message.unwrap().starts_with("Dependency not met"));
+        }
+        assert_eq!(ready_ids(repo.find_ready_for_processing(10).await.unwrap()), vec![cleanup.id]);
+
+        // A dependency on a job that already ended the wrong way can never be met
+        let late = repo
+            .create_with_dependencies(
+                Job::new(task_id, json!({}), JobPriority::Normal),
+                vec![dependency(&parent, DependencyCondition::OnSuccess)],
+            )
+            .await
+            .unwrap();
+        assert_eq!(late.status, JobStatus::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_failure_cascades_cancellation() {
+        check_failure_cascades_cancellation(create_test_db().await).await;
+    }
+
+    /// A job is stored with all of its dependencies or not at all
+    async fn check_jobs_are_created_with_all_their_dependencies_or_not_at_all(db: DatabaseConnection) {
+        let task_id = create_task(&db, json!({})).await;
+        let repo = JobRepository::new(db);
+
+        let parent = repo.create(Job::new(task_id, json!({}), JobPriority::Normal)).await.unwrap();
+        let mut missing = parent.clone();
+        missing.id = parent.id + 100;
+        let created = repo
+            .create_with_dependencies(
+                Job::new(task_id, json!({}), JobPriority::Normal),
+                vec![
+                    dependency(&parent, DependencyCondition::OnSuccess),
+                    dependency(&missing, DependencyCondition::OnSuccess),
+                ],
+            )
+            .await;
+        assert!(created.is_err());
+        assert_eq!(repo.count().await.unwrap(), 1);
+
+        // A parent cannot be deleted from under the jobs waiting for it
+        let child = repo
+            .create_with_dependencies(
+                Job::new(task_id, json!({}), JobPriority::Normal),
+                vec![dependency(&parent, DependencyCondition::OnSuccess)],
+            )
+            .await
+            .unwrap();
+        assert!(repo.delete(parent.id).await.is_err());
+        repo.delete(child.id).await.unwrap();
+        repo.delete(parent.id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_jobs_are_created_with_all_their_dependencies_or_not_at_all() {
+        check_jobs_are_created_with_all_their_dependencies_or_not_at_all(create_test_db().await).await;
+    }
+
+    /// A reused idempotency key returns the first job until the key expires
+    async fn check_idempotency_key_replays_until_it_expires(db: DatabaseConnection) {
+        let task_id = create_task(&db, json!({})).await;
+        let repo = JobRepository::new(db.clone());
+
+        let mut job = Job::new(task_id, json!({"order": 42}), JobPriority::Normal);
+        job.idempotency_key = Some("order-42".to_string());
+        let first = match repo.create_or_replay(job, Vec::new()).await.unwrap() {
+            JobCreation::Created(job) => job,
+            JobCreation::Replayed(_) => panic!("the first job with a key must be created"),
+        };
+
+        let mut retry = Job::new(task_id, json!({"order": 42}), JobPriority::Normal);
+        retry.idempotency_key = Some("order-42".to_string());
+        match repo.create_or_replay(retry, Vec::new()).await.unwrap() {
+            JobCreation::Replayed(job) => assert_eq!(job.id, first.id),
+            JobCreation::Created(_) => panic!("a reused key must replay the first job"),
+        }
+
+        // Reusing the key for other input is refused
+        let mut other = Job::new(task_id, json!({"order": 43}), JobPriority::Normal);
+        other.idempotency_key = Some("order-42".to_string());
+        assert!(repo.create_or_replay(other.clone(), Vec::new()).await.is_err());
+
+        // Without a key, identical input is queued again
+        let unkeyed = repo
+            .create_or_replay(Job::new(task_id, json!({"order": 42}), JobPriority::Normal), Vec::new())
+            .await
+            .unwrap();
+        assert!(matches!(unkeyed, JobCreation::Created(_)));
+
+        // An expired key is released to the next job that uses it, whatever its input
+        let expired = JobRepository::new(db).with_idempotency_retention(chrono::Duration::zero());
+        let second = match expired.create_or_replay(other, Vec::new()).await.unwrap() {
+            JobCreation::Created(job) => job,
+            JobCreation::Replayed(_) => panic!("an expired key must not replay"),
+        };
+        assert_ne!(second.id, first.id);
+        assert_eq!(
+            repo.find_by_idempotency_key("order-42").await.unwrap().map(|job| job.id),
+            Some(second.id)
+        );
+        assert_eq!(repo.find_by_id(first.id).await.unwrap().unwrap().idempotency_key, None);
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_key_replays_until_it_expires() {
+        check_idempotency_key_replays_until_it_expires(create_test_db().await).await;
+    }
+
+    /// Identical input is deduplicated within the window its task sets
+    async fn check_identical_input_is_deduplicated_within_the_task_window(db: DatabaseConnection) {
+        let task_id = create_task(&db, json!({"dedupe_window_minutes": 10})).await;
+        let repo = JobRepository::new(db);
+
+        let first = repo
+            .create(Job::new(task_id, json!({"a": 1, "b": [2, 3]}), JobPriority::Normal))
+            .await
+            .unwrap();
+        match repo
+            .create_or_replay(Job::new(task_id, json!({"b": [2, 3], "a": 1}), JobPriority::High), Vec::new())
+            .await
+            .unwrap()
+        {
+            JobCreation::Replayed(job) => assert_eq!(job.id, first.id),
+            JobCreation::Created(_) => panic!("identical input must be deduplicated"),
+        }
+
+        let other = repo
+            .create_or_replay(Job::new(task_id, json!({"a": 2}), JobPriority::Normal), Vec::new())
+            .await
+            .unwrap();
+        assert!(matches!(other, JobCreation::Created(_)));
+
+        // A failed job does not hold back a new attempt
+        repo.update_status(first.id, JobStatus::Failed).await.unwrap();
+        let again = repo
+            .create_or_replay(Job::new(task_id, json!({"a": 1, "b": [2, 3]}), JobPriority::Normal), Vec::new())
+            .await
+            .unwrap();
+        assert!(matches!(again, JobCreation::Created(job) if job.id != first.id));
+    }
+
+    #[tokio::test]
+    async fn test_identical_input_is_deduplicated_within_the_task_window() {
+        check_identical_input_is_deduplicated_within_the_task_window(create_test_db().await).await;
+    }
+
+    #[tokio::test]
+    async fn test_jobs_for_quarantined_tasks_are_refused() {
+        let db = create_test_db().await;
+        let task_id = create_task(&db, json!({})).await;
+        let tasks = TaskRepository::new(db.clone());
+        let mut task = tasks.find_by_id(task_id).await.unwrap().unwrap();
+        task.enabled = false;
+        task.sync_status = "quarantined".to_string();
+        task.signature_status = Some("unsigned".to_string());
+        tasks.update(task).await.unwrap();
+
+        let refused = JobRepository::new(db)
+            .create(Job::new(task_id, json!({}), JobPriority::Normal))
+            .await
+            .unwrap_err();
+        assert!(refused.to_string().contains("disabled"), "{}", refused);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_claims_are_disjoint() {
+        let (first, second) = create_shared_test_db().await;
+        check_claims_are_disjoint(first, second).await;
+    }
+
+    #[tokio::test]
+    async fn test_expired_leases_are_reclaimed() {
+        let (first, second) = create_shared_test_db().await;
+        check_expired_leases_are_reclaimed(first, second).await;
+    }
+
+    /// Runs migrations, repositories and the lease and dependency queries against
+    /// the scratch database in RATCHET_TEST_POSTGRES_URL, if set
+    #[cfg(feature = "postgres")]
+    #[tokio::test]
+    async fn test_postgres_job_queue() {
+        use sea_orm_migration::MigratorTrait;
+
+        let Ok(url) = std::env::var("RATCHET_TEST_POSTGRES_URL") else {
+            return;
+        };
+        let config = DatabaseConfig {
+            url,
+            max_connections: 5,
+            connection_timeout: Duration::from_secs(10),
+        };
+        let first = DatabaseConnection::new(config.clone()).await.unwrap();
+        first.migrate().await.unwrap();
+        // Migrating again is a no-op, and the latest migration can be undone and redone
+        first.migrate().await.unwrap();
+        crate::database::migrations::Migrator::down(first.get_connection(), Some(1))
+            .await
+            .unwrap();
+        first.migrate().await.unwrap();
+        let second = DatabaseConnection::new(config).await.unwrap();
+
+        clear_postgres_jobs(&first).await;
+        check_claims_are_disjoint(first.clone(), second.clone()).await;
+        clear_postgres_jobs(&first).await;
+        check_expired_leases_are_reclaimed(first.clone(), second).await;
+        clear_postgres_jobs(&first).await;
+        check_dependents_wait_for_their_condition(first.clone()).await;
+        clear_postgres_jobs(&first).await;
+        check_failure_cascades_cancellation(first.clone()).await;
+        clear_postgres_jobs(&first).await;
+        check_jobs_are_created_with_all_their_dependencies_or_not_at_all(first.clone()).await;
+        clear_postgres_jobs(&first).await;
+        check_idempotency_key_replays_until_it_expires(first.clone()).await;
+        clear_postgres_jobs(&first).await;
+        check_identical_input_is_deduplicated_within_the_task_window(first).await;
+    }
+}
@@ -0,0 +1,123 @@
+use crate::database::{
+    entities::{leader_leases, LeaderLease, LeaderLeaseActiveModel, LeaderLeases},
+    DatabaseConnection, DatabaseError,
+};
+use sea_orm::{sea_query::Expr, ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set, SqlErr};
+use std::time::Duration;
+
+/// Repository for leader leases
+///
+/// A lease names a role only one server may hold at a time, such as running the
+/// cron scheduler. Every change is a single conditional statement, so servers
+/// sharing a SQLite or Postgres database never both believe they hold a role.
+#[derive(Clone)]
+pub struct LeaseRepository {
+    db: DatabaseConnection,
+}
+
+impl LeaseRepository {
+    /// Create a new lease repository
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Take or renew the lease `name` for `owner` for the next `ttl`
+    ///
+    /// Returns whether `owner` holds the lease afterwards. The lease can only be
+    /// taken from another owner once it has expired.
+    pub async fn try_acquire(&self, name: &str, owner: &str, ttl: Duration) -> Result<bool, DatabaseError> {
+        let now = chrono::Utc::now();
+        let expires_at = now + chrono::Duration::milliseconds(ttl.as_millis() as i64);
+
+        let renewed = LeaderLeases::update_many()
+            .col_expr(leader_leases::Column::ExpiresAt, Expr::value(expires_at))
+            .filter(leader_leases::Column::Name.eq(name))
+            .filter(leader_leases::Column::Owner.eq(owner))
+            .exec(self.db.get_connection())
+            .await?;
+        if renewed.rows_affected == 1 {
+            return Ok(true);
+        }
+
+        let taken_over = LeaderLeases::update_many()
+            .col_expr(leader_leases::Column::Owner, Expr::value(owner))
+            .col_expr(leader_leases::Column::AcquiredAt, Expr::value(now))
+            .col_expr(leader_leases::Column::ExpiresAt, Expr::value(expires_at))
+            .filter(leader_leases::Column::Name.eq(name))
+            .filter(leader_leases::Column::ExpiresAt.lt(now))
+            .exec(self.db.get_connection())
+            .await?;
+        if taken_over.rows_affected == 1 {
+            return Ok(true);
+        }
+
+        let active_model = LeaderLeaseActiveModel {
+            name: Set(name.to_string()),
+            owner: Set(owner.to_string()),
+            acquired_at: Set(now),
+            expires_at: Set(expires_at),
+        };
+        match active_model.insert(self.db.get_connection()).await {
+            Ok(_) => Ok(true),
+            // The lease exists and another owner holds it
+            Err(e) if matches!(e.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Give up the lease `name` if `owner` holds it
+    pub async fn release(&self, name: &str, owner: &str) -> Result<bool, DatabaseError> {
+        let result = LeaderLeases::delete_many()
+            .filter(leader_leases::Column::Name.eq(name))
+            .filter(leader_leases::Column::Owner.eq(owner))
+            .exec(self.db.get_connection())
+            .await?;
+        Ok(result.rows_affected == 1)
+    }
+
+    /// Find the lease `name`, whether or not it has expired
+    pub async fn find(&self, name: &str) -> Result<Option<LeaderLease>, DatabaseError> {
+        let lease = LeaderLeases::find_by_id(name.to_string())
+            .one(self.db.get_connection())
+            .await?;
+        Ok(lease)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seaorm::config::DatabaseConfig;
+
+    async fn create_test_db() -> DatabaseConnection {
+        let config = DatabaseConfig {
+            url: "sqlite::memory:".to_string(),
+            max_connections: 5,
+            connection_timeout: Duration::from_secs(10),
+        };
+
+        let db = DatabaseConnection::new(config).await.unwrap();
+        db.migrate().await.unwrap();
+        db
+    }
+
+    #[tokio::test]
+    async fn test_only_one_owner_holds_a_lease_until_it_expires() {
+        let repo = LeaseRepository::new(create_test_db().await);
+        let ttl = Duration::from_millis(200);
+
+        assert!(repo.try_acquire("scheduler", "a", ttl).await.unwrap());
+        assert!(!repo.try_acquire("scheduler", "b", ttl).await.unwrap());
+        assert!(repo.try_acquire("scheduler", "a", ttl).await.unwrap());
+        assert!(repo.try_acquire("repository-sync", "b", ttl).await.unwrap());
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert!(repo.try_acquire("scheduler", "b", ttl).await.unwrap());
+        assert!(!repo.try_acquire("scheduler", "a", ttl).await.unwrap());
+        assert_eq!(repo.find("scheduler").await.unwrap().unwrap().owner, "b");
+
+        assert!(!repo.release("scheduler", "a").await.unwrap());
+        assert!(repo.release("scheduler", "b").await.unwrap());
+        assert!(repo.try_acquire("scheduler", "a", ttl).await.unwrap());
+    }
+}
@@ -1,20 +1,30 @@
 pub mod api_key_repository;
+pub mod delivery_result_repository;
 pub mod execution_repository;
 pub mod job_repository;
+pub mod lease_repository;
 pub mod repository_service;
 pub mod schedule_repository;
 pub mod session_repository;
+pub mod task_secret_repository;
 pub mod task_repository;
 pub mod user_repository;
+pub mod webhook_trigger_repository;
+pub mod workflow_repository;
 
 pub use api_key_repository::SeaOrmApiKeyRepository;
+pub use delivery_result_repository::DeliveryResultRepository;
 pub use execution_repository::ExecutionRepository;
-pub use job_repository::JobRepository;
+pub use job_repository::{JobCreation, JobRepository, NewJobDependency};
+pub use lease_repository::LeaseRepository;
 pub use repository_service::RepositoryService;
 pub use schedule_repository::ScheduleRepository;
 pub use session_repository::SeaOrmSessionRepository;
+pub use task_secret_repository::TaskSecretRepository;
 pub use task_repository::TaskRepository;
 pub use user_repository::SeaOrmUserRepository;
+pub use webhook_trigger_repository::{DeliveryClaim, WebhookTriggerRepository};
+pub use workflow_repository::WorkflowRepository;
 
 use crate::seaorm::connection::DatabaseError;
 use async_trait::async_trait;
@@ -33,9 +43,14 @@ pub struct RepositoryFactory {
     pub execution_repo: ExecutionRepository,
     pub schedule_repo: ScheduleRepository,
     pub job_repo: JobRepository,
+    pub lease_repo: LeaseRepository,
+    pub delivery_result_repo: DeliveryResultRepository,
     pub user_repo: SeaOrmUserRepository,
     pub session_repo: SeaOrmSessionRepository,
     pub api_key_repo: SeaOrmApiKeyRepository,
+    pub task_secret_repo: TaskSecretRepository,
+    pub workflow_repo: WorkflowRepository,
+    pub webhook_trigger_repo: WebhookTriggerRepository,
     pub repository_service: RepositoryService,
     db: crate::seaorm::connection::DatabaseConnection,
 }
@@ -48,9 +63,14 @@ impl RepositoryFactory {
             execution_repo: ExecutionRepository::new(db.clone()),
             schedule_repo: ScheduleRepository::new(db.clone()),
             job_repo: JobRepository::new(db.clone()),
+            lease_repo: LeaseRepository::new(db.clone()),
+            delivery_result_repo: DeliveryResultRepository::new(db.clone()),
             user_repo: SeaOrmUserRepository::new(db.clone()),
             session_repo: SeaOrmSessionRepository::new(db.clone()),
             api_key_repo: SeaOrmApiKeyRepository::new(db.clone()),
+            task_secret_repo: TaskSecretRepository::new(db.clone()),
+            workflow_repo: WorkflowRepository::new(db.clone()),
+            webhook_trigger_repo: WebhookTriggerRepository::new(db.clone()),
             repository_service: RepositoryService::new(std::sync::Arc::new(db.get_connection().clone())),
             db,
         }
@@ -81,6 +101,16 @@ impl RepositoryFactory {
         self.job_repo.clone()
     }
 
+    /// Get the lease repository
+    pub fn lease_repository(&self) -> LeaseRepository {
+        self.lease_repo.clone()
+    }
+
+    /// Get the delivery result repository
+    pub fn delivery_result_repository(&self) -> DeliveryResultRepository {
+        self.delivery_result_repo.clone()
+    }
+
     /// Get the user repository
     pub fn user_repository(&self) -> SeaOrmUserRepository {
         self.user_repo.clone()
@@ -96,6 +126,21 @@ impl RepositoryFactory {
         self.api_key_repo.clone()
     }
 
+    /// Get the task secret repository
+    pub fn task_secret_repository(&self) -> TaskSecretRepository {
+        self.task_secret_repo.clone()
+    }
+
+    /// Get the workflow repository
+    pub fn workflow_repository(&self) -> WorkflowRepository {
+        self.workflow_repo.clone()
+    }
+
+    /// Get the webhook trigger repository
+    pub fn webhook_trigger_repository(&self) -> WebhookTriggerRepository {
+        self.webhook_trigger_repo.clone()
+    }
+
     /// Get the repository service
     pub fn repository_service(&self) -> RepositoryService {
         self.repository_service.clone()
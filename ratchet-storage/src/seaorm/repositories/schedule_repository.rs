@@ -22,6 +22,7 @@ impl ScheduleRepository {
         let active_model = ScheduleActiveModel {
             uuid: Set(schedule.uuid),
             task_id: Set(schedule.task_id),
+            workflow_id: Set(schedule.workflow_id),
             name: Set(schedule.name),
             cron_expression: Set(schedule.cron_expression),
             input_data: Set(schedule.input_data),
@@ -32,6 +33,12 @@ impl ScheduleRepository {
             max_executions: Set(schedule.max_executions),
             metadata: Set(schedule.metadata),
             output_destinations: Set(schedule.output_destinations),
+            timezone: Set(schedule.timezone),
+            overlap_policy: Set(schedule.overlap_policy),
+            misfire_policy: Set(schedule.misfire_policy),
+            catch_up_limit: Set(schedule.catch_up_limit),
+            jitter_seconds: Set(schedule.jitter_seconds),
+            blackout_windows: Set(schedule.blackout_windows),
             created_at: Set(schedule.created_at),
             updated_at: Set(schedule.updated_at),
             ..Default::default()
@@ -79,7 +86,7 @@ impl ScheduleRepository {
 
     /// Update schedule
     pub async fn update(&self, schedule: Schedule) -> Result<Schedule, DatabaseError> {
-        let mut active_model: ScheduleActiveModel = schedule.into();
+        let mut active_model = ScheduleActiveModel::from(schedule).reset_all();
         active_model.updated_at = Set(chrono::Utc::now());
 
         let updated_schedule = active_model.update(self.db.get_connection()).await?;
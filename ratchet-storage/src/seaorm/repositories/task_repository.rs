@@ -50,9 +50,24 @@ impl TaskRepository {
             input_schema: Set(task.input_schema),
             output_schema: Set(task.output_schema),
             enabled: Set(task.enabled),
+            source_code: Set(task.source_code),
+            source_type: Set(task.source_type),
+            storage_type: Set(task.storage_type),
+            file_path: Set(task.file_path),
+            checksum: Set(task.checksum),
+            repository_id: Set(task.repository_id),
+            repository_path: Set(task.repository_path),
+            last_synced_at: Set(task.last_synced_at),
+            sync_status: Set(task.sync_status),
+            is_editable: Set(task.is_editable),
+            created_from: Set(task.created_from),
+            needs_push: Set(task.needs_push),
             created_at: Set(task.created_at),
             updated_at: Set(task.updated_at),
             validated_at: Set(task.validated_at),
+            source_modified_at: Set(task.source_modified_at),
+            signature_status: Set(task.signature_status),
+            signed_by: Set(task.signed_by),
             ..Default::default()
         };
 
@@ -129,6 +144,8 @@ impl TaskRepository {
             updated_at: Set(chrono::Utc::now()), // Update the timestamp
             validated_at: Set(task.validated_at),
             source_modified_at: Set(task.source_modified_at),
+            signature_status: Set(task.signature_status),
+            signed_by: Set(task.signed_by),
         };
 
         let updated_task = active_model.update(self.db.get_connection()).await?;
@@ -424,6 +441,8 @@ mod tests {
             updated_at: chrono::Utc::now(),
             source_modified_at: None,
             validated_at: None,
+            signature_status: None,
+            signed_by: None,
         }
     }
 
@@ -439,6 +458,8 @@ mod tests {
         let created_task = repo.create(task).await.unwrap();
         assert!(created_task.id > 0);
         assert_eq!(created_task.uuid, task_uuid);
+        assert_eq!(created_task.source_code, "console.log('Hello from test-task');");
+        assert_eq!(created_task.checksum, "sample123");
 
         // Find by ID
         let found_task = repo.find_by_id(created_task.id).await.unwrap();
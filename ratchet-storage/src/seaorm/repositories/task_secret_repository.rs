@@ -0,0 +1,197 @@
+use crate::database::{
+    entities::{task_secrets, SecretScope, TaskSecret, TaskSecretActiveModel, TaskSecrets},
+    DatabaseConnection, DatabaseError,
+};
+use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, EntityTrait, QueryFilter, QueryOrder, Set};
+
+/// Repository for encrypted task secrets
+///
+/// Values are stored exactly as handed in; encrypting them is up to the caller.
+#[derive(Clone)]
+pub struct TaskSecretRepository {
+    db: DatabaseConnection,
+}
+
+impl TaskSecretRepository {
+    /// Create a new task secret repository
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Create a secret, or replace the value of an existing one with the same scope and name
+    ///
+    /// The description of an existing secret is kept when `description` is `None`.
+    pub async fn upsert(
+        &self,
+        name: &str,
+        scope: SecretScope,
+        scope_id: &str,
+        encrypted_value: String,
+        description: Option<String>,
+    ) -> Result<TaskSecret, DatabaseError> {
+        let now = chrono::Utc::now();
+
+        if let Some(existing) = self.find(scope, scope_id, name).await? {
+            let mut active_model: TaskSecretActiveModel = existing.into();
+            active_model.encrypted_value = Set(encrypted_value);
+            if description.is_some() {
+                active_model.description = Set(description);
+            }
+            active_model.updated_at = Set(now);
+            let updated = active_model.update(self.db.get_connection()).await?;
+            return Ok(updated);
+        }
+
+        let active_model = TaskSecretActiveModel {
+            name: Set(name.to_string()),
+            scope: Set(scope),
+            scope_id: Set(scope_id.to_string()),
+            encrypted_value: Set(encrypted_value),
+            description: Set(description),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        };
+        let created = active_model.insert(self.db.get_connection()).await?;
+        Ok(created)
+    }
+
+    /// Find a secret by scope and name
+    pub async fn find(&self, scope: SecretScope, scope_id: &str, name: &str) -> Result<Option<TaskSecret>, DatabaseError> {
+        let secret = TaskSecrets::find()
+            .filter(task_secrets::Column::Scope.eq(scope))
+            .filter(task_secrets::Column::ScopeId.eq(scope_id))
+            .filter(task_secrets::Column::Name.eq(name))
+            .one(self.db.get_connection())
+            .await?;
+        Ok(secret)
+    }
+
+    /// List secrets, optionally restricted to one scope
+    pub async fn list(&self, scope: Option<SecretScope>) -> Result<Vec<TaskSecret>, DatabaseError> {
+        let mut query = TaskSecrets::find();
+        if let Some(scope) = scope {
+            query = query.filter(task_secrets::Column::Scope.eq(scope));
+        }
+
+        let secrets = query
+            .order_by_asc(task_secrets::Column::Scope)
+            .order_by_asc(task_secrets::Column::ScopeId)
+            .order_by_asc(task_secrets::Column::Name)
+            .all(self.db.get_connection())
+            .await?;
+        Ok(secrets)
+    }
+
+    /// Delete a secret, returning whether it existed
+    pub async fn delete(&self, scope: SecretScope, scope_id: &str, name: &str) -> Result<bool, DatabaseError> {
+        let result = TaskSecrets::delete_many()
+            .filter(task_secrets::Column::Scope.eq(scope))
+            .filter(task_secrets::Column::ScopeId.eq(scope_id))
+            .filter(task_secrets::Column::Name.eq(name))
+            .exec(self.db.get_connection())
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Find the secrets named in `names` that a task can see
+    ///
+    /// Every visible scope is returned; callers pick the most specific secret per
+    /// name using [`SecretScope::precedence`].
+    pub async fn find_visible(
+        &self,
+        names: &[String],
+        task_uuid: Option<&str>,
+        repository_id: Option<&str>,
+    ) -> Result<Vec<TaskSecret>, DatabaseError> {
+        if names.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut scopes = Condition::any().add(task_secrets::Column::Scope.eq(SecretScope::Global));
+        if let Some(repository_id) = repository_id {
+            scopes = scopes.add(
+                Condition::all()
+                    .add(task_secrets::Column::Scope.eq(SecretScope::Repository))
+                    .add(task_secrets::Column::ScopeId.eq(repository_id)),
+            );
+        }
+        if let Some(task_uuid) = task_uuid {
+            scopes = scopes.add(
+                Condition::all()
+                    .add(task_secrets::Column::Scope.eq(SecretScope::Task))
+                    .add(task_secrets::Column::ScopeId.eq(task_uuid)),
+            );
+        }
+
+        let secrets = TaskSecrets::find()
+            .filter(task_secrets::Column::Name.is_in(names.iter().cloned()))
+            .filter(scopes)
+            .all(self.db.get_connection())
+            .await?;
+        Ok(secrets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seaorm::config::DatabaseConfig;
+    use std::time::Duration;
+
+    async fn create_test_db() -> DatabaseConnection {
+        let config = DatabaseConfig {
+            url: "sqlite::memory:".to_string(),
+            max_connections: 5,
+            connection_timeout: Duration::from_secs(10),
+        };
+
+        let db = DatabaseConnection::new(config).await.unwrap();
+        db.migrate().await.unwrap();
+        db
+    }
+
+    #[tokio::test]
+    async fn test_upsert_replaces_value() {
+        let repo = TaskSecretRepository::new(create_test_db().await);
+
+        let created = repo
+            .upsert("API_KEY", SecretScope::Global, "", "first".to_string(), Some("Weather API".to_string()))
+            .await
+            .unwrap();
+        let updated = repo
+            .upsert("API_KEY", SecretScope::Global, "", "second".to_string(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(created.id, updated.id);
+        assert_eq!(updated.encrypted_value, "second");
+        assert_eq!(updated.description.as_deref(), Some("Weather API"));
+        assert_eq!(repo.list(None).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_find_visible_respects_scope() {
+        let repo = TaskSecretRepository::new(create_test_db().await);
+        repo.upsert("TOKEN", SecretScope::Global, "", "global".to_string(), None)
+            .await
+            .unwrap();
+        repo.upsert("TOKEN", SecretScope::Task, "task-a", "task".to_string(), None)
+            .await
+            .unwrap();
+        repo.upsert("TOKEN", SecretScope::Repository, "7", "repository".to_string(), None)
+            .await
+            .unwrap();
+
+        let names = vec!["TOKEN".to_string()];
+        let visible = repo.find_visible(&names, Some("task-b"), None).await.unwrap();
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].scope, SecretScope::Global);
+
+        let visible = repo.find_visible(&names, Some("task-a"), Some("7")).await.unwrap();
+        assert_eq!(visible.len(), 3);
+
+        assert!(repo.delete(SecretScope::Task, "task-a", "TOKEN").await.unwrap());
+        assert!(!repo.delete(SecretScope::Task, "task-a", "TOKEN").await.unwrap());
+    }
+}
@@ -0,0 +1,265 @@
+use crate::database::{
+    entities::{
+        webhook_trigger_deliveries, webhook_triggers, WebhookTrigger, WebhookTriggerActiveModel,
+        WebhookTriggerDeliveries, WebhookTriggerDelivery, WebhookTriggerDeliveryActiveModel, WebhookTriggers,
+    },
+    DatabaseConnection, DatabaseError,
+};
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set, SqlErr};
+
+/// Result of recording a delivery ID
+#[derive(Debug, Clone)]
+pub enum DeliveryClaim {
+    /// First delivery with this ID
+    New(WebhookTriggerDelivery),
+    /// The ID was recorded before, by this earlier delivery
+    Seen(WebhookTriggerDelivery),
+}
+
+/// Repository for webhook triggers and the delivery IDs they have accepted
+///
+/// Signature settings, input mappings and filter schemas are stored as JSON;
+/// validating them is up to the caller.
+#[derive(Clone)]
+pub struct WebhookTriggerRepository {
+    db: DatabaseConnection,
+}
+
+impl WebhookTriggerRepository {
+    /// Create a new webhook trigger repository
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Create a new trigger
+    pub async fn create(&self, trigger: WebhookTrigger) -> Result<WebhookTrigger, DatabaseError> {
+        let active_model = WebhookTriggerActiveModel {
+            uuid: Set(trigger.uuid),
+            name: Set(trigger.name),
+            description: Set(trigger.description),
+            task_id: Set(trigger.task_id),
+            enabled: Set(trigger.enabled),
+            signature: Set(trigger.signature),
+            input_mapping: Set(trigger.input_mapping),
+            filter_schema: Set(trigger.filter_schema),
+            delivery_id_header: Set(trigger.delivery_id_header),
+            created_at: Set(trigger.created_at),
+            updated_at: Set(trigger.updated_at),
+            ..Default::default()
+        };
+
+        let created = active_model.insert(self.db.get_connection()).await?;
+        Ok(created)
+    }
+
+    /// Replace the settings of a trigger
+    pub async fn update(&self, trigger: WebhookTrigger) -> Result<WebhookTrigger, DatabaseError> {
+        let active_model = WebhookTriggerActiveModel {
+            id: Set(trigger.id),
+            name: Set(trigger.name),
+            description: Set(trigger.description),
+            task_id: Set(trigger.task_id),
+            enabled: Set(trigger.enabled),
+            signature: Set(trigger.signature),
+            input_mapping: Set(trigger.input_mapping),
+            filter_schema: Set(trigger.filter_schema),
+            delivery_id_header: Set(trigger.delivery_id_header),
+            updated_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        };
+
+        let updated = active_model.update(self.db.get_connection()).await?;
+        Ok(updated)
+    }
+
+    /// Find a trigger by ID
+    pub async fn find_by_id(&self, id: i32) -> Result<Option<WebhookTrigger>, DatabaseError> {
+        let trigger = WebhookTriggers::find_by_id(id).one(self.db.get_connection()).await?;
+        Ok(trigger)
+    }
+
+    /// Find a trigger by name
+    pub async fn find_by_name(&self, name: &str) -> Result<Option<WebhookTrigger>, DatabaseError> {
+        let trigger = WebhookTriggers::find()
+            .filter(webhook_triggers::Column::Name.eq(name))
+            .one(self.db.get_connection())
+            .await?;
+        Ok(trigger)
+    }
+
+    /// List all triggers by name
+    pub async fn list(&self) -> Result<Vec<WebhookTrigger>, DatabaseError> {
+        let triggers = WebhookTriggers::find()
+            .order_by_asc(webhook_triggers::Column::Name)
+            .all(self.db.get_connection())
+            .await?;
+        Ok(triggers)
+    }
+
+    /// Delete a trigger and its delivery records, returning whether it existed
+    pub async fn delete(&self, id: i32) -> Result<bool, DatabaseError> {
+        WebhookTriggerDeliveries::delete_many()
+            .filter(webhook_trigger_deliveries::Column::TriggerId.eq(id))
+            .exec(self.db.get_connection())
+            .await?;
+        let result = WebhookTriggers::delete_by_id(id).exec(self.db.get_connection()).await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Record a delivery ID for a trigger unless it was recorded before
+    ///
+    /// The unique index on (trigger, delivery ID) settles concurrent deliveries
+    /// of the same ID: exactly one of them gets [`DeliveryClaim::New`].
+    pub async fn claim_delivery(&self, trigger_id: i32, delivery_id: &str) -> Result<DeliveryClaim, DatabaseError> {
+        if let Some(seen) = self.find_delivery(trigger_id, delivery_id).await? {
+            return Ok(DeliveryClaim::Seen(seen));
+        }
+
+        let active_model = WebhookTriggerDeliveryActiveModel {
+            trigger_id: Set(trigger_id),
+            delivery_id: Set(delivery_id.to_string()),
+            job_id: Set(None),
+            received_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        };
+        match active_model.insert(self.db.get_connection()).await {
+            Ok(created) => Ok(DeliveryClaim::New(created)),
+            Err(e) if matches!(e.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+                match self.find_delivery(trigger_id, delivery_id).await? {
+                    Some(seen) => Ok(DeliveryClaim::Seen(seen)),
+                    None => Err(e.into()),
+                }
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Link a recorded delivery to the job it queued
+    pub async fn set_delivery_job(&self, id: i32, job_id: i32) -> Result<(), DatabaseError> {
+        let active_model = WebhookTriggerDeliveryActiveModel {
+            id: Set(id),
+            job_id: Set(Some(job_id)),
+            ..Default::default()
+        };
+        active_model.update(self.db.get_connection()).await?;
+        Ok(())
+    }
+
+    /// Forget a recorded delivery so that its ID can be delivered again
+    pub async fn release_delivery(&self, id: i32) -> Result<(), DatabaseError> {
+        WebhookTriggerDeliveries::delete_by_id(id).exec(self.db.get_connection()).await?;
+        Ok(())
+    }
+
+    /// Forget delivery IDs of a trigger received before `before`, returning how many were removed
+    ///
+    /// IDs starting with `keep_prefix` are kept however old they are; they go with the trigger.
+    pub async fn prune_deliveries(
+        &self,
+        trigger_id: i32,
+        before: DateTime<Utc>,
+        keep_prefix: Option<&str>,
+    ) -> Result<u64, DatabaseError> {
+        let mut delete = WebhookTriggerDeliveries::delete_many()
+            .filter(webhook_trigger_deliveries::Column::TriggerId.eq(trigger_id))
+            .filter(webhook_trigger_deliveries::Column::ReceivedAt.lt(before));
+        if let Some(prefix) = keep_prefix {
+            delete = delete.filter(webhook_trigger_deliveries::Column::DeliveryId.not_like(format!("{}%", prefix)));
+        }
+        let result = delete.exec(self.db.get_connection()).await?;
+        Ok(result.rows_affected)
+    }
+
+    async fn find_delivery(
+        &self,
+        trigger_id: i32,
+        delivery_id: &str,
+    ) -> Result<Option<WebhookTriggerDelivery>, DatabaseError> {
+        let delivery = WebhookTriggerDeliveries::find()
+            .filter(webhook_trigger_deliveries::Column::TriggerId.eq(trigger_id))
+            .filter(webhook_trigger_deliveries::Column::DeliveryId.eq(delivery_id))
+            .one(self.db.get_connection())
+            .await?;
+        Ok(delivery)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::repositories::TaskRepository;
+    use crate::seaorm::config::DatabaseConfig;
+    use crate::testing::TaskBuilder;
+    use serde_json::json;
+    use std::time::Duration;
+
+    async fn create_test_db() -> DatabaseConnection {
+        let config = DatabaseConfig {
+            url: "sqlite::memory:".to_string(),
+            max_connections: 5,
+            connection_timeout: Duration::from_secs(10),
+        };
+
+        let db = DatabaseConnection::new(config).await.unwrap();
+        db.migrate().await.unwrap();
+        db
+    }
+
+    async fn create_trigger(db: &DatabaseConnection) -> WebhookTrigger {
+        let now = chrono::Utc::now();
+        let task = TaskRepository::new(db.clone())
+            .create(TaskBuilder::new().with_name("deploy").build())
+            .await
+            .unwrap();
+
+        WebhookTriggerRepository::new(db.clone())
+            .create(WebhookTrigger {
+                id: 0,
+                uuid: uuid::Uuid::new_v4(),
+                name: "github-push".to_string(),
+                description: None,
+                task_id: task.id,
+                enabled: true,
+                signature: json!({"secretName": "GITHUB_SECRET", "header": "X-Hub-Signature-256"}),
+                input_mapping: None,
+                filter_schema: None,
+                delivery_id_header: Some("X-GitHub-Delivery".to_string()),
+                created_at: now,
+                updated_at: now,
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_delivery_ids_are_claimed_once() {
+        let db = create_test_db().await;
+        let trigger = create_trigger(&db).await;
+        let repo = WebhookTriggerRepository::new(db);
+        assert_eq!(repo.find_by_name("github-push").await.unwrap().unwrap().id, trigger.id);
+
+        let first = match repo.claim_delivery(trigger.id, "abc").await.unwrap() {
+            DeliveryClaim::New(delivery) => delivery,
+            DeliveryClaim::Seen(_) => panic!("first delivery reported as seen"),
+        };
+        repo.set_delivery_job(first.id, 7).await.unwrap();
+
+        match repo.claim_delivery(trigger.id, "abc").await.unwrap() {
+            DeliveryClaim::Seen(seen) => assert_eq!(seen.job_id, Some(7)),
+            DeliveryClaim::New(_) => panic!("repeated delivery accepted"),
+        }
+        assert!(matches!(
+            repo.claim_delivery(trigger.id, "def").await.unwrap(),
+            DeliveryClaim::New(_)
+        ));
+
+        let cutoff = chrono::Utc::now() + chrono::Duration::seconds(1);
+        assert_eq!(repo.prune_deliveries(trigger.id, cutoff, Some("d")).await.unwrap(), 1);
+        assert_eq!(repo.prune_deliveries(trigger.id, cutoff, None).await.unwrap(), 1);
+        assert!(matches!(
+            repo.claim_delivery(trigger.id, "abc").await.unwrap(),
+            DeliveryClaim::New(_)
+        ));
+    }
+}
@@ -0,0 +1,275 @@
+use crate::database::{
+    entities::{
+        workflow_runs, workflows, Workflow, WorkflowActiveModel, WorkflowRun, WorkflowRunActiveModel, WorkflowRunStatus,
+        WorkflowRuns, Workflows,
+    },
+    DatabaseConnection, DatabaseError,
+};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+};
+
+/// Repository for workflows and their runs
+///
+/// Step definitions and step states are stored as JSON; validating them is up to the caller.
+#[derive(Clone)]
+pub struct WorkflowRepository {
+    db: DatabaseConnection,
+}
+
+impl WorkflowRepository {
+    /// Create a new workflow repository
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Create a new workflow
+    pub async fn create(&self, workflow: Workflow) -> Result<Workflow, DatabaseError> {
+        let active_model = WorkflowActiveModel {
+            uuid: Set(workflow.uuid),
+            name: Set(workflow.name),
+            description: Set(workflow.description),
+            steps: Set(workflow.steps),
+            enabled: Set(workflow.enabled),
+            created_at: Set(workflow.created_at),
+            updated_at: Set(workflow.updated_at),
+            ..Default::default()
+        };
+
+        let created = active_model.insert(self.db.get_connection()).await?;
+        Ok(created)
+    }
+
+    /// Replace the definition of a workflow
+    pub async fn update(&self, workflow: Workflow) -> Result<Workflow, DatabaseError> {
+        let active_model = WorkflowActiveModel {
+            id: Set(workflow.id),
+            name: Set(workflow.name),
+            description: Set(workflow.description),
+            steps: Set(workflow.steps),
+            enabled: Set(workflow.enabled),
+            updated_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        };
+
+        let updated = active_model.update(self.db.get_connection()).await?;
+        Ok(updated)
+    }
+
+    /// Find a workflow by ID
+    pub async fn find_by_id(&self, id: i32) -> Result<Option<Workflow>, DatabaseError> {
+        let workflow = Workflows::find_by_id(id).one(self.db.get_connection()).await?;
+        Ok(workflow)
+    }
+
+    /// Find a workflow by name
+    pub async fn find_by_name(&self, name: &str) -> Result<Option<Workflow>, DatabaseError> {
+        let workflow = Workflows::find()
+            .filter(workflows::Column::Name.eq(name))
+            .one(self.db.get_connection())
+            .await?;
+        Ok(workflow)
+    }
+
+    /// List all workflows by name
+    pub async fn list(&self) -> Result<Vec<Workflow>, DatabaseError> {
+        let workflows = Workflows::find()
+            .order_by_asc(workflows::Column::Name)
+            .all(self.db.get_connection())
+            .await?;
+        Ok(workflows)
+    }
+
+    /// Delete a workflow and its runs, returning whether it existed
+    pub async fn delete(&self, id: i32) -> Result<bool, DatabaseError> {
+        WorkflowRuns::delete_many()
+            .filter(workflow_runs::Column::WorkflowId.eq(id))
+            .exec(self.db.get_connection())
+            .await?;
+        let result = Workflows::delete_by_id(id).exec(self.db.get_connection()).await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Create a new run
+    pub async fn create_run(&self, run: WorkflowRun) -> Result<WorkflowRun, DatabaseError> {
+        let active_model = WorkflowRunActiveModel {
+            uuid: Set(run.uuid),
+            workflow_id: Set(run.workflow_id),
+            schedule_id: Set(run.schedule_id),
+            status: Set(run.status),
+            input: Set(run.input),
+            output: Set(run.output),
+            error_message: Set(run.error_message),
+            steps: Set(run.steps),
+            queued_at: Set(run.queued_at),
+            started_at: Set(run.started_at),
+            completed_at: Set(run.completed_at),
+            ..Default::default()
+        };
+
+        let created = active_model.insert(self.db.get_connection()).await?;
+        Ok(created)
+    }
+
+    /// Store the progress of a run
+    pub async fn update_run(&self, run: WorkflowRun) -> Result<WorkflowRun, DatabaseError> {
+        let active_model: WorkflowRunActiveModel = run.into();
+        let updated = active_model.reset_all().update(self.db.get_connection()).await?;
+        Ok(updated)
+    }
+
+    /// Find a run by ID
+    pub async fn find_run_by_id(&self, id: i32) -> Result<Option<WorkflowRun>, DatabaseError> {
+        let run = WorkflowRuns::find_by_id(id).one(self.db.get_connection()).await?;
+        Ok(run)
+    }
+
+    /// List the latest runs, optionally of one workflow, newest first
+    pub async fn list_runs(&self, workflow_id: Option<i32>, limit: u64) -> Result<Vec<WorkflowRun>, DatabaseError> {
+        let mut query = WorkflowRuns::find();
+        if let Some(workflow_id) = workflow_id {
+            query = query.filter(workflow_runs::Column::WorkflowId.eq(workflow_id));
+        }
+
+        let runs = query
+            .order_by_desc(workflow_runs::Column::QueuedAt)
+            .order_by_desc(workflow_runs::Column::Id)
+            .limit(limit)
+            .all(self.db.get_connection())
+            .await?;
+        Ok(runs)
+    }
+
+    /// Find the run a schedule queued last
+    pub async fn find_latest_run_for_schedule(&self, schedule_id: i32) -> Result<Option<WorkflowRun>, DatabaseError> {
+        let run = WorkflowRuns::find()
+            .filter(workflow_runs::Column::ScheduleId.eq(schedule_id))
+            .order_by_desc(workflow_runs::Column::QueuedAt)
+            .order_by_desc(workflow_runs::Column::Id)
+            .one(self.db.get_connection())
+            .await?;
+        Ok(run)
+    }
+
+    /// Move up to `limit` queued runs to running, oldest first, and return them
+    ///
+    /// A run is only returned to the caller that moved it, so several processors
+    /// can poll the same database.
+    pub async fn claim_queued_runs(&self, limit: u64) -> Result<Vec<WorkflowRun>, DatabaseError> {
+        let queued = WorkflowRuns::find()
+            .filter(workflow_runs::Column::Status.eq(WorkflowRunStatus::Queued))
+            .order_by_asc(workflow_runs::Column::QueuedAt)
+            .order_by_asc(workflow_runs::Column::Id)
+            .limit(limit)
+            .all(self.db.get_connection())
+            .await?;
+
+        let now = chrono::Utc::now();
+        let mut claimed = Vec::with_capacity(queued.len());
+        for mut run in queued {
+            let result = WorkflowRuns::update_many()
+                .col_expr(workflow_runs::Column::Status, Expr::value(WorkflowRunStatus::Running))
+                .col_expr(workflow_runs::Column::StartedAt, Expr::value(now))
+                .filter(workflow_runs::Column::Id.eq(run.id))
+                .filter(workflow_runs::Column::Status.eq(WorkflowRunStatus::Queued))
+                .exec(self.db.get_connection())
+                .await?;
+            if result.rows_affected == 1 {
+                run.status = WorkflowRunStatus::Running;
+                run.started_at = Some(now);
+                claimed.push(run);
+            }
+        }
+        Ok(claimed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seaorm::config::DatabaseConfig;
+    use serde_json::json;
+    use std::time::Duration;
+
+    async fn create_test_db() -> DatabaseConnection {
+        let config = DatabaseConfig {
+            url: "sqlite::memory:".to_string(),
+            max_connections: 5,
+            connection_timeout: Duration::from_secs(10),
+        };
+
+        let db = DatabaseConnection::new(config).await.unwrap();
+        db.migrate().await.unwrap();
+        db
+    }
+
+    fn workflow(name: &str) -> Workflow {
+        let now = chrono::Utc::now();
+        Workflow {
+            id: 0,
+            uuid: uuid::Uuid::new_v4(),
+            name: name.to_string(),
+            description: None,
+            steps: json!([{ "id": "fetch", "taskId": "1" }]),
+            enabled: true,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn queued_run(workflow_id: i32) -> WorkflowRun {
+        WorkflowRun {
+            id: 0,
+            uuid: uuid::Uuid::new_v4(),
+            workflow_id,
+            schedule_id: None,
+            status: WorkflowRunStatus::Queued,
+            input: json!({}),
+            output: None,
+            error_message: None,
+            steps: json!([]),
+            queued_at: chrono::Utc::now(),
+            started_at: None,
+            completed_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_workflow_crud() {
+        let repo = WorkflowRepository::new(create_test_db().await);
+
+        let created = repo.create(workflow("nightly")).await.unwrap();
+        assert_eq!(repo.find_by_name("nightly").await.unwrap().unwrap().id, created.id);
+
+        let mut changed = created.clone();
+        changed.enabled = false;
+        let updated = repo.update(changed).await.unwrap();
+        assert!(!updated.enabled);
+        assert_eq!(repo.list().await.unwrap().len(), 1);
+
+        assert!(repo.delete(created.id).await.unwrap());
+        assert!(repo.find_by_id(created.id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_queued_runs_are_claimed_once() {
+        let repo = WorkflowRepository::new(create_test_db().await);
+        let workflow = repo.create(workflow("pipeline")).await.unwrap();
+        repo.create_run(queued_run(workflow.id)).await.unwrap();
+        repo.create_run(queued_run(workflow.id)).await.unwrap();
+
+        let claimed = repo.claim_queued_runs(10).await.unwrap();
+        assert_eq!(claimed.len(), 2);
+        assert!(claimed.iter().all(|run| run.status == WorkflowRunStatus::Running));
+        assert!(repo.claim_queued_runs(10).await.unwrap().is_empty());
+
+        let mut finished = claimed[0].clone();
+        finished.status = WorkflowRunStatus::Completed;
+        finished.output = Some(json!({ "fetch": 1 }));
+        repo.update_run(finished).await.unwrap();
+
+        let runs = repo.list_runs(Some(workflow.id), 10).await.unwrap();
+        assert_eq!(runs.len(), 2);
+        assert!(runs.iter().any(|run| run.status == WorkflowRunStatus::Completed));
+    }
+}
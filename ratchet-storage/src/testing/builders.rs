@@ -9,22 +9,23 @@ use uuid::Uuid;
 
 #[cfg(all(feature = "testing", feature = "seaorm"))]
 use crate::seaorm::entities::{
-    delivery_results::{ActiveModel as DeliveryResultActiveModel, Model as DeliveryResult},
+    delivery_results::{ActiveModel as DeliveryResultActiveModel, DeliveryStatus, Model as DeliveryResult},
     executions::{ActiveModel as ExecutionActiveModel, ExecutionStatus, Model as Execution},
     jobs::{ActiveModel as JobActiveModel, JobPriority, JobStatus, Model as Job},
     schedules::{ActiveModel as ScheduleActiveModel, Model as Schedule},
-    tasks::{ActiveModel as TaskActiveModel, Model as Task},
 };
-#[cfg(all(feature = "testing", feature = "seaorm"))]
+#[cfg(all(any(test, feature = "testing"), feature = "seaorm"))]
+use crate::seaorm::entities::tasks::{ActiveModel as TaskActiveModel, Model as Task};
+#[cfg(all(any(test, feature = "testing"), feature = "seaorm"))]
 use sea_orm::Set;
 
 /// Builder pattern for creating test tasks
-#[cfg(all(feature = "testing", feature = "seaorm"))]
+#[cfg(all(any(test, feature = "testing"), feature = "seaorm"))]
 pub struct TaskBuilder {
     task: Task,
 }
 
-#[cfg(all(feature = "testing", feature = "seaorm"))]
+#[cfg(all(any(test, feature = "testing"), feature = "seaorm"))]
 impl TaskBuilder {
     pub fn new() -> Self {
         Self {
@@ -56,6 +57,8 @@ impl TaskBuilder {
                 updated_at: Utc::now(),
                 validated_at: Some(Utc::now()),
                 source_modified_at: Some(Utc::now()),
+                signature_status: None,
+                signed_by: None,
             },
         }
     }
@@ -105,6 +108,16 @@ impl TaskBuilder {
         self
     }
 
+    pub fn with_metadata(mut self, metadata: serde_json::Value) -> Self {
+        self.task.metadata = metadata;
+        self
+    }
+
+    pub fn with_source_code(mut self, source_code: impl Into<String>) -> Self {
+        self.task.source_code = source_code.into();
+        self
+    }
+
     pub fn build(self) -> Task {
         self.task
     }
@@ -139,11 +152,13 @@ impl TaskBuilder {
             updated_at: Set(task.updated_at),
             validated_at: Set(task.validated_at),
             source_modified_at: Set(task.source_modified_at),
+            signature_status: Set(task.signature_status),
+            signed_by: Set(task.signed_by),
         }
     }
 }
 
-#[cfg(all(feature = "testing", feature = "seaorm"))]
+#[cfg(all(any(test, feature = "testing"), feature = "seaorm"))]
 impl Default for TaskBuilder {
     fn default() -> Self {
         Self::new()
@@ -175,6 +190,11 @@ impl ExecutionBuilder {
                 duration_ms: None,
                 http_requests: None,
                 recording_path: None,
+                progress: None,
+                partial_output: None,
+                logs: None,
+                parent_execution_uuid: None,
+                cache_hit: false,
             },
         }
     }
@@ -267,6 +287,11 @@ impl ExecutionBuilder {
             duration_ms: Set(execution.duration_ms),
             http_requests: Set(execution.http_requests),
             recording_path: Set(execution.recording_path),
+            progress: Set(execution.progress),
+            partial_output: Set(execution.partial_output),
+            logs: Set(execution.logs),
+            parent_execution_uuid: Set(execution.parent_execution_uuid),
+            cache_hit: Set(execution.cache_hit),
         }
     }
 }
@@ -308,6 +333,10 @@ impl JobBuilder {
                 completed_at: None,
                 metadata: None,
                 output_destinations: None,
+                idempotency_key: None,
+                input_hash: None,
+                lease_owner: None,
+                lease_expires_at: None,
             },
         }
     }
@@ -399,6 +428,10 @@ impl JobBuilder {
             completed_at: Set(job.completed_at),
             metadata: Set(job.metadata),
             output_destinations: Set(job.output_destinations),
+            idempotency_key: Set(job.idempotency_key),
+            input_hash: Set(job.input_hash),
+            lease_owner: Set(job.lease_owner),
+            lease_expires_at: Set(job.lease_expires_at),
         }
     }
 }
@@ -424,6 +457,7 @@ impl ScheduleBuilder {
                 id: 1,
                 uuid: Uuid::new_v4(),
                 task_id: 1,
+                workflow_id: None,
                 name: "test-schedule".to_string(),
                 cron_expression: "0 0 * * *".to_string(),
                 input_data: json!({}),
@@ -434,6 +468,12 @@ impl ScheduleBuilder {
                 max_executions: None,
                 metadata: None,
                 output_destinations: None,
+                timezone: "UTC".to_string(),
+                overlap_policy: Default::default(),
+                misfire_policy: Default::default(),
+                catch_up_limit: None,
+                jitter_seconds: 0,
+                blackout_windows: None,
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
             },
@@ -521,6 +561,7 @@ impl ScheduleBuilder {
             id: Set(schedule.id),
             uuid: Set(schedule.uuid),
             task_id: Set(schedule.task_id),
+            workflow_id: Set(schedule.workflow_id),
             name: Set(schedule.name),
             cron_expression: Set(schedule.cron_expression),
             input_data: Set(schedule.input_data),
@@ -531,6 +572,12 @@ impl ScheduleBuilder {
             max_executions: Set(schedule.max_executions),
             metadata: Set(schedule.metadata),
             output_destinations: Set(schedule.output_destinations),
+            timezone: Set(schedule.timezone),
+            overlap_policy: Set(schedule.overlap_policy),
+            misfire_policy: Set(schedule.misfire_policy),
+            catch_up_limit: Set(schedule.catch_up_limit),
+            jitter_seconds: Set(schedule.jitter_seconds),
+            blackout_windows: Set(schedule.blackout_windows),
             created_at: Set(schedule.created_at),
             updated_at: Set(schedule.updated_at),
         }
@@ -560,6 +607,10 @@ impl DeliveryResultBuilder {
                 execution_id: 1,
                 destination_type: "webhook".to_string(),
                 destination_id: "test-destination".to_string(),
+                destination_index: 0,
+                attempt: 1,
+                status: DeliveryStatus::DeadLetter,
+                next_attempt_at: None,
                 success: false,
                 delivery_time_ms: 0,
                 size_bytes: 0,
@@ -600,8 +651,25 @@ impl DeliveryResultBuilder {
         self
     }
 
+    pub fn with_attempt(mut self, attempt: i32) -> Self {
+        self.delivery_result.attempt = attempt;
+        self
+    }
+
+    pub fn with_status(mut self, status: DeliveryStatus) -> Self {
+        self.delivery_result.status = status;
+        self
+    }
+
+    pub fn retrying_at(mut self, next_attempt_at: chrono::DateTime<Utc>) -> Self {
+        self.delivery_result.status = DeliveryStatus::Retrying;
+        self.delivery_result.next_attempt_at = Some(next_attempt_at);
+        self
+    }
+
     pub fn successful(mut self) -> Self {
         self.delivery_result.success = true;
+        self.delivery_result.status = DeliveryStatus::Delivered;
         self.delivery_result.delivery_time_ms = 1000;
         self.delivery_result.size_bytes = 256;
         self
@@ -609,6 +677,7 @@ impl DeliveryResultBuilder {
 
     pub fn failed(mut self, error_message: impl Into<String>) -> Self {
         self.delivery_result.success = false;
+        self.delivery_result.status = DeliveryStatus::DeadLetter;
         self.delivery_result.error_message = Some(error_message.into());
         self
     }
@@ -645,6 +714,10 @@ impl DeliveryResultBuilder {
             execution_id: Set(delivery_result.execution_id),
             destination_type: Set(delivery_result.destination_type),
             destination_id: Set(delivery_result.destination_id),
+            destination_index: Set(delivery_result.destination_index),
+            attempt: Set(delivery_result.attempt),
+            status: Set(delivery_result.status),
+            next_attempt_at: Set(delivery_result.next_attempt_at),
             success: Set(delivery_result.success),
             delivery_time_ms: Set(delivery_result.delivery_time_ms),
             size_bytes: Set(delivery_result.size_bytes),
@@ -420,6 +420,8 @@ mod tests {
                 updated_at: chrono::Utc::now(),
                 source_modified_at: None,
                 validated_at: Some(chrono::Utc::now()),
+                signature_status: None,
+                signed_by: None,
             },
             crate::seaorm::entities::tasks::Model {
                 id: 2,
@@ -449,6 +451,8 @@ mod tests {
                 updated_at: chrono::Utc::now(),
                 source_modified_at: None,
                 validated_at: Some(chrono::Utc::now()),
+                signature_status: None,
+                signed_by: None,
             },
         ];
 
@@ -491,6 +495,8 @@ mod tests {
             updated_at: chrono::Utc::now(),
             source_modified_at: None,
             validated_at: Some(chrono::Utc::now()),
+            signature_status: None,
+            signed_by: None,
         }];
         db.seed_tasks(tasks).await.unwrap();
 
@@ -549,6 +555,8 @@ mod tests {
             updated_at: chrono::Utc::now(),
             source_modified_at: None,
             validated_at: Some(chrono::Utc::now()),
+            signature_status: None,
+            signed_by: None,
         }];
         db.seed_tasks(tasks).await.unwrap();
 
@@ -590,6 +598,8 @@ mod tests {
                 updated_at: chrono::Utc::now(),
                 source_modified_at: None,
                 validated_at: Some(chrono::Utc::now()),
+                signature_status: None,
+                signed_by: None,
             },
             crate::seaorm::entities::tasks::Model {
                 id: 2,
@@ -619,6 +629,8 @@ mod tests {
                 updated_at: chrono::Utc::now(),
                 source_modified_at: None,
                 validated_at: Some(chrono::Utc::now()),
+                signature_status: None,
+                signed_by: None,
             },
         ];
         db.seed_tasks(tasks).await.unwrap();
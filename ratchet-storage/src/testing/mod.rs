@@ -32,7 +32,9 @@ pub mod fixtures;
 
 // Re-export commonly used testing utilities
 #[cfg(all(feature = "testing", feature = "seaorm"))]
-pub use builders::{factories, DeliveryResultBuilder, ExecutionBuilder, JobBuilder, ScheduleBuilder, TaskBuilder};
+pub use builders::{factories, DeliveryResultBuilder, ExecutionBuilder, JobBuilder, ScheduleBuilder};
+#[cfg(all(any(test, feature = "testing"), feature = "seaorm"))]
+pub use builders::TaskBuilder;
 #[cfg(all(feature = "testing", feature = "seaorm"))]
 pub use database::{SharedTestDatabase, TestDatabase, TestDatabaseError};
 #[cfg(all(feature = "testing", feature = "seaorm"))]
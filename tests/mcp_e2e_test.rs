@@ -59,7 +59,7 @@ async fn create_test_adapter() -> Result<RatchetMcpAdapter> {
     // Create unified interfaces - use bridge registry for testing
     let server_config = ratchet_server::config::ServerConfig::default();
     let registry: Arc<dyn TaskRegistry> = Arc::new(
-        ratchet_server::bridges::BridgeTaskRegistry::new(&server_config)
+        ratchet_server::bridges::BridgeTaskRegistry::new(&server_config, Default::default())
             .await
             .expect("Failed to create bridge registry")
     );
@@ -73,6 +73,10 @@ async fn create_test_adapter() -> Result<RatchetMcpAdapter> {
         task_timeout_seconds: 30,
         restart_on_crash: true,
         max_restart_attempts: 3,
+        limits: Default::default(),
+        calls: None,
+        egress: Default::default(),
+        http_client: Default::default(),
     };
     let executor = Arc::new(ProcessTaskExecutor::new(executor_config));
 